@@ -759,6 +759,35 @@ impl ActionLog {
             .collect()
     }
 
+    /// Renders the edits that haven't been reviewed by the user as a unified diff,
+    /// in the same format as `git diff`.
+    pub fn unified_diff(&self, cx: &App) -> String {
+        let mut output = String::new();
+        for (buffer, tracked) in &self.tracked_buffers {
+            if !tracked.has_edits(cx) {
+                continue;
+            }
+            let buffer = buffer.read(cx);
+            let Some(file) = buffer.file() else {
+                continue;
+            };
+            let path = file.path().as_unix_str();
+            let (old_path, old_text) = match &tracked.status {
+                TrackedBufferStatus::Created {
+                    existing_file_content: None,
+                } => ("/dev/null".to_string(), String::new()),
+                _ => (format!("a/{path}"), tracked.diff_base.to_string()),
+            };
+            let (new_path, new_text) = match &tracked.status {
+                TrackedBufferStatus::Deleted => ("/dev/null".to_string(), String::new()),
+                _ => (format!("b/{path}"), buffer.text()),
+            };
+            output.push_str(&format!("--- {old_path}\n+++ {new_path}\n"));
+            output.push_str(&language::unified_diff(&old_text, &new_text));
+        }
+        output
+    }
+
     /// Iterate over buffers changed since last read or edited by the model
     pub fn stale_buffers<'a>(&'a self, cx: &'a App) -> impl Iterator<Item = &'a Entity<Buffer>> {
         self.tracked_buffers
@@ -1335,6 +1364,60 @@ mod tests {
         assert_eq!(unreviewed_hunks(&action_log, cx), vec![]);
     }
 
+    #[gpui::test]
+    async fn test_unified_diff(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(path!("/dir"), json!({"file1": "abc\ndef\nghi\n"}))
+            .await;
+        let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+        let action_log = cx.new(|_| ActionLog::new(project.clone()));
+        let file1_path = project
+            .read_with(cx, |project, cx| project.find_project_path("dir/file1", cx))
+            .unwrap();
+        let file2_path = project
+            .read_with(cx, |project, cx| project.find_project_path("dir/file2", cx))
+            .unwrap();
+        let buffer1 = project
+            .update(cx, |project, cx| project.open_buffer(file1_path, cx))
+            .await
+            .unwrap();
+        let buffer2 = project
+            .update(cx, |project, cx| project.open_buffer(file2_path, cx))
+            .await
+            .unwrap();
+
+        cx.update(|cx| {
+            action_log.update(cx, |log, cx| log.buffer_read(buffer1.clone(), cx));
+            buffer1.update(cx, |buffer, cx| {
+                buffer
+                    .edit([(Point::new(1, 1)..Point::new(1, 2), "E")], None, cx)
+                    .unwrap()
+            });
+            action_log.update(cx, |log, cx| log.buffer_edited(buffer1.clone(), cx));
+
+            action_log.update(cx, |log, cx| log.buffer_created(buffer2.clone(), cx));
+            buffer2.update(cx, |buffer, cx| buffer.set_text("lorem\n", cx));
+            action_log.update(cx, |log, cx| log.buffer_edited(buffer2.clone(), cx));
+        });
+        cx.run_until_parked();
+
+        let diff = action_log.read_with(cx, |log, cx| log.unified_diff(cx));
+        let (file1_diff, file2_diff) = diff.split_at(diff.find("--- /dev/null").unwrap());
+        assert!(file1_diff.starts_with("--- a/file1\n+++ b/file1\n"));
+        assert!(file1_diff.contains("-def\n+dEf\n"));
+        assert!(file2_diff.starts_with("--- /dev/null\n+++ b/file2\n"));
+        assert!(file2_diff.contains("+lorem\n"));
+
+        action_log.update(cx, |log, cx| log.keep_all_edits(None, cx));
+        cx.run_until_parked();
+        assert_eq!(
+            action_log.read_with(cx, |log, cx| log.unified_diff(cx)),
+            ""
+        );
+    }
+
     #[gpui::test(iterations = 10)]
     async fn test_overwriting_files(cx: &mut TestAppContext) {
         init_test(cx);
@@ -1,6 +1,7 @@
 mod db;
 mod edit_agent;
 mod history_store;
+mod hooks;
 mod legacy_thread;
 mod native_agent_server;
 pub mod outline;
@@ -45,6 +46,9 @@ use std::sync::Arc;
 use util::ResultExt;
 use util::rel_path::RelPath;
 
+/// Built-in slash command that compacts the current thread.
+const COMPACT_COMMAND_NAME: &str = "compact";
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct ProjectSnapshot {
     pub worktree_snapshots: Vec<project::telemetry_snapshot::TelemetryWorktreeSnapshot>,
@@ -382,7 +386,9 @@ impl NativeAgent {
         Ok(())
     }
 
-    fn build_project_context(
+    /// Collects the worktrees, their rules files (e.g. `AGENTS.md` or `.rules`)
+    /// and the default user rules that make up a thread's system prompt.
+    pub fn build_project_context(
         project: &Entity<Project>,
         prompt_store: Option<&Entity<PromptStore>>,
         cx: &mut App,
@@ -671,9 +677,13 @@ impl NativeAgent {
                 .or_insert(0) += 1;
         }
 
-        registry
-            .prompts()
-            .flat_map(|context_server_prompt| {
+        let compact_command = acp::AvailableCommand::new(
+            COMPACT_COMMAND_NAME,
+            "Summarize older messages to free up space in the context window",
+        );
+
+        std::iter::once(compact_command)
+            .chain(registry.prompts().flat_map(|context_server_prompt| {
                 let prompt = &context_server_prompt.prompt;
 
                 let should_prefix = prompt_name_counts
@@ -709,7 +719,7 @@ impl NativeAgent {
                 }
 
                 Some(command)
-            })
+            }))
             .collect()
     }
 
@@ -1022,6 +1032,11 @@ impl NativeAgentConnection {
                                     thread.update_retry_status(status, cx)
                                 })?;
                             }
+                            ThreadEvent::Compaction(compaction) => {
+                                acp_thread.update(cx, |thread, cx| {
+                                    Self::push_compaction_marker(thread, &compaction, cx)
+                                })?;
+                            }
                             ThreadEvent::Stop(stop_reason) => {
                                 log::debug!("Assistant message complete: {:?}", stop_reason);
                                 return Ok(acp::PromptResponse::new(stop_reason));
@@ -1039,6 +1054,47 @@ impl NativeAgentConnection {
             anyhow::Ok(acp::PromptResponse::new(acp::StopReason::EndTurn))
         })
     }
+
+    fn push_compaction_marker(
+        acp_thread: &mut AcpThread,
+        compaction: &CompactionSummary,
+        cx: &mut Context<AcpThread>,
+    ) {
+        let marker = format!(
+            "**Context compacted** at {}. Earlier messages were summarized to free up \
+            space in the context window; the full transcript is still shown above.",
+            compaction.created_at.format("%Y-%m-%d %H:%M UTC")
+        );
+        acp_thread.push_assistant_content_block_with_indent(marker.into(), false, true, cx);
+    }
+
+    fn compact(
+        &self,
+        session_id: acp::SessionId,
+        cx: &mut App,
+    ) -> Task<Result<acp::PromptResponse>> {
+        let Some((thread, acp_thread)) = self
+            .0
+            .read(cx)
+            .sessions
+            .get(&session_id)
+            .map(|s| (s.thread.clone(), s.acp_thread.clone()))
+        else {
+            return Task::ready(Err(anyhow!("Session not found")));
+        };
+
+        let compaction = thread.update(cx, |thread, cx| {
+            thread.cancel(cx);
+            thread.compact(cx)
+        });
+        cx.spawn(async move |cx| {
+            let compaction = compaction.await?;
+            acp_thread.update(cx, |thread, cx| {
+                Self::push_compaction_marker(thread, &compaction, cx)
+            })?;
+            Ok(acp::PromptResponse::new(acp::StopReason::EndTurn))
+        })
+    }
 }
 
 struct Command<'a> {
@@ -1282,6 +1338,10 @@ impl acp_thread::AgentConnection for NativeAgentConnection {
                     )
                 });
             };
+
+            if explicit_server_id.is_none() && parsed_command.prompt_name == COMPACT_COMMAND_NAME {
+                return self.compact(session_id, cx);
+            }
         };
 
         let path_style = self.0.read(cx).project.read(cx).path_style(cx);
@@ -1858,6 +1918,100 @@ mod internal_tests {
         });
     }
 
+    #[gpui::test]
+    async fn test_export_import_thread(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree("/", json!({ "a": {} })).await;
+        let project = Project::test(fs.clone(), [path!("/a").as_ref()], cx).await;
+        let text_thread_store =
+            cx.new(|cx| assistant_text_thread::TextThreadStore::fake(project.clone(), cx));
+        let history_store = cx.new(|cx| HistoryStore::new(text_thread_store, cx));
+        let agent = NativeAgent::new(
+            project.clone(),
+            history_store.clone(),
+            Templates::new(),
+            None,
+            fs.clone(),
+            &mut cx.to_async(),
+        )
+        .await
+        .unwrap();
+        let connection = Rc::new(NativeAgentConnection(agent.clone()));
+
+        let acp_thread = cx
+            .update(|cx| {
+                connection
+                    .clone()
+                    .new_thread(project.clone(), Path::new(""), cx)
+            })
+            .await
+            .unwrap();
+        let session_id = acp_thread.read_with(cx, |thread, _| thread.session_id().clone());
+        let thread = agent.read_with(cx, |agent, _| {
+            agent.sessions.get(&session_id).unwrap().thread.clone()
+        });
+
+        let model = Arc::new(FakeLanguageModel::default());
+        let summary_model = Arc::new(FakeLanguageModel::default());
+        thread.update(cx, |thread, cx| {
+            thread.set_model(model.clone(), cx);
+            thread.set_summarization_model(Some(summary_model.clone()), cx);
+        });
+
+        let send = acp_thread.update(cx, |thread, cx| thread.send(vec!["Hello".into()], cx));
+        let send = cx.foreground_executor().spawn(send);
+        cx.run_until_parked();
+        model.send_last_completion_stream_text_chunk("Hi!");
+        model.end_last_completion_stream();
+        cx.run_until_parked();
+        summary_model.send_last_completion_stream_text_chunk("Greeting");
+        summary_model.end_last_completion_stream();
+        send.await.unwrap();
+        cx.run_until_parked();
+
+        let mut exported = thread.read_with(cx, |thread, cx| thread.to_export(cx)).await;
+        exported.diff = "--- a/main.rs\n+++ b/main.rs\n@@ -1 +1 @@\n-a\n+b\n".into();
+        let imported = history_store
+            .update(cx, |history_store, cx| {
+                history_store.import_thread(exported.to_json().unwrap().into_bytes(), cx)
+            })
+            .await
+            .unwrap();
+        cx.run_until_parked();
+        // The unreviewed edits come back for review instead of being dropped.
+        assert_eq!(imported.diff, exported.diff);
+        let imported = imported.metadata;
+        assert_ne!(imported.id, session_id);
+        // The imported thread keeps its original timestamp, so the order of the two entries is arbitrary.
+        let entries = history_entries(&history_store, cx);
+        assert_eq!(entries.len(), 2);
+        assert!(entries.contains(&(
+            HistoryEntryId::AcpThread(imported.id.clone()),
+            "Greeting".into()
+        )));
+
+        let imported_thread = agent
+            .update(cx, |agent, cx| agent.open_thread(imported.id.clone(), cx))
+            .await
+            .unwrap();
+        imported_thread.read_with(cx, |thread, cx| {
+            assert_eq!(
+                thread.to_markdown(cx),
+                formatdoc! {"
+                    ## User
+
+                    Hello
+
+                    ## Assistant
+
+                    Hi!
+
+                "}
+            )
+        });
+    }
+
     fn history_entries(
         history: &Entity<HistoryStore>,
         cx: &mut TestAppContext,
@@ -2,7 +2,7 @@ use crate::{AgentMessage, AgentMessageContent, UserMessage, UserMessageContent};
 use acp_thread::UserMessageId;
 use agent_client_protocol as acp;
 use agent_settings::{AgentProfileId, CompletionMode};
-use anyhow::{Result, anyhow};
+use anyhow::{Context as _, Result, anyhow};
 use chrono::{DateTime, Utc};
 use collections::{HashMap, IndexMap};
 use futures::{FutureExt, future::Shared};
@@ -52,6 +52,13 @@ pub struct DbThread {
     pub profile: Option<AgentProfileId>,
 }
 
+#[derive(Serialize)]
+struct SerializedThread<'a> {
+    #[serde(flatten)]
+    thread: &'a DbThread,
+    version: &'static str,
+}
+
 impl DbThread {
     pub const VERSION: &'static str = "0.3.0";
 
@@ -213,6 +220,67 @@ impl DbThread {
     }
 }
 
+/// A thread in a standalone, versioned JSON format that can be shared
+/// between machines and imported into another user's history.
+#[derive(Debug)]
+pub struct ExportedThread {
+    pub exported_at: DateTime<Utc>,
+    pub thread: DbThread,
+    /// Unified diff of the agent's edits that hadn't been kept or rejected at export time.
+    pub diff: String,
+}
+
+impl ExportedThread {
+    pub const VERSION: &'static str = "1";
+
+    pub fn to_json(&self) -> Result<String> {
+        #[derive(Serialize)]
+        struct SerializedExportedThread<'a> {
+            version: &'static str,
+            exported_at: DateTime<Utc>,
+            thread: SerializedThread<'a>,
+            diff: &'a str,
+        }
+
+        Ok(serde_json::to_string_pretty(&SerializedExportedThread {
+            version: Self::VERSION,
+            exported_at: self.exported_at,
+            thread: SerializedThread {
+                thread: &self.thread,
+                version: DbThread::VERSION,
+            },
+            diff: &self.diff,
+        })?)
+    }
+
+    pub fn from_json(json: &[u8]) -> Result<Self> {
+        #[derive(Deserialize)]
+        struct SerializedExportedThread {
+            version: String,
+            exported_at: DateTime<Utc>,
+            thread: serde_json::Value,
+            #[serde(default)]
+            diff: String,
+        }
+
+        let exported = serde_json::from_slice::<SerializedExportedThread>(json)
+            .context("not an exported agent thread")?;
+        anyhow::ensure!(
+            exported.version == Self::VERSION,
+            "unsupported thread export version {}",
+            exported.version
+        );
+        // Threads exported by older versions of Zed are upgraded the same way
+        // as threads stored in the database.
+        let thread = DbThread::from_json(&serde_json::to_vec(&exported.thread)?)?;
+        Ok(Self {
+            exported_at: exported.exported_at,
+            thread,
+            diff: exported.diff,
+        })
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum DataType {
     #[serde(rename = "json")]
@@ -321,17 +389,10 @@ impl ThreadsDatabase {
     ) -> Result<()> {
         const COMPRESSION_LEVEL: i32 = 3;
 
-        #[derive(Serialize)]
-        struct SerializedThread {
-            #[serde(flatten)]
-            thread: DbThread,
-            version: &'static str,
-        }
-
         let title = thread.title.to_string();
         let updated_at = thread.updated_at.to_rfc3339();
         let json_data = serde_json::to_string(&SerializedThread {
-            thread,
+            thread: &thread,
             version: DbThread::VERSION,
         })?;
 
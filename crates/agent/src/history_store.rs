@@ -1,4 +1,4 @@
-use crate::{DbThread, DbThreadMetadata, ThreadsDatabase};
+use crate::{DbThread, DbThreadMetadata, ExportedThread, ThreadsDatabase};
 use acp_thread::MentionUri;
 use agent_client_protocol as acp;
 use anyhow::{Context as _, Result, anyhow};
@@ -119,6 +119,14 @@ enum SerializedRecentOpen {
     TextThread(String),
 }
 
+/// A thread added to the history by [`HistoryStore::import_thread`].
+#[derive(Debug)]
+pub struct ImportedThread {
+    pub metadata: DbThreadMetadata,
+    /// Unified diff of the edits that were unreviewed when the thread was exported.
+    pub diff: String,
+}
+
 pub struct HistoryStore {
     threads: Vec<DbThreadMetadata>,
     entries: Vec<HistoryEntry>,
@@ -188,6 +196,40 @@ impl HistoryStore {
         })
     }
 
+    /// Saves a thread exported with [`crate::Thread::to_export`] as a new entry in the history,
+    /// so that it can be opened and continued like any other thread.
+    ///
+    /// The exported diff is returned for review rather than applied, since the
+    /// importing project may not match the one the thread was exported from.
+    pub fn import_thread(
+        &mut self,
+        json: Vec<u8>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<ImportedThread>> {
+        let database_future = ThreadsDatabase::connect(cx);
+        cx.spawn(async move |this, cx| {
+            let exported = cx
+                .background_spawn(async move { ExportedThread::from_json(&json) })
+                .await?;
+            // Always import as a new thread, so importing the same file twice
+            // (or on the machine it came from) doesn't overwrite anything.
+            let metadata = DbThreadMetadata {
+                id: acp::SessionId::new(uuid::Uuid::new_v4().to_string()),
+                title: exported.thread.title.clone(),
+                updated_at: exported.thread.updated_at,
+            };
+            let database = database_future.await.map_err(|err| anyhow!(err))?;
+            database
+                .save_thread(metadata.id.clone(), exported.thread)
+                .await?;
+            this.update(cx, |this, cx| this.reload(cx))?;
+            Ok(ImportedThread {
+                metadata,
+                diff: exported.diff,
+            })
+        })
+    }
+
     pub fn delete_threads(&mut self, cx: &mut Context<Self>) -> Task<Result<()>> {
         let database_future = ThreadsDatabase::connect(cx);
         cx.spawn(async move |this, cx| {
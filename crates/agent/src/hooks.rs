@@ -0,0 +1,158 @@
+use agent_settings::AgentHooksSettings;
+use anyhow::Result;
+use futures::AsyncWriteExt as _;
+use gpui::{App, AsyncApp, Entity, Task};
+use language_model::LanguageModelToolResultContent;
+use project::Project;
+use serde::Serialize;
+use settings::{AgentHookContent, Settings as _, SettingsLocation};
+use std::process::Stdio;
+use util::{ResultExt as _, rel_path::RelPath};
+
+/// An agent lifecycle event, sent as JSON on stdin to every hook configured for it.
+#[derive(Debug, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum HookEvent {
+    PreToolUse {
+        session_id: String,
+        tool_name: String,
+        tool_input: serde_json::Value,
+    },
+    PostToolUse {
+        session_id: String,
+        tool_name: String,
+        tool_input: serde_json::Value,
+        tool_output: String,
+        is_error: bool,
+    },
+    Stop {
+        session_id: String,
+        last_agent_message: String,
+        /// Whether the turn is continuing because a stop hook failed before.
+        stop_hook_active: bool,
+    },
+}
+
+impl HookEvent {
+    fn hooks(&self, settings: &AgentHooksSettings) -> Vec<AgentHookContent> {
+        match self {
+            HookEvent::PreToolUse { tool_name, .. } => settings
+                .pre_tool_use
+                .iter()
+                .filter(|hook| hook.matches_tool(tool_name))
+                .cloned()
+                .collect(),
+            HookEvent::PostToolUse { tool_name, .. } => settings
+                .post_tool_use
+                .iter()
+                .filter(|hook| hook.matches_tool(tool_name))
+                .cloned()
+                .collect(),
+            HookEvent::Stop { .. } => settings.stop.clone(),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum HookOutcome {
+    /// Every hook exited successfully. Contains whatever they printed.
+    Continue(Option<String>),
+    /// A hook exited with a non-zero status. Contains what it printed, explaining why.
+    Block(String),
+}
+
+/// Runs the project's hooks for the given event one after another, stopping at the first
+/// one that blocks. Returns `None` when no hooks are configured for the event.
+pub fn run_hooks(
+    project: &Entity<Project>,
+    event: HookEvent,
+    cx: &mut App,
+) -> Option<Task<HookOutcome>> {
+    let location = project
+        .read(cx)
+        .visible_worktrees(cx)
+        .next()
+        .map(|worktree| SettingsLocation {
+            worktree_id: worktree.read(cx).id(),
+            path: RelPath::empty(),
+        });
+    let hooks = event.hooks(AgentHooksSettings::get(location, cx));
+    if hooks.is_empty() {
+        return None;
+    }
+
+    let input = serde_json::to_vec(&event).log_err()?;
+    let project = project.clone();
+    Some(cx.spawn(async move |cx| {
+        let mut outputs = Vec::new();
+        for hook in hooks {
+            match run_hook(&project, &hook, input.clone(), cx).await {
+                Ok((true, output)) => {
+                    if !output.is_empty() {
+                        outputs.push(output);
+                    }
+                }
+                Ok((false, output)) => {
+                    return HookOutcome::Block(if output.is_empty() {
+                        format!("Blocked by hook `{}`", hook.command)
+                    } else {
+                        output
+                    });
+                }
+                Err(error) => {
+                    log::error!("Failed to run agent hook `{}`: {error:#}", hook.command);
+                }
+            }
+        }
+        HookOutcome::Continue((!outputs.is_empty()).then(|| outputs.join("\n")))
+    }))
+}
+
+async fn run_hook(
+    project: &Entity<Project>,
+    hook: &AgentHookContent,
+    input: Vec<u8>,
+    cx: &mut AsyncApp,
+) -> Result<(bool, String)> {
+    let mut command = project
+        .update(cx, |project, cx| {
+            project.exec_in_shell(hook.command.clone(), cx)
+        })?
+        .await?;
+    command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    let mut child = command.spawn()?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        // Hooks aren't required to read their input, so a broken pipe is fine.
+        cx.background_spawn(async move {
+            stdin.write_all(&input).await.ok();
+            stdin.flush().await.ok();
+        })
+        .detach();
+    }
+
+    let output = cx.background_spawn(child.output()).await?;
+    let mut text = String::from_utf8_lossy(&output.stdout).into_owned();
+    text.push_str(&String::from_utf8_lossy(&output.stderr));
+    Ok((output.status.success(), text.trim().to_string()))
+}
+
+/// Appends the output of a hook to the result the model sees for a tool call.
+pub fn append_hook_output(
+    content: LanguageModelToolResultContent,
+    hook_output: &str,
+) -> LanguageModelToolResultContent {
+    match content {
+        LanguageModelToolResultContent::Text(text) => {
+            LanguageModelToolResultContent::Text(format!("{text}\n\n{hook_output}").into())
+        }
+        LanguageModelToolResultContent::Image(image) => {
+            log::warn!("Dropping hook output for a tool that returned an image: {hook_output}");
+            LanguageModelToolResultContent::Image(image)
+        }
+    }
+}
@@ -24,8 +24,9 @@ use indoc::indoc;
 use language_model::{
     LanguageModel, LanguageModelCompletionError, LanguageModelCompletionEvent, LanguageModelId,
     LanguageModelProviderName, LanguageModelRegistry, LanguageModelRequest,
-    LanguageModelRequestMessage, LanguageModelToolResult, LanguageModelToolSchemaFormat,
-    LanguageModelToolUse, MessageContent, Role, StopReason, fake_provider::FakeLanguageModel,
+    LanguageModelRequestMessage, LanguageModelToolResult, LanguageModelToolResultContent,
+    LanguageModelToolSchemaFormat, LanguageModelToolUse, MessageContent, Role, StopReason,
+    fake_provider::FakeLanguageModel,
 };
 use pretty_assertions::assert_eq;
 use project::{
@@ -1208,6 +1209,344 @@ async fn test_profiles(cx: &mut TestAppContext) {
     assert_eq!(tool_names, vec![InfiniteTool::name()]);
 }
 
+#[gpui::test]
+async fn test_delegate_tool(cx: &mut TestAppContext) {
+    let ThreadTest { model, thread, .. } = setup(cx, TestModel::Fake).await;
+    let fake_model = model.as_fake();
+
+    let handle = Rc::new(cx.update(|cx| FakeTerminalHandle::new_never_exits(cx)));
+    let environment = Rc::new(FakeThreadEnvironment { handle });
+    let events = thread
+        .update(cx, |thread, cx| {
+            thread.add_tool(DelegateTool::new(cx.weak_entity(), environment));
+            thread.send(UserMessageId::new(), ["Why does the parser test fail?"], cx)
+        })
+        .unwrap();
+    cx.run_until_parked();
+
+    let input = json!({
+        "title": "Investigate parser test",
+        "task": "Find out why test_parse fails in parser.rs.",
+        "tools": [ThinkingTool::name()],
+    });
+    fake_model.send_last_completion_stream_event(LanguageModelCompletionEvent::ToolUse(
+        LanguageModelToolUse {
+            id: "delegate_1".into(),
+            name: DelegateTool::name().into(),
+            raw_input: input.to_string(),
+            input,
+            is_input_complete: true,
+            thought_signature: None,
+        },
+    ));
+    fake_model.end_last_completion_stream();
+    cx.run_until_parked();
+
+    // The sub-agent starts from a fresh context and can't delegate further.
+    let subagent_request = fake_model.pending_completions().pop().unwrap();
+    assert_eq!(
+        tool_names_for_completion(&subagent_request),
+        vec![ThinkingTool::name()]
+    );
+    assert_eq!(subagent_request.messages.len(), 2);
+    assert!(
+        subagent_request.messages[1]
+            .string_contents()
+            .starts_with("Find out why test_parse fails in parser.rs.")
+    );
+    fake_model.send_last_completion_stream_text_chunk("The fixture is missing a newline.");
+    fake_model.end_last_completion_stream();
+    cx.run_until_parked();
+
+    // Only the sub-agent's final response is reported back to the parent.
+    let parent_request = fake_model.pending_completions().pop().unwrap();
+    let Some(MessageContent::ToolResult(tool_result)) = parent_request
+        .messages
+        .last()
+        .and_then(|message| message.content.first())
+    else {
+        panic!("expected the last message to contain a tool result");
+    };
+    assert!(!tool_result.is_error);
+    assert_eq!(
+        tool_result.content,
+        "The fixture is missing a newline.".into()
+    );
+    fake_model.send_last_completion_stream_text_chunk("Fixed.");
+    fake_model.end_last_completion_stream();
+
+    let events = events.collect::<Vec<_>>().await;
+    assert_eq!(stop_events(events), vec![acp::StopReason::EndTurn]);
+}
+
+#[gpui::test]
+async fn test_delegate_tool_with_unknown_tools(cx: &mut TestAppContext) {
+    let ThreadTest { model, thread, .. } = setup(cx, TestModel::Fake).await;
+    let fake_model = model.as_fake();
+
+    let handle = Rc::new(cx.update(|cx| FakeTerminalHandle::new_never_exits(cx)));
+    let environment = Rc::new(FakeThreadEnvironment { handle });
+    let events = thread
+        .update(cx, |thread, cx| {
+            thread.add_tool(DelegateTool::new(cx.weak_entity(), environment));
+            thread.send(UserMessageId::new(), ["Why does the parser test fail?"], cx)
+        })
+        .unwrap();
+    cx.run_until_parked();
+
+    let input = json!({
+        "title": "Investigate parser test",
+        "task": "Find out why test_parse fails in parser.rs.",
+        "tools": [ThinkingTool::name(), "read_everything", "fix_it"],
+    });
+    fake_model.send_last_completion_stream_event(LanguageModelCompletionEvent::ToolUse(
+        LanguageModelToolUse {
+            id: "delegate_1".into(),
+            name: DelegateTool::name().into(),
+            raw_input: input.to_string(),
+            input,
+            is_input_complete: true,
+            thought_signature: None,
+        },
+    ));
+    fake_model.end_last_completion_stream();
+    cx.run_until_parked();
+
+    // No sub-agent is started, and the model is told which names were wrong.
+    let parent_request = fake_model.pending_completions().pop().unwrap();
+    let Some(MessageContent::ToolResult(tool_result)) = parent_request
+        .messages
+        .last()
+        .and_then(|message| message.content.first())
+    else {
+        panic!("expected the last message to contain a tool result");
+    };
+    assert!(tool_result.is_error);
+    let LanguageModelToolResultContent::Text(error) = &tool_result.content else {
+        panic!("expected a text tool result");
+    };
+    assert!(
+        error.starts_with("Unknown tools: fix_it, read_everything."),
+        "unexpected error: {error}"
+    );
+    fake_model.end_last_completion_stream();
+
+    let events = events.collect::<Vec<_>>().await;
+    assert_eq!(stop_events(events), vec![acp::StopReason::EndTurn]);
+}
+
+#[cfg(unix)]
+#[gpui::test]
+async fn test_pre_tool_use_hook_blocks_tool_call(cx: &mut TestAppContext) {
+    let (ThreadTest { model, thread, .. }, _dir) = setup_with_hooks(
+        cx,
+        json!({
+            "pre_tool_use": [{
+                "command": "grep -q '\"tool_name\":\"echo\"' && echo 'echo is not allowed here' && exit 1",
+                "tools": [EchoTool::name()],
+            }]
+        }),
+    )
+    .await;
+    let fake_model = model.as_fake();
+
+    let mut events = thread
+        .update(cx, |thread, cx| {
+            thread.add_tool(EchoTool);
+            thread.send(UserMessageId::new(), ["Say hello"], cx)
+        })
+        .unwrap();
+    cx.run_until_parked();
+    send_echo_tool_use(fake_model, "hello");
+
+    let update = next_tool_call_result(&mut events).await;
+    assert_eq!(update.fields.status, Some(acp::ToolCallStatus::Failed));
+    cx.run_until_parked();
+
+    let tool_result = last_tool_result(fake_model);
+    assert!(tool_result.is_error);
+    assert_eq!(
+        tool_result.content,
+        "A hook blocked this tool call:\necho is not allowed here".into()
+    );
+}
+
+#[cfg(unix)]
+#[gpui::test]
+async fn test_post_tool_use_hook_annotates_tool_result(cx: &mut TestAppContext) {
+    let (ThreadTest { model, thread, .. }, _dir) = setup_with_hooks(
+        cx,
+        json!({
+            "post_tool_use": [{
+                "command": "grep -q '\"tool_output\":\"hello\"' && echo 'Remember to run the formatter.'",
+            }]
+        }),
+    )
+    .await;
+    let fake_model = model.as_fake();
+
+    let mut events = thread
+        .update(cx, |thread, cx| {
+            thread.add_tool(EchoTool);
+            thread.send(UserMessageId::new(), ["Say hello"], cx)
+        })
+        .unwrap();
+    cx.run_until_parked();
+    send_echo_tool_use(fake_model, "hello");
+
+    let update = next_tool_call_result(&mut events).await;
+    assert_eq!(update.fields.status, Some(acp::ToolCallStatus::Completed));
+    cx.run_until_parked();
+
+    let tool_result = last_tool_result(fake_model);
+    assert!(!tool_result.is_error);
+    assert_eq!(
+        tool_result.content,
+        "hello\n\nRemember to run the formatter.".into()
+    );
+}
+
+#[cfg(unix)]
+#[gpui::test]
+async fn test_stop_hook_continues_turn(cx: &mut TestAppContext) {
+    // Blocks the first attempt to stop, and lets the agent stop once it's continuing.
+    let (ThreadTest { model, thread, .. }, _dir) = setup_with_hooks(
+        cx,
+        json!({
+            "stop": [{
+                "command": "grep -q '\"stop_hook_active\":true' || { echo 'Run the tests first.'; exit 1; }",
+            }]
+        }),
+    )
+    .await;
+    let fake_model = model.as_fake();
+
+    let mut events = thread
+        .update(cx, |thread, cx| {
+            thread.send(UserMessageId::new(), ["Fix the bug"], cx)
+        })
+        .unwrap();
+    cx.run_until_parked();
+    fake_model.send_last_completion_stream_text_chunk("Fixed it.");
+    fake_model.end_last_completion_stream();
+
+    loop {
+        let event = events
+            .next()
+            .await
+            .expect("no hook message received")
+            .unwrap();
+        if let ThreadEvent::UserMessage(message) = event
+            && message.to_markdown().contains("Run the tests first.")
+        {
+            break;
+        }
+    }
+    cx.run_until_parked();
+
+    let request = fake_model.pending_completions().pop().unwrap();
+    assert_eq!(
+        request.messages.last().unwrap().string_contents(),
+        "A stop hook blocked ending the turn:\n\nRun the tests first."
+    );
+    fake_model.send_last_completion_stream_text_chunk("The tests pass.");
+    fake_model.end_last_completion_stream();
+
+    let events = events.collect::<Vec<_>>().await;
+    assert_eq!(stop_events(events), vec![acp::StopReason::EndTurn]);
+    thread.read_with(cx, |thread, _| {
+        assert!(thread.to_markdown().contains("The tests pass."));
+    });
+}
+
+/// Sets up a thread whose project lives in a real directory, since hooks run as
+/// shell commands in the project's first worktree.
+#[cfg(unix)]
+async fn setup_with_hooks(
+    cx: &mut TestAppContext,
+    hooks: serde_json::Value,
+) -> (ThreadTest, tempfile::TempDir) {
+    let mut test = setup(cx, TestModel::Fake).await;
+    cx.run_until_parked();
+    cx.update(|cx| {
+        SettingsStore::update_global(cx, |store, cx| {
+            store.update_user_settings(cx, |settings| {
+                settings.project.agent_hooks = Some(serde_json::from_value(hooks).unwrap());
+                // Run hooks with a plain `sh` instead of loading the user's shell environment.
+                settings.project.load_direnv = Some(settings::DirenvSettings::Disabled);
+                settings.terminal.get_or_insert_default().project.shell =
+                    Some(settings::Shell::Program("sh".into()));
+            })
+        })
+    });
+
+    let dir = tempfile::tempdir().unwrap();
+    test.fs.insert_tree(dir.path(), json!({})).await;
+    let project = Project::test(test.fs.clone(), [dir.path()], cx).await;
+    let context_server_registry =
+        cx.new(|cx| ContextServerRegistry::new(project.read(cx).context_server_store(), cx));
+    test.thread = cx.new(|cx| {
+        Thread::new(
+            project,
+            test.project_context.clone(),
+            context_server_registry,
+            Templates::new(),
+            Some(test.model.clone()),
+            cx,
+        )
+    });
+    (test, dir)
+}
+
+fn send_echo_tool_use(fake_model: &FakeLanguageModel, text: &str) {
+    let input = json!({ "text": text });
+    fake_model.send_last_completion_stream_event(LanguageModelCompletionEvent::ToolUse(
+        LanguageModelToolUse {
+            id: "echo_1".into(),
+            name: EchoTool::name().into(),
+            raw_input: input.to_string(),
+            input,
+            is_input_complete: true,
+            thought_signature: None,
+        },
+    ));
+    fake_model.end_last_completion_stream();
+}
+
+/// Waits for a tool call to finish. Hooks run as real processes, so
+/// `run_until_parked` can return before they exit.
+async fn next_tool_call_result(
+    events: &mut UnboundedReceiver<Result<ThreadEvent>>,
+) -> acp::ToolCallUpdate {
+    loop {
+        let event = events
+            .next()
+            .await
+            .expect("no tool call result received")
+            .unwrap();
+        if let ThreadEvent::ToolCallUpdate(acp_thread::ToolCallUpdate::UpdateFields(update)) = event
+            && matches!(
+                update.fields.status,
+                Some(acp::ToolCallStatus::Completed | acp::ToolCallStatus::Failed)
+            )
+        {
+            return update;
+        }
+    }
+}
+
+fn last_tool_result(fake_model: &FakeLanguageModel) -> LanguageModelToolResult {
+    let request = fake_model.pending_completions().pop().unwrap();
+    let Some(MessageContent::ToolResult(tool_result)) = request
+        .messages
+        .last()
+        .and_then(|message| message.content.first())
+    else {
+        panic!("expected the last message to contain a tool result");
+    };
+    tool_result.clone()
+}
+
 #[gpui::test]
 async fn test_mcp_tools(cx: &mut TestAppContext) {
     let ThreadTest {
@@ -2010,6 +2349,175 @@ async fn test_title_generation(cx: &mut TestAppContext) {
     thread.read_with(cx, |thread, _| assert_eq!(thread.title(), "Hello world"));
 }
 
+#[gpui::test]
+async fn test_auto_compaction(cx: &mut TestAppContext) {
+    let ThreadTest { model, thread, .. } = setup(cx, TestModel::Fake).await;
+    let fake_model = model.as_fake();
+
+    let summary_model = Arc::new(FakeLanguageModel::default());
+    thread.update(cx, |thread, cx| {
+        thread.set_summarization_model(Some(summary_model.clone()), cx);
+        thread.set_title("Compaction".into(), cx);
+    });
+
+    let events = thread
+        .update(cx, |thread, cx| {
+            thread.send(UserMessageId::new(), ["Message 1"], cx)
+        })
+        .unwrap();
+    cx.run_until_parked();
+    fake_model.send_last_completion_stream_text_chunk("Response 1");
+    fake_model.send_last_completion_stream_event(LanguageModelCompletionEvent::UsageUpdate(
+        language_model::TokenUsage {
+            input_tokens: fake_model.max_token_count() * 9 / 10,
+            output_tokens: 0,
+            cache_creation_input_tokens: 0,
+            cache_read_input_tokens: 0,
+        },
+    ));
+    fake_model.end_last_completion_stream();
+    events.collect::<Vec<_>>().await;
+
+    // The thread is close to the context window, so the next turn compacts it
+    // before talking to the model.
+    let events = thread
+        .update(cx, |thread, cx| {
+            thread.send(UserMessageId::new(), ["Message 2"], cx)
+        })
+        .unwrap();
+    cx.run_until_parked();
+    assert_eq!(fake_model.pending_completions(), Vec::new());
+    thread.read_with(cx, |thread, _| assert!(thread.is_compacting()));
+
+    let compaction_request = summary_model.pending_completions().pop().unwrap();
+    assert_eq!(
+        compaction_request
+            .messages
+            .iter()
+            .map(|message| message.string_contents())
+            .collect::<Vec<_>>(),
+        vec![
+            "Message 1".to_string(),
+            "Response 1".to_string(),
+            agent_settings::COMPACT_THREAD_PROMPT.to_string(),
+        ]
+    );
+    summary_model.send_last_completion_stream_text_chunk("The user said hello.");
+    summary_model.end_last_completion_stream();
+    cx.run_until_parked();
+
+    // Only the summary and the unanswered message are sent to the model.
+    let request = fake_model.pending_completions().pop().unwrap();
+    assert_eq!(request.messages.len(), 3);
+    assert!(
+        request.messages[1]
+            .string_contents()
+            .contains("<summary>\nThe user said hello.\n</summary>")
+    );
+    assert_eq!(request.messages[2].string_contents(), "Message 2");
+    fake_model.send_last_completion_stream_text_chunk("Response 2");
+    fake_model.end_last_completion_stream();
+
+    let events = events.collect::<Vec<_>>().await;
+    assert!(
+        events
+            .iter()
+            .any(|event| matches!(event, Ok(ThreadEvent::Compaction(_))))
+    );
+
+    // The full transcript is preserved for display.
+    thread.read_with(cx, |thread, _| {
+        assert!(!thread.is_compacting());
+        assert_eq!(
+            thread.to_markdown(),
+            indoc! {"
+                ## User
+
+                Message 1
+
+                ## Assistant
+
+                Response 1
+
+                ## Compacted Context
+
+                The user said hello.
+
+                ## User
+
+                Message 2
+
+                ## Assistant
+
+                Response 2
+            "}
+        );
+    });
+}
+
+#[gpui::test]
+async fn test_cancel_during_compaction(cx: &mut TestAppContext) {
+    let ThreadTest { model, thread, .. } = setup(cx, TestModel::Fake).await;
+    let fake_model = model.as_fake();
+
+    let summary_model = Arc::new(FakeLanguageModel::default());
+    thread.update(cx, |thread, cx| {
+        thread.set_summarization_model(Some(summary_model.clone()), cx);
+        thread.set_title("Compaction".into(), cx);
+    });
+
+    let events = thread
+        .update(cx, |thread, cx| {
+            thread.send(UserMessageId::new(), ["Message 1"], cx)
+        })
+        .unwrap();
+    cx.run_until_parked();
+    fake_model.send_last_completion_stream_text_chunk("Response 1");
+    fake_model.send_last_completion_stream_event(LanguageModelCompletionEvent::UsageUpdate(
+        language_model::TokenUsage {
+            input_tokens: fake_model.max_token_count() * 9 / 10,
+            output_tokens: 0,
+            cache_creation_input_tokens: 0,
+            cache_read_input_tokens: 0,
+        },
+    ));
+    fake_model.end_last_completion_stream();
+    events.collect::<Vec<_>>().await;
+
+    let events = thread
+        .update(cx, |thread, cx| {
+            thread.send(UserMessageId::new(), ["Message 2"], cx)
+        })
+        .unwrap();
+    cx.run_until_parked();
+    thread.read_with(cx, |thread, _| assert!(thread.is_compacting()));
+
+    // Cancelling the turn aborts the compaction without inserting a summary.
+    thread.update(cx, |thread, cx| thread.cancel(cx));
+    events.collect::<Vec<_>>().await;
+    cx.run_until_parked();
+    thread.read_with(cx, |thread, _| {
+        assert!(!thread.is_compacting());
+        assert!(!thread.to_markdown().contains("Compacted Context"));
+    });
+
+    // The thread can still be compacted afterwards, and the compaction
+    // finishes even if its task is dropped.
+    thread.update(cx, |thread, cx| thread.compact(cx)).detach();
+    cx.run_until_parked();
+    thread.read_with(cx, |thread, _| assert!(thread.is_compacting()));
+    summary_model.send_last_completion_stream_text_chunk("The user said hello.");
+    summary_model.end_last_completion_stream();
+    cx.run_until_parked();
+    thread.read_with(cx, |thread, _| {
+        assert!(!thread.is_compacting());
+        let markdown = thread.to_markdown();
+        let summary_ix = markdown.find("The user said hello.").unwrap();
+        assert!(markdown.find("Response 1").unwrap() < summary_ix);
+        assert!(summary_ix < markdown.find("Message 2").unwrap());
+    });
+}
+
 #[gpui::test]
 async fn test_building_request_with_pending_tools(cx: &mut TestAppContext) {
     let ThreadTest { model, thread, .. } = setup(cx, TestModel::Fake).await;
@@ -2612,6 +3120,7 @@ async fn setup(cx: &mut TestAppContext, model: TestModel) -> ThreadTest {
                             ToolRequiringPermission::name(): true,
                             InfiniteTool::name(): true,
                             ThinkingTool::name(): true,
+                            DelegateTool::name(): true,
                         }
                     }
                 }
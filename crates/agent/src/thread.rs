@@ -1,16 +1,17 @@
 use crate::{
     ContextServerRegistry, CopyPathTool, CreateDirectoryTool, DbLanguageModel, DbThread,
-    DeletePathTool, DiagnosticsTool, EditFileTool, FetchTool, FindPathTool, GrepTool,
-    ListDirectoryTool, MovePathTool, NowTool, OpenTool, ProjectSnapshot, ReadFileTool,
-    RestoreFileFromDiskTool, SaveFileTool, SystemPromptTemplate, Template, Templates, TerminalTool,
-    ThinkingTool, WebSearchTool,
+    DelegateTool, DeletePathTool, DiagnosticsTool, EditFileTool, ExportedThread, FetchTool,
+    FindPathTool, GrepTool, ListDirectoryTool, MovePathTool, NowTool, OpenTool, ProjectSnapshot,
+    ReadFileTool, RestoreFileFromDiskTool, SaveFileTool, SystemPromptTemplate, Template, Templates,
+    TerminalTool, ThinkingTool, WebSearchTool,
+    hooks::{self, HookEvent, HookOutcome},
 };
 use acp_thread::{MentionUri, UserMessageId};
 use action_log::ActionLog;
 
 use agent_client_protocol as acp;
 use agent_settings::{
-    AgentProfileId, AgentProfileSettings, AgentSettings, CompletionMode,
+    AgentProfileId, AgentProfileSettings, AgentSettings, COMPACT_THREAD_PROMPT, CompletionMode,
     SUMMARIZE_THREAD_DETAILED_PROMPT, SUMMARIZE_THREAD_PROMPT,
 };
 use anyhow::{Context as _, Result, anyhow};
@@ -29,6 +30,7 @@ use futures::{
 use gpui::{
     App, AppContext, AsyncApp, Context, Entity, EventEmitter, SharedString, Task, WeakEntity,
 };
+use itertools::Itertools as _;
 use language_model::{
     LanguageModel, LanguageModelCompletionError, LanguageModelCompletionEvent, LanguageModelExt,
     LanguageModelId, LanguageModelImage, LanguageModelProviderId, LanguageModelRegistry,
@@ -56,6 +58,8 @@ use util::{ResultExt, debug_panic, markdown::MarkdownCodeBlock, paths::PathStyle
 use uuid::Uuid;
 
 const TOOL_CANCELED_MESSAGE: &str = "Tool canceled by user";
+/// How many times stop hooks can keep a single turn going.
+const MAX_STOP_HOOK_CONTINUATIONS: usize = 3;
 pub const MAX_TOOL_NAME_LENGTH: usize = 64;
 
 /// The ID of the user prompt that initiated a request.
@@ -96,6 +100,9 @@ pub enum Message {
     User(UserMessage),
     Agent(AgentMessage),
     Resume,
+    /// Replaces every message before it when building requests, while the
+    /// full transcript is kept for display.
+    Compaction(CompactionSummary),
 }
 
 impl Message {
@@ -122,6 +129,7 @@ impl Message {
                 cache: false,
                 reasoning_details: None,
             }],
+            Message::Compaction(compaction) => vec![compaction.to_request()],
         }
     }
 
@@ -130,17 +138,47 @@ impl Message {
             Message::User(message) => message.to_markdown(),
             Message::Agent(message) => message.to_markdown(),
             Message::Resume => "[resume]\n".into(),
+            Message::Compaction(compaction) => compaction.to_markdown(),
         }
     }
 
     pub fn role(&self) -> Role {
         match self {
-            Message::User(_) | Message::Resume => Role::User,
+            Message::User(_) | Message::Resume | Message::Compaction(_) => Role::User,
             Message::Agent(_) => Role::Assistant,
         }
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompactionSummary {
+    pub summary: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl CompactionSummary {
+    pub fn to_markdown(&self) -> String {
+        format!("## Compacted Context\n\n{}\n", self.summary)
+    }
+
+    fn to_request(&self) -> LanguageModelRequestMessage {
+        LanguageModelRequestMessage {
+            role: Role::User,
+            content: vec![
+                format!(
+                    "The earlier part of this conversation was compacted to save context. \
+                    This is a summary of everything that happened before this point:\n\n\
+                    <summary>\n{}\n</summary>",
+                    self.summary.trim()
+                )
+                .into(),
+            ],
+            cache: false,
+            reasoning_details: None,
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct UserMessage {
     pub id: UserMessageId,
@@ -559,6 +597,7 @@ pub enum ThreadEvent {
     ToolCallUpdate(acp_thread::ToolCallUpdate),
     ToolCallAuthorization(ToolCallAuthorization),
     Retry(acp_thread::RetryStatus),
+    Compaction(CompactionSummary),
     Stop(acp::StopReason),
 }
 
@@ -595,6 +634,9 @@ pub struct Thread {
     pending_title_generation: Option<Task<()>>,
     pending_summary_generation: Option<Shared<Task<Option<SharedString>>>>,
     summary: Option<SharedString>,
+    /// Holds the task that summarizes the thread while it's being compacted.
+    /// Dropping it, e.g. when the thread is cancelled, aborts the compaction.
+    pending_compaction: Option<Task<()>>,
     messages: Vec<Message>,
     user_store: Entity<UserStore>,
     completion_mode: CompletionMode,
@@ -604,6 +646,8 @@ pub struct Thread {
     running_turn: Option<RunningTurn>,
     pending_message: Option<AgentMessage>,
     tools: BTreeMap<SharedString, Arc<dyn AnyAgentTool>>,
+    /// When set, only these tools are offered to the model, regardless of the profile.
+    tool_subset: Option<HashSet<SharedString>>,
     tool_use_limit_reached: bool,
     request_token_usage: HashMap<UserMessageId, language_model::TokenUsage>,
     #[allow(unused)]
@@ -652,12 +696,14 @@ impl Thread {
             pending_title_generation: None,
             pending_summary_generation: None,
             summary: None,
+            pending_compaction: None,
             messages: Vec::new(),
             user_store: project.read(cx).user_store(),
             completion_mode: AgentSettings::get_global(cx).preferred_completion_mode,
             running_turn: None,
             pending_message: None,
             tools: BTreeMap::default(),
+            tool_subset: None,
             tool_use_limit_reached: false,
             request_token_usage: HashMap::default(),
             cumulative_token_usage: TokenUsage::default(),
@@ -714,6 +760,7 @@ impl Thread {
                     }
                 }
                 Message::Resume => {}
+                Message::Compaction(compaction) => stream.send_compaction(compaction),
             }
         }
         rx
@@ -844,12 +891,14 @@ impl Thread {
             pending_title_generation: None,
             pending_summary_generation: None,
             summary: db_thread.detailed_summary,
+            pending_compaction: None,
             messages: db_thread.messages,
             user_store: project.read(cx).user_store(),
             completion_mode: db_thread.completion_mode.unwrap_or_default(),
             running_turn: None,
             pending_message: None,
             tools: BTreeMap::default(),
+            tool_subset: None,
             tool_use_limit_reached: false,
             request_token_usage: db_thread.request_token_usage.clone(),
             cumulative_token_usage: db_thread.cumulative_token_usage,
@@ -894,6 +943,20 @@ impl Thread {
         })
     }
 
+    /// Serializes the thread, including the agent's unreviewed edits, so it can be
+    /// imported into another user's history with [`crate::HistoryStore::import_thread`].
+    pub fn to_export(&self, cx: &App) -> Task<ExportedThread> {
+        let diff = self.action_log.read(cx).unified_diff(cx);
+        let thread = self.to_db(cx);
+        cx.background_spawn(async move {
+            ExportedThread {
+                exported_at: Utc::now(),
+                thread: thread.await,
+                diff,
+            }
+        })
+    }
+
     /// Create a snapshot of the current project state including git information and unsaved buffers.
     fn project_snapshot(
         project: Entity<Project>,
@@ -986,6 +1049,7 @@ impl Thread {
         let language_registry = self.project.read(cx).languages().clone();
         self.add_tool(CopyPathTool::new(self.project.clone()));
         self.add_tool(CreateDirectoryTool::new(self.project.clone()));
+        self.add_tool(DelegateTool::new(cx.weak_entity(), environment.clone()));
         self.add_tool(DeletePathTool::new(
             self.project.clone(),
             self.action_log.clone(),
@@ -1024,6 +1088,74 @@ impl Thread {
         self.tools.remove(name).is_some()
     }
 
+    /// Restricts the tools offered to the model to the given names, on top of
+    /// what the profile enables. Passing `None` lifts the restriction.
+    ///
+    /// Fails without changing the restriction if any of the names doesn't
+    /// belong to a tool registered on this thread or a context server.
+    pub fn set_tool_subset(
+        &mut self,
+        tool_subset: Option<HashSet<SharedString>>,
+        cx: &App,
+    ) -> Result<()> {
+        if let Some(tool_subset) = tool_subset.as_ref() {
+            let context_server_registry = self.context_server_registry.read(cx);
+            let unknown_tools = tool_subset
+                .iter()
+                .filter(|tool_name| {
+                    !self.tools.contains_key(*tool_name)
+                        && !context_server_registry
+                            .servers()
+                            .any(|(_, server_tools)| server_tools.contains_key(*tool_name))
+                })
+                .sorted()
+                .collect::<Vec<_>>();
+            if !unknown_tools.is_empty() {
+                return Err(anyhow!(
+                    "Unknown tools: {}. Available tools: {}",
+                    unknown_tools.iter().join(", "),
+                    self.tools.keys().join(", ")
+                ));
+            }
+        }
+        self.tool_subset = tool_subset;
+        Ok(())
+    }
+
+    /// Creates a thread with its own context window for running a delegated
+    /// task. It shares this thread's project, models, context servers and
+    /// action log, so edits it makes show up for review here.
+    pub fn new_subagent(
+        &self,
+        profile_id: Option<AgentProfileId>,
+        cx: &mut Context<Self>,
+    ) -> Entity<Thread> {
+        let project = self.project.clone();
+        let project_context = self.project_context.clone();
+        let context_server_registry = self.context_server_registry.clone();
+        let templates = self.templates.clone();
+        let model = self.model.clone();
+        let summarization_model = self.summarization_model.clone();
+        let completion_mode = self.completion_mode;
+        let action_log = self.action_log.clone();
+        let profile_id = profile_id.unwrap_or_else(|| self.profile_id.clone());
+        cx.new(|cx| {
+            let mut thread = Thread::new(
+                project,
+                project_context,
+                context_server_registry,
+                templates,
+                model,
+                cx,
+            );
+            thread.action_log = action_log;
+            thread.completion_mode = completion_mode;
+            thread.set_summarization_model(summarization_model, cx);
+            thread.set_profile(profile_id, cx);
+            thread
+        })
+    }
+
     pub fn profile(&self) -> &AgentProfileId {
         &self.profile_id
     }
@@ -1045,6 +1177,9 @@ impl Thread {
         if let Some(running_turn) = self.running_turn.take() {
             running_turn.cancel();
         }
+        if self.pending_compaction.take().is_some() {
+            cx.notify();
+        }
         self.flush_pending_message(cx);
     }
 
@@ -1072,7 +1207,7 @@ impl Thread {
                 Message::User(message) => {
                     self.request_token_usage.remove(&message.id);
                 }
-                Message::Agent(_) | Message::Resume => {}
+                Message::Agent(_) | Message::Resume | Message::Compaction(_) => {}
             }
         }
         self.clear_summary();
@@ -1298,7 +1433,16 @@ impl Thread {
     ) -> Result<()> {
         let mut attempt = 0;
         let mut intent = CompletionIntent::UserPrompt;
+        let mut stop_hook_continuations = 0;
         loop {
+            if this.read_with(cx, |this, cx| this.should_auto_compact(cx))? {
+                let compaction = this.update(cx, |this, cx| this.compact(cx))?;
+                match compaction.await {
+                    Ok(compaction) => event_stream.send_compaction(&compaction),
+                    Err(error) => log::error!("Failed to compact thread: {error:#}"),
+                }
+            }
+
             let request =
                 this.update(cx, |this, cx| this.build_completion_request(intent, cx))??;
 
@@ -1381,7 +1525,28 @@ impl Thread {
             } else if this.read_with(cx, |this, _| this.tool_use_limit_reached)? {
                 return Err(language_model::ToolUseLimitReachedError.into());
             } else if end_turn {
-                return Ok(());
+                if stop_hook_continuations >= MAX_STOP_HOOK_CONTINUATIONS {
+                    return Ok(());
+                }
+                let Some(hook_output) =
+                    Self::run_stop_hooks(this, stop_hook_continuations > 0, cx).await?
+                else {
+                    return Ok(());
+                };
+                stop_hook_continuations += 1;
+                this.update(cx, |this, cx| {
+                    let message = UserMessage {
+                        id: UserMessageId::new(),
+                        content: vec![UserMessageContent::Text(format!(
+                            "A stop hook blocked ending the turn:\n\n{hook_output}"
+                        ))],
+                    };
+                    event_stream.send_user_message(&message);
+                    this.messages.push(Message::User(message));
+                    cx.notify();
+                })?;
+                intent = CompletionIntent::UserPrompt;
+                attempt = 0;
             } else {
                 intent = CompletionIntent::ToolResults;
                 attempt = 0;
@@ -1389,6 +1554,37 @@ impl Thread {
         }
     }
 
+    /// Runs the project's stop hooks, returning their output if one of them
+    /// wants the agent to keep going.
+    async fn run_stop_hooks(
+        this: &WeakEntity<Self>,
+        stop_hook_active: bool,
+        cx: &mut AsyncApp,
+    ) -> Result<Option<String>> {
+        let stop = this.update(cx, |this, cx| {
+            let last_agent_message = match this.messages.last() {
+                Some(Message::Agent(message)) => message.to_markdown(),
+                _ => String::new(),
+            };
+            hooks::run_hooks(
+                &this.project,
+                HookEvent::Stop {
+                    session_id: this.id.to_string(),
+                    last_agent_message,
+                    stop_hook_active,
+                },
+                cx,
+            )
+        })?;
+        let Some(stop) = stop else {
+            return Ok(None);
+        };
+        match stop.await {
+            HookOutcome::Block(output) => Ok(Some(output)),
+            HookOutcome::Continue(_) => Ok(None),
+        }
+    }
+
     fn handle_completion_error(
         &mut self,
         error: LanguageModelCompletionError,
@@ -1652,9 +1848,40 @@ impl Thread {
             acp::ToolCallUpdateFields::new().status(acp::ToolCallStatus::InProgress),
         );
         let supports_images = self.model().is_some_and(|model| model.supports_images());
-        let tool_result = tool.run(tool_use.input, tool_event_stream, cx);
+        let session_id = self.id.to_string();
+        let pre_tool_use = hooks::run_hooks(
+            &self.project,
+            HookEvent::PreToolUse {
+                session_id: session_id.clone(),
+                tool_name: tool_use.name.to_string(),
+                tool_input: tool_use.input.clone(),
+            },
+            cx,
+        );
+        let tool_result = match pre_tool_use {
+            None => tool.run(tool_use.input.clone(), tool_event_stream, cx),
+            Some(pre_tool_use) => {
+                let input = tool_use.input.clone();
+                cx.spawn(async move |_, cx| match pre_tool_use.await {
+                    HookOutcome::Block(output) => {
+                        Err(anyhow!("A hook blocked this tool call:\n{output}"))
+                    }
+                    HookOutcome::Continue(hook_output) => {
+                        let mut output = cx
+                            .update(|cx| tool.run(input, tool_event_stream, cx))?
+                            .await?;
+                        if let Some(hook_output) = hook_output {
+                            output.llm_output =
+                                hooks::append_hook_output(output.llm_output, &hook_output);
+                        }
+                        Ok(output)
+                    }
+                })
+            }
+        };
         log::debug!("Running tool {}", tool_use.name);
-        Some(cx.foreground_executor().spawn(async move {
+        let project = self.project.clone();
+        Some(cx.spawn(async move |_, cx| {
             let tool_result = tool_result.await.and_then(|output| {
                 if let LanguageModelToolResultContent::Image(_) = &output.llm_output
                     && !supports_images
@@ -1666,7 +1893,7 @@ impl Thread {
                 Ok(output)
             });
 
-            match tool_result {
+            let mut result = match tool_result {
                 Ok(output) => LanguageModelToolResult {
                     tool_use_id: tool_use.id,
                     tool_name: tool_use.name,
@@ -1681,7 +1908,44 @@ impl Thread {
                     content: LanguageModelToolResultContent::Text(Arc::from(error.to_string())),
                     output: Some(error.to_string().into()),
                 },
+            };
+
+            let tool_output = match &result.content {
+                LanguageModelToolResultContent::Text(text) => text.to_string(),
+                LanguageModelToolResultContent::Image(_) => String::new(),
+            };
+            let post_tool_use = cx
+                .update(|cx| {
+                    hooks::run_hooks(
+                        &project,
+                        HookEvent::PostToolUse {
+                            session_id,
+                            tool_name: result.tool_name.to_string(),
+                            tool_input: tool_use.input,
+                            tool_output,
+                            is_error: result.is_error,
+                        },
+                        cx,
+                    )
+                })
+                .ok()
+                .flatten();
+            if let Some(post_tool_use) = post_tool_use {
+                match post_tool_use.await {
+                    HookOutcome::Continue(None) => {}
+                    HookOutcome::Continue(Some(hook_output)) => {
+                        result.content = hooks::append_hook_output(result.content, &hook_output);
+                    }
+                    HookOutcome::Block(hook_output) => {
+                        result.is_error = true;
+                        result.content = hooks::append_hook_output(
+                            result.content,
+                            &format!("A hook flagged this tool call:\n{hook_output}"),
+                        );
+                    }
+                }
             }
+            result
         }))
     }
 
@@ -1746,7 +2010,7 @@ impl Thread {
             ..Default::default()
         };
 
-        for message in &self.messages {
+        for message in self.messages_for_request() {
             request.messages.extend(message.to_request());
         }
 
@@ -1811,7 +2075,7 @@ impl Thread {
             ..Default::default()
         };
 
-        for message in &self.messages {
+        for message in self.messages_for_request() {
             request.messages.extend(message.to_request());
         }
 
@@ -1857,6 +2121,193 @@ impl Thread {
         }));
     }
 
+    pub fn is_compacting(&self) -> bool {
+        self.pending_compaction.is_some()
+    }
+
+    /// Returns the messages that are sent to the model. After a compaction,
+    /// everything before the latest [`Message::Compaction`] is replaced by it.
+    fn messages_for_request(&self) -> &[Message] {
+        let start = self
+            .messages
+            .iter()
+            .rposition(|message| matches!(message, Message::Compaction(_)))
+            .unwrap_or(0);
+        &self.messages[start..]
+    }
+
+    fn should_auto_compact(&self, cx: &App) -> bool {
+        let settings = AgentSettings::get_global(cx);
+        if !settings.auto_compact || self.is_compacting() || self.summarization_model.is_none() {
+            return false;
+        }
+
+        let Some(model) = self.model.as_ref() else {
+            return false;
+        };
+        let max_tokens = model.max_token_count_for_mode(self.completion_mode.into());
+
+        // Usage recorded before the latest compaction was measured against
+        // messages we no longer send, so only consider what came after it.
+        let Some(usage) = self
+            .messages_for_request()
+            .iter()
+            .rev()
+            .find_map(|message| match message {
+                Message::User(message) => self.request_token_usage.get(&message.id),
+                Message::Agent(_) | Message::Resume | Message::Compaction(_) => None,
+            })
+        else {
+            return false;
+        };
+
+        max_tokens > 0
+            && usage.total_tokens() as f64
+                >= max_tokens as f64 * settings.auto_compact_threshold as f64
+    }
+
+    /// Summarizes the conversation with the summarization model and records the
+    /// result as a [`Message::Compaction`], which stands in for all earlier
+    /// messages in subsequent requests. A trailing user message that hasn't been
+    /// answered yet is kept verbatim after the summary.
+    ///
+    /// The compaction is aborted if the thread is cancelled or the compacted
+    /// messages change before the summary arrives.
+    pub fn compact(&mut self, cx: &mut Context<Self>) -> Task<Result<CompactionSummary>> {
+        if self.is_compacting() {
+            return Task::ready(Err(anyhow!("Thread is already being compacted")));
+        }
+        let Some(model) = self.summarization_model.clone() else {
+            return Task::ready(Err(anyhow!("No summarization model available")));
+        };
+
+        let mut compacted_len = self.messages.len();
+        if let Some(Message::User(_)) = self.messages.last() {
+            compacted_len -= 1;
+        }
+        let messages_to_compact = &self.messages[..compacted_len];
+        let start = messages_to_compact
+            .iter()
+            .rposition(|message| matches!(message, Message::Compaction(_)))
+            .unwrap_or(0);
+        if messages_to_compact[start..]
+            .iter()
+            .all(|message| matches!(message, Message::Compaction(_) | Message::Resume))
+        {
+            return Task::ready(Err(anyhow!("Nothing to compact")));
+        }
+
+        let mut request = LanguageModelRequest {
+            thread_id: Some(self.id.to_string()),
+            prompt_id: Some(self.prompt_id.to_string()),
+            intent: Some(CompletionIntent::ThreadContextSummarization),
+            temperature: AgentSettings::temperature_for_model(&model, cx),
+            ..Default::default()
+        };
+
+        for message in &messages_to_compact[start..] {
+            request.messages.extend(message.to_request());
+        }
+
+        request.messages.push(LanguageModelRequestMessage {
+            role: Role::User,
+            content: vec![COMPACT_THREAD_PROMPT.into()],
+            cache: false,
+            reasoning_details: None,
+        });
+
+        // The summary is inserted after the last compacted message, which is
+        // found again by its preceding user message once the summary arrives.
+        let anchor = messages_to_compact
+            .iter()
+            .enumerate()
+            .rev()
+            .find_map(|(ix, message)| match message {
+                Message::User(message) => Some((ix, message.id.clone())),
+                _ => None,
+            });
+
+        let (result_tx, result_rx) = oneshot::channel();
+        self.pending_compaction = Some(cx.spawn(async move |this, cx| {
+            let summary = async {
+                let mut summary = String::new();
+                let mut events = model.stream_completion(request, cx).await?;
+                while let Some(event) = events.next().await {
+                    match event? {
+                        LanguageModelCompletionEvent::Text(text) => summary.push_str(&text),
+                        LanguageModelCompletionEvent::UsageUpdated { amount, limit } => {
+                            this.update(cx, |thread, cx| {
+                                thread.update_model_request_usage(amount, limit, cx);
+                            })?;
+                        }
+                        _ => {}
+                    }
+                }
+                anyhow::ensure!(
+                    !summary.trim().is_empty(),
+                    "summarization model returned an empty summary"
+                );
+                anyhow::Ok(summary)
+            }
+            .await
+            .context("failed to compact thread");
+
+            _ = this.update(cx, |this, cx| {
+                this.pending_compaction = None;
+                cx.notify();
+                let result = summary.and_then(|summary| {
+                    this.insert_compaction(summary, compacted_len, anchor.as_ref(), cx)
+                });
+                result_tx.send(result).ok();
+            });
+        }));
+        cx.notify();
+
+        cx.background_spawn(async move {
+            result_rx
+                .await
+                .map_err(|_| anyhow!("Compaction was cancelled"))?
+        })
+    }
+
+    fn insert_compaction(
+        &mut self,
+        summary: String,
+        compacted_len: usize,
+        anchor: Option<&(usize, UserMessageId)>,
+        cx: &mut Context<Self>,
+    ) -> Result<CompactionSummary> {
+        let ix = match anchor {
+            Some((anchor_ix, anchor_id)) => {
+                let current_ix = self.messages.iter().position(
+                    |message| matches!(message, Message::User(message) if &message.id == anchor_id),
+                );
+                current_ix
+                    .map(|current_ix| current_ix + compacted_len - anchor_ix)
+                    .filter(|ix| *ix <= self.messages.len())
+            }
+            None => Some(compacted_len).filter(|ix| *ix <= self.messages.len()),
+        }
+        .context("Thread changed while it was being compacted")?;
+
+        let compaction = CompactionSummary {
+            summary,
+            created_at: Utc::now(),
+        };
+        self.messages
+            .insert(ix, Message::Compaction(compaction.clone()));
+
+        // The latest usage was measured against the full history, so
+        // it no longer reflects what we send to the model.
+        if let Some(id) = self.last_user_message().map(|message| message.id.clone()) {
+            self.request_token_usage.remove(&id);
+        }
+        cx.emit(TokenUsageUpdated(self.latest_token_usage()));
+        self.updated_at = Utc::now();
+        self.clear_summary();
+        Ok(compaction)
+    }
+
     pub fn set_title(&mut self, title: SharedString, cx: &mut Context<Self>) {
         self.pending_title_generation = None;
         if Some(&title) != self.title.as_ref() {
@@ -1879,6 +2330,7 @@ impl Thread {
                 Message::User(user_message) => Some(user_message),
                 Message::Agent(_) => None,
                 Message::Resume => None,
+                Message::Compaction(_) => None,
             })
     }
 
@@ -2038,6 +2490,10 @@ impl Thread {
             }
         }
 
+        if let Some(tool_subset) = self.tool_subset.as_ref() {
+            tools.retain(|tool_name, _| tool_subset.contains(tool_name));
+        }
+
         tools
     }
 
@@ -2075,7 +2531,7 @@ impl Thread {
             cache: false,
             reasoning_details: None,
         }];
-        for message in &self.messages {
+        for message in self.messages_for_request() {
             messages.extend(message.to_request());
         }
 
@@ -2472,6 +2928,12 @@ impl ThreadEventStream {
             .ok();
     }
 
+    fn send_compaction(&self, compaction: &CompactionSummary) {
+        self.0
+            .unbounded_send(Ok(ThreadEvent::Compaction(compaction.clone())))
+            .ok();
+    }
+
     fn send_retry(&self, status: acp_thread::RetryStatus) {
         self.0.unbounded_send(Ok(ThreadEvent::Retry(status))).ok();
     }
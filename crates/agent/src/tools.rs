@@ -1,6 +1,7 @@
 mod context_server_registry;
 mod copy_path_tool;
 mod create_directory_tool;
+mod delegate_tool;
 mod delete_path_tool;
 mod diagnostics_tool;
 mod edit_file_tool;
@@ -25,6 +26,7 @@ use language_model::{LanguageModelRequestTool, LanguageModelToolSchemaFormat};
 pub use context_server_registry::*;
 pub use copy_path_tool::*;
 pub use create_directory_tool::*;
+pub use delegate_tool::*;
 pub use delete_path_tool::*;
 pub use diagnostics_tool::*;
 pub use edit_file_tool::*;
@@ -83,6 +85,7 @@ macro_rules! tools {
 tools! {
     CopyPathTool,
     CreateDirectoryTool,
+    DelegateTool,
     DeletePathTool,
     DiagnosticsTool,
     EditFileTool,
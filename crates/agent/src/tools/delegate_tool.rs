@@ -0,0 +1,203 @@
+use acp_thread::UserMessageId;
+use agent_client_protocol as acp;
+use agent_settings::{AgentProfileId, AgentSettings};
+use anyhow::{Result, anyhow};
+use futures::StreamExt as _;
+use gpui::{App, SharedString, Task, WeakEntity};
+use itertools::Itertools as _;
+use language_model::LanguageModelToolResultContent;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use settings::Settings as _;
+use std::{rc::Rc, sync::Arc};
+use util::markdown::MarkdownInlineCode;
+
+use crate::{AgentTool, Thread, ThreadEnvironment, ThreadEvent, ToolCallEventStream};
+
+const SUBAGENT_INSTRUCTIONS: &str = "You are a sub-agent working on a task delegated to you by another agent. \
+    The other agent cannot see your messages or tool calls, only your final response. \
+    Work on the task until it's done, then end your turn with a concise summary of what you found or changed, \
+    including relevant file paths and any open questions.";
+
+/// Delegates a focused task to a sub-agent that runs in its own context window and reports back a summary.
+///
+/// Use this for self-contained work that would otherwise fill up your context with intermediate steps, such as investigating why a test fails, surveying how an API is used across the codebase, or making a well-specified mechanical change.
+///
+/// The sub-agent can't see this conversation, so the task must include all the context it needs. Only its final summary is returned to you.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
+pub struct DelegateToolInput {
+    /// A short title for the task, shown to the user (e.g. "Investigate failing parser test").
+    pub title: String,
+    /// Self-contained instructions for the sub-agent, including any relevant paths, symbols, and constraints.
+    pub task: String,
+    /// The ID of the agent profile the sub-agent should use. Defaults to the current profile.
+    #[serde(default)]
+    pub profile: Option<String>,
+    /// The names of the tools the sub-agent may use. Defaults to every tool enabled in its profile.
+    #[serde(default)]
+    pub tools: Option<Vec<String>>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DelegateToolOutput {
+    summary: String,
+    transcript: String,
+}
+
+impl From<DelegateToolOutput> for LanguageModelToolResultContent {
+    fn from(output: DelegateToolOutput) -> Self {
+        if output.summary.is_empty() {
+            "The sub-agent finished without a summary.".into()
+        } else {
+            output.summary.into()
+        }
+    }
+}
+
+pub struct DelegateTool {
+    thread: WeakEntity<Thread>,
+    environment: Rc<dyn ThreadEnvironment>,
+}
+
+impl DelegateTool {
+    pub fn new(thread: WeakEntity<Thread>, environment: Rc<dyn ThreadEnvironment>) -> Self {
+        Self {
+            thread,
+            environment,
+        }
+    }
+}
+
+impl AgentTool for DelegateTool {
+    type Input = DelegateToolInput;
+    type Output = DelegateToolOutput;
+
+    fn name() -> &'static str {
+        "delegate"
+    }
+
+    fn kind() -> acp::ToolKind {
+        acp::ToolKind::Other
+    }
+
+    fn initial_title(
+        &self,
+        input: Result<Self::Input, serde_json::Value>,
+        _cx: &mut App,
+    ) -> SharedString {
+        match input {
+            Ok(input) => format!("Delegate {}", MarkdownInlineCode(&input.title)).into(),
+            Err(_) => "Delegate task".into(),
+        }
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: Self::Input,
+        event_stream: ToolCallEventStream,
+        cx: &mut App,
+    ) -> Task<Result<Self::Output>> {
+        let profile_id = input.profile.map(|profile| AgentProfileId(profile.into()));
+        if let Some(profile_id) = profile_id.as_ref() {
+            let profiles = &AgentSettings::get_global(cx).profiles;
+            if !profiles.contains_key(profile_id) {
+                return Task::ready(Err(anyhow!(
+                    "Profile {} doesn't exist. Available profiles: {}",
+                    profile_id,
+                    profiles.keys().join(", ")
+                )));
+            }
+        }
+
+        cx.spawn(async move |cx| {
+            // The parent thread is busy running this tool, so the sub-agent
+            // can only be created once we're off its update stack.
+            let subagent = self
+                .thread
+                .update(cx, |thread, cx| thread.new_subagent(profile_id, cx))?;
+            let mut events = subagent.update(cx, |subagent, cx| {
+                subagent.add_default_tools(self.environment.clone(), cx);
+                // Sub-agents can't delegate further, so a task can't fan out indefinitely.
+                subagent.remove_tool(Self::name());
+                subagent.set_tool_subset(
+                    input
+                        .tools
+                        .map(|tools| tools.into_iter().map(SharedString::from).collect()),
+                    cx,
+                )?;
+                subagent.send(
+                    UserMessageId::new(),
+                    [format!("{}\n\n{}", input.task, SUBAGENT_INSTRUCTIONS).as_str()],
+                    cx,
+                )
+            })??;
+
+            let mut summary = String::new();
+            while let Some(event) = events.next().await {
+                match event? {
+                    ThreadEvent::AgentText(text) => summary.push_str(&text),
+                    ThreadEvent::ToolCall(_) => {
+                        // Only the text after the last tool call is the final response.
+                        summary.clear();
+                        let transcript =
+                            subagent.read_with(cx, |subagent, _| subagent.to_markdown())?;
+                        event_stream.update_fields(
+                            acp::ToolCallUpdateFields::new().content(vec![transcript.into()]),
+                        );
+                    }
+                    ThreadEvent::ToolCallAuthorization(authorization) => {
+                        let title = authorization
+                            .tool_call
+                            .fields
+                            .title
+                            .clone()
+                            .unwrap_or_else(|| "run a tool".into());
+                        let authorize = cx.update(|cx| {
+                            event_stream.authorize(format!("Sub-agent wants to {title}"), cx)
+                        })?;
+                        let option_id = if authorize.await.is_ok() {
+                            "allow"
+                        } else {
+                            "deny"
+                        };
+                        authorization
+                            .response
+                            .send(acp::PermissionOptionId::new(option_id))
+                            .ok();
+                    }
+                    ThreadEvent::Stop(acp::StopReason::EndTurn) => break,
+                    ThreadEvent::Stop(reason) => {
+                        return Err(anyhow!("Sub-agent stopped early: {reason:?}"));
+                    }
+                    ThreadEvent::UserMessage(_)
+                    | ThreadEvent::AgentThinking(_)
+                    | ThreadEvent::ToolCallUpdate(_)
+                    | ThreadEvent::Retry(_)
+                    | ThreadEvent::Compaction(_) => {}
+                }
+            }
+
+            let transcript = subagent.read_with(cx, |subagent, _| subagent.to_markdown())?;
+            event_stream.update_fields(
+                acp::ToolCallUpdateFields::new().content(vec![transcript.clone().into()]),
+            );
+            Ok(DelegateToolOutput {
+                summary: summary.trim().to_string(),
+                transcript,
+            })
+        })
+    }
+
+    fn replay(
+        &self,
+        _input: Self::Input,
+        output: Self::Output,
+        event_stream: ToolCallEventStream,
+        _cx: &mut App,
+    ) -> Result<()> {
+        event_stream.update_fields(
+            acp::ToolCallUpdateFields::new().content(vec![output.transcript.into()]),
+        );
+        Ok(())
+    }
+}
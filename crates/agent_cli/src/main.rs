@@ -0,0 +1,134 @@
+mod headless;
+mod run;
+
+use anyhow::Context as _;
+use clap::{Parser, ValueEnum};
+use gpui::Application;
+use reqwest_client::ReqwestClient;
+use std::io::Read as _;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// Runs the Zed agent against a project without opening a window.
+#[derive(Parser, Debug)]
+#[command(name = "zed-agent", disable_version_flag = true)]
+struct Args {
+    /// Path to the project directory the agent should work in.
+    #[arg(value_name = "PROJECT_PATH")]
+    project: PathBuf,
+    /// Prompt to send to the agent. Read from stdin if omitted or `-`.
+    #[arg(long, short)]
+    prompt: Option<String>,
+    /// ID of the agent profile to use. Defaults to `agent.default_profile`.
+    #[arg(long)]
+    profile: Option<String>,
+    /// provider/model to use. Defaults to `agent.default_model`.
+    #[arg(long)]
+    model: Option<String>,
+    /// Record the agent's edits in the diff, then revert them.
+    #[arg(long)]
+    dry_run: bool,
+    /// Allow tool calls that would otherwise ask for confirmation.
+    /// Without this flag, such tool calls are denied.
+    #[arg(long)]
+    allow_tool_actions: bool,
+    #[arg(long, value_enum, default_value_t = OutputFormat::Markdown)]
+    format: OutputFormat,
+    /// Write the result to this file instead of stdout.
+    #[arg(long, short)]
+    output: Option<PathBuf>,
+    /// Output current environment variables as JSON to stdout
+    #[arg(long, hide = true)]
+    printenv: bool,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum OutputFormat {
+    Json,
+    Markdown,
+}
+
+fn main() {
+    let args = Args::parse();
+
+    // This prevents errors showing up in the logs, because
+    // project::environment::load_shell_environment() calls
+    // std::env::current_exe().unwrap() --printenv
+    if args.printenv {
+        util::shell_env::print_env();
+        return;
+    }
+
+    env_logger::init();
+
+    let prompt = match args.prompt.as_deref() {
+        Some(prompt) if prompt != "-" => prompt.to_string(),
+        _ => {
+            let mut prompt = String::new();
+            if let Err(error) = std::io::stdin().read_to_string(&mut prompt) {
+                eprintln!("Failed to read prompt from stdin: {error}");
+                std::process::exit(1);
+            }
+            prompt
+        }
+    };
+    if prompt.trim().is_empty() {
+        eprintln!("Prompt is empty");
+        std::process::exit(1);
+    }
+
+    let http_client = Arc::new(ReqwestClient::new());
+    let app = Application::headless().with_http_client(http_client);
+
+    app.run(move |cx| {
+        let app_state = match headless::init(cx) {
+            Ok(app_state) => Arc::new(app_state),
+            Err(error) => {
+                eprintln!("Error: {error:?}");
+                std::process::exit(1);
+            }
+        };
+
+        cx.spawn(async move |cx| {
+            let result = async {
+                let project_path = args
+                    .project
+                    .canonicalize()
+                    .with_context(|| format!("invalid project path {:?}", args.project))?;
+                let output = run::run_prompt(
+                    run::RunParams {
+                        project_path,
+                        prompt,
+                        profile: args.profile,
+                        model: args.model,
+                        dry_run: args.dry_run,
+                        allow_tool_actions: args.allow_tool_actions,
+                    },
+                    app_state,
+                    cx,
+                )
+                .await?;
+
+                let output = match args.format {
+                    OutputFormat::Json => serde_json::to_string_pretty(&output)?,
+                    OutputFormat::Markdown => output.to_markdown(),
+                };
+                match &args.output {
+                    Some(path) => std::fs::write(path, output)
+                        .with_context(|| format!("failed to write {path:?}"))?,
+                    None => println!("{output}"),
+                }
+                anyhow::Ok(())
+            }
+            .await;
+
+            if let Err(error) = result {
+                eprintln!("Error: {error:?}");
+                std::process::exit(1);
+            }
+
+            cx.update(|cx| cx.quit()).ok();
+        })
+        .detach();
+    });
+}
@@ -0,0 +1,433 @@
+use crate::headless::AgentCliAppState;
+use acp_thread::UserMessageId;
+use agent::{ContextServerRegistry, NativeAgent, Templates, Thread, ThreadEvent};
+use agent_client_protocol as acp;
+use agent_settings::{AgentProfileId, AgentSettings};
+use anyhow::{Context as _, Result, anyhow};
+use futures::StreamExt as _;
+use futures::future::{self, Shared};
+use gpui::{App, AppContext as _, AsyncApp, Entity, Task};
+use language_model::{LanguageModel, LanguageModelRegistry, SelectedModel};
+use project::Project;
+use prompt_store::PromptStore;
+use serde::Serialize;
+use settings::Settings as _;
+use std::fmt::Write as _;
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::str::FromStr as _;
+use std::sync::Arc;
+use util::ResultExt as _;
+use util::markdown::MarkdownCodeBlock;
+
+pub struct RunParams {
+    pub project_path: PathBuf,
+    pub prompt: String,
+    pub profile: Option<String>,
+    pub model: Option<String>,
+    pub dry_run: bool,
+    pub allow_tool_actions: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RunOutput {
+    pub project: PathBuf,
+    pub model: String,
+    pub profile: String,
+    pub dry_run: bool,
+    pub stop_reason: acp::StopReason,
+    /// The conversation, rendered as Markdown.
+    pub transcript: String,
+    /// Unified diff of every file the agent changed.
+    pub diff: String,
+}
+
+impl RunOutput {
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = String::new();
+        writeln!(markdown, "# Agent Run\n").ok();
+        writeln!(markdown, "- Project: `{}`", self.project.display()).ok();
+        writeln!(markdown, "- Model: `{}`", self.model).ok();
+        writeln!(markdown, "- Profile: `{}`", self.profile).ok();
+        writeln!(markdown, "- Stop reason: `{:?}`", self.stop_reason).ok();
+        if self.dry_run {
+            writeln!(markdown, "- Dry run: edits were reverted").ok();
+        }
+        writeln!(
+            markdown,
+            "\n## Transcript\n\n{}",
+            self.transcript.trim_end()
+        )
+        .ok();
+        writeln!(markdown, "\n## Diff\n").ok();
+        if self.diff.is_empty() {
+            writeln!(markdown, "No changes.").ok();
+        } else {
+            writeln!(
+                markdown,
+                "{}",
+                MarkdownCodeBlock {
+                    tag: "diff",
+                    text: &self.diff,
+                }
+            )
+            .ok();
+        }
+        markdown
+    }
+}
+
+pub async fn run_prompt(
+    params: RunParams,
+    app_state: Arc<AgentCliAppState>,
+    cx: &mut AsyncApp,
+) -> Result<RunOutput> {
+    let authenticate = cx.update(|cx| {
+        LanguageModelRegistry::global(cx).update(cx, |registry, cx| {
+            registry
+                .providers()
+                .iter()
+                .map(|provider| provider.authenticate(cx))
+                .collect::<Vec<_>>()
+        })
+    })?;
+    future::join_all(authenticate).await;
+
+    let (model, profile_id) = cx.update(|cx| {
+        let settings = AgentSettings::get_global(cx);
+        let model_name = match params.model {
+            Some(model_name) => model_name,
+            None => settings
+                .default_model
+                .as_ref()
+                .map(|selection| format!("{}/{}", selection.provider.0, selection.model))
+                .context("No model specified and no `agent.default_model` configured")?,
+        };
+        let model = find_model(&model_name, cx)?;
+
+        let profile_id = params
+            .profile
+            .map(|profile| AgentProfileId(profile.into()))
+            .unwrap_or_else(|| settings.default_profile.clone());
+        anyhow::ensure!(
+            settings.profiles.contains_key(&profile_id),
+            "Profile {} doesn't exist. Available profiles: {}",
+            profile_id,
+            settings
+                .profiles
+                .keys()
+                .map(|id| id.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+        anyhow::Ok((model, profile_id))
+    })??;
+    let prompt_store = cx.update(|cx| PromptStore::global(cx))?.await.log_err();
+
+    run_with_model(params, model, profile_id, prompt_store, app_state, cx).await
+}
+
+async fn run_with_model(
+    params: RunParams,
+    model: Arc<dyn LanguageModel>,
+    profile_id: AgentProfileId,
+    prompt_store: Option<Entity<PromptStore>>,
+    app_state: Arc<AgentCliAppState>,
+    cx: &mut AsyncApp,
+) -> Result<RunOutput> {
+    let project = cx.update(|cx| {
+        Project::local(
+            app_state.client.clone(),
+            app_state.node_runtime.clone(),
+            app_state.user_store.clone(),
+            app_state.languages.clone(),
+            app_state.fs.clone(),
+            None,
+            false,
+            cx,
+        )
+    })?;
+    let worktree = project
+        .update(cx, |project, cx| {
+            project.create_worktree(&params.project_path, true, cx)
+        })?
+        .await?;
+    worktree
+        .update(cx, |worktree, _cx| {
+            worktree.as_local().unwrap().scan_complete()
+        })?
+        .await;
+
+    // Pick up the same rules files and default user rules as threads in the editor.
+    let project_context = cx
+        .update(|cx| NativeAgent::build_project_context(&project, prompt_store.as_ref(), cx))?
+        .await;
+
+    let thread = cx.update(|cx| {
+        let project_context = cx.new(|_cx| project_context);
+        let context_server_registry =
+            cx.new(|cx| ContextServerRegistry::new(project.read(cx).context_server_store(), cx));
+        cx.new(|cx| {
+            let mut thread = Thread::new(
+                project.clone(),
+                project_context,
+                context_server_registry,
+                Templates::new(),
+                Some(model.clone()),
+                cx,
+            );
+            thread.add_default_tools(
+                Rc::new(HeadlessThreadEnvironment {
+                    project: project.clone(),
+                }),
+                cx,
+            );
+            thread.set_profile(profile_id.clone(), cx);
+            thread
+        })
+    })?;
+
+    let mut events = thread.update(cx, |thread, cx| {
+        thread.send(UserMessageId::new(), [params.prompt.as_str()], cx)
+    })??;
+
+    let mut stop_reason = None;
+    while let Some(event) = events.next().await {
+        match event? {
+            ThreadEvent::ToolCall(tool_call) => {
+                log::info!("Tool call: {}", tool_call.title);
+            }
+            ThreadEvent::ToolCallAuthorization(authorization) => {
+                let title = authorization.tool_call.fields.title.unwrap_or_default();
+                let option_id = if params.allow_tool_actions {
+                    log::info!("Allowing tool call: {title}");
+                    "allow"
+                } else {
+                    eprintln!(
+                        "Denied tool call that requires confirmation: {title} \
+                        (pass --allow-tool-actions to allow it)"
+                    );
+                    "deny"
+                };
+                authorization
+                    .response
+                    .send(acp::PermissionOptionId::new(option_id))
+                    .ok();
+            }
+            ThreadEvent::Retry(status) => {
+                log::warn!("Retrying completion: {status:?}");
+            }
+            ThreadEvent::Stop(reason) => {
+                stop_reason = Some(reason);
+                break;
+            }
+            ThreadEvent::UserMessage(_)
+            | ThreadEvent::AgentText(_)
+            | ThreadEvent::AgentThinking(_)
+            | ThreadEvent::ToolCallUpdate(_)
+            | ThreadEvent::Compaction(_) => {}
+        }
+    }
+    let stop_reason = stop_reason.context("Agent stopped without a stop reason")?;
+
+    let action_log = thread.read_with(cx, |thread, _| thread.action_log().clone())?;
+    let diff = action_log.read_with(cx, |action_log, cx| action_log.unified_diff(cx))?;
+    if params.dry_run {
+        action_log
+            .update(cx, |action_log, cx| action_log.reject_all_edits(None, cx))?
+            .await;
+    } else {
+        action_log.update(cx, |action_log, cx| action_log.keep_all_edits(None, cx))?;
+    }
+
+    let transcript = thread.read_with(cx, |thread, _| thread.to_markdown())?;
+    Ok(RunOutput {
+        project: params.project_path,
+        model: format!("{}/{}", model.provider_id().0, model.id().0),
+        profile: profile_id.to_string(),
+        dry_run: params.dry_run,
+        stop_reason,
+        transcript,
+        diff,
+    })
+}
+
+fn find_model(model_name: &str, cx: &App) -> Result<Arc<dyn LanguageModel>> {
+    let selected = SelectedModel::from_str(model_name).map_err(|error| anyhow!(error))?;
+    let registry = LanguageModelRegistry::read_global(cx);
+    registry
+        .available_models(cx)
+        .find(|model| model.id() == selected.model && model.provider_id() == selected.provider)
+        .with_context(|| {
+            format!(
+                "No language model with ID {}/{} was available. Available models: {}",
+                selected.provider.0,
+                selected.model.0,
+                registry
+                    .available_models(cx)
+                    .map(|model| format!("{}/{}", model.provider_id().0, model.id().0))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )
+        })
+}
+
+struct HeadlessThreadEnvironment {
+    project: Entity<Project>,
+}
+
+struct HeadlessTerminalHandle {
+    terminal: Entity<acp_thread::Terminal>,
+}
+
+impl agent::TerminalHandle for HeadlessTerminalHandle {
+    fn id(&self, cx: &AsyncApp) -> Result<acp::TerminalId> {
+        self.terminal.read_with(cx, |term, _cx| term.id().clone())
+    }
+
+    fn wait_for_exit(&self, cx: &AsyncApp) -> Result<Shared<Task<acp::TerminalExitStatus>>> {
+        self.terminal
+            .read_with(cx, |term, _cx| term.wait_for_exit())
+    }
+
+    fn current_output(&self, cx: &AsyncApp) -> Result<acp::TerminalOutputResponse> {
+        self.terminal
+            .read_with(cx, |term, cx| term.current_output(cx))
+    }
+
+    fn kill(&self, cx: &AsyncApp) -> Result<()> {
+        cx.update(|cx| {
+            self.terminal.update(cx, |terminal, cx| {
+                terminal.kill(cx);
+            });
+        })?;
+        Ok(())
+    }
+}
+
+impl agent::ThreadEnvironment for HeadlessThreadEnvironment {
+    fn create_terminal(
+        &self,
+        command: String,
+        cwd: Option<PathBuf>,
+        output_byte_limit: Option<u64>,
+        cx: &mut AsyncApp,
+    ) -> Task<Result<Rc<dyn agent::TerminalHandle>>> {
+        let project = self.project.clone();
+        cx.spawn(async move |cx| {
+            let language_registry =
+                project.read_with(cx, |project, _cx| project.languages().clone())?;
+            let id = acp::TerminalId::new(uuid::Uuid::new_v4().to_string());
+            let terminal =
+                acp_thread::create_terminal_entity(command, &[], vec![], cwd.clone(), &project, cx)
+                    .await?;
+            let terminal = cx.new(|cx| {
+                acp_thread::Terminal::new(
+                    id,
+                    "",
+                    cwd,
+                    output_byte_limit.map(|limit| limit as usize),
+                    terminal,
+                    language_registry,
+                    cx,
+                )
+            })?;
+            Ok(Rc::new(HeadlessTerminalHandle { terminal }) as Rc<dyn agent::TerminalHandle>)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use client::{Client, UserStore};
+    use clock::FakeSystemClock;
+    use fs::FakeFs;
+    use gpui::{TestAppContext, http_client::FakeHttpClient};
+    use language::LanguageRegistry;
+    use language_model::fake_provider::FakeLanguageModel;
+    use node_runtime::NodeRuntime;
+    use serde_json::json;
+    use util::path;
+
+    #[gpui::test]
+    async fn test_run_with_fake_model(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+        cx.update(|cx| {
+            settings::init(cx);
+            LanguageModelRegistry::test(cx);
+        });
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/project"),
+            json!({
+                "AGENTS.md": "Always answer in haiku.",
+                "src": {
+                    "main.rs": "fn main() {}\n",
+                },
+            }),
+        )
+        .await;
+
+        let clock = Arc::new(FakeSystemClock::new());
+        let client = cx.update(|cx| Client::new(clock, FakeHttpClient::with_404_response(), cx));
+        let user_store = cx.new(|cx| UserStore::new(client.clone(), cx));
+        let app_state = Arc::new(AgentCliAppState {
+            languages: Arc::new(LanguageRegistry::test(cx.executor())),
+            client,
+            user_store,
+            fs,
+            node_runtime: NodeRuntime::unavailable(),
+        });
+
+        let model = Arc::new(FakeLanguageModel::default());
+        let run = cx.spawn({
+            let model = model.clone();
+            |mut cx| async move {
+                run_with_model(
+                    RunParams {
+                        project_path: PathBuf::from(path!("/project")),
+                        prompt: "What does main do?".into(),
+                        profile: None,
+                        model: None,
+                        dry_run: false,
+                        allow_tool_actions: false,
+                    },
+                    model,
+                    AgentProfileId("write".into()),
+                    None,
+                    app_state,
+                    &mut cx,
+                )
+                .await
+            }
+        });
+        cx.run_until_parked();
+
+        // The project's rules file ends up in the system prompt, as it does in the editor.
+        let request = model.pending_completions().pop().unwrap();
+        assert!(
+            request.messages[0]
+                .string_contents()
+                .contains("Always answer in haiku.")
+        );
+        assert!(
+            request
+                .messages
+                .last()
+                .unwrap()
+                .string_contents()
+                .contains("What does main do?")
+        );
+        model.send_last_completion_stream_text_chunk("It does nothing at all.");
+        model.end_last_completion_stream();
+
+        let output = run.await.unwrap();
+        assert_eq!(output.model, "fake/fake");
+        assert_eq!(output.profile, "write");
+        assert_eq!(output.stop_reason, acp::StopReason::EndTurn);
+        assert!(output.transcript.contains("It does nothing at all."));
+        assert!(output.diff.is_empty());
+    }
+}
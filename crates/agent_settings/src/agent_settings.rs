@@ -10,8 +10,8 @@ use project::DisableAiSettings;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use settings::{
-    DefaultAgentView, DockPosition, DockSide, LanguageModelParameters, LanguageModelSelection,
-    NotifyWhenAgentWaiting, RegisterSetting, Settings,
+    AgentHookContent, DefaultAgentView, DockPosition, DockSide, LanguageModelParameters,
+    LanguageModelSelection, NotifyWhenAgentWaiting, RegisterSetting, Settings,
 };
 
 pub use crate::agent_profile::*;
@@ -19,6 +19,7 @@ pub use crate::agent_profile::*;
 pub const SUMMARIZE_THREAD_PROMPT: &str = include_str!("prompts/summarize_thread_prompt.txt");
 pub const SUMMARIZE_THREAD_DETAILED_PROMPT: &str =
     include_str!("prompts/summarize_thread_detailed_prompt.txt");
+pub const COMPACT_THREAD_PROMPT: &str = include_str!("prompts/compact_thread_prompt.txt");
 
 #[derive(Clone, Debug, RegisterSetting)]
 pub struct AgentSettings {
@@ -49,6 +50,8 @@ pub struct AgentSettings {
     pub expand_terminal_card: bool,
     pub use_modifier_to_send: bool,
     pub message_editor_min_lines: usize,
+    pub auto_compact: bool,
+    pub auto_compact_threshold: f32,
 }
 
 impl AgentSettings {
@@ -193,6 +196,29 @@ impl Settings for AgentSettings {
             expand_terminal_card: agent.expand_terminal_card.unwrap(),
             use_modifier_to_send: agent.use_modifier_to_send.unwrap(),
             message_editor_min_lines: agent.message_editor_min_lines.unwrap(),
+            auto_compact: agent.auto_compact.unwrap(),
+            auto_compact_threshold: agent.auto_compact_threshold.unwrap().clamp(0.1, 1.0),
+        }
+    }
+}
+
+/// Shell commands that run at points in the agent's lifecycle.
+///
+/// Unlike [`AgentSettings`], these can be configured per project.
+#[derive(Clone, Debug, Default, RegisterSetting)]
+pub struct AgentHooksSettings {
+    pub pre_tool_use: Vec<AgentHookContent>,
+    pub post_tool_use: Vec<AgentHookContent>,
+    pub stop: Vec<AgentHookContent>,
+}
+
+impl Settings for AgentHooksSettings {
+    fn from_settings(content: &settings::SettingsContent) -> Self {
+        let hooks = content.project.agent_hooks.clone().unwrap();
+        Self {
+            pre_tool_use: hooks.pre_tool_use.unwrap(),
+            post_tool_use: hooks.post_tool_use.unwrap(),
+            stop: hooks.stop.unwrap(),
         }
     }
 }
@@ -1241,6 +1241,10 @@ fn extension_only_provides_context_server(manifest: &ExtensionManifest) -> bool
         && manifest.slash_commands.is_empty()
         && manifest.snippets.is_none()
         && manifest.debug_locators.is_empty()
+        && manifest.task_providers.is_empty()
+        && manifest.formatters.is_empty()
+        && manifest.code_action_providers.is_empty()
+        && manifest.language_model_providers.is_empty()
 }
 
 pub(crate) fn resolve_extension_for_context_server(
@@ -18,9 +18,10 @@ use zed_actions::agent::{OpenClaudeCodeOnboardingModal, ReauthenticateAgent};
 use crate::ManageProfiles;
 use crate::ui::{AcpOnboardingModal, ClaudeCodeOnboardingModal};
 use crate::{
-    AddContextServer, AgentDiffPane, Follow, InlineAssistant, NewTextThread, NewThread,
-    OpenActiveThreadAsMarkdown, OpenAgentDiff, OpenHistory, ResetTrialEndUpsell, ResetTrialUpsell,
-    ToggleNavigationMenu, ToggleNewThreadMenu, ToggleOptionsMenu,
+    AddContextServer, AgentDiffPane, ExportThread, Follow, ImportThread, InlineAssistant,
+    NewTextThread, NewThread, OpenActiveThreadAsMarkdown, OpenAgentDiff, OpenHistory,
+    ResetTrialEndUpsell, ResetTrialUpsell, ToggleNavigationMenu, ToggleNewThreadMenu,
+    ToggleOptionsMenu,
     acp::AcpThreadView,
     agent_configuration::{AgentConfiguration, AssistantConfigurationEvent},
     slash_command::SlashCommandCompletionProvider,
@@ -45,8 +46,8 @@ use extension_host::ExtensionStore;
 use fs::Fs;
 use gpui::{
     Action, Animation, AnimationExt, AnyElement, App, AsyncWindowContext, Corner, DismissEvent,
-    Entity, EventEmitter, ExternalPaths, FocusHandle, Focusable, KeyContext, Pixels, Subscription,
-    Task, UpdateGlobal, WeakEntity, prelude::*, pulsating_between,
+    Entity, EventEmitter, ExternalPaths, FocusHandle, Focusable, KeyContext, PathPromptOptions,
+    Pixels, Subscription, Task, UpdateGlobal, WeakEntity, prelude::*, pulsating_between,
 };
 use language::LanguageRegistry;
 use language_model::{ConfigurationError, LanguageModelRegistry};
@@ -64,6 +65,7 @@ use util::ResultExt as _;
 use workspace::{
     CollaboratorId, DraggedSelection, DraggedTab, ToggleZoom, ToolbarItemView, Workspace,
     dock::{DockPosition, Panel, PanelEvent},
+    notifications::DetachAndPromptErr as _,
 };
 use zed_actions::{
     DecreaseBufferFontSize, IncreaseBufferFontSize, ResetBufferFontSize,
@@ -1195,6 +1197,108 @@ impl AgentPanel {
         }
     }
 
+    fn export_thread(&mut self, _: &ExportThread, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(thread) = self.active_native_agent_thread(cx) else {
+            return;
+        };
+        let thread = thread.read(cx);
+        let file_name = thread
+            .title()
+            .chars()
+            .map(|c| {
+                if c.is_alphanumeric() || c == '-' || c == '_' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect::<String>();
+        let export = thread.to_export(cx);
+        let directory = self
+            .project
+            .read(cx)
+            .visible_worktrees(cx)
+            .next()
+            .map(|worktree| worktree.read(cx).abs_path().to_path_buf())
+            .unwrap_or_else(|| paths::home_dir().clone());
+        let path = cx.prompt_for_new_path(&directory, Some(&format!("{file_name}.json")));
+        let fs = self.fs.clone();
+        cx.spawn_in(window, async move |_, _| {
+            let Some(path) = path.await?? else {
+                return Ok(());
+            };
+            let json = export.await.to_json()?;
+            fs.atomic_write(path, json).await
+        })
+        .detach_and_prompt_err("Failed to export thread", window, cx, |_, _, _| None);
+    }
+
+    fn import_thread(&mut self, _: &ImportThread, window: &mut Window, cx: &mut Context<Self>) {
+        let paths = cx.prompt_for_paths(PathPromptOptions {
+            files: true,
+            directories: false,
+            multiple: false,
+            prompt: Some("Import".into()),
+        });
+        let fs = self.fs.clone();
+        let history_store = self.history_store.clone();
+        cx.spawn_in(window, async move |this, cx| {
+            let Some(path) = paths.await??.and_then(|paths| paths.into_iter().next()) else {
+                return Ok(());
+            };
+            let json = fs.load_bytes(&path).await?;
+            let imported = history_store
+                .update(cx, |history_store, cx| {
+                    history_store.import_thread(json, cx)
+                })?
+                .await?;
+            let title = imported.metadata.title.clone();
+            this.update_in(cx, |this, window, cx| {
+                this.load_agent_thread(imported.metadata, window, cx)
+            })?;
+            if imported.diff.is_empty() {
+                return Ok(());
+            }
+
+            // The edits were made in the exporter's project, so show them for
+            // review next to the thread instead of applying them here.
+            let (workspace, project, diff_language) = this.read_with(cx, |this, _| {
+                (
+                    this.workspace.clone(),
+                    this.project.clone(),
+                    this.language_registry.language_for_name("Diff"),
+                )
+            })?;
+            let diff_language = diff_language.await.log_err();
+            let buffer = project
+                .update(cx, |project, cx| project.create_buffer(false, cx))?
+                .await?;
+            buffer.update(cx, |buffer, cx| {
+                buffer.set_text(imported.diff, cx);
+                buffer.set_language(diff_language, cx);
+                buffer.set_capability(language::Capability::ReadOnly, cx);
+            })?;
+            workspace.update_in(cx, |workspace, window, cx| {
+                let title = format!("{title} (Exported Edits)");
+                let buffer =
+                    cx.new(|cx| MultiBuffer::singleton(buffer, cx).with_title(title.clone()));
+                workspace.add_item_to_active_pane(
+                    Box::new(cx.new(|cx| {
+                        let mut editor =
+                            Editor::for_multibuffer(buffer, Some(project.clone()), window, cx);
+                        editor.set_breadcrumb_header(title);
+                        editor
+                    })),
+                    None,
+                    true,
+                    window,
+                    cx,
+                );
+            })
+        })
+        .detach_and_prompt_err("Failed to import thread", window, cx, |_, _, _| None);
+    }
+
     fn handle_agent_configuration_event(
         &mut self,
         _entity: &Entity<AgentConfiguration>,
@@ -1893,6 +1997,7 @@ impl AgentPanel {
                                             );
                                         }
                                     })
+                                    .action("Export Thread…", Box::new(ExportThread))
                                     .separator();
                             }
                         }
@@ -1910,6 +2015,7 @@ impl AgentPanel {
                             )
                             .action("Add Custom Server…", Box::new(AddContextServer))
                             .separator()
+                            .action("Import Thread…", Box::new(ImportThread))
                             .action("Rules", Box::new(OpenRulesLibrary::default()))
                             .action("Profiles", Box::new(ManageProfiles::default()))
                             .action("Settings", Box::new(OpenSettings))
@@ -2762,6 +2868,8 @@ impl Render for AgentPanel {
                 this.open_configuration(window, cx);
             }))
             .on_action(cx.listener(Self::open_active_thread_as_markdown))
+            .on_action(cx.listener(Self::export_thread))
+            .on_action(cx.listener(Self::import_thread))
             .on_action(cx.listener(Self::deploy_rules_library))
             .on_action(cx.listener(Self::go_back))
             .on_action(cx.listener(Self::toggle_navigation_menu))
@@ -1912,7 +1912,7 @@ impl CodeActionProvider for AssistantCodeActionProvider {
             }
 
             Task::ready(Ok(vec![CodeAction {
-                server_id: language::LanguageServerId(0),
+                server_id: None,
                 range: snapshot.anchor_before(range.start)..snapshot.anchor_after(range.end),
                 lsp_action: LspAction::Action(Box::new(lsp::CodeAction {
                     title: "Fix with Assistant".into(),
@@ -0,0 +1,138 @@
+use editor::{Editor, actions::SelectAll};
+use gpui::{
+    App, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, Render, SharedString, Styled,
+    Subscription, div, prelude::*,
+};
+use language::Buffer;
+use project::bookmark_store::BookmarkStore;
+use text::Point;
+use ui::prelude::*;
+use workspace::{ModalView, Workspace};
+
+/// Prompts for the label of the bookmark at the cursor of an editor.
+pub struct BookmarkLabel {
+    label_editor: Entity<Editor>,
+    bookmark_store: Entity<BookmarkStore>,
+    buffer: Entity<Buffer>,
+    position: text::Anchor,
+    row: u32,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl ModalView for BookmarkLabel {}
+
+impl Focusable for BookmarkLabel {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.label_editor.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for BookmarkLabel {}
+
+impl BookmarkLabel {
+    pub(crate) fn toggle(
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        let Some(editor) = workspace.active_item_as::<Editor>(cx) else {
+            return;
+        };
+        let head = editor.read(cx).selections.newest_anchor().head();
+        let Some((buffer, position)) = editor
+            .read(cx)
+            .buffer()
+            .read(cx)
+            .text_anchor_for_position(head, cx)
+        else {
+            return;
+        };
+        let bookmark_store = workspace.project().read(cx).bookmark_store();
+        workspace.toggle_modal(window, cx, move |window, cx| {
+            Self::new(bookmark_store, buffer, position, window, cx)
+        });
+    }
+
+    fn new(
+        bookmark_store: Entity<BookmarkStore>,
+        buffer: Entity<Buffer>,
+        position: text::Anchor,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let row = buffer.read(cx).summary_for_anchor::<Point>(&position).row;
+        let label = bookmark_store
+            .read(cx)
+            .bookmark_at_row(&buffer, row, cx)
+            .and_then(|bookmark| bookmark.label.clone());
+        let label_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Bookmark label", window, cx);
+            if let Some(label) = label {
+                editor.set_text(label.to_string(), window, cx);
+                editor.select_all(&SelectAll, window, cx);
+            }
+            editor
+        });
+        let label_editor_change =
+            cx.subscribe_in(&label_editor, window, Self::on_label_editor_event);
+
+        Self {
+            label_editor,
+            bookmark_store,
+            buffer,
+            position,
+            row,
+            _subscriptions: vec![label_editor_change],
+        }
+    }
+
+    fn on_label_editor_event(
+        &mut self,
+        _: &Entity<Editor>,
+        event: &editor::EditorEvent,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let editor::EditorEvent::Blurred = event {
+            cx.emit(DismissEvent)
+        }
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, _: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, _: &mut Window, cx: &mut Context<Self>) {
+        let label = SharedString::from(self.label_editor.read(cx).text(cx));
+        self.bookmark_store.update(cx, |bookmark_store, cx| {
+            bookmark_store.set_bookmark_label(&self.buffer, self.position, Some(label), cx)
+        });
+        cx.emit(DismissEvent);
+    }
+}
+
+impl Render for BookmarkLabel {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .w(rems(24.))
+            .elevation_2(cx)
+            .key_context("BookmarkLabel")
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::confirm))
+            .child(
+                div()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .px_2()
+                    .py_1()
+                    .child(self.label_editor.clone()),
+            )
+            .child(
+                h_flex().px_2().py_1().gap_1().child(
+                    Label::new(format!("Label the bookmark on line {}", self.row + 1))
+                        .color(Color::Muted),
+                ),
+            )
+    }
+}
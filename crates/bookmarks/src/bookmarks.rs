@@ -0,0 +1,219 @@
+//! Lists the bookmarks of a project in a picker and a panel, and goes from
+//! one bookmark to the next across the files of the project.
+
+mod bookmark_label;
+mod bookmarks_panel;
+mod bookmarks_picker;
+
+use editor::{Editor, SelectionEffects, scroll::Autoscroll};
+use gpui::{App, Context, Window, actions};
+use project::{ProjectItem as _, ProjectPath, bookmark_store::BookmarkEntry};
+use text::Point;
+use workspace::Workspace;
+
+pub use bookmarks_panel::{BookmarksPanel, BookmarksPanelSettings};
+
+actions!(
+    bookmarks,
+    [
+        /// Opens a picker of the bookmarks in the project.
+        Toggle,
+        /// Goes to the next bookmark in the project.
+        GoToNext,
+        /// Goes to the previous bookmark in the project.
+        GoToPrevious,
+        /// Sets the label of the bookmark at the cursor, adding a bookmark if there is none.
+        EditLabel,
+        /// Removes all bookmarks in the project.
+        ClearAll
+    ]
+);
+
+pub fn init(cx: &mut App) {
+    bookmarks_panel::init(cx);
+    cx.observe_new(|workspace: &mut Workspace, _, _| {
+        workspace.register_action(|workspace, _: &Toggle, window, cx| {
+            bookmarks_picker::BookmarksPicker::toggle(workspace, window, cx);
+        });
+        workspace.register_action(|workspace, _: &GoToNext, window, cx| {
+            go_to_adjacent_bookmark(workspace, Direction::Next, window, cx);
+        });
+        workspace.register_action(|workspace, _: &GoToPrevious, window, cx| {
+            go_to_adjacent_bookmark(workspace, Direction::Previous, window, cx);
+        });
+        workspace.register_action(|workspace, _: &EditLabel, window, cx| {
+            bookmark_label::BookmarkLabel::toggle(workspace, window, cx);
+        });
+        workspace.register_action(|workspace, _: &ClearAll, _, cx| {
+            workspace
+                .project()
+                .read(cx)
+                .bookmark_store()
+                .update(cx, |bookmark_store, cx| bookmark_store.clear_bookmarks(cx));
+        });
+    })
+    .detach();
+}
+
+/// Opens the file of a bookmark and moves the cursor to it.
+pub(crate) fn open_bookmark(
+    workspace: &mut Workspace,
+    bookmark: &BookmarkEntry,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let point = Point::new(bookmark.row, 0);
+    let open = workspace.open_path(bookmark.path.clone(), None, true, window, cx);
+    cx.spawn_in(window, async move |_, cx| {
+        let item = open.await?;
+        if let Some(editor) = item.downcast::<Editor>() {
+            editor.update_in(cx, |editor, window, cx| {
+                editor.change_selections(
+                    SelectionEffects::scroll(Autoscroll::center()),
+                    window,
+                    cx,
+                    |s| s.select_ranges([point..point]),
+                );
+            })?;
+        }
+        anyhow::Ok(())
+    })
+    .detach_and_log_err(cx);
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Direction {
+    Next,
+    Previous,
+}
+
+fn go_to_adjacent_bookmark(
+    workspace: &mut Workspace,
+    direction: Direction,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let bookmarks = workspace
+        .project()
+        .read(cx)
+        .bookmark_store()
+        .read(cx)
+        .all_bookmarks(cx);
+    let cursor = workspace.active_item_as::<Editor>(cx).and_then(|editor| {
+        let editor = editor.read(cx);
+        let head = editor.selections.newest_anchor().head();
+        let (buffer, position) = editor
+            .buffer()
+            .read(cx)
+            .text_anchor_for_position(head, cx)?;
+        let buffer = buffer.read(cx);
+        let path = buffer.project_path(cx)?;
+        let row = buffer.summary_for_anchor::<Point>(&position).row;
+        Some((path, row))
+    });
+
+    let positions = bookmarks
+        .iter()
+        .map(|bookmark| (&bookmark.path, bookmark.row))
+        .collect::<Vec<_>>();
+    let Some(ix) = adjacent_bookmark_ix(
+        &positions,
+        cursor.as_ref().map(|(path, row)| (path, *row)),
+        direction,
+    ) else {
+        return;
+    };
+    open_bookmark(workspace, &bookmarks[ix], window, cx);
+}
+
+/// Returns the index of the bookmark after or before the cursor, wrapping
+/// around at the ends of the project. Bookmarks are ordered by path and row.
+fn adjacent_bookmark_ix(
+    bookmarks: &[(&ProjectPath, u32)],
+    cursor: Option<(&ProjectPath, u32)>,
+    direction: Direction,
+) -> Option<usize> {
+    if bookmarks.is_empty() {
+        return None;
+    }
+    let Some(cursor) = cursor else {
+        return match direction {
+            Direction::Next => Some(0),
+            Direction::Previous => Some(bookmarks.len() - 1),
+        };
+    };
+    let ix = match direction {
+        Direction::Next => bookmarks
+            .iter()
+            .position(|bookmark| *bookmark > cursor)
+            .unwrap_or(0),
+        Direction::Previous => bookmarks
+            .iter()
+            .rposition(|bookmark| *bookmark < cursor)
+            .unwrap_or(bookmarks.len() - 1),
+    };
+    Some(ix)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use project::WorktreeId;
+    use util::rel_path::rel_path;
+
+    #[test]
+    fn test_adjacent_bookmark_ix() {
+        let path = |worktree_id, path| ProjectPath {
+            worktree_id: WorktreeId::from_usize(worktree_id),
+            path: rel_path(path).into(),
+        };
+        let a = path(1, "a.txt");
+        let b = path(1, "b.txt");
+        let c = path(2, "a.txt");
+        let bookmarks = [(&a, 3), (&a, 10), (&b, 0), (&c, 5)];
+
+        assert_eq!(adjacent_bookmark_ix(&[], None, Direction::Next), None);
+        assert_eq!(
+            adjacent_bookmark_ix(&bookmarks, None, Direction::Next),
+            Some(0)
+        );
+        assert_eq!(
+            adjacent_bookmark_ix(&bookmarks, None, Direction::Previous),
+            Some(3)
+        );
+
+        // On a bookmark, goes to the ones around it.
+        assert_eq!(
+            adjacent_bookmark_ix(&bookmarks, Some((&a, 10)), Direction::Next),
+            Some(2)
+        );
+        assert_eq!(
+            adjacent_bookmark_ix(&bookmarks, Some((&a, 10)), Direction::Previous),
+            Some(0)
+        );
+
+        // Between bookmarks, and in files without bookmarks.
+        assert_eq!(
+            adjacent_bookmark_ix(&bookmarks, Some((&a, 5)), Direction::Next),
+            Some(1)
+        );
+        assert_eq!(
+            adjacent_bookmark_ix(
+                &bookmarks,
+                Some((&path(1, "c.txt"), 0)),
+                Direction::Previous
+            ),
+            Some(2)
+        );
+
+        // Wraps around at the ends.
+        assert_eq!(
+            adjacent_bookmark_ix(&bookmarks, Some((&c, 5)), Direction::Next),
+            Some(0)
+        );
+        assert_eq!(
+            adjacent_bookmark_ix(&bookmarks, Some((&a, 0)), Direction::Previous),
+            Some(3)
+        );
+    }
+}
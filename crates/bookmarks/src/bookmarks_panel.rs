@@ -0,0 +1,346 @@
+use anyhow::Result;
+use db::kvp::KEY_VALUE_STORE;
+use gpui::{
+    Action, App, AsyncWindowContext, Context, Entity, EventEmitter, FocusHandle, Focusable, Pixels,
+    SharedString, Subscription, Task, WeakEntity, Window, actions,
+};
+use project::{Fs, Project, bookmark_store::BookmarkStore};
+use serde::{Deserialize, Serialize};
+use settings::{RegisterSetting, Settings, SettingsStore};
+use std::sync::Arc;
+use ui::{IconButton, ListItem, ListItemSpacing, Tab, Tooltip, prelude::*};
+use util::{ResultExt, TryFutureExt};
+use workspace::{
+    Workspace,
+    dock::{DockPosition, Panel, PanelEvent},
+};
+
+use crate::{ClearAll, open_bookmark};
+
+const BOOKMARKS_PANEL_KEY: &str = "BookmarksPanel";
+
+actions!(
+    bookmarks_panel,
+    [
+        /// Toggles focus on the bookmarks panel.
+        ToggleFocus
+    ]
+);
+
+#[derive(Debug, RegisterSetting)]
+pub struct BookmarksPanelSettings {
+    pub button: bool,
+    pub dock: DockPosition,
+    pub default_width: Pixels,
+}
+
+impl Settings for BookmarksPanelSettings {
+    fn from_settings(content: &settings::SettingsContent) -> Self {
+        let panel = content.bookmarks_panel.as_ref().unwrap();
+        Self {
+            button: panel.button.unwrap(),
+            dock: panel.dock.unwrap().into(),
+            default_width: panel.default_width.map(px).unwrap(),
+        }
+    }
+}
+
+pub fn init(cx: &mut App) {
+    cx.observe_new(|workspace: &mut Workspace, _, _| {
+        workspace.register_action(|workspace, _: &ToggleFocus, window, cx| {
+            workspace.toggle_panel_focus::<BookmarksPanel>(window, cx);
+        });
+    })
+    .detach();
+}
+
+#[derive(Serialize, Deserialize)]
+struct SerializedBookmarksPanel {
+    width: Option<Pixels>,
+}
+
+/// Lists the bookmarks of all files in the project.
+pub struct BookmarksPanel {
+    fs: Arc<dyn Fs>,
+    workspace: WeakEntity<Workspace>,
+    project: Entity<Project>,
+    focus_handle: FocusHandle,
+    width: Option<Pixels>,
+    pending_serialization: Task<Option<()>>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl BookmarksPanel {
+    pub fn new(
+        workspace: &mut Workspace,
+        _: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) -> Entity<Self> {
+        let fs = workspace.app_state().fs.clone();
+        let project = workspace.project().clone();
+        let workspace_handle = workspace.weak_handle();
+
+        cx.new(|cx| {
+            let bookmark_store = project.read(cx).bookmark_store();
+            Self {
+                fs,
+                workspace: workspace_handle,
+                project,
+                focus_handle: cx.focus_handle(),
+                width: None,
+                pending_serialization: Task::ready(None),
+                _subscriptions: vec![
+                    cx.observe_global::<SettingsStore>(|_, cx| cx.notify()),
+                    cx.observe(&bookmark_store, |_, _, cx| cx.notify()),
+                ],
+            }
+        })
+    }
+
+    pub fn load(
+        workspace: WeakEntity<Workspace>,
+        cx: AsyncWindowContext,
+    ) -> Task<Result<Entity<Self>>> {
+        cx.spawn(async move |cx| {
+            let serialized_panel = if let Some(panel) = cx
+                .background_spawn(async move { KEY_VALUE_STORE.read_kvp(BOOKMARKS_PANEL_KEY) })
+                .await
+                .log_err()
+                .flatten()
+            {
+                Some(serde_json::from_str::<SerializedBookmarksPanel>(&panel)?)
+            } else {
+                None
+            };
+
+            workspace.update_in(cx, |workspace, window, cx| {
+                let panel = Self::new(workspace, window, cx);
+                if let Some(serialized_panel) = serialized_panel {
+                    panel.update(cx, |panel, cx| {
+                        panel.width = serialized_panel.width.map(|w| w.round());
+                        cx.notify();
+                    });
+                }
+                panel
+            })
+        })
+    }
+
+    fn serialize(&mut self, cx: &mut Context<Self>) {
+        let width = self.width;
+        self.pending_serialization = cx.background_spawn(
+            async move {
+                KEY_VALUE_STORE
+                    .write_kvp(
+                        BOOKMARKS_PANEL_KEY.into(),
+                        serde_json::to_string(&SerializedBookmarksPanel { width })?,
+                    )
+                    .await?;
+                anyhow::Ok(())
+            }
+            .log_err(),
+        );
+    }
+
+    fn bookmark_store(&self, cx: &App) -> Entity<BookmarkStore> {
+        self.project.read(cx).bookmark_store()
+    }
+
+    fn render_bookmarks(&self, cx: &Context<Self>) -> impl IntoElement {
+        let bookmarks = self.bookmark_store(cx).read(cx).all_bookmarks(cx);
+        v_flex().children(bookmarks.into_iter().enumerate().map(|(ix, bookmark)| {
+            let snapshot = bookmark.buffer.read(cx).snapshot();
+            let line = snapshot
+                .text_for_range(
+                    text::Point::new(bookmark.row, 0)
+                        ..text::Point::new(bookmark.row, snapshot.line_len(bookmark.row)),
+                )
+                .collect::<String>();
+            let location = format!(
+                "{}:{}",
+                bookmark
+                    .path
+                    .path
+                    .display(self.project.read(cx).path_style(cx)),
+                bookmark.row + 1
+            );
+            let (title, subtitle) = match bookmark.bookmark.label.clone() {
+                Some(label) => (label, Some(SharedString::from(location))),
+                None => (SharedString::from(location), None),
+            };
+            let path = bookmark.path.clone();
+            let position = bookmark.bookmark.position;
+
+            ListItem::new(("bookmark", ix))
+                .spacing(ListItemSpacing::Sparse)
+                .start_slot(
+                    Icon::new(IconName::Bookmark)
+                        .size(IconSize::Small)
+                        .color(Color::Accent),
+                )
+                .child(
+                    v_flex()
+                        .min_w_0()
+                        .child(h_flex().gap_2().child(Label::new(title)).when_some(
+                            subtitle,
+                            |this, subtitle| {
+                                this.child(
+                                    Label::new(subtitle)
+                                        .size(LabelSize::Small)
+                                        .color(Color::Muted),
+                                )
+                            },
+                        ))
+                        .child(
+                            Label::new(line.trim().to_string())
+                                .size(LabelSize::Small)
+                                .color(Color::Muted)
+                                .truncate(),
+                        ),
+                )
+                .on_click(cx.listener(move |this, _, window, cx| {
+                    this.workspace
+                        .update(cx, |workspace, cx| {
+                            open_bookmark(workspace, &bookmark, window, cx)
+                        })
+                        .ok();
+                }))
+                .end_hover_slot(
+                    IconButton::new(("remove-bookmark", ix), IconName::Close)
+                        .icon_size(IconSize::Small)
+                        .tooltip(Tooltip::text("Remove Bookmark"))
+                        .on_click(cx.listener(move |this, _, _, cx| {
+                            this.bookmark_store(cx).update(cx, |bookmark_store, cx| {
+                                bookmark_store.remove_bookmark(&path, position, cx)
+                            });
+                        })),
+                )
+        }))
+    }
+}
+
+impl Render for BookmarksPanel {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let has_bookmarks = !self
+            .bookmark_store(cx)
+            .read(cx)
+            .all_bookmarks(cx)
+            .is_empty();
+        v_flex()
+            .key_context("BookmarksPanel")
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .child(
+                h_flex()
+                    .justify_between()
+                    .px_2()
+                    .py_1()
+                    // Match the height of the tab bar so they line up.
+                    .h(Tab::container_height(cx))
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border)
+                    .child(Label::new("Bookmarks"))
+                    .child(
+                        IconButton::new("clear-bookmarks", IconName::Trash)
+                            .icon_size(IconSize::Small)
+                            .disabled(!has_bookmarks)
+                            .tooltip(move |_window, cx| {
+                                Tooltip::for_action("Remove All Bookmarks", &ClearAll, cx)
+                            })
+                            .on_click(cx.listener(|this, _, _, cx| {
+                                this.bookmark_store(cx).update(cx, |bookmark_store, cx| {
+                                    bookmark_store.clear_bookmarks(cx)
+                                });
+                            })),
+                    ),
+            )
+            .child(
+                v_flex()
+                    .id("bookmarks")
+                    .flex_1()
+                    .overflow_y_scroll()
+                    .p_1()
+                    .map(|this| {
+                        if has_bookmarks {
+                            this.child(self.render_bookmarks(cx))
+                        } else {
+                            this.child(
+                                div().m_2().child(
+                                    Label::new(
+                                        "Toggle a bookmark on a line of a file to list it here.",
+                                    )
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted),
+                                ),
+                            )
+                        }
+                    }),
+            )
+    }
+}
+
+impl Focusable for BookmarksPanel {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<PanelEvent> for BookmarksPanel {}
+
+impl Panel for BookmarksPanel {
+    fn persistent_name() -> &'static str {
+        "BookmarksPanel"
+    }
+
+    fn panel_key() -> &'static str {
+        BOOKMARKS_PANEL_KEY
+    }
+
+    fn position(&self, _: &Window, cx: &App) -> DockPosition {
+        BookmarksPanelSettings::get_global(cx).dock
+    }
+
+    fn position_is_valid(&self, position: DockPosition) -> bool {
+        matches!(position, DockPosition::Left | DockPosition::Right)
+    }
+
+    fn set_position(&mut self, position: DockPosition, _: &mut Window, cx: &mut Context<Self>) {
+        settings::update_settings_file(self.fs.clone(), cx, move |settings, _| {
+            settings.bookmarks_panel.get_or_insert_default().dock = Some(position.into())
+        });
+    }
+
+    fn size(&self, _: &Window, cx: &App) -> Pixels {
+        self.width
+            .unwrap_or_else(|| BookmarksPanelSettings::get_global(cx).default_width)
+    }
+
+    fn set_size(&mut self, size: Option<Pixels>, _: &mut Window, cx: &mut Context<Self>) {
+        self.width = size;
+        self.serialize(cx);
+        cx.notify();
+    }
+
+    fn icon(&self, _: &Window, cx: &App) -> Option<IconName> {
+        BookmarksPanelSettings::get_global(cx)
+            .button
+            .then_some(IconName::Bookmark)
+    }
+
+    fn icon_tooltip(&self, _window: &Window, _cx: &App) -> Option<&'static str> {
+        Some("Bookmarks Panel")
+    }
+
+    fn icon_label(&self, _window: &Window, cx: &App) -> Option<String> {
+        let count = self.bookmark_store(cx).read(cx).all_bookmarks(cx).len();
+        (count > 0).then(|| count.to_string())
+    }
+
+    fn toggle_action(&self) -> Box<dyn Action> {
+        Box::new(ToggleFocus)
+    }
+
+    fn activation_priority(&self) -> u32 {
+        11
+    }
+}
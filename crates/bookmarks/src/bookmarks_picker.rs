@@ -0,0 +1,268 @@
+use fuzzy::{StringMatch, StringMatchCandidate, match_strings};
+use gpui::{
+    App, Context, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, ParentElement,
+    Render, SharedString, Styled, Task, WeakEntity, Window,
+};
+use picker::{Picker, PickerDelegate};
+use project::bookmark_store::BookmarkEntry;
+use std::sync::Arc;
+use text::Point;
+use ui::{HighlightedLabel, ListItem, ListItemSpacing, prelude::*};
+use util::ResultExt;
+use workspace::{ModalView, Workspace};
+
+use crate::open_bookmark;
+
+pub struct BookmarksPicker {
+    picker: Entity<Picker<BookmarksPickerDelegate>>,
+}
+
+impl BookmarksPicker {
+    pub(crate) fn toggle(
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        let project = workspace.project().read(cx);
+        let path_style = project.path_style(cx);
+        let bookmarks = project.bookmark_store().read(cx).all_bookmarks(cx);
+        let workspace_handle = workspace.weak_handle();
+        workspace.toggle_modal(window, cx, move |window, cx| {
+            Self::new(workspace_handle, bookmarks, path_style, window, cx)
+        });
+    }
+
+    fn new(
+        workspace: WeakEntity<Workspace>,
+        bookmarks: Vec<BookmarkEntry>,
+        path_style: util::paths::PathStyle,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let delegate = BookmarksPickerDelegate::new(
+            cx.entity().downgrade(),
+            workspace,
+            bookmarks,
+            path_style,
+            cx,
+        );
+        let picker = cx.new(|cx| Picker::uniform_list(delegate, window, cx));
+        Self { picker }
+    }
+}
+
+impl Render for BookmarksPicker {
+    fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("BookmarksPicker")
+            .w(rems(34.))
+            .child(self.picker.clone())
+    }
+}
+
+impl Focusable for BookmarksPicker {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for BookmarksPicker {}
+impl ModalView for BookmarksPicker {}
+
+pub struct BookmarksPickerDelegate {
+    bookmarks_picker: WeakEntity<BookmarksPicker>,
+    workspace: WeakEntity<Workspace>,
+    bookmarks: Vec<BookmarkEntry>,
+    /// The text of the line of each bookmark.
+    lines: Vec<SharedString>,
+    candidates: Vec<StringMatchCandidate>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+}
+
+impl BookmarksPickerDelegate {
+    fn new(
+        bookmarks_picker: WeakEntity<BookmarksPicker>,
+        workspace: WeakEntity<Workspace>,
+        bookmarks: Vec<BookmarkEntry>,
+        path_style: util::paths::PathStyle,
+        cx: &App,
+    ) -> Self {
+        let lines = bookmarks
+            .iter()
+            .map(|bookmark| {
+                let snapshot = bookmark.buffer.read(cx).snapshot();
+                let line = snapshot
+                    .text_for_range(
+                        Point::new(bookmark.row, 0)
+                            ..Point::new(bookmark.row, snapshot.line_len(bookmark.row)),
+                    )
+                    .collect::<String>();
+                line.trim().to_string().into()
+            })
+            .collect();
+        let candidates = bookmarks
+            .iter()
+            .enumerate()
+            .map(|(candidate_id, bookmark)| {
+                let mut string = format!(
+                    "{}:{}",
+                    bookmark.path.path.display(path_style),
+                    bookmark.row + 1
+                );
+                if let Some(label) = &bookmark.bookmark.label {
+                    string.push(' ');
+                    string.push_str(label);
+                }
+                StringMatchCandidate::new(candidate_id, &string)
+            })
+            .collect();
+
+        Self {
+            bookmarks_picker,
+            workspace,
+            bookmarks,
+            lines,
+            candidates,
+            matches: Vec::new(),
+            selected_index: 0,
+        }
+    }
+}
+
+impl PickerDelegate for BookmarksPickerDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _window: &mut Window, _cx: &mut App) -> Arc<str> {
+        "Go to a bookmark…".into()
+    }
+
+    fn no_matches_text(&self, _window: &mut Window, _cx: &mut App) -> Option<SharedString> {
+        Some(if self.bookmarks.is_empty() {
+            "No bookmarks in this project".into()
+        } else {
+            "No matching bookmarks".into()
+        })
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn confirm(&mut self, _: bool, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        if let Some(mat) = self.matches.get(self.selected_index) {
+            let bookmark = &self.bookmarks[mat.candidate_id];
+            self.workspace
+                .update(cx, |workspace, cx| {
+                    open_bookmark(workspace, bookmark, window, cx)
+                })
+                .log_err();
+        }
+        self.dismissed(window, cx);
+    }
+
+    fn dismissed(&mut self, _: &mut Window, cx: &mut Context<Picker<Self>>) {
+        self.bookmarks_picker
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(
+        &mut self,
+        ix: usize,
+        _window: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Task<()> {
+        let background = cx.background_executor().clone();
+        let candidates = self.candidates.clone();
+        cx.spawn_in(window, async move |this, cx| {
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, candidate)| StringMatch {
+                        candidate_id: index,
+                        string: candidate.string,
+                        positions: Vec::new(),
+                        score: 0.0,
+                    })
+                    .collect()
+            } else {
+                let mut matches = match_strings(
+                    &candidates,
+                    &query,
+                    false,
+                    true,
+                    100,
+                    &Default::default(),
+                    background,
+                )
+                .await;
+                // Keep the bookmarks in the order of the project, as they are
+                // usually few and recognized by their position.
+                matches.sort_unstable_by_key(|mat| mat.candidate_id);
+                matches
+            };
+
+            this.update(cx, |this, cx| {
+                let delegate = &mut this.delegate;
+                delegate.matches = matches;
+                delegate.selected_index = delegate
+                    .selected_index
+                    .min(delegate.matches.len().saturating_sub(1));
+                cx.notify();
+            })
+            .log_err();
+        })
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let mat = self.matches.get(ix)?;
+        let line = self.lines.get(mat.candidate_id)?.clone();
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .toggle_state(selected)
+                .start_slot(
+                    Icon::new(IconName::Bookmark)
+                        .size(IconSize::Small)
+                        .color(Color::Accent),
+                )
+                .child(
+                    h_flex()
+                        .gap_2()
+                        .min_w_0()
+                        .child(HighlightedLabel::new(
+                            mat.string.clone(),
+                            mat.positions.clone(),
+                        ))
+                        .child(
+                            Label::new(line)
+                                .size(LabelSize::Small)
+                                .color(Color::Muted)
+                                .truncate(),
+                        ),
+                ),
+        )
+    }
+}
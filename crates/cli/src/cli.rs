@@ -14,6 +14,7 @@ pub enum CliRequest {
         paths: Vec<String>,
         urls: Vec<String>,
         diff_paths: Vec<[String; 2]>,
+        merge_paths: Vec<[String; 4]>,
         wsl: Option<String>,
         wait: bool,
         open_new_workspace: Option<bool>,
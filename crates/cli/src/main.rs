@@ -119,6 +119,11 @@ struct Args {
     /// Pairs of file paths to diff. Can be specified multiple times.
     #[arg(long, action = clap::ArgAction::Append, num_args = 2, value_names = ["OLD_PATH", "NEW_PATH"])]
     diff: Vec<String>,
+    /// Three-way merges a base file with the changes of two other files, and
+    /// writes the result to an output file, like git's mergetool. Can be
+    /// specified multiple times.
+    #[arg(long, action = clap::ArgAction::Append, num_args = 4, value_names = ["BASE", "LOCAL", "REMOTE", "OUTPUT"])]
+    merge: Vec<String>,
     /// Uninstall Zed from user system
     #[cfg(all(
         any(target_os = "linux", target_os = "macos"),
@@ -473,6 +478,7 @@ fn main() -> Result<()> {
     let mut paths = vec![];
     let mut urls = vec![];
     let mut diff_paths = vec![];
+    let mut merge_paths = vec![];
     let mut stdin_tmp_file: Option<fs::File> = None;
     let mut anonymous_fd_tmp_files = vec![];
 
@@ -483,6 +489,15 @@ fn main() -> Result<()> {
         ]);
     }
 
+    for paths in args.merge.chunks(4) {
+        merge_paths.push([
+            parse_path_with_position(&paths[0])?,
+            parse_path_with_position(&paths[1])?,
+            parse_path_with_position(&paths[2])?,
+            parse_path_with_position(&paths[3])?,
+        ]);
+    }
+
     #[cfg(target_os = "windows")]
     let wsl = args.wsl.as_ref();
     #[cfg(not(target_os = "windows"))]
@@ -538,6 +553,7 @@ fn main() -> Result<()> {
                     paths,
                     urls,
                     diff_paths,
+                    merge_paths,
                     wsl,
                     wait: args.wait,
                     open_new_workspace,
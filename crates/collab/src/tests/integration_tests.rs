@@ -19,8 +19,8 @@ use git::{
     status::{FileStatus, StatusCode, TrackedStatus, UnmergedStatus, UnmergedStatusCode},
 };
 use gpui::{
-    App, BackgroundExecutor, Entity, Modifiers, MouseButton, MouseDownEvent, TestAppContext,
-    UpdateGlobal, px, size,
+    App, AppContext as _, BackgroundExecutor, Entity, Modifiers, MouseButton, MouseDownEvent,
+    TestAppContext, UpdateGlobal, px, size,
 };
 use language::{
     Diagnostic, DiagnosticEntry, DiagnosticSourceKind, FakeLspAdapter, Language, LanguageConfig,
@@ -32,12 +32,13 @@ use lsp::{LanguageServerId, OneOf};
 use parking_lot::Mutex;
 use pretty_assertions::assert_eq;
 use project::{
-    DiagnosticSummary, HoverBlockKind, Project, ProjectPath,
+    DiagnosticSummary, HoverBlockKind, Project, ProjectPath, TerminalSharing,
     lsp_store::{FormatTrigger, LspFormatTarget, SymbolLocation},
     search::{SearchQuery, SearchResult},
 };
 use prompt_store::PromptBuilder;
 use rand::prelude::*;
+use rpc::proto;
 use serde_json::json;
 use settings::{LanguageServerFormatterSpecifier, PrettierSettingsContent, SettingsStore};
 use std::{
@@ -51,6 +52,10 @@ use std::{
     },
     time::Duration,
 };
+use terminal::{
+    Terminal, TerminalBuilder,
+    terminal_settings::{AlternateScroll, CursorShape},
+};
 use unindent::Unindent as _;
 use util::{path, rel_path::rel_path, uri};
 use workspace::Pane;
@@ -3715,6 +3720,78 @@ async fn test_buffer_reloading(
     });
 }
 
+#[gpui::test(iterations = 10)]
+async fn test_buffer_encoding(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    server
+        .create_room(&mut [(&client_a, cx_a), (&client_b, cx_b)])
+        .await;
+    let active_call_a = cx_a.read(ActiveCall::global);
+
+    client_a
+        .fs()
+        .insert_tree(path!("/dir"), json!({ "a.txt": "" }))
+        .await;
+    // "Café" in Windows-1252.
+    client_a
+        .fs()
+        .insert_file(path!("/dir/a.txt"), vec![0x43, 0x61, 0x66, 0xe9])
+        .await;
+    let (project_a, worktree_id) = client_a.build_local_project(path!("/dir"), cx_a).await;
+    let project_id = active_call_a
+        .update(cx_a, |call, cx| call.share_project(project_a.clone(), cx))
+        .await
+        .unwrap();
+    let project_b = client_b.join_remote_project(project_id, cx_b).await;
+
+    // The guest's buffer has the encoding that the host detected.
+    let buffer_b = project_b
+        .update(cx_b, |p, cx| {
+            p.open_buffer((worktree_id, rel_path("a.txt")), cx)
+        })
+        .await
+        .unwrap();
+    buffer_b.read_with(cx_b, |buf, _| {
+        assert_eq!(buf.text(), "Café");
+        assert_eq!(buf.encoding(), encoding_rs::WINDOWS_1252);
+    });
+
+    // A guest changes the encoding, which the host saves the file with.
+    buffer_b.update(cx_b, |buf, cx| {
+        buf.update_encoding(encoding_rs::UTF_16BE, true, cx);
+    });
+    executor.run_until_parked();
+    let buffer_a = project_a
+        .update(cx_a, |p, cx| {
+            p.open_buffer((worktree_id, rel_path("a.txt")), cx)
+        })
+        .await
+        .unwrap();
+    buffer_a.read_with(cx_a, |buf, _| {
+        assert_eq!(buf.encoding(), encoding_rs::UTF_16BE);
+        assert!(buf.has_bom());
+    });
+
+    project_b
+        .update(cx_b, |p, cx| p.save_buffer(buffer_b.clone(), cx))
+        .await
+        .unwrap();
+    assert_eq!(
+        client_a
+            .fs()
+            .load_bytes(path!("/dir/a.txt").as_ref())
+            .await
+            .unwrap(),
+        [0xfe, 0xff, 0x00, 0x43, 0x00, 0x61, 0x00, 0x66, 0x00, 0xe9]
+    );
+}
+
 #[gpui::test(iterations = 10)]
 async fn test_editing_while_guest_opens_buffer(
     executor: BackgroundExecutor,
@@ -7100,3 +7177,183 @@ async fn test_remote_git_branches(
 
     assert_eq!(host_branch.name(), "totally-new-branch");
 }
+
+#[gpui::test(iterations = 10)]
+async fn test_sharing_terminals(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    server
+        .create_room(&mut [(&client_a, cx_a), (&client_b, cx_b)])
+        .await;
+    let active_call_a = cx_a.read(ActiveCall::global);
+    let active_call_b = cx_b.read(ActiveCall::global);
+
+    client_a
+        .fs()
+        .insert_tree(path!("/a"), json!({ "a.txt": "a-contents" }))
+        .await;
+    let (project_a, _) = client_a.build_local_project(path!("/a"), cx_a).await;
+    let project_id = active_call_a
+        .update(cx_a, |call, cx| call.share_project(project_a.clone(), cx))
+        .await
+        .unwrap();
+    let project_b = client_b.join_remote_project(project_id, cx_b).await;
+    executor.run_until_parked();
+
+    // The terminals the host shares with client B, and whether they were closed.
+    let shared_terminals_b = Rc::new(RefCell::new(
+        Vec::<(Entity<Terminal>, Rc<Cell<bool>>)>::new(),
+    ));
+    cx_b.update(|cx| {
+        let shared_terminals_b = shared_terminals_b.clone();
+        cx.subscribe(&project_b, move |_, event: &project::Event, cx| {
+            if let project::Event::SharedTerminalOpened(terminal) = event {
+                let closed = Rc::new(Cell::new(false));
+                cx.subscribe(terminal, {
+                    let closed = closed.clone();
+                    move |_, event: &terminal::Event, _| {
+                        if matches!(event, terminal::Event::CloseTerminal) {
+                            closed.set(true);
+                        }
+                    }
+                })
+                .detach();
+                shared_terminals_b
+                    .borrow_mut()
+                    .push((terminal.clone(), closed));
+            }
+        })
+        .detach();
+    });
+
+    // Instead of a shell, the host's terminal sends its input to a channel.
+    let (input_tx, mut input_rx) = mpsc::unbounded();
+    let terminal_a = cx_a.new(|cx| {
+        let mut terminal =
+            TerminalBuilder::new_display_only(CursorShape::default(), AlternateScroll::On, None, 0)
+                .unwrap()
+                .subscribe(cx);
+        terminal.set_input_forwarder(Some(input_tx));
+        terminal
+    });
+    let terminal_id = terminal_a.entity_id().as_u64();
+    terminal_a.update(cx_a, |terminal, cx| {
+        terminal.write_output(b"$ cargo test\n", cx)
+    });
+
+    // Client A shares the terminal read-only, and client B sees its output.
+    project_a
+        .update(cx_a, |project, cx| {
+            project.share_terminal(&terminal_a, TerminalSharing::ReadOnly, cx)
+        })
+        .unwrap();
+    executor.advance_clock(Duration::from_secs(1));
+    executor.run_until_parked();
+    let (terminal_b, terminal_b_closed) = shared_terminals_b.borrow()[0].clone();
+    terminal_b.read_with(cx_b, |terminal, _| {
+        assert!(screen_contains(terminal, "$ cargo test"));
+    });
+
+    // Client B sees the output the terminal prints later.
+    terminal_a.update(cx_a, |terminal, cx| {
+        terminal.write_output(b"test result: ok\n", cx)
+    });
+    executor.advance_clock(Duration::from_secs(1));
+    executor.run_until_parked();
+    terminal_b.read_with(cx_b, |terminal, _| {
+        assert!(screen_contains(terminal, "$ cargo test"));
+        assert!(screen_contains(terminal, "test result: ok"));
+    });
+
+    // The input of client B doesn't reach the terminal while it's read-only.
+    terminal_b.update(cx_b, |terminal, _| terminal.input(b"q".to_vec()));
+    executor.run_until_parked();
+    assert!(input_rx.try_next().is_err());
+    let response = client_b
+        .client()
+        .request(proto::SharedTerminalInput {
+            project_id,
+            terminal_id,
+            input: b"q".to_vec(),
+        })
+        .await;
+    assert!(response.is_err());
+    assert!(input_rx.try_next().is_err());
+
+    // Once client A shares the terminal read-write, the input of client B reaches it.
+    project_a
+        .update(cx_a, |project, cx| {
+            project.share_terminal(&terminal_a, TerminalSharing::ReadWrite, cx)
+        })
+        .unwrap();
+    executor.run_until_parked();
+    terminal_b.update(cx_b, |terminal, _| terminal.input(b"ls\r".to_vec()));
+    executor.run_until_parked();
+    assert_eq!(input_rx.try_next().unwrap(), Some(b"ls\r".to_vec()));
+
+    // Client A stops sharing the terminal, which closes it for client B.
+    project_a.update(cx_a, |project, cx| {
+        project.unshare_terminal(&terminal_a, cx)
+    });
+    executor.run_until_parked();
+    assert!(terminal_b_closed.get());
+    terminal_a.update(cx_a, |terminal, cx| {
+        terminal.write_output(b"$ cargo build\n", cx)
+    });
+    executor.advance_clock(Duration::from_secs(1));
+    executor.run_until_parked();
+    terminal_b.read_with(cx_b, |terminal, _| {
+        assert!(!screen_contains(terminal, "$ cargo build"));
+    });
+    let response = client_b
+        .client()
+        .request(proto::SharedTerminalInput {
+            project_id,
+            terminal_id,
+            input: b"q".to_vec(),
+        })
+        .await;
+    assert!(response.is_err());
+    assert!(input_rx.try_next().is_err());
+
+    // Client A shares the terminal again, and client B sees it again.
+    project_a
+        .update(cx_a, |project, cx| {
+            project.share_terminal(&terminal_a, TerminalSharing::ReadWrite, cx)
+        })
+        .unwrap();
+    executor.advance_clock(Duration::from_secs(1));
+    executor.run_until_parked();
+    assert_eq!(shared_terminals_b.borrow().len(), 2);
+    let (terminal_b, terminal_b_closed) = shared_terminals_b.borrow()[1].clone();
+    terminal_b.read_with(cx_b, |terminal, _| {
+        assert!(screen_contains(terminal, "$ cargo build"));
+    });
+
+    // Client B leaves the call, which closes the terminal and stops forwarding its input.
+    active_call_b
+        .update(cx_b, |call, cx| call.hang_up(cx))
+        .await
+        .unwrap();
+    executor.run_until_parked();
+    assert!(terminal_b_closed.get());
+    terminal_b.update(cx_b, |terminal, _| terminal.input(b"q".to_vec()));
+    executor.run_until_parked();
+    assert!(input_rx.try_next().is_err());
+    project_a.read_with(cx_a, |project, _| {
+        assert_eq!(project.collaborators().len(), 0);
+    });
+}
+
+fn screen_contains(terminal: &Terminal, text: &str) -> bool {
+    terminal
+        .screen_snapshot()
+        .lines
+        .iter()
+        .any(|line| line.contains(text))
+}
@@ -50,6 +50,7 @@ use workspace::notifications::{ErrorMessagePrompt, NotificationId, show_app_noti
 
 pub mod cursor_excerpt;
 pub mod example_spec;
+mod fim;
 mod license_detection;
 pub mod mercury;
 mod onboarding_modal;
@@ -172,6 +173,7 @@ pub enum EditPredictionModel {
     Zeta2,
     Sweep,
     Mercury,
+    Local,
 }
 
 pub struct EditPredictionModelInput {
@@ -1034,7 +1036,9 @@ impl EditPredictionStore {
                     return;
                 }
             }
-            EditPredictionModel::Sweep | EditPredictionModel::Mercury => return,
+            EditPredictionModel::Sweep
+            | EditPredictionModel::Mercury
+            | EditPredictionModel::Local => return,
         }
 
         let Some(project_state) = self.projects.get_mut(&project.entity_id()) else {
@@ -1188,7 +1192,9 @@ impl EditPredictionStore {
                     return;
                 }
             }
-            EditPredictionModel::Sweep | EditPredictionModel::Mercury => return,
+            EditPredictionModel::Sweep
+            | EditPredictionModel::Mercury
+            | EditPredictionModel::Local => return,
         }
 
         self.reject_predictions_tx
@@ -1365,6 +1371,17 @@ impl EditPredictionStore {
     #[cfg(test)]
     pub const THROTTLE_TIMEOUT: Duration = Duration::ZERO;
 
+    /// Local model servers are usually slower than hosted ones, so rather than
+    /// throttling, their requests wait until the user has stopped editing.
+    fn debounce_timeout(&self, cx: &App) -> Option<Duration> {
+        (self.edit_prediction_model == EditPredictionModel::Local).then(|| {
+            all_language_settings(None, cx)
+                .edit_predictions
+                .local
+                .debounce
+        })
+    }
+
     fn queue_prediction_refresh(
         &mut self,
         project: Entity<Project>,
@@ -1377,13 +1394,16 @@ impl EditPredictionStore {
             -> Task<Result<Option<(EditPredictionResult, PredictionRequestedBy)>>>
         + 'static,
     ) {
+        let debounce_timeout = self.debounce_timeout(cx);
         let project_state = self.get_or_init_project(&project, cx);
         let pending_prediction_id = project_state.next_pending_prediction_id;
         project_state.next_pending_prediction_id += 1;
         let last_request = project_state.last_prediction_refresh;
 
         let task = cx.spawn(async move |this, cx| {
-            if let Some((last_entity, last_timestamp)) = last_request
+            if let Some(timeout) = debounce_timeout {
+                cx.background_executor().timer(timeout).await;
+            } else if let Some((last_entity, last_timestamp)) = last_request
                 && throttle_entity == last_entity
                 && let Some(timeout) =
                     (last_timestamp + Self::THROTTLE_TIMEOUT).checked_duration_since(Instant::now())
@@ -1396,13 +1416,26 @@ impl EditPredictionStore {
             let mut is_cancelled = true;
             this.update(cx, |this, cx| {
                 let project_state = this.get_or_init_project(&project, cx);
-                if !project_state
+                if project_state
                     .cancelled_predictions
                     .remove(&pending_prediction_id)
                 {
-                    project_state.last_prediction_refresh = Some((throttle_entity, Instant::now()));
-                    is_cancelled = false;
+                    return;
+                }
+                // When debouncing, a refresh queued while this one was waiting
+                // restarts the wait, so this one is dropped in its favor.
+                if debounce_timeout.is_some()
+                    && project_state.next_pending_prediction_id > pending_prediction_id + 1
+                {
+                    project_state
+                        .pending_predictions
+                        .retain(|pending_prediction| {
+                            pending_prediction.id != pending_prediction_id
+                        });
+                    return;
                 }
+                project_state.last_prediction_refresh = Some((throttle_entity, Instant::now()));
+                is_cancelled = false;
             })
             .ok();
             if is_cancelled {
@@ -1571,6 +1604,7 @@ impl EditPredictionStore {
             EditPredictionModel::Zeta2 => zeta2::request_prediction_with_zeta2(self, inputs, cx),
             EditPredictionModel::Sweep => self.sweep_ai.request_prediction_with_sweep(inputs, cx),
             EditPredictionModel::Mercury => self.mercury.request_prediction(inputs, cx),
+            EditPredictionModel::Local => fim::request_prediction_with_fim(inputs, cx),
         };
 
         cx.spawn(async move |this, cx| {
@@ -24,7 +24,14 @@ use pretty_assertions::{assert_eq, assert_matches};
 use project::{FakeFs, Project};
 use serde_json::json;
 use settings::SettingsStore;
-use std::{path::Path, sync::Arc, time::Duration};
+use std::{
+    path::Path,
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering::SeqCst},
+    },
+    time::Duration,
+};
 use util::{path, rel_path::rel_path};
 use uuid::Uuid;
 use zeta_prompt::ZetaPromptInput;
@@ -2082,6 +2089,173 @@ async fn test_unauthenticated_with_custom_url_allows_prediction_impl(cx: &mut Te
     );
 }
 
+#[gpui::test]
+async fn test_local_fim_prediction(cx: &mut TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/project",
+        serde_json::json!({
+            "main.rs": "fn main() {\n    \n}\n"
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs.clone(), [path!("/project").as_ref()], cx).await;
+
+    let captured_request = Arc::new(Mutex::new(None));
+    let http_client = FakeHttpClient::create({
+        let captured_request = captured_request.clone();
+        move |req| {
+            let uri = req.uri().path().to_string();
+            let mut body = req.into_body();
+            let captured_request = captured_request.clone();
+            async move {
+                assert_eq!(uri, "/api/generate");
+                let mut buf = Vec::new();
+                body.read_to_end(&mut buf).await.ok();
+                *captured_request.lock() =
+                    Some(serde_json::from_slice::<serde_json::Value>(&buf).unwrap());
+                Ok(Response::builder()
+                    .body(
+                        json!({ "response": "println!(\"Hello, world!\");\n" })
+                            .to_string()
+                            .into(),
+                    )
+                    .unwrap())
+            }
+        }
+    });
+
+    let client =
+        cx.update(|cx| client::Client::new(Arc::new(FakeSystemClock::new()), http_client, cx));
+    cx.update(|cx| {
+        language_model::RefreshLlmTokenListener::register(client.clone(), cx);
+    });
+
+    let ep_store = cx.new(|cx| EditPredictionStore::new(client, project.read(cx).user_store(), cx));
+
+    let buffer = project
+        .update(cx, |project, cx| {
+            let path = project
+                .find_project_path(path!("/project/main.rs"), cx)
+                .unwrap();
+            project.open_buffer(path, cx)
+        })
+        .await
+        .unwrap();
+
+    let cursor = buffer.read_with(cx, |buffer, _| buffer.anchor_before(Point::new(1, 4)));
+    ep_store.update(cx, |ep_store, cx| {
+        ep_store.register_buffer(&buffer, &project, cx)
+    });
+    cx.background_executor.run_until_parked();
+
+    let prediction = ep_store
+        .update(cx, |ep_store, cx| {
+            ep_store.set_edit_prediction_model(EditPredictionModel::Local);
+            ep_store.request_prediction(&project, &buffer, cursor, Default::default(), cx)
+        })
+        .await
+        .unwrap()
+        .unwrap()
+        .prediction
+        .unwrap();
+
+    let request = captured_request.lock().take().unwrap();
+    assert_eq!(request["model"], "qwen2.5-coder:1.5b");
+    assert_eq!(request["raw"], true);
+    assert_eq!(
+        request["prompt"],
+        "<|fim_prefix|>fn main() {\n    <|fim_suffix|>\n}\n<|fim_middle|>"
+    );
+
+    buffer.update(cx, |buffer, cx| {
+        buffer.edit(prediction.edits.iter().cloned(), None, cx)
+    });
+    assert_eq!(
+        buffer.read_with(cx, |buffer, _| buffer.text()),
+        "fn main() {\n    println!(\"Hello, world!\");\n}\n"
+    );
+}
+
+#[gpui::test]
+async fn test_local_fim_prediction_debounce(cx: &mut TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/project",
+        serde_json::json!({
+            "main.rs": "fn main() {\n    \n}\n"
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs.clone(), [path!("/project").as_ref()], cx).await;
+
+    let request_count = Arc::new(AtomicUsize::new(0));
+    let http_client = FakeHttpClient::create({
+        let request_count = request_count.clone();
+        move |_| {
+            request_count.fetch_add(1, SeqCst);
+            async move {
+                Ok(Response::builder()
+                    .body(json!({ "response": "println!();\n" }).to_string().into())
+                    .unwrap())
+            }
+        }
+    });
+
+    let client =
+        cx.update(|cx| client::Client::new(Arc::new(FakeSystemClock::new()), http_client, cx));
+    cx.update(|cx| {
+        language_model::RefreshLlmTokenListener::register(client.clone(), cx);
+    });
+
+    let ep_store = cx.new(|cx| EditPredictionStore::new(client, project.read(cx).user_store(), cx));
+
+    let buffer = project
+        .update(cx, |project, cx| {
+            let path = project
+                .find_project_path(path!("/project/main.rs"), cx)
+                .unwrap();
+            project.open_buffer(path, cx)
+        })
+        .await
+        .unwrap();
+
+    let cursor = buffer.read_with(cx, |buffer, _| buffer.anchor_before(Point::new(1, 4)));
+    ep_store.update(cx, |ep_store, cx| {
+        ep_store.set_edit_prediction_model(EditPredictionModel::Local);
+        ep_store.register_buffer(&buffer, &project, cx)
+    });
+    cx.background_executor.run_until_parked();
+
+    // Each refresh within the debounce delay of the previous one restarts the wait.
+    let debounce = Duration::from_millis(150);
+    for _ in 0..3 {
+        ep_store.update(cx, |ep_store, cx| {
+            ep_store.refresh_prediction_from_buffer(project.clone(), buffer.clone(), cursor, cx)
+        });
+        cx.executor().advance_clock(debounce / 2);
+        cx.run_until_parked();
+        assert_eq!(request_count.load(SeqCst), 0);
+    }
+
+    cx.executor().advance_clock(debounce);
+    cx.run_until_parked();
+    assert_eq!(request_count.load(SeqCst), 1);
+    ep_store.update(cx, |ep_store, cx| {
+        assert!(
+            ep_store
+                .prediction_at(&buffer, None, &project, cx)
+                .is_some()
+        );
+    });
+}
+
 #[ctor::ctor]
 fn init_logger() {
     zlog::init_test();
@@ -0,0 +1,277 @@
+use crate::{
+    DebugEvent, EditPredictionFinishedDebugEvent, EditPredictionId, EditPredictionModelInput,
+    EditPredictionStartedDebugEvent, cursor_excerpt, prediction::EditPredictionResult,
+};
+use anyhow::{Context as _, Result};
+use futures::AsyncReadExt as _;
+use gpui::{
+    App, AppContext as _, Task,
+    http_client::{self, AsyncBody, HttpClient, Method},
+};
+use language::{
+    OffsetRangeExt as _, ToOffset as _, ToPoint as _,
+    language_settings::{
+        LocalEditPredictionApi, LocalEditPredictionSettings, all_language_settings,
+    },
+};
+use serde::{Deserialize, Serialize};
+use std::{path::Path, sync::Arc, time::Instant};
+use uuid::Uuid;
+
+/// Requests a fill-in-the-middle completion at the cursor from the model server
+/// configured in the `edit_predictions.local` settings.
+pub(crate) fn request_prediction_with_fim(
+    EditPredictionModelInput {
+        buffer,
+        snapshot,
+        position,
+        events,
+        related_files,
+        debug_tx,
+        ..
+    }: EditPredictionModelInput,
+    cx: &mut App,
+) -> Task<Result<Option<EditPredictionResult>>> {
+    let settings = all_language_settings(None, cx)
+        .edit_predictions
+        .local
+        .clone();
+    let full_path: Arc<Path> = snapshot
+        .file()
+        .map(|file| file.full_path(cx))
+        .unwrap_or_else(|| "untitled".into())
+        .into();
+
+    let http_client = cx.http_client();
+    let cursor_point = position.to_point(&snapshot);
+    let buffer_snapshotted_at = Instant::now();
+    let active_buffer = buffer.clone();
+
+    let result = cx.background_spawn(async move {
+        // The model only inserts text at the cursor, so the editable range is kept to the
+        // cursor's surroundings and the token budget goes to the prefix and suffix.
+        let (editable_range, context_range) =
+            cursor_excerpt::editable_and_context_ranges_for_cursor_position(
+                cursor_point,
+                &snapshot,
+                0,
+                settings.max_context_tokens as usize,
+            );
+        let context_offset_range = context_range.to_offset(&snapshot);
+        let editable_offset_range = editable_range.to_offset(&snapshot);
+        let cursor_offset_in_excerpt =
+            cursor_point.to_offset(&snapshot) - context_offset_range.start;
+
+        let inputs = zeta_prompt::ZetaPromptInput {
+            events,
+            related_files,
+            cursor_offset_in_excerpt,
+            cursor_path: full_path.clone(),
+            cursor_excerpt: snapshot
+                .text_for_range(context_range)
+                .collect::<String>()
+                .into(),
+            editable_range_in_excerpt: (editable_offset_range.start - context_offset_range.start)
+                ..(editable_offset_range.end - context_offset_range.start),
+        };
+
+        let prefix = &inputs.cursor_excerpt[..cursor_offset_in_excerpt];
+        let suffix = &inputs.cursor_excerpt[cursor_offset_in_excerpt..];
+        let prompt = build_prompt(&settings.prompt_template, &full_path, prefix, suffix);
+
+        if let Some(debug_tx) = &debug_tx {
+            debug_tx
+                .unbounded_send(DebugEvent::EditPredictionStarted(
+                    EditPredictionStartedDebugEvent {
+                        buffer: active_buffer.downgrade(),
+                        prompt: Some(prompt.clone()),
+                        position,
+                    },
+                ))
+                .ok();
+        }
+
+        let (id, completion) = request_completion(http_client.as_ref(), &settings, prompt).await?;
+        let response_received_at = Instant::now();
+
+        if let Some(debug_tx) = &debug_tx {
+            debug_tx
+                .unbounded_send(DebugEvent::EditPredictionFinished(
+                    EditPredictionFinishedDebugEvent {
+                        buffer: active_buffer.downgrade(),
+                        model_output: Some(completion.clone()),
+                        position,
+                    },
+                ))
+                .ok();
+        }
+
+        let completion = clean_completion(&completion, suffix);
+        let mut edits = Vec::new();
+        if !completion.trim().is_empty() {
+            edits.push((position..position, Arc::from(completion)));
+        }
+
+        anyhow::Ok((id, edits, snapshot, response_received_at, inputs))
+    });
+
+    cx.spawn(async move |cx| {
+        let (id, edits, old_snapshot, response_received_at, inputs) =
+            result.await.context("Local edit prediction failed")?;
+        anyhow::Ok(Some(
+            EditPredictionResult::new(
+                EditPredictionId(id.into()),
+                &buffer,
+                &old_snapshot,
+                edits.into(),
+                buffer_snapshotted_at,
+                response_received_at,
+                inputs,
+                cx,
+            )
+            .await,
+        ))
+    })
+}
+
+fn build_prompt(template: &str, path: &Path, prefix: &str, suffix: &str) -> String {
+    template
+        .replace("{file_path}", &path.to_string_lossy())
+        .replace("{prefix}", prefix)
+        .replace("{suffix}", suffix)
+}
+
+/// Drops trailing whitespace, along with a repeat of the rest of the cursor's line,
+/// which models often generate even though it's part of the suffix.
+fn clean_completion<'a>(completion: &'a str, suffix: &str) -> &'a str {
+    let completion = completion.trim_end();
+    let rest_of_line = suffix.lines().next().unwrap_or_default().trim();
+    if rest_of_line.is_empty() {
+        completion
+    } else {
+        completion
+            .strip_suffix(rest_of_line)
+            .unwrap_or(completion)
+            .trim_end()
+    }
+}
+
+#[derive(Serialize)]
+struct OllamaGenerateRequest<'a> {
+    model: &'a str,
+    prompt: String,
+    /// Prevents Ollama from wrapping the prompt in the model's chat template.
+    raw: bool,
+    stream: bool,
+    options: OllamaGenerateOptions,
+}
+
+#[derive(Serialize)]
+struct OllamaGenerateOptions {
+    num_predict: u32,
+    temperature: f32,
+}
+
+#[derive(Deserialize)]
+struct OllamaGenerateResponse {
+    response: String,
+}
+
+#[derive(Serialize)]
+struct CompletionRequest<'a> {
+    model: &'a str,
+    prompt: String,
+    max_tokens: u32,
+    temperature: f32,
+    stream: bool,
+}
+
+#[derive(Deserialize)]
+struct CompletionResponse {
+    #[serde(default)]
+    id: Option<String>,
+    choices: Vec<CompletionChoice>,
+}
+
+#[derive(Deserialize)]
+struct CompletionChoice {
+    text: String,
+}
+
+async fn request_completion(
+    http_client: &dyn HttpClient,
+    settings: &LocalEditPredictionSettings,
+    prompt: String,
+) -> Result<(String, String)> {
+    let api_url = settings.api_url.trim_end_matches('/');
+    let (uri, body) = match settings.api {
+        LocalEditPredictionApi::Ollama => (
+            format!("{api_url}/api/generate"),
+            serde_json::to_vec(&OllamaGenerateRequest {
+                model: &settings.model,
+                prompt,
+                raw: true,
+                stream: false,
+                options: OllamaGenerateOptions {
+                    num_predict: settings.max_output_tokens,
+                    temperature: 0.,
+                },
+            })?,
+        ),
+        LocalEditPredictionApi::OpenAiCompatible => (
+            format!("{api_url}/v1/completions"),
+            serde_json::to_vec(&CompletionRequest {
+                model: &settings.model,
+                prompt,
+                max_tokens: settings.max_output_tokens,
+                temperature: 0.,
+                stream: false,
+            })?,
+        ),
+    };
+
+    let request = http_client::Request::builder()
+        .uri(uri)
+        .header("Content-Type", "application/json")
+        .method(Method::POST)
+        .body(AsyncBody::from(body))
+        .context("Failed to create request")?;
+
+    let mut response = http_client
+        .send(request)
+        .await
+        .context("Failed to send request")?;
+
+    let mut body = Vec::new();
+    response
+        .body_mut()
+        .read_to_end(&mut body)
+        .await
+        .context("Failed to read response body")?;
+
+    if !response.status().is_success() {
+        anyhow::bail!(
+            "Request failed with status: {:?}\nBody: {}",
+            response.status(),
+            String::from_utf8_lossy(&body),
+        );
+    }
+
+    match settings.api {
+        LocalEditPredictionApi::Ollama => {
+            let response: OllamaGenerateResponse =
+                serde_json::from_slice(&body).context("Failed to parse response")?;
+            Ok((Uuid::new_v4().to_string(), response.response))
+        }
+        LocalEditPredictionApi::OpenAiCompatible => {
+            let mut response: CompletionResponse =
+                serde_json::from_slice(&body).context("Failed to parse response")?;
+            let choice = response
+                .choices
+                .pop()
+                .context("Response contained no completions")?;
+            let id = response.id.unwrap_or_else(|| Uuid::new_v4().to_string());
+            Ok((id, choice.text))
+        }
+    }
+}
@@ -20,7 +20,10 @@ use gpui::{
 use indoc::indoc;
 use language::{
     EditPredictionsMode, File, Language,
-    language_settings::{self, AllLanguageSettings, EditPredictionProvider, all_language_settings},
+    language_settings::{
+        self, AllLanguageSettings, EditPredictionProvider, LocalEditPredictionApi,
+        all_language_settings,
+    },
 };
 use project::DisableAiSettings;
 use regex::Regex;
@@ -293,6 +296,64 @@ impl Render for EditPredictionButton {
                         .with_handle(self.popover_menu_handle.clone()),
                 )
             }
+            EditPredictionProvider::Local => {
+                let enabled = self.editor_enabled.unwrap_or(true);
+                let local_settings = &all_language_settings.edit_predictions.local;
+                let icon = match local_settings.api {
+                    LocalEditPredictionApi::Ollama => IconName::AiOllama,
+                    LocalEditPredictionApi::OpenAiCompatible => IconName::AiOpenAiCompat,
+                };
+                let tooltip_meta: SharedString =
+                    format!("Powered by {}", local_settings.model).into();
+                let this = cx.weak_entity();
+
+                let is_refreshing = self
+                    .edit_prediction_provider
+                    .as_ref()
+                    .is_some_and(|provider| provider.is_refreshing(cx));
+
+                let icon_button = IconButton::new("local-edit-prediction-icon", icon)
+                    .shape(IconButtonShape::Square)
+                    .when(!enabled, |this| {
+                        this.indicator(Indicator::dot().color(Color::Ignored))
+                            .indicator_border_color(Some(cx.theme().colors().status_bar_background))
+                    })
+                    .when(!self.popover_menu_handle.is_deployed(), |element| {
+                        element.tooltip(move |_window, cx| {
+                            Tooltip::with_meta(
+                                "Edit Prediction",
+                                Some(&ToggleMenu),
+                                tooltip_meta.clone(),
+                                cx,
+                            )
+                        })
+                    });
+
+                let popover_menu = PopoverMenu::new("local-edit-prediction")
+                    .menu(move |window, cx| {
+                        this.update(cx, |this, cx| this.build_local_context_menu(window, cx))
+                            .ok()
+                    })
+                    .anchor(Corner::BottomRight)
+                    .with_handle(self.popover_menu_handle.clone());
+
+                let popover_menu = if is_refreshing {
+                    popover_menu.trigger(
+                        icon_button.with_animation(
+                            "pulsating-label",
+                            Animation::new(Duration::from_secs(2))
+                                .repeat()
+                                .with_easing(pulsating_between(0.2, 1.0)),
+                            |icon_button, delta| icon_button.alpha(delta),
+                        ),
+                    )
+                } else {
+                    popover_menu.trigger(icon_button)
+                };
+
+                div().child(popover_menu.into_any_element())
+            }
+
             provider @ (EditPredictionProvider::Experimental(_) | EditPredictionProvider::Zed) => {
                 let enabled = self.editor_enabled.unwrap_or(true);
 
@@ -547,6 +608,8 @@ impl EditPredictionButton {
             providers.push(EditPredictionProvider::Codestral);
         }
 
+        providers.push(EditPredictionProvider::Local);
+
         if cx.has_flag::<SweepFeatureFlag>()
             && edit_prediction::sweep_ai::sweep_api_token(cx)
                 .read(cx)
@@ -595,6 +658,7 @@ impl EditPredictionButton {
                     EditPredictionProvider::Copilot => "GitHub Copilot",
                     EditPredictionProvider::Supermaven => "Supermaven",
                     EditPredictionProvider::Codestral => "Codestral",
+                    EditPredictionProvider::Local => "Local Model",
                     EditPredictionProvider::Experimental(
                         EXPERIMENTAL_SWEEP_EDIT_PREDICTION_PROVIDER_NAME,
                     ) => "Sweep",
@@ -985,6 +1049,17 @@ impl EditPredictionButton {
         })
     }
 
+    fn build_local_context_menu(
+        &self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Entity<ContextMenu> {
+        ContextMenu::build(window, cx, |menu, window, cx| {
+            let menu = self.build_language_settings_menu(menu, window, cx);
+            self.add_provider_switching_section(menu, EditPredictionProvider::Local, cx)
+        })
+    }
+
     fn build_edit_prediction_context_menu(
         &self,
         provider: EditPredictionProvider,
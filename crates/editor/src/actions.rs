@@ -372,6 +372,8 @@ actions!(
         #[action(deprecated_aliases = ["editor::AcceptPartialCopilotSuggestion"])]
         AcceptNextWordEditPrediction,
         AcceptNextLineEditPrediction,
+        /// Aligns all selections to the same column by inserting spaces before them.
+        AlignSelections,
         /// Applies all diff hunks in the editor.
         ApplyAllDiffHunks,
         /// Applies the diff hunk at the current position.
@@ -759,6 +761,8 @@ actions!(
         ShowWordCompletions,
         /// Randomly shuffles selected lines.
         ShuffleLines,
+        /// Randomly shuffles the contents of the selections.
+        ShuffleSelections,
         /// Navigates to the next signature in the signature help popup.
         SignatureHelpNext,
         /// Navigates to the previous signature in the signature help popup.
@@ -769,6 +773,8 @@ actions!(
         SortLinesCaseInsensitive,
         /// Sorts selected lines case-sensitively.
         SortLinesCaseSensitive,
+        /// Sorts the contents of the selections.
+        SortSelections,
         /// Stops the language server for the current file.
         StopLanguageServer,
         /// Switches between source and header files.
@@ -787,6 +793,8 @@ actions!(
         EnableBreakpoint,
         /// Edits the log message for a breakpoint.
         EditLogBreakpoint,
+        /// Toggles a bookmark at the current line.
+        ToggleBookmark,
         /// Toggles automatic signature help.
         ToggleAutoSignatureHelp,
         /// Toggles inline git blame display.
@@ -840,6 +848,8 @@ actions!(
         UniqueLinesCaseInsensitive,
         /// Removes duplicate lines (case-sensitive).
         UniqueLinesCaseSensitive,
+        /// Removes duplicate contents of the selections, keeping the first occurrences.
+        UniqueSelections,
         /// Removes the surrounding syntax node (for example brackets, or closures)
         /// from the current selections.
         UnwrapSyntaxNode,
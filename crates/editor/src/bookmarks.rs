@@ -0,0 +1,237 @@
+//! Bookmarks in editors, which are shown in the gutter and persisted for each
+//! worktree of a project.
+
+use std::ops::Range;
+
+use collections::{HashMap, HashSet};
+use gpui::{App, AppContext as _, Context, Entity, Window};
+use multi_buffer::Anchor;
+use project::{
+    Project, Worktree,
+    bookmark_store::{Bookmark, BookmarkStore, BookmarkStoreEvent, SerializedBookmark},
+};
+use text::Bias;
+use ui::{ButtonStyle, IconButton, IconName, IconSize, Tooltip, prelude::*};
+use util::{ResultExt as _, rel_path::RelPath};
+use workspace::Workspace;
+
+use crate::{
+    DisplayPoint, DisplayRow, Editor, ToPoint as _, actions::ToggleBookmark,
+    display_map::ToDisplayPoint as _, persistence::DB,
+};
+
+pub(crate) fn init(cx: &mut App) {
+    cx.observe_new(|workspace: &mut Workspace, _, cx| {
+        let project = workspace.project().clone();
+        if project.read(cx).is_via_collab() {
+            return;
+        }
+
+        let bookmark_store = project.read(cx).bookmark_store();
+        for worktree in project.read(cx).visible_worktrees(cx) {
+            load_bookmarks(&worktree, &bookmark_store, cx);
+        }
+        cx.subscribe(&project, {
+            let bookmark_store = bookmark_store.clone();
+            move |_, project, event, cx| {
+                if let project::Event::WorktreeAdded(worktree_id) = event
+                    && let Some(worktree) = project.read(cx).worktree_for_id(*worktree_id, cx)
+                    && worktree.read(cx).is_visible()
+                {
+                    load_bookmarks(&worktree, &bookmark_store, cx);
+                }
+            }
+        })
+        .detach();
+        cx.subscribe(
+            &bookmark_store,
+            |workspace, bookmark_store, _: &BookmarkStoreEvent, cx| {
+                save_bookmarks(workspace.project(), &bookmark_store, cx);
+            },
+        )
+        .detach();
+    })
+    .detach();
+}
+
+fn load_bookmarks(
+    worktree: &Entity<Worktree>,
+    bookmark_store: &Entity<BookmarkStore>,
+    cx: &mut App,
+) {
+    let worktree_id = worktree.read(cx).id();
+    let worktree_path = worktree.read(cx).abs_path();
+    let Some(bookmarks) = DB.get_bookmarks(worktree_path).log_err() else {
+        return;
+    };
+    if bookmarks.is_empty() {
+        return;
+    }
+
+    let bookmarks = bookmarks
+        .into_iter()
+        .filter_map(|(path, row, label)| {
+            Some(SerializedBookmark {
+                path: RelPath::unix(&path).log_err()?.into_arc(),
+                row,
+                label,
+            })
+        })
+        .collect();
+    bookmark_store
+        .update(cx, |bookmark_store, cx| {
+            bookmark_store.load_serialized_bookmarks(worktree_id, bookmarks, cx)
+        })
+        .detach_and_log_err(cx);
+}
+
+fn save_bookmarks(project: &Entity<Project>, bookmark_store: &Entity<BookmarkStore>, cx: &mut App) {
+    let bookmark_store = bookmark_store.read(cx);
+    let saves = project
+        .read(cx)
+        .visible_worktrees(cx)
+        .filter(|worktree| !bookmark_store.is_loading(worktree.read(cx).id()))
+        .map(|worktree| {
+            let worktree = worktree.read(cx);
+            let bookmarks = bookmark_store
+                .serialized_bookmarks(worktree.id(), cx)
+                .into_iter()
+                .map(|bookmark| {
+                    (
+                        bookmark.path.as_unix_str().to_string(),
+                        bookmark.row,
+                        bookmark.label,
+                    )
+                })
+                .collect();
+            DB.save_bookmarks(worktree.abs_path(), bookmarks)
+        })
+        .collect::<Vec<_>>();
+    cx.background_spawn(async move {
+        for save in saves {
+            save.await.log_err();
+        }
+    })
+    .detach();
+}
+
+impl Editor {
+    pub fn toggle_bookmark(&mut self, _: &ToggleBookmark, _: &mut Window, cx: &mut Context<Self>) {
+        let Some(bookmark_store) = self.bookmark_store.clone() else {
+            return;
+        };
+
+        let multi_buffer = self.buffer.read(cx);
+        let snapshot = multi_buffer.snapshot(cx);
+        let mut rows = HashSet::default();
+        let mut positions = Vec::new();
+        for selection in self.selections.disjoint_anchors_arc().iter() {
+            let head = selection.head();
+            // There might be multiple cursors on the same line, which should
+            // only toggle its bookmark once.
+            if !rows.insert(head.to_point(&snapshot).row) {
+                continue;
+            }
+            if let Some(buffer) = multi_buffer.buffer_for_anchor(head, cx) {
+                positions.push((buffer, head.text_anchor));
+            }
+        }
+
+        bookmark_store.update(cx, |bookmark_store, cx| {
+            for (buffer, position) in positions {
+                bookmark_store.toggle_bookmark(&buffer, position, cx);
+            }
+        });
+    }
+
+    fn toggle_bookmark_at_anchor(&mut self, position: Anchor, cx: &mut Context<Self>) {
+        let Some(bookmark_store) = self.bookmark_store.clone() else {
+            return;
+        };
+        let Some(buffer) = self.buffer.read(cx).buffer_for_anchor(position, cx) else {
+            return;
+        };
+        bookmark_store.update(cx, |bookmark_store, cx| {
+            bookmark_store.toggle_bookmark(&buffer, position.text_anchor, cx);
+        });
+    }
+
+    /// Returns the bookmarks within the given display rows, by the row they
+    /// are shown on.
+    pub(crate) fn active_bookmarks(
+        &self,
+        range: Range<DisplayRow>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> HashMap<DisplayRow, (Anchor, Bookmark)> {
+        let mut bookmark_display_points = HashMap::default();
+        let (Some(bookmark_store), Some(project)) = (self.bookmark_store.clone(), self.project())
+        else {
+            return bookmark_display_points;
+        };
+
+        let snapshot = self.snapshot(window, cx);
+        let multi_buffer_snapshot = snapshot.buffer_snapshot();
+        let range = snapshot.display_point_to_point(DisplayPoint::new(range.start, 0), Bias::Left)
+            ..snapshot.display_point_to_point(DisplayPoint::new(range.end, 0), Bias::Right);
+
+        for (buffer_snapshot, range, excerpt_id) in
+            multi_buffer_snapshot.range_to_buffer_ranges(range)
+        {
+            let Some(buffer) = project
+                .read(cx)
+                .buffer_for_id(buffer_snapshot.remote_id(), cx)
+            else {
+                continue;
+            };
+            let bookmarks = bookmark_store.read(cx).bookmarks(
+                &buffer,
+                buffer_snapshot.anchor_before(range.start)..buffer_snapshot.anchor_after(range.end),
+                buffer_snapshot,
+                cx,
+            );
+            for bookmark in bookmarks {
+                let multi_buffer_anchor = Anchor::in_buffer(excerpt_id, bookmark.position);
+                let position = multi_buffer_anchor
+                    .to_point(multi_buffer_snapshot)
+                    .to_display_point(&snapshot);
+                bookmark_display_points
+                    .insert(position.row(), (multi_buffer_anchor, bookmark.clone()));
+            }
+        }
+
+        bookmark_display_points
+    }
+
+    pub(crate) fn render_bookmark(
+        &self,
+        position: Anchor,
+        row: DisplayRow,
+        bookmark: &Bookmark,
+        cx: &mut Context<Self>,
+    ) -> IconButton {
+        let focus_handle = self.focus_handle.clone();
+        let label = bookmark.label.clone();
+        IconButton::new(("bookmark_indicator", row.0 as usize), IconName::Bookmark)
+            .icon_size(IconSize::XSmall)
+            .size(ui::ButtonSize::None)
+            .icon_color(Color::Accent)
+            .style(ButtonStyle::Transparent)
+            .on_click(cx.listener(move |editor, _, window, cx| {
+                window.focus(&editor.focus_handle(cx), cx);
+                editor.toggle_bookmark_at_anchor(position, cx);
+            }))
+            .tooltip(move |_window, cx| match &label {
+                Some(label) => Tooltip::with_meta_in(
+                    label.clone(),
+                    Some(&ToggleBookmark),
+                    "Click to remove the bookmark.",
+                    &focus_handle,
+                    cx,
+                ),
+                None => {
+                    Tooltip::for_action_in("Remove Bookmark", &ToggleBookmark, &focus_handle, cx)
+                }
+            })
+    }
+}
@@ -13,6 +13,7 @@
 //! If you're looking to improve Vim mode, you should check out Vim crate that wraps Editor and overrides its behavior.
 pub mod actions;
 pub mod blink_manager;
+mod bookmarks;
 mod bracket_colorization;
 mod clangd_ext;
 pub mod code_context_menus;
@@ -145,9 +146,10 @@ use parking_lot::Mutex;
 use persistence::DB;
 use project::{
     BreakpointWithPosition, CodeAction, Completion, CompletionDisplayOptions, CompletionIntent,
-    CompletionResponse, CompletionSource, DisableAiSettings, DocumentHighlight, InlayHint, InlayId,
-    InvalidationStrategy, Location, LocationLink, LspAction, PrepareRenameResponse, Project,
-    ProjectItem, ProjectPath, ProjectTransaction, TaskSourceKind,
+    CompletionResponse, CompletionSource, DisableAiSettings, DocumentHighlight, DocumentLink,
+    InlayHint, InlayId, InvalidationStrategy, Location, LocationLink, LspAction,
+    PrepareRenameResponse, Project, ProjectItem, ProjectPath, ProjectTransaction, TaskSourceKind,
+    bookmark_store::BookmarkStore,
     debugger::{
         breakpoint_store::{
             Breakpoint, BreakpointEditAction, BreakpointSessionState, BreakpointState,
@@ -325,6 +327,7 @@ pub fn init(cx: &mut App) {
     workspace::register_project_item::<Editor>(cx);
     workspace::FollowableViewRegistry::register::<Editor>(cx);
     workspace::register_serializable_item::<Editor>(cx);
+    bookmarks::init(cx);
 
     cx.observe_new(
         |workspace: &mut Workspace, _: Option<&mut Window>, _cx: &mut Context<Workspace>| {
@@ -1103,6 +1106,8 @@ pub struct Editor {
     debounced_selection_highlight_task: Option<(Range<Anchor>, Task<()>)>,
     document_highlights_task: Option<Task<()>>,
     linked_editing_range_task: Option<Task<Option<()>>>,
+    selection_ranges_task: Option<Task<()>>,
+    document_links: HashMap<BufferId, hover_links::DocumentLinks>,
     linked_edit_ranges: linked_editing_ranges::LinkedEditingRanges,
     pending_rename: Option<RenameState>,
     searchable: bool,
@@ -1178,6 +1183,7 @@ pub struct Editor {
     tasks: BTreeMap<(BufferId, BufferRow), RunnableTasks>,
     tasks_update_task: Option<Task<()>>,
     breakpoint_store: Option<Entity<BreakpointStore>>,
+    bookmark_store: Option<Entity<BookmarkStore>>,
     gutter_breakpoint_indicator: (Option<PhantomBreakpointIndicator>, Option<Task<()>>),
     hovered_diff_hunk_row: Option<DisplayRow>,
     pull_diagnostics_task: Task<()>,
@@ -2175,6 +2181,10 @@ impl Editor {
             (EditorMode::Full { .. }, Some(project)) => Some(project.read(cx).breakpoint_store()),
             _ => None,
         };
+        let bookmark_store = match (&mode, project.as_ref()) {
+            (EditorMode::Full { .. }, Some(project)) => Some(project.read(cx).bookmark_store()),
+            _ => None,
+        };
 
         let mut code_action_providers = Vec::new();
         let mut load_uncommitted_diff = None;
@@ -2268,6 +2278,8 @@ impl Editor {
             debounced_selection_highlight_task: None,
             document_highlights_task: None,
             linked_editing_range_task: None,
+            selection_ranges_task: None,
+            document_links: HashMap::default(),
             pending_rename: None,
             searchable: !is_minimap,
             cursor_shape: EditorSettings::get_global(cx)
@@ -2339,6 +2351,7 @@ impl Editor {
             tasks: BTreeMap::default(),
 
             breakpoint_store,
+            bookmark_store,
             gutter_breakpoint_indicator: (None, None),
             hovered_diff_hunk_row: None,
             _subscriptions: (!is_minimap)
@@ -2421,6 +2434,11 @@ impl Editor {
                     cx.notify();
                 }));
         }
+        if let Some(bookmarks) = editor.bookmark_store.as_ref() {
+            editor
+                ._subscriptions
+                .push(cx.observe(bookmarks, |_, _, cx| cx.notify()));
+        }
         editor.tasks_update_task = Some(editor.refresh_runnables(window, cx));
         editor._subscriptions.extend(project_subscriptions);
 
@@ -6145,7 +6163,7 @@ impl Editor {
                 })?;
             if available_commands.contains(&lsp_command.command) {
                 Some(CodeAction {
-                    server_id: *server_id,
+                    server_id: Some(*server_id),
                     range: language::Anchor::MIN..language::Anchor::MIN,
                     lsp_action: LspAction::Command(lsp_command.clone()),
                     resolved: false,
@@ -11604,6 +11622,198 @@ impl Editor {
         });
     }
 
+    pub fn align_selections(
+        &mut self,
+        _: &AlignSelections,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.hide_mouse_cursor(HideMouseCursorOrigin::TypingAction, cx);
+        let display_snapshot = self.display_snapshot(cx);
+        let selections = self.selections.all::<Point>(&display_snapshot);
+        if selections.len() < 2 {
+            return;
+        }
+
+        let buffer = self.buffer.read(cx).snapshot(cx);
+        // The start and character column of each selection, grouped by row.
+        // The first selections of all rows are aligned with each other, then
+        // the second ones, and so on.
+        let mut rows: Vec<Vec<(Point, u32)>> = Vec::new();
+        let mut last_row = None;
+        for selection in &selections {
+            let start = selection.start;
+            let column = buffer
+                .text_for_range(Point::new(start.row, 0)..start)
+                .map(|chunk| chunk.chars().count() as u32)
+                .sum::<u32>();
+            if last_row == Some(start.row)
+                && let Some(row) = rows.last_mut()
+            {
+                row.push((start, column));
+            } else {
+                rows.push(vec![(start, column)]);
+            }
+            last_row = Some(start.row);
+        }
+
+        let mut edits = Vec::new();
+        let mut row_padding = vec![0; rows.len()];
+        let max_selections_per_row = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+        for index in 0..max_selections_per_row {
+            let aligned_column = rows
+                .iter()
+                .zip(&row_padding)
+                .filter_map(|(row, padding)| Some(row.get(index)?.1 + padding))
+                .max()
+                .unwrap_or(0);
+            for (row, padding) in rows.iter().zip(&mut row_padding) {
+                let Some((start, column)) = row.get(index) else {
+                    continue;
+                };
+                let len = aligned_column - (column + *padding);
+                if len > 0 {
+                    edits.push((*start..*start, " ".repeat(len as usize)));
+                    *padding += len;
+                }
+            }
+        }
+        if edits.is_empty() {
+            return;
+        }
+        edits.sort_by_key(|(range, _)| range.start);
+
+        // Anchor the selections after the padding inserted before them.
+        let new_selections = selections
+            .iter()
+            .map(|selection| {
+                let start = buffer.anchor_after(selection.start);
+                let end = if selection.is_empty() {
+                    start
+                } else {
+                    buffer.anchor_before(selection.end)
+                };
+                if selection.reversed {
+                    end..start
+                } else {
+                    start..end
+                }
+            })
+            .collect::<Vec<_>>();
+
+        self.transact(window, cx, |this, window, cx| {
+            this.buffer.update(cx, |buffer, cx| {
+                buffer.edit(edits, None, cx);
+            });
+            this.change_selections(Default::default(), window, cx, |s| {
+                s.select_anchor_ranges(new_selections);
+            });
+        });
+    }
+
+    pub fn sort_selections(
+        &mut self,
+        _: &SortSelections,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.manipulate_selection_contents(window, cx, |contents| {
+            contents.sort_by(|a, b| util::paths::natural_sort(a, b))
+        })
+    }
+
+    pub fn shuffle_selections(
+        &mut self,
+        _: &ShuffleSelections,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.manipulate_selection_contents(window, cx, |contents| {
+            contents.shuffle(&mut rand::rng())
+        })
+    }
+
+    pub fn unique_selections(
+        &mut self,
+        _: &UniqueSelections,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.manipulate_selection_contents(window, cx, |contents| {
+            let len = contents.len();
+            let mut seen = HashSet::default();
+            contents.retain(|content| seen.insert(content.clone()));
+            contents.resize(len, String::new());
+        })
+    }
+
+    fn manipulate_selection_contents<M>(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+        manipulate: M,
+    ) where
+        M: FnOnce(&mut Vec<String>),
+    {
+        self.hide_mouse_cursor(HideMouseCursorOrigin::TypingAction, cx);
+        let (ranges, mut contents): (Vec<_>, Vec<_>) =
+            self.selection_contents(cx).into_iter().unzip();
+        if contents.len() < 2 {
+            return;
+        }
+        manipulate(&mut contents);
+        self.replace_selection_contents(ranges.into_iter().zip(contents).collect(), window, cx);
+    }
+
+    /// Returns the range and text of each selection, in order.
+    pub fn selection_contents(&self, cx: &mut App) -> Vec<(Range<Anchor>, String)> {
+        let display_snapshot = self.display_snapshot(cx);
+        let buffer = display_snapshot.buffer_snapshot();
+        self.selections
+            .all::<MultiBufferOffset>(&display_snapshot)
+            .into_iter()
+            .map(|selection| {
+                let range =
+                    buffer.anchor_before(selection.start)..buffer.anchor_after(selection.end);
+                let text = buffer.text_for_range(selection.range()).collect();
+                (range, text)
+            })
+            .collect()
+    }
+
+    /// Replaces the text of each range with the given text as one transaction,
+    /// and selects the new texts. The ranges must be in order and disjoint.
+    pub fn replace_selection_contents(
+        &mut self,
+        contents: Vec<(Range<Anchor>, String)>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let buffer = self.buffer.read(cx).snapshot(cx);
+        let mut edits = Vec::new();
+        let mut new_selections = Vec::new();
+        let mut selection_adjustment = 0isize;
+        for (range, text) in contents {
+            let range = range.to_offset(&buffer);
+            let start = (range.start.0 as isize + selection_adjustment) as usize;
+            new_selections.push(MultiBufferOffset(start)..MultiBufferOffset(start + text.len()));
+            selection_adjustment += text.len() as isize - (range.end.0 - range.start.0) as isize;
+            if buffer.text_for_range(range.clone()).collect::<String>() != text {
+                edits.push((range, text));
+            }
+        }
+
+        self.transact(window, cx, |this, window, cx| {
+            this.buffer.update(cx, |buffer, cx| {
+                buffer.edit(edits, None, cx);
+            });
+            this.change_selections(Default::default(), window, cx, |s| {
+                s.select_ranges(new_selections);
+            });
+            this.request_autoscroll(Autoscroll::fit(), cx);
+        });
+    }
+
     fn manipulate_lines<M>(
         &mut self,
         window: &mut Window,
@@ -15781,6 +15991,101 @@ impl Editor {
         _: &SelectLargerSyntaxNode,
         window: &mut Window,
         cx: &mut Context<Self>,
+    ) {
+        if let Some(selection_ranges) = self.lsp_selection_ranges(cx) {
+            self.selection_ranges_task = Some(cx.spawn_in(window, async move |editor, cx| {
+                let selection_ranges = selection_ranges.await;
+                editor
+                    .update_in(cx, |editor, window, cx| {
+                        editor.select_larger_range(&selection_ranges, window, cx)
+                    })
+                    .ok();
+            }));
+        } else {
+            self.select_larger_range(&[], window, cx);
+        }
+    }
+
+    /// Requests the ranges that language servers consider to contain each selection, from the
+    /// innermost to the outermost, for selections in languages that prefer them over syntax nodes.
+    fn lsp_selection_ranges(
+        &self,
+        cx: &mut Context<Self>,
+    ) -> Option<Task<Vec<Vec<Range<Anchor>>>>> {
+        let provider = self.semantics_provider.clone()?;
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let selections = self
+            .selections
+            .all::<MultiBufferOffset>(&self.display_snapshot(cx));
+        let mut positions_by_buffer =
+            HashMap::<BufferId, (Entity<Buffer>, Vec<(usize, ExcerptId, text::Anchor)>)>::default();
+        for (ix, selection) in selections.iter().enumerate() {
+            if !snapshot
+                .language_settings_at(selection.start, cx)
+                .prefer_lsp_selection_ranges
+            {
+                continue;
+            }
+            let position = snapshot.anchor_before(selection.start);
+            let Some(buffer) = self.buffer.read(cx).buffer_for_anchor(position, cx) else {
+                continue;
+            };
+            positions_by_buffer
+                .entry(buffer.read(cx).remote_id())
+                .or_insert_with(|| (buffer, Vec::new()))
+                .1
+                .push((ix, position.excerpt_id, position.text_anchor));
+        }
+        if positions_by_buffer.is_empty() {
+            return None;
+        }
+
+        let selection_count = selections.len();
+        let tasks = positions_by_buffer
+            .into_values()
+            .filter_map(|(buffer, positions)| {
+                let task = provider.selection_ranges(
+                    &buffer,
+                    positions.iter().map(|(_, _, position)| *position).collect(),
+                    cx,
+                )?;
+                Some((positions, task))
+            })
+            .collect::<Vec<_>>();
+        Some(cx.spawn(async move |editor, cx| {
+            let mut selection_ranges = vec![Vec::new(); selection_count];
+            for (positions, task) in tasks {
+                let Some(ranges) = task.await.log_err() else {
+                    continue;
+                };
+                editor
+                    .read_with(cx, |editor, cx| {
+                        let snapshot = editor.buffer.read(cx).snapshot(cx);
+                        for ((ix, excerpt_id, _), ranges) in positions.into_iter().zip(ranges) {
+                            selection_ranges[ix] = ranges
+                                .into_iter()
+                                .filter_map(|range| {
+                                    let start =
+                                        snapshot.anchor_in_excerpt(excerpt_id, range.start)?;
+                                    let end = snapshot.anchor_in_excerpt(excerpt_id, range.end)?;
+                                    Some(start..end)
+                                })
+                                .collect();
+                        }
+                    })
+                    .ok();
+            }
+            selection_ranges
+        }))
+    }
+
+    /// Expands each selection to the first of its language server ranges that is larger than it,
+    /// or otherwise to its enclosing syntax node.
+    fn select_larger_range(
+        &mut self,
+        selection_ranges: &[Vec<Range<Anchor>>],
+        window: &mut Window,
+        cx: &mut Context<Self>,
     ) {
         let Some(visible_row_count) = self.visible_row_count() else {
             return;
@@ -15798,12 +16103,41 @@ impl Editor {
         let display_map = self.display_map.update(cx, |map, cx| map.snapshot(cx));
         let buffer = self.buffer.read(cx).snapshot(cx);
 
+        // Ignore the language server's ranges if the selections changed while they were requested.
+        let selection_ranges = if selection_ranges.len() == old_selections.len() {
+            selection_ranges
+        } else {
+            &[]
+        };
+
         let mut selected_larger_node = false;
         let mut new_selections = old_selections
             .iter()
-            .map(|selection| {
+            .enumerate()
+            .map(|(ix, selection)| {
                 let old_range = selection.start..selection.end;
 
+                if let Some(lsp_range) = selection_ranges
+                    .get(ix)
+                    .into_iter()
+                    .flatten()
+                    .map(|range| range.to_offset(&buffer))
+                    .find(|range| {
+                        range.start <= old_range.start
+                            && range.end >= old_range.end
+                            && *range != old_range
+                    })
+                {
+                    selected_larger_node = true;
+                    return Selection {
+                        id: selection.id,
+                        start: lsp_range.start,
+                        end: lsp_range.end,
+                        goal: SelectionGoal::None,
+                        reversed: selection.reversed,
+                    };
+                }
+
                 if let Some((node, _)) = buffer.syntax_ancestor(old_range.clone()) {
                     // manually select word at selection
                     if ["string_content", "inline"].contains(&node.kind()) {
@@ -21917,6 +22251,7 @@ impl Editor {
                 self.refresh_inlay_hints(InlayHintRefreshReason::ExcerptsRemoved(ids.clone()), cx);
                 for buffer_id in removed_buffer_ids {
                     self.registered_buffers.remove(buffer_id);
+                    self.document_links.remove(buffer_id);
                 }
                 jsx_tag_auto_close::refresh_enabled_in_any_buffer(self, multibuffer, cx);
                 cx.emit(EditorEvent::ExcerptsRemoved {
@@ -24031,6 +24366,26 @@ pub trait SemanticsProvider {
         cx: &mut App,
     ) -> Option<Task<Result<Vec<DocumentHighlight>>>>;
 
+    fn selection_ranges(
+        &self,
+        buffer: &Entity<Buffer>,
+        positions: Vec<text::Anchor>,
+        cx: &mut App,
+    ) -> Option<Task<Result<Vec<Vec<Range<text::Anchor>>>>>>;
+
+    fn document_links(
+        &self,
+        buffer: &Entity<Buffer>,
+        cx: &mut App,
+    ) -> Option<Task<Result<Vec<DocumentLink>>>>;
+
+    fn resolve_document_link(
+        &self,
+        link: DocumentLink,
+        buffer: Entity<Buffer>,
+        cx: &mut App,
+    ) -> Option<Task<Result<DocumentLink>>>;
+
     fn definitions(
         &self,
         buffer: &Entity<Buffer>,
@@ -24521,6 +24876,36 @@ impl SemanticsProvider for Entity<Project> {
         }))
     }
 
+    fn selection_ranges(
+        &self,
+        buffer: &Entity<Buffer>,
+        positions: Vec<text::Anchor>,
+        cx: &mut App,
+    ) -> Option<Task<Result<Vec<Vec<Range<text::Anchor>>>>>> {
+        Some(self.update(cx, |project, cx| {
+            project.selection_ranges(buffer, positions, cx)
+        }))
+    }
+
+    fn document_links(
+        &self,
+        buffer: &Entity<Buffer>,
+        cx: &mut App,
+    ) -> Option<Task<Result<Vec<DocumentLink>>>> {
+        Some(self.update(cx, |project, cx| project.document_links(buffer, cx)))
+    }
+
+    fn resolve_document_link(
+        &self,
+        link: DocumentLink,
+        buffer: Entity<Buffer>,
+        cx: &mut App,
+    ) -> Option<Task<Result<DocumentLink>>> {
+        Some(self.update(cx, |project, cx| {
+            project.resolve_document_link(link, buffer, cx)
+        }))
+    }
+
     fn definitions(
         &self,
         buffer: &Entity<Buffer>,
@@ -24865,6 +25250,7 @@ impl EditorSnapshot {
 
             let show_runnables = self.show_runnables.unwrap_or(gutter_settings.runnables);
             let show_breakpoints = self.show_breakpoints.unwrap_or(gutter_settings.breakpoints);
+            let show_bookmarks = self.bookmark_store.is_some() && gutter_settings.bookmarks;
 
             let git_blame_entries_width =
                 self.git_blame_gutter_max_author_length
@@ -24888,7 +25274,7 @@ impl EditorSnapshot {
             let mut left_padding = git_blame_entries_width.unwrap_or(Pixels::ZERO);
             left_padding += if !is_singleton {
                 ch_width * 4.0
-            } else if show_runnables || show_breakpoints {
+            } else if show_runnables || show_breakpoints || show_bookmarks {
                 ch_width * 3.0
             } else if show_git_gutter && show_line_numbers {
                 ch_width * 2.0
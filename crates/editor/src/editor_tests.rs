@@ -5884,6 +5884,84 @@ async fn test_rotate_selections(cx: &mut TestAppContext) {
     "});
 }
 
+#[gpui::test]
+async fn test_align_selections(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+
+    cx.set_state(indoc! {"
+        aˇ = 1
+        abcˇ = 2
+        abˇ = 3
+    "});
+    cx.update_editor(|e, window, cx| e.align_selections(&AlignSelections, window, cx));
+    cx.assert_editor_state(indoc! {"
+        a  ˇ = 1
+        abcˇ = 2
+        ab ˇ = 3
+    "});
+
+    // Columns are counted in characters, and selections keep their contents
+    cx.set_state(indoc! {"
+        é=«1ˇ»
+        yyy=«2ˇ»
+    "});
+    cx.update_editor(|e, window, cx| e.align_selections(&AlignSelections, window, cx));
+    cx.assert_editor_state(indoc! {"
+        é=  «1ˇ»
+        yyy=«2ˇ»
+    "});
+
+    // The nth selections of each row are aligned with each other
+    cx.set_state(indoc! {"
+        aˇ,b,ˇc
+        aaaˇ,bˇ,c
+    "});
+    cx.update_editor(|e, window, cx| e.align_selections(&AlignSelections, window, cx));
+    cx.assert_editor_state(indoc! {"
+        a  ˇ,b,ˇc
+        aaaˇ,b ˇ,c
+    "});
+}
+
+#[gpui::test]
+async fn test_manipulate_selection_contents(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+
+    // Numbers are sorted by value
+    cx.set_state("[«10ˇ», «9ˇ», «100ˇ»]");
+    cx.update_editor(|e, window, cx| e.sort_selections(&SortSelections, window, cx));
+    cx.assert_editor_state("[«9ˇ», «10ˇ», «100ˇ»]");
+
+    cx.set_state(indoc! {"
+        «banana
+        ˇ»«apple
+        ˇ»«cherry
+        ˇ»"});
+    cx.update_editor(|e, window, cx| e.sort_selections(&SortSelections, window, cx));
+    cx.assert_editor_state(indoc! {"
+        «apple
+        ˇ»«banana
+        ˇ»«cherry
+        ˇ»"});
+
+    cx.set_state("«aˇ» «bˇ» «aˇ» «cˇ» «bˇ»");
+    cx.update_editor(|e, window, cx| e.unique_selections(&UniqueSelections, window, cx));
+    cx.assert_editor_state("«aˇ» «bˇ» «cˇ» ˇ ˇ");
+
+    // Shuffling keeps the contents of the selections
+    cx.set_state("«1ˇ» «2ˇ» «3ˇ»");
+    cx.update_editor(|e, window, cx| e.shuffle_selections(&ShuffleSelections, window, cx));
+    cx.update_editor(|e, _, cx| {
+        let mut contents = e.text(cx).split(' ').map(str::to_owned).collect::<Vec<_>>();
+        contents.sort();
+        assert_eq!(contents, ["1", "2", "3"]);
+    });
+}
+
 #[gpui::test]
 fn test_move_line_up_down(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
@@ -9497,6 +9575,94 @@ async fn test_select_larger_syntax_node_for_cursor_at_symbol(cx: &mut TestAppCon
     });
 }
 
+#[gpui::test]
+async fn test_select_larger_syntax_node_with_lsp_selection_ranges(cx: &mut TestAppContext) {
+    init_test(cx, |settings| {
+        settings.defaults.prefer_lsp_selection_ranges = Some(true);
+    });
+
+    let mut cx = EditorLspTestContext::new_rust(
+        lsp::ServerCapabilities {
+            selection_range_provider: Some(lsp::SelectionRangeProviderCapability::Simple(true)),
+            ..Default::default()
+        },
+        cx,
+    )
+    .await;
+
+    // The server knows about the arguments of a macro, which the syntax tree doesn't.
+    cx.lsp
+        .set_request_handler::<lsp::request::SelectionRangeRequest, _, _>(|params, _| async move {
+            Ok(Some(
+                params
+                    .positions
+                    .into_iter()
+                    .filter(|position| position.line == 1)
+                    .map(|_| lsp::SelectionRange {
+                        range: lsp::Range::new(lsp::Position::new(1, 7), lsp::Position::new(1, 14)),
+                        parent: Some(Box::new(lsp::SelectionRange {
+                            range: lsp::Range::new(
+                                lsp::Position::new(1, 4),
+                                lsp::Position::new(1, 15),
+                            ),
+                            parent: None,
+                        })),
+                    })
+                    .collect(),
+            ))
+        });
+
+    cx.set_state(indoc! {"
+        fn main() {
+            m!(ˇabc def);
+        }
+    "});
+    cx.update_editor(|editor, window, cx| {
+        editor.select_larger_syntax_node(&SelectLargerSyntaxNode, window, cx);
+    });
+    cx.run_until_parked();
+    cx.assert_editor_state(indoc! {"
+        fn main() {
+            m!(«abc defˇ»);
+        }
+    "});
+
+    cx.update_editor(|editor, window, cx| {
+        editor.select_larger_syntax_node(&SelectLargerSyntaxNode, window, cx);
+    });
+    cx.run_until_parked();
+    cx.assert_editor_state(indoc! {"
+        fn main() {
+            «ˇm!(abc def)»;
+        }
+    "});
+
+    cx.update_editor(|editor, window, cx| {
+        editor.select_smaller_syntax_node(&SelectSmallerSyntaxNode, window, cx);
+    });
+    cx.assert_editor_state(indoc! {"
+        fn main() {
+            m!(«abc defˇ»);
+        }
+    "});
+
+    // Without ranges from the server, syntax nodes are used.
+    cx.set_state(indoc! {"
+        fn ˇmain() {
+            m!(abc def);
+        }
+    "});
+    cx.update_editor(|editor, window, cx| {
+        editor.select_larger_syntax_node(&SelectLargerSyntaxNode, window, cx);
+    });
+    cx.run_until_parked();
+    cx.assert_editor_state(indoc! {"
+        fn «mainˇ»() {
+            m!(abc def);
+        }
+    "});
+}
+
 #[gpui::test]
 async fn test_select_larger_smaller_syntax_node_for_string(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
@@ -24086,6 +24252,77 @@ async fn test_breakpoint_toggling(cx: &mut TestAppContext) {
     assert_breakpoint(&breakpoints, &abs_path, vec![]);
 }
 
+#[gpui::test]
+async fn test_bookmark_toggling(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/a"),
+        json!({
+            "main.rs": "First line\nSecond line\nThird line\nFourth line",
+        }),
+    )
+    .await;
+    let project = Project::test(fs, [path!("/a").as_ref()], cx).await;
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer(path!("/a/main.rs"), cx)
+        })
+        .await
+        .unwrap();
+    let (editor, cx) = cx.add_window_view(|window, cx| {
+        Editor::new(
+            EditorMode::full(),
+            MultiBuffer::build_from_buffer(buffer.clone(), cx),
+            Some(project.clone()),
+            window,
+            cx,
+        )
+    });
+    let bookmark_rows = |cx: &mut VisualTestContext| {
+        project.read_with(cx, |project, cx| {
+            project
+                .bookmark_store()
+                .read(cx)
+                .all_bookmarks(cx)
+                .into_iter()
+                .map(|entry| entry.row)
+                .collect::<Vec<_>>()
+        })
+    };
+
+    // Several cursors on one line toggle a single bookmark.
+    editor.update_in(cx, |editor, window, cx| {
+        editor.change_selections(SelectionEffects::no_scroll(), window, cx, |s| {
+            s.select_ranges([
+                Point::new(1, 0)..Point::new(1, 0),
+                Point::new(1, 3)..Point::new(1, 3),
+                Point::new(3, 2)..Point::new(3, 2),
+            ])
+        });
+        editor.toggle_bookmark(&actions::ToggleBookmark, window, cx);
+    });
+    assert_eq!(bookmark_rows(cx), [1, 3]);
+
+    // Bookmarks move with the lines they are on.
+    editor.update_in(cx, |editor, window, cx| {
+        editor.change_selections(SelectionEffects::no_scroll(), window, cx, |s| {
+            s.select_ranges([Point::new(0, 0)..Point::new(0, 0)])
+        });
+        editor.newline(&Newline, window, cx);
+    });
+    assert_eq!(bookmark_rows(cx), [2, 4]);
+
+    editor.update_in(cx, |editor, window, cx| {
+        editor.change_selections(SelectionEffects::no_scroll(), window, cx, |s| {
+            s.select_ranges([Point::new(2, 5)..Point::new(2, 5)])
+        });
+        editor.toggle_bookmark(&actions::ToggleBookmark, window, cx);
+    });
+    assert_eq!(bookmark_rows(cx), [4]);
+}
+
 #[gpui::test]
 async fn test_log_breakpoint_editing(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
@@ -61,6 +61,7 @@ use multi_buffer::{
 use edit_prediction_types::EditPredictionGranularity;
 use project::{
     Entry, ProjectPath,
+    bookmark_store::Bookmark,
     debugger::breakpoint_store::{Breakpoint, BreakpointSessionState},
     project_settings::ProjectSettings,
 };
@@ -251,6 +252,10 @@ impl EditorElement {
         register_action(editor, window, Editor::shuffle_lines);
         register_action(editor, window, Editor::rotate_selections_forward);
         register_action(editor, window, Editor::rotate_selections_backward);
+        register_action(editor, window, Editor::align_selections);
+        register_action(editor, window, Editor::sort_selections);
+        register_action(editor, window, Editor::shuffle_selections);
+        register_action(editor, window, Editor::unique_selections);
         register_action(editor, window, Editor::convert_indentation_to_spaces);
         register_action(editor, window, Editor::convert_indentation_to_tabs);
         register_action(editor, window, Editor::convert_to_upper_case);
@@ -613,6 +618,7 @@ impl EditorElement {
         register_action(editor, window, Editor::open_selections_in_multibuffer);
         register_action(editor, window, Editor::toggle_breakpoint);
         register_action(editor, window, Editor::edit_log_breakpoint);
+        register_action(editor, window, Editor::toggle_bookmark);
         register_action(editor, window, Editor::enable_breakpoint);
         register_action(editor, window, Editor::disable_breakpoint);
         if editor.read(cx).enable_wrap_selections_in_tag(cx) {
@@ -3021,6 +3027,61 @@ impl EditorElement {
         })
     }
 
+    fn layout_bookmarks(
+        &self,
+        line_height: Pixels,
+        range: Range<DisplayRow>,
+        scroll_position: gpui::Point<ScrollOffset>,
+        gutter_dimensions: &GutterDimensions,
+        gutter_hitbox: &Hitbox,
+        display_hunks: &[(DisplayDiffHunk, Option<Hitbox>)],
+        snapshot: &EditorSnapshot,
+        bookmarks: HashMap<DisplayRow, (Anchor, Bookmark)>,
+        row_infos: &[RowInfo],
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Vec<AnyElement> {
+        self.editor.update(cx, |editor, cx| {
+            bookmarks
+                .into_iter()
+                .filter_map(|(display_row, (position, bookmark))| {
+                    if !range.contains(&display_row)
+                        || row_infos
+                            .get((display_row - range.start).0 as usize)
+                            .is_some_and(|row_info| {
+                                row_info.expand_info.is_some()
+                                    || row_info
+                                        .diff_status
+                                        .is_some_and(|status| status.is_deleted())
+                            })
+                    {
+                        return None;
+                    }
+
+                    let row =
+                        MultiBufferRow(DisplayPoint::new(display_row, 0).to_point(snapshot).row);
+                    if snapshot.is_line_folded(row) {
+                        return None;
+                    }
+
+                    let button = editor.render_bookmark(position, display_row, &bookmark, cx);
+                    let button = prepaint_gutter_button(
+                        button,
+                        display_row,
+                        line_height,
+                        gutter_dimensions,
+                        scroll_position,
+                        gutter_hitbox,
+                        display_hunks,
+                        window,
+                        cx,
+                    );
+                    Some(button)
+                })
+                .collect_vec()
+        })
+    }
+
     #[allow(clippy::too_many_arguments)]
     fn layout_run_indicators(
         &self,
@@ -3033,6 +3094,7 @@ impl EditorElement {
         display_hunks: &[(DisplayDiffHunk, Option<Hitbox>)],
         snapshot: &EditorSnapshot,
         breakpoints: &mut HashMap<DisplayRow, (Anchor, Breakpoint, Option<BreakpointSessionState>)>,
+        bookmarks: &mut HashMap<DisplayRow, (Anchor, Bookmark)>,
         window: &mut Window,
         cx: &mut App,
     ) -> Vec<AnyElement> {
@@ -3107,6 +3169,7 @@ impl EditorElement {
                         return None;
                     }
 
+                    bookmarks.remove(&display_row);
                     let button = editor.render_run_indicator(
                         &self.style,
                         Some(display_row) == active_task_indicator_row,
@@ -6440,6 +6503,10 @@ impl EditorElement {
                 breakpoint.paint(window, cx);
             }
 
+            for bookmark in layout.bookmarks.iter_mut() {
+                bookmark.paint(window, cx);
+            }
+
             for test_indicator in layout.test_indicators.iter_mut() {
                 test_indicator.paint(window, cx);
             }
@@ -9438,6 +9505,15 @@ impl Element for EditorElement {
                             active_rows.entry(*display_row).or_default().breakpoint = true;
                         }
                     }
+                    let mut bookmark_rows = if EditorSettings::get_global(cx).gutter.bookmarks {
+                        self.editor.update(cx, |editor, cx| {
+                            editor.active_bookmarks(start_row..end_row, window, cx)
+                        })
+                    } else {
+                        HashMap::default()
+                    };
+                    bookmark_rows
+                        .retain(|display_row, _| !breakpoint_rows.contains_key(display_row));
 
                     let line_numbers = self.layout_line_numbers(
                         Some(&gutter_hitbox),
@@ -9457,10 +9533,14 @@ impl Element for EditorElement {
                     // line numbers so we don't paint a line number debug accent color if a user
                     // has their mouse over that line when a breakpoint isn't there
                     self.editor.update(cx, |editor, _| {
+                        // Bookmarks are shown instead of the indicator, so they can be clicked.
                         if let Some(phantom_breakpoint) = &mut editor
                             .gutter_breakpoint_indicator
                             .0
-                            .filter(|phantom_breakpoint| phantom_breakpoint.is_active)
+                            .filter(|phantom_breakpoint| {
+                                phantom_breakpoint.is_active
+                                    && !bookmark_rows.contains_key(&phantom_breakpoint.display_row)
+                            })
                         {
                             // Is there a non-phantom breakpoint on this line?
                             phantom_breakpoint.collides_with_existing_breakpoint = true;
@@ -10026,6 +10106,7 @@ impl Element for EditorElement {
                             &display_hunks,
                             &snapshot,
                             &mut breakpoint_rows,
+                            &mut bookmark_rows,
                             window,
                             cx,
                         )
@@ -10053,6 +10134,19 @@ impl Element for EditorElement {
                     } else {
                         Vec::new()
                     };
+                    let bookmarks = self.layout_bookmarks(
+                        line_height,
+                        start_row..end_row,
+                        scroll_position,
+                        &gutter_dimensions,
+                        &gutter_hitbox,
+                        &display_hunks,
+                        &snapshot,
+                        bookmark_rows,
+                        &row_infos,
+                        window,
+                        cx,
+                    );
 
                     self.layout_signature_help(
                         &hitbox,
@@ -10249,6 +10343,7 @@ impl Element for EditorElement {
                         mouse_context_menu,
                         test_indicators,
                         breakpoints,
+                        bookmarks,
                         crease_toggles,
                         crease_trailers,
                         tab_invisible,
@@ -10425,6 +10520,7 @@ pub struct EditorLayout {
     selections: Vec<(PlayerColor, Vec<SelectionLayout>)>,
     test_indicators: Vec<AnyElement>,
     breakpoints: Vec<AnyElement>,
+    bookmarks: Vec<AnyElement>,
     crease_toggles: Vec<Option<AnyElement>>,
     expand_toggles: Vec<Option<(AnyElement, gpui::Point<Pixels>)>>,
     diff_hunk_controls: Vec<AnyElement>,
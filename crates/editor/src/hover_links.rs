@@ -1,18 +1,20 @@
 use crate::{
     Anchor, Editor, EditorSettings, EditorSnapshot, FindAllReferences, GoToDefinition,
     GoToDefinitionSplit, GoToTypeDefinition, GoToTypeDefinitionSplit, GotoDefinitionKind,
-    Navigated, PointForPosition, SelectPhase, editor_settings::GoToDefinitionFallback,
-    scroll::ScrollAmount,
+    Navigated, PointForPosition, SelectPhase, SemanticsProvider,
+    editor_settings::GoToDefinitionFallback, scroll::ScrollAmount,
 };
-use gpui::{App, AsyncWindowContext, Context, Entity, Modifiers, Task, Window, px};
+use collections::HashMap;
+use futures::{FutureExt as _, future::Shared};
+use gpui::{App, AsyncWindowContext, Context, Entity, Modifiers, Task, WeakEntity, Window, px};
 use language::{Bias, ToOffset};
 use linkify::{LinkFinder, LinkKind};
 use lsp::LanguageServerId;
-use project::{InlayId, LocationLink, Project, ResolvedPath};
+use project::{DocumentLink, InlayId, LocationLink, Project, ResolvedPath};
 use regex::Regex;
 use settings::Settings;
-use std::{ops::Range, sync::LazyLock};
-use text::OffsetRangeExt;
+use std::{ops::Range, rc::Rc, sync::Arc, sync::LazyLock};
+use text::{BufferId, OffsetRangeExt};
 use theme::ActiveTheme as _;
 use util::{ResultExt, TryFutureExt as _, maybe};
 
@@ -75,6 +77,15 @@ pub struct InlayHighlight {
     pub range: Range<usize>,
 }
 
+/// The document links of a buffer, as requested from its language server
+/// for a version of the buffer.
+pub(crate) struct DocumentLinks {
+    version: clock::Global,
+    links: Shared<Task<Arc<[DocumentLink]>>>,
+    /// The links that were resolved since they were requested, by their range.
+    resolved: HashMap<Range<text::Anchor>, DocumentLink>,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum TriggerPoint {
     Text(Anchor),
@@ -147,6 +158,71 @@ impl Editor {
         }
     }
 
+    /// Returns the document links of the buffer, requesting them if the buffer changed since
+    /// they were last requested.
+    fn document_links(
+        &mut self,
+        buffer: &Entity<language::Buffer>,
+        cx: &mut Context<Self>,
+    ) -> Option<Shared<Task<Arc<[DocumentLink]>>>> {
+        if self.ignore_lsp_data() {
+            return None;
+        }
+        let provider = self.semantics_provider.clone()?;
+        let (buffer_id, version) = {
+            let buffer = buffer.read(cx);
+            (buffer.remote_id(), buffer.version())
+        };
+        if let Some(document_links) = self.document_links.get(&buffer_id)
+            && document_links.version == version
+        {
+            return Some(document_links.links.clone());
+        }
+
+        let links = provider.document_links(buffer, cx)?;
+        let links = cx
+            .background_spawn(async move { links.await.log_err().unwrap_or_default().into() })
+            .shared();
+        self.document_links.insert(
+            buffer_id,
+            DocumentLinks {
+                version,
+                links: links.clone(),
+                resolved: HashMap::default(),
+            },
+        );
+        Some(links)
+    }
+
+    /// Returns the resolved document link at the given range, if it was resolved for this
+    /// version of the buffer.
+    fn resolved_document_link(
+        &self,
+        buffer_id: BufferId,
+        version: &clock::Global,
+        range: &Range<text::Anchor>,
+    ) -> Option<DocumentLink> {
+        let document_links = self.document_links.get(&buffer_id)?;
+        if &document_links.version != version {
+            return None;
+        }
+        document_links.resolved.get(range).cloned()
+    }
+
+    fn cache_resolved_document_link(
+        &mut self,
+        buffer_id: BufferId,
+        version: &clock::Global,
+        range: Range<text::Anchor>,
+        link: DocumentLink,
+    ) {
+        if let Some(document_links) = self.document_links.get_mut(&buffer_id)
+            && &document_links.version == version
+        {
+            document_links.resolved.insert(range, link);
+        }
+    }
+
     pub(crate) fn hide_hovered_link(&mut self, cx: &mut Context<Self>) {
         self.hovered_link_state.take();
         self.clear_highlights::<HoveredLinkState>(cx);
@@ -346,13 +422,34 @@ pub fn show_link_definition(
     }
     let project = editor.project.clone();
     let provider = editor.semantics_provider.clone();
+    let document_links = match trigger_point {
+        TriggerPoint::Text(_) => editor.document_links(&buffer, cx),
+        TriggerPoint::InlayHint(..) => None,
+    };
 
     let snapshot = snapshot.buffer_snapshot().clone();
     hovered_link_state.task = Some(cx.spawn_in(window, async move |this, cx| {
         async move {
             let result = match &trigger_point {
                 TriggerPoint::Text(_) => {
-                    if let Some((url_range, url)) = find_url(&buffer, text_anchor, cx.clone()) {
+                    if let Some((link_range, link)) = find_document_link(
+                        this.clone(),
+                        document_links,
+                        &buffer,
+                        text_anchor,
+                        provider.clone(),
+                        project.clone(),
+                        cx,
+                    )
+                    .await
+                    {
+                        let range = snapshot
+                            .anchor_range_in_excerpt(excerpt_id, link_range)
+                            .map(RangeInEditor::Text);
+                        Some((range, vec![link]))
+                    } else if let Some((url_range, url)) =
+                        find_url(&buffer, text_anchor, cx.clone())
+                    {
                         this.read_with(cx, |_, _| {
                             let range = maybe!({
                                 let range =
@@ -468,6 +565,73 @@ pub fn show_link_definition(
     editor.hovered_link_state = Some(hovered_link_state);
 }
 
+/// Finds the document link at the position, resolving its target if the
+/// language server didn't provide it upfront.
+async fn find_document_link(
+    editor: WeakEntity<Editor>,
+    document_links: Option<Shared<Task<Arc<[DocumentLink]>>>>,
+    buffer: &Entity<language::Buffer>,
+    position: text::Anchor,
+    provider: Option<Rc<dyn SemanticsProvider>>,
+    project: Option<Entity<Project>>,
+    cx: &mut AsyncWindowContext,
+) -> Option<(Range<text::Anchor>, HoverLink)> {
+    let snapshot = buffer.read_with(cx, |buffer, _| buffer.snapshot()).ok()?;
+    let document_links = document_links?.await;
+    let mut link = document_links
+        .iter()
+        .find(|link| {
+            link.range.start.cmp(&position, &snapshot).is_le()
+                && link.range.end.cmp(&position, &snapshot).is_ge()
+        })?
+        .clone();
+    if link.target().is_none() {
+        let buffer_id = snapshot.remote_id();
+        let range = link.range.clone();
+        let resolved = editor
+            .read_with(cx, |editor, _| {
+                editor.resolved_document_link(buffer_id, snapshot.version(), &range)
+            })
+            .ok()
+            .flatten();
+        link = match resolved {
+            Some(resolved) => resolved,
+            None => {
+                let resolve = cx
+                    .update(|_, cx| provider?.resolve_document_link(link, buffer.clone(), cx))
+                    .ok()??;
+                let resolved = resolve.await.log_err()?;
+                editor
+                    .update(cx, |editor, _| {
+                        editor.cache_resolved_document_link(
+                            buffer_id,
+                            snapshot.version(),
+                            range,
+                            resolved.clone(),
+                        )
+                    })
+                    .ok();
+                resolved
+            }
+        };
+    }
+
+    let target = link.target()?;
+    let hover_link = if target.scheme() == "file" {
+        let path = target.to_file_path().ok()?;
+        let resolved_path = project?
+            .update(cx, |project, cx| {
+                project.resolve_abs_path(&path.to_string_lossy(), cx)
+            })
+            .ok()?
+            .await?;
+        HoverLink::File(resolved_path)
+    } else {
+        HoverLink::Url(target.to_string())
+    };
+    Some((link.range, hover_link))
+}
+
 pub(crate) fn find_url(
     buffer: &Entity<language::Buffer>,
     position: text::Anchor,
@@ -780,6 +944,10 @@ mod tests {
     use lsp::request::{GotoDefinition, GotoTypeDefinition};
     use multi_buffer::MultiBufferOffset;
     use settings::InlayHintSettingsContent;
+    use std::{
+        str::FromStr,
+        sync::atomic::{self, AtomicUsize},
+    };
     use util::{assert_set_eq, path};
     use workspace::item::Item;
 
@@ -1306,6 +1474,85 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    async fn test_document_links(cx: &mut gpui::TestAppContext) {
+        init_test(cx, |_| {});
+        let mut cx = EditorLspTestContext::new_rust(
+            lsp::ServerCapabilities {
+                document_link_provider: Some(lsp::DocumentLinkOptions {
+                    resolve_provider: Some(true),
+                    work_done_progress_options: Default::default(),
+                }),
+                ..Default::default()
+            },
+            cx,
+        )
+        .await;
+
+        cx.set_state(indoc! {"
+            use serde::Deserialize;ˇ
+        "});
+        let link_range = cx.lsp_range(indoc! {"
+            use «serde»::Deserialize;
+        "});
+        let mut link_requests = cx.set_request_handler::<lsp::request::DocumentLinkRequest, _, _>(
+            move |_, _, _| async move {
+                Ok(Some(vec![lsp::DocumentLink {
+                    range: link_range,
+                    target: None,
+                    tooltip: None,
+                    data: None,
+                }]))
+            },
+        );
+        let resolve_count = Arc::new(AtomicUsize::new(0));
+        let mut resolve_requests = cx
+            .lsp
+            .set_request_handler::<lsp::request::DocumentLinkResolve, _, _>({
+                let resolve_count = resolve_count.clone();
+                move |link, _| {
+                    resolve_count.fetch_add(1, atomic::Ordering::SeqCst);
+                    async move {
+                        Ok(lsp::DocumentLink {
+                            target: Some(lsp::Uri::from_str("https://docs.rs/serde").unwrap()),
+                            ..link
+                        })
+                    }
+                }
+            });
+
+        let screen_coord = cx.pixel_position(indoc! {"
+            use serˇde::Deserialize;
+        "});
+        cx.simulate_mouse_move(screen_coord, None, Modifiers::secondary_key());
+        link_requests.next().await;
+        resolve_requests.next().await;
+        cx.run_until_parked();
+        cx.assert_editor_text_highlights::<HoveredLinkState>(indoc! {"
+            use «serdeˇ»::Deserialize;
+        "});
+
+        cx.simulate_click(screen_coord, Modifiers::secondary_key());
+        assert_eq!(cx.opened_url(), Some("https://docs.rs/serde".into()));
+        assert_eq!(resolve_count.load(atomic::Ordering::SeqCst), 1);
+
+        // Hovering the link again reuses its resolved target.
+        let other_coord = cx.pixel_position(indoc! {"
+            use serde::Deserˇialize;
+        "});
+        cx.simulate_mouse_move(other_coord, None, Modifiers::none());
+        cx.run_until_parked();
+        cx.assert_editor_text_highlights::<HoveredLinkState>(indoc! {"
+            use serde::Deserialize;
+        "});
+        cx.simulate_mouse_move(screen_coord, None, Modifiers::secondary_key());
+        cx.run_until_parked();
+        cx.assert_editor_text_highlights::<HoveredLinkState>(indoc! {"
+            use «serdeˇ»::Deserialize;
+        "});
+        assert_eq!(resolve_count.load(atomic::Ordering::SeqCst), 1);
+    }
+
     #[gpui::test]
     async fn test_urls_at_beginning_of_buffer(cx: &mut gpui::TestAppContext) {
         init_test(cx, |_| {});
@@ -1,4 +1,4 @@
-use anyhow::Result;
+use anyhow::{Context as _, Result};
 use db::{
     query,
     sqlez::{
@@ -10,7 +10,10 @@ use db::{
 };
 use fs::MTime;
 use itertools::Itertools as _;
-use std::path::PathBuf;
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
 use workspace::{ItemId, WorkspaceDb, WorkspaceId};
 
@@ -121,6 +124,13 @@ impl Domain for EditorDb {
     //   start: usize,
     //   end: usize,
     // )
+    //
+    // bookmarks(
+    //   worktree_path: PathBuf,
+    //   path: String,
+    //   row: u32,
+    //   label: Option<String>,
+    // )
 
     const MIGRATIONS: &[&str] = &[
         sql! (
@@ -197,6 +207,15 @@ impl Domain for EditorDb {
                 ON DELETE CASCADE
             ) STRICT;
         ),
+        sql! (
+            CREATE TABLE bookmarks (
+                worktree_path BLOB NOT NULL,
+                path TEXT NOT NULL,
+                row INTEGER NOT NULL,
+                label TEXT,
+                PRIMARY KEY(worktree_path, path, row)
+            ) STRICT;
+        ),
     ];
 }
 
@@ -281,6 +300,38 @@ impl EditorDb {
         }
     }
 
+    // Returns the path within the worktree, row and label of each bookmark
+    query! {
+        pub fn get_bookmarks(worktree_path: Arc<Path>) -> Result<Vec<(String, u32, Option<String>)>> {
+            SELECT path, row, label
+            FROM bookmarks
+            WHERE worktree_path = ?
+            ORDER BY path, row
+        }
+    }
+
+    pub async fn save_bookmarks(
+        &self,
+        worktree_path: Arc<Path>,
+        bookmarks: Vec<(String, u32, Option<String>)>,
+    ) -> Result<()> {
+        log::debug!("Saving bookmarks for worktree {worktree_path:?}");
+        self.write(move |conn| {
+            conn.exec_bound(sql!(
+                DELETE FROM bookmarks WHERE worktree_path = ?1;
+            ))?(worktree_path.clone())
+            .context("Clearing old bookmarks")?;
+            for (path, row, label) in bookmarks {
+                conn.exec_bound(sql!(
+                    INSERT OR IGNORE INTO bookmarks (worktree_path, path, row, label)
+                    VALUES (?1, ?2, ?3, ?4);
+                ))?((worktree_path.clone(), path, row, label))?;
+            }
+            Ok(())
+        })
+        .await
+    }
+
     pub async fn save_editor_selections(
         &self,
         editor_id: ItemId,
@@ -465,4 +516,40 @@ mod tests {
             .unwrap();
         assert_eq!(have, serialized_editor);
     }
+
+    #[gpui::test]
+    async fn test_save_and_get_bookmarks() {
+        let worktree_path: Arc<Path> = Path::new("/bookmarks-test").into();
+        let other_worktree_path: Arc<Path> = Path::new("/other-bookmarks-test").into();
+        let bookmarks = vec![
+            ("src/main.rs".to_string(), 3, None),
+            ("README.md".to_string(), 0, Some("intro".to_string())),
+        ];
+
+        DB.save_bookmarks(worktree_path.clone(), bookmarks)
+            .await
+            .unwrap();
+        DB.save_bookmarks(
+            other_worktree_path.clone(),
+            vec![("lib.rs".to_string(), 1, None)],
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            DB.get_bookmarks(worktree_path.clone()).unwrap(),
+            [
+                ("README.md".to_string(), 0, Some("intro".to_string())),
+                ("src/main.rs".to_string(), 3, None),
+            ]
+        );
+
+        DB.save_bookmarks(worktree_path.clone(), Vec::new())
+            .await
+            .unwrap();
+        assert_eq!(DB.get_bookmarks(worktree_path).unwrap(), []);
+        assert_eq!(
+            DB.get_bookmarks(other_worktree_path).unwrap(),
+            [("lib.rs".to_string(), 1, None)]
+        );
+    }
 }
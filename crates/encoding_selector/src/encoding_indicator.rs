@@ -0,0 +1,89 @@
+use editor::Editor;
+use gpui::{Entity, SharedString, Subscription, WeakEntity};
+use language::Buffer;
+use ui::{Tooltip, prelude::*};
+use workspace::{StatusBarSettings, StatusItemView, item::ItemHandle, item::Settings};
+
+use crate::{EncodingSelector, Toggle, encoding_label};
+
+#[derive(Default)]
+pub struct EncodingIndicator {
+    encoding: Option<SharedString>,
+    active_editor: Option<WeakEntity<Editor>>,
+    active_buffer: Option<WeakEntity<Buffer>>,
+    _observe_active_editor: Option<Subscription>,
+    /// Encodings can change without the editor changing, when collaborators
+    /// change them.
+    _observe_active_buffer: Option<Subscription>,
+}
+
+impl EncodingIndicator {
+    fn update(&mut self, editor: Entity<Editor>, window: &mut Window, cx: &mut Context<Self>) {
+        self.encoding = None;
+        self.active_editor = None;
+
+        if let Some((_, buffer, _)) = editor.read(cx).active_excerpt(cx) {
+            let is_same_buffer = self
+                .active_buffer
+                .as_ref()
+                .is_some_and(|active_buffer| active_buffer == &buffer.downgrade());
+            if !is_same_buffer {
+                self.active_buffer = Some(buffer.downgrade());
+                let editor = editor.clone();
+                self._observe_active_buffer =
+                    Some(cx.observe_in(&buffer, window, move |this, _, window, cx| {
+                        this.update(editor.clone(), window, cx)
+                    }));
+            }
+            let buffer = buffer.read(cx);
+            self.encoding = Some(encoding_label(buffer.encoding(), buffer.has_bom()));
+            self.active_editor = Some(editor.downgrade());
+        } else {
+            self.active_buffer = None;
+            self._observe_active_buffer = None;
+        }
+
+        cx.notify();
+    }
+}
+
+impl Render for EncodingIndicator {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        if !StatusBarSettings::get_global(cx).encoding_button {
+            return div();
+        }
+
+        div().when_some(self.encoding.clone(), |el, encoding| {
+            el.child(
+                Button::new("change-encoding", encoding)
+                    .label_size(LabelSize::Small)
+                    .on_click(cx.listener(|this, _, window, cx| {
+                        if let Some(editor) = this.active_editor.as_ref() {
+                            EncodingSelector::toggle(editor, None, window, cx);
+                        }
+                    }))
+                    .tooltip(|_window, cx| Tooltip::for_action("Select Encoding", &Toggle, cx)),
+            )
+        })
+    }
+}
+
+impl StatusItemView for EncodingIndicator {
+    fn set_active_pane_item(
+        &mut self,
+        active_pane_item: Option<&dyn ItemHandle>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(editor) = active_pane_item.and_then(|item| item.downcast::<Editor>()) {
+            self._observe_active_editor = Some(cx.observe_in(&editor, window, Self::update));
+            self.update(editor, window, cx);
+        } else {
+            self.encoding = None;
+            self.active_buffer = None;
+            self._observe_active_editor = None;
+            self._observe_active_buffer = None;
+        }
+        cx.notify();
+    }
+}
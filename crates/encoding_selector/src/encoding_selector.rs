@@ -0,0 +1,561 @@
+mod encoding_indicator;
+
+use collections::HashSet;
+use editor::Editor;
+pub use encoding_indicator::EncodingIndicator;
+use encoding_rs::Encoding;
+use fuzzy::{StringMatch, StringMatchCandidate, match_strings};
+use gpui::{
+    DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, SharedString, Task, WeakEntity,
+    actions,
+};
+use language::Buffer;
+use picker::{Picker, PickerDelegate};
+use project::Project;
+use std::sync::Arc;
+use ui::{HighlightedLabel, ListItem, ListItemSpacing, prelude::*};
+use util::ResultExt;
+use workspace::{ModalView, Workspace};
+
+actions!(
+    encoding_selector,
+    [
+        /// Toggles the encoding selector modal, for reopening or saving the
+        /// file with another character encoding.
+        Toggle,
+        /// Reopens the file with another character encoding.
+        ReopenWithEncoding,
+        /// Saves the file with another character encoding.
+        SaveWithEncoding
+    ]
+);
+
+pub fn init(cx: &mut App) {
+    cx.observe_new(EncodingSelector::register).detach();
+}
+
+/// Returns the name of a character encoding as shown to users.
+pub fn encoding_label(encoding: &'static Encoding, has_bom: bool) -> SharedString {
+    if encoding == encoding_rs::UTF_8 && has_bom {
+        "UTF-8 with BOM".into()
+    } else if encoding == encoding_rs::UTF_16LE {
+        "UTF-16 LE".into()
+    } else if encoding == encoding_rs::UTF_16BE {
+        "UTF-16 BE".into()
+    } else {
+        encoding.name().into()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum EncodingAction {
+    Reopen,
+    Save,
+}
+
+impl EncodingAction {
+    fn label(&self) -> &'static str {
+        match self {
+            EncodingAction::Reopen => "Reopen with Encoding",
+            EncodingAction::Save => "Save with Encoding",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct EncodingOption {
+    encoding: &'static Encoding,
+    has_bom: bool,
+    description: &'static str,
+}
+
+impl EncodingOption {
+    fn new(encoding: &'static Encoding, description: &'static str) -> Self {
+        Self {
+            encoding,
+            has_bom: false,
+            description,
+        }
+    }
+
+    fn label(&self) -> String {
+        let name = encoding_label(self.encoding, self.has_bom);
+        if self.description.is_empty() {
+            name.to_string()
+        } else {
+            format!("{} ({name})", self.description)
+        }
+    }
+}
+
+/// Returns the encodings that a file can be reopened or saved with, starting
+/// with the Unicode ones.
+fn encoding_options(action: EncodingAction) -> Vec<EncodingOption> {
+    let mut options = vec![EncodingOption::new(encoding_rs::UTF_8, "")];
+    // A byte order mark is kept when a file is reopened, so it's only chosen when saving.
+    if action == EncodingAction::Save {
+        options.push(EncodingOption {
+            has_bom: true,
+            ..EncodingOption::new(encoding_rs::UTF_8, "")
+        });
+    }
+    options.extend([
+        EncodingOption {
+            has_bom: action == EncodingAction::Save,
+            ..EncodingOption::new(encoding_rs::UTF_16LE, "")
+        },
+        EncodingOption {
+            has_bom: action == EncodingAction::Save,
+            ..EncodingOption::new(encoding_rs::UTF_16BE, "")
+        },
+        EncodingOption::new(encoding_rs::WINDOWS_1252, "Western"),
+        EncodingOption::new(encoding_rs::ISO_8859_15, "Western"),
+        EncodingOption::new(encoding_rs::MACINTOSH, "Western"),
+        EncodingOption::new(encoding_rs::WINDOWS_1250, "Central European"),
+        EncodingOption::new(encoding_rs::ISO_8859_2, "Central European"),
+        EncodingOption::new(encoding_rs::ISO_8859_3, "South European"),
+        EncodingOption::new(encoding_rs::ISO_8859_16, "Southeast European"),
+        EncodingOption::new(encoding_rs::WINDOWS_1257, "Baltic"),
+        EncodingOption::new(encoding_rs::ISO_8859_4, "Baltic"),
+        EncodingOption::new(encoding_rs::ISO_8859_13, "Baltic"),
+        EncodingOption::new(encoding_rs::ISO_8859_10, "Nordic"),
+        EncodingOption::new(encoding_rs::ISO_8859_14, "Celtic"),
+        EncodingOption::new(encoding_rs::WINDOWS_1251, "Cyrillic"),
+        EncodingOption::new(encoding_rs::ISO_8859_5, "Cyrillic"),
+        EncodingOption::new(encoding_rs::KOI8_R, "Cyrillic"),
+        EncodingOption::new(encoding_rs::KOI8_U, "Cyrillic"),
+        EncodingOption::new(encoding_rs::IBM866, "Cyrillic"),
+        EncodingOption::new(encoding_rs::WINDOWS_1253, "Greek"),
+        EncodingOption::new(encoding_rs::ISO_8859_7, "Greek"),
+        EncodingOption::new(encoding_rs::WINDOWS_1254, "Turkish"),
+        EncodingOption::new(encoding_rs::WINDOWS_1255, "Hebrew"),
+        EncodingOption::new(encoding_rs::ISO_8859_8, "Hebrew"),
+        EncodingOption::new(encoding_rs::WINDOWS_1256, "Arabic"),
+        EncodingOption::new(encoding_rs::ISO_8859_6, "Arabic"),
+        EncodingOption::new(encoding_rs::WINDOWS_1258, "Vietnamese"),
+        EncodingOption::new(encoding_rs::WINDOWS_874, "Thai"),
+        EncodingOption::new(encoding_rs::SHIFT_JIS, "Japanese"),
+        EncodingOption::new(encoding_rs::EUC_JP, "Japanese"),
+        EncodingOption::new(encoding_rs::ISO_2022_JP, "Japanese"),
+        EncodingOption::new(encoding_rs::GBK, "Simplified Chinese"),
+        EncodingOption::new(encoding_rs::GB18030, "Simplified Chinese"),
+        EncodingOption::new(encoding_rs::BIG5, "Traditional Chinese"),
+        EncodingOption::new(encoding_rs::EUC_KR, "Korean"),
+    ]);
+    options
+}
+
+pub struct EncodingSelector {
+    picker: Entity<Picker<EncodingSelectorDelegate>>,
+}
+
+impl EncodingSelector {
+    fn register(editor: &mut Editor, _window: Option<&mut Window>, cx: &mut Context<Editor>) {
+        let editor_handle = cx.weak_entity();
+        editor
+            .register_action({
+                let editor_handle = editor_handle.clone();
+                move |_: &Toggle, window, cx| {
+                    Self::toggle(&editor_handle, None, window, cx);
+                }
+            })
+            .detach();
+        editor
+            .register_action({
+                let editor_handle = editor_handle.clone();
+                move |_: &ReopenWithEncoding, window, cx| {
+                    Self::toggle(&editor_handle, Some(EncodingAction::Reopen), window, cx);
+                }
+            })
+            .detach();
+        editor
+            .register_action(move |_: &SaveWithEncoding, window, cx| {
+                Self::toggle(&editor_handle, Some(EncodingAction::Save), window, cx);
+            })
+            .detach();
+    }
+
+    fn toggle(
+        editor: &WeakEntity<Editor>,
+        action: Option<EncodingAction>,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        let Some((workspace, buffer)) = editor
+            .update(cx, |editor, cx| {
+                Some((editor.workspace()?, editor.active_excerpt(cx)?.1))
+            })
+            .ok()
+            .flatten()
+        else {
+            return;
+        };
+
+        workspace.update(cx, |workspace, cx| {
+            let project = workspace.project().clone();
+            let weak_workspace = cx.weak_entity();
+            workspace.toggle_modal(window, cx, move |window, cx| {
+                EncodingSelector::new(buffer, project, weak_workspace, action, window, cx)
+            });
+        })
+    }
+
+    fn new(
+        buffer: Entity<Buffer>,
+        project: Entity<Project>,
+        workspace: WeakEntity<Workspace>,
+        action: Option<EncodingAction>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let delegate = EncodingSelectorDelegate::new(
+            cx.entity().downgrade(),
+            buffer.clone(),
+            project,
+            workspace,
+            action,
+        );
+        let picker = cx.new(|cx| Picker::uniform_list(delegate, window, cx));
+
+        // Guess the encoding of the file on disk, to suggest reopening it
+        // with that encoding. Only the host of a project can read its files.
+        let load_bytes = buffer
+            .read(cx)
+            .file()
+            .and_then(|file| file.as_local())
+            .map(|file| file.load_bytes(cx));
+        if let Some(load_bytes) = load_bytes {
+            let picker = picker.downgrade();
+            cx.spawn_in(window, async move |_, cx| {
+                let bytes = load_bytes.await?;
+                let (detected, _) = cx
+                    .background_spawn(async move { language::encoding::detect_encoding(&bytes) })
+                    .await;
+                picker.update_in(cx, |picker, window, cx| {
+                    picker.delegate.detected = Some(detected);
+                    picker.delegate.update_options();
+                    picker.refresh(window, cx);
+                })
+            })
+            .detach_and_log_err(cx);
+        }
+
+        Self { picker }
+    }
+}
+
+impl Render for EncodingSelector {
+    fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("EncodingSelector")
+            .w(rems(34.))
+            .child(self.picker.clone())
+    }
+}
+
+impl Focusable for EncodingSelector {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for EncodingSelector {}
+impl ModalView for EncodingSelector {}
+
+struct EncodingSelectorDelegate {
+    encoding_selector: WeakEntity<EncodingSelector>,
+    buffer: Entity<Buffer>,
+    project: Entity<Project>,
+    workspace: WeakEntity<Workspace>,
+    /// What to do with the chosen encoding, which is chosen first when the
+    /// selector is toggled.
+    action: Option<EncodingAction>,
+    /// The encoding that the file on disk seems to have.
+    detected: Option<&'static Encoding>,
+    options: Vec<EncodingOption>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+}
+
+impl EncodingSelectorDelegate {
+    fn new(
+        encoding_selector: WeakEntity<EncodingSelector>,
+        buffer: Entity<Buffer>,
+        project: Entity<Project>,
+        workspace: WeakEntity<Workspace>,
+        action: Option<EncodingAction>,
+    ) -> Self {
+        let mut this = Self {
+            encoding_selector,
+            buffer,
+            project,
+            workspace,
+            action,
+            detected: None,
+            options: Vec::new(),
+            matches: Vec::new(),
+            selected_index: 0,
+        };
+        this.update_options();
+        this
+    }
+
+    /// Lists the encodings for the chosen action, with the detected encoding
+    /// first when reopening.
+    fn update_options(&mut self) {
+        let Some(action) = self.action else {
+            return;
+        };
+        self.options = encoding_options(action);
+        if action == EncodingAction::Reopen
+            && let Some(detected) = self.detected
+            && let Some(ix) = self
+                .options
+                .iter()
+                .position(|option| option.encoding == detected)
+        {
+            let option = self.options.remove(ix);
+            self.options.insert(0, option);
+        }
+    }
+
+    fn candidates(&self) -> Vec<StringMatchCandidate> {
+        match self.action {
+            None => [EncodingAction::Reopen, EncodingAction::Save]
+                .iter()
+                .enumerate()
+                .map(|(id, action)| StringMatchCandidate::new(id, action.label()))
+                .collect(),
+            Some(_) => self
+                .options
+                .iter()
+                .enumerate()
+                .map(|(id, option)| StringMatchCandidate::new(id, &option.label()))
+                .collect(),
+        }
+    }
+
+    fn is_current(&self, option: &EncodingOption, cx: &App) -> bool {
+        let buffer = self.buffer.read(cx);
+        option.encoding == buffer.encoding()
+            && (option.encoding != encoding_rs::UTF_8 || option.has_bom == buffer.has_bom())
+    }
+
+    fn reopen(&self, option: EncodingOption, window: &mut Window, cx: &mut App) {
+        let buffer = self.buffer.clone();
+        let workspace = self.workspace.clone();
+        if buffer.read(cx).is_dirty() {
+            workspace
+                .update(cx, |workspace, cx| {
+                    workspace.show_error(
+                        &"Save or discard the changes to the file before reopening it with another encoding",
+                        cx,
+                    )
+                })
+                .ok();
+            return;
+        }
+
+        let previous_encoding = buffer.read(cx).encoding();
+        buffer.update(cx, |buffer, cx| {
+            let has_bom = buffer.has_bom();
+            buffer.update_encoding(option.encoding, has_bom, cx);
+        });
+        let reload = self.project.update(cx, |project, cx| {
+            project.reload_buffers(HashSet::from_iter([buffer.clone()]), true, cx)
+        });
+        window
+            .spawn(cx, async move |cx| {
+                if let Err(error) = reload.await {
+                    buffer.update(cx, |buffer, cx| {
+                        let has_bom = buffer.has_bom();
+                        buffer.update_encoding(previous_encoding, has_bom, cx);
+                    })?;
+                    workspace.update(cx, |workspace, cx| workspace.show_error(&error, cx))?;
+                }
+                anyhow::Ok(())
+            })
+            .detach_and_log_err(cx);
+    }
+
+    fn save(&self, option: EncodingOption, window: &mut Window, cx: &mut App) {
+        let buffer = self.buffer.clone();
+        let workspace = self.workspace.clone();
+        let (previous_encoding, previous_has_bom) = {
+            let buffer = buffer.read(cx);
+            (buffer.encoding(), buffer.has_bom())
+        };
+        buffer.update(cx, |buffer, cx| {
+            buffer.update_encoding(option.encoding, option.has_bom, cx);
+        });
+        let save = self
+            .project
+            .update(cx, |project, cx| project.save_buffer(buffer.clone(), cx));
+        window
+            .spawn(cx, async move |cx| {
+                if let Err(error) = save.await {
+                    buffer.update(cx, |buffer, cx| {
+                        buffer.update_encoding(previous_encoding, previous_has_bom, cx);
+                    })?;
+                    workspace.update(cx, |workspace, cx| workspace.show_error(&error, cx))?;
+                }
+                anyhow::Ok(())
+            })
+            .detach_and_log_err(cx);
+    }
+}
+
+impl PickerDelegate for EncodingSelectorDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _window: &mut Window, _cx: &mut App) -> Arc<str> {
+        match self.action {
+            None => "Reopen or save with encoding…".into(),
+            Some(EncodingAction::Reopen) => "Reopen with encoding…".into(),
+            Some(EncodingAction::Save) => "Save with encoding…".into(),
+        }
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn confirm(&mut self, _: bool, window: &mut Window, cx: &mut Context<Picker<Self>>) {
+        let Some(mat) = self.matches.get(self.selected_index) else {
+            return;
+        };
+        match self.action {
+            None => {
+                self.action = [EncodingAction::Reopen, EncodingAction::Save]
+                    .get(mat.candidate_id)
+                    .copied();
+                self.update_options();
+                self.selected_index = self
+                    .options
+                    .iter()
+                    .position(|option| self.is_current(option, cx))
+                    .unwrap_or(0);
+                cx.defer_in(window, |picker, window, cx| {
+                    picker.set_query("", window, cx);
+                    picker.refresh_placeholder(window, cx);
+                    picker.refresh(window, cx);
+                });
+                return;
+            }
+            Some(EncodingAction::Reopen) => {
+                if let Some(option) = self.options.get(mat.candidate_id) {
+                    self.reopen(*option, window, cx);
+                }
+            }
+            Some(EncodingAction::Save) => {
+                if let Some(option) = self.options.get(mat.candidate_id) {
+                    self.save(*option, window, cx);
+                }
+            }
+        }
+        self.dismissed(window, cx);
+    }
+
+    fn dismissed(&mut self, _: &mut Window, cx: &mut Context<Picker<Self>>) {
+        self.encoding_selector
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(
+        &mut self,
+        ix: usize,
+        _window: &mut Window,
+        _: &mut Context<Picker<Self>>,
+    ) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        window: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Task<()> {
+        let background = cx.background_executor().clone();
+        let candidates = self.candidates();
+        cx.spawn_in(window, async move |this, cx| {
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, candidate)| StringMatch {
+                        candidate_id: index,
+                        string: candidate.string,
+                        positions: Vec::new(),
+                        score: 0.0,
+                    })
+                    .collect()
+            } else {
+                match_strings(
+                    &candidates,
+                    &query,
+                    false,
+                    true,
+                    100,
+                    &Default::default(),
+                    background,
+                )
+                .await
+            };
+
+            this.update(cx, |this, cx| {
+                let delegate = &mut this.delegate;
+                delegate.matches = matches;
+                delegate.selected_index = delegate
+                    .selected_index
+                    .min(delegate.matches.len().saturating_sub(1));
+                cx.notify();
+            })
+            .log_err();
+        })
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _: &mut Window,
+        cx: &mut Context<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let mat = self.matches.get(ix)?;
+        let option = self.action.and_then(|_| self.options.get(mat.candidate_id));
+        let is_current = option.is_some_and(|option| self.is_current(option, cx));
+        let is_detected = self.action == Some(EncodingAction::Reopen)
+            && option.is_some_and(|option| Some(option.encoding) == self.detected);
+
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .toggle_state(selected)
+                .child(HighlightedLabel::new(
+                    mat.string.clone(),
+                    mat.positions.clone(),
+                ))
+                .end_slot(
+                    h_flex()
+                        .gap_2()
+                        .when(is_detected, |this| {
+                            this.child(
+                                Label::new("Detected")
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted),
+                            )
+                        })
+                        .when(is_current, |this| {
+                            this.child(Icon::new(IconName::Check).color(Color::Muted))
+                        }),
+                ),
+        )
+    }
+}
@@ -3,8 +3,11 @@ pub mod extension_builder;
 mod extension_events;
 mod extension_host_proxy;
 mod extension_manifest;
+#[cfg(any(test, feature = "test-support"))]
+mod fake_extension;
 mod types;
 
+use std::ops::Range;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
@@ -15,13 +18,15 @@ use fs::normalize_path;
 use gpui::{App, Task};
 use language::LanguageName;
 use semver::Version;
-use task::{SpawnInTerminal, ZedDebugConfig};
+use task::{SpawnInTerminal, TaskTemplates, ZedDebugConfig};
 use util::rel_path::RelPath;
 
 pub use crate::capabilities::*;
 pub use crate::extension_events::*;
 pub use crate::extension_host_proxy::*;
 pub use crate::extension_manifest::*;
+#[cfg(any(test, feature = "test-support"))]
+pub use crate::fake_extension::*;
 pub use crate::types::*;
 
 /// Initializes the `extension` crate.
@@ -168,6 +173,53 @@ pub trait Extension: Send + Sync + 'static {
         locator_name: String,
         config: SpawnInTerminal,
     ) -> Result<DebugRequest>;
+
+    async fn tasks_for_language(
+        &self,
+        provider_name: Arc<str>,
+        language_name: LanguageName,
+        file_path: Option<Arc<RelPath>>,
+        worktree: Arc<dyn WorktreeDelegate>,
+    ) -> Result<TaskTemplates>;
+
+    async fn format_buffer(
+        &self,
+        formatter_name: Arc<str>,
+        text: String,
+        language_name: LanguageName,
+        settings: FormatSettings,
+    ) -> Result<Vec<TextEdit>>;
+
+    async fn code_actions(
+        &self,
+        provider_name: Arc<str>,
+        text: String,
+        language_name: LanguageName,
+        range: Range<usize>,
+    ) -> Result<Vec<CodeAction>>;
+
+    async fn language_model_provider_models(
+        &self,
+        provider_id: Arc<str>,
+        api_key: Option<String>,
+    ) -> Result<Vec<LanguageModelInfo>>;
+
+    /// Starts streaming a completion and returns the ID of the completion stream.
+    async fn language_model_stream_completion(
+        &self,
+        provider_id: Arc<str>,
+        model_id: String,
+        api_key: Option<String>,
+        request: CompletionRequest,
+    ) -> Result<u64>;
+
+    /// Returns the next event of the completion stream, or `None` once it has ended.
+    async fn language_model_completion_next_event(
+        &self,
+        stream_id: u64,
+    ) -> Result<Option<CompletionEvent>>;
+
+    async fn language_model_completion_close(&self, stream_id: u64) -> Result<()>;
 }
 
 pub fn parse_wasm_extension_version(extension_id: &str, wasm_bytes: &[u8]) -> Result<Version> {
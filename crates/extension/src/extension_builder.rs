@@ -548,7 +548,8 @@ impl ExtensionBuilder {
     }
 }
 
-async fn populate_defaults(
+/// Fills in the manifest fields that are discovered from the extension directory.
+pub async fn populate_defaults(
     manifest: &mut ExtensionManifest,
     extension_path: &Path,
     fs: Arc<dyn Fs>,
@@ -12,6 +12,8 @@ use std::{
     sync::Arc,
 };
 
+use util::serde::default_true;
+
 use crate::ExtensionCapability;
 
 /// This is the old version of the extension manifest, from when it was `extension.json`.
@@ -93,6 +95,14 @@ pub struct ExtensionManifest {
     pub debug_adapters: BTreeMap<Arc<str>, DebugAdapterManifestEntry>,
     #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
     pub debug_locators: BTreeMap<Arc<str>, DebugLocatorManifestEntry>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub task_providers: BTreeMap<Arc<str>, TaskProviderManifestEntry>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub formatters: BTreeMap<Arc<str>, FormatterManifestEntry>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub code_action_providers: BTreeMap<Arc<str>, CodeActionProviderManifestEntry>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub language_model_providers: BTreeMap<Arc<str>, LanguageModelProviderManifestEntry>,
 }
 
 impl ExtensionManifest {
@@ -121,6 +131,7 @@ impl ExtensionManifest {
         !self.language_servers.is_empty()
             || !self.debug_adapters.is_empty()
             || !self.debug_locators.is_empty()
+            || !self.formatters.is_empty()
     }
 }
 
@@ -288,6 +299,40 @@ pub struct DebugAdapterManifestEntry {
 #[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
 pub struct DebugLocatorManifestEntry {}
 
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+pub struct TaskProviderManifestEntry {
+    /// The list of languages this task provider offers tasks for.
+    pub languages: Vec<LanguageName>,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+pub struct FormatterManifestEntry {
+    /// The list of languages this formatter can format.
+    pub languages: Vec<LanguageName>,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+pub struct CodeActionProviderManifestEntry {
+    /// The list of languages this code action provider offers code actions for.
+    pub languages: Vec<LanguageName>,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
+pub struct LanguageModelProviderManifestEntry {
+    /// The name of the provider, displayed in the model selector.
+    pub name: String,
+    /// The environment variable to read the provider's API key from, if it is not configured in Zed.
+    ///
+    /// Must start with `ZED_<EXTENSION_ID>_`, with the extension ID in upper snake case.
+    #[serde(default)]
+    pub api_key_env_var: Option<String>,
+    /// Whether the provider's models can only be used once an API key is configured.
+    ///
+    /// Providers that don't need one, such as local servers, can set this to `false`.
+    #[serde(default = "default_true")]
+    pub requires_api_key: bool,
+}
+
 impl ExtensionManifest {
     pub async fn load(fs: Arc<dyn Fs>, extension_dir: &Path) -> Result<Self> {
         let extension_name = extension_dir
@@ -358,6 +403,10 @@ fn manifest_from_old_manifest(
         capabilities: Vec::new(),
         debug_adapters: Default::default(),
         debug_locators: Default::default(),
+        task_providers: Default::default(),
+        formatters: Default::default(),
+        code_action_providers: Default::default(),
+        language_model_providers: Default::default(),
     }
 }
 
@@ -391,6 +440,10 @@ mod tests {
             capabilities: vec![],
             debug_adapters: Default::default(),
             debug_locators: Default::default(),
+            task_providers: Default::default(),
+            formatters: Default::default(),
+            code_action_providers: Default::default(),
+            language_model_providers: Default::default(),
         }
     }
 
@@ -519,4 +572,27 @@ args = ["--serve"]
         assert_eq!(target.cmd, "./agent");
         assert_eq!(target.args, vec!["--serve"]);
     }
+
+    #[test]
+    fn parse_manifest_with_language_model_providers() {
+        let toml_src = r#"
+id = "example.gateway-ext"
+name = "Gateway Example"
+version = "1.0.0"
+schema_version = 0
+
+[language_model_providers.gateway]
+name = "Gateway"
+
+[language_model_providers.local]
+name = "Local Models"
+requires_api_key = false
+"#;
+
+        let manifest: ExtensionManifest = toml::from_str(toml_src).expect("manifest should parse");
+        let gateway = manifest.language_model_providers.get("gateway").unwrap();
+        assert!(gateway.requires_api_key);
+        let local = manifest.language_model_providers.get("local").unwrap();
+        assert!(!local.requires_api_key);
+    }
 }
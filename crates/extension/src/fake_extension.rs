@@ -0,0 +1,317 @@
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::{Result, bail};
+use async_trait::async_trait;
+use collections::BTreeMap;
+use language::LanguageName;
+use lsp::LanguageServerName;
+use task::{SpawnInTerminal, TaskTemplates, ZedDebugConfig};
+use util::rel_path::RelPath;
+
+use crate::{
+    BuildTaskTemplate, CodeAction, CodeActionProviderManifestEntry, CodeLabel, Command, Completion,
+    CompletionEvent, CompletionRequest, ContextServerConfiguration, DebugAdapterBinary,
+    DebugRequest, DebugScenario, DebugTaskDefinition, Extension, ExtensionManifest, FormatSettings,
+    FormatterManifestEntry, KeyValueStoreDelegate, LanguageModelInfo, LibManifestEntry,
+    ProjectDelegate, SchemaVersion, SlashCommand, SlashCommandArgumentCompletion,
+    SlashCommandOutput, StartDebuggingRequestArgumentsRequest, Symbol, TextEdit, WorktreeDelegate,
+};
+
+type FormatHandler = Box<dyn Fn(&str, FormatSettings) -> Result<Vec<TextEdit>> + Send + Sync>;
+type CodeActionsHandler = Box<dyn Fn(&str, Range<usize>) -> Result<Vec<CodeAction>> + Send + Sync>;
+
+/// An [`Extension`] for tests, which answers formatting and code action requests with the
+/// handlers it was built with.
+pub struct FakeExtension {
+    manifest: ExtensionManifest,
+    formatters: BTreeMap<Arc<str>, FormatHandler>,
+    code_action_providers: BTreeMap<Arc<str>, CodeActionsHandler>,
+}
+
+impl FakeExtension {
+    pub fn new(extension_id: &str) -> Self {
+        Self {
+            manifest: ExtensionManifest {
+                id: extension_id.into(),
+                name: extension_id.to_string(),
+                version: "0.0.1".into(),
+                schema_version: SchemaVersion(1),
+                description: None,
+                repository: None,
+                authors: Vec::new(),
+                lib: LibManifestEntry::default(),
+                themes: Vec::new(),
+                icon_themes: Vec::new(),
+                languages: Vec::new(),
+                grammars: BTreeMap::default(),
+                language_servers: BTreeMap::default(),
+                context_servers: BTreeMap::default(),
+                agent_servers: BTreeMap::default(),
+                slash_commands: BTreeMap::default(),
+                snippets: None,
+                capabilities: Vec::new(),
+                debug_adapters: BTreeMap::default(),
+                debug_locators: BTreeMap::default(),
+                task_providers: BTreeMap::default(),
+                formatters: BTreeMap::default(),
+                code_action_providers: BTreeMap::default(),
+                language_model_providers: BTreeMap::default(),
+            },
+            formatters: BTreeMap::default(),
+            code_action_providers: BTreeMap::default(),
+        }
+    }
+
+    /// Adds a formatter for the given languages, which formats the text with `format`.
+    pub fn with_formatter(
+        mut self,
+        formatter_name: &str,
+        languages: Vec<LanguageName>,
+        format: impl Fn(&str, FormatSettings) -> Result<Vec<TextEdit>> + Send + Sync + 'static,
+    ) -> Self {
+        let formatter_name: Arc<str> = formatter_name.into();
+        self.manifest
+            .formatters
+            .insert(formatter_name.clone(), FormatterManifestEntry { languages });
+        self.formatters.insert(formatter_name, Box::new(format));
+        self
+    }
+
+    /// Adds a code action provider for the given languages, which offers the actions returned
+    /// by `code_actions` for the text and range.
+    pub fn with_code_action_provider(
+        mut self,
+        provider_name: &str,
+        languages: Vec<LanguageName>,
+        code_actions: impl Fn(&str, Range<usize>) -> Result<Vec<CodeAction>> + Send + Sync + 'static,
+    ) -> Self {
+        let provider_name: Arc<str> = provider_name.into();
+        self.manifest.code_action_providers.insert(
+            provider_name.clone(),
+            CodeActionProviderManifestEntry { languages },
+        );
+        self.code_action_providers
+            .insert(provider_name, Box::new(code_actions));
+        self
+    }
+}
+
+#[async_trait]
+impl Extension for FakeExtension {
+    fn manifest(&self) -> Arc<ExtensionManifest> {
+        Arc::new(self.manifest.clone())
+    }
+
+    fn work_dir(&self) -> Arc<Path> {
+        Path::new("/fake-extension-work-dir").into()
+    }
+
+    async fn language_server_command(
+        &self,
+        _: LanguageServerName,
+        _: LanguageName,
+        _: Arc<dyn WorktreeDelegate>,
+    ) -> Result<Command> {
+        bail!("FakeExtension does not provide language servers")
+    }
+
+    async fn language_server_initialization_options(
+        &self,
+        _: LanguageServerName,
+        _: LanguageName,
+        _: Arc<dyn WorktreeDelegate>,
+    ) -> Result<Option<String>> {
+        bail!("FakeExtension does not provide language servers")
+    }
+
+    async fn language_server_workspace_configuration(
+        &self,
+        _: LanguageServerName,
+        _: Arc<dyn WorktreeDelegate>,
+    ) -> Result<Option<String>> {
+        bail!("FakeExtension does not provide language servers")
+    }
+
+    async fn language_server_additional_initialization_options(
+        &self,
+        _: LanguageServerName,
+        _: LanguageServerName,
+        _: Arc<dyn WorktreeDelegate>,
+    ) -> Result<Option<String>> {
+        bail!("FakeExtension does not provide language servers")
+    }
+
+    async fn language_server_additional_workspace_configuration(
+        &self,
+        _: LanguageServerName,
+        _: LanguageServerName,
+        _: Arc<dyn WorktreeDelegate>,
+    ) -> Result<Option<String>> {
+        bail!("FakeExtension does not provide language servers")
+    }
+
+    async fn labels_for_completions(
+        &self,
+        _: LanguageServerName,
+        _: Vec<Completion>,
+    ) -> Result<Vec<Option<CodeLabel>>> {
+        bail!("FakeExtension does not provide language servers")
+    }
+
+    async fn labels_for_symbols(
+        &self,
+        _: LanguageServerName,
+        _: Vec<Symbol>,
+    ) -> Result<Vec<Option<CodeLabel>>> {
+        bail!("FakeExtension does not provide language servers")
+    }
+
+    async fn complete_slash_command_argument(
+        &self,
+        _: SlashCommand,
+        _: Vec<String>,
+    ) -> Result<Vec<SlashCommandArgumentCompletion>> {
+        bail!("FakeExtension does not provide slash commands")
+    }
+
+    async fn run_slash_command(
+        &self,
+        _: SlashCommand,
+        _: Vec<String>,
+        _: Option<Arc<dyn WorktreeDelegate>>,
+    ) -> Result<SlashCommandOutput> {
+        bail!("FakeExtension does not provide slash commands")
+    }
+
+    async fn context_server_command(
+        &self,
+        _: Arc<str>,
+        _: Arc<dyn ProjectDelegate>,
+    ) -> Result<Command> {
+        bail!("FakeExtension does not provide context servers")
+    }
+
+    async fn context_server_configuration(
+        &self,
+        _: Arc<str>,
+        _: Arc<dyn ProjectDelegate>,
+    ) -> Result<Option<ContextServerConfiguration>> {
+        bail!("FakeExtension does not provide context servers")
+    }
+
+    async fn suggest_docs_packages(&self, _: Arc<str>) -> Result<Vec<String>> {
+        bail!("FakeExtension does not provide docs")
+    }
+
+    async fn index_docs(
+        &self,
+        _: Arc<str>,
+        _: Arc<str>,
+        _: Arc<dyn KeyValueStoreDelegate>,
+    ) -> Result<()> {
+        bail!("FakeExtension does not provide docs")
+    }
+
+    async fn get_dap_binary(
+        &self,
+        _: Arc<str>,
+        _: DebugTaskDefinition,
+        _: Option<PathBuf>,
+        _: Arc<dyn WorktreeDelegate>,
+    ) -> Result<DebugAdapterBinary> {
+        bail!("FakeExtension does not provide debug adapters")
+    }
+
+    async fn dap_request_kind(
+        &self,
+        _: Arc<str>,
+        _: serde_json::Value,
+    ) -> Result<StartDebuggingRequestArgumentsRequest> {
+        bail!("FakeExtension does not provide debug adapters")
+    }
+
+    async fn dap_config_to_scenario(&self, _: ZedDebugConfig) -> Result<DebugScenario> {
+        bail!("FakeExtension does not provide debug adapters")
+    }
+
+    async fn dap_locator_create_scenario(
+        &self,
+        _: String,
+        _: BuildTaskTemplate,
+        _: String,
+        _: String,
+    ) -> Result<Option<DebugScenario>> {
+        bail!("FakeExtension does not provide debug locators")
+    }
+
+    async fn run_dap_locator(&self, _: String, _: SpawnInTerminal) -> Result<DebugRequest> {
+        bail!("FakeExtension does not provide debug locators")
+    }
+
+    async fn tasks_for_language(
+        &self,
+        _: Arc<str>,
+        _: LanguageName,
+        _: Option<Arc<RelPath>>,
+        _: Arc<dyn WorktreeDelegate>,
+    ) -> Result<TaskTemplates> {
+        bail!("FakeExtension does not provide tasks")
+    }
+
+    async fn format_buffer(
+        &self,
+        formatter_name: Arc<str>,
+        text: String,
+        _: LanguageName,
+        settings: FormatSettings,
+    ) -> Result<Vec<TextEdit>> {
+        let Some(format) = self.formatters.get(&formatter_name) else {
+            bail!("unknown formatter {formatter_name}")
+        };
+        format(&text, settings)
+    }
+
+    async fn code_actions(
+        &self,
+        provider_name: Arc<str>,
+        text: String,
+        _: LanguageName,
+        range: Range<usize>,
+    ) -> Result<Vec<CodeAction>> {
+        let Some(code_actions) = self.code_action_providers.get(&provider_name) else {
+            bail!("unknown code action provider {provider_name}")
+        };
+        code_actions(&text, range)
+    }
+
+    async fn language_model_provider_models(
+        &self,
+        _: Arc<str>,
+        _: Option<String>,
+    ) -> Result<Vec<LanguageModelInfo>> {
+        bail!("FakeExtension does not provide language models")
+    }
+
+    async fn language_model_stream_completion(
+        &self,
+        _: Arc<str>,
+        _: String,
+        _: Option<String>,
+        _: CompletionRequest,
+    ) -> Result<u64> {
+        bail!("FakeExtension does not provide language models")
+    }
+
+    async fn language_model_completion_next_event(
+        &self,
+        _: u64,
+    ) -> Result<Option<CompletionEvent>> {
+        bail!("FakeExtension does not provide language models")
+    }
+
+    async fn language_model_completion_close(&self, _: u64) -> Result<()> {
+        bail!("FakeExtension does not provide language models")
+    }
+}
@@ -0,0 +1,32 @@
+use std::ops::Range;
+
+/// An edit to the text of a buffer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextEdit {
+    /// The byte range of the text to replace.
+    pub range: Range<usize>,
+    /// The text to replace the range with.
+    pub new_text: String,
+}
+
+/// The settings to format a buffer with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FormatSettings {
+    /// The number of columns an indentation level spans.
+    pub tab_size: u32,
+    /// Whether to indent with tabs instead of spaces.
+    pub hard_tabs: bool,
+    /// The column at which lines should be wrapped.
+    pub preferred_line_length: u32,
+}
+
+/// A code action that an extension offers for a range of a buffer.
+#[derive(Debug, Clone)]
+pub struct CodeAction {
+    /// The title of the code action, displayed in the code actions menu.
+    pub title: String,
+    /// The kind of the code action, like `quickfix` or `refactor.extract`.
+    pub kind: Option<String>,
+    /// The edits the code action makes to the text of the buffer.
+    pub edits: Vec<TextEdit>,
+}
@@ -0,0 +1,144 @@
+/// A language model offered by a language model provider.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LanguageModelInfo {
+    /// The ID of the model, as used by the provider's API.
+    pub id: String,
+    /// The name of the model to display to the user.
+    pub name: String,
+    /// The maximum number of tokens in the context window.
+    pub max_token_count: u64,
+    /// The maximum number of tokens the model can produce, if limited.
+    pub max_output_tokens: Option<u64>,
+    /// Whether the model supports tool calls.
+    pub supports_tools: bool,
+    /// Whether the model accepts images as input.
+    pub supports_images: bool,
+}
+
+/// A request for a completion from a language model.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompletionRequest {
+    /// The messages of the conversation so far.
+    pub messages: Vec<RequestMessage>,
+    /// The tools the model may call.
+    pub tools: Vec<ToolDefinition>,
+    /// How the model should choose whether to call tools.
+    pub tool_choice: Option<ToolChoice>,
+    /// The sequences at which the model should stop generating.
+    pub stop: Vec<String>,
+    /// The sampling temperature.
+    pub temperature: Option<f32>,
+    /// Whether the model may think before answering.
+    pub thinking_allowed: bool,
+}
+
+/// The author of a message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageRole {
+    User,
+    Assistant,
+    System,
+}
+
+/// A message in a completion request.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RequestMessage {
+    /// The author of the message.
+    pub role: MessageRole,
+    /// The content of the message.
+    pub content: Vec<MessageContent>,
+    /// Whether the provider should cache the conversation up to this message.
+    pub cache: bool,
+}
+
+/// A part of a message.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MessageContent {
+    Text(String),
+    Thinking(Thinking),
+    /// A PNG image, encoded as base64.
+    Image(String),
+    ToolUse(ToolUse),
+    ToolResult(ToolResult),
+}
+
+/// The reasoning of a model.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Thinking {
+    /// The text of the reasoning.
+    pub text: String,
+    /// The signature the provider attached to the reasoning, if any.
+    pub signature: Option<String>,
+}
+
+/// A tool that the model may call.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ToolDefinition {
+    /// The name of the tool.
+    pub name: String,
+    /// The description of the tool.
+    pub description: String,
+    /// The JSON schema of the tool's input.
+    pub input_schema: String,
+}
+
+/// How the model should choose whether to call tools.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToolChoice {
+    Auto,
+    Any,
+    None,
+}
+
+/// A tool call made by the model.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ToolUse {
+    /// The ID of the tool call.
+    pub id: String,
+    /// The name of the called tool.
+    pub name: String,
+    /// The input of the tool call, as JSON.
+    pub input: String,
+}
+
+/// The result of a tool call.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ToolResult {
+    /// The ID of the tool call this is the result of.
+    pub tool_use_id: String,
+    /// The name of the called tool.
+    pub tool_name: String,
+    /// Whether the tool call failed.
+    pub is_error: bool,
+    /// The output of the tool.
+    pub content: String,
+}
+
+/// An event in a streamed completion.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CompletionEvent {
+    Started,
+    Text(String),
+    Thinking(Thinking),
+    ToolUse(ToolUse),
+    Stop(StopReason),
+    UsageUpdate(TokenUsage),
+}
+
+/// The reason a model stopped generating.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StopReason {
+    EndTurn,
+    MaxTokens,
+    ToolUse,
+    Refusal,
+}
+
+/// The number of tokens used by a completion.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TokenUsage {
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_creation_input_tokens: u64,
+    pub cache_read_input_tokens: u64,
+}
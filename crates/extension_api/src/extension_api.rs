@@ -5,6 +5,7 @@ pub mod process;
 pub mod settings;
 
 use core::fmt;
+use std::collections::BTreeMap;
 
 use wit::*;
 
@@ -15,9 +16,9 @@ pub use serde_json;
 // We explicitly enumerate the symbols we want to re-export, as there are some
 // that we may want to shadow to provide a cleaner Rust API.
 pub use wit::{
-    CodeLabel, CodeLabelSpan, CodeLabelSpanLiteral, Command, DownloadedFileType, EnvVars,
-    KeyValueStore, LanguageServerInstallationStatus, Project, Range, Worktree, download_file,
-    make_file_executable,
+    CodeAction, CodeLabel, CodeLabelSpan, CodeLabelSpanLiteral, Command, DownloadedFileType,
+    EnvVars, FormatSettings, KeyValueStore, LanguageServerInstallationStatus, LanguageTask,
+    Project, Range, TextEdit, Worktree, download_file, make_file_executable,
     zed::extension::context_server::ContextServerConfiguration,
     zed::extension::dap::{
         AttachRequest, BuildTaskDefinition, BuildTaskDefinitionTemplatePayload, BuildTaskTemplate,
@@ -54,6 +55,21 @@ pub mod lsp {
     };
 }
 
+/// Constructs for providing language models to Zed's agent.
+pub mod language_model {
+    pub use crate::wit::zed::extension::language_model::{
+        CompletionEvent, CompletionRequest, LanguageModelInfo, MessageContent, MessageRole,
+        RequestMessage, StopReason, Thinking, TokenUsage, ToolChoice, ToolDefinition, ToolResult,
+        ToolUse,
+    };
+
+    /// The events of a streamed completion, in order.
+    ///
+    /// Iterating the stream may block, e.g., while waiting for the next chunk of an
+    /// [`HttpResponseStream`](crate::http_client::HttpResponseStream).
+    pub type CompletionEventStream = Box<dyn Iterator<Item = Result<CompletionEvent, String>>>;
+}
+
 /// A result returned from a Zed extension.
 pub type Result<T, E = String> = core::result::Result<T, E>;
 
@@ -259,6 +275,63 @@ pub trait Extension: Send + Sync {
     ) -> Result<DebugRequest, String> {
         Err("`run_dap_locator` not implemented".to_string())
     }
+
+    /// Returns the tasks that the specified task provider offers for a file in the given language.
+    ///
+    /// Tasks with tags are also offered as runnables, next to the code captured with the same tag
+    /// by the language's `runnables.scm` query (e.g., a "run test under cursor" task).
+    fn tasks_for_language(
+        &mut self,
+        _provider_name: String,
+        _language_name: String,
+        _file_path: Option<String>,
+        _worktree: &Worktree,
+    ) -> Result<Vec<LanguageTask>, String> {
+        Ok(Vec::new())
+    }
+
+    /// Returns the edits that format the text of a buffer in the given language with the specified formatter.
+    fn format_buffer(
+        &mut self,
+        _formatter_name: String,
+        _text: String,
+        _language_name: String,
+        _settings: FormatSettings,
+    ) -> Result<Vec<TextEdit>, String> {
+        Err("`format_buffer` not implemented".to_string())
+    }
+
+    /// Returns the code actions that the specified code action provider offers for the given byte range
+    /// of the text of a buffer in the given language.
+    fn code_actions(
+        &mut self,
+        _provider_name: String,
+        _text: String,
+        _language_name: String,
+        _range: Range,
+    ) -> Result<Vec<CodeAction>, String> {
+        Ok(Vec::new())
+    }
+
+    /// Returns the models that the specified language model provider offers.
+    fn language_model_provider_models(
+        &mut self,
+        _provider_id: String,
+        _api_key: Option<String>,
+    ) -> Result<Vec<language_model::LanguageModelInfo>, String> {
+        Ok(Vec::new())
+    }
+
+    /// Starts streaming a completion from a model of the specified language model provider.
+    fn language_model_stream_completion(
+        &mut self,
+        _provider_id: String,
+        _model_id: String,
+        _api_key: Option<String>,
+        _request: language_model::CompletionRequest,
+    ) -> Result<language_model::CompletionEventStream, String> {
+        Err("`language_model_stream_completion` not implemented".to_string())
+    }
 }
 
 /// Registers the provided type as a Zed extension.
@@ -325,6 +398,23 @@ fn extension() -> &'static mut dyn Extension {
 
 static mut EXTENSION: Option<Box<dyn Extension>> = None;
 
+struct CompletionStreams {
+    next_id: u64,
+    streams: BTreeMap<u64, language_model::CompletionEventStream>,
+}
+
+fn completion_streams() -> &'static mut CompletionStreams {
+    #[expect(static_mut_refs)]
+    unsafe {
+        &mut COMPLETION_STREAMS
+    }
+}
+
+static mut COMPLETION_STREAMS: CompletionStreams = CompletionStreams {
+    next_id: 0,
+    streams: BTreeMap::new(),
+};
+
 #[cfg(target_arch = "wasm32")]
 #[unsafe(link_section = "zed:api-version")]
 #[doc(hidden)]
@@ -517,6 +607,83 @@ impl wit::Guest for Component {
     ) -> Result<DebugRequest, String> {
         extension().run_dap_locator(locator_name, build_task)
     }
+
+    fn tasks_for_language(
+        provider_name: String,
+        language_name: String,
+        file_path: Option<String>,
+        worktree: &Worktree,
+    ) -> Result<Vec<LanguageTask>, String> {
+        extension().tasks_for_language(provider_name, language_name, file_path, worktree)
+    }
+
+    fn format_buffer(
+        formatter_name: String,
+        text: String,
+        language_name: String,
+        settings: FormatSettings,
+    ) -> Result<Vec<TextEdit>, String> {
+        extension().format_buffer(formatter_name, text, language_name, settings)
+    }
+
+    fn code_actions(
+        provider_name: String,
+        text: String,
+        language_name: String,
+        range: Range,
+    ) -> Result<Vec<CodeAction>, String> {
+        extension().code_actions(provider_name, text, language_name, range)
+    }
+
+    fn language_model_provider_models(
+        provider_id: String,
+        api_key: Option<String>,
+    ) -> Result<Vec<language_model::LanguageModelInfo>, String> {
+        extension().language_model_provider_models(provider_id, api_key)
+    }
+
+    fn language_model_stream_completion(
+        provider_id: String,
+        model_id: String,
+        api_key: Option<String>,
+        request: language_model::CompletionRequest,
+    ) -> Result<u64, String> {
+        let stream = extension().language_model_stream_completion(
+            provider_id,
+            model_id,
+            api_key,
+            request,
+        )?;
+        let streams = completion_streams();
+        let stream_id = streams.next_id;
+        streams.next_id += 1;
+        streams.streams.insert(stream_id, stream);
+        Ok(stream_id)
+    }
+
+    fn language_model_completion_next_event(
+        stream_id: u64,
+    ) -> Result<Option<language_model::CompletionEvent>, String> {
+        let streams = &mut completion_streams().streams;
+        let stream = streams
+            .get_mut(&stream_id)
+            .ok_or_else(|| format!("no completion stream with ID {stream_id}"))?;
+        match stream.next() {
+            Some(Ok(event)) => Ok(Some(event)),
+            Some(Err(error)) => {
+                streams.remove(&stream_id);
+                Err(error)
+            }
+            None => {
+                streams.remove(&stream_id);
+                Ok(None)
+            }
+        }
+    }
+
+    fn language_model_completion_close(stream_id: u64) {
+        completion_streams().streams.remove(&stream_id);
+    }
 }
 
 /// The ID of a language server.
@@ -0,0 +1,509 @@
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use ::fs::RealFs;
+use anyhow::{Context as _, Result, bail};
+use async_trait::async_trait;
+use extension::extension_builder::CompileExtensionOptions;
+use extension::{Extension, ExtensionHostProxy, ExtensionManifest, SlashCommand, WorktreeDelegate};
+use extension_host::headless_host::HeadlessExtensionStore;
+use extension_host::wasm_host::WasmExtension;
+use gpui::{Application, AsyncApp, Entity};
+use language::{LanguageName, LanguageServerName};
+use node_runtime::NodeRuntime;
+use release_channel::AppVersion;
+use reqwest_client::ReqwestClient;
+use serde::Deserialize;
+use util::rel_path::RelPath;
+
+#[derive(clap::Args, Debug)]
+pub struct TestArgs {
+    /// The path to the extension directory
+    #[arg(default_value = ".")]
+    source_dir: PathBuf,
+    /// The path to the test file. Defaults to `tests/extension_test.toml` in the extension directory.
+    #[arg(long)]
+    spec: Option<PathBuf>,
+    /// The path to a directory where build dependencies are downloaded
+    #[arg(long)]
+    scratch_dir: Option<PathBuf>,
+}
+
+/// The tests to run against an extension, read from `tests/extension_test.toml`.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TestSpec {
+    /// The fixture directory the extension sees as the worktree, relative to the test file.
+    #[serde(default)]
+    worktree: Option<PathBuf>,
+    /// The paths that `which` returns for binaries in the worktree.
+    #[serde(default)]
+    which: HashMap<String, String>,
+    /// The environment of the worktree's shell.
+    #[serde(default)]
+    env: HashMap<String, String>,
+    #[serde(default)]
+    language_servers: Vec<LanguageServerTest>,
+    #[serde(default)]
+    slash_commands: Vec<SlashCommandTest>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct LanguageServerTest {
+    id: String,
+    language: String,
+    #[serde(default)]
+    expect: LanguageServerExpectation,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct LanguageServerExpectation {
+    command: Option<PathBuf>,
+    args: Option<Vec<String>>,
+    /// Environment variables the command must set, in addition to any others.
+    #[serde(default)]
+    env: HashMap<String, String>,
+    /// A substring of the error the extension must return instead of a command.
+    error: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SlashCommandTest {
+    name: String,
+    #[serde(default)]
+    arguments: Vec<String>,
+    #[serde(default)]
+    expect: SlashCommandExpectation,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SlashCommandExpectation {
+    text: Option<String>,
+    /// The labels of the output sections, in order.
+    sections: Option<Vec<String>>,
+    /// A substring of the error the extension must return instead of an output.
+    error: Option<String>,
+}
+
+/// A worktree backed by a fixture directory, with a fake `$PATH` and shell environment.
+struct FixtureWorktree {
+    root: PathBuf,
+    which: HashMap<String, String>,
+    env: HashMap<String, String>,
+}
+
+#[async_trait]
+impl WorktreeDelegate for FixtureWorktree {
+    fn id(&self) -> u64 {
+        0
+    }
+
+    fn root_path(&self) -> String {
+        self.root.to_string_lossy().into_owned()
+    }
+
+    async fn read_text_file(&self, path: &RelPath) -> Result<String> {
+        let path = self.root.join(path.as_std_path());
+        fs::read_to_string(&path).with_context(|| format!("failed to read {}", path.display()))
+    }
+
+    async fn which(&self, binary_name: String) -> Option<String> {
+        self.which.get(&binary_name).cloned()
+    }
+
+    async fn shell_env(&self) -> Vec<(String, String)> {
+        self.env
+            .iter()
+            .map(|(name, value)| (name.clone(), value.clone()))
+            .collect()
+    }
+}
+
+pub fn run(args: TestArgs) -> Result<()> {
+    let extension_path = args
+        .source_dir
+        .canonicalize()
+        .context("failed to canonicalize source_dir")?;
+    let spec_path = args
+        .spec
+        .unwrap_or_else(|| extension_path.join("tests").join("extension_test.toml"));
+    let spec_content = fs::read_to_string(&spec_path)
+        .with_context(|| format!("failed to read {}", spec_path.display()))?;
+    let spec = toml::from_str::<TestSpec>(&spec_content)
+        .with_context(|| format!("invalid test file {}", spec_path.display()))?;
+    let spec_dir = spec_path
+        .parent()
+        .context("invalid test file path")?
+        .to_path_buf();
+    let worktree = match &spec.worktree {
+        Some(worktree) => Some(
+            spec_dir
+                .join(worktree)
+                .canonicalize()
+                .with_context(|| format!("fixture worktree {} not found", worktree.display()))?,
+        ),
+        None => None,
+    };
+
+    let scratch_dir = args
+        .scratch_dir
+        .unwrap_or_else(|| env::temp_dir().join("zed-extension-cli"));
+    fs::create_dir_all(&scratch_dir).context("failed to create scratch_dir")?;
+    let extensions_dir = tempfile::tempdir().context("failed to create extensions dir")?;
+
+    let fs = Arc::new(RealFs::new(None, gpui::background_executor()));
+    let extension_id = tokio::runtime::Runtime::new()?.block_on(async {
+        log::info!("loading extension manifest");
+        let mut manifest = ExtensionManifest::load(fs.clone(), &extension_path).await?;
+
+        log::info!("compiling extension");
+        crate::compile_extension(
+            &extension_path,
+            &mut manifest,
+            scratch_dir,
+            CompileExtensionOptions { release: false },
+            fs.clone(),
+        )
+        .await?;
+
+        crate::copy_extension_resources(
+            &manifest,
+            &extension_path,
+            &extensions_dir.path().join(manifest.id.as_ref()),
+            fs.clone(),
+        )
+        .await
+        .context("failed to copy extension resources")?;
+        anyhow::Ok(manifest.id)
+    })?;
+
+    let http_client = Arc::new(ReqwestClient::user_agent(&crate::user_agent())?);
+    Application::headless()
+        .with_http_client(http_client)
+        .run(move |cx| {
+            settings::init(cx);
+            release_channel::init(AppVersion::load(env!("CARGO_PKG_VERSION"), None, None), cx);
+            gpui_tokio::init(cx);
+
+            let store = HeadlessExtensionStore::new(
+                Arc::new(RealFs::new(None, cx.background_executor().clone())),
+                cx.http_client(),
+                extensions_dir.path().to_path_buf(),
+                Arc::new(ExtensionHostProxy::new()),
+                NodeRuntime::unavailable(),
+                cx,
+            );
+
+            cx.spawn(async move |cx| {
+                let worktree = worktree.map(|root| {
+                    Arc::new(FixtureWorktree {
+                        root,
+                        which: spec.which.clone(),
+                        env: spec.env.clone(),
+                    }) as Arc<dyn WorktreeDelegate>
+                });
+                let result = run_tests(&store, &extension_id, &spec, worktree, cx).await;
+                drop(extensions_dir);
+
+                if let Err(error) = result {
+                    eprintln!("Error: {error:?}");
+                    std::process::exit(1);
+                }
+                cx.update(|cx| cx.quit()).ok();
+            })
+            .detach();
+        });
+
+    Ok(())
+}
+
+async fn run_tests(
+    store: &Entity<HeadlessExtensionStore>,
+    extension_id: &str,
+    spec: &TestSpec,
+    worktree: Option<Arc<dyn WorktreeDelegate>>,
+    cx: &mut AsyncApp,
+) -> Result<()> {
+    let (fs, wasm_host, extension_dir) = store.read_with(cx, |store, _| {
+        (
+            store.fs.clone(),
+            store.wasm_host.clone(),
+            store.extension_dir.join(extension_id),
+        )
+    })?;
+    let manifest = Arc::new(ExtensionManifest::load(fs, &extension_dir).await?);
+    let extension = WasmExtension::load(&extension_dir, &manifest, wasm_host, cx).await?;
+
+    let mut passed = 0;
+    let mut failed = 0;
+    for test in &spec.language_servers {
+        let name = format!("language server `{}` for `{}`", test.id, test.language);
+        let result = test_language_server(&extension, &manifest, test, worktree.clone()).await;
+        report(&name, result, &mut passed, &mut failed);
+    }
+    for test in &spec.slash_commands {
+        let name = format!("slash command `/{}`", test.name);
+        let result = test_slash_command(&extension, &manifest, test, worktree.clone()).await;
+        report(&name, result, &mut passed, &mut failed);
+    }
+
+    println!();
+    if failed > 0 {
+        bail!("test result: FAILED. {passed} passed; {failed} failed");
+    }
+    println!("test result: ok. {passed} passed; {failed} failed");
+    Ok(())
+}
+
+fn report(name: &str, result: Result<()>, passed: &mut usize, failed: &mut usize) {
+    match result {
+        Ok(()) => {
+            println!("test {name} ... ok");
+            *passed += 1;
+        }
+        Err(error) => {
+            println!("test {name} ... FAILED\n    {error:#}");
+            *failed += 1;
+        }
+    }
+}
+
+async fn test_language_server(
+    extension: &WasmExtension,
+    manifest: &ExtensionManifest,
+    test: &LanguageServerTest,
+    worktree: Option<Arc<dyn WorktreeDelegate>>,
+) -> Result<()> {
+    let language_server_name = LanguageServerName::from(test.id.as_str());
+    let language_name = LanguageName::new(&test.language);
+    let entry = manifest
+        .language_servers
+        .get(&language_server_name)
+        .context("language server is not declared in extension.toml")?;
+    anyhow::ensure!(
+        entry
+            .languages()
+            .into_iter()
+            .any(|language| language == language_name),
+        "language server does not list `{}` in its languages",
+        test.language
+    );
+    let worktree = worktree.context("language server tests require a `worktree`")?;
+
+    let result = extension
+        .language_server_command(language_server_name, language_name, worktree)
+        .await;
+    let command = match (result, &test.expect.error) {
+        (Ok(command), None) => command,
+        (Err(error), Some(expected_error)) => {
+            return expect_error(&format!("{error:#}"), expected_error);
+        }
+        (Ok(command), Some(_)) => bail!("expected an error, got {command:?}"),
+        (Err(error), None) => return Err(error),
+    };
+
+    if let Some(expected_command) = &test.expect.command {
+        anyhow::ensure!(
+            &command.command == expected_command,
+            "expected command {expected_command:?}, got {:?}",
+            command.command
+        );
+    }
+    if let Some(expected_args) = &test.expect.args {
+        anyhow::ensure!(
+            &command.args == expected_args,
+            "expected args {expected_args:?}, got {:?}",
+            command.args
+        );
+    }
+    for (name, expected_value) in &test.expect.env {
+        let value = command
+            .env
+            .iter()
+            .find(|(env_name, _)| env_name == name)
+            .map(|(_, value)| value);
+        anyhow::ensure!(
+            value == Some(expected_value),
+            "expected ${name} to be {expected_value:?}, got {value:?}"
+        );
+    }
+    Ok(())
+}
+
+async fn test_slash_command(
+    extension: &WasmExtension,
+    manifest: &ExtensionManifest,
+    test: &SlashCommandTest,
+    worktree: Option<Arc<dyn WorktreeDelegate>>,
+) -> Result<()> {
+    let entry = manifest
+        .slash_commands
+        .get(test.name.as_str())
+        .context("slash command is not declared in extension.toml")?;
+    let command = SlashCommand {
+        name: test.name.clone(),
+        description: entry.description.clone(),
+        tooltip_text: entry.description.clone(),
+        requires_argument: entry.requires_argument,
+    };
+
+    let result = extension
+        .run_slash_command(command, test.arguments.clone(), worktree)
+        .await;
+    let output = match (result, &test.expect.error) {
+        (Ok(output), None) => output,
+        (Err(error), Some(expected_error)) => {
+            return expect_error(&format!("{error:#}"), expected_error);
+        }
+        (Ok(output), Some(_)) => bail!("expected an error, got {:?}", output.text),
+        (Err(error), None) => return Err(error),
+    };
+
+    if let Some(expected_text) = &test.expect.text {
+        anyhow::ensure!(
+            &output.text == expected_text,
+            "expected text {expected_text:?}, got {:?}",
+            output.text
+        );
+    }
+    if let Some(expected_sections) = &test.expect.sections {
+        let sections = output
+            .sections
+            .iter()
+            .map(|section| section.label.as_str())
+            .collect::<Vec<_>>();
+        anyhow::ensure!(
+            sections == *expected_sections,
+            "expected sections {expected_sections:?}, got {sections:?}"
+        );
+    }
+    Ok(())
+}
+
+fn expect_error(error: &str, expected_error: &str) -> Result<()> {
+    anyhow::ensure!(
+        error.contains(expected_error),
+        "expected an error containing {expected_error:?}, got {error:?}"
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_spec() {
+        let spec = toml::from_str::<TestSpec>(
+            r#"
+            worktree = "fixtures/project"
+
+            [which]
+            my-server = "/usr/local/bin/my-server"
+
+            [env]
+            PATH = "/usr/bin"
+
+            [[language_servers]]
+            id = "my-server"
+            language = "My Language"
+
+            [language_servers.expect]
+            command = "/usr/local/bin/my-server"
+            args = ["--stdio"]
+            env = { RUST_LOG = "info" }
+
+            [[language_servers]]
+            id = "my-server"
+            language = "Other Language"
+            expect = { error = "must be installed" }
+
+            [[slash_commands]]
+            name = "echo"
+            arguments = ["hello", "world"]
+
+            [slash_commands.expect]
+            text = "hello world"
+            sections = ["Echo"]
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(spec.worktree, Some(PathBuf::from("fixtures/project")));
+        assert_eq!(spec.which["my-server"], "/usr/local/bin/my-server");
+        assert_eq!(spec.env["PATH"], "/usr/bin");
+
+        let [language_server, failing_language_server] = spec.language_servers.as_slice() else {
+            panic!("expected two language server tests");
+        };
+        assert_eq!(language_server.id, "my-server");
+        assert_eq!(language_server.language, "My Language");
+        assert_eq!(
+            language_server.expect.command,
+            Some(PathBuf::from("/usr/local/bin/my-server"))
+        );
+        assert_eq!(language_server.expect.args, Some(vec!["--stdio".into()]));
+        assert_eq!(language_server.expect.env["RUST_LOG"], "info");
+        assert_eq!(language_server.expect.error, None);
+        assert_eq!(failing_language_server.expect.command, None);
+        assert_eq!(
+            failing_language_server.expect.error.as_deref(),
+            Some("must be installed")
+        );
+
+        let [slash_command] = spec.slash_commands.as_slice() else {
+            panic!("expected one slash command test");
+        };
+        assert_eq!(slash_command.name, "echo");
+        assert_eq!(slash_command.arguments, ["hello", "world"]);
+        assert_eq!(slash_command.expect.text.as_deref(), Some("hello world"));
+        assert_eq!(slash_command.expect.sections, Some(vec!["Echo".into()]));
+    }
+
+    #[test]
+    fn test_parse_minimal_spec() {
+        let spec = toml::from_str::<TestSpec>(
+            r#"
+            [[slash_commands]]
+            name = "echo"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(spec.worktree, None);
+        assert!(spec.which.is_empty());
+        assert!(spec.language_servers.is_empty());
+        assert!(spec.slash_commands[0].arguments.is_empty());
+        assert_eq!(spec.slash_commands[0].expect.text, None);
+    }
+
+    #[test]
+    fn test_parse_spec_with_unknown_keys() {
+        // Misspelled keys are rejected rather than silently skipping the expectation.
+        let error = toml::from_str::<TestSpec>("worktrees = \"fixtures\"\n").unwrap_err();
+        assert!(error.to_string().contains("unknown field `worktrees`"));
+
+        let error = toml::from_str::<TestSpec>(
+            r#"
+            [[language_servers]]
+            id = "my-server"
+            language = "My Language"
+            expect = { comand = "my-server" }
+            "#,
+        )
+        .unwrap_err();
+        assert!(error.to_string().contains("unknown field `comand`"));
+    }
+
+    #[test]
+    fn test_expect_error() {
+        assert!(expect_error("my-server must be installed", "must be installed").is_ok());
+        assert!(expect_error("permission denied", "must be installed").is_err());
+    }
+}
@@ -0,0 +1,554 @@
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use ::fs::{Fs, RealFs};
+use anyhow::{Context as _, Result, bail};
+use extension::extension_builder::populate_defaults;
+use extension::{ExtensionCapability, ExtensionLibraryKind, ExtensionManifest};
+use language::LanguageConfig;
+
+#[derive(clap::Args, Debug)]
+pub struct LintArgs {
+    /// The path to the extension directory
+    #[arg(default_value = ".")]
+    source_dir: PathBuf,
+}
+
+/// Keys that [`ExtensionManifest`] accepts but does not serialize back, with `*` matching any key.
+const TOLERATED_KEYS: &[&[&str]] = &[
+    // Alias of `rev`.
+    &["grammars", "*", "commit"],
+    // Documented for readability, but unused.
+    &["language_servers", "*", "name"],
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Severity {
+    Warning,
+    Error,
+}
+
+#[derive(Debug)]
+pub(crate) struct Diagnostic {
+    severity: Severity,
+    message: String,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.severity {
+            Severity::Warning => write!(f, "warning: {}", self.message),
+            Severity::Error => write!(f, "error: {}", self.message),
+        }
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct Diagnostics(Vec<Diagnostic>);
+
+impl Diagnostics {
+    pub(crate) fn errors(&self) -> impl Iterator<Item = &Diagnostic> {
+        self.0
+            .iter()
+            .filter(|diagnostic| diagnostic.severity == Severity::Error)
+    }
+
+    fn error(&mut self, message: impl Into<String>) {
+        self.0.push(Diagnostic {
+            severity: Severity::Error,
+            message: message.into(),
+        });
+    }
+
+    fn warning(&mut self, message: impl Into<String>) {
+        self.0.push(Diagnostic {
+            severity: Severity::Warning,
+            message: message.into(),
+        });
+    }
+}
+
+pub async fn run(args: LintArgs) -> Result<()> {
+    let fs = Arc::new(RealFs::new(None, gpui::background_executor()));
+    let extension_path = args
+        .source_dir
+        .canonicalize()
+        .context("failed to canonicalize source_dir")?;
+
+    let diagnostics = lint_extension(&extension_path, fs).await?;
+    for diagnostic in &diagnostics.0 {
+        eprintln!("{diagnostic}");
+    }
+
+    let error_count = diagnostics.errors().count();
+    if error_count > 0 {
+        bail!(
+            "found {error_count} error(s) in {}",
+            extension_path.display()
+        );
+    }
+    Ok(())
+}
+
+pub(crate) async fn lint_extension(extension_path: &Path, fs: Arc<dyn Fs>) -> Result<Diagnostics> {
+    let mut diagnostics = Diagnostics::default();
+
+    let manifest_path = extension_path.join("extension.toml");
+    if !fs.is_file(&manifest_path).await {
+        diagnostics.error(format!(
+            "no extension.toml found in {}",
+            extension_path.display()
+        ));
+        return Ok(diagnostics);
+    }
+    let manifest_content = fs
+        .load(&manifest_path)
+        .await
+        .context("failed to read extension.toml")?;
+    let raw_manifest = match toml::from_str::<toml::Value>(&manifest_content) {
+        Ok(raw_manifest) => raw_manifest,
+        Err(error) => {
+            diagnostics.error(format!("extension.toml is not valid TOML: {error}"));
+            return Ok(diagnostics);
+        }
+    };
+    let mut manifest = match toml::from_str::<ExtensionManifest>(&manifest_content) {
+        Ok(manifest) => manifest,
+        Err(error) => {
+            diagnostics.error(format!("invalid extension.toml: {error}"));
+            return Ok(diagnostics);
+        }
+    };
+
+    for key in unknown_keys(&raw_manifest, &manifest)? {
+        diagnostics.error(format!("unknown key `{key}` in extension.toml"));
+    }
+
+    populate_defaults(&mut manifest, extension_path, fs.clone()).await?;
+
+    check_metadata(&manifest, &mut diagnostics);
+    check_lib(&manifest, extension_path, fs.as_ref(), &mut diagnostics).await;
+    check_languages(&manifest, extension_path, fs.as_ref(), &mut diagnostics).await;
+    check_files(&manifest, extension_path, fs.as_ref(), &mut diagnostics).await;
+    check_capabilities(&manifest, &mut diagnostics);
+
+    Ok(diagnostics)
+}
+
+/// Returns the dotted paths of the keys in `raw_manifest` that [`ExtensionManifest`] ignored.
+fn unknown_keys(raw_manifest: &toml::Value, manifest: &ExtensionManifest) -> Result<Vec<String>> {
+    let known_manifest =
+        toml::Value::try_from(manifest).context("failed to serialize extension manifest")?;
+    let mut unknown_keys = Vec::new();
+    collect_unknown_keys(
+        raw_manifest,
+        &known_manifest,
+        &mut Vec::new(),
+        &mut unknown_keys,
+    );
+    Ok(unknown_keys)
+}
+
+fn collect_unknown_keys(
+    raw: &toml::Value,
+    known: &toml::Value,
+    path: &mut Vec<String>,
+    unknown_keys: &mut Vec<String>,
+) {
+    match (raw, known) {
+        (toml::Value::Table(raw), toml::Value::Table(known)) => {
+            for (key, raw_value) in raw {
+                path.push(key.clone());
+                match known.get(key) {
+                    Some(known_value) => {
+                        collect_unknown_keys(raw_value, known_value, path, unknown_keys)
+                    }
+                    None if !is_tolerated_key(path) => unknown_keys.push(path.join(".")),
+                    None => {}
+                }
+                path.pop();
+            }
+        }
+        (toml::Value::Array(raw), toml::Value::Array(known)) => {
+            for (ix, (raw_value, known_value)) in raw.iter().zip(known).enumerate() {
+                path.push(ix.to_string());
+                collect_unknown_keys(raw_value, known_value, path, unknown_keys);
+                path.pop();
+            }
+        }
+        _ => {}
+    }
+}
+
+fn is_tolerated_key(path: &[String]) -> bool {
+    TOLERATED_KEYS.iter().any(|pattern| {
+        pattern.len() == path.len()
+            && pattern
+                .iter()
+                .zip(path)
+                .all(|(pattern, key)| *pattern == "*" || *pattern == key.as_str())
+    })
+}
+
+fn check_metadata(manifest: &ExtensionManifest, diagnostics: &mut Diagnostics) {
+    let id = manifest.id.as_ref();
+    if id.is_empty()
+        || !id
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+    {
+        diagnostics.error(format!(
+            "extension id `{id}` must only contain lowercase letters, digits and dashes"
+        ));
+    }
+    if id.contains("zed") {
+        diagnostics.warning(format!(
+            "extension id `{id}` should not contain `zed`, as all extensions are for Zed"
+        ));
+    }
+    if semver::Version::parse(&manifest.version).is_err() {
+        diagnostics.error(format!(
+            "version `{}` is not a valid semantic version",
+            manifest.version
+        ));
+    }
+    if manifest.schema_version.is_v0() {
+        diagnostics.warning("`schema_version` should be set to 1");
+    }
+    if manifest.repository.is_none() {
+        diagnostics.warning("`repository` is required to publish the extension");
+    }
+}
+
+async fn check_lib(
+    manifest: &ExtensionManifest,
+    extension_path: &Path,
+    fs: &dyn Fs,
+    diagnostics: &mut Diagnostics,
+) {
+    let wasm_features = [
+        ("language_servers", manifest.language_servers.is_empty()),
+        ("context_servers", manifest.context_servers.is_empty()),
+        ("slash_commands", manifest.slash_commands.is_empty()),
+        ("debug_adapters", manifest.debug_adapters.is_empty()),
+        ("debug_locators", manifest.debug_locators.is_empty()),
+        ("task_providers", manifest.task_providers.is_empty()),
+        ("formatters", manifest.formatters.is_empty()),
+        (
+            "code_action_providers",
+            manifest.code_action_providers.is_empty(),
+        ),
+        (
+            "language_model_providers",
+            manifest.language_model_providers.is_empty(),
+        ),
+    ];
+
+    match manifest.lib.kind {
+        Some(ExtensionLibraryKind::Rust) => {
+            if !fs.is_file(&extension_path.join("Cargo.toml")).await {
+                diagnostics.error("`lib.kind` is `Rust`, but there is no Cargo.toml");
+            }
+        }
+        None => {
+            for (feature, is_empty) in wasm_features {
+                if !is_empty {
+                    diagnostics.error(format!(
+                        "`{feature}` must be implemented in Rust, but there is no Cargo.toml"
+                    ));
+                }
+            }
+        }
+    }
+}
+
+async fn check_languages(
+    manifest: &ExtensionManifest,
+    extension_path: &Path,
+    fs: &dyn Fs,
+    diagnostics: &mut Diagnostics,
+) {
+    let mut used_grammars = Vec::new();
+    for language_path in &manifest.languages {
+        let config_path = extension_path.join(language_path).join("config.toml");
+        let Ok(config_content) = fs.load(&config_path).await else {
+            diagnostics.error(format!(
+                "language directory `{}` has no config.toml",
+                language_path.display()
+            ));
+            continue;
+        };
+        let config = match toml::from_str::<LanguageConfig>(&config_content) {
+            Ok(config) => config,
+            Err(error) => {
+                diagnostics.error(format!(
+                    "invalid `{}`: {error}",
+                    language_path.join("config.toml").display()
+                ));
+                continue;
+            }
+        };
+
+        if let Some(grammar) = &config.grammar {
+            if !manifest.grammars.contains_key(grammar) {
+                diagnostics.error(format!(
+                    "language `{}` uses the grammar `{grammar}`, which is not declared in `[grammars]`",
+                    config.name
+                ));
+            }
+            used_grammars.push(grammar.clone());
+        }
+    }
+
+    for (grammar_name, grammar) in &manifest.grammars {
+        if !used_grammars.contains(grammar_name) {
+            diagnostics.warning(format!(
+                "grammar `{grammar_name}` is not used by any language"
+            ));
+        }
+        if grammar.rev.len() != 40 || !grammar.rev.chars().all(|c| c.is_ascii_hexdigit()) {
+            diagnostics.warning(format!(
+                "grammar `{grammar_name}` should be pinned to a full commit SHA, not `{}`",
+                grammar.rev
+            ));
+        }
+    }
+
+    for (language_server_name, language_server) in &manifest.language_servers {
+        if language_server.languages().into_iter().next().is_none() {
+            diagnostics.error(format!(
+                "language server `{language_server_name}` does not list any `languages`"
+            ));
+        }
+    }
+}
+
+async fn check_files(
+    manifest: &ExtensionManifest,
+    extension_path: &Path,
+    fs: &dyn Fs,
+    diagnostics: &mut Diagnostics,
+) {
+    let mut paths = Vec::new();
+    paths.extend(manifest.themes.iter().cloned());
+    paths.extend(manifest.icon_themes.iter().cloned());
+    paths.extend(manifest.snippets.iter().cloned());
+    for (debug_adapter_name, debug_adapter) in &manifest.debug_adapters {
+        paths.push(extension::build_debug_adapter_schema_path(
+            debug_adapter_name,
+            debug_adapter,
+        ));
+    }
+
+    for path in paths {
+        if !fs.is_file(&extension_path.join(&path)).await {
+            diagnostics.error(format!("`{}` does not exist", path.display()));
+        }
+    }
+}
+
+fn check_capabilities(manifest: &ExtensionManifest, diagnostics: &mut Diagnostics) {
+    for capability in &manifest.capabilities {
+        match capability {
+            ExtensionCapability::ProcessExec(capability) => {
+                if capability.command.is_empty() {
+                    diagnostics.error("`process:exec` capability has an empty `command`");
+                } else if capability.command == "*" {
+                    diagnostics.warning("`process:exec` capability allows running any command");
+                }
+                if let Some(ix) = capability.args.iter().position(|arg| arg == "**")
+                    && ix + 1 != capability.args.len()
+                {
+                    diagnostics.error(format!(
+                        "`process:exec` capability for `{}` uses `**` before its last argument",
+                        capability.command
+                    ));
+                }
+            }
+            ExtensionCapability::DownloadFile(capability) => {
+                if capability.host.is_empty() {
+                    diagnostics.error("`download_file` capability has an empty `host`");
+                } else if capability.host == "*" {
+                    diagnostics
+                        .warning("`download_file` capability allows downloading from any host");
+                }
+                if let Some(ix) = capability.path.iter().position(|segment| segment == "**")
+                    && ix + 1 != capability.path.len()
+                {
+                    diagnostics.error(format!(
+                        "`download_file` capability for `{}` uses `**` before its last path segment",
+                        capability.host
+                    ));
+                }
+            }
+            ExtensionCapability::NpmInstallPackage(capability) => {
+                if capability.package.is_empty() {
+                    diagnostics.error("`npm:install` capability has an empty `package`");
+                } else if capability.package == "*" {
+                    diagnostics.warning("`npm:install` capability allows installing any package");
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+
+    async fn lint(files: &[(&str, &str)], cx: &mut TestAppContext) -> Vec<String> {
+        cx.executor().allow_parking();
+        let dir = tempfile::tempdir().unwrap();
+        for (path, content) in files {
+            let path = dir.path().join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        }
+        let fs = Arc::new(RealFs::new(None, cx.executor()));
+        lint_extension(dir.path(), fs)
+            .await
+            .unwrap()
+            .0
+            .iter()
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    const MANIFEST: &str = r#"
+        id = "my-extension"
+        name = "My Extension"
+        version = "0.1.0"
+        schema_version = 1
+        repository = "https://github.com/example/my-extension"
+    "#;
+
+    #[gpui::test]
+    async fn test_missing_grammar(cx: &mut TestAppContext) {
+        let diagnostics = lint(
+            &[
+                ("extension.toml", MANIFEST),
+                (
+                    "languages/my-language/config.toml",
+                    "name = \"My Language\"\ngrammar = \"my-language\"\n",
+                ),
+            ],
+            cx,
+        )
+        .await;
+        assert_eq!(
+            diagnostics,
+            [
+                "error: language `My Language` uses the grammar `my-language`, which is not declared in `[grammars]`"
+            ]
+        );
+    }
+
+    #[gpui::test]
+    async fn test_missing_files(cx: &mut TestAppContext) {
+        let manifest = format!("{MANIFEST}\nthemes = [\"themes/missing.json\"]\n");
+        let diagnostics = lint(&[("extension.toml", &manifest)], cx).await;
+        assert_eq!(diagnostics, ["error: `themes/missing.json` does not exist"]);
+    }
+
+    #[gpui::test]
+    async fn test_wasm_features_without_lib(cx: &mut TestAppContext) {
+        let manifest =
+            format!("{MANIFEST}\n[language_servers.my-server]\nlanguages = [\"My Language\"]\n");
+        let diagnostics = lint(&[("extension.toml", &manifest)], cx).await;
+        assert_eq!(
+            diagnostics,
+            ["error: `language_servers` must be implemented in Rust, but there is no Cargo.toml"]
+        );
+    }
+
+    #[gpui::test]
+    async fn test_capabilities(cx: &mut TestAppContext) {
+        let manifest = format!(
+            r#"{MANIFEST}
+            [[capabilities]]
+            kind = "process:exec"
+            command = ""
+            args = []
+
+            [[capabilities]]
+            kind = "process:exec"
+            command = "cargo"
+            args = ["**", "build"]
+
+            [[capabilities]]
+            kind = "download_file"
+            host = "*"
+            path = ["releases", "**"]
+
+            [[capabilities]]
+            kind = "npm:install"
+            package = ""
+            "#
+        );
+        let diagnostics = lint(&[("extension.toml", &manifest)], cx).await;
+        assert_eq!(
+            diagnostics,
+            [
+                "error: `process:exec` capability has an empty `command`",
+                "error: `process:exec` capability for `cargo` uses `**` before its last argument",
+                "warning: `download_file` capability allows downloading from any host",
+                "error: `npm:install` capability has an empty `package`",
+            ]
+        );
+    }
+
+    fn unknown_keys_in(manifest_content: &str) -> Vec<String> {
+        let raw_manifest = toml::from_str::<toml::Value>(manifest_content).unwrap();
+        let manifest = toml::from_str::<ExtensionManifest>(manifest_content).unwrap();
+        unknown_keys(&raw_manifest, &manifest).unwrap()
+    }
+
+    #[test]
+    fn test_unknown_keys() {
+        let manifest_content = r#"
+            id = "my-extension"
+            name = "My Extension"
+            version = "0.1.0"
+            schema_version = 1
+            licence = "MIT"
+
+            [grammars.my-language]
+            repository = "https://github.com/example/tree-sitter-my-language"
+            commit = "0123456789abcdef0123456789abcdef01234567"
+
+            [language_servers.my-language-server]
+            name = "My Language Server"
+            languages = ["My Language"]
+            language_id = "my-language"
+
+            [[capabilities]]
+            kind = "process:exec"
+            command = "my-language-server"
+            args = ["**"]
+            env = []
+        "#;
+
+        assert_eq!(
+            unknown_keys_in(manifest_content),
+            [
+                "capabilities.0.env",
+                "language_servers.my-language-server.language_id",
+                "licence",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_no_unknown_keys_in_minimal_manifest() {
+        let manifest_content = r#"
+            id = "my-theme"
+            name = "My Theme"
+            version = "0.1.0"
+            schema_version = 1
+        "#;
+
+        assert!(unknown_keys_in(manifest_content).is_empty());
+    }
+}
@@ -1,3 +1,7 @@
+mod harness;
+mod lint;
+mod scaffold;
+
 use std::collections::{BTreeSet, HashMap};
 use std::env;
 use std::fs;
@@ -6,7 +10,7 @@ use std::sync::Arc;
 
 use ::fs::{CopyOptions, Fs, RealFs, copy_recursive};
 use anyhow::{Context as _, Result, bail};
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use extension::ExtensionManifest;
 use extension::extension_builder::{CompileExtensionOptions, ExtensionBuilder};
 use language::LanguageConfig;
@@ -16,65 +20,84 @@ use tokio::process::Command;
 use tree_sitter::{Language, Query, WasmStore};
 
 #[derive(Parser, Debug)]
-#[command(name = "zed-extension")]
+#[command(
+    name = "zed-extension",
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true
+)]
 struct Args {
+    #[command(subcommand)]
+    command: Option<CliCommand>,
     /// The path to the extension directory
-    #[arg(long)]
-    source_dir: PathBuf,
+    #[arg(long, required = true)]
+    source_dir: Option<PathBuf>,
     /// The output directory to place the packaged extension.
-    #[arg(long)]
-    output_dir: PathBuf,
+    #[arg(long, required = true)]
+    output_dir: Option<PathBuf>,
     /// The path to a directory where build dependencies are downloaded
-    #[arg(long)]
-    scratch_dir: PathBuf,
+    #[arg(long, required = true)]
+    scratch_dir: Option<PathBuf>,
 }
 
-#[tokio::main]
-async fn main() -> Result<()> {
+#[derive(Subcommand, Debug)]
+enum CliCommand {
+    /// Create a new extension from a template.
+    New(scaffold::NewArgs),
+    /// Check an extension's `extension.toml` for mistakes.
+    Lint(lint::LintArgs),
+    /// Compile an extension and run its tests against a fixture worktree.
+    Test(harness::TestArgs),
+}
+
+fn main() -> Result<()> {
     env_logger::init();
 
     let args = Args::parse();
+    match args.command {
+        Some(CliCommand::New(args)) => scaffold::run(args),
+        Some(CliCommand::Lint(args)) => tokio::runtime::Runtime::new()?.block_on(lint::run(args)),
+        Some(CliCommand::Test(args)) => harness::run(args),
+        None => {
+            let (Some(source_dir), Some(output_dir), Some(scratch_dir)) =
+                (args.source_dir, args.output_dir, args.scratch_dir)
+            else {
+                bail!("--source-dir, --output-dir and --scratch-dir are required");
+            };
+            tokio::runtime::Runtime::new()?.block_on(package(source_dir, output_dir, scratch_dir))
+        }
+    }
+}
+
+/// Compiles, tests and packages the extension into `archive.tar.gz` and `manifest.json`.
+async fn package(source_dir: PathBuf, output_dir: PathBuf, scratch_dir: PathBuf) -> Result<()> {
     let fs = Arc::new(RealFs::new(None, gpui::background_executor()));
     let engine = wasmtime::Engine::default();
     let mut wasm_store = WasmStore::new(&engine)?;
 
-    let extension_path = args
-        .source_dir
+    let extension_path = source_dir
         .canonicalize()
         .context("failed to canonicalize source_dir")?;
-    let scratch_dir = args
-        .scratch_dir
+    let scratch_dir = scratch_dir
         .canonicalize()
         .context("failed to canonicalize scratch_dir")?;
-    let output_dir = if args.output_dir.is_relative() {
-        env::current_dir()?.join(&args.output_dir)
+    let output_dir = if output_dir.is_relative() {
+        env::current_dir()?.join(&output_dir)
     } else {
-        args.output_dir
+        output_dir
     };
 
     log::info!("loading extension manifest");
     let mut manifest = ExtensionManifest::load(fs.clone(), &extension_path).await?;
 
     log::info!("compiling extension");
-
-    let user_agent = format!(
-        "Zed Extension CLI/{} ({}; {})",
-        env!("CARGO_PKG_VERSION"),
-        std::env::consts::OS,
-        std::env::consts::ARCH
-    );
-    let http_client = Arc::new(ReqwestClient::user_agent(&user_agent)?);
-
-    let builder = ExtensionBuilder::new(http_client, scratch_dir);
-    builder
-        .compile_extension(
-            &extension_path,
-            &mut manifest,
-            CompileExtensionOptions { release: true },
-            fs.clone(),
-        )
-        .await
-        .context("failed to compile extension")?;
+    compile_extension(
+        &extension_path,
+        &mut manifest,
+        scratch_dir,
+        CompileExtensionOptions { release: true },
+        fs.clone(),
+    )
+    .await?;
 
     let grammars = test_grammars(&manifest, &extension_path, &mut wasm_store)?;
     test_languages(&manifest, &extension_path, &grammars)?;
@@ -119,6 +142,30 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
+fn user_agent() -> String {
+    format!(
+        "Zed Extension CLI/{} ({}; {})",
+        env!("CARGO_PKG_VERSION"),
+        std::env::consts::OS,
+        std::env::consts::ARCH
+    )
+}
+
+async fn compile_extension(
+    extension_path: &Path,
+    manifest: &mut ExtensionManifest,
+    scratch_dir: PathBuf,
+    options: CompileExtensionOptions,
+    fs: Arc<dyn Fs>,
+) -> Result<()> {
+    let http_client = Arc::new(ReqwestClient::user_agent(&user_agent())?);
+    let builder = ExtensionBuilder::new(http_client, scratch_dir);
+    builder
+        .compile_extension(extension_path, manifest, options, fs)
+        .await
+        .context("failed to compile extension")
+}
+
 /// Returns the set of features provided by the extension.
 fn extension_provides(manifest: &ExtensionManifest) -> BTreeSet<ExtensionProvides> {
     let mut provides = BTreeSet::default();
@@ -0,0 +1,426 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context as _, Result, bail};
+use clap::ValueEnum;
+
+/// The version of `zed_extension_api` that new extensions depend on, which is the latest one
+/// that stable releases of Zed can load.
+const EXTENSION_API_VERSION: &str = "0.7.0";
+
+#[derive(clap::Args, Debug)]
+pub struct NewArgs {
+    /// The directory to create the extension in
+    path: PathBuf,
+    /// The kind of extension to create
+    #[arg(long, value_enum, default_value_t = Template::Language)]
+    template: Template,
+    /// The ID of the extension. Defaults to the name of the directory.
+    #[arg(long)]
+    id: Option<String>,
+    /// The display name of the extension. Defaults to the ID in title case.
+    #[arg(long)]
+    name: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum Template {
+    /// A language with a Tree-sitter grammar and highlights.
+    Language,
+    /// A language server for an existing language.
+    LanguageServer,
+    /// A theme family.
+    Theme,
+    /// An assistant slash command.
+    SlashCommand,
+}
+
+struct TemplateContext {
+    id: String,
+    name: String,
+    /// The ID with dashes replaced by underscores, for use as a Rust crate name.
+    crate_name: String,
+    /// The name with spaces removed, for use as a Rust type name.
+    type_name: String,
+}
+
+pub fn run(args: NewArgs) -> Result<()> {
+    if args.path.exists()
+        && fs::read_dir(&args.path)
+            .with_context(|| format!("failed to read {}", args.path.display()))?
+            .next()
+            .is_some()
+    {
+        bail!("{} already exists and is not empty", args.path.display());
+    }
+
+    let id = match args.id {
+        Some(id) => id,
+        None => args
+            .path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .context("cannot infer the extension ID from the path, pass --id")?
+            .to_lowercase()
+            .replace(['_', ' '], "-"),
+    };
+    let name = args.name.unwrap_or_else(|| title_case(&id));
+    let cx = TemplateContext {
+        crate_name: id.replace('-', "_"),
+        type_name: name.chars().filter(char::is_ascii_alphanumeric).collect(),
+        id,
+        name,
+    };
+
+    let files = match args.template {
+        Template::Language => language_template(&cx),
+        Template::LanguageServer => language_server_template(&cx),
+        Template::Theme => theme_template(&cx),
+        Template::SlashCommand => slash_command_template(&cx),
+    };
+    for (relative_path, content) in files {
+        write_file(&args.path, &relative_path, &content)?;
+    }
+
+    println!("Created extension `{}` in {}", cx.id, args.path.display());
+    Ok(())
+}
+
+fn write_file(extension_path: &Path, relative_path: &str, content: &str) -> Result<()> {
+    let path = extension_path.join(relative_path);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("failed to create {}", parent.display()))?;
+    }
+    fs::write(&path, content).with_context(|| format!("failed to write {}", path.display()))
+}
+
+fn title_case(id: &str) -> String {
+    id.split('-')
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                .unwrap_or_default()
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn extension_toml(cx: &TemplateContext, description: &str, sections: &str) -> String {
+    format!(
+        r#"id = "{id}"
+name = "{name}"
+version = "0.0.1"
+schema_version = 1
+authors = ["Your Name <you@example.com>"]
+description = "{description}"
+repository = "https://github.com/your-name/{id}"
+{sections}"#,
+        id = cx.id,
+        name = cx.name,
+    )
+}
+
+fn cargo_toml(cx: &TemplateContext) -> String {
+    format!(
+        r#"[package]
+name = "{crate_name}"
+version = "0.0.1"
+edition = "2021"
+publish = false
+
+[lib]
+crate-type = ["cdylib"]
+
+[dependencies]
+zed_extension_api = "{EXTENSION_API_VERSION}"
+"#,
+        crate_name = cx.crate_name,
+    )
+}
+
+fn gitignore(has_lib: bool) -> String {
+    let mut gitignore = String::from("/grammars\n");
+    if has_lib {
+        gitignore.push_str("/target\n/extension.wasm\n");
+    }
+    gitignore
+}
+
+fn language_template(cx: &TemplateContext) -> Vec<(String, String)> {
+    let sections = format!(
+        r#"
+# Replace with the repository of your grammar, and pin `rev` to a full commit SHA.
+[grammars.{id}]
+repository = "https://github.com/your-name/tree-sitter-{id}"
+rev = "main"
+"#,
+        id = cx.id,
+    );
+    vec![
+        (
+            "extension.toml".into(),
+            extension_toml(cx, &format!("{} language support.", cx.name), &sections),
+        ),
+        (
+            format!("languages/{}/config.toml", cx.id),
+            format!(
+                r#"name = "{name}"
+grammar = "{id}"
+path_suffixes = ["{id}"]
+line_comments = ["# "]
+tab_size = 4
+"#,
+                id = cx.id,
+                name = cx.name,
+            ),
+        ),
+        (
+            format!("languages/{}/highlights.scm", cx.id),
+            "(comment) @comment\n".into(),
+        ),
+        (".gitignore".into(), gitignore(false)),
+    ]
+}
+
+fn language_server_template(cx: &TemplateContext) -> Vec<(String, String)> {
+    let sections = format!(
+        r#"
+[language_servers.{id}]
+name = "{name}"
+# Replace with the languages the language server supports.
+languages = ["{name}"]
+"#,
+        id = cx.id,
+        name = cx.name,
+    );
+    vec![
+        (
+            "extension.toml".into(),
+            extension_toml(cx, &format!("The {} language server.", cx.name), &sections),
+        ),
+        ("Cargo.toml".into(), cargo_toml(cx)),
+        (
+            "src/lib.rs".into(),
+            format!(
+                r#"use zed_extension_api::{{self as zed, LanguageServerId, Result}};
+
+struct {type_name}Extension;
+
+impl zed::Extension for {type_name}Extension {{
+    fn new() -> Self {{
+        Self
+    }}
+
+    fn language_server_command(
+        &mut self,
+        _language_server_id: &LanguageServerId,
+        worktree: &zed::Worktree,
+    ) -> Result<zed::Command> {{
+        let command = worktree
+            .which("{id}")
+            .ok_or_else(|| "{id} must be installed and available on $PATH".to_string())?;
+        Ok(zed::Command {{
+            command,
+            args: vec!["--stdio".into()],
+            env: worktree.shell_env(),
+        }})
+    }}
+}}
+
+zed::register_extension!({type_name}Extension);
+"#,
+                id = cx.id,
+                type_name = cx.type_name,
+            ),
+        ),
+        (
+            "tests/extension_test.toml".into(),
+            format!(
+                r#"worktree = "fixtures/project"
+
+[which]
+{id} = "/usr/local/bin/{id}"
+
+[[language_servers]]
+id = "{id}"
+language = "{name}"
+
+[language_servers.expect]
+command = "/usr/local/bin/{id}"
+args = ["--stdio"]
+"#,
+                id = cx.id,
+                name = cx.name,
+            ),
+        ),
+        ("tests/fixtures/project/.gitkeep".into(), String::new()),
+        (".gitignore".into(), gitignore(true)),
+    ]
+}
+
+fn theme_template(cx: &TemplateContext) -> Vec<(String, String)> {
+    vec![
+        (
+            "extension.toml".into(),
+            extension_toml(cx, &format!("The {} theme.", cx.name), ""),
+        ),
+        (
+            format!("themes/{}.json", cx.id),
+            format!(
+                r##"{{
+  "$schema": "https://zed.dev/schema/themes/v0.2.0.json",
+  "name": "{name}",
+  "author": "Your Name",
+  "themes": [
+    {{
+      "name": "{name} Dark",
+      "appearance": "dark",
+      "style": {{
+        "background": "#1e1e1eff",
+        "editor.background": "#1e1e1eff",
+        "text": "#d4d4d4ff",
+        "syntax": {{
+          "comment": {{ "color": "#6a9955ff" }},
+          "keyword": {{ "color": "#569cd6ff" }},
+          "string": {{ "color": "#ce9178ff" }}
+        }}
+      }}
+    }}
+  ]
+}}
+"##,
+                name = cx.name,
+            ),
+        ),
+        (".gitignore".into(), gitignore(false)),
+    ]
+}
+
+fn slash_command_template(cx: &TemplateContext) -> Vec<(String, String)> {
+    let sections = r#"
+[slash_commands.echo]
+description = "echoes the provided input"
+requires_argument = true
+"#;
+    vec![
+        (
+            "extension.toml".into(),
+            extension_toml(cx, "Slash commands for the assistant.", sections),
+        ),
+        ("Cargo.toml".into(), cargo_toml(cx)),
+        (
+            "src/lib.rs".into(),
+            format!(
+                r#"use zed_extension_api::{{
+    self as zed, SlashCommand, SlashCommandOutput, SlashCommandOutputSection, Worktree,
+}};
+
+struct {type_name}Extension;
+
+impl zed::Extension for {type_name}Extension {{
+    fn new() -> Self {{
+        Self
+    }}
+
+    fn run_slash_command(
+        &self,
+        command: SlashCommand,
+        args: Vec<String>,
+        _worktree: Option<&Worktree>,
+    ) -> Result<SlashCommandOutput, String> {{
+        match command.name.as_str() {{
+            "echo" => {{
+                if args.is_empty() {{
+                    return Err("nothing to echo".to_string());
+                }}
+
+                let text = args.join(" ");
+                Ok(SlashCommandOutput {{
+                    sections: vec![SlashCommandOutputSection {{
+                        range: (0..text.len()).into(),
+                        label: "Echo".to_string(),
+                    }}],
+                    text,
+                }})
+            }}
+            command => Err(format!("unknown slash command: \"{{command}}\"")),
+        }}
+    }}
+}}
+
+zed::register_extension!({type_name}Extension);
+"#,
+                type_name = cx.type_name,
+            ),
+        ),
+        (
+            "tests/extension_test.toml".into(),
+            r#"[[slash_commands]]
+name = "echo"
+arguments = ["hello", "world"]
+
+[slash_commands.expect]
+text = "hello world"
+sections = ["Echo"]
+"#
+            .into(),
+        ),
+        (".gitignore".into(), gitignore(true)),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::fs::RealFs;
+    use gpui::TestAppContext;
+    use release_channel::ReleaseChannel;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_extension_api_version_is_released() {
+        let version = semver::Version::parse(EXTENSION_API_VERSION).unwrap();
+        assert!(
+            extension_host::wasm_host::wit::is_supported_wasm_api_version(
+                ReleaseChannel::Stable,
+                version
+            )
+        );
+    }
+
+    #[gpui::test]
+    async fn test_scaffolded_extensions_pass_lint(cx: &mut TestAppContext) {
+        cx.executor().allow_parking();
+        let fs = Arc::new(RealFs::new(None, cx.executor()));
+        for template in Template::value_variants() {
+            let dir = tempfile::tempdir().unwrap();
+            let path = dir.path().join("my-extension");
+            run(NewArgs {
+                path: path.clone(),
+                template: *template,
+                id: None,
+                name: None,
+            })
+            .unwrap();
+
+            let diagnostics = crate::lint::lint_extension(&path, fs.clone())
+                .await
+                .unwrap();
+            let errors = diagnostics
+                .errors()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>();
+            assert!(errors.is_empty(), "{template:?}: {errors:?}");
+        }
+    }
+
+    #[test]
+    fn test_title_case() {
+        assert_eq!(title_case("my-language"), "My Language");
+        assert_eq!(title_case("a--b-"), "A B");
+    }
+}
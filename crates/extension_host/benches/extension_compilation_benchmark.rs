@@ -148,6 +148,10 @@ fn manifest() -> ExtensionManifest {
         )],
         debug_adapters: Default::default(),
         debug_locators: Default::default(),
+        task_providers: Default::default(),
+        formatters: Default::default(),
+        code_action_providers: Default::default(),
+        language_model_providers: Default::default(),
     }
 }
 
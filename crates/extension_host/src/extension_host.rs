@@ -15,10 +15,11 @@ use collections::{BTreeMap, BTreeSet, HashSet, btree_map};
 pub use extension::ExtensionManifest;
 use extension::extension_builder::{CompileExtensionOptions, ExtensionBuilder};
 use extension::{
-    ExtensionContextServerProxy, ExtensionDebugAdapterProviderProxy, ExtensionEvents,
-    ExtensionGrammarProxy, ExtensionHostProxy, ExtensionLanguageProxy,
-    ExtensionLanguageServerProxy, ExtensionSlashCommandProxy, ExtensionSnippetProxy,
-    ExtensionThemeProxy,
+    ExtensionCodeActionProviderProxy, ExtensionContextServerProxy,
+    ExtensionDebugAdapterProviderProxy, ExtensionEvents, ExtensionFormatterProxy,
+    ExtensionGrammarProxy, ExtensionHostProxy, ExtensionLanguageModelProviderProxy,
+    ExtensionLanguageProxy, ExtensionLanguageServerProxy, ExtensionSlashCommandProxy,
+    ExtensionSnippetProxy, ExtensionTaskProviderProxy, ExtensionThemeProxy,
 };
 use fs::{Fs, RemoveOptions};
 use futures::future::join_all;
@@ -1217,6 +1218,19 @@ impl ExtensionStore {
             for locator in extension.manifest.debug_locators.keys() {
                 self.proxy.unregister_debug_locator(locator.clone());
             }
+            for provider in extension.manifest.task_providers.keys() {
+                self.proxy.unregister_task_provider(provider.clone());
+            }
+            for formatter in extension.manifest.formatters.keys() {
+                self.proxy.unregister_formatter(formatter.clone());
+            }
+            for provider in extension.manifest.code_action_providers.keys() {
+                self.proxy.unregister_code_action_provider(provider.clone());
+            }
+            for provider_id in extension.manifest.language_model_providers.keys() {
+                self.proxy
+                    .unregister_language_model_provider(provider_id.clone(), cx);
+            }
             for command_name in extension.manifest.slash_commands.keys() {
                 self.proxy.unregister_slash_command(command_name.clone());
             }
@@ -1449,6 +1463,39 @@ impl ExtensionStore {
                         this.proxy
                             .register_debug_locator(extension.clone(), debug_adapter.clone());
                     }
+
+                    for (provider, meta) in &manifest.task_providers {
+                        this.proxy.register_task_provider(
+                            extension.clone(),
+                            provider.clone(),
+                            meta.languages.clone(),
+                        );
+                    }
+
+                    for (formatter, meta) in &manifest.formatters {
+                        this.proxy.register_formatter(
+                            extension.clone(),
+                            formatter.clone(),
+                            meta.languages.clone(),
+                        );
+                    }
+
+                    for (provider, meta) in &manifest.code_action_providers {
+                        this.proxy.register_code_action_provider(
+                            extension.clone(),
+                            provider.clone(),
+                            meta.languages.clone(),
+                        );
+                    }
+
+                    for (provider_id, provider) in &manifest.language_model_providers {
+                        this.proxy.register_language_model_provider(
+                            extension.clone(),
+                            provider_id.clone(),
+                            provider.clone(),
+                            cx,
+                        );
+                    }
                 }
 
                 this.wasm_extensions.extend(wasm_extensions);
@@ -165,6 +165,10 @@ async fn test_extension_store(cx: &mut TestAppContext) {
                         capabilities: Vec::new(),
                         debug_adapters: Default::default(),
                         debug_locators: Default::default(),
+                        task_providers: Default::default(),
+                        formatters: Default::default(),
+                        code_action_providers: Default::default(),
+                        language_model_providers: Default::default(),
                     }),
                     dev: false,
                 },
@@ -196,6 +200,10 @@ async fn test_extension_store(cx: &mut TestAppContext) {
                         capabilities: Vec::new(),
                         debug_adapters: Default::default(),
                         debug_locators: Default::default(),
+                        task_providers: Default::default(),
+                        formatters: Default::default(),
+                        code_action_providers: Default::default(),
+                        language_model_providers: Default::default(),
                     }),
                     dev: false,
                 },
@@ -376,6 +384,10 @@ async fn test_extension_store(cx: &mut TestAppContext) {
                 capabilities: Vec::new(),
                 debug_adapters: Default::default(),
                 debug_locators: Default::default(),
+                task_providers: Default::default(),
+                formatters: Default::default(),
+                code_action_providers: Default::default(),
+                language_model_providers: Default::default(),
             }),
             dev: false,
         },
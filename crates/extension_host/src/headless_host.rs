@@ -4,8 +4,8 @@ use anyhow::{Context as _, Result};
 use client::{TypedEnvelope, proto};
 use collections::{HashMap, HashSet};
 use extension::{
-    Extension, ExtensionDebugAdapterProviderProxy, ExtensionHostProxy, ExtensionLanguageProxy,
-    ExtensionLanguageServerProxy, ExtensionManifest,
+    Extension, ExtensionDebugAdapterProviderProxy, ExtensionFormatterProxy, ExtensionHostProxy,
+    ExtensionLanguageProxy, ExtensionLanguageServerProxy, ExtensionManifest,
 };
 use fs::{Fs, RemoveOptions, RenameOptions};
 use futures::future::join_all;
@@ -32,6 +32,7 @@ pub struct HeadlessExtensionStore {
     pub loaded_extensions: HashMap<Arc<str>, Arc<str>>,
     pub loaded_languages: HashMap<Arc<str>, Vec<LanguageName>>,
     pub loaded_language_servers: HashMap<Arc<str>, Vec<(LanguageServerName, LanguageName)>>,
+    pub loaded_formatters: HashMap<Arc<str>, Vec<Arc<str>>>,
 }
 
 impl HeadlessExtensionStore {
@@ -58,6 +59,7 @@ impl HeadlessExtensionStore {
             loaded_extensions: Default::default(),
             loaded_languages: Default::default(),
             loaded_language_servers: Default::default(),
+            loaded_formatters: Default::default(),
         })
     }
 
@@ -212,6 +214,21 @@ impl HeadlessExtensionStore {
             log::info!("Loaded debug locator: {}", debug_locator);
         }
 
+        for (formatter, meta) in &manifest.formatters {
+            this.update(cx, |this, _cx| {
+                this.loaded_formatters
+                    .entry(manifest.id.clone())
+                    .or_default()
+                    .push(formatter.clone());
+                this.proxy.register_formatter(
+                    wasm_extension.clone(),
+                    formatter.clone(),
+                    meta.languages.clone(),
+                );
+            })?;
+            log::info!("Loaded formatter: {}", formatter);
+        }
+
         Ok(())
     }
 
@@ -228,6 +245,14 @@ impl HeadlessExtensionStore {
             .unwrap_or_default();
         self.proxy.remove_languages(&languages_to_remove, &[]);
 
+        for formatter in self
+            .loaded_formatters
+            .remove(extension_id)
+            .unwrap_or_default()
+        {
+            self.proxy.unregister_formatter(formatter);
+        }
+
         let servers_to_remove = self
             .loaded_language_servers
             .remove(extension_id)
@@ -6,9 +6,10 @@ use anyhow::{Context as _, Result, anyhow, bail};
 use async_trait::async_trait;
 use dap::{DebugRequest, StartDebuggingRequestArgumentsRequest};
 use extension::{
-    CodeLabel, Command, Completion, ContextServerConfiguration, DebugAdapterBinary,
-    DebugTaskDefinition, ExtensionCapability, ExtensionHostProxy, KeyValueStoreDelegate,
-    ProjectDelegate, SlashCommand, SlashCommandArgumentCompletion, SlashCommandOutput, Symbol,
+    CodeAction, CodeLabel, Command, Completion, CompletionEvent, CompletionRequest,
+    ContextServerConfiguration, DebugAdapterBinary, DebugTaskDefinition, ExtensionCapability,
+    ExtensionHostProxy, FormatSettings, KeyValueStoreDelegate, LanguageModelInfo, ProjectDelegate,
+    SlashCommand, SlashCommandArgumentCompletion, SlashCommandOutput, Symbol, TextEdit,
     WorktreeDelegate,
 };
 use fs::{Fs, normalize_path};
@@ -32,6 +33,7 @@ use semver::Version;
 use settings::Settings;
 use std::{
     borrow::Cow,
+    ops::Range,
     path::{Path, PathBuf},
     sync::{
         Arc, LazyLock, OnceLock,
@@ -39,8 +41,8 @@ use std::{
     },
     time::Duration,
 };
-use task::{DebugScenario, SpawnInTerminal, TaskTemplate, ZedDebugConfig};
-use util::paths::SanitizedPath;
+use task::{DebugScenario, SpawnInTerminal, TaskTemplate, TaskTemplates, ZedDebugConfig};
+use util::{paths::SanitizedPath, rel_path::RelPath};
 use wasmtime::{
     CacheStore, Engine, Store,
     component::{Component, ResourceTable},
@@ -488,6 +490,140 @@ impl extension::Extension for WasmExtension {
         })
         .await?
     }
+
+    async fn tasks_for_language(
+        &self,
+        provider_name: Arc<str>,
+        language_name: LanguageName,
+        file_path: Option<Arc<RelPath>>,
+        worktree: Arc<dyn WorktreeDelegate>,
+    ) -> Result<TaskTemplates> {
+        self.call(|extension, store| {
+            async move {
+                let resource = store.data_mut().table().push(worktree)?;
+                extension
+                    .call_tasks_for_language(
+                        store,
+                        &provider_name,
+                        &language_name,
+                        file_path.as_ref().map(|path| path.as_unix_str()),
+                        resource,
+                    )
+                    .await?
+                    .map_err(|err| store.data().extension_error(err))
+            }
+            .boxed()
+        })
+        .await?
+    }
+
+    async fn format_buffer(
+        &self,
+        formatter_name: Arc<str>,
+        text: String,
+        language_name: LanguageName,
+        settings: FormatSettings,
+    ) -> Result<Vec<TextEdit>> {
+        self.call(|extension, store| {
+            async move {
+                extension
+                    .call_format_buffer(store, &formatter_name, &text, &language_name, settings)
+                    .await?
+                    .map_err(|err| store.data().extension_error(err))
+            }
+            .boxed()
+        })
+        .await?
+    }
+
+    async fn code_actions(
+        &self,
+        provider_name: Arc<str>,
+        text: String,
+        language_name: LanguageName,
+        range: Range<usize>,
+    ) -> Result<Vec<CodeAction>> {
+        self.call(|extension, store| {
+            async move {
+                extension
+                    .call_code_actions(store, &provider_name, &text, &language_name, range)
+                    .await?
+                    .map_err(|err| store.data().extension_error(err))
+            }
+            .boxed()
+        })
+        .await?
+    }
+
+    async fn language_model_provider_models(
+        &self,
+        provider_id: Arc<str>,
+        api_key: Option<String>,
+    ) -> Result<Vec<LanguageModelInfo>> {
+        self.call(|extension, store| {
+            async move {
+                extension
+                    .call_language_model_provider_models(store, &provider_id, api_key.as_deref())
+                    .await?
+                    .map_err(|err| store.data().extension_error(err))
+            }
+            .boxed()
+        })
+        .await?
+    }
+
+    async fn language_model_stream_completion(
+        &self,
+        provider_id: Arc<str>,
+        model_id: String,
+        api_key: Option<String>,
+        request: CompletionRequest,
+    ) -> Result<u64> {
+        self.call(|extension, store| {
+            async move {
+                extension
+                    .call_language_model_stream_completion(
+                        store,
+                        &provider_id,
+                        &model_id,
+                        api_key.as_deref(),
+                        request,
+                    )
+                    .await?
+                    .map_err(|err| store.data().extension_error(err))
+            }
+            .boxed()
+        })
+        .await?
+    }
+
+    async fn language_model_completion_next_event(
+        &self,
+        stream_id: u64,
+    ) -> Result<Option<CompletionEvent>> {
+        self.call(|extension, store| {
+            async move {
+                extension
+                    .call_language_model_completion_next_event(store, stream_id)
+                    .await?
+                    .map_err(|err| store.data().extension_error(err))
+            }
+            .boxed()
+        })
+        .await?
+    }
+
+    async fn language_model_completion_close(&self, stream_id: u64) -> Result<()> {
+        self.call(|extension, store| {
+            async move {
+                extension
+                    .call_language_model_completion_close(store, stream_id)
+                    .await
+            }
+            .boxed()
+        })
+        .await?
+    }
 }
 
 pub struct WasmState {
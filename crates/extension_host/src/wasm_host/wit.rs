@@ -14,7 +14,7 @@ use gpui::BackgroundExecutor;
 use language::LanguageName;
 use lsp::LanguageServerName;
 use release_channel::ReleaseChannel;
-use task::{DebugScenario, SpawnInTerminal, TaskTemplate, ZedDebugConfig};
+use task::{DebugScenario, SpawnInTerminal, TaskTemplate, TaskTemplates, ZedDebugConfig};
 
 use crate::wasm_host::wit::since_v0_6_0::dap::StartDebuggingRequestArgumentsRequest;
 
@@ -1111,6 +1111,146 @@ impl Extension {
             _ => anyhow::bail!("`dap_locator_create_scenario` not available prior to v0.6.0"),
         }
     }
+
+    pub async fn call_tasks_for_language(
+        &self,
+        store: &mut Store<WasmState>,
+        provider_name: &str,
+        language_name: &LanguageName,
+        file_path: Option<&str>,
+        resource: Resource<Arc<dyn WorktreeDelegate>>,
+    ) -> Result<Result<TaskTemplates, String>> {
+        match self {
+            Extension::V0_8_0(ext) => Ok(ext
+                .call_tasks_for_language(
+                    store,
+                    provider_name,
+                    language_name.as_ref(),
+                    file_path,
+                    resource,
+                )
+                .await?
+                .map(|tasks| TaskTemplates(tasks.into_iter().map(Into::into).collect()))),
+            _ => anyhow::bail!("`tasks_for_language` not available prior to v0.8.0"),
+        }
+    }
+
+    pub async fn call_format_buffer(
+        &self,
+        store: &mut Store<WasmState>,
+        formatter_name: &str,
+        text: &str,
+        language_name: &LanguageName,
+        settings: extension::FormatSettings,
+    ) -> Result<Result<Vec<extension::TextEdit>, String>> {
+        match self {
+            Extension::V0_8_0(ext) => Ok(ext
+                .call_format_buffer(
+                    store,
+                    formatter_name,
+                    text,
+                    language_name.as_ref(),
+                    settings.into(),
+                )
+                .await?
+                .map(|edits| edits.into_iter().map(Into::into).collect())),
+            _ => anyhow::bail!("`format_buffer` not available prior to v0.8.0"),
+        }
+    }
+
+    pub async fn call_code_actions(
+        &self,
+        store: &mut Store<WasmState>,
+        provider_name: &str,
+        text: &str,
+        language_name: &LanguageName,
+        range: std::ops::Range<usize>,
+    ) -> Result<Result<Vec<extension::CodeAction>, String>> {
+        match self {
+            Extension::V0_8_0(ext) => Ok(ext
+                .call_code_actions(
+                    store,
+                    provider_name,
+                    text,
+                    language_name.as_ref(),
+                    Range {
+                        start: range.start as u32,
+                        end: range.end as u32,
+                    },
+                )
+                .await?
+                .map(|actions| actions.into_iter().map(Into::into).collect())),
+            _ => anyhow::bail!("`code_actions` not available prior to v0.8.0"),
+        }
+    }
+
+    pub async fn call_language_model_provider_models(
+        &self,
+        store: &mut Store<WasmState>,
+        provider_id: &str,
+        api_key: Option<&str>,
+    ) -> Result<Result<Vec<extension::LanguageModelInfo>, String>> {
+        match self {
+            Extension::V0_8_0(ext) => Ok(ext
+                .call_language_model_provider_models(store, provider_id, api_key)
+                .await?
+                .map(|models| models.into_iter().map(Into::into).collect())),
+            _ => anyhow::bail!("`language_model_provider_models` not available prior to v0.8.0"),
+        }
+    }
+
+    pub async fn call_language_model_stream_completion(
+        &self,
+        store: &mut Store<WasmState>,
+        provider_id: &str,
+        model_id: &str,
+        api_key: Option<&str>,
+        request: extension::CompletionRequest,
+    ) -> Result<Result<u64, String>> {
+        match self {
+            Extension::V0_8_0(ext) => {
+                ext.call_language_model_stream_completion(
+                    store,
+                    provider_id,
+                    model_id,
+                    api_key,
+                    &request.into(),
+                )
+                .await
+            }
+            _ => anyhow::bail!("`language_model_stream_completion` not available prior to v0.8.0"),
+        }
+    }
+
+    pub async fn call_language_model_completion_next_event(
+        &self,
+        store: &mut Store<WasmState>,
+        stream_id: u64,
+    ) -> Result<Result<Option<extension::CompletionEvent>, String>> {
+        match self {
+            Extension::V0_8_0(ext) => Ok(ext
+                .call_language_model_completion_next_event(store, stream_id)
+                .await?
+                .map(|event| event.map(Into::into))),
+            _ => anyhow::bail!(
+                "`language_model_completion_next_event` not available prior to v0.8.0"
+            ),
+        }
+    }
+
+    pub async fn call_language_model_completion_close(
+        &self,
+        store: &mut Store<WasmState>,
+        stream_id: u64,
+    ) -> Result<()> {
+        match self {
+            Extension::V0_8_0(ext) => {
+                ext.call_language_model_completion_close(store, stream_id)
+                    .await
+            }
+            _ => anyhow::bail!("`language_model_completion_close` not available prior to v0.8.0"),
+        }
+    }
 }
 
 trait ToWasmtimeResult<T> {
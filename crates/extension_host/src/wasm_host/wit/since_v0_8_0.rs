@@ -324,6 +324,197 @@ impl TryFrom<SpawnInTerminal> for ResolvedTask {
     }
 }
 
+impl From<LanguageTask> for task::TaskTemplate {
+    fn from(value: LanguageTask) -> Self {
+        Self {
+            label: value.label,
+            command: value.command,
+            args: value.args,
+            env: value.env.into_iter().collect(),
+            cwd: value.cwd,
+            tags: value.tags,
+            ..Default::default()
+        }
+    }
+}
+
+impl From<TextEdit> for extension::TextEdit {
+    fn from(value: TextEdit) -> Self {
+        Self {
+            range: value.range.into(),
+            new_text: value.new_text,
+        }
+    }
+}
+
+impl From<extension::FormatSettings> for FormatSettings {
+    fn from(value: extension::FormatSettings) -> Self {
+        Self {
+            tab_size: value.tab_size,
+            hard_tabs: value.hard_tabs,
+            preferred_line_length: value.preferred_line_length,
+        }
+    }
+}
+
+impl From<CodeAction> for extension::CodeAction {
+    fn from(value: CodeAction) -> Self {
+        Self {
+            title: value.title,
+            kind: value.kind,
+            edits: value.edits.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<language_model::LanguageModelInfo> for extension::LanguageModelInfo {
+    fn from(value: language_model::LanguageModelInfo) -> Self {
+        Self {
+            id: value.id,
+            name: value.name,
+            max_token_count: value.max_token_count,
+            max_output_tokens: value.max_output_tokens,
+            supports_tools: value.supports_tools,
+            supports_images: value.supports_images,
+        }
+    }
+}
+
+impl From<extension::CompletionRequest> for language_model::CompletionRequest {
+    fn from(value: extension::CompletionRequest) -> Self {
+        Self {
+            messages: value.messages.into_iter().map(Into::into).collect(),
+            tools: value.tools.into_iter().map(Into::into).collect(),
+            tool_choice: value.tool_choice.map(Into::into),
+            stop: value.stop,
+            temperature: value.temperature,
+            thinking_allowed: value.thinking_allowed,
+        }
+    }
+}
+
+impl From<extension::RequestMessage> for language_model::RequestMessage {
+    fn from(value: extension::RequestMessage) -> Self {
+        Self {
+            role: value.role.into(),
+            content: value.content.into_iter().map(Into::into).collect(),
+            cache: value.cache,
+        }
+    }
+}
+
+impl From<extension::MessageRole> for language_model::MessageRole {
+    fn from(value: extension::MessageRole) -> Self {
+        match value {
+            extension::MessageRole::User => Self::User,
+            extension::MessageRole::Assistant => Self::Assistant,
+            extension::MessageRole::System => Self::System,
+        }
+    }
+}
+
+impl From<extension::MessageContent> for language_model::MessageContent {
+    fn from(value: extension::MessageContent) -> Self {
+        match value {
+            extension::MessageContent::Text(text) => Self::Text(text),
+            extension::MessageContent::Thinking(thinking) => Self::Thinking(thinking.into()),
+            extension::MessageContent::Image(image) => Self::Image(image),
+            extension::MessageContent::ToolUse(tool_use) => Self::ToolUse(tool_use.into()),
+            extension::MessageContent::ToolResult(tool_result) => {
+                Self::ToolResult(language_model::ToolResult {
+                    tool_use_id: tool_result.tool_use_id,
+                    tool_name: tool_result.tool_name,
+                    is_error: tool_result.is_error,
+                    content: tool_result.content,
+                })
+            }
+        }
+    }
+}
+
+impl From<extension::Thinking> for language_model::Thinking {
+    fn from(value: extension::Thinking) -> Self {
+        Self {
+            text: value.text,
+            signature: value.signature,
+        }
+    }
+}
+
+impl From<language_model::Thinking> for extension::Thinking {
+    fn from(value: language_model::Thinking) -> Self {
+        Self {
+            text: value.text,
+            signature: value.signature,
+        }
+    }
+}
+
+impl From<extension::ToolUse> for language_model::ToolUse {
+    fn from(value: extension::ToolUse) -> Self {
+        Self {
+            id: value.id,
+            name: value.name,
+            input: value.input,
+        }
+    }
+}
+
+impl From<language_model::ToolUse> for extension::ToolUse {
+    fn from(value: language_model::ToolUse) -> Self {
+        Self {
+            id: value.id,
+            name: value.name,
+            input: value.input,
+        }
+    }
+}
+
+impl From<extension::ToolDefinition> for language_model::ToolDefinition {
+    fn from(value: extension::ToolDefinition) -> Self {
+        Self {
+            name: value.name,
+            description: value.description,
+            input_schema: value.input_schema,
+        }
+    }
+}
+
+impl From<extension::ToolChoice> for language_model::ToolChoice {
+    fn from(value: extension::ToolChoice) -> Self {
+        match value {
+            extension::ToolChoice::Auto => Self::Auto,
+            extension::ToolChoice::Any => Self::Any,
+            extension::ToolChoice::None => Self::None,
+        }
+    }
+}
+
+impl From<language_model::CompletionEvent> for extension::CompletionEvent {
+    fn from(value: language_model::CompletionEvent) -> Self {
+        match value {
+            language_model::CompletionEvent::Started => Self::Started,
+            language_model::CompletionEvent::Text(text) => Self::Text(text),
+            language_model::CompletionEvent::Thinking(thinking) => Self::Thinking(thinking.into()),
+            language_model::CompletionEvent::ToolUse(tool_use) => Self::ToolUse(tool_use.into()),
+            language_model::CompletionEvent::Stop(reason) => Self::Stop(match reason {
+                language_model::StopReason::EndTurn => extension::StopReason::EndTurn,
+                language_model::StopReason::MaxTokens => extension::StopReason::MaxTokens,
+                language_model::StopReason::ToolUse => extension::StopReason::ToolUse,
+                language_model::StopReason::Refusal => extension::StopReason::Refusal,
+            }),
+            language_model::CompletionEvent::UsageUpdate(usage) => {
+                Self::UsageUpdate(extension::TokenUsage {
+                    input_tokens: usage.input_tokens,
+                    output_tokens: usage.output_tokens,
+                    cache_creation_input_tokens: usage.cache_creation_input_tokens,
+                    cache_read_input_tokens: usage.cache_read_input_tokens,
+                })
+            }
+        }
+    }
+}
+
 impl From<CodeLabel> for extension::CodeLabel {
     fn from(value: CodeLabel) -> Self {
         Self {
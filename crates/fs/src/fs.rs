@@ -37,7 +37,8 @@ use rope::Rope;
 use serde::{Deserialize, Serialize};
 use smol::io::AsyncWriteExt;
 use std::{
-    io::{self, Write},
+    io::{self, Read as _, Seek as _, SeekFrom, Write},
+    ops::Range,
     path::{Component, Path, PathBuf},
     pin::Pin,
     sync::Arc,
@@ -121,9 +122,15 @@ pub trait Fs: Send + Sync {
         Ok(String::from_utf8(self.load_bytes(path).await?)?)
     }
     async fn load_bytes(&self, path: &Path) -> Result<Vec<u8>>;
+    /// Loads the bytes of the file within `range`, which are fewer than the
+    /// range's length where the range extends past the end of the file.
+    async fn load_range(&self, path: &Path, range: Range<u64>) -> Result<Vec<u8>>;
     async fn atomic_write(&self, path: PathBuf, text: String) -> Result<()>;
     async fn save(&self, path: &Path, text: &Rope, line_ending: LineEnding) -> Result<()>;
     async fn write(&self, path: &Path, content: &[u8]) -> Result<()>;
+    /// Overwrites the bytes of an existing file from `offset`, leaving the rest
+    /// of the file as it is.
+    async fn write_at(&self, path: &Path, offset: u64, content: &[u8]) -> Result<()>;
     async fn canonicalize(&self, path: &Path) -> Result<PathBuf>;
     async fn is_file(&self, path: &Path) -> bool;
     async fn is_dir(&self, path: &Path) -> bool;
@@ -763,6 +770,22 @@ impl Fs for RealFs {
         Ok(bytes)
     }
 
+    async fn load_range(&self, path: &Path, range: Range<u64>) -> Result<Vec<u8>> {
+        let path = path.to_path_buf();
+        let bytes = self
+            .executor
+            .spawn(async move {
+                let mut file = std::fs::File::open(path)?;
+                file.seek(SeekFrom::Start(range.start))?;
+                let mut bytes = Vec::new();
+                file.take(range.end.saturating_sub(range.start))
+                    .read_to_end(&mut bytes)?;
+                io::Result::Ok(bytes)
+            })
+            .await?;
+        Ok(bytes)
+    }
+
     #[cfg(not(target_os = "windows"))]
     async fn atomic_write(&self, path: PathBuf, data: String) -> Result<()> {
         smol::unblock(move || {
@@ -837,6 +860,19 @@ impl Fs for RealFs {
             .await
     }
 
+    async fn write_at(&self, path: &Path, offset: u64, content: &[u8]) -> Result<()> {
+        let path = path.to_owned();
+        let contents = content.to_owned();
+        self.executor
+            .spawn(async move {
+                let mut file = std::fs::OpenOptions::new().write(true).open(path)?;
+                file.seek(SeekFrom::Start(offset))?;
+                file.write_all(&contents)?;
+                Ok(())
+            })
+            .await
+    }
+
     async fn canonicalize(&self, path: &Path) -> Result<PathBuf> {
         let path = path.to_owned();
         self.executor
@@ -2555,6 +2591,15 @@ impl Fs for FakeFs {
         self.load_internal(path).await
     }
 
+    async fn load_range(&self, path: &Path, range: Range<u64>) -> Result<Vec<u8>> {
+        let mut bytes = self.load_internal(path).await?;
+        let end = (range.end as usize).min(bytes.len());
+        let start = (range.start as usize).min(end);
+        bytes.truncate(end);
+        bytes.drain(..start);
+        Ok(bytes)
+    }
+
     async fn atomic_write(&self, path: PathBuf, data: String) -> Result<()> {
         self.simulate_random_delay().await;
         let path = normalize_path(path.as_path());
@@ -2586,6 +2631,18 @@ impl Fs for FakeFs {
         Ok(())
     }
 
+    async fn write_at(&self, path: &Path, offset: u64, content: &[u8]) -> Result<()> {
+        let mut bytes = self.load_internal(path).await?;
+        let start = offset as usize;
+        let end = start + content.len();
+        if bytes.len() < end {
+            bytes.resize(end, 0);
+        }
+        bytes[start..end].copy_from_slice(content);
+        self.write_file_internal(normalize_path(path), bytes, false)?;
+        Ok(())
+    }
+
     async fn canonicalize(&self, path: &Path) -> Result<PathBuf> {
         let path = normalize_path(path);
         self.simulate_random_delay().await;
@@ -3390,6 +3447,26 @@ mod tests {
         assert_eq!(content, "World");
     }
 
+    #[gpui::test]
+    async fn test_realfs_load_range_and_write_at(executor: BackgroundExecutor) {
+        let fs = RealFs {
+            bundled_git_binary_path: None,
+            executor,
+            next_job_id: Arc::new(AtomicUsize::new(0)),
+            job_event_subscribers: Arc::new(Mutex::new(Vec::new())),
+        };
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("file.bin");
+        std::fs::write(&path, b"0123456789").unwrap();
+
+        assert_eq!(fs.load_range(&path, 2..5).await.unwrap(), b"234");
+        assert_eq!(fs.load_range(&path, 8..20).await.unwrap(), b"89");
+        assert_eq!(fs.load_range(&path, 20..30).await.unwrap(), b"");
+
+        fs.write_at(&path, 3, b"ab").await.unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), b"012ab56789");
+    }
+
     #[gpui::test]
     async fn test_realfs_atomic_write_non_existing_file(executor: BackgroundExecutor) {
         let fs = RealFs {
@@ -0,0 +1,951 @@
+//! MergeView provides a three-way merge editor, showing the changes that two
+//! sides made to a common base alongside an editable result.
+
+use crate::file_diff_view::build_buffer_diff;
+use anyhow::Result;
+use buffer_diff::BufferDiff;
+use editor::{
+    Editor, EditorEvent, MultiBuffer, MultiBufferOffset, SelectionEffects, scroll::Autoscroll,
+};
+use gpui::{
+    AnyElement, App, AppContext as _, Context, Entity, EventEmitter, FocusHandle, Focusable,
+    IntoElement, Render, SharedString, Subscription, Task, Window, actions,
+};
+use language::{Anchor, Buffer, Point, ToOffset as _};
+use project::{ConflictRegion, ConflictSet, ConflictSetUpdate, Project};
+use std::{
+    any::{Any, TypeId},
+    ops::Range,
+    path::PathBuf,
+    sync::Arc,
+};
+use ui::{Color, Icon, IconName, Label, LabelCommon as _, LabelSize, prelude::*};
+use util::paths::PathExt as _;
+use workspace::{
+    Item, ItemNavHistory, ToolbarItemLocation, Workspace,
+    item::{BreadcrumbText, ItemEvent, SaveOptions, TabContentParams},
+    searchable::SearchableItemHandle,
+};
+
+actions!(
+    merge_view,
+    [
+        /// Resolves the conflict at the cursor with the left (local) side.
+        AcceptLeft,
+        /// Resolves the conflict at the cursor with the right (remote) side.
+        AcceptRight,
+        /// Resolves the conflict at the cursor with both sides, left first.
+        AcceptBoth,
+        /// Moves the cursor to the next unresolved conflict.
+        GoToNextConflict,
+        /// Moves the cursor to the previous unresolved conflict.
+        GoToPreviousConflict
+    ]
+);
+
+const LOCAL_LABEL: &str = "Local";
+const BASE_LABEL: &str = "Base";
+const REMOTE_LABEL: &str = "Remote";
+
+pub struct MergeView {
+    base_editor: Entity<Editor>,
+    local_editor: Entity<Editor>,
+    remote_editor: Entity<Editor>,
+    result_editor: Entity<Editor>,
+    base_buffer: Entity<Buffer>,
+    local_buffer: Entity<Buffer>,
+    remote_buffer: Entity<Buffer>,
+    result_buffer: Entity<Buffer>,
+    conflict_set: Entity<ConflictSet>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl MergeView {
+    /// Opens a merge of the changes that `local_path` and `remote_path` made
+    /// to `base_path`, writing the result to `output_path`.
+    pub fn open(
+        base_path: PathBuf,
+        local_path: PathBuf,
+        remote_path: PathBuf,
+        output_path: PathBuf,
+        workspace: &Workspace,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Task<Result<Entity<Self>>> {
+        let workspace = workspace.weak_handle();
+        window.spawn(cx, async move |cx| {
+            let project = workspace.update(cx, |workspace, _| workspace.project().clone())?;
+            let base_buffer = project
+                .update(cx, |project, cx| project.open_local_buffer(&base_path, cx))?
+                .await?;
+            let local_buffer = project
+                .update(cx, |project, cx| project.open_local_buffer(&local_path, cx))?
+                .await?;
+            let remote_buffer = project
+                .update(cx, |project, cx| {
+                    project.open_local_buffer(&remote_path, cx)
+                })?
+                .await?;
+            let result_buffer = project
+                .update(cx, |project, cx| {
+                    project.open_local_buffer(&output_path, cx)
+                })?
+                .await?;
+
+            let local_diff = build_buffer_diff(&base_buffer, &local_buffer, cx).await?;
+            let remote_diff = build_buffer_diff(&base_buffer, &remote_buffer, cx).await?;
+
+            workspace.update_in(cx, |workspace, window, cx| {
+                let merge_view = cx.new(|cx| {
+                    MergeView::new(
+                        base_buffer,
+                        local_buffer,
+                        remote_buffer,
+                        result_buffer,
+                        local_diff,
+                        remote_diff,
+                        project.clone(),
+                        window,
+                        cx,
+                    )
+                });
+
+                let pane = workspace.active_pane();
+                pane.update(cx, |pane, cx| {
+                    pane.add_item(Box::new(merge_view.clone()), true, true, None, window, cx);
+                });
+
+                merge_view
+            })
+        })
+    }
+
+    pub fn new(
+        base_buffer: Entity<Buffer>,
+        local_buffer: Entity<Buffer>,
+        remote_buffer: Entity<Buffer>,
+        result_buffer: Entity<Buffer>,
+        local_diff: Entity<BufferDiff>,
+        remote_diff: Entity<BufferDiff>,
+        project: Entity<Project>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let base_text = base_buffer.read(cx).text();
+        let local_text = local_buffer.read(cx).text();
+        let remote_text = remote_buffer.read(cx).text();
+        // The output is usually the merge that git already made, with conflict
+        // markers where it couldn't merge, and may have conflicts that rerere or
+        // the user resolved since. Only an output without such work, being empty
+        // or a copy of one of the files, is replaced with a merge of the sides.
+        let output_text = result_buffer.read(cx).text();
+        if output_text.is_empty() || [&base_text, &local_text, &remote_text].contains(&&output_text)
+        {
+            let local_hunks = line_hunks(&local_diff, &local_buffer, cx);
+            let remote_hunks = line_hunks(&remote_diff, &remote_buffer, cx);
+            let merge = merge_lines(
+                &base_text,
+                &local_text,
+                &remote_text,
+                &local_hunks,
+                &remote_hunks,
+            );
+            if output_text != merge {
+                result_buffer.update(cx, |buffer, cx| buffer.set_text(merge, cx));
+            }
+        }
+        let conflict_set = project.read(cx).git_store().update(cx, |git_store, cx| {
+            git_store.open_merge_conflict_set(result_buffer.clone(), cx)
+        });
+
+        let base_editor = cx.new(|cx| {
+            let mut editor =
+                Editor::for_buffer(base_buffer.clone(), Some(project.clone()), window, cx);
+            editor.set_read_only(true);
+            editor.disable_diagnostics(cx);
+            editor
+        });
+        let local_editor = side_editor(&local_buffer, local_diff, &project, window, cx);
+        let remote_editor = side_editor(&remote_buffer, remote_diff, &project, window, cx);
+        let result_editor = cx
+            .new(|cx| Editor::for_buffer(result_buffer.clone(), Some(project.clone()), window, cx));
+
+        let subscriptions = vec![
+            cx.subscribe(&result_editor, |_, _, event: &EditorEvent, cx| {
+                cx.emit(event.clone())
+            }),
+            cx.subscribe(&conflict_set, |_, _, _: &ConflictSetUpdate, cx| cx.notify()),
+        ];
+
+        Self {
+            base_editor,
+            local_editor,
+            remote_editor,
+            result_editor,
+            base_buffer,
+            local_buffer,
+            remote_buffer,
+            result_buffer,
+            conflict_set,
+            _subscriptions: subscriptions,
+        }
+    }
+
+    fn conflicts(&self, cx: &App) -> Arc<[ConflictRegion]> {
+        self.conflict_set.read(cx).snapshot().conflicts
+    }
+
+    fn cursor_offset(&self, cx: &mut App) -> usize {
+        let cursor = self.result_editor.update(cx, |editor, cx| {
+            editor
+                .selections
+                .newest::<MultiBufferOffset>(&editor.display_snapshot(cx))
+                .head()
+        });
+        cursor.0
+    }
+
+    /// Returns the index of the conflict containing the cursor, or else of
+    /// the first one after it.
+    fn conflict_at_cursor(&self, cx: &mut App) -> Option<usize> {
+        let cursor = self.cursor_offset(cx);
+        let buffer = self.result_buffer.read(cx).snapshot();
+        let conflicts = self.conflicts(cx);
+        conflicts
+            .iter()
+            .position(|conflict| conflict.range.end.to_offset(&buffer) >= cursor)
+    }
+
+    fn accept_left(&mut self, _: &AcceptLeft, window: &mut Window, cx: &mut Context<Self>) {
+        self.resolve_conflict_at_cursor(|conflict| vec![conflict.ours.clone()], window, cx);
+    }
+
+    fn accept_right(&mut self, _: &AcceptRight, window: &mut Window, cx: &mut Context<Self>) {
+        self.resolve_conflict_at_cursor(|conflict| vec![conflict.theirs.clone()], window, cx);
+    }
+
+    fn accept_both(&mut self, _: &AcceptBoth, window: &mut Window, cx: &mut Context<Self>) {
+        self.resolve_conflict_at_cursor(
+            |conflict| vec![conflict.ours.clone(), conflict.theirs.clone()],
+            window,
+            cx,
+        );
+    }
+
+    fn resolve_conflict_at_cursor(
+        &mut self,
+        kept_ranges: impl FnOnce(&ConflictRegion) -> Vec<Range<Anchor>>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(ix) = self.conflict_at_cursor(cx) else {
+            return;
+        };
+        let conflicts = self.conflicts(cx);
+        let conflict = &conflicts[ix];
+        conflict.resolve(self.result_buffer.clone(), &kept_ranges(conflict), cx);
+        if let Some(next_conflict) = conflicts.get(ix + 1) {
+            self.select_conflict(next_conflict, window, cx);
+        }
+    }
+
+    fn go_to_next_conflict(
+        &mut self,
+        _: &GoToNextConflict,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let cursor = self.cursor_offset(cx);
+        let buffer = self.result_buffer.read(cx).snapshot();
+        let conflicts = self.conflicts(cx);
+        if let Some(conflict) = conflicts
+            .iter()
+            .find(|conflict| conflict.range.start.to_offset(&buffer) > cursor)
+            .or_else(|| conflicts.first())
+        {
+            self.select_conflict(conflict, window, cx);
+        }
+    }
+
+    fn go_to_previous_conflict(
+        &mut self,
+        _: &GoToPreviousConflict,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let cursor = self.cursor_offset(cx);
+        let buffer = self.result_buffer.read(cx).snapshot();
+        let conflicts = self.conflicts(cx);
+        if let Some(conflict) = conflicts
+            .iter()
+            .rfind(|conflict| conflict.range.start.to_offset(&buffer) < cursor)
+            .or_else(|| conflicts.last())
+        {
+            self.select_conflict(conflict, window, cx);
+        }
+    }
+
+    fn select_conflict(
+        &mut self,
+        conflict: &ConflictRegion,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let offset = MultiBufferOffset(
+            conflict
+                .range
+                .start
+                .to_offset(&self.result_buffer.read(cx).snapshot()),
+        );
+        self.result_editor.update(cx, |editor, cx| {
+            editor.change_selections(
+                SelectionEffects::scroll(Autoscroll::center()),
+                window,
+                cx,
+                |selections| selections.select_ranges([offset..offset]),
+            );
+        });
+    }
+
+    fn render_pane(
+        &self,
+        title: &'static str,
+        buffer: &Entity<Buffer>,
+        editor: &Entity<Editor>,
+        cx: &App,
+    ) -> impl IntoElement {
+        v_flex()
+            .flex_1()
+            .min_w_0()
+            .min_h_0()
+            .child(
+                h_flex()
+                    .px_2()
+                    .py_1()
+                    .gap_2()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .child(Label::new(title).size(LabelSize::Small))
+                    .child(
+                        Label::new(buffer_path(buffer, cx))
+                            .size(LabelSize::Small)
+                            .color(Color::Muted)
+                            .truncate(),
+                    ),
+            )
+            .child(div().flex_1().min_h_0().child(editor.clone()))
+    }
+}
+
+/// Creates a read-only editor showing the changes a side made to the base.
+fn side_editor(
+    buffer: &Entity<Buffer>,
+    diff: Entity<BufferDiff>,
+    project: &Entity<Project>,
+    window: &mut Window,
+    cx: &mut Context<MergeView>,
+) -> Entity<Editor> {
+    let multibuffer = cx.new(|cx| {
+        let mut multibuffer = MultiBuffer::singleton(buffer.clone(), cx);
+        multibuffer.add_diff(diff, cx);
+        multibuffer
+    });
+    cx.new(|cx| {
+        let mut editor = Editor::for_multibuffer(multibuffer, Some(project.clone()), window, cx);
+        editor.set_read_only(true);
+        editor.start_temporary_diff_override();
+        editor.disable_diagnostics(cx);
+        editor.set_expand_all_diff_hunks(cx);
+        editor.set_render_diff_hunk_controls(
+            Arc::new(|_, _, _, _, _, _, _, _| gpui::Empty.into_any_element()),
+            cx,
+        );
+        editor
+    })
+}
+
+fn buffer_path(buffer: &Entity<Buffer>, cx: &App) -> String {
+    buffer
+        .read(cx)
+        .file()
+        .map(|file| file.full_path(cx).compact().to_string_lossy().into_owned())
+        .unwrap_or_else(|| "untitled".into())
+}
+
+/// A change one side made to the base, as ranges of rows in the base and in
+/// that side.
+#[derive(Clone, Debug, PartialEq)]
+struct LineHunk {
+    base: Range<u32>,
+    side: Range<u32>,
+}
+
+fn line_hunks(diff: &Entity<BufferDiff>, buffer: &Entity<Buffer>, cx: &App) -> Vec<LineHunk> {
+    let buffer = buffer.read(cx).text_snapshot();
+    let diff = diff.read(cx);
+    let base = diff.base_text();
+    let end_row = |point: Point| {
+        if point.column > 0 {
+            point.row + 1
+        } else {
+            point.row
+        }
+    };
+    diff.hunks(&buffer, cx)
+        .map(|hunk| {
+            let base_start = base.offset_to_point(hunk.diff_base_byte_range.start);
+            let base_end = base.offset_to_point(hunk.diff_base_byte_range.end);
+            LineHunk {
+                base: base_start.row..end_row(base_end),
+                side: hunk.range.start.row..end_row(hunk.range.end),
+            }
+        })
+        .collect()
+}
+
+/// Merges the changes that both sides made to the base, line by line. Changes
+/// to different lines are combined, and changes to the same or adjacent lines
+/// are written as conflicts with diff3-style markers, unless both sides made
+/// the same change.
+fn merge_lines(
+    base: &str,
+    local: &str,
+    remote: &str,
+    local_hunks: &[LineHunk],
+    remote_hunks: &[LineHunk],
+) -> String {
+    let base_lines = base.split_inclusive('\n').collect::<Vec<_>>();
+    let side_lines = [
+        local.split_inclusive('\n').collect::<Vec<_>>(),
+        remote.split_inclusive('\n').collect::<Vec<_>>(),
+    ];
+    let hunks = [local_hunks, remote_hunks];
+    let mut next_hunk = [0; 2];
+    // The difference between a side's rows and the base's rows, outside of
+    // the changes that were merged so far.
+    let mut row_delta = [0i64; 2];
+    let mut base_row = 0;
+    let mut text = String::new();
+
+    while let Some(start) = (0..2)
+        .filter_map(|side| hunks[side].get(next_hunk[side]))
+        .map(|hunk| hunk.base.start)
+        .min()
+    {
+        // Grow the chunk until it includes all hunks that overlap or touch it.
+        let mut end = start;
+        let mut changed = [false; 2];
+        let side_starts = row_delta.map(|delta| (start as i64 + delta) as usize);
+        loop {
+            let mut grew = false;
+            for side in 0..2 {
+                while let Some(hunk) = hunks[side].get(next_hunk[side])
+                    && hunk.base.start <= end
+                {
+                    end = end.max(hunk.base.end);
+                    row_delta[side] += hunk.side.len() as i64 - hunk.base.len() as i64;
+                    changed[side] = true;
+                    next_hunk[side] += 1;
+                    grew = true;
+                }
+            }
+            if !grew {
+                break;
+            }
+        }
+
+        push_lines(&mut text, rows(&base_lines, base_row..start as usize));
+        let [local_chunk, remote_chunk] = [0, 1].map(|side| {
+            let side_end = (end as i64 + row_delta[side]) as usize;
+            rows(&side_lines[side], side_starts[side]..side_end)
+        });
+        match changed {
+            [true, false] => push_lines(&mut text, local_chunk),
+            [false, true] => push_lines(&mut text, remote_chunk),
+            _ if local_chunk == remote_chunk => push_lines(&mut text, local_chunk),
+            _ => {
+                let base_chunk = rows(&base_lines, start as usize..end as usize);
+                push_marker(&mut text, &format!("<<<<<<< {LOCAL_LABEL}"));
+                push_lines(&mut text, local_chunk);
+                push_marker(&mut text, &format!("||||||| {BASE_LABEL}"));
+                push_lines(&mut text, base_chunk);
+                push_marker(&mut text, "=======");
+                push_lines(&mut text, remote_chunk);
+                push_marker(&mut text, &format!(">>>>>>> {REMOTE_LABEL}"));
+            }
+        }
+        base_row = end as usize;
+    }
+    push_lines(&mut text, rows(&base_lines, base_row..base_lines.len()));
+
+    text
+}
+
+fn rows<'a, 'b>(lines: &'b [&'a str], rows: Range<usize>) -> &'b [&'a str] {
+    let end = rows.end.min(lines.len());
+    &lines[rows.start.min(end)..end]
+}
+
+fn push_lines(text: &mut String, lines: &[&str]) {
+    for line in lines {
+        end_line(text);
+        text.push_str(line);
+    }
+}
+
+fn push_marker(text: &mut String, marker: &str) {
+    end_line(text);
+    text.push_str(marker);
+    text.push('\n');
+}
+
+/// Ends the text's last line with a newline, which only the last line of a
+/// file may lack, so that more lines can follow it.
+fn end_line(text: &mut String) {
+    if !text.is_empty() && !text.ends_with('\n') {
+        text.push('\n');
+    }
+}
+
+impl EventEmitter<EditorEvent> for MergeView {}
+
+impl Focusable for MergeView {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.result_editor.focus_handle(cx)
+    }
+}
+
+impl Item for MergeView {
+    type Event = EditorEvent;
+
+    fn tab_icon(&self, _window: &Window, _cx: &App) -> Option<Icon> {
+        Some(Icon::new(IconName::Diff).color(Color::Muted))
+    }
+
+    fn tab_content(&self, params: TabContentParams, _window: &Window, cx: &App) -> AnyElement {
+        Label::new(self.tab_content_text(params.detail.unwrap_or_default(), cx))
+            .color(if params.selected {
+                Color::Default
+            } else {
+                Color::Muted
+            })
+            .into_any_element()
+    }
+
+    fn tab_content_text(&self, _detail: usize, cx: &App) -> SharedString {
+        let filename = self
+            .result_buffer
+            .read(cx)
+            .file()
+            .and_then(|file| {
+                Some(
+                    file.full_path(cx)
+                        .file_name()?
+                        .to_string_lossy()
+                        .to_string(),
+                )
+            })
+            .unwrap_or_else(|| "untitled".into());
+        format!("Merge {filename}").into()
+    }
+
+    fn tab_tooltip_text(&self, cx: &App) -> Option<SharedString> {
+        Some(format!("Merge {}", buffer_path(&self.result_buffer, cx)).into())
+    }
+
+    fn to_item_events(event: &EditorEvent, f: impl FnMut(ItemEvent)) {
+        Editor::to_item_events(event, f)
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("Merge View Opened")
+    }
+
+    fn deactivated(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.result_editor
+            .update(cx, |editor, cx| editor.deactivated(window, cx));
+    }
+
+    fn act_as_type<'a>(
+        &'a self,
+        type_id: TypeId,
+        self_handle: &'a Entity<Self>,
+        _: &'a App,
+    ) -> Option<gpui::AnyEntity> {
+        if type_id == TypeId::of::<Self>() {
+            Some(self_handle.clone().into())
+        } else if type_id == TypeId::of::<Editor>() {
+            Some(self.result_editor.clone().into())
+        } else {
+            None
+        }
+    }
+
+    fn as_searchable(&self, _: &Entity<Self>, _: &App) -> Option<Box<dyn SearchableItemHandle>> {
+        Some(Box::new(self.result_editor.clone()))
+    }
+
+    fn for_each_project_item(
+        &self,
+        cx: &App,
+        f: &mut dyn FnMut(gpui::EntityId, &dyn project::ProjectItem),
+    ) {
+        self.result_editor.for_each_project_item(cx, f)
+    }
+
+    fn set_nav_history(
+        &mut self,
+        nav_history: ItemNavHistory,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.result_editor.update(cx, |editor, _| {
+            editor.set_nav_history(Some(nav_history));
+        });
+    }
+
+    fn navigate(
+        &mut self,
+        data: Box<dyn Any>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        self.result_editor
+            .update(cx, |editor, cx| editor.navigate(data, window, cx))
+    }
+
+    fn breadcrumb_location(&self, _: &App) -> ToolbarItemLocation {
+        ToolbarItemLocation::PrimaryLeft
+    }
+
+    fn breadcrumbs(&self, theme: &theme::Theme, cx: &App) -> Option<Vec<BreadcrumbText>> {
+        self.result_editor.breadcrumbs(theme, cx)
+    }
+
+    fn added_to_workspace(
+        &mut self,
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        for editor in [
+            &self.base_editor,
+            &self.local_editor,
+            &self.remote_editor,
+            &self.result_editor,
+        ] {
+            editor.update(cx, |editor, cx| {
+                editor.added_to_workspace(workspace, window, cx)
+            });
+        }
+    }
+
+    fn is_dirty(&self, cx: &App) -> bool {
+        self.result_buffer.read(cx).is_dirty()
+    }
+
+    fn can_save(&self, cx: &App) -> bool {
+        // The result editor handles the output buffer, so delegate to it
+        self.result_editor.read(cx).can_save(cx)
+    }
+
+    fn save(
+        &mut self,
+        options: SaveOptions,
+        project: Entity<Project>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        self.result_editor
+            .update(cx, |editor, cx| editor.save(options, project, window, cx))
+    }
+}
+
+impl Render for MergeView {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let conflict_count = self.conflicts(cx).len();
+        let conflicts_label = match conflict_count {
+            0 => Label::new("No conflicts left").color(Color::Success),
+            1 => Label::new("1 conflict left").color(Color::Warning),
+            count => Label::new(format!("{count} conflicts left")).color(Color::Warning),
+        };
+        let border_color = cx.theme().colors().border;
+
+        v_flex()
+            .key_context("MergeView")
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .on_action(cx.listener(Self::accept_left))
+            .on_action(cx.listener(Self::accept_right))
+            .on_action(cx.listener(Self::accept_both))
+            .on_action(cx.listener(Self::go_to_next_conflict))
+            .on_action(cx.listener(Self::go_to_previous_conflict))
+            .child(
+                h_flex()
+                    .flex_1()
+                    .min_h_0()
+                    .border_b_1()
+                    .border_color(border_color)
+                    .child(self.render_pane(
+                        LOCAL_LABEL,
+                        &self.local_buffer,
+                        &self.local_editor,
+                        cx,
+                    ))
+                    .child(div().h_full().border_l_1().border_color(border_color))
+                    .child(self.render_pane(BASE_LABEL, &self.base_buffer, &self.base_editor, cx))
+                    .child(div().h_full().border_l_1().border_color(border_color))
+                    .child(self.render_pane(
+                        REMOTE_LABEL,
+                        &self.remote_buffer,
+                        &self.remote_editor,
+                        cx,
+                    )),
+            )
+            .child(
+                v_flex()
+                    .flex_1()
+                    .min_h_0()
+                    .child(
+                        h_flex()
+                            .px_2()
+                            .py_1()
+                            .gap_2()
+                            .border_b_1()
+                            .border_color(cx.theme().colors().border_variant)
+                            .child(Label::new("Result").size(LabelSize::Small))
+                            .child(
+                                Label::new(buffer_path(&self.result_buffer, cx))
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted)
+                                    .truncate(),
+                            )
+                            .child(div().flex_1())
+                            .child(conflicts_label.size(LabelSize::Small)),
+                    )
+                    .child(div().flex_1().min_h_0().child(self.result_editor.clone())),
+            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+    use project::{FakeFs, Project};
+    use settings::SettingsStore;
+    use std::time::Duration;
+    use unindent::unindent;
+    use util::path;
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            theme::init(theme::LoadThemes::JustBase, cx);
+        });
+    }
+
+    fn hunk(base: Range<u32>, side: Range<u32>) -> LineHunk {
+        LineHunk { base, side }
+    }
+
+    #[test]
+    fn test_merge_lines() {
+        let base = "a\nb\nc\nd\ne\n";
+
+        // Changes to different lines are combined.
+        let local = "A\nb\nc\nd\ne\n";
+        let remote = "a\nb\nc\nD\nE\nf\n";
+        assert_eq!(
+            merge_lines(
+                base,
+                local,
+                remote,
+                &[hunk(0..1, 0..1)],
+                &[hunk(3..5, 3..6)]
+            ),
+            "A\nb\nc\nD\nE\nf\n"
+        );
+
+        // Identical changes aren't conflicts.
+        let local = "a\nB\nc\nd\ne\n";
+        assert_eq!(
+            merge_lines(base, local, local, &[hunk(1..2, 1..2)], &[hunk(1..2, 1..2)]),
+            local
+        );
+
+        // Different changes to the same lines are conflicts, even if one of
+        // them is a deletion.
+        let local = "a\nB\nc\nd\n";
+        let remote = "a\nX\nc\nd\nE\n";
+        assert_eq!(
+            merge_lines(
+                base,
+                local,
+                remote,
+                &[hunk(1..2, 1..2), hunk(4..5, 4..4)],
+                &[hunk(1..2, 1..2), hunk(4..5, 4..5)]
+            ),
+            unindent(
+                "
+                    a
+                    <<<<<<< Local
+                    B
+                    ||||||| Base
+                    b
+                    =======
+                    X
+                    >>>>>>> Remote
+                    c
+                    d
+                    <<<<<<< Local
+                    ||||||| Base
+                    e
+                    =======
+                    E
+                    >>>>>>> Remote
+                    "
+            )
+        );
+
+        // Changes to adjacent lines are conflicts.
+        let local = "a\nB\nc\nd\ne\n";
+        let remote = "a\nb\nC\nd\ne\n";
+        assert_eq!(
+            merge_lines(
+                base,
+                local,
+                remote,
+                &[hunk(1..2, 1..2)],
+                &[hunk(2..3, 2..3)]
+            )
+            .matches("<<<<<<<")
+            .count(),
+            1
+        );
+
+        // Insertions at the end of a file without a trailing newline.
+        let base = "a\nb";
+        let local = "a\nb\nc";
+        let remote = "a\nb\nd";
+        assert_eq!(
+            merge_lines(
+                base,
+                local,
+                remote,
+                &[hunk(1..2, 1..3)],
+                &[hunk(1..2, 1..3)]
+            ),
+            "a\n<<<<<<< Local\nb\nc\n||||||| Base\nb\n=======\nb\nd\n>>>>>>> Remote\n"
+        );
+    }
+
+    #[gpui::test]
+    async fn test_merge_view(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/test"),
+            serde_json::json!({
+                "base.txt": "one\ntwo\nthree\nfour\nfive\n",
+                "local.txt": "ONE\ntwo\nthree\nfour\nFIVE\n",
+                "remote.txt": "one\ntwo\nTHREE\nfour\nfive!\n",
+                "output.txt": "",
+            }),
+        )
+        .await;
+
+        let project = Project::test(fs.clone(), [path!("/test").as_ref()], cx).await;
+
+        let (workspace, cx) =
+            cx.add_window_view(|window, cx| Workspace::test_new(project.clone(), window, cx));
+
+        let merge_view = workspace
+            .update_in(cx, |workspace, window, cx| {
+                MergeView::open(
+                    path!("/test/base.txt").into(),
+                    path!("/test/local.txt").into(),
+                    path!("/test/remote.txt").into(),
+                    path!("/test/output.txt").into(),
+                    workspace,
+                    window,
+                    cx,
+                )
+            })
+            .await
+            .unwrap();
+        cx.run_until_parked();
+
+        let result_text = |cx: &mut gpui::VisualTestContext| {
+            merge_view.read_with(cx, |merge_view, cx| {
+                merge_view.result_buffer.read(cx).text()
+            })
+        };
+        assert_eq!(
+            result_text(cx),
+            "ONE\ntwo\nTHREE\nfour\n<<<<<<< Local\nFIVE\n||||||| Base\nfive\n=======\nfive!\n>>>>>>> Remote\n"
+        );
+        merge_view.read_with(cx, |merge_view, cx| {
+            assert_eq!(merge_view.conflicts(cx).len(), 1);
+            assert!(merge_view.is_dirty(cx));
+            assert_eq!(merge_view.tab_content_text(0, cx), "Merge output.txt");
+        });
+
+        merge_view.update_in(cx, |merge_view, window, cx| {
+            merge_view.accept_both(&AcceptBoth, window, cx);
+        });
+        cx.executor().advance_clock(Duration::from_secs(1));
+        cx.run_until_parked();
+        assert_eq!(result_text(cx), "ONE\ntwo\nTHREE\nfour\nFIVE\nfive!\n");
+        merge_view.read_with(cx, |merge_view, cx| {
+            assert_eq!(merge_view.conflicts(cx).len(), 0);
+        });
+    }
+
+    #[gpui::test]
+    async fn test_merge_view_keeps_existing_resolutions(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        // Git merged the first change, and the user resolved the conflict on
+        // the third line, while the one on the last line is left.
+        let output = "ONE\ntwo\nThree\nfour\n<<<<<<< HEAD\nFIVE\n=======\nfive!\n>>>>>>> theirs\n";
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/test"),
+            serde_json::json!({
+                "base.txt": "one\ntwo\nthree\nfour\nfive\n",
+                "local.txt": "ONE\ntwo\nthree\nfour\nFIVE\n",
+                "remote.txt": "one\ntwo\nTHREE\nfour\nfive!\n",
+                "output.txt": output,
+            }),
+        )
+        .await;
+
+        let project = Project::test(fs.clone(), [path!("/test").as_ref()], cx).await;
+        let (workspace, cx) =
+            cx.add_window_view(|window, cx| Workspace::test_new(project.clone(), window, cx));
+        let merge_view = workspace
+            .update_in(cx, |workspace, window, cx| {
+                MergeView::open(
+                    path!("/test/base.txt").into(),
+                    path!("/test/local.txt").into(),
+                    path!("/test/remote.txt").into(),
+                    path!("/test/output.txt").into(),
+                    workspace,
+                    window,
+                    cx,
+                )
+            })
+            .await
+            .unwrap();
+        cx.run_until_parked();
+
+        merge_view.read_with(cx, |merge_view, cx| {
+            assert_eq!(merge_view.result_buffer.read(cx).text(), output);
+            assert_eq!(merge_view.conflicts(cx).len(), 1);
+            assert!(!merge_view.is_dirty(cx));
+        });
+    }
+}
@@ -0,0 +1,120 @@
+use editor::{Editor, EditorEvent};
+use gpui::{
+    App, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, Render, Subscription,
+    WeakEntity,
+};
+use ui::prelude::*;
+use workspace::ModalView;
+
+use crate::HexEditor;
+
+/// Asks for an offset of the file in a hex editor to go to.
+pub struct GoToOffset {
+    offset_editor: Entity<Editor>,
+    hex_editor: WeakEntity<HexEditor>,
+    len: u64,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl ModalView for GoToOffset {}
+
+impl EventEmitter<DismissEvent> for GoToOffset {}
+
+impl Focusable for GoToOffset {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.offset_editor.focus_handle(cx)
+    }
+}
+
+impl GoToOffset {
+    pub fn new(
+        hex_editor: WeakEntity<HexEditor>,
+        len: u64,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let offset_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Offset", window, cx);
+            editor
+        });
+        let subscriptions = vec![
+            cx.subscribe_in(&offset_editor, window, |_, _, event, _, cx| match event {
+                EditorEvent::Blurred => cx.emit(DismissEvent),
+                EditorEvent::BufferEdited => cx.notify(),
+                _ => {}
+            }),
+        ];
+        Self {
+            offset_editor,
+            hex_editor,
+            len,
+            _subscriptions: subscriptions,
+        }
+    }
+
+    /// Returns the offset that was entered, in hexadecimal, if it is in the
+    /// file.
+    fn offset_from_query(&self, cx: &App) -> Option<u64> {
+        let query = self.offset_editor.read(cx).text(cx);
+        let query = query.trim();
+        let digits = query
+            .strip_prefix("0x")
+            .or_else(|| query.strip_prefix("0X"))
+            .unwrap_or(query);
+        let offset = u64::from_str_radix(digits, 16).ok()?;
+        (offset < self.len).then_some(offset)
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, _: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(offset) = self.offset_from_query(cx) else {
+            return;
+        };
+        self.hex_editor
+            .update(cx, |hex_editor, cx| {
+                hex_editor.go_to_offset(offset, cx);
+                hex_editor.focus_handle(cx).focus(window, cx);
+            })
+            .ok();
+        cx.emit(DismissEvent);
+    }
+}
+
+impl Render for GoToOffset {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let help_text = match self.offset_from_query(cx) {
+            Some(offset) => format!("Go to offset {offset:#x}"),
+            None if self.len == 0 => "The file is empty".to_string(),
+            None => format!(
+                "Enter a hexadecimal offset between 0 and {:#x}",
+                self.len - 1
+            ),
+        };
+
+        v_flex()
+            .w(rems(24.))
+            .elevation_2(cx)
+            .key_context("GoToOffset")
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::confirm))
+            .child(
+                div()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .px_2()
+                    .py_1()
+                    .child(self.offset_editor.clone()),
+            )
+            .child(
+                h_flex()
+                    .px_2()
+                    .py_1()
+                    .gap_1()
+                    .child(Label::new(help_text).color(Color::Muted)),
+            )
+    }
+}
@@ -0,0 +1,1097 @@
+//! Viewing and editing files as bytes, in hexadecimal and ASCII columns.
+//!
+//! Files that look binary open in a hex editor, as does any file with
+//! `hex_editor: open as hex`. Files are read in pages as they are scrolled,
+//! so that large ones take little memory, and bytes can only be overwritten,
+//! so that edits are saved in place.
+
+mod go_to_offset;
+mod hex_file;
+
+use std::{ops::Range, sync::Arc};
+
+use editor::{
+    EditorSettings,
+    actions::{
+        Copy, MoveDown, MoveLeft, MovePageDown, MovePageUp, MoveRight, MoveToBeginning,
+        MoveToBeginningOfLine, MoveToEnd, MoveToEndOfLine, MoveUp, SelectAll, SelectDown,
+        SelectLeft, SelectRight, SelectToBeginning, SelectToBeginningOfLine, SelectToEnd,
+        SelectToEndOfLine, SelectUp, Undo,
+    },
+    items::entry_git_aware_label_color,
+};
+use file_icons::FileIcons;
+use gpui::{
+    AnyElement, App, ClipboardItem, Context, Entity, EventEmitter, FocusHandle, Focusable, Hsla,
+    InteractiveElement, IntoElement, KeyDownEvent, MouseButton, MouseDownEvent, MouseMoveEvent,
+    ParentElement, Render, ScrollStrategy, SharedString, Styled, Subscription, Task,
+    UniformListScrollHandle, Window, actions, uniform_list,
+};
+use project::{Project, ProjectPath, search::SearchQuery};
+use settings::Settings;
+use theme::{Theme, ThemeSettings};
+use ui::{WithScrollbar, prelude::*};
+use util::paths::PathExt;
+use workspace::{
+    ItemSettings, Pane, ToolbarItemLocation, Workspace, WorkspaceId,
+    item::{BreadcrumbText, Item, ItemEvent, ProjectItem, SaveOptions, TabContentParams},
+    searchable::{Direction, SearchEvent, SearchOptions, SearchableItem, SearchableItemHandle},
+};
+
+pub use crate::hex_file::{BytePattern, HexFile, HexFileEvent, parse_hex_bytes};
+
+actions!(
+    hex_editor,
+    [
+        /// Opens the active file as bytes in a hex editor.
+        OpenAsHex,
+        /// Switches typing between the hexadecimal and the ASCII column.
+        ToggleColumn,
+        /// Goes to an offset of the file.
+        GoToOffset,
+    ]
+);
+
+/// The number of bytes shown in each row.
+const BYTES_PER_ROW: u64 = 16;
+
+/// The maximum number of matches a search finds.
+const MAX_SEARCH_MATCHES: usize = 100_000;
+
+/// The maximum number of bytes that are copied at once.
+const MAX_COPY_LEN: u64 = 16 * 1024 * 1024;
+
+pub fn init(cx: &mut App) {
+    workspace::register_fallback_project_item::<HexEditor>(cx);
+    cx.observe_new(|workspace: &mut Workspace, _, _| {
+        workspace.register_action(open_as_hex);
+    })
+    .detach();
+}
+
+fn open_as_hex(
+    workspace: &mut Workspace,
+    _: &OpenAsHex,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let Some(project_path) = workspace
+        .active_item(cx)
+        .and_then(|item| item.project_path(cx))
+    else {
+        return;
+    };
+    if let Some(hex_editor) = workspace
+        .items_of_type::<HexEditor>(cx)
+        .find(|hex_editor| hex_editor.read(cx).file.read(cx).project_path() == &project_path)
+    {
+        workspace.activate_item(&hex_editor, true, true, window, cx);
+        return;
+    }
+
+    let project = workspace.project().clone();
+    let Some(open) = HexFile::open(&project, &project_path, cx) else {
+        workspace.show_error(&"Only files of local projects can be opened as hex", cx);
+        return;
+    };
+    cx.spawn_in(window, async move |workspace, cx| {
+        let file = open.await?;
+        workspace.update_in(cx, |workspace, window, cx| {
+            let hex_editor = cx.new(|cx| HexEditor::new(file, project, cx));
+            workspace.add_item_to_active_pane(Box::new(hex_editor), None, true, window, cx);
+        })
+    })
+    .detach_and_log_err(cx);
+}
+
+/// The column that bytes are typed in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Column {
+    Hex,
+    Ascii,
+}
+
+pub enum HexEditorEvent {
+    Edited,
+    Saved,
+}
+
+pub struct HexEditor {
+    file: Entity<HexFile>,
+    project: Entity<Project>,
+    focus_handle: FocusHandle,
+    scroll_handle: UniformListScrollHandle,
+    cursor: u64,
+    /// The end of the selection that doesn't move when it is extended.
+    selection_tail: u64,
+    column: Column,
+    /// The high half of the byte being typed in the hexadecimal column.
+    pending_nibble: Option<u8>,
+    visible_rows: Range<usize>,
+    matches: Vec<Range<u64>>,
+    active_match: Option<usize>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl HexEditor {
+    pub fn new(file: Entity<HexFile>, project: Entity<Project>, cx: &mut Context<Self>) -> Self {
+        let subscriptions = vec![
+            cx.observe(&file, |_, _, cx| cx.notify()),
+            cx.subscribe(&file, |_, _, event, cx| match event {
+                HexFileEvent::Edited => {
+                    cx.emit(HexEditorEvent::Edited);
+                    cx.emit(SearchEvent::MatchesInvalidated);
+                }
+                HexFileEvent::Saved => cx.emit(HexEditorEvent::Saved),
+            }),
+        ];
+        Self {
+            file,
+            project,
+            focus_handle: cx.focus_handle(),
+            scroll_handle: UniformListScrollHandle::new(),
+            cursor: 0,
+            selection_tail: 0,
+            column: Column::Hex,
+            pending_nibble: None,
+            visible_rows: 0..0,
+            matches: Vec::new(),
+            active_match: None,
+            _subscriptions: subscriptions,
+        }
+    }
+
+    pub fn cursor(&self) -> u64 {
+        self.cursor
+    }
+
+    /// Returns the selected bytes, which always include the byte at the
+    /// cursor.
+    pub fn selection(&self, cx: &App) -> Range<u64> {
+        if self.file.read(cx).len() == 0 {
+            return 0..0;
+        }
+        self.cursor.min(self.selection_tail)..self.cursor.max(self.selection_tail) + 1
+    }
+
+    fn row_count(&self, cx: &App) -> usize {
+        self.file.read(cx).len().div_ceil(BYTES_PER_ROW) as usize
+    }
+
+    /// Moves the cursor to `offset`, extending the selection if `select` is
+    /// true.
+    fn move_cursor(&mut self, offset: u64, select: bool, cx: &mut Context<Self>) {
+        let max_offset = self.file.read(cx).len().saturating_sub(1);
+        self.cursor = offset.min(max_offset);
+        if !select {
+            self.selection_tail = self.cursor;
+        }
+        self.pending_nibble = None;
+        self.scroll_handle.scroll_to_item(
+            (self.cursor / BYTES_PER_ROW) as usize,
+            ScrollStrategy::Nearest,
+        );
+        cx.notify();
+    }
+
+    /// Selects the byte at `offset` and scrolls to it.
+    pub fn go_to_offset(&mut self, offset: u64, cx: &mut Context<Self>) {
+        self.move_cursor(offset, false, cx);
+        self.scroll_handle.scroll_to_item(
+            (self.cursor / BYTES_PER_ROW) as usize,
+            ScrollStrategy::Center,
+        );
+    }
+
+    fn move_by(&mut self, delta: i64, select: bool, cx: &mut Context<Self>) {
+        let offset = self.cursor.saturating_add_signed(delta);
+        self.move_cursor(offset, select, cx);
+    }
+
+    fn page_len(&self) -> i64 {
+        self.visible_rows.len().max(1) as i64 * BYTES_PER_ROW as i64
+    }
+
+    fn row_start(&self) -> u64 {
+        self.cursor - self.cursor % BYTES_PER_ROW
+    }
+
+    fn move_left(&mut self, _: &MoveLeft, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_by(-1, false, cx);
+    }
+
+    fn move_right(&mut self, _: &MoveRight, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_by(1, false, cx);
+    }
+
+    fn move_up(&mut self, _: &MoveUp, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_by(-(BYTES_PER_ROW as i64), false, cx);
+    }
+
+    fn move_down(&mut self, _: &MoveDown, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_by(BYTES_PER_ROW as i64, false, cx);
+    }
+
+    fn select_left(&mut self, _: &SelectLeft, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_by(-1, true, cx);
+    }
+
+    fn select_right(&mut self, _: &SelectRight, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_by(1, true, cx);
+    }
+
+    fn select_up(&mut self, _: &SelectUp, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_by(-(BYTES_PER_ROW as i64), true, cx);
+    }
+
+    fn select_down(&mut self, _: &SelectDown, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_by(BYTES_PER_ROW as i64, true, cx);
+    }
+
+    fn move_page_up(&mut self, _: &MovePageUp, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_by(-self.page_len(), false, cx);
+    }
+
+    fn move_page_down(&mut self, _: &MovePageDown, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_by(self.page_len(), false, cx);
+    }
+
+    fn move_to_beginning_of_line(
+        &mut self,
+        _: &MoveToBeginningOfLine,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.move_cursor(self.row_start(), false, cx);
+    }
+
+    fn move_to_end_of_line(&mut self, _: &MoveToEndOfLine, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_cursor(self.row_start() + BYTES_PER_ROW - 1, false, cx);
+    }
+
+    fn select_to_beginning_of_line(
+        &mut self,
+        _: &SelectToBeginningOfLine,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.move_cursor(self.row_start(), true, cx);
+    }
+
+    fn select_to_end_of_line(
+        &mut self,
+        _: &SelectToEndOfLine,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.move_cursor(self.row_start() + BYTES_PER_ROW - 1, true, cx);
+    }
+
+    fn move_to_beginning(&mut self, _: &MoveToBeginning, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_cursor(0, false, cx);
+    }
+
+    fn move_to_end(&mut self, _: &MoveToEnd, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_cursor(u64::MAX, false, cx);
+    }
+
+    fn select_to_beginning(
+        &mut self,
+        _: &SelectToBeginning,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.move_cursor(0, true, cx);
+    }
+
+    fn select_to_end(&mut self, _: &SelectToEnd, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_cursor(u64::MAX, true, cx);
+    }
+
+    fn select_all(&mut self, _: &SelectAll, _: &mut Window, cx: &mut Context<Self>) {
+        self.selection_tail = 0;
+        self.move_cursor(u64::MAX, true, cx);
+    }
+
+    fn toggle_column(&mut self, _: &ToggleColumn, _: &mut Window, cx: &mut Context<Self>) {
+        self.column = match self.column {
+            Column::Hex => Column::Ascii,
+            Column::Ascii => Column::Hex,
+        };
+        self.pending_nibble = None;
+        cx.notify();
+    }
+
+    fn undo(&mut self, _: &Undo, _: &mut Window, cx: &mut Context<Self>) {
+        if let Some(offset) = self.file.update(cx, |file, cx| file.undo(cx)) {
+            self.move_cursor(offset, false, cx);
+        }
+    }
+
+    /// Copies the selected bytes, as hexadecimal bytes from the hexadecimal
+    /// column and as text from the ASCII column.
+    fn copy(&mut self, _: &Copy, _: &mut Window, cx: &mut Context<Self>) {
+        let selection = self.selection(cx);
+        if selection.is_empty() || selection.end - selection.start > MAX_COPY_LEN {
+            return;
+        }
+        let read = self.file.read(cx).read(selection, cx);
+        let column = self.column;
+        cx.spawn(async move |_, cx| {
+            let bytes = read.await?;
+            let text = match column {
+                Column::Hex => format_hex_bytes(&bytes),
+                Column::Ascii => String::from_utf8_lossy(&bytes).into_owned(),
+            };
+            cx.update(|cx| cx.write_to_clipboard(ClipboardItem::new_string(text)))
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn toggle_go_to_offset(&mut self, _: &GoToOffset, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(workspace) = window.root::<Workspace>().flatten() else {
+            return;
+        };
+        let hex_editor = cx.entity().downgrade();
+        let len = self.file.read(cx).len();
+        workspace.update(cx, |workspace, cx| {
+            workspace.toggle_modal(window, cx, |window, cx| {
+                go_to_offset::GoToOffset::new(hex_editor, len, window, cx)
+            });
+        });
+    }
+
+    fn key_down(&mut self, event: &KeyDownEvent, _: &mut Window, cx: &mut Context<Self>) {
+        let modifiers = &event.keystroke.modifiers;
+        if modifiers.control || modifiers.alt || modifiers.platform || modifiers.function {
+            return;
+        }
+        let Some(key_char) = event.keystroke.key_char.as_deref() else {
+            return;
+        };
+        let mut chars = key_char.chars();
+        if let (Some(c), None) = (chars.next(), chars.next())
+            && self.type_char(c, cx)
+        {
+            cx.stop_propagation();
+        }
+    }
+
+    /// Overwrites the byte at the cursor with a typed character, returning
+    /// whether the character could be typed in the current column.
+    fn type_char(&mut self, c: char, cx: &mut Context<Self>) -> bool {
+        let offset = self.cursor;
+        if offset >= self.file.read(cx).len() {
+            return false;
+        }
+        let byte = match self.column {
+            Column::Hex => {
+                let Some(nibble) = c.to_digit(16).map(|digit| digit as u8) else {
+                    return false;
+                };
+                let Some(high) = self.pending_nibble.take() else {
+                    self.pending_nibble = Some(nibble);
+                    cx.notify();
+                    return true;
+                };
+                (high << 4) | nibble
+            }
+            Column::Ascii => {
+                if !c.is_ascii() || c.is_ascii_control() {
+                    return false;
+                }
+                c as u8
+            }
+        };
+        self.file
+            .update(cx, |file, cx| file.overwrite(offset, byte, cx));
+        self.move_cursor(offset + 1, false, cx);
+        true
+    }
+
+    fn mouse_down(
+        &mut self,
+        offset: u64,
+        column: Column,
+        event: &MouseDownEvent,
+        cx: &mut Context<Self>,
+    ) {
+        self.column = column;
+        self.move_cursor(offset, event.modifiers.shift, cx);
+    }
+
+    fn mouse_move(&mut self, offset: u64, event: &MouseMoveEvent, cx: &mut Context<Self>) {
+        if event.dragging() && self.cursor != offset {
+            self.move_cursor(offset, true, cx);
+        }
+    }
+
+    fn render_rows(
+        &mut self,
+        range: Range<usize>,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Vec<AnyElement> {
+        self.visible_rows = range.clone();
+        let rows_start = range.start as u64 * BYTES_PER_ROW;
+        let rows_end = range.end as u64 * BYTES_PER_ROW;
+        self.file
+            .update(cx, |file, cx| file.load(rows_start..rows_end, cx));
+
+        let len = self.file.read(cx).len();
+        let offset_width = offset_width(len);
+        let selection = self.selection(cx);
+        let colors = cx.theme().colors();
+        let player = cx.theme().players().local();
+        let styles = ByteStyles {
+            selection: player.selection,
+            cursor: player.cursor,
+            modified: cx.theme().status().modified,
+            muted: colors.text_muted,
+            search_match: colors.search_match_background,
+            active_search_match: colors.search_active_match_background,
+        };
+        let offset_color = colors.editor_line_number;
+
+        range
+            .map(|row| {
+                let row_start = row as u64 * BYTES_PER_ROW;
+                let row_end = (row_start + BYTES_PER_ROW).min(len);
+                let mut hex_cells = Vec::with_capacity(BYTES_PER_ROW as usize);
+                let mut ascii_cells = Vec::with_capacity(BYTES_PER_ROW as usize);
+                for offset in row_start..row_start + BYTES_PER_ROW {
+                    if offset >= row_end {
+                        hex_cells.push(div().w(rems(1.5)).into_any_element());
+                        continue;
+                    }
+                    hex_cells.push(self.render_byte(offset, Column::Hex, &selection, &styles, cx));
+                    ascii_cells.push(self.render_byte(
+                        offset,
+                        Column::Ascii,
+                        &selection,
+                        &styles,
+                        cx,
+                    ));
+                }
+
+                h_flex()
+                    .id(row)
+                    .w_full()
+                    .gap_4()
+                    .px_2()
+                    .whitespace_nowrap()
+                    .child(
+                        div()
+                            .text_color(offset_color)
+                            .child(format!("{row_start:0offset_width$x}")),
+                    )
+                    .child(h_flex().gap_1().children(hex_cells))
+                    .child(h_flex().children(ascii_cells))
+                    .into_any_element()
+            })
+            .collect()
+    }
+
+    fn render_byte(
+        &self,
+        offset: u64,
+        column: Column,
+        selection: &Range<u64>,
+        styles: &ByteStyles,
+        cx: &mut Context<Self>,
+    ) -> AnyElement {
+        let file = self.file.read(cx);
+        let byte = file.byte(offset);
+        let text = match (column, byte) {
+            (Column::Hex, Some(byte)) => format!("{byte:02x}"),
+            (Column::Hex, None) => "··".to_string(),
+            (Column::Ascii, Some(byte)) if byte.is_ascii_graphic() || byte == b' ' => {
+                (byte as char).to_string()
+            }
+            (Column::Ascii, _) => ".".to_string(),
+        };
+        let text = if column == Column::Hex
+            && offset == self.cursor
+            && let Some(high) = self.pending_nibble
+        {
+            format!("{high:x}_")
+        } else {
+            text
+        };
+        let text_color = if file.is_edited(offset) {
+            Some(styles.modified)
+        } else if byte.is_none() || (column == Column::Ascii && !byte.is_some_and(is_printable)) {
+            Some(styles.muted)
+        } else {
+            None
+        };
+        let match_ix = self
+            .matches
+            .partition_point(|search_match| search_match.end <= offset);
+        let background = if selection.contains(&offset) {
+            Some(styles.selection)
+        } else if self
+            .matches
+            .get(match_ix)
+            .is_some_and(|search_match| search_match.contains(&offset))
+        {
+            Some(if self.active_match == Some(match_ix) {
+                styles.active_search_match
+            } else {
+                styles.search_match
+            })
+        } else {
+            None
+        };
+        let is_cursor = offset == self.cursor && column == self.column;
+
+        div()
+            .when(column == Column::Hex, |this| this.w(rems(1.5)))
+            .when_some(text_color, |this, color| this.text_color(color))
+            .when_some(background, |this, color| this.bg(color))
+            .when(is_cursor, |this| {
+                this.border_b_2().border_color(styles.cursor)
+            })
+            .on_mouse_down(
+                MouseButton::Left,
+                cx.listener(move |this, event, _, cx| this.mouse_down(offset, column, event, cx)),
+            )
+            .on_mouse_move(
+                cx.listener(move |this, event, _, cx| this.mouse_move(offset, event, cx)),
+            )
+            .child(text)
+            .into_any_element()
+    }
+}
+
+struct ByteStyles {
+    selection: Hsla,
+    cursor: Hsla,
+    modified: Hsla,
+    muted: Hsla,
+    search_match: Hsla,
+    active_search_match: Hsla,
+}
+
+fn is_printable(byte: u8) -> bool {
+    byte.is_ascii_graphic() || byte == b' '
+}
+
+/// Returns the number of hexadecimal digits offsets are shown with.
+fn offset_width(len: u64) -> usize {
+    let digits = (u64::BITS - len.saturating_sub(1).leading_zeros()).div_ceil(4) as usize;
+    digits.max(8)
+}
+
+fn format_hex_bytes(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+impl EventEmitter<HexEditorEvent> for HexEditor {}
+impl EventEmitter<SearchEvent> for HexEditor {}
+
+impl Focusable for HexEditor {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for HexEditor {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let file = self.file.read(cx);
+        let len = file.len();
+        let selection = self.selection(cx);
+        let status = if let Some(error) = file.load_error() {
+            format!("Failed to read the file: {error}")
+        } else if selection.end - selection.start > 1 {
+            format!(
+                "{} bytes selected at {:#x} of {len} bytes",
+                selection.end - selection.start,
+                selection.start
+            )
+        } else {
+            format!("Offset {:#x} of {len} bytes", self.cursor)
+        };
+        let column = match self.column {
+            Column::Hex => "Typing hexadecimal",
+            Column::Ascii => "Typing ASCII",
+        };
+        let settings = ThemeSettings::get_global(cx);
+        let (buffer_font, buffer_font_size) =
+            (settings.buffer_font.clone(), settings.buffer_font_size(cx));
+
+        v_flex()
+            .key_context("HexEditor")
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::move_left))
+            .on_action(cx.listener(Self::move_right))
+            .on_action(cx.listener(Self::move_up))
+            .on_action(cx.listener(Self::move_down))
+            .on_action(cx.listener(Self::select_left))
+            .on_action(cx.listener(Self::select_right))
+            .on_action(cx.listener(Self::select_up))
+            .on_action(cx.listener(Self::select_down))
+            .on_action(cx.listener(Self::move_page_up))
+            .on_action(cx.listener(Self::move_page_down))
+            .on_action(cx.listener(Self::move_to_beginning_of_line))
+            .on_action(cx.listener(Self::move_to_end_of_line))
+            .on_action(cx.listener(Self::select_to_beginning_of_line))
+            .on_action(cx.listener(Self::select_to_end_of_line))
+            .on_action(cx.listener(Self::move_to_beginning))
+            .on_action(cx.listener(Self::move_to_end))
+            .on_action(cx.listener(Self::select_to_beginning))
+            .on_action(cx.listener(Self::select_to_end))
+            .on_action(cx.listener(Self::select_all))
+            .on_action(cx.listener(Self::toggle_column))
+            .on_action(cx.listener(Self::undo))
+            .on_action(cx.listener(Self::copy))
+            .on_action(cx.listener(Self::toggle_go_to_offset))
+            .on_key_down(cx.listener(Self::key_down))
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .child(
+                h_flex()
+                    .px_2()
+                    .py_1()
+                    .gap_2()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .child(
+                        Label::new(column)
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    )
+                    .child(div().flex_1())
+                    .child(
+                        Label::new(status)
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    ),
+            )
+            .child(
+                div()
+                    .flex_1()
+                    .size_full()
+                    .font(buffer_font)
+                    .text_size(buffer_font_size)
+                    .child(
+                        uniform_list(
+                            "hex-editor-rows",
+                            self.row_count(cx),
+                            cx.processor(|this, range, window, cx| {
+                                this.render_rows(range, window, cx)
+                            }),
+                        )
+                        .track_scroll(&self.scroll_handle)
+                        .size_full(),
+                    )
+                    .vertical_scrollbar_for(&self.scroll_handle, window, cx),
+            )
+    }
+}
+
+impl Item for HexEditor {
+    type Event = HexEditorEvent;
+
+    fn to_item_events(event: &Self::Event, mut f: impl FnMut(ItemEvent)) {
+        match event {
+            HexEditorEvent::Edited => {
+                f(ItemEvent::Edit);
+                f(ItemEvent::UpdateTab);
+            }
+            HexEditorEvent::Saved => f(ItemEvent::UpdateTab),
+        }
+    }
+
+    fn for_each_project_item(
+        &self,
+        cx: &App,
+        f: &mut dyn FnMut(gpui::EntityId, &dyn project::ProjectItem),
+    ) {
+        f(self.file.entity_id(), self.file.read(cx))
+    }
+
+    fn tab_tooltip_text(&self, cx: &App) -> Option<SharedString> {
+        let abs_path = self.file.read(cx).abs_path();
+        Some(abs_path.compact().to_string_lossy().into_owned().into())
+    }
+
+    fn tab_content(&self, params: TabContentParams, _window: &Window, cx: &App) -> AnyElement {
+        let project_path = self.file.read(cx).project_path().clone();
+        let label_color = if ItemSettings::get_global(cx).git_status {
+            let git_status = self
+                .project
+                .read(cx)
+                .project_path_git_status(&project_path, cx)
+                .map(|status| status.summary())
+                .unwrap_or_default();
+
+            self.project
+                .read(cx)
+                .entry_for_path(&project_path, cx)
+                .map(|entry| {
+                    entry_git_aware_label_color(git_status, entry.is_ignored, params.selected)
+                })
+                .unwrap_or_else(|| params.text_color())
+        } else {
+            params.text_color()
+        };
+
+        Label::new(self.tab_content_text(params.detail.unwrap_or_default(), cx))
+            .single_line()
+            .color(label_color)
+            .when(params.preview, |this| this.italic())
+            .into_any_element()
+    }
+
+    fn tab_content_text(&self, _: usize, cx: &App) -> SharedString {
+        self.file
+            .read(cx)
+            .project_path()
+            .path
+            .file_name()
+            .unwrap_or_default()
+            .to_string()
+            .into()
+    }
+
+    fn tab_icon(&self, _: &Window, cx: &App) -> Option<Icon> {
+        let path = self.file.read(cx).abs_path();
+        ItemSettings::get_global(cx)
+            .file_icons
+            .then(|| FileIcons::get_icon(path, cx))
+            .flatten()
+            .map(Icon::from_path)
+    }
+
+    fn breadcrumb_location(&self, cx: &App) -> ToolbarItemLocation {
+        if EditorSettings::get_global(cx).toolbar.breadcrumbs {
+            ToolbarItemLocation::PrimaryLeft
+        } else {
+            ToolbarItemLocation::Hidden
+        }
+    }
+
+    fn breadcrumbs(&self, _theme: &Theme, cx: &App) -> Option<Vec<BreadcrumbText>> {
+        let project = self.project.read(cx);
+        let project_path = self.file.read(cx).project_path();
+        let mut path = project_path.path.clone();
+        if project.visible_worktrees(cx).count() > 1
+            && let Some(worktree) = project.worktree_for_id(project_path.worktree_id, cx)
+        {
+            path = worktree.read(cx).root_name().join(&path);
+        }
+
+        Some(vec![BreadcrumbText {
+            text: path.display(project.path_style(cx)).to_string(),
+            highlights: None,
+            font: Some(ThemeSettings::get_global(cx).buffer_font.clone()),
+        }])
+    }
+
+    fn is_dirty(&self, cx: &App) -> bool {
+        self.file.read(cx).is_dirty()
+    }
+
+    fn can_save(&self, _: &App) -> bool {
+        true
+    }
+
+    fn save(
+        &mut self,
+        _: SaveOptions,
+        _: Entity<Project>,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<anyhow::Result<()>> {
+        self.file.update(cx, |file, cx| file.save(cx))
+    }
+
+    fn save_as(
+        &mut self,
+        _: Entity<Project>,
+        _: ProjectPath,
+        _: &mut Window,
+        _: &mut Context<Self>,
+    ) -> Task<anyhow::Result<()>> {
+        Task::ready(Err(anyhow::anyhow!(
+            "files opened as hex can only be saved in place"
+        )))
+    }
+
+    fn reload(
+        &mut self,
+        _: Entity<Project>,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<anyhow::Result<()>> {
+        self.file.update(cx, |file, cx| file.reload(cx))
+    }
+
+    fn as_searchable(
+        &self,
+        handle: &Entity<Self>,
+        _: &App,
+    ) -> Option<Box<dyn SearchableItemHandle>> {
+        Some(Box::new(handle.clone()))
+    }
+
+    fn can_split(&self) -> bool {
+        true
+    }
+
+    fn clone_on_split(
+        &self,
+        _workspace_id: Option<WorkspaceId>,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Option<Entity<Self>>>
+    where
+        Self: Sized,
+    {
+        let file = self.file.clone();
+        let project = self.project.clone();
+        Task::ready(Some(cx.new(|cx| Self::new(file, project, cx))))
+    }
+
+    fn buffer_kind(&self, _: &App) -> workspace::item::ItemBufferKind {
+        workspace::item::ItemBufferKind::Singleton
+    }
+}
+
+impl SearchableItem for HexEditor {
+    type Match = Range<u64>;
+
+    fn supported_options(&self) -> SearchOptions {
+        SearchOptions {
+            case: true,
+            word: false,
+            regex: false,
+            replacement: false,
+            selection: false,
+            find_in_results: false,
+        }
+    }
+
+    fn clear_matches(&mut self, _: &mut Window, cx: &mut Context<Self>) {
+        self.matches.clear();
+        self.active_match = None;
+        cx.notify();
+    }
+
+    fn update_matches(
+        &mut self,
+        matches: &[Self::Match],
+        active_match_index: Option<usize>,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.matches = matches.to_vec();
+        self.active_match = active_match_index;
+        cx.notify();
+    }
+
+    fn query_suggestion(&mut self, _: &mut Window, cx: &mut Context<Self>) -> String {
+        let selection = self.selection(cx);
+        if selection.end - selection.start < 2 {
+            return String::new();
+        }
+        let file = self.file.read(cx);
+        selection
+            .map(|offset| file.byte(offset))
+            .collect::<Option<Vec<_>>>()
+            .filter(|bytes| bytes.len() <= 64)
+            .map(|bytes| format_hex_bytes(&bytes))
+            .unwrap_or_default()
+    }
+
+    fn activate_match(
+        &mut self,
+        index: usize,
+        matches: &[Self::Match],
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.active_match = Some(index);
+        if let Some(active_match) = matches.get(index) {
+            self.go_to_offset(active_match.start, cx);
+            self.move_cursor(active_match.end - 1, true, cx);
+        }
+    }
+
+    fn select_matches(&mut self, _: &[Self::Match], _: &mut Window, _: &mut Context<Self>) {
+        // Hex editors have a single selection.
+    }
+
+    fn replace(&mut self, _: &Self::Match, _: &SearchQuery, _: &mut Window, _: &mut Context<Self>) {
+        // Bytes are overwritten by typing them.
+    }
+
+    fn find_matches(
+        &mut self,
+        query: Arc<SearchQuery>,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Vec<Self::Match>> {
+        let pattern = BytePattern::parse(query.as_str(), query.case_sensitive());
+        let search = self.file.read(cx).search(pattern, MAX_SEARCH_MATCHES, cx);
+        cx.background_spawn(async move {
+            search.await.unwrap_or_else(|error| {
+                log::error!("failed to search file: {error:#}");
+                Vec::new()
+            })
+        })
+    }
+
+    fn active_match_index(
+        &mut self,
+        _: Direction,
+        matches: &[Self::Match],
+        _: &mut Window,
+        _: &mut Context<Self>,
+    ) -> Option<usize> {
+        if matches.is_empty() {
+            return None;
+        }
+        Some(
+            matches
+                .iter()
+                .position(|search_match| search_match.end > self.cursor)
+                .unwrap_or(matches.len() - 1),
+        )
+    }
+}
+
+impl ProjectItem for HexEditor {
+    type Item = HexFile;
+
+    fn for_project_item(
+        project: Entity<Project>,
+        _: Option<&Pane>,
+        item: Entity<Self::Item>,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self
+    where
+        Self: Sized,
+    {
+        Self::new(item, project, cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use editor::Editor;
+    use gpui::TestAppContext;
+    use project::FakeFs;
+    use serde_json::json;
+    use util::{path, rel_path::rel_path};
+    use workspace::AppState;
+
+    #[test]
+    fn test_offset_width() {
+        assert_eq!(offset_width(0x100), 8);
+        assert_eq!(offset_width(0x1_0000_0001), 9);
+    }
+
+    #[gpui::test]
+    async fn test_opening_binary_files(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            AppState::test(cx);
+            editor::init(cx);
+            super::init(cx);
+        });
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            path!("/dir"),
+            json!({
+                "a.txt": "fn main() {}\n",
+                "empty": "",
+            }),
+        )
+        .await;
+        fs.insert_file(path!("/dir/a.bin"), b"\x7fELF\x02\x01\x01\x00\x00".to_vec())
+            .await;
+        let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+        let worktree_id = project.read_with(cx, |project, cx| {
+            project.worktrees(cx).next().unwrap().read(cx).id()
+        });
+        let (workspace, cx) =
+            cx.add_window_view(|window, cx| Workspace::test_new(project.clone(), window, cx));
+
+        let open = |path: &str, cx: &mut gpui::VisualTestContext| {
+            workspace.update_in(cx, |workspace, window, cx| {
+                workspace.open_path((worktree_id, rel_path(path)), None, true, window, cx)
+            })
+        };
+        let item = open("a.bin", cx).await.unwrap();
+        assert!(item.downcast::<HexEditor>().is_some());
+        let item = open("a.txt", cx).await.unwrap();
+        assert!(item.downcast::<Editor>().is_some());
+        let item = open("empty", cx).await.unwrap();
+        assert!(item.downcast::<Editor>().is_some());
+    }
+
+    #[gpui::test]
+    async fn test_typing_and_saving(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            AppState::test(cx);
+            super::init(cx);
+        });
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(path!("/dir"), json!({})).await;
+        fs.insert_file(path!("/dir/a.bin"), b"\x00\x01\x02\x03ab\x00\x00".to_vec())
+            .await;
+        let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+        let project_path = project.read_with(cx, |project, cx| ProjectPath {
+            worktree_id: project.worktrees(cx).next().unwrap().read(cx).id(),
+            path: rel_path("a.bin").into(),
+        });
+        let file = cx
+            .update(|cx| HexFile::open(&project, &project_path, cx))
+            .unwrap()
+            .await
+            .unwrap();
+
+        let (hex_editor, cx) =
+            cx.add_window_view(|_, cx| HexEditor::new(file.clone(), project.clone(), cx));
+        cx.run_until_parked();
+        hex_editor.update_in(cx, |hex_editor, window, cx| {
+            hex_editor.file.update(cx, |file, cx| file.load(0..8, cx));
+            hex_editor.move_right(&MoveRight, window, cx);
+            assert!(hex_editor.type_char('f', cx));
+            assert!(!hex_editor.type_char('g', cx));
+            assert!(hex_editor.type_char('F', cx));
+            assert_eq!(hex_editor.cursor(), 2);
+
+            hex_editor.toggle_column(&ToggleColumn, window, cx);
+            assert!(hex_editor.type_char('Z', cx));
+            assert!(!hex_editor.type_char('é', cx));
+            assert_eq!(hex_editor.cursor(), 3);
+
+            hex_editor.select_to_end(&SelectToEnd, window, cx);
+            assert_eq!(hex_editor.selection(cx), 3..8);
+            assert!(hex_editor.is_dirty(cx));
+        });
+        cx.run_until_parked();
+        file.read_with(cx, |file, _| {
+            assert_eq!(file.byte(1), Some(0xff));
+            assert_eq!(file.byte(2), Some(b'Z'));
+        });
+
+        let save = hex_editor.update_in(cx, |hex_editor, window, cx| {
+            hex_editor.save(SaveOptions::default(), project.clone(), window, cx)
+        });
+        save.await.unwrap();
+        assert_eq!(
+            fs.load_bytes(path!("/dir/a.bin").as_ref()).await.unwrap(),
+            b"\x00\xffZ\x03ab\x00\x00"
+        );
+        hex_editor.read_with(cx, |hex_editor, cx| assert!(!hex_editor.is_dirty(cx)));
+    }
+}
@@ -0,0 +1,554 @@
+//! The bytes of a file, read in pages through [`Fs`], with the bytes that
+//! have been overwritten but not saved yet.
+
+use std::{collections::BTreeMap, ops::Range, path::PathBuf, sync::Arc};
+
+use anyhow::{Context as _, Result};
+use collections::HashMap;
+use fs::Fs;
+use gpui::{App, AppContext as _, Context, Entity, EventEmitter, SharedString, Task};
+use project::{Project, ProjectEntryId, ProjectPath};
+
+/// The number of bytes read from the file at once.
+const PAGE_LEN: u64 = 64 * 1024;
+
+/// The maximum number of pages that are kept in memory.
+const MAX_CACHED_PAGES: usize = 64;
+
+/// The number of bytes searched at once.
+const SEARCH_BLOCK_LEN: u64 = 1024 * 1024;
+
+enum Page {
+    Loading,
+    Loaded(Arc<[u8]>),
+    Failed,
+}
+
+/// An overwrite of a byte, recording what it replaced so that it can be
+/// undone.
+struct Overwrite {
+    offset: u64,
+    previous: Option<u8>,
+}
+
+pub enum HexFileEvent {
+    Edited,
+    Saved,
+}
+
+/// A file that is viewed and edited as bytes. Bytes can only be overwritten,
+/// so the length of the file doesn't change and edits are saved in place.
+pub struct HexFile {
+    fs: Arc<dyn Fs>,
+    abs_path: PathBuf,
+    project_path: ProjectPath,
+    entry_id: Option<ProjectEntryId>,
+    len: u64,
+    pages: HashMap<u64, Page>,
+    edits: BTreeMap<u64, u8>,
+    undo_stack: Vec<Overwrite>,
+    load_error: Option<SharedString>,
+}
+
+impl EventEmitter<HexFileEvent> for HexFile {}
+
+impl HexFile {
+    pub fn new(
+        fs: Arc<dyn Fs>,
+        abs_path: PathBuf,
+        project_path: ProjectPath,
+        entry_id: Option<ProjectEntryId>,
+        len: u64,
+    ) -> Self {
+        Self {
+            fs,
+            abs_path,
+            project_path,
+            entry_id,
+            len,
+            pages: HashMap::default(),
+            edits: BTreeMap::new(),
+            undo_stack: Vec::new(),
+            load_error: None,
+        }
+    }
+
+    /// Opens a file of a local project, whatever its contents.
+    pub fn open(
+        project: &Entity<Project>,
+        path: &ProjectPath,
+        cx: &mut App,
+    ) -> Option<Task<Result<Entity<Self>>>> {
+        let project = project.read(cx);
+        // Files of remote projects can't be read in pages yet.
+        if !project.is_local() {
+            return None;
+        }
+        let entry = project.entry_for_path(path, cx)?;
+        if !entry.is_file() {
+            return None;
+        }
+        let entry_id = Some(entry.id);
+        let abs_path = project.absolute_path(path, cx)?;
+        let project_path = path.clone();
+        let fs = project.fs().clone();
+
+        Some(cx.spawn(async move |cx| {
+            let len = fs
+                .metadata(&abs_path)
+                .await?
+                .with_context(|| format!("{abs_path:?} doesn't exist"))?
+                .len;
+            cx.new(|_| Self::new(fs, abs_path, project_path, entry_id, len))
+        }))
+    }
+
+    pub fn abs_path(&self) -> &PathBuf {
+        &self.abs_path
+    }
+
+    pub fn project_path(&self) -> &ProjectPath {
+        &self.project_path
+    }
+
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    pub fn load_error(&self) -> Option<&SharedString> {
+        self.load_error.as_ref()
+    }
+
+    /// Returns the byte at `offset`, or `None` if its page hasn't been loaded.
+    pub fn byte(&self, offset: u64) -> Option<u8> {
+        if let Some(byte) = self.edits.get(&offset) {
+            return Some(*byte);
+        }
+        self.saved_byte(offset)
+    }
+
+    /// Returns the byte at `offset` as it is on disk.
+    fn saved_byte(&self, offset: u64) -> Option<u8> {
+        match self.pages.get(&(offset / PAGE_LEN))? {
+            Page::Loaded(bytes) => bytes.get((offset % PAGE_LEN) as usize).copied(),
+            Page::Loading | Page::Failed => None,
+        }
+    }
+
+    pub fn is_edited(&self, offset: u64) -> bool {
+        self.edits.contains_key(&offset)
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        !self.edits.is_empty()
+    }
+
+    /// Loads the pages of `range` that aren't loaded yet, evicting the other
+    /// pages when too many are in memory.
+    pub fn load(&mut self, range: Range<u64>, cx: &mut Context<Self>) {
+        let range = range.start.min(self.len)..range.end.min(self.len);
+        if range.is_empty() {
+            return;
+        }
+        let pages = range.start / PAGE_LEN..(range.end - 1) / PAGE_LEN + 1;
+        if self.pages.len() >= MAX_CACHED_PAGES {
+            self.pages.retain(|page, _| pages.contains(page));
+        }
+
+        for page in pages {
+            if self.pages.contains_key(&page) {
+                continue;
+            }
+            let fs = self.fs.clone();
+            let abs_path = self.abs_path.clone();
+            let page_range = page * PAGE_LEN..((page + 1) * PAGE_LEN).min(self.len);
+            self.pages.insert(page, Page::Loading);
+            cx.spawn(async move |this, cx| {
+                let result = fs.load_range(&abs_path, page_range).await;
+                this.update(cx, |this, cx| {
+                    let page_state = match result {
+                        Ok(bytes) => Page::Loaded(bytes.into()),
+                        Err(error) => {
+                            log::error!("failed to read {:?}: {error:#}", this.abs_path);
+                            this.load_error = Some(format!("{error:#}").into());
+                            Page::Failed
+                        }
+                    };
+                    this.pages.insert(page, page_state);
+                    cx.notify();
+                })
+                .ok();
+            })
+            .detach();
+        }
+    }
+
+    /// Overwrites the byte at `offset`, which must be within the file.
+    pub fn overwrite(&mut self, offset: u64, byte: u8, cx: &mut Context<Self>) {
+        if offset >= self.len {
+            return;
+        }
+        let previous = self.edits.get(&offset).copied();
+        self.undo_stack.push(Overwrite { offset, previous });
+        self.set_byte(offset, Some(byte));
+        cx.emit(HexFileEvent::Edited);
+        cx.notify();
+    }
+
+    /// Undoes the last overwrite, returning the offset of the byte it restored.
+    pub fn undo(&mut self, cx: &mut Context<Self>) -> Option<u64> {
+        let overwrite = self.undo_stack.pop()?;
+        self.set_byte(overwrite.offset, overwrite.previous);
+        cx.emit(HexFileEvent::Edited);
+        cx.notify();
+        Some(overwrite.offset)
+    }
+
+    fn set_byte(&mut self, offset: u64, byte: Option<u8>) {
+        match byte {
+            Some(byte) if self.saved_byte(offset) != Some(byte) => {
+                self.edits.insert(offset, byte);
+            }
+            _ => {
+                self.edits.remove(&offset);
+            }
+        }
+    }
+
+    /// Writes the overwritten bytes to the file, in place.
+    pub fn save(&mut self, cx: &mut Context<Self>) -> Task<Result<()>> {
+        let runs = edit_runs(&self.edits);
+        let fs = self.fs.clone();
+        let abs_path = self.abs_path.clone();
+        cx.spawn(async move |this, cx| {
+            for (offset, bytes) in &runs {
+                fs.write_at(&abs_path, *offset, bytes)
+                    .await
+                    .with_context(|| format!("failed to write {abs_path:?}"))?;
+            }
+            this.update(cx, |this, cx| {
+                // Only the bytes that were saved are cleared, as more may have
+                // been overwritten while saving.
+                for (offset, bytes) in runs {
+                    for (offset, byte) in (offset..).zip(bytes) {
+                        if this.edits.get(&offset) == Some(&byte) {
+                            this.edits.remove(&offset);
+                        }
+                    }
+                }
+                this.undo_stack.clear();
+                this.pages.clear();
+                cx.emit(HexFileEvent::Saved);
+                cx.notify();
+            })
+        })
+    }
+
+    /// Discards the overwritten bytes, and reads the file again.
+    pub fn reload(&mut self, cx: &mut Context<Self>) -> Task<Result<()>> {
+        let fs = self.fs.clone();
+        let abs_path = self.abs_path.clone();
+        cx.spawn(async move |this, cx| {
+            let len = fs
+                .metadata(&abs_path)
+                .await?
+                .with_context(|| format!("{abs_path:?} doesn't exist"))?
+                .len;
+            this.update(cx, |this, cx| {
+                this.len = len;
+                this.pages.clear();
+                this.edits.clear();
+                this.undo_stack.clear();
+                this.load_error = None;
+                cx.emit(HexFileEvent::Saved);
+                cx.notify();
+            })
+        })
+    }
+
+    /// Reads the bytes of `range`, with its overwritten bytes.
+    pub fn read(&self, range: Range<u64>, cx: &App) -> Task<Result<Vec<u8>>> {
+        let fs = self.fs.clone();
+        let abs_path = self.abs_path.clone();
+        let edits = self
+            .edits
+            .range(range.clone())
+            .map(|(offset, byte)| (*offset, *byte))
+            .collect::<Vec<_>>();
+        cx.background_spawn(async move {
+            let mut bytes = fs.load_range(&abs_path, range.clone()).await?;
+            for (offset, byte) in edits {
+                if let Some(slot) = bytes.get_mut((offset - range.start) as usize) {
+                    *slot = byte;
+                }
+            }
+            Ok(bytes)
+        })
+    }
+
+    /// Finds the occurrences of `pattern` in the file, with its overwritten
+    /// bytes, stopping after `max_matches`.
+    pub fn search(
+        &self,
+        pattern: BytePattern,
+        max_matches: usize,
+        cx: &App,
+    ) -> Task<Result<Vec<Range<u64>>>> {
+        let fs = self.fs.clone();
+        let abs_path = self.abs_path.clone();
+        let len = self.len;
+        let edits = self.edits.clone();
+        cx.background_spawn(async move {
+            let mut matches = Vec::new();
+            if pattern.bytes.is_empty() {
+                return Ok(matches);
+            }
+            let overlap = pattern.bytes.len() as u64 - 1;
+            let mut search_start = 0;
+            let mut block_start = 0;
+            while block_start < len && matches.len() < max_matches {
+                let block_end = (block_start + SEARCH_BLOCK_LEN).min(len);
+                let mut block = fs
+                    .load_range(&abs_path, block_start..(block_end + overlap).min(len))
+                    .await?;
+                let block_range = block_start..block_start + block.len() as u64;
+                for (offset, byte) in edits.range(block_range) {
+                    block[(offset - block_start) as usize] = *byte;
+                }
+                for start in pattern.find_in(&block) {
+                    let start = block_start + start as u64;
+                    if start >= block_end || matches.len() >= max_matches {
+                        break;
+                    }
+                    // Matches may overlap the end of the previous block.
+                    if start >= search_start {
+                        matches.push(start..start + pattern.bytes.len() as u64);
+                        search_start = start + pattern.bytes.len() as u64;
+                    }
+                }
+                block_start = block_end;
+                smol::future::yield_now().await;
+            }
+            Ok(matches)
+        })
+    }
+}
+
+impl project::ProjectItem for HexFile {
+    fn try_open(
+        project: &Entity<Project>,
+        path: &ProjectPath,
+        cx: &mut App,
+    ) -> Option<Task<Result<Entity<Self>>>> {
+        Self::open(project, path, cx)
+    }
+
+    fn entry_id(&self, _: &App) -> Option<ProjectEntryId> {
+        self.entry_id
+    }
+
+    fn project_path(&self, _: &App) -> Option<ProjectPath> {
+        Some(self.project_path.clone())
+    }
+
+    fn is_dirty(&self) -> bool {
+        HexFile::is_dirty(self)
+    }
+}
+
+/// Returns the runs of consecutive overwritten bytes, by offset.
+fn edit_runs(edits: &BTreeMap<u64, u8>) -> Vec<(u64, Vec<u8>)> {
+    let mut runs: Vec<(u64, Vec<u8>)> = Vec::new();
+    for (offset, byte) in edits {
+        if let Some((start, bytes)) = runs.last_mut()
+            && *start + bytes.len() as u64 == *offset
+        {
+            bytes.push(*byte);
+        } else {
+            runs.push((*offset, vec![*byte]));
+        }
+    }
+    runs
+}
+
+/// The bytes searched for in a file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BytePattern {
+    bytes: Vec<u8>,
+    /// Whether ASCII letters match regardless of case.
+    ignore_case: bool,
+}
+
+impl BytePattern {
+    /// Parses a search query, which is either hexadecimal bytes after a `0x`
+    /// prefix, such as `0x7f 45 4c 46` or `0x7f454c46`, or text, which is
+    /// searched for as UTF-8.
+    pub fn parse(query: &str, case_sensitive: bool) -> Self {
+        match query.strip_prefix("0x").and_then(parse_hex_bytes) {
+            Some(bytes) => Self {
+                bytes,
+                ignore_case: false,
+            },
+            None => Self {
+                bytes: query.as_bytes().to_vec(),
+                ignore_case: !case_sensitive,
+            },
+        }
+    }
+
+    /// Returns the starts of the occurrences of the pattern in `haystack`,
+    /// including overlapping ones.
+    fn find_in<'a>(&'a self, haystack: &'a [u8]) -> impl Iterator<Item = usize> + 'a {
+        haystack
+            .windows(self.bytes.len())
+            .enumerate()
+            .filter(|(_, window)| {
+                if self.ignore_case {
+                    window.eq_ignore_ascii_case(&self.bytes)
+                } else {
+                    *window == self.bytes.as_slice()
+                }
+            })
+            .map(|(start, _)| start)
+    }
+}
+
+/// Parses bytes written as pairs of hexadecimal digits, which may be
+/// separated by whitespace.
+pub fn parse_hex_bytes(text: &str) -> Option<Vec<u8>> {
+    let mut bytes = Vec::new();
+    for word in text.split_whitespace() {
+        let digits = word
+            .chars()
+            .map(|c| c.to_digit(16).map(|digit| digit as u8))
+            .collect::<Option<Vec<_>>>()?;
+        if digits.len() % 2 != 0 {
+            return None;
+        }
+        bytes.extend(digits.chunks(2).map(|pair| pair[0] << 4 | pair[1]));
+    }
+    (!bytes.is_empty()).then_some(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+    use project::{FakeFs, WorktreeId};
+    use util::{path, rel_path::rel_path};
+
+    #[test]
+    fn test_parse_hex_bytes() {
+        assert_eq!(
+            parse_hex_bytes("7f 45 4C 46"),
+            Some(vec![0x7f, 0x45, 0x4c, 0x46])
+        );
+        assert_eq!(parse_hex_bytes("cafe  00"), Some(vec![0xca, 0xfe, 0x00]));
+        assert_eq!(parse_hex_bytes("abc"), None);
+        assert_eq!(parse_hex_bytes("zz"), None);
+        assert_eq!(parse_hex_bytes("  "), None);
+
+        assert_eq!(
+            BytePattern::parse("Hello", false),
+            BytePattern {
+                bytes: b"Hello".to_vec(),
+                ignore_case: true,
+            }
+        );
+        assert_eq!(
+            BytePattern::parse("cafe", true),
+            BytePattern {
+                bytes: b"cafe".to_vec(),
+                ignore_case: false,
+            }
+        );
+        assert_eq!(
+            BytePattern::parse("0xcafe 00", false),
+            BytePattern {
+                bytes: vec![0xca, 0xfe, 0x00],
+                ignore_case: false,
+            }
+        );
+        assert_eq!(
+            BytePattern::parse("0xzz", false),
+            BytePattern {
+                bytes: b"0xzz".to_vec(),
+                ignore_case: true,
+            }
+        );
+    }
+
+    #[test]
+    fn test_edit_runs() {
+        let edits = BTreeMap::from_iter([(1, 0xa), (2, 0xb), (3, 0xc), (7, 0xd), (9, 0xe)]);
+        assert_eq!(
+            edit_runs(&edits),
+            [(1, vec![0xa, 0xb, 0xc]), (7, vec![0xd]), (9, vec![0xe])]
+        );
+    }
+
+    #[gpui::test]
+    async fn test_overwrite_search_and_save(cx: &mut TestAppContext) {
+        let fs = FakeFs::new(cx.executor());
+        let mut contents = vec![0u8; 3 * PAGE_LEN as usize];
+        contents[10..14].copy_from_slice(b"\x7fELF");
+        contents[PAGE_LEN as usize - 2..PAGE_LEN as usize + 2].copy_from_slice(b"\x7fELF");
+        fs.insert_file(path!("/dir/a.bin"), contents.clone()).await;
+
+        let file = cx.new(|_| {
+            HexFile::new(
+                fs.clone(),
+                PathBuf::from(path!("/dir/a.bin")),
+                ProjectPath {
+                    worktree_id: WorktreeId::from_usize(0),
+                    path: rel_path("a.bin").into(),
+                },
+                None,
+                contents.len() as u64,
+            )
+        });
+        file.update(cx, |file, cx| file.load(0..20, cx));
+        cx.run_until_parked();
+        file.read_with(cx, |file, _| {
+            assert_eq!(file.byte(11), Some(b'E'));
+            assert_eq!(file.byte(PAGE_LEN), None);
+        });
+
+        let search = |pattern: &str, cx: &mut TestAppContext| {
+            file.read_with(cx, |file, cx| {
+                file.search(BytePattern::parse(pattern, true), usize::MAX, cx)
+            })
+        };
+        assert_eq!(
+            search("0x7f 45 4c 46", cx).await.unwrap(),
+            [10..14, PAGE_LEN - 2..PAGE_LEN + 2]
+        );
+
+        file.update(cx, |file, cx| {
+            file.overwrite(12, b'X', cx);
+            file.overwrite(13, b'Y', cx);
+            file.overwrite(13, b'F', cx);
+            assert!(file.is_dirty());
+            assert_eq!(file.byte(12), Some(b'X'));
+            assert!(!file.is_edited(13));
+        });
+        assert_eq!(search("0x7f 45 58 46", cx).await.unwrap(), [10..14]);
+
+        file.update(cx, |file, cx| {
+            assert_eq!(file.undo(cx), Some(13));
+            assert_eq!(file.undo(cx), Some(13));
+            assert_eq!(file.byte(13), Some(b'F'));
+            assert!(file.is_dirty());
+        });
+        let save = file.update(cx, |file, cx| file.save(cx));
+        cx.run_until_parked();
+        save.await.unwrap();
+        file.read_with(cx, |file, _| assert!(!file.is_dirty()));
+
+        contents[12] = b'X';
+        assert_eq!(
+            fs.load_bytes(path!("/dir/a.bin").as_ref()).await.unwrap(),
+            contents
+        );
+    }
+}
@@ -49,6 +49,7 @@ pub enum IconName {
     BoltOutlined,
     Book,
     BookCopy,
+    Bookmark,
     Box,
     CaseSensitive,
     Chat,
@@ -0,0 +1,451 @@
+//! Records the actions that keystrokes dispatch and the text typed in editors,
+//! so that they can be played back as keyboard macros.
+
+mod playback;
+mod recording_indicator;
+mod save_macro;
+
+use std::{num::NonZeroUsize, rc::Rc};
+
+use anyhow::{Context as _, Result};
+use collections::HashMap;
+use editor::{Editor, EditorEvent, actions::HandleInput};
+use gpui::{Action, App, AppContext as _, Context, Entity, Global, Window, actions, prelude::*};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use settings::{KeymapAction, RegisterSetting, Settings};
+use workspace::Workspace;
+
+use playback::{Playback, PlaybackStep};
+pub use recording_indicator::RecordingIndicator;
+use save_macro::SaveMacro;
+
+actions!(
+    keyboard_macros,
+    [
+        /// Starts recording a keyboard macro, or stops the recording in progress.
+        ToggleRecording,
+        /// Plays the last recorded keyboard macro once at each selection of the
+        /// active editor.
+        PlayLastOnEverySelection,
+        /// Saves the last recorded keyboard macro under a name, so that it can
+        /// be bound in the keymap.
+        SaveLast,
+    ]
+);
+
+/// Plays the last recorded keyboard macro.
+#[derive(PartialEq, Clone, Default, Deserialize, JsonSchema, Action)]
+#[action(namespace = keyboard_macros)]
+#[serde(deny_unknown_fields)]
+pub struct PlayLast {
+    /// How many times to play the macro.
+    #[serde(default)]
+    pub times: Option<NonZeroUsize>,
+}
+
+/// Plays a keyboard macro of the `keyboard_macros` setting.
+#[derive(PartialEq, Clone, Default, Deserialize, JsonSchema, Action)]
+#[action(namespace = keyboard_macros)]
+#[serde(deny_unknown_fields)]
+pub struct Play {
+    /// The name of the macro.
+    pub name: String,
+    /// How many times to play the macro.
+    #[serde(default)]
+    pub times: Option<NonZeroUsize>,
+    /// Whether to play the macro once at each selection of the active editor.
+    #[serde(default)]
+    pub on_every_selection: bool,
+}
+
+#[derive(Debug, RegisterSetting)]
+pub struct KeyboardMacrosSettings {
+    pub macros: HashMap<String, Vec<KeymapAction>>,
+}
+
+impl Settings for KeyboardMacrosSettings {
+    fn from_settings(content: &settings::SettingsContent) -> Self {
+        Self {
+            macros: content.keyboard_macros.clone().unwrap(),
+        }
+    }
+}
+
+pub fn init(cx: &mut App) {
+    let recorder = cx.new(|_| MacroRecorder::default());
+    cx.set_global(GlobalMacroRecorder(recorder));
+
+    cx.observe_keystrokes(|event, _, cx| {
+        if let Some(action) = &event.action
+            && is_recorded(action.as_ref())
+        {
+            MacroRecorder::update_global(cx, |recorder| recorder.record(action.boxed_clone()));
+        }
+    })
+    .detach();
+
+    cx.observe_new(|_: &mut Editor, _, cx| {
+        let editor = cx.entity();
+        App::subscribe(cx, &editor, |_, event: &EditorEvent, cx| {
+            if let EditorEvent::InputHandled { text, .. } = event {
+                MacroRecorder::update_global(cx, |recorder| {
+                    recorder.record(Box::new(HandleInput(text.to_string())))
+                });
+            }
+        })
+        .detach();
+    })
+    .detach();
+
+    cx.observe_new(|workspace: &mut Workspace, _, _| {
+        workspace.register_action(|_, _: &ToggleRecording, _, cx| {
+            if let Some(recorder) = MacroRecorder::global(cx) {
+                recorder.update(cx, |recorder, cx| recorder.toggle_recording(cx));
+            }
+        });
+        workspace.register_action(|workspace, action: &PlayLast, window, cx| {
+            play_last(workspace, times(action.times), false, window, cx)
+        });
+        workspace.register_action(|workspace, _: &PlayLastOnEverySelection, window, cx| {
+            play_last(workspace, 1, true, window, cx)
+        });
+        workspace.register_action(|workspace, action: &Play, window, cx| {
+            if let Err(error) = play_saved(workspace, action, window, cx) {
+                workspace.show_error(&error, cx);
+            }
+        });
+        workspace.register_action(SaveMacro::toggle);
+    })
+    .detach();
+}
+
+struct GlobalMacroRecorder(Entity<MacroRecorder>);
+
+impl Global for GlobalMacroRecorder {}
+
+/// Records keyboard macros and plays them back.
+#[derive(Default)]
+pub struct MacroRecorder {
+    /// The actions of the macro being recorded, if any.
+    recording: Option<Vec<Box<dyn Action>>>,
+    last_recording: Option<Rc<[Box<dyn Action>]>>,
+    playback: Option<Playback>,
+}
+
+impl MacroRecorder {
+    pub fn global(cx: &App) -> Option<Entity<Self>> {
+        cx.try_global::<GlobalMacroRecorder>()
+            .map(|recorder| recorder.0.clone())
+    }
+
+    fn update_global(cx: &mut App, update: impl FnOnce(&mut Self)) {
+        if let Some(recorder) = Self::global(cx) {
+            recorder.update(cx, |recorder, _| update(recorder));
+        }
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.recording.is_some()
+    }
+
+    fn toggle_recording(&mut self, cx: &mut Context<Self>) {
+        if let Some(recording) = self.recording.take() {
+            // Keep the previous macro when nothing was recorded, as when the
+            // recording is started by mistake.
+            if !recording.is_empty() {
+                self.last_recording = Some(recording.into());
+            }
+        } else {
+            self.recording = Some(Vec::new());
+        }
+        cx.notify();
+    }
+
+    fn record(&mut self, action: Box<dyn Action>) {
+        // What a macro plays is not recorded, as the action that plays it is.
+        if self.is_playing() {
+            return;
+        }
+        if let Some(recording) = &mut self.recording {
+            recording.push(action);
+        }
+    }
+}
+
+/// Returns whether the action dispatched by a keystroke is recorded.
+fn is_recorded(action: &dyn Action) -> bool {
+    let action = action.as_any();
+    // The last macro is recorded as the actions it plays, by `play_last`.
+    !(action.is::<ToggleRecording>()
+        || action.is::<SaveLast>()
+        || action.is::<PlayLast>()
+        || action.is::<PlayLastOnEverySelection>()
+        // These insert text, which is recorded instead.
+        || action.is::<editor::actions::ConfirmCompletion>()
+        || action.is::<editor::actions::ConfirmCompletionInsert>()
+        || action.is::<editor::actions::ConfirmCompletionReplace>()
+        || action.is::<editor::actions::AcceptNextWordEditPrediction>()
+        || action.is::<editor::actions::AcceptNextLineEditPrediction>())
+}
+
+fn times(times: Option<NonZeroUsize>) -> usize {
+    times.map_or(1, NonZeroUsize::get)
+}
+
+fn play_last(
+    workspace: &mut Workspace,
+    times: usize,
+    on_every_selection: bool,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let Some(recorder) = MacroRecorder::global(cx) else {
+        return;
+    };
+    let Some(actions) = recorder.read(cx).last_recording.clone() else {
+        return;
+    };
+    recorder.update(cx, |recorder, _| {
+        if !recorder.is_playing()
+            && let Some(recording) = &mut recorder.recording
+        {
+            for _ in 0..times {
+                recording.extend(actions.iter().map(|action| action.boxed_clone()));
+            }
+        }
+    });
+    play(workspace, &actions, times, on_every_selection, window, cx);
+}
+
+fn play_saved(
+    workspace: &mut Workspace,
+    action: &Play,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) -> Result<()> {
+    let keymap_actions = KeyboardMacrosSettings::get_global(cx)
+        .macros
+        .get(&action.name)
+        .with_context(|| format!("there is no keyboard macro named {:?}", action.name))?;
+    let actions = keymap_actions
+        .iter()
+        .map(|keymap_action| {
+            keymap_action.build(cx).map_err(|error| {
+                anyhow::anyhow!(
+                    "invalid action in keyboard macro {:?}: {error}",
+                    action.name
+                )
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    play(
+        workspace,
+        &actions,
+        times(action.times),
+        action.on_every_selection,
+        window,
+        cx,
+    );
+    Ok(())
+}
+
+fn play(
+    workspace: &mut Workspace,
+    actions: &[Box<dyn Action>],
+    times: usize,
+    on_every_selection: bool,
+    window: &mut Window,
+    cx: &mut Context<Workspace>,
+) {
+    let Some(recorder) = MacroRecorder::global(cx) else {
+        return;
+    };
+    let steps = match workspace.active_item_as::<Editor>(cx) {
+        Some(editor) if on_every_selection => {
+            PlaybackStep::on_every_selection(actions, times, &editor, cx)
+        }
+        _ => PlaybackStep::repeated(actions, times),
+    };
+    recorder.update(cx, |recorder, cx| recorder.play(steps, window, cx));
+}
+
+/// Returns how the actions of a macro are written in the `keyboard_macros`
+/// setting.
+fn keymap_actions(actions: &[Box<dyn Action>], cx: &App) -> Result<Vec<KeymapAction>> {
+    actions
+        .iter()
+        .map(|action| {
+            if let Some(HandleInput(text)) = action.as_any().downcast_ref::<HandleInput>() {
+                return Ok(serde_json::from_value(serde_json::json!([
+                    action.name(),
+                    text
+                ]))?);
+            }
+            KeymapAction::for_action(action.as_ref(), cx).with_context(|| {
+                format!(
+                    "can't save the {} action of the keyboard macro, as its input is unknown",
+                    action.name()
+                )
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use editor::MultiBufferOffset;
+    use gpui::{TestAppContext, VisualTestContext};
+    use project::{FakeFs, Project};
+    use settings::{KeymapFile, SettingsStore};
+    use util::test::marked_text_ranges;
+    use workspace::AppState;
+
+    #[gpui::test]
+    async fn test_record_and_play(cx: &mut TestAppContext) {
+        let (editor, cx) = init_test("ˇone\ntwo\nthree\nfour", cx).await;
+
+        cx.simulate_keystrokes("ctrl-r");
+        cx.simulate_input("- ");
+        cx.simulate_keystrokes("down home ctrl-r");
+        cx.run_until_parked();
+        assert_text(&editor, "- one\ntwo\nthree\nfour", cx);
+
+        cx.simulate_keystrokes("ctrl-p");
+        cx.run_until_parked();
+        assert_text(&editor, "- one\n- two\nthree\nfour", cx);
+
+        cx.dispatch_action(PlayLast {
+            times: NonZeroUsize::new(2),
+        });
+        cx.run_until_parked();
+        assert_text(&editor, "- one\n- two\n- three\n- four", cx);
+
+        let keymap_actions = cx.update(|_, cx| {
+            let recorder = MacroRecorder::global(cx).unwrap();
+            let actions = recorder.read(cx).last_recording.clone().unwrap();
+            keymap_actions(&actions, cx).unwrap()
+        });
+        assert_eq!(
+            serde_json::to_value(keymap_actions).unwrap(),
+            serde_json::json!([
+                ["editor::HandleInput", "-"],
+                ["editor::HandleInput", " "],
+                "editor::MoveDown",
+                ["editor::MoveToBeginningOfLine", { "stop_at_soft_wraps": false }],
+            ])
+        );
+    }
+
+    #[gpui::test]
+    async fn test_play_on_every_selection(cx: &mut TestAppContext) {
+        let (editor, cx) = init_test("ˇone\ntwo\nthree", cx).await;
+
+        cx.simulate_keystrokes("ctrl-r");
+        cx.simulate_input("- ");
+        cx.simulate_keystrokes("ctrl-r");
+        cx.run_until_parked();
+        assert_text(&editor, "- one\ntwo\nthree", cx);
+
+        editor.update_in(cx, |editor, window, cx| {
+            editor.change_selections(Default::default(), window, cx, |s| {
+                s.select_ranges([
+                    MultiBufferOffset(6)..MultiBufferOffset(6),
+                    MultiBufferOffset(10)..MultiBufferOffset(10),
+                ])
+            });
+        });
+        cx.dispatch_action(PlayLastOnEverySelection);
+        cx.run_until_parked();
+        assert_text(&editor, "- one\n- two\n- three", cx);
+        editor.update(cx, |editor, _| {
+            assert_eq!(editor.selections.count(), 2);
+        });
+    }
+
+    #[gpui::test]
+    async fn test_play_saved(cx: &mut TestAppContext) {
+        let (editor, cx) = init_test("ˇone\ntwo", cx).await;
+        cx.update(|_, cx| {
+            SettingsStore::update_global(cx, |store, cx| {
+                store.update_user_settings(cx, |settings| {
+                    settings.keyboard_macros = Some(HashMap::from_iter([(
+                        "quote".to_string(),
+                        serde_json::from_value(serde_json::json!([
+                            ["editor::HandleInput", "> "],
+                            "editor::MoveDown",
+                            ["editor::MoveToBeginningOfLine", { "stop_at_soft_wraps": false }],
+                        ]))
+                        .unwrap(),
+                    )]));
+                });
+            });
+        });
+
+        cx.dispatch_action(Play {
+            name: "quote".into(),
+            times: NonZeroUsize::new(2),
+            on_every_selection: false,
+        });
+        cx.run_until_parked();
+        assert_text(&editor, "> one\n> two", cx);
+    }
+
+    async fn init_test(
+        text: &str,
+        cx: &mut TestAppContext,
+    ) -> (Entity<Editor>, &mut VisualTestContext) {
+        cx.update(|cx| {
+            AppState::test(cx);
+            editor::init(cx);
+            crate::init(cx);
+            let bindings = KeymapFile::load_panic_on_failure(
+                r#"[
+                    {
+                        "bindings": {
+                            "ctrl-r": "keyboard_macros::ToggleRecording",
+                            "ctrl-p": "keyboard_macros::PlayLast"
+                        }
+                    },
+                    {
+                        "context": "Editor",
+                        "bindings": {
+                            "down": "editor::MoveDown",
+                            "home": ["editor::MoveToBeginningOfLine", { "stop_at_soft_wraps": false }]
+                        }
+                    }
+                ]"#,
+                cx,
+            );
+            cx.bind_keys(bindings);
+        });
+
+        let project = Project::test(FakeFs::new(cx.executor()), [], cx).await;
+        let (workspace, cx) =
+            cx.add_window_view(|window, cx| Workspace::test_new(project, window, cx));
+        let (text, selections) = marked_text_ranges(text, true);
+        let editor = workspace.update_in(cx, |workspace, window, cx| {
+            let editor =
+                cx.new(|cx| {
+                    let mut editor = Editor::multi_line(window, cx);
+                    editor.set_text(text, window, cx);
+                    editor.change_selections(Default::default(), window, cx, |s| {
+                        s.select_ranges(selections.into_iter().map(|range| {
+                            MultiBufferOffset(range.start)..MultiBufferOffset(range.end)
+                        }))
+                    });
+                    editor
+                });
+            workspace.add_item_to_active_pane(Box::new(editor.clone()), None, true, window, cx);
+            editor
+        });
+        cx.run_until_parked();
+        (editor, cx)
+    }
+
+    #[track_caller]
+    fn assert_text(editor: &Entity<Editor>, expected: &str, cx: &mut VisualTestContext) {
+        assert_eq!(editor.update(cx, |editor, cx| editor.text(cx)), expected);
+    }
+}
@@ -0,0 +1,161 @@
+use std::{cell::RefCell, collections::VecDeque, ops::Range, rc::Rc};
+
+use editor::{Anchor, Editor, SelectionEffects};
+use gpui::{Action, App, Context, Entity, WeakEntity, Window};
+
+use crate::MacroRecorder;
+
+/// Playback is aborted after this many steps, in case a macro plays itself.
+const MAX_PLAYED_STEPS: usize = 10000;
+
+pub(crate) struct Playback {
+    steps: VecDeque<PlaybackStep>,
+    played: usize,
+}
+
+pub(crate) enum PlaybackStep {
+    Action(Box<dyn Action>),
+    /// Selects a range of the editor, before the macro is played there.
+    Select {
+        editor: WeakEntity<Editor>,
+        range: Range<Anchor>,
+    },
+    /// Collects the selections that the macro left in the editor, so that all
+    /// of them are restored after the macro has been played at each selection.
+    CollectSelections {
+        editor: WeakEntity<Editor>,
+        selections: Rc<RefCell<Vec<Range<Anchor>>>>,
+    },
+    RestoreSelections {
+        editor: WeakEntity<Editor>,
+        selections: Rc<RefCell<Vec<Range<Anchor>>>>,
+    },
+}
+
+impl PlaybackStep {
+    pub(crate) fn repeated(actions: &[Box<dyn Action>], times: usize) -> Vec<Self> {
+        (0..times)
+            .flat_map(|_| actions.iter())
+            .map(|action| Self::Action(action.boxed_clone()))
+            .collect()
+    }
+
+    /// Returns the steps that play the actions at each selection of the editor,
+    /// from the first to the last one.
+    pub(crate) fn on_every_selection(
+        actions: &[Box<dyn Action>],
+        times: usize,
+        editor: &Entity<Editor>,
+        cx: &App,
+    ) -> Vec<Self> {
+        let weak_editor = editor.downgrade();
+        let selections = Rc::new(RefCell::new(Vec::new()));
+        let mut steps = Vec::new();
+        for range in editor.read(cx).selections.disjoint_anchor_ranges() {
+            steps.push(Self::Select {
+                editor: weak_editor.clone(),
+                range,
+            });
+            steps.extend(Self::repeated(actions, times));
+            steps.push(Self::CollectSelections {
+                editor: weak_editor.clone(),
+                selections: selections.clone(),
+            });
+        }
+        steps.push(Self::RestoreSelections {
+            editor: weak_editor,
+            selections,
+        });
+        steps
+    }
+}
+
+impl MacroRecorder {
+    pub(crate) fn is_playing(&self) -> bool {
+        self.playback.is_some()
+    }
+
+    /// Plays the steps one at a time, each after the effects of the previous
+    /// one, so that the actions of a macro see the state that they would have
+    /// seen when it was recorded. Steps played by a macro that is being played
+    /// are played before the rest of its steps.
+    pub(crate) fn play(
+        &mut self,
+        steps: Vec<PlaybackStep>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(playback) = &mut self.playback {
+            for step in steps.into_iter().rev() {
+                playback.steps.push_front(step);
+            }
+            return;
+        }
+
+        self.playback = Some(Playback {
+            steps: steps.into(),
+            played: 0,
+        });
+        cx.notify();
+        let recorder = cx.entity();
+        window.defer(cx, move |window, cx| play_next_step(recorder, window, cx));
+    }
+}
+
+fn play_next_step(recorder: Entity<MacroRecorder>, window: &mut Window, cx: &mut App) {
+    let step = recorder.update(cx, |recorder, cx| {
+        let playback = recorder.playback.as_mut()?;
+        let step = if playback.played < MAX_PLAYED_STEPS {
+            playback.steps.pop_front()
+        } else {
+            log::error!("aborting keyboard macro after {MAX_PLAYED_STEPS} steps");
+            None
+        };
+        if step.is_none() {
+            recorder.playback = None;
+            cx.notify();
+        } else {
+            playback.played += 1;
+        }
+        step
+    });
+    let Some(step) = step else {
+        return;
+    };
+
+    match step {
+        PlaybackStep::Action(action) => window.dispatch_action(action, cx),
+        PlaybackStep::Select { editor, range } => {
+            select(&editor, vec![range], window, cx);
+        }
+        PlaybackStep::CollectSelections { editor, selections } => {
+            if let Some(editor) = editor.upgrade() {
+                selections
+                    .borrow_mut()
+                    .extend(editor.read(cx).selections.disjoint_anchor_ranges());
+            }
+        }
+        PlaybackStep::RestoreSelections { editor, selections } => {
+            let selections = selections.take();
+            if !selections.is_empty() {
+                select(&editor, selections, window, cx);
+            }
+        }
+    }
+    window.defer(cx, move |window, cx| play_next_step(recorder, window, cx));
+}
+
+fn select(
+    editor: &WeakEntity<Editor>,
+    ranges: Vec<Range<Anchor>>,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    editor
+        .update(cx, |editor, cx| {
+            editor.change_selections(SelectionEffects::no_scroll(), window, cx, |s| {
+                s.select_anchor_ranges(ranges)
+            })
+        })
+        .ok();
+}
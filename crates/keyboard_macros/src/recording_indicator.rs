@@ -0,0 +1,60 @@
+use gpui::{Context, IntoElement, ParentElement, Render, Subscription, Window, div};
+use ui::{
+    Button, ButtonCommon, Clickable, Color, FluentBuilder, IconName, IconPosition, IconSize,
+    LabelSize, Tooltip,
+};
+use workspace::{StatusItemView, item::ItemHandle};
+
+use crate::{MacroRecorder, ToggleRecording};
+
+/// Shows in the status bar that a keyboard macro is being recorded.
+pub struct RecordingIndicator {
+    is_recording: bool,
+    _observe_recorder: Option<Subscription>,
+}
+
+impl RecordingIndicator {
+    pub fn new(cx: &mut Context<Self>) -> Self {
+        let recorder = MacroRecorder::global(cx);
+        Self {
+            is_recording: recorder
+                .as_ref()
+                .is_some_and(|recorder| recorder.read(cx).is_recording()),
+            _observe_recorder: recorder.map(|recorder| {
+                cx.observe(&recorder, |this, recorder, cx| {
+                    this.is_recording = recorder.read(cx).is_recording();
+                    cx.notify();
+                })
+            }),
+        }
+    }
+}
+
+impl Render for RecordingIndicator {
+    fn render(&mut self, _: &mut Window, _: &mut Context<Self>) -> impl IntoElement {
+        div().when(self.is_recording, |el| {
+            el.child(
+                Button::new("keyboard-macro-recording", "Recording Macro")
+                    .label_size(LabelSize::Small)
+                    .icon(IconName::Circle)
+                    .icon_color(Color::Error)
+                    .icon_size(IconSize::Small)
+                    .icon_position(IconPosition::Start)
+                    .on_click(|_, window, cx| window.dispatch_action(Box::new(ToggleRecording), cx))
+                    .tooltip(|_window, cx| {
+                        Tooltip::for_action("Stop Recording", &ToggleRecording, cx)
+                    }),
+            )
+        })
+    }
+}
+
+impl StatusItemView for RecordingIndicator {
+    fn set_active_pane_item(
+        &mut self,
+        _: Option<&dyn ItemHandle>,
+        _: &mut Window,
+        _: &mut Context<Self>,
+    ) {
+    }
+}
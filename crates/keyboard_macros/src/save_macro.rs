@@ -0,0 +1,137 @@
+use std::sync::Arc;
+
+use editor::Editor;
+use fs::Fs;
+use gpui::{
+    App, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, Render, Styled, Subscription,
+    div, prelude::*,
+};
+use settings::KeymapAction;
+use ui::prelude::*;
+use workspace::{ModalView, Workspace};
+
+use crate::{MacroRecorder, SaveLast, keymap_actions};
+
+/// Prompts for the name to save the last recorded keyboard macro under.
+pub struct SaveMacro {
+    name_editor: Entity<Editor>,
+    fs: Arc<dyn Fs>,
+    actions: Vec<KeymapAction>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl ModalView for SaveMacro {}
+
+impl Focusable for SaveMacro {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.name_editor.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for SaveMacro {}
+
+impl SaveMacro {
+    pub(crate) fn toggle(
+        workspace: &mut Workspace,
+        _: &SaveLast,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        let Some(recording) =
+            MacroRecorder::global(cx).and_then(|recorder| recorder.read(cx).last_recording.clone())
+        else {
+            return;
+        };
+        let actions = match keymap_actions(&recording, cx) {
+            Ok(actions) => actions,
+            Err(error) => {
+                workspace.show_error(&error, cx);
+                return;
+            }
+        };
+        let fs = workspace.app_state().fs.clone();
+        workspace.toggle_modal(window, cx, move |window, cx| {
+            Self::new(fs, actions, window, cx)
+        });
+    }
+
+    fn new(
+        fs: Arc<dyn Fs>,
+        actions: Vec<KeymapAction>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let name_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Macro name", window, cx);
+            editor
+        });
+        let name_editor_change = cx.subscribe_in(&name_editor, window, Self::on_name_editor_event);
+
+        Self {
+            name_editor,
+            fs,
+            actions,
+            _subscriptions: vec![name_editor_change],
+        }
+    }
+
+    fn on_name_editor_event(
+        &mut self,
+        _: &Entity<Editor>,
+        event: &editor::EditorEvent,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let editor::EditorEvent::Blurred = event {
+            cx.emit(DismissEvent)
+        }
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, _: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, _: &mut Window, cx: &mut Context<Self>) {
+        let name = self.name_editor.read(cx).text(cx).trim().to_string();
+        if name.is_empty() {
+            return;
+        }
+        let actions = self.actions.clone();
+        settings::update_settings_file(self.fs.clone(), cx, move |settings, _| {
+            settings
+                .keyboard_macros
+                .get_or_insert_default()
+                .insert(name, actions);
+        });
+        cx.emit(DismissEvent);
+    }
+}
+
+impl Render for SaveMacro {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .w(rems(24.))
+            .elevation_2(cx)
+            .key_context("SaveMacro")
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::confirm))
+            .child(
+                div()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .px_2()
+                    .py_1()
+                    .child(self.name_editor.clone()),
+            )
+            .child(
+                h_flex().px_2().py_1().gap_1().child(
+                    Label::new(format!(
+                        "Save the last keyboard macro of {} actions",
+                        self.actions.len()
+                    ))
+                    .color(Color::Muted),
+                ),
+            )
+    }
+}
@@ -340,6 +340,17 @@ pub enum Operation {
         /// The buffer's lamport timestamp.
         lamport_timestamp: clock::Lamport,
     },
+
+    /// An update to the character encoding that this buffer's file is read
+    /// and saved with.
+    UpdateEncoding {
+        /// The character encoding.
+        encoding: &'static Encoding,
+        /// Whether the file starts with a Byte Order Mark.
+        has_bom: bool,
+        /// The buffer's lamport timestamp.
+        lamport_timestamp: clock::Lamport,
+    },
 }
 
 /// An event that occurs in a buffer.
@@ -976,6 +987,8 @@ impl Buffer {
         this.text.set_line_ending(proto::deserialize_line_ending(
             rpc::proto::LineEnding::from_i32(message.line_ending).context("missing line_ending")?,
         ));
+        this.encoding = proto::deserialize_encoding(&message.encoding);
+        this.has_bom = message.has_bom;
         this.saved_version = proto::deserialize_version(&message.saved_version);
         this.saved_mtime = message.saved_mtime.map(|time| time.into());
         Ok(this)
@@ -990,6 +1003,8 @@ impl Buffer {
             line_ending: proto::serialize_line_ending(self.line_ending()) as i32,
             saved_version: proto::serialize_version(&self.saved_version),
             saved_mtime: self.saved_mtime.map(|time| time.into()),
+            encoding: proto::serialize_encoding(self.encoding),
+            has_bom: self.has_bom,
         }
     }
 
@@ -1408,6 +1423,31 @@ impl Buffer {
         self.has_bom = has_bom;
     }
 
+    /// Changes the character encoding of the buffer and whether it has a Byte
+    /// Order Mark, which the buffer's file is reloaded and saved with, and
+    /// sends the change to collaborators.
+    pub fn update_encoding(
+        &mut self,
+        encoding: &'static Encoding,
+        has_bom: bool,
+        cx: &mut Context<Self>,
+    ) {
+        self.encoding = encoding;
+        self.has_bom = has_bom;
+
+        let lamport_timestamp = self.text.lamport_clock.tick();
+        self.send_operation(
+            Operation::UpdateEncoding {
+                encoding,
+                has_bom,
+                lamport_timestamp,
+            },
+            true,
+            cx,
+        );
+        cx.notify();
+    }
+
     /// Assign a language to the buffer.
     pub fn set_language_async(&mut self, language: Option<Arc<Language>>, cx: &mut Context<Self>) {
         self.set_language_(language, cfg!(any(test, feature = "test-support")), cx);
@@ -1490,16 +1530,28 @@ impl Buffer {
         let (tx, rx) = futures::channel::oneshot::channel();
         let prev_version = self.text.version();
         self.reload_task = Some(cx.spawn(async move |this, cx| {
-            let Some((new_mtime, new_text)) = this.update(cx, |this, cx| {
+            let Some((new_mtime, new_bytes, encoding)) = this.update(cx, |this, cx| {
                 let file = this.file.as_ref()?.as_local()?;
 
-                Some((file.disk_state().mtime(), file.load(cx)))
+                Some((
+                    file.disk_state().mtime(),
+                    file.load_bytes(cx),
+                    this.encoding,
+                ))
             })?
             else {
                 return Ok(());
             };
 
-            let new_text = new_text.await?;
+            let new_bytes = new_bytes.await?;
+            let (new_text, has_bom) = cx
+                .background_spawn(async move { crate::encoding::decode(new_bytes, encoding) })
+                .await;
+            this.update(cx, |this, cx| {
+                if this.has_bom != has_bom {
+                    this.update_encoding(encoding, has_bom, cx);
+                }
+            })?;
             let diff = this
                 .update(cx, |this, cx| this.diff(new_text.clone(), cx))?
                 .await;
@@ -2842,7 +2894,9 @@ impl Buffer {
             Operation::UpdateSelections { selections, .. } => selections
                 .iter()
                 .all(|s| self.can_resolve(&s.start) && self.can_resolve(&s.end)),
-            Operation::UpdateCompletionTriggers { .. } | Operation::UpdateLineEnding { .. } => true,
+            Operation::UpdateCompletionTriggers { .. }
+            | Operation::UpdateLineEnding { .. }
+            | Operation::UpdateEncoding { .. } => true,
         }
     }
 
@@ -2915,6 +2969,15 @@ impl Buffer {
                 self.text.set_line_ending(line_ending);
                 self.text.lamport_clock.observe(lamport_timestamp);
             }
+            Operation::UpdateEncoding {
+                encoding,
+                has_bom,
+                lamport_timestamp,
+            } => {
+                self.encoding = encoding;
+                self.has_bom = has_bom;
+                self.text.lamport_clock.observe(lamport_timestamp);
+            }
         }
     }
 
@@ -5430,6 +5493,9 @@ impl operation_queue::Operation for Operation {
             }
             | Operation::UpdateLineEnding {
                 lamport_timestamp, ..
+            }
+            | Operation::UpdateEncoding {
+                lamport_timestamp, ..
             } => *lamport_timestamp,
         }
     }
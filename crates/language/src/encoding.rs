@@ -0,0 +1,177 @@
+use anyhow::{Result, anyhow};
+use chardetng::EncodingDetector;
+use encoding_rs::Encoding;
+
+/// Guesses the character encoding of a file's contents, returning it along
+/// with whether the contents start with a byte order mark.
+///
+/// A byte order mark decides the encoding. Otherwise contents that are valid
+/// UTF-8 are taken to be UTF-8, and the encoding of any other contents is
+/// guessed from their bytes.
+pub fn detect_encoding(bytes: &[u8]) -> (&'static Encoding, bool) {
+    if let Some((encoding, _)) = Encoding::for_bom(bytes) {
+        return (encoding, true);
+    }
+
+    // ISO-2022-JP (and other ISO-2022 variants) consists entirely of 7-bit ASCII bytes,
+    // so it is valid UTF-8. However, it contains escape sequences starting with '\x1b'.
+    // If we find an escape character, we double-check the encoding to prevent
+    // displaying raw escape sequences instead of the correct characters.
+    if std::str::from_utf8(bytes).is_ok() && !bytes.contains(&0x1b) {
+        return (encoding_rs::UTF_8, false);
+    }
+
+    let mut detector = EncodingDetector::new();
+    detector.feed(bytes, true);
+    // Use None for TLD hint to ensure neutral detection logic.
+    (detector.guess(None, true), false)
+}
+
+/// The number of bytes at the start of a file that are checked to tell
+/// whether it is binary, as Git does.
+const BINARY_CHECK_LEN: usize = 8000;
+
+/// Returns whether a file's contents look binary, rather than text, which is
+/// decided by whether there are NUL bytes near their start.
+pub fn is_binary(bytes: &[u8]) -> bool {
+    // UTF-16 and UTF-32 text has NUL bytes, and starts with a byte order mark.
+    let has_wide_bom = bytes.starts_with(&[0xff, 0xfe]) || bytes.starts_with(&[0xfe, 0xff]);
+    !has_wide_bom && bytes[..bytes.len().min(BINARY_CHECK_LEN)].contains(&0)
+}
+
+/// Decodes a file's contents with the given encoding, returning the text along
+/// with whether the contents start with the encoding's byte order mark, which
+/// isn't part of the text.
+///
+/// Bytes that are invalid in the encoding are decoded as replacement characters.
+pub fn decode(bytes: Vec<u8>, encoding: &'static Encoding) -> (String, bool) {
+    let bom_len = match Encoding::for_bom(&bytes) {
+        Some((bom_encoding, bom_len)) if bom_encoding == encoding => bom_len,
+        _ => 0,
+    };
+
+    if encoding == encoding_rs::UTF_8 && bom_len == 0 {
+        return match String::from_utf8(bytes) {
+            Ok(text) => (text, false),
+            Err(error) => (
+                String::from_utf8_lossy(error.as_bytes()).into_owned(),
+                false,
+            ),
+        };
+    }
+
+    let (text, _) = encoding.decode_without_bom_handling(&bytes[bom_len..]);
+    (text.into_owned(), bom_len > 0)
+}
+
+/// Encodes text with the given encoding, preceded by the encoding's byte order
+/// mark if `has_bom` is true.
+///
+/// Fails if the text has characters that the encoding can't represent, rather
+/// than replacing them.
+pub fn encode(text: &str, encoding: &'static Encoding, has_bom: bool) -> Result<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(text.len() + 3);
+    if encoding == encoding_rs::UTF_16LE || encoding == encoding_rs::UTF_16BE {
+        // encoding_rs only decodes UTF-16, and would encode it as UTF-8.
+        let little_endian = encoding == encoding_rs::UTF_16LE;
+        if has_bom {
+            bytes.extend_from_slice(if little_endian {
+                &[0xFF, 0xFE]
+            } else {
+                &[0xFE, 0xFF]
+            });
+        }
+        for unit in text.encode_utf16() {
+            bytes.extend_from_slice(&if little_endian {
+                unit.to_le_bytes()
+            } else {
+                unit.to_be_bytes()
+            });
+        }
+        return Ok(bytes);
+    }
+
+    if has_bom && encoding == encoding_rs::UTF_8 {
+        bytes.extend_from_slice(&[0xEF, 0xBB, 0xBF]);
+    }
+    let (encoded, _, has_unmappable_characters) = encoding.encode(text);
+    if has_unmappable_characters {
+        return Err(anyhow!(
+            "the text has characters that can't be saved in {}",
+            encoding.name()
+        ));
+    }
+    bytes.extend_from_slice(&encoded);
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_encoding() {
+        assert_eq!(
+            detect_encoding("こんにちは".as_bytes()),
+            (encoding_rs::UTF_8, false)
+        );
+        assert_eq!(
+            detect_encoding(&[0xEF, 0xBB, 0xBF, b'a']),
+            (encoding_rs::UTF_8, true)
+        );
+        assert_eq!(
+            detect_encoding(&[0xFF, 0xFE, 0x53, 0x30]),
+            (encoding_rs::UTF_16LE, true)
+        );
+        assert_eq!(
+            detect_encoding(&[0x43, 0x61, 0x66, 0xe9]),
+            (encoding_rs::WINDOWS_1252, false)
+        );
+    }
+
+    #[test]
+    fn test_is_binary() {
+        assert!(is_binary(b"\x7fELF\x02\x01\x01\x00"));
+        assert!(!is_binary(b"fn main() {}\n"));
+        assert!(!is_binary(b""));
+        assert!(!is_binary(&[0xFF, 0xFE, 0x61, 0x00]));
+
+        let mut late_nul = vec![b'a'; BINARY_CHECK_LEN];
+        late_nul.push(0);
+        assert!(!is_binary(&late_nul));
+    }
+
+    #[test]
+    fn test_decode_and_encode() {
+        let utf16 = [0xFE, 0xFF, 0x30, 0x53, 0x30, 0x93];
+        assert_eq!(
+            decode(utf16.to_vec(), encoding_rs::UTF_16BE),
+            ("こん".to_string(), true)
+        );
+        assert_eq!(encode("こん", encoding_rs::UTF_16BE, true).unwrap(), utf16);
+        assert_eq!(
+            encode("こん", encoding_rs::UTF_16LE, false).unwrap(),
+            [0x53, 0x30, 0x93, 0x30]
+        );
+
+        // A byte order mark of another encoding is decoded as text.
+        assert_eq!(
+            decode(vec![0xEF, 0xBB, 0xBF, b'a'], encoding_rs::WINDOWS_1252),
+            ("ï»¿a".to_string(), false)
+        );
+        assert_eq!(
+            decode(vec![0xEF, 0xBB, 0xBF, b'a'], encoding_rs::UTF_8),
+            ("a".to_string(), true)
+        );
+        assert_eq!(
+            encode("a", encoding_rs::UTF_8, true).unwrap(),
+            [0xEF, 0xBB, 0xBF, b'a']
+        );
+
+        assert_eq!(
+            encode("Café", encoding_rs::WINDOWS_1252, false).unwrap(),
+            [0x43, 0x61, 0x66, 0xe9]
+        );
+        assert!(encode("こんにちは", encoding_rs::WINDOWS_1252, false).is_err());
+    }
+}
@@ -12,12 +12,12 @@ use itertools::{Either, Itertools};
 
 pub use settings::{
     CompletionSettingsContent, EditPredictionProvider, EditPredictionsMode, FormatOnSave,
-    Formatter, FormatterList, InlayHintKind, LanguageSettingsContent, LspInsertMode,
-    RewrapBehavior, ShowWhitespaceSetting, SoftWrap, WordsCompletionMode,
+    Formatter, FormatterList, InlayHintKind, LanguageSettingsContent, LocalEditPredictionApi,
+    LspInsertMode, RewrapBehavior, ShowWhitespaceSetting, SoftWrap, WordsCompletionMode,
 };
 use settings::{RegisterSetting, Settings, SettingsLocation, SettingsStore};
 use shellexpand;
-use std::{borrow::Cow, num::NonZeroU32, path::Path, sync::Arc};
+use std::{borrow::Cow, num::NonZeroU32, path::Path, sync::Arc, time::Duration};
 
 /// Returns the settings for the specified language from the provided file.
 pub fn language_settings<'a>(
@@ -141,6 +141,8 @@ pub struct LanguageSettings {
     pub code_actions_on_format: HashMap<String, bool>,
     /// Whether to perform linked edits
     pub linked_edits: bool,
+    /// Whether to expand selections using the language server's selection ranges
+    pub prefer_lsp_selection_ranges: bool,
     /// Task configuration for this language.
     pub tasks: LanguageTaskSettings,
     /// Whether to pop the completions menu while typing in an editor without
@@ -385,6 +387,8 @@ pub struct EditPredictionSettings {
     pub copilot: CopilotSettings,
     /// Settings specific to Codestral.
     pub codestral: CodestralSettings,
+    /// Settings specific to the local provider.
+    pub local: LocalEditPredictionSettings,
     /// Whether edit predictions are enabled in the assistant panel.
     /// This setting has no effect if globally disabled.
     pub enabled_in_text_threads: bool,
@@ -430,6 +434,24 @@ pub struct CodestralSettings {
     pub api_url: Option<String>,
 }
 
+#[derive(Clone, Debug, Default)]
+pub struct LocalEditPredictionSettings {
+    /// The API the model server exposes.
+    pub api: LocalEditPredictionApi,
+    /// The URL of the model server.
+    pub api_url: String,
+    /// The model to request completions from.
+    pub model: String,
+    /// The template used to build the fill-in-the-middle prompt.
+    pub prompt_template: String,
+    /// Maximum number of tokens from around the cursor to include in the prompt.
+    pub max_context_tokens: u32,
+    /// Maximum number of tokens to generate.
+    pub max_output_tokens: u32,
+    /// How long to wait after the last edit before requesting a prediction.
+    pub debounce: Duration,
+}
+
 impl AllLanguageSettings {
     /// Returns the [`LanguageSettings`] for the language with the specified name.
     pub fn language<'a>(
@@ -588,6 +610,7 @@ impl settings::Settings for AllLanguageSettings {
                     .unwrap(),
                 code_actions_on_format: settings.code_actions_on_format.unwrap(),
                 linked_edits: settings.linked_edits.unwrap(),
+                prefer_lsp_selection_ranges: settings.prefer_lsp_selection_ranges.unwrap(),
                 tasks: LanguageTaskSettings {
                     variables: tasks.variables.unwrap_or_default(),
                     enabled: tasks.enabled.unwrap(),
@@ -654,6 +677,17 @@ impl settings::Settings for AllLanguageSettings {
             api_url: codestral.api_url,
         };
 
+        let local = edit_predictions.local.unwrap();
+        let local_settings = LocalEditPredictionSettings {
+            api: local.api.unwrap(),
+            api_url: local.api_url.unwrap(),
+            model: local.model.unwrap(),
+            prompt_template: local.prompt_template.unwrap(),
+            max_context_tokens: local.max_context_tokens.unwrap(),
+            max_output_tokens: local.max_output_tokens.unwrap(),
+            debounce: Duration::from_millis(local.debounce_ms.unwrap().0),
+        };
+
         let enabled_in_text_threads = edit_predictions.enabled_in_text_threads.unwrap();
 
         let mut file_types: FxHashMap<Arc<str>, (GlobSet, Vec<String>)> = FxHashMap::default();
@@ -692,6 +726,7 @@ impl settings::Settings for AllLanguageSettings {
                 mode: edit_predictions_mode,
                 copilot: copilot_settings,
                 codestral: codestral_settings,
+                local: local_settings,
                 enabled_in_text_threads,
             },
             defaults: default_language_settings,
@@ -30,6 +30,17 @@ pub fn serialize_line_ending(message: text::LineEnding) -> proto::LineEnding {
     }
 }
 
+/// Deserializes a character encoding from the RPC representation, which is
+/// its name, defaulting to UTF-8 for peers that don't send one.
+pub fn deserialize_encoding(name: &str) -> &'static encoding_rs::Encoding {
+    encoding_rs::Encoding::for_label(name.as_bytes()).unwrap_or(encoding_rs::UTF_8)
+}
+
+/// Serializes a character encoding to be sent over RPC.
+pub fn serialize_encoding(encoding: &'static encoding_rs::Encoding) -> String {
+    encoding.name().to_string()
+}
+
 /// Serializes a [`crate::Operation`] to be sent over RPC.
 pub fn serialize_operation(operation: &crate::Operation) -> proto::Operation {
     proto::Operation {
@@ -100,6 +111,17 @@ pub fn serialize_operation(operation: &crate::Operation) -> proto::Operation {
                 lamport_timestamp: lamport_timestamp.value,
                 line_ending: serialize_line_ending(*line_ending) as i32,
             }),
+
+            crate::Operation::UpdateEncoding {
+                encoding,
+                has_bom,
+                lamport_timestamp,
+            } => proto::operation::Variant::UpdateEncoding(proto::operation::UpdateEncoding {
+                replica_id: lamport_timestamp.replica_id.as_u16() as u32,
+                lamport_timestamp: lamport_timestamp.value,
+                encoding: serialize_encoding(encoding),
+                has_bom: *has_bom,
+            }),
         }),
     }
 }
@@ -368,6 +390,16 @@ pub fn deserialize_operation(message: proto::Operation) -> Result<crate::Operati
                     ),
                 }
             }
+            proto::operation::Variant::UpdateEncoding(message) => {
+                crate::Operation::UpdateEncoding {
+                    lamport_timestamp: clock::Lamport {
+                        replica_id: ReplicaId::new(message.replica_id as u16),
+                        value: message.lamport_timestamp,
+                    },
+                    encoding: deserialize_encoding(&message.encoding),
+                    has_bom: message.has_bom,
+                }
+            }
         },
     )
 }
@@ -528,6 +560,10 @@ pub fn lamport_timestamp_for_operation(operation: &proto::Operation) -> Option<c
             replica_id = op.replica_id;
             value = op.lamport_timestamp;
         }
+        proto::operation::Variant::UpdateEncoding(op) => {
+            replica_id = op.replica_id;
+            value = op.lamport_timestamp;
+        }
     }
 
     Some(clock::Lamport {
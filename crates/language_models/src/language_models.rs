@@ -30,6 +30,7 @@ pub fn init(user_store: Entity<UserStore>, client: Arc<Client>, cx: &mut App) {
     registry.update(cx, |registry, cx| {
         register_language_model_providers(registry, user_store, client.clone(), cx);
     });
+    provider::extension::init(cx);
 
     let mut openai_compatible_providers = AllLanguageModelSettings::get_global(cx)
         .openai_compatible
@@ -0,0 +1,980 @@
+use anyhow::{Result, anyhow};
+use convert_case::{Case, Casing};
+use extension::{
+    Extension, ExtensionHostProxy, ExtensionLanguageModelProviderProxy,
+    LanguageModelProviderManifestEntry,
+};
+use futures::{FutureExt, StreamExt, future, future::BoxFuture, stream::BoxStream};
+use gpui::{
+    AnyView, App, AsyncApp, BackgroundExecutor, Context, Entity, SharedString, Task, Window,
+};
+use language_model::{
+    ApiKeyState, AuthenticateError, EnvVar, LanguageModel, LanguageModelCompletionError,
+    LanguageModelCompletionEvent, LanguageModelId, LanguageModelName, LanguageModelProvider,
+    LanguageModelProviderId, LanguageModelProviderName, LanguageModelProviderState,
+    LanguageModelRegistry, LanguageModelRequest, LanguageModelToolChoice,
+    LanguageModelToolResultContent, LanguageModelToolUse, MessageContent, RateLimiter, Role,
+    StopReason, TokenUsage,
+};
+use std::sync::Arc;
+use ui::{ElevationIndex, Tooltip, prelude::*};
+use ui_input::InputField;
+use util::ResultExt;
+
+pub fn init(cx: &mut App) {
+    let proxy = ExtensionHostProxy::default_global(cx);
+    proxy.register_language_model_provider_proxy(LanguageModelRegistryProxy);
+}
+
+struct LanguageModelRegistryProxy;
+
+impl ExtensionLanguageModelProviderProxy for LanguageModelRegistryProxy {
+    fn register_language_model_provider(
+        &self,
+        extension: Arc<dyn Extension>,
+        provider_id: Arc<str>,
+        provider: LanguageModelProviderManifestEntry,
+        cx: &mut App,
+    ) {
+        let registry = LanguageModelRegistry::global(cx);
+        let id = LanguageModelProviderId::from(provider_id.clone());
+        if registry.read(cx).provider(&id).is_some() {
+            log::error!(
+                "extension {} declares language model provider {provider_id}, which is already registered",
+                extension.manifest().id
+            );
+            return;
+        }
+
+        let provider = ExtensionLanguageModelProvider::new(extension, provider_id, provider, cx);
+        registry.update(cx, |registry, cx| {
+            registry.register_provider(Arc::new(provider), cx);
+        });
+    }
+
+    fn unregister_language_model_provider(&self, provider_id: Arc<str>, cx: &mut App) {
+        LanguageModelRegistry::global(cx).update(cx, |registry, cx| {
+            registry.unregister_provider(LanguageModelProviderId::from(provider_id), cx);
+        });
+    }
+}
+
+/// A language model provider registered by an extension.
+pub struct ExtensionLanguageModelProvider {
+    id: LanguageModelProviderId,
+    name: LanguageModelProviderName,
+    state: Entity<State>,
+}
+
+pub struct State {
+    extension: Arc<dyn Extension>,
+    provider_id: Arc<str>,
+    provider_name: SharedString,
+    /// The key under which the API key is stored in the system keychain.
+    credentials_url: SharedString,
+    api_key_state: ApiKeyState,
+    /// Whether completions can only be requested once an API key is configured.
+    requires_api_key: bool,
+    models: Vec<extension::LanguageModelInfo>,
+    fetch_models_task: Option<Task<()>>,
+}
+
+impl State {
+    fn is_authenticated(&self) -> bool {
+        !self.requires_api_key || self.api_key_state.has_key()
+    }
+
+    fn api_key(&self) -> Option<String> {
+        self.api_key_state
+            .key(&self.credentials_url)
+            .map(|key| key.to_string())
+    }
+
+    fn set_api_key(&mut self, api_key: Option<String>, cx: &mut Context<Self>) -> Task<Result<()>> {
+        let store = self.api_key_state.store(
+            self.credentials_url.clone(),
+            api_key,
+            |this| &mut this.api_key_state,
+            cx,
+        );
+        cx.spawn(async move |this, cx| {
+            store.await?;
+            this.update(cx, |this, cx| this.fetch_models(cx))
+        })
+    }
+
+    fn authenticate(&mut self, cx: &mut Context<Self>) -> Task<Result<(), AuthenticateError>> {
+        let load = self.api_key_state.load_if_needed(
+            self.credentials_url.clone(),
+            |this| &mut this.api_key_state,
+            cx,
+        );
+        cx.spawn(async move |this, cx| {
+            load.await?;
+            this.update(cx, |this, cx| {
+                if this.models.is_empty() && this.fetch_models_task.is_none() {
+                    this.fetch_models(cx);
+                }
+            })
+            .ok();
+            Ok(())
+        })
+    }
+
+    fn fetch_models(&mut self, cx: &mut Context<Self>) {
+        let extension = self.extension.clone();
+        let provider_id = self.provider_id.clone();
+        let api_key = self.api_key();
+        self.fetch_models_task = Some(cx.spawn(async move |this, cx| {
+            let models = extension
+                .language_model_provider_models(provider_id, api_key)
+                .await
+                .log_err()
+                .unwrap_or_default();
+            this.update(cx, |this, cx| {
+                this.models = models;
+                this.fetch_models_task = None;
+                cx.notify();
+            })
+            .ok();
+        }));
+    }
+}
+
+impl ExtensionLanguageModelProvider {
+    pub fn new(
+        extension: Arc<dyn Extension>,
+        provider_id: Arc<str>,
+        provider: LanguageModelProviderManifestEntry,
+        cx: &mut App,
+    ) -> Self {
+        let extension_id = extension.manifest().id.clone();
+        let api_key_env_var_name =
+            api_key_env_var_name(&extension_id, &provider_id, provider.api_key_env_var);
+        let credentials_url = format!("zed-extension://{}/{}", extension_id, provider_id);
+        let provider_name = SharedString::from(provider.name);
+        let state = cx.new(|_| State {
+            extension,
+            provider_id: provider_id.clone(),
+            provider_name: provider_name.clone(),
+            api_key_state: ApiKeyState::new(
+                credentials_url.clone().into(),
+                EnvVar::new(api_key_env_var_name.into()),
+            ),
+            credentials_url: credentials_url.into(),
+            requires_api_key: provider.requires_api_key,
+            models: Vec::new(),
+            fetch_models_task: None,
+        });
+
+        Self {
+            id: provider_id.into(),
+            name: LanguageModelProviderName(provider_name),
+            state,
+        }
+    }
+
+    fn create_language_model(&self, model: extension::LanguageModelInfo) -> Arc<dyn LanguageModel> {
+        Arc::new(ExtensionLanguageModel {
+            id: LanguageModelId::from(model.id.clone()),
+            provider_id: self.id.clone(),
+            provider_name: self.name.clone(),
+            model,
+            state: self.state.clone(),
+            request_limiter: RateLimiter::new(4),
+        })
+    }
+}
+
+/// Returns the environment variable to read a provider's API key from.
+///
+/// Extensions may only read variables prefixed with `ZED_<EXTENSION_ID>_`, so that they can't
+/// read credentials meant for other programs. Declared variables without that prefix are
+/// replaced with the default, `ZED_<EXTENSION_ID>_<PROVIDER_ID>_API_KEY`.
+fn api_key_env_var_name(
+    extension_id: &str,
+    provider_id: &str,
+    declared_env_var_name: Option<String>,
+) -> String {
+    let prefix = format!("ZED_{}_", extension_id.to_case(Case::UpperSnake));
+    let default_env_var_name = format!("{prefix}{}_API_KEY", provider_id.to_case(Case::UpperSnake));
+    match declared_env_var_name {
+        Some(env_var_name) if env_var_name.starts_with(&prefix) => env_var_name,
+        Some(env_var_name) => {
+            log::error!(
+                "extension {extension_id} declares the API key environment variable {env_var_name} \
+                for language model provider {provider_id}, which doesn't start with {prefix}. \
+                Reading {default_env_var_name} instead."
+            );
+            default_env_var_name
+        }
+        None => default_env_var_name,
+    }
+}
+
+impl LanguageModelProviderState for ExtensionLanguageModelProvider {
+    type ObservableEntity = State;
+
+    fn observable_entity(&self) -> Option<Entity<Self::ObservableEntity>> {
+        Some(self.state.clone())
+    }
+}
+
+impl LanguageModelProvider for ExtensionLanguageModelProvider {
+    fn id(&self) -> LanguageModelProviderId {
+        self.id.clone()
+    }
+
+    fn name(&self) -> LanguageModelProviderName {
+        self.name.clone()
+    }
+
+    fn default_model(&self, cx: &App) -> Option<Arc<dyn LanguageModel>> {
+        self.state
+            .read(cx)
+            .models
+            .first()
+            .map(|model| self.create_language_model(model.clone()))
+    }
+
+    fn default_fast_model(&self, _cx: &App) -> Option<Arc<dyn LanguageModel>> {
+        None
+    }
+
+    fn provided_models(&self, cx: &App) -> Vec<Arc<dyn LanguageModel>> {
+        self.state
+            .read(cx)
+            .models
+            .iter()
+            .map(|model| self.create_language_model(model.clone()))
+            .collect()
+    }
+
+    fn is_authenticated(&self, cx: &App) -> bool {
+        self.state.read(cx).is_authenticated()
+    }
+
+    fn authenticate(&self, cx: &mut App) -> Task<Result<(), AuthenticateError>> {
+        self.state.update(cx, |state, cx| state.authenticate(cx))
+    }
+
+    fn configuration_view(
+        &self,
+        _target_agent: language_model::ConfigurationViewTargetAgent,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> AnyView {
+        cx.new(|cx| ConfigurationView::new(self.state.clone(), window, cx))
+            .into()
+    }
+
+    fn reset_credentials(&self, cx: &mut App) -> Task<Result<()>> {
+        self.state
+            .update(cx, |state, cx| state.set_api_key(None, cx))
+    }
+}
+
+pub struct ExtensionLanguageModel {
+    id: LanguageModelId,
+    provider_id: LanguageModelProviderId,
+    provider_name: LanguageModelProviderName,
+    model: extension::LanguageModelInfo,
+    state: Entity<State>,
+    request_limiter: RateLimiter,
+}
+
+impl LanguageModel for ExtensionLanguageModel {
+    fn id(&self) -> LanguageModelId {
+        self.id.clone()
+    }
+
+    fn name(&self) -> LanguageModelName {
+        LanguageModelName::from(self.model.name.clone())
+    }
+
+    fn provider_id(&self) -> LanguageModelProviderId {
+        self.provider_id.clone()
+    }
+
+    fn provider_name(&self) -> LanguageModelProviderName {
+        self.provider_name.clone()
+    }
+
+    fn supports_tools(&self) -> bool {
+        self.model.supports_tools
+    }
+
+    fn supports_images(&self) -> bool {
+        self.model.supports_images
+    }
+
+    fn supports_tool_choice(&self, choice: LanguageModelToolChoice) -> bool {
+        match choice {
+            LanguageModelToolChoice::Auto | LanguageModelToolChoice::Any => {
+                self.model.supports_tools
+            }
+            LanguageModelToolChoice::None => true,
+        }
+    }
+
+    fn telemetry_id(&self) -> String {
+        format!("extension/{}/{}", self.provider_id, self.model.id)
+    }
+
+    fn max_token_count(&self) -> u64 {
+        self.model.max_token_count
+    }
+
+    fn max_output_tokens(&self) -> Option<u64> {
+        self.model.max_output_tokens
+    }
+
+    fn count_tokens(
+        &self,
+        request: LanguageModelRequest,
+        cx: &App,
+    ) -> BoxFuture<'static, Result<u64>> {
+        // Extensions don't expose their tokenizers, so estimate with OpenAI's.
+        cx.background_spawn(async move {
+            let messages = super::open_ai::collect_tiktoken_messages(request);
+            tiktoken_rs::num_tokens_from_messages("gpt-4o", &messages).map(|tokens| tokens as u64)
+        })
+        .boxed()
+    }
+
+    fn stream_completion(
+        &self,
+        request: LanguageModelRequest,
+        cx: &AsyncApp,
+    ) -> BoxFuture<
+        'static,
+        Result<
+            BoxStream<'static, Result<LanguageModelCompletionEvent, LanguageModelCompletionError>>,
+            LanguageModelCompletionError,
+        >,
+    > {
+        let Ok((extension, provider_id, api_key, requires_api_key)) =
+            self.state.read_with(cx, |state, _cx| {
+                (
+                    state.extension.clone(),
+                    state.provider_id.clone(),
+                    state.api_key(),
+                    state.requires_api_key,
+                )
+            })
+        else {
+            return future::ready(Err(anyhow!("App state dropped").into())).boxed();
+        };
+
+        let provider = self.provider_name.clone();
+        let model_id = self.model.id.clone();
+        let executor = cx.background_executor().clone();
+        let request = into_extension_request(request);
+        let future = self.request_limiter.stream(async move {
+            if requires_api_key && api_key.is_none() {
+                return Err(LanguageModelCompletionError::NoApiKey { provider });
+            }
+            let stream_id = extension
+                .language_model_stream_completion(provider_id, model_id, api_key, request)
+                .await?;
+            Ok(completion_events(CompletionStream {
+                extension,
+                stream_id,
+                executor,
+                ended: false,
+            }))
+        });
+
+        async move { Ok(future.await?.boxed()) }.boxed()
+    }
+}
+
+/// A completion stream that an extension is producing.
+///
+/// The extension is told to close the stream if it's dropped before it has ended.
+struct CompletionStream {
+    extension: Arc<dyn Extension>,
+    stream_id: u64,
+    executor: BackgroundExecutor,
+    ended: bool,
+}
+
+impl Drop for CompletionStream {
+    fn drop(&mut self) {
+        if self.ended {
+            return;
+        }
+        let extension = self.extension.clone();
+        let stream_id = self.stream_id;
+        self.executor
+            .spawn(async move {
+                extension
+                    .language_model_completion_close(stream_id)
+                    .await
+                    .log_err();
+            })
+            .detach();
+    }
+}
+
+fn completion_events(
+    stream: CompletionStream,
+) -> BoxStream<'static, Result<LanguageModelCompletionEvent, LanguageModelCompletionError>> {
+    futures::stream::unfold(stream, |mut stream| async move {
+        if stream.ended {
+            return None;
+        }
+        let event = stream
+            .extension
+            .language_model_completion_next_event(stream.stream_id)
+            .await;
+        match event {
+            Ok(Some(event)) => Some((Ok(from_extension_event(event)), stream)),
+            Ok(None) => {
+                stream.ended = true;
+                None
+            }
+            Err(error) => {
+                stream.ended = true;
+                Some((Err(error.into()), stream))
+            }
+        }
+    })
+    .boxed()
+}
+
+fn into_extension_request(request: LanguageModelRequest) -> extension::CompletionRequest {
+    extension::CompletionRequest {
+        messages: request
+            .messages
+            .into_iter()
+            .map(|message| extension::RequestMessage {
+                role: match message.role {
+                    Role::User => extension::MessageRole::User,
+                    Role::Assistant => extension::MessageRole::Assistant,
+                    Role::System => extension::MessageRole::System,
+                },
+                content: message
+                    .content
+                    .into_iter()
+                    .filter_map(into_extension_content)
+                    .collect(),
+                cache: message.cache,
+            })
+            .collect(),
+        tools: request
+            .tools
+            .into_iter()
+            .map(|tool| extension::ToolDefinition {
+                name: tool.name,
+                description: tool.description,
+                input_schema: tool.input_schema.to_string(),
+            })
+            .collect(),
+        tool_choice: request.tool_choice.map(|choice| match choice {
+            LanguageModelToolChoice::Auto => extension::ToolChoice::Auto,
+            LanguageModelToolChoice::Any => extension::ToolChoice::Any,
+            LanguageModelToolChoice::None => extension::ToolChoice::None,
+        }),
+        stop: request.stop,
+        temperature: request.temperature,
+        thinking_allowed: request.thinking_allowed,
+    }
+}
+
+fn into_extension_content(content: MessageContent) -> Option<extension::MessageContent> {
+    Some(match content {
+        MessageContent::Text(text) => extension::MessageContent::Text(text),
+        MessageContent::Thinking { text, signature } => {
+            extension::MessageContent::Thinking(extension::Thinking { text, signature })
+        }
+        MessageContent::RedactedThinking(_) => return None,
+        MessageContent::Image(image) => extension::MessageContent::Image(image.source.to_string()),
+        MessageContent::ToolUse(tool_use) => {
+            extension::MessageContent::ToolUse(extension::ToolUse {
+                id: tool_use.id.to_string(),
+                name: tool_use.name.to_string(),
+                input: tool_use.input.to_string(),
+            })
+        }
+        MessageContent::ToolResult(tool_result) => {
+            extension::MessageContent::ToolResult(extension::ToolResult {
+                tool_use_id: tool_result.tool_use_id.to_string(),
+                tool_name: tool_result.tool_name.to_string(),
+                is_error: tool_result.is_error,
+                content: match tool_result.content {
+                    LanguageModelToolResultContent::Text(text) => text.to_string(),
+                    LanguageModelToolResultContent::Image(_) => "[image]".to_string(),
+                },
+            })
+        }
+    })
+}
+
+fn from_extension_event(event: extension::CompletionEvent) -> LanguageModelCompletionEvent {
+    match event {
+        extension::CompletionEvent::Started => LanguageModelCompletionEvent::Started,
+        extension::CompletionEvent::Text(text) => LanguageModelCompletionEvent::Text(text),
+        extension::CompletionEvent::Thinking(thinking) => LanguageModelCompletionEvent::Thinking {
+            text: thinking.text,
+            signature: thinking.signature,
+        },
+        extension::CompletionEvent::ToolUse(tool_use) => {
+            match serde_json::from_str(&tool_use.input) {
+                Ok(input) => LanguageModelCompletionEvent::ToolUse(LanguageModelToolUse {
+                    id: tool_use.id.into(),
+                    name: tool_use.name.into(),
+                    raw_input: tool_use.input,
+                    input,
+                    is_input_complete: true,
+                    thought_signature: None,
+                }),
+                Err(error) => LanguageModelCompletionEvent::ToolUseJsonParseError {
+                    id: tool_use.id.into(),
+                    tool_name: tool_use.name.into(),
+                    raw_input: tool_use.input.into(),
+                    json_parse_error: error.to_string(),
+                },
+            }
+        }
+        extension::CompletionEvent::Stop(reason) => {
+            LanguageModelCompletionEvent::Stop(match reason {
+                extension::StopReason::EndTurn => StopReason::EndTurn,
+                extension::StopReason::MaxTokens => StopReason::MaxTokens,
+                extension::StopReason::ToolUse => StopReason::ToolUse,
+                extension::StopReason::Refusal => StopReason::Refusal,
+            })
+        }
+        extension::CompletionEvent::UsageUpdate(usage) => {
+            LanguageModelCompletionEvent::UsageUpdate(TokenUsage {
+                input_tokens: usage.input_tokens,
+                output_tokens: usage.output_tokens,
+                cache_creation_input_tokens: usage.cache_creation_input_tokens,
+                cache_read_input_tokens: usage.cache_read_input_tokens,
+            })
+        }
+    }
+}
+
+struct ConfigurationView {
+    api_key_editor: Entity<InputField>,
+    state: Entity<State>,
+    load_credentials_task: Option<Task<()>>,
+}
+
+impl ConfigurationView {
+    fn new(state: Entity<State>, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let api_key_editor = cx.new(|cx| {
+            InputField::new(
+                window,
+                cx,
+                "000000000000000000000000000000000000000000000000000",
+            )
+        });
+
+        cx.observe(&state, |_, _, cx| {
+            cx.notify();
+        })
+        .detach();
+
+        let load_credentials_task = Some(cx.spawn_in(window, {
+            let state = state.clone();
+            async move |this, cx| {
+                if let Some(task) = state
+                    .update(cx, |state, cx| state.authenticate(cx))
+                    .log_err()
+                {
+                    // We don't log an error, because "not signed in" is also an error.
+                    let _ = task.await;
+                }
+                this.update(cx, |this, cx| {
+                    this.load_credentials_task = None;
+                    cx.notify();
+                })
+                .log_err();
+            }
+        }));
+
+        Self {
+            api_key_editor,
+            state,
+            load_credentials_task,
+        }
+    }
+
+    fn save_api_key(&mut self, _: &menu::Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        let api_key = self.api_key_editor.read(cx).text(cx).trim().to_string();
+        if api_key.is_empty() {
+            return;
+        }
+
+        self.api_key_editor
+            .update(cx, |input, cx| input.set_text("", window, cx));
+
+        let state = self.state.clone();
+        cx.spawn_in(window, async move |_, cx| {
+            state
+                .update(cx, |state, cx| state.set_api_key(Some(api_key), cx))?
+                .await
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn reset_api_key(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.api_key_editor
+            .update(cx, |input, cx| input.set_text("", window, cx));
+
+        let state = self.state.clone();
+        cx.spawn_in(window, async move |_, cx| {
+            state
+                .update(cx, |state, cx| state.set_api_key(None, cx))?
+                .await
+        })
+        .detach_and_log_err(cx);
+    }
+}
+
+impl Render for ConfigurationView {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let state = self.state.read(cx);
+        let env_var_set = state.api_key_state.is_from_env_var();
+        let env_var_name = state.api_key_state.env_var_name();
+
+        let api_key_section = if !state.api_key_state.has_key() {
+            v_flex()
+                .on_action(cx.listener(Self::save_api_key))
+                .child(Label::new(if state.requires_api_key {
+                    format!(
+                        "To use Zed's agent with {}, you need to add an API key.",
+                        state.provider_name
+                    )
+                } else {
+                    format!(
+                        "{} doesn't need an API key, but you can add one.",
+                        state.provider_name
+                    )
+                }))
+                .child(
+                    div()
+                        .pt(DynamicSpacing::Base04.rems(cx))
+                        .child(self.api_key_editor.clone()),
+                )
+                .child(
+                    Label::new(format!(
+                        "You can also assign the {env_var_name} environment variable and restart Zed."
+                    ))
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+                )
+                .into_any()
+        } else {
+            h_flex()
+                .mt_1()
+                .p_1()
+                .justify_between()
+                .rounded_md()
+                .border_1()
+                .border_color(cx.theme().colors().border)
+                .bg(cx.theme().colors().background)
+                .child(
+                    h_flex()
+                        .flex_1()
+                        .min_w_0()
+                        .gap_1()
+                        .child(Icon::new(IconName::Check).color(Color::Success))
+                        .child(Label::new(if env_var_set {
+                            format!("API key set in {env_var_name} environment variable")
+                        } else {
+                            format!("API key configured for {}", state.provider_name)
+                        })),
+                )
+                .child(
+                    Button::new("reset-api-key", "Reset API Key")
+                        .label_size(LabelSize::Small)
+                        .icon(IconName::Undo)
+                        .icon_size(IconSize::Small)
+                        .icon_position(IconPosition::Start)
+                        .layer(ElevationIndex::ModalSurface)
+                        .when(env_var_set, |this| {
+                            this.tooltip(Tooltip::text(format!(
+                                "To reset your API key, unset the {env_var_name} environment variable."
+                            )))
+                        })
+                        .on_click(
+                            cx.listener(|this, _, window, cx| this.reset_api_key(window, cx)),
+                        ),
+                )
+                .into_any()
+        };
+
+        if self.load_credentials_task.is_some() {
+            div().child(Label::new("Loading credentials…")).into_any()
+        } else {
+            v_flex().size_full().child(api_key_section).into_any()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use language_model::{
+        LanguageModelImage, LanguageModelRequestMessage, LanguageModelRequestTool,
+        LanguageModelToolResult, LanguageModelToolUseId,
+    };
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_api_key_env_var_name() {
+        assert_eq!(
+            api_key_env_var_name("my-extension", "my-gateway", None),
+            "ZED_MY_EXTENSION_MY_GATEWAY_API_KEY"
+        );
+        assert_eq!(
+            api_key_env_var_name(
+                "my-extension",
+                "my-gateway",
+                Some("ZED_MY_EXTENSION_GATEWAY_KEY".to_string())
+            ),
+            "ZED_MY_EXTENSION_GATEWAY_KEY"
+        );
+        // Variables of other programs, or other extensions, can't be read.
+        assert_eq!(
+            api_key_env_var_name(
+                "my-extension",
+                "my-gateway",
+                Some("OPENAI_API_KEY".to_string())
+            ),
+            "ZED_MY_EXTENSION_MY_GATEWAY_API_KEY"
+        );
+        assert_eq!(
+            api_key_env_var_name(
+                "my-extension",
+                "my-gateway",
+                Some("ZED_OTHER_EXTENSION_API_KEY".to_string())
+            ),
+            "ZED_MY_EXTENSION_MY_GATEWAY_API_KEY"
+        );
+    }
+
+    #[test]
+    fn test_into_extension_request() {
+        let request = LanguageModelRequest {
+            thread_id: None,
+            prompt_id: None,
+            intent: None,
+            mode: None,
+            messages: vec![
+                LanguageModelRequestMessage {
+                    role: Role::System,
+                    content: vec![MessageContent::Text("Be brief.".into())],
+                    cache: false,
+                    reasoning_details: None,
+                },
+                LanguageModelRequestMessage {
+                    role: Role::User,
+                    content: vec![
+                        MessageContent::Text("What's in this image?".into()),
+                        MessageContent::Image(LanguageModelImage {
+                            source: "aGVsbG8=".into(),
+                            size: None,
+                        }),
+                    ],
+                    cache: true,
+                    reasoning_details: None,
+                },
+                LanguageModelRequestMessage {
+                    role: Role::Assistant,
+                    content: vec![
+                        MessageContent::Thinking {
+                            text: "Let me look.".into(),
+                            signature: Some("signature".into()),
+                        },
+                        MessageContent::RedactedThinking("redacted".into()),
+                        MessageContent::ToolUse(LanguageModelToolUse {
+                            id: "tool-1".into(),
+                            name: "describe".into(),
+                            raw_input: r#"{"detail":"high"}"#.into(),
+                            input: json!({ "detail": "high" }),
+                            is_input_complete: true,
+                            thought_signature: None,
+                        }),
+                    ],
+                    cache: false,
+                    reasoning_details: None,
+                },
+                LanguageModelRequestMessage {
+                    role: Role::User,
+                    content: vec![
+                        MessageContent::ToolResult(LanguageModelToolResult {
+                            tool_use_id: "tool-1".into(),
+                            tool_name: "describe".into(),
+                            is_error: false,
+                            content: LanguageModelToolResultContent::Text("A cat.".into()),
+                            output: None,
+                        }),
+                        MessageContent::ToolResult(LanguageModelToolResult {
+                            tool_use_id: "tool-2".into(),
+                            tool_name: "screenshot".into(),
+                            is_error: true,
+                            content: LanguageModelToolResultContent::Image(
+                                LanguageModelImage::empty(),
+                            ),
+                            output: None,
+                        }),
+                    ],
+                    cache: false,
+                    reasoning_details: None,
+                },
+            ],
+            tools: vec![LanguageModelRequestTool {
+                name: "describe".into(),
+                description: "Describes an image.".into(),
+                input_schema: json!({ "type": "object" }),
+            }],
+            tool_choice: Some(LanguageModelToolChoice::Any),
+            stop: vec!["STOP".into()],
+            temperature: Some(0.5),
+            thinking_allowed: true,
+        };
+
+        assert_eq!(
+            into_extension_request(request),
+            extension::CompletionRequest {
+                messages: vec![
+                    extension::RequestMessage {
+                        role: extension::MessageRole::System,
+                        content: vec![extension::MessageContent::Text("Be brief.".into())],
+                        cache: false,
+                    },
+                    extension::RequestMessage {
+                        role: extension::MessageRole::User,
+                        content: vec![
+                            extension::MessageContent::Text("What's in this image?".into()),
+                            extension::MessageContent::Image("aGVsbG8=".into()),
+                        ],
+                        cache: true,
+                    },
+                    extension::RequestMessage {
+                        role: extension::MessageRole::Assistant,
+                        content: vec![
+                            extension::MessageContent::Thinking(extension::Thinking {
+                                text: "Let me look.".into(),
+                                signature: Some("signature".into()),
+                            }),
+                            extension::MessageContent::ToolUse(extension::ToolUse {
+                                id: "tool-1".into(),
+                                name: "describe".into(),
+                                input: r#"{"detail":"high"}"#.into(),
+                            }),
+                        ],
+                        cache: false,
+                    },
+                    extension::RequestMessage {
+                        role: extension::MessageRole::User,
+                        content: vec![
+                            extension::MessageContent::ToolResult(extension::ToolResult {
+                                tool_use_id: "tool-1".into(),
+                                tool_name: "describe".into(),
+                                is_error: false,
+                                content: "A cat.".into(),
+                            }),
+                            extension::MessageContent::ToolResult(extension::ToolResult {
+                                tool_use_id: "tool-2".into(),
+                                tool_name: "screenshot".into(),
+                                is_error: true,
+                                content: "[image]".into(),
+                            }),
+                        ],
+                        cache: false,
+                    },
+                ],
+                tools: vec![extension::ToolDefinition {
+                    name: "describe".into(),
+                    description: "Describes an image.".into(),
+                    input_schema: r#"{"type":"object"}"#.into(),
+                }],
+                tool_choice: Some(extension::ToolChoice::Any),
+                stop: vec!["STOP".into()],
+                temperature: Some(0.5),
+                thinking_allowed: true,
+            }
+        );
+    }
+
+    #[test]
+    fn test_from_extension_event() {
+        assert_eq!(
+            from_extension_event(extension::CompletionEvent::Started),
+            LanguageModelCompletionEvent::Started
+        );
+        assert_eq!(
+            from_extension_event(extension::CompletionEvent::Text("Hello".into())),
+            LanguageModelCompletionEvent::Text("Hello".into())
+        );
+        assert_eq!(
+            from_extension_event(extension::CompletionEvent::Thinking(extension::Thinking {
+                text: "Hmm".into(),
+                signature: None,
+            })),
+            LanguageModelCompletionEvent::Thinking {
+                text: "Hmm".into(),
+                signature: None,
+            }
+        );
+        assert_eq!(
+            from_extension_event(extension::CompletionEvent::ToolUse(extension::ToolUse {
+                id: "tool-1".into(),
+                name: "describe".into(),
+                input: r#"{"detail":"high"}"#.into(),
+            })),
+            LanguageModelCompletionEvent::ToolUse(LanguageModelToolUse {
+                id: "tool-1".into(),
+                name: "describe".into(),
+                raw_input: r#"{"detail":"high"}"#.into(),
+                input: json!({ "detail": "high" }),
+                is_input_complete: true,
+                thought_signature: None,
+            })
+        );
+        let LanguageModelCompletionEvent::ToolUseJsonParseError {
+            id,
+            tool_name,
+            raw_input,
+            ..
+        } = from_extension_event(extension::CompletionEvent::ToolUse(extension::ToolUse {
+            id: "tool-2".into(),
+            name: "describe".into(),
+            input: "{".into(),
+        }))
+        else {
+            panic!("expected invalid tool input to be reported");
+        };
+        assert_eq!(id, LanguageModelToolUseId::from("tool-2"));
+        assert_eq!(tool_name.as_ref(), "describe");
+        assert_eq!(raw_input.as_ref(), "{");
+        assert_eq!(
+            from_extension_event(extension::CompletionEvent::Stop(
+                extension::StopReason::Refusal
+            )),
+            LanguageModelCompletionEvent::Stop(StopReason::Refusal)
+        );
+        assert_eq!(
+            from_extension_event(extension::CompletionEvent::UsageUpdate(
+                extension::TokenUsage {
+                    input_tokens: 1,
+                    output_tokens: 2,
+                    cache_creation_input_tokens: 3,
+                    cache_read_input_tokens: 4,
+                }
+            )),
+            LanguageModelCompletionEvent::UsageUpdate(TokenUsage {
+                input_tokens: 1,
+                output_tokens: 2,
+                cache_creation_input_tokens: 3,
+                cache_read_input_tokens: 4,
+            })
+        );
+    }
+}
@@ -0,0 +1,116 @@
+use editor::{Editor, EditorEvent};
+use gpui::{
+    App, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, Render, Subscription,
+    WeakEntity,
+};
+use ui::prelude::*;
+use workspace::ModalView;
+
+use crate::LargeFileView;
+
+/// Asks for a line of a large file to scroll to.
+pub struct GoToLine {
+    line_editor: Entity<Editor>,
+    view: WeakEntity<LargeFileView>,
+    line_count: usize,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl ModalView for GoToLine {}
+
+impl EventEmitter<DismissEvent> for GoToLine {}
+
+impl Focusable for GoToLine {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.line_editor.focus_handle(cx)
+    }
+}
+
+impl GoToLine {
+    pub fn new(
+        view: WeakEntity<LargeFileView>,
+        line_count: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let line_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            editor.set_placeholder_text("Line number", window, cx);
+            editor
+        });
+        let subscriptions =
+            vec![
+                cx.subscribe_in(&line_editor, window, |_, _, event, _, cx| match event {
+                    EditorEvent::Blurred => cx.emit(DismissEvent),
+                    EditorEvent::BufferEdited => cx.notify(),
+                    _ => {}
+                }),
+            ];
+        Self {
+            line_editor,
+            view,
+            line_count,
+            _subscriptions: subscriptions,
+        }
+    }
+
+    /// Returns the zero-based row that was entered, if it is in the file.
+    fn row_from_query(&self, cx: &App) -> Option<usize> {
+        let line = self
+            .line_editor
+            .read(cx)
+            .text(cx)
+            .trim()
+            .parse::<usize>()
+            .ok()?;
+        (1..=self.line_count).contains(&line).then(|| line - 1)
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, _: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(row) = self.row_from_query(cx) else {
+            return;
+        };
+        self.view
+            .update(cx, |view, cx| {
+                view.go_to_row(row, cx);
+                view.focus_handle(cx).focus(window, cx);
+            })
+            .ok();
+        cx.emit(DismissEvent);
+    }
+}
+
+impl Render for GoToLine {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let help_text = match self.row_from_query(cx) {
+            Some(row) => format!("Go to line {}", row + 1),
+            None => format!("Enter a line number between 1 and {}", self.line_count),
+        };
+
+        v_flex()
+            .w(rems(24.))
+            .elevation_2(cx)
+            .key_context("GoToLine")
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::confirm))
+            .child(
+                div()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .px_2()
+                    .py_1()
+                    .child(self.line_editor.clone()),
+            )
+            .child(
+                h_flex()
+                    .px_2()
+                    .py_1()
+                    .gap_1()
+                    .child(Label::new(help_text).color(Color::Muted)),
+            )
+    }
+}
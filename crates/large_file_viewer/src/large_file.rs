@@ -0,0 +1,362 @@
+//! Random access to files that are too large to load into a buffer, with an
+//! index of their lines that is built incrementally.
+
+use std::{io, ops::Range, path::Path, sync::Arc};
+
+use regex::bytes::Regex;
+
+/// The number of lines between the line starts recorded in a [`LineIndex`].
+const LINE_INDEX_STRIDE: usize = 256;
+
+/// The number of bytes read from the file at once.
+const READ_BLOCK_SIZE: usize = 64 * 1024;
+
+/// The maximum number of bytes of a line that are shown or searched.
+pub const MAX_LINE_LEN: usize = 16 * 1024;
+
+/// A file that is read in pages, rather than loaded into memory.
+pub trait RandomAccess: Send + Sync {
+    fn len(&self) -> u64;
+
+    fn read_at(&self, offset: u64, buf: &mut [u8]) -> io::Result<usize>;
+}
+
+struct DiskFile {
+    file: std::fs::File,
+    len: u64,
+}
+
+impl RandomAccess for DiskFile {
+    fn len(&self) -> u64 {
+        self.len
+    }
+
+    #[cfg(unix)]
+    fn read_at(&self, offset: u64, buf: &mut [u8]) -> io::Result<usize> {
+        std::os::unix::fs::FileExt::read_at(&self.file, buf, offset)
+    }
+
+    #[cfg(windows)]
+    fn read_at(&self, offset: u64, buf: &mut [u8]) -> io::Result<usize> {
+        std::os::windows::fs::FileExt::seek_read(&self.file, buf, offset)
+    }
+}
+
+impl RandomAccess for Vec<u8> {
+    fn len(&self) -> u64 {
+        self.as_slice().len() as u64
+    }
+
+    fn read_at(&self, offset: u64, buf: &mut [u8]) -> io::Result<usize> {
+        let start = (offset as usize).min(self.as_slice().len());
+        let len = buf.len().min(self.as_slice().len() - start);
+        buf[..len].copy_from_slice(&self[start..start + len]);
+        Ok(len)
+    }
+}
+
+/// Opens a file on the local disk for random access.
+pub fn open(path: &Path) -> io::Result<Arc<dyn RandomAccess>> {
+    let file = std::fs::File::open(path)?;
+    let len = file.metadata()?.len();
+    Ok(Arc::new(DiskFile { file, len }))
+}
+
+/// A line of a large file, truncated to [`MAX_LINE_LEN`] bytes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Line {
+    pub text: String,
+    pub is_truncated: bool,
+}
+
+/// A match of a search in a large file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LineMatch {
+    pub row: usize,
+    /// The range of the match within the line, in bytes.
+    pub range: Range<usize>,
+}
+
+/// The positions of lines in a large file, recording the start of every
+/// [`LINE_INDEX_STRIDE`]th line, so that any line can be found by reading at
+/// most that many lines.
+#[derive(Clone, Debug)]
+pub struct LineIndex {
+    line_starts: Vec<u64>,
+    newlines: usize,
+    indexed_len: u64,
+    current_line_start: u64,
+    /// The row of the longest line so far, and its length in bytes.
+    longest_line: (usize, u64),
+}
+
+impl Default for LineIndex {
+    fn default() -> Self {
+        Self {
+            line_starts: vec![0],
+            newlines: 0,
+            indexed_len: 0,
+            current_line_start: 0,
+            longest_line: (0, 0),
+        }
+    }
+}
+
+impl LineIndex {
+    /// Indexes at most `max_len` more bytes of the file, returning whether
+    /// the whole file is indexed.
+    pub fn index(&mut self, file: &dyn RandomAccess, max_len: u64) -> io::Result<bool> {
+        let end = file.len().min(self.indexed_len.saturating_add(max_len));
+        let mut buf = vec![0; READ_BLOCK_SIZE];
+        while self.indexed_len < end {
+            let len = READ_BLOCK_SIZE.min((end - self.indexed_len) as usize);
+            let read = file.read_at(self.indexed_len, &mut buf[..len])?;
+            if read == 0 {
+                break;
+            }
+            for (ix, byte) in buf[..read].iter().enumerate() {
+                if *byte == b'\n' {
+                    let line_end = self.indexed_len + ix as u64;
+                    self.end_line(line_end);
+                    self.newlines += 1;
+                    self.current_line_start = line_end + 1;
+                    if self.newlines.is_multiple_of(LINE_INDEX_STRIDE) {
+                        self.line_starts.push(self.current_line_start);
+                    }
+                }
+            }
+            self.indexed_len += read as u64;
+        }
+
+        let is_complete = self.indexed_len >= file.len();
+        if is_complete {
+            self.end_line(self.indexed_len);
+        }
+        Ok(is_complete)
+    }
+
+    fn end_line(&mut self, line_end: u64) {
+        let len = line_end - self.current_line_start;
+        if len > self.longest_line.1 {
+            self.longest_line = (self.newlines, len);
+        }
+    }
+
+    /// The number of lines in the indexed part of the file.
+    pub fn line_count(&self) -> usize {
+        self.newlines + 1
+    }
+
+    /// The number of bytes of the file that are indexed.
+    pub fn indexed_len(&self) -> u64 {
+        self.indexed_len
+    }
+
+    /// The row of the longest line in the indexed part of the file.
+    pub fn longest_row(&self) -> usize {
+        self.longest_line.0
+    }
+
+    /// Reads the line at `row`, which must be in the indexed part of the file.
+    pub fn read_line(&self, file: &dyn RandomAccess, row: usize) -> io::Result<Line> {
+        let Some(&start) = self.line_starts.get(row / LINE_INDEX_STRIDE) else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("line {row} is not indexed"),
+            ));
+        };
+        let mut lines_to_skip = row % LINE_INDEX_STRIDE;
+        let mut offset = start;
+        let mut text = Vec::new();
+        let mut buf = vec![0; READ_BLOCK_SIZE];
+        'read: loop {
+            let read = file.read_at(offset, &mut buf)?;
+            if read == 0 {
+                break;
+            }
+            let mut block = &buf[..read];
+            while lines_to_skip > 0 {
+                match block.iter().position(|byte| *byte == b'\n') {
+                    Some(ix) => {
+                        lines_to_skip -= 1;
+                        block = &block[ix + 1..];
+                    }
+                    None => {
+                        offset += read as u64;
+                        continue 'read;
+                    }
+                }
+            }
+            let line_end = block.iter().position(|byte| *byte == b'\n');
+            let remaining_len = MAX_LINE_LEN + 1 - text.len();
+            text.extend_from_slice(&block[..line_end.unwrap_or(block.len()).min(remaining_len)]);
+            if line_end.is_some() || text.len() > MAX_LINE_LEN {
+                break;
+            }
+            offset += read as u64;
+        }
+
+        let is_truncated = text.len() > MAX_LINE_LEN;
+        text.truncate(MAX_LINE_LEN);
+        if text.last() == Some(&b'\r') {
+            text.pop();
+        }
+        Ok(Line {
+            text: String::from_utf8_lossy(&text).into_owned(),
+            is_truncated,
+        })
+    }
+}
+
+/// A search through a large file, which runs a block at a time, so that it
+/// can be interleaved with other work and cancelled.
+pub struct LineSearch {
+    regex: Regex,
+    max_matches: usize,
+    offset: u64,
+    row: usize,
+    line: Vec<u8>,
+    matches: Vec<LineMatch>,
+}
+
+impl LineSearch {
+    pub fn new(regex: Regex, max_matches: usize) -> Self {
+        Self {
+            regex,
+            max_matches,
+            offset: 0,
+            row: 0,
+            line: Vec::new(),
+            matches: Vec::new(),
+        }
+    }
+
+    /// Searches the next block of the file, returning whether the search is done.
+    pub fn search_block(&mut self, file: &dyn RandomAccess) -> io::Result<bool> {
+        let mut buf = vec![0; READ_BLOCK_SIZE];
+        let read = file.read_at(self.offset, &mut buf)?;
+        self.offset += read as u64;
+        if read == 0 {
+            self.end_line();
+            return Ok(true);
+        }
+
+        for chunk in buf[..read].split_inclusive(|byte| *byte == b'\n') {
+            let (text, ends_line) = match chunk.split_last() {
+                Some((b'\n', text)) => (text, true),
+                _ => (chunk, false),
+            };
+            let remaining_len = MAX_LINE_LEN.saturating_sub(self.line.len());
+            self.line
+                .extend_from_slice(&text[..text.len().min(remaining_len)]);
+            if ends_line {
+                self.end_line();
+                self.row += 1;
+            }
+            if self.matches.len() >= self.max_matches {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    fn end_line(&mut self) {
+        if self.line.last() == Some(&b'\r') {
+            self.line.pop();
+        }
+        for found in self.regex.find_iter(&self.line) {
+            if self.matches.len() >= self.max_matches {
+                break;
+            }
+            self.matches.push(LineMatch {
+                row: self.row,
+                range: found.range(),
+            });
+        }
+        self.line.clear();
+    }
+
+    pub fn into_matches(self) -> Vec<LineMatch> {
+        self.matches
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(text: &str) -> Vec<u8> {
+        text.as_bytes().to_vec()
+    }
+
+    fn index(file: &Vec<u8>, chunk_len: u64) -> LineIndex {
+        let mut index = LineIndex::default();
+        while !index.index(file, chunk_len).unwrap() {}
+        index
+    }
+
+    #[test]
+    fn test_line_index() {
+        let text = (0..1000)
+            .map(|ix| format!("line {ix}{}", "!".repeat(ix % 7)))
+            .collect::<Vec<_>>()
+            .join("\r\n");
+        let file = file(&text);
+
+        for chunk_len in [7, 1000, u64::MAX] {
+            let line_index = index(&file, chunk_len);
+            assert_eq!(line_index.line_count(), 1000);
+            assert_eq!(line_index.indexed_len(), file.len() as u64);
+            for row in [0, 1, 255, 256, 257, 511, 512, 999] {
+                assert_eq!(
+                    line_index.read_line(&file, row).unwrap(),
+                    Line {
+                        text: format!("line {row}{}", "!".repeat(row % 7)),
+                        is_truncated: false,
+                    }
+                );
+            }
+        }
+        assert!(index(&file, u64::MAX).read_line(&file, 1000).is_err());
+    }
+
+    #[test]
+    fn test_long_lines() {
+        let long_line = "a".repeat(MAX_LINE_LEN * 3);
+        let file = file(&format!("short\n{long_line}\n\nend"));
+        let line_index = index(&file, u64::MAX);
+        assert_eq!(line_index.line_count(), 4);
+        assert_eq!(line_index.longest_row(), 1);
+
+        let line = line_index.read_line(&file, 1).unwrap();
+        assert!(line.is_truncated);
+        assert_eq!(line.text.len(), MAX_LINE_LEN);
+        assert_eq!(line_index.read_line(&file, 2).unwrap().text, "");
+        assert_eq!(line_index.read_line(&file, 3).unwrap().text, "end");
+    }
+
+    #[test]
+    fn test_search() {
+        let text = (0..10_000)
+            .map(|ix| format!("row {ix}: {}", if ix % 1000 == 0 { "ERROR" } else { "ok" }))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let file = file(&text);
+
+        let search = |pattern: &str, max_matches: usize| {
+            let mut search = LineSearch::new(Regex::new(pattern).unwrap(), max_matches);
+            while !search.search_block(&file).unwrap() {}
+            search.into_matches()
+        };
+
+        let matches = search("ERROR", usize::MAX);
+        assert_eq!(
+            matches.iter().map(|m| m.row).collect::<Vec<_>>(),
+            (0..10).map(|ix| ix * 1000).collect::<Vec<_>>()
+        );
+        assert_eq!(matches[1].range, 10..15);
+
+        assert_eq!(search("ERROR", 3).len(), 3);
+        assert_eq!(search(r"^row 9999: ok$", usize::MAX).len(), 1);
+    }
+}
@@ -0,0 +1,750 @@
+//! Read-only viewing of files that are too large to load into an editor.
+//!
+//! Large files are read in pages and indexed by line in the background, so
+//! that opening them takes little memory. They have no syntax highlighting or
+//! language servers, but can be searched and navigated by line.
+
+mod go_to_line;
+mod large_file;
+mod large_file_viewer_settings;
+
+use std::{ops::Range, path::PathBuf, sync::Arc};
+
+use collections::HashMap;
+use editor::{EditorSettings, items::entry_git_aware_label_color};
+use file_icons::FileIcons;
+use gpui::{
+    AnyElement, App, Context, Entity, EventEmitter, FocusHandle, Focusable, HighlightStyle,
+    InteractiveElement, IntoElement, ListHorizontalSizingBehavior, ParentElement, Render,
+    ScrollStrategy, SharedString, Styled, StyledText, Subscription, Task, UniformListScrollHandle,
+    Window, uniform_list,
+};
+use project::{Project, ProjectEntryId, ProjectPath, search::SearchQuery};
+use regex::bytes::RegexBuilder;
+use settings::Settings;
+use theme::{Theme, ThemeSettings};
+use ui::{WithScrollbar, prelude::*};
+use util::paths::PathExt;
+use workspace::{
+    ItemSettings, Pane, ToolbarItemLocation, Workspace, WorkspaceId,
+    item::{BreadcrumbText, Item, ProjectItem, TabContentParams},
+    searchable::{Direction, SearchEvent, SearchOptions, SearchableItem, SearchableItemHandle},
+};
+
+use crate::go_to_line::GoToLine;
+pub use crate::large_file::{Line, LineIndex, LineMatch, RandomAccess};
+use crate::large_file::{LineSearch, MAX_LINE_LEN};
+pub use crate::large_file_viewer_settings::*;
+
+/// How many bytes of the file are indexed between updates of the view.
+const INDEX_CHUNK_LEN: u64 = 64 * 1024 * 1024;
+
+/// The maximum number of matches a search finds.
+const MAX_SEARCH_MATCHES: usize = 100_000;
+
+/// The maximum number of lines that are kept in memory for rendering.
+const MAX_CACHED_LINES: usize = 4096;
+
+pub fn init(cx: &mut App) {
+    workspace::register_project_item::<LargeFileView>(cx);
+}
+
+/// A file that is too large to load into a buffer, which is read in pages.
+pub struct LargeFileItem {
+    file: Arc<dyn RandomAccess>,
+    line_index: LineIndex,
+    is_indexed: bool,
+    index_error: Option<SharedString>,
+    abs_path: PathBuf,
+    project_path: ProjectPath,
+    entry_id: Option<ProjectEntryId>,
+    _index: Task<()>,
+}
+
+impl LargeFileItem {
+    pub fn new(
+        file: Arc<dyn RandomAccess>,
+        abs_path: PathBuf,
+        project_path: ProjectPath,
+        entry_id: Option<ProjectEntryId>,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let index = cx.spawn(async move |this, cx| {
+            loop {
+                let Ok((file, mut line_index)) =
+                    this.read_with(cx, |this, _| (this.file.clone(), this.line_index.clone()))
+                else {
+                    break;
+                };
+                let result = cx
+                    .background_spawn(async move {
+                        let is_complete = line_index.index(file.as_ref(), INDEX_CHUNK_LEN)?;
+                        anyhow::Ok((line_index, is_complete))
+                    })
+                    .await;
+                let is_done = this.update(cx, |this, cx| {
+                    let is_done = match result {
+                        Ok((line_index, is_complete)) => {
+                            this.line_index = line_index;
+                            is_complete
+                        }
+                        Err(error) => {
+                            log::error!("failed to index {:?}: {error:#}", this.abs_path);
+                            this.index_error = Some(format!("{error:#}").into());
+                            true
+                        }
+                    };
+                    this.is_indexed = is_done;
+                    cx.notify();
+                    is_done
+                });
+                if is_done.unwrap_or(true) {
+                    break;
+                }
+            }
+        });
+
+        Self {
+            file,
+            line_index: LineIndex::default(),
+            is_indexed: false,
+            index_error: None,
+            abs_path,
+            project_path,
+            entry_id,
+            _index: index,
+        }
+    }
+
+    pub fn line_index(&self) -> &LineIndex {
+        &self.line_index
+    }
+
+    pub fn is_indexed(&self) -> bool {
+        self.is_indexed
+    }
+
+    pub fn len(&self) -> u64 {
+        self.file.len()
+    }
+}
+
+impl project::ProjectItem for LargeFileItem {
+    fn try_open(
+        project: &Entity<Project>,
+        path: &ProjectPath,
+        cx: &mut App,
+    ) -> Option<Task<anyhow::Result<Entity<Self>>>> {
+        let threshold = LargeFileViewerSettings::get_global(cx).threshold;
+        let entry = project.read(cx).entry_for_path(path, cx)?;
+        if !entry.is_file() || entry.size < threshold {
+            return None;
+        }
+        // Files of remote projects can't be read in pages yet, and loading
+        // them into a buffer would transfer and hold all of their contents.
+        if !project.read(cx).is_local() {
+            return Some(Task::ready(Err(anyhow::anyhow!(
+                "file is too large to open remotely ({} bytes, the limit is {threshold} bytes)",
+                entry.size
+            ))));
+        }
+        let entry_id = Some(entry.id);
+        let abs_path = project.read(cx).absolute_path(path, cx)?;
+        let project_path = path.clone();
+
+        Some(cx.spawn(async move |cx| {
+            let file = cx
+                .background_spawn({
+                    let abs_path = abs_path.clone();
+                    async move { large_file::open(&abs_path) }
+                })
+                .await?;
+            cx.new(|cx| LargeFileItem::new(file, abs_path, project_path, entry_id, cx))
+        }))
+    }
+
+    fn entry_id(&self, _: &App) -> Option<ProjectEntryId> {
+        self.entry_id
+    }
+
+    fn project_path(&self, _: &App) -> Option<ProjectPath> {
+        Some(self.project_path.clone())
+    }
+
+    fn is_dirty(&self) -> bool {
+        false
+    }
+}
+
+pub struct LargeFileView {
+    item: Entity<LargeFileItem>,
+    project: Entity<Project>,
+    focus_handle: FocusHandle,
+    scroll_handle: UniformListScrollHandle,
+    lines: HashMap<usize, Line>,
+    matches: Vec<LineMatch>,
+    active_match: Option<usize>,
+    highlighted_row: Option<usize>,
+    _observe_item: Subscription,
+}
+
+impl LargeFileView {
+    pub fn new(
+        item: Entity<LargeFileItem>,
+        project: Entity<Project>,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        Self {
+            _observe_item: cx.observe(&item, |_, _, cx| cx.notify()),
+            item,
+            project,
+            focus_handle: cx.focus_handle(),
+            scroll_handle: UniformListScrollHandle::new(),
+            lines: HashMap::default(),
+            matches: Vec::new(),
+            active_match: None,
+            highlighted_row: None,
+        }
+    }
+
+    /// Scrolls to the given row and highlights it.
+    pub fn go_to_row(&mut self, row: usize, cx: &mut Context<Self>) {
+        self.highlighted_row = Some(row);
+        self.scroll_handle
+            .scroll_to_item(row, ScrollStrategy::Center);
+        cx.notify();
+    }
+
+    fn toggle_go_to_line(
+        &mut self,
+        _: &editor::actions::ToggleGoToLine,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(workspace) = window.root::<Workspace>().flatten() else {
+            return;
+        };
+        let view = cx.entity().downgrade();
+        let line_count = self.item.read(cx).line_index.line_count();
+        workspace.update(cx, |workspace, cx| {
+            workspace.toggle_modal(window, cx, |window, cx| {
+                GoToLine::new(view, line_count, window, cx)
+            });
+        });
+    }
+
+    fn line(&mut self, row: usize, cx: &App) -> &Line {
+        if self.lines.len() >= MAX_CACHED_LINES {
+            self.lines.clear();
+        }
+        self.lines.entry(row).or_insert_with(|| {
+            let item = self.item.read(cx);
+            item.line_index
+                .read_line(item.file.as_ref(), row)
+                .unwrap_or_else(|error| {
+                    log::error!("failed to read line {row} of {:?}: {error}", item.abs_path);
+                    Line {
+                        text: String::new(),
+                        is_truncated: false,
+                    }
+                })
+        })
+    }
+
+    fn render_lines(
+        &mut self,
+        range: Range<usize>,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Vec<AnyElement> {
+        let line_count = self.item.read(cx).line_index.line_count();
+        let line_number_width = line_count.to_string().len();
+        let colors = cx.theme().colors();
+        let (editor_line_number, active_line_background) = (
+            colors.editor_line_number,
+            colors.editor_active_line_background,
+        );
+        let (match_background, active_match_background) = (
+            colors.search_match_background,
+            colors.search_active_match_background,
+        );
+
+        range
+            .map(|row| {
+                let first_match = self.matches.partition_point(|m| m.row < row);
+                let highlights = self.matches[first_match..]
+                    .iter()
+                    .enumerate()
+                    .take_while(|(_, m)| m.row == row)
+                    .map(|(ix, m)| {
+                        let background = if self.active_match == Some(first_match + ix) {
+                            active_match_background
+                        } else {
+                            match_background
+                        };
+                        (
+                            m.range.clone(),
+                            HighlightStyle {
+                                background_color: Some(background),
+                                ..Default::default()
+                            },
+                        )
+                    })
+                    .collect::<Vec<_>>();
+                let is_highlighted = self.highlighted_row == Some(row);
+                let line = self.line(row, cx);
+                let highlights = highlights
+                    .into_iter()
+                    .filter(|(range, _)| {
+                        range.end <= line.text.len()
+                            && line.text.is_char_boundary(range.start)
+                            && line.text.is_char_boundary(range.end)
+                    })
+                    .collect::<Vec<_>>();
+
+                h_flex()
+                    .id(row)
+                    .w_full()
+                    .gap_4()
+                    .px_2()
+                    .whitespace_nowrap()
+                    .when(is_highlighted, |this| this.bg(active_line_background))
+                    .child(
+                        div()
+                            .text_color(editor_line_number)
+                            .child(format!("{:>line_number_width$}", row + 1)),
+                    )
+                    .child(StyledText::new(line.text.clone()).with_highlights(highlights))
+                    .when(line.is_truncated, |this| {
+                        this.child(
+                            Label::new(format!("… (line longer than {MAX_LINE_LEN} bytes)"))
+                                .color(Color::Muted),
+                        )
+                    })
+                    .into_any_element()
+            })
+            .collect()
+    }
+}
+
+impl EventEmitter<()> for LargeFileView {}
+impl EventEmitter<SearchEvent> for LargeFileView {}
+
+impl Focusable for LargeFileView {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for LargeFileView {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let item = self.item.read(cx);
+        let line_index = &item.line_index;
+        let (line_count, longest_row) = (line_index.line_count(), line_index.longest_row());
+        let status = if let Some(error) = &item.index_error {
+            format!("Failed to index the file: {error}")
+        } else if item.is_indexed {
+            format!("{line_count} lines")
+        } else {
+            format!(
+                "Indexing lines… {}%",
+                line_index.indexed_len() * 100 / item.len().max(1)
+            )
+        };
+        let settings = ThemeSettings::get_global(cx);
+        let (buffer_font, buffer_font_size) =
+            (settings.buffer_font.clone(), settings.buffer_font_size(cx));
+
+        v_flex()
+            .key_context("LargeFileViewer")
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::toggle_go_to_line))
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .child(
+                h_flex()
+                    .px_2()
+                    .py_1()
+                    .gap_2()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .child(
+                        Icon::new(IconName::Info)
+                            .size(IconSize::Small)
+                            .color(Color::Muted),
+                    )
+                    .child(
+                        Label::new(
+                            "This file is too large to edit, so it is read-only and has no syntax highlighting or language servers.",
+                        )
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                    )
+                    .child(div().flex_1())
+                    .child(
+                        Label::new(status)
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    ),
+            )
+            .child(
+                div()
+                    .flex_1()
+                    .size_full()
+                    .font(buffer_font)
+                    .text_size(buffer_font_size)
+                    .child(
+                        uniform_list(
+                            "large-file-lines",
+                            line_count,
+                            cx.processor(|this, range, window, cx| {
+                                this.render_lines(range, window, cx)
+                            }),
+                        )
+                        .with_horizontal_sizing_behavior(
+                            ListHorizontalSizingBehavior::Unconstrained,
+                        )
+                        .with_width_from_item(Some(longest_row))
+                        .track_scroll(&self.scroll_handle)
+                        .size_full(),
+                    )
+                    .vertical_scrollbar_for(&self.scroll_handle, window, cx),
+            )
+    }
+}
+
+impl Item for LargeFileView {
+    type Event = ();
+
+    fn for_each_project_item(
+        &self,
+        cx: &App,
+        f: &mut dyn FnMut(gpui::EntityId, &dyn project::ProjectItem),
+    ) {
+        f(self.item.entity_id(), self.item.read(cx))
+    }
+
+    fn tab_tooltip_text(&self, cx: &App) -> Option<SharedString> {
+        let abs_path = &self.item.read(cx).abs_path;
+        Some(abs_path.compact().to_string_lossy().into_owned().into())
+    }
+
+    fn tab_content(&self, params: TabContentParams, _window: &Window, cx: &App) -> AnyElement {
+        let project_path = self.item.read(cx).project_path.clone();
+        let label_color = if ItemSettings::get_global(cx).git_status {
+            let git_status = self
+                .project
+                .read(cx)
+                .project_path_git_status(&project_path, cx)
+                .map(|status| status.summary())
+                .unwrap_or_default();
+
+            self.project
+                .read(cx)
+                .entry_for_path(&project_path, cx)
+                .map(|entry| {
+                    entry_git_aware_label_color(git_status, entry.is_ignored, params.selected)
+                })
+                .unwrap_or_else(|| params.text_color())
+        } else {
+            params.text_color()
+        };
+
+        Label::new(self.tab_content_text(params.detail.unwrap_or_default(), cx))
+            .single_line()
+            .color(label_color)
+            .when(params.preview, |this| this.italic())
+            .into_any_element()
+    }
+
+    fn tab_content_text(&self, _: usize, cx: &App) -> SharedString {
+        self.item
+            .read(cx)
+            .project_path
+            .path
+            .file_name()
+            .unwrap_or_default()
+            .to_string()
+            .into()
+    }
+
+    fn tab_icon(&self, _: &Window, cx: &App) -> Option<Icon> {
+        let path = &self.item.read(cx).abs_path;
+        ItemSettings::get_global(cx)
+            .file_icons
+            .then(|| FileIcons::get_icon(path, cx))
+            .flatten()
+            .map(Icon::from_path)
+    }
+
+    fn breadcrumb_location(&self, cx: &App) -> ToolbarItemLocation {
+        if EditorSettings::get_global(cx).toolbar.breadcrumbs {
+            ToolbarItemLocation::PrimaryLeft
+        } else {
+            ToolbarItemLocation::Hidden
+        }
+    }
+
+    fn breadcrumbs(&self, _theme: &Theme, cx: &App) -> Option<Vec<BreadcrumbText>> {
+        let project = self.project.read(cx);
+        let project_path = &self.item.read(cx).project_path;
+        let mut path = project_path.path.clone();
+        if project.visible_worktrees(cx).count() > 1
+            && let Some(worktree) = project.worktree_for_id(project_path.worktree_id, cx)
+        {
+            path = worktree.read(cx).root_name().join(&path);
+        }
+
+        Some(vec![BreadcrumbText {
+            text: path.display(project.path_style(cx)).to_string(),
+            highlights: None,
+            font: Some(ThemeSettings::get_global(cx).buffer_font.clone()),
+        }])
+    }
+
+    fn as_searchable(
+        &self,
+        handle: &Entity<Self>,
+        _: &App,
+    ) -> Option<Box<dyn SearchableItemHandle>> {
+        Some(Box::new(handle.clone()))
+    }
+
+    fn can_split(&self) -> bool {
+        true
+    }
+
+    fn clone_on_split(
+        &self,
+        _workspace_id: Option<WorkspaceId>,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Option<Entity<Self>>>
+    where
+        Self: Sized,
+    {
+        let item = self.item.clone();
+        let project = self.project.clone();
+        Task::ready(Some(cx.new(|cx| Self::new(item, project, cx))))
+    }
+
+    fn buffer_kind(&self, _: &App) -> workspace::item::ItemBufferKind {
+        workspace::item::ItemBufferKind::Singleton
+    }
+}
+
+impl SearchableItem for LargeFileView {
+    type Match = LineMatch;
+
+    fn supported_options(&self) -> SearchOptions {
+        SearchOptions {
+            case: true,
+            word: true,
+            regex: true,
+            replacement: false,
+            selection: false,
+            find_in_results: false,
+        }
+    }
+
+    fn clear_matches(&mut self, _: &mut Window, cx: &mut Context<Self>) {
+        self.matches.clear();
+        self.active_match = None;
+        cx.notify();
+    }
+
+    fn update_matches(
+        &mut self,
+        matches: &[Self::Match],
+        active_match_index: Option<usize>,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.matches = matches.to_vec();
+        self.active_match = active_match_index;
+        cx.notify();
+    }
+
+    fn query_suggestion(&mut self, _: &mut Window, _: &mut Context<Self>) -> String {
+        String::new()
+    }
+
+    fn activate_match(
+        &mut self,
+        index: usize,
+        matches: &[Self::Match],
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.active_match = Some(index);
+        if let Some(active_match) = matches.get(index) {
+            self.go_to_row(active_match.row, cx);
+        }
+    }
+
+    fn select_matches(&mut self, _: &[Self::Match], _: &mut Window, _: &mut Context<Self>) {
+        // Large files have no selections.
+    }
+
+    fn replace(&mut self, _: &Self::Match, _: &SearchQuery, _: &mut Window, _: &mut Context<Self>) {
+        // Large files are read-only.
+    }
+
+    fn find_matches(
+        &mut self,
+        query: Arc<SearchQuery>,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Vec<Self::Match>> {
+        let pattern = if query.is_regex() {
+            query.as_str().to_string()
+        } else {
+            regex::escape(query.as_str())
+        };
+        let pattern = if query.whole_word() {
+            format!(r"\b{pattern}\b")
+        } else {
+            pattern
+        };
+        let regex = match RegexBuilder::new(&pattern)
+            .case_insensitive(!query.case_sensitive())
+            .build()
+        {
+            Ok(regex) => regex,
+            Err(_) => return Task::ready(Vec::new()),
+        };
+
+        let file = self.item.read(cx).file.clone();
+        cx.background_spawn(async move {
+            let mut search = LineSearch::new(regex, MAX_SEARCH_MATCHES);
+            loop {
+                match search.search_block(file.as_ref()) {
+                    Ok(true) => break,
+                    Ok(false) => smol::future::yield_now().await,
+                    Err(error) => {
+                        log::error!("failed to search large file: {error}");
+                        break;
+                    }
+                }
+            }
+            search.into_matches()
+        })
+    }
+
+    fn active_match_index(
+        &mut self,
+        _: Direction,
+        matches: &[Self::Match],
+        _: &mut Window,
+        _: &mut Context<Self>,
+    ) -> Option<usize> {
+        if matches.is_empty() {
+            return None;
+        }
+        // Start from the first match that is visible.
+        let top_row = self.scroll_handle.logical_scroll_top_index();
+        Some(
+            matches
+                .iter()
+                .position(|m| m.row >= top_row)
+                .unwrap_or(matches.len() - 1),
+        )
+    }
+}
+
+impl ProjectItem for LargeFileView {
+    type Item = LargeFileItem;
+
+    fn for_project_item(
+        project: Entity<Project>,
+        _: Option<&Pane>,
+        item: Entity<Self::Item>,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self
+    where
+        Self: Sized,
+    {
+        Self::new(item, project, cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+    use project::FakeFs;
+    use serde_json::json;
+    use util::{path, rel_path::rel_path};
+    use workspace::AppState;
+
+    #[gpui::test]
+    async fn test_search_and_go_to_line(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            AppState::test(cx);
+            super::init(cx);
+        });
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(path!("/dir"), json!({ "a.txt": "" })).await;
+        let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+        let worktree_id = project.read_with(cx, |project, cx| {
+            project.worktrees(cx).next().unwrap().read(cx).id()
+        });
+
+        let text = (0..5000)
+            .map(|ix| {
+                format!(
+                    "line {ix}: {}",
+                    if ix % 1000 == 999 { "Error" } else { "ok" }
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        let item = cx.new(|cx| {
+            LargeFileItem::new(
+                Arc::new(text.into_bytes()),
+                PathBuf::from(path!("/dir/a.txt")),
+                ProjectPath {
+                    worktree_id,
+                    path: rel_path("a.txt").into(),
+                },
+                None,
+                cx,
+            )
+        });
+        cx.run_until_parked();
+        item.read_with(cx, |item, _| {
+            assert!(item.is_indexed());
+            assert_eq!(item.line_index().line_count(), 5000);
+        });
+
+        let (view, cx) = cx.add_window_view(|_, cx| LargeFileView::new(item, project, cx));
+        let query = Arc::new(
+            SearchQuery::text(
+                "error",
+                false,
+                false,
+                false,
+                Default::default(),
+                Default::default(),
+                false,
+                None,
+            )
+            .unwrap(),
+        );
+        let matches = view
+            .update_in(cx, |view, window, cx| view.find_matches(query, window, cx))
+            .await;
+        assert_eq!(
+            matches.iter().map(|m| m.row).collect::<Vec<_>>(),
+            [999, 1999, 2999, 3999, 4999]
+        );
+
+        view.update_in(cx, |view, window, cx| {
+            view.update_matches(&matches, Some(0), window, cx);
+            view.activate_match(2, &matches, window, cx);
+            assert_eq!(view.highlighted_row, Some(2999));
+            assert_eq!(view.line(2999, cx).text, "line 2999: Error");
+        });
+    }
+}
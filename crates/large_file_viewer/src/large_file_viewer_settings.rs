@@ -0,0 +1,22 @@
+use settings::{RegisterSetting, Settings};
+
+/// The settings for the large file viewer.
+#[derive(Clone, Debug, RegisterSetting)]
+pub struct LargeFileViewerSettings {
+    /// The size, in bytes, from which files are opened in the large file viewer.
+    pub threshold: u64,
+}
+
+impl Settings for LargeFileViewerSettings {
+    fn from_settings(content: &settings::SettingsContent) -> Self {
+        let threshold_mb = content
+            .large_file_viewer
+            .clone()
+            .unwrap()
+            .threshold_mb
+            .unwrap();
+        Self {
+            threshold: threshold_mb.saturating_mul(1024 * 1024),
+        }
+    }
+}
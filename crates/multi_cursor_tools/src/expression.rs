@@ -0,0 +1,295 @@
+use anyhow::{Context as _, Result, anyhow};
+use std::{iter::Peekable, str::Chars};
+
+/// An arithmetic expression to evaluate for each selection.
+///
+/// Expressions support numbers, `+`, `-`, `*`, `/`, `%`, `^` (power),
+/// parentheses, the constant `pi`, the functions `abs`, `ceil`, `floor`,
+/// `round`, `sqrt`, `min` and `max`, and the variables `x` (the selection's
+/// contents as a number), `i` (the selection's index, starting at 0) and `n`
+/// (the number of selections).
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expression {
+    Number(f64),
+    Variable(Variable),
+    Negate(Box<Expression>),
+    Binary(Box<Expression>, Operator, Box<Expression>),
+    Call(Function, Vec<Expression>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Variable {
+    X,
+    I,
+    N,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Operator {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Remainder,
+    Power,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Function {
+    Abs,
+    Ceil,
+    Floor,
+    Round,
+    Sqrt,
+    Min,
+    Max,
+}
+
+/// The values of the variables for one selection.
+pub struct Variables {
+    /// The selection's contents as a number, if they are one.
+    pub x: Option<f64>,
+    pub i: f64,
+    pub n: f64,
+}
+
+impl Expression {
+    pub fn parse(source: &str) -> Result<Self> {
+        let mut parser = Parser {
+            chars: source.chars().peekable(),
+        };
+        let expression = parser.parse_sum()?;
+        parser.skip_whitespace();
+        match parser.chars.next() {
+            Some(c) => Err(anyhow!("Unexpected `{c}`")),
+            None => Ok(expression),
+        }
+    }
+
+    pub fn evaluate(&self, variables: &Variables) -> Result<f64> {
+        Ok(match self {
+            Expression::Number(number) => *number,
+            Expression::Variable(Variable::X) => variables.x.context("`x` isn't a number")?,
+            Expression::Variable(Variable::I) => variables.i,
+            Expression::Variable(Variable::N) => variables.n,
+            Expression::Negate(operand) => -operand.evaluate(variables)?,
+            Expression::Binary(left, operator, right) => {
+                let left = left.evaluate(variables)?;
+                let right = right.evaluate(variables)?;
+                match operator {
+                    Operator::Add => left + right,
+                    Operator::Subtract => left - right,
+                    Operator::Multiply => left * right,
+                    Operator::Divide => left / right,
+                    Operator::Remainder => left % right,
+                    Operator::Power => left.powf(right),
+                }
+            }
+            Expression::Call(function, arguments) => {
+                let arguments = arguments
+                    .iter()
+                    .map(|argument| argument.evaluate(variables))
+                    .collect::<Result<Vec<_>>>()?;
+                match function {
+                    Function::Abs => arguments[0].abs(),
+                    Function::Ceil => arguments[0].ceil(),
+                    Function::Floor => arguments[0].floor(),
+                    Function::Round => arguments[0].round(),
+                    Function::Sqrt => arguments[0].sqrt(),
+                    Function::Min => arguments.into_iter().fold(f64::INFINITY, f64::min),
+                    Function::Max => arguments.into_iter().fold(f64::NEG_INFINITY, f64::max),
+                }
+            }
+        })
+    }
+}
+
+/// Formats the result of an expression, without a fractional part if it is a
+/// whole number.
+pub fn format_number(number: f64) -> Result<String> {
+    if !number.is_finite() {
+        return Err(anyhow!("The result isn't a number"));
+    }
+    if number.fract() == 0.0 && number.abs() < 1e15 {
+        Ok(format!("{}", number as i64))
+    } else {
+        Ok(number.to_string())
+    }
+}
+
+struct Parser<'a> {
+    chars: Peekable<Chars<'a>>,
+}
+
+impl Parser<'_> {
+    fn skip_whitespace(&mut self) {
+        while self.chars.next_if(|c| c.is_whitespace()).is_some() {}
+    }
+
+    fn next_if_eq(&mut self, expected: char) -> bool {
+        self.skip_whitespace();
+        self.chars.next_if_eq(&expected).is_some()
+    }
+
+    fn parse_sum(&mut self) -> Result<Expression> {
+        let mut expression = self.parse_product()?;
+        loop {
+            let operator = if self.next_if_eq('+') {
+                Operator::Add
+            } else if self.next_if_eq('-') {
+                Operator::Subtract
+            } else {
+                return Ok(expression);
+            };
+            let right = self.parse_product()?;
+            expression = Expression::Binary(Box::new(expression), operator, Box::new(right));
+        }
+    }
+
+    fn parse_product(&mut self) -> Result<Expression> {
+        let mut expression = self.parse_unary()?;
+        loop {
+            let operator = if self.next_if_eq('*') {
+                Operator::Multiply
+            } else if self.next_if_eq('/') {
+                Operator::Divide
+            } else if self.next_if_eq('%') {
+                Operator::Remainder
+            } else {
+                return Ok(expression);
+            };
+            let right = self.parse_unary()?;
+            expression = Expression::Binary(Box::new(expression), operator, Box::new(right));
+        }
+    }
+
+    fn parse_unary(&mut self) -> Result<Expression> {
+        if self.next_if_eq('-') {
+            Ok(Expression::Negate(Box::new(self.parse_unary()?)))
+        } else if self.next_if_eq('+') {
+            self.parse_unary()
+        } else {
+            self.parse_power()
+        }
+    }
+
+    fn parse_power(&mut self) -> Result<Expression> {
+        let base = self.parse_atom()?;
+        if self.next_if_eq('^') {
+            let exponent = self.parse_unary()?;
+            Ok(Expression::Binary(
+                Box::new(base),
+                Operator::Power,
+                Box::new(exponent),
+            ))
+        } else {
+            Ok(base)
+        }
+    }
+
+    fn parse_atom(&mut self) -> Result<Expression> {
+        self.skip_whitespace();
+        match self.chars.peek().copied() {
+            Some('(') => {
+                self.chars.next();
+                let expression = self.parse_sum()?;
+                if !self.next_if_eq(')') {
+                    return Err(anyhow!("Missing `)`"));
+                }
+                Ok(expression)
+            }
+            Some(c) if c.is_ascii_digit() || c == '.' => {
+                let mut number = String::new();
+                while let Some(c) = self.chars.next_if(|c| c.is_ascii_digit() || *c == '.') {
+                    number.push(c);
+                }
+                let number = number
+                    .parse()
+                    .with_context(|| format!("`{number}` isn't a number"))?;
+                Ok(Expression::Number(number))
+            }
+            Some(c) if c.is_alphabetic() => {
+                let mut name = String::new();
+                while let Some(c) = self.chars.next_if(|c| c.is_alphanumeric()) {
+                    name.push(c);
+                }
+                self.parse_name(&name)
+            }
+            Some(c) => Err(anyhow!("Unexpected `{c}`")),
+            None => Err(anyhow!("Unexpected end of expression")),
+        }
+    }
+
+    fn parse_name(&mut self, name: &str) -> Result<Expression> {
+        let (function, arity) = match name {
+            "x" => return Ok(Expression::Variable(Variable::X)),
+            "i" => return Ok(Expression::Variable(Variable::I)),
+            "n" => return Ok(Expression::Variable(Variable::N)),
+            "pi" => return Ok(Expression::Number(std::f64::consts::PI)),
+            "abs" => (Function::Abs, Some(1)),
+            "ceil" => (Function::Ceil, Some(1)),
+            "floor" => (Function::Floor, Some(1)),
+            "round" => (Function::Round, Some(1)),
+            "sqrt" => (Function::Sqrt, Some(1)),
+            "min" => (Function::Min, None),
+            "max" => (Function::Max, None),
+            _ => return Err(anyhow!("Unknown name `{name}`")),
+        };
+
+        if !self.next_if_eq('(') {
+            return Err(anyhow!("Missing `(` after `{name}`"));
+        }
+        let mut arguments = vec![self.parse_sum()?];
+        while self.next_if_eq(',') {
+            arguments.push(self.parse_sum()?);
+        }
+        if !self.next_if_eq(')') {
+            return Err(anyhow!("Missing `)`"));
+        }
+        if let Some(arity) = arity
+            && arguments.len() != arity
+        {
+            return Err(anyhow!("`{name}` takes {arity} argument"));
+        }
+        Ok(Expression::Call(function, arguments))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn evaluate(source: &str, x: Option<f64>) -> Result<f64> {
+        Expression::parse(source)?.evaluate(&Variables { x, i: 2., n: 5. })
+    }
+
+    #[test]
+    fn test_evaluate() {
+        assert_eq!(evaluate("1 + 2 * 3", None).unwrap(), 7.);
+        assert_eq!(evaluate("(1 + 2) * 3", None).unwrap(), 9.);
+        assert_eq!(evaluate("10 - 4 - 3", None).unwrap(), 3.);
+        assert_eq!(evaluate("2 ^ 3 ^ 2", None).unwrap(), 512.);
+        assert_eq!(evaluate("-2 ^ 2", None).unwrap(), -4.);
+        assert_eq!(evaluate("7 % 4 + .5", None).unwrap(), 3.5);
+        assert_eq!(evaluate("x * 2 + i", Some(1.5)).unwrap(), 5.);
+        assert_eq!(evaluate("n - i", None).unwrap(), 3.);
+        assert_eq!(evaluate("max(x, 1, i)", Some(-1.)).unwrap(), 2.);
+        assert_eq!(evaluate("round(sqrt(x))", Some(10.)).unwrap(), 3.);
+
+        assert!(evaluate("x + 1", None).is_err());
+        assert!(evaluate("1 +", None).is_err());
+        assert!(evaluate("(1 + 2", None).is_err());
+        assert!(evaluate("1 2", None).is_err());
+        assert!(evaluate("y", None).is_err());
+        assert!(evaluate("abs(1, 2)", None).is_err());
+        assert!(evaluate("1..2", None).is_err());
+    }
+
+    #[test]
+    fn test_format_number() {
+        assert_eq!(format_number(3.).unwrap(), "3");
+        assert_eq!(format_number(-0.25).unwrap(), "-0.25");
+        assert!(format_number(1. / 0.).is_err());
+        assert!(format_number(f64::NAN).is_err());
+    }
+}
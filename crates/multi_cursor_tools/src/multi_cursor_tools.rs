@@ -0,0 +1,362 @@
+mod expression;
+mod sequence;
+
+use anyhow::{Result, anyhow, bail};
+use editor::{Anchor, Editor, EditorEvent};
+use expression::{Expression, Variables, format_number};
+use futures::AsyncWriteExt as _;
+use gpui::{
+    App, AsyncWindowContext, DismissEvent, Entity, EventEmitter, FocusHandle, Focusable, Render,
+    Subscription, WeakEntity, actions,
+};
+use project::Project;
+use sequence::Sequence;
+use std::{ops::Range, process::Stdio};
+use ui::prelude::*;
+use util::ResultExt as _;
+use workspace::{ModalView, notifications::DetachAndPromptErr as _};
+
+actions!(
+    multi_cursor_tools,
+    [
+        /// Inserts an incrementing sequence of numbers or letters at each selection.
+        InsertSequence,
+        /// Replaces each selection with the result of an arithmetic expression.
+        EvaluateExpression,
+        /// Replaces each selection with the output of a shell command, which
+        /// gets the selection as its input.
+        PipeThroughCommand
+    ]
+);
+
+pub fn init(cx: &mut App) {
+    cx.observe_new(SelectionTransform::register).detach();
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum TransformKind {
+    Sequence,
+    Expression,
+    Command,
+}
+
+/// Asks for a sequence, expression or shell command, and replaces the contents
+/// of each selection with its result.
+pub struct SelectionTransform {
+    kind: TransformKind,
+    query_editor: Entity<Editor>,
+    active_editor: Entity<Editor>,
+    contents: Vec<(Range<Anchor>, String)>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl ModalView for SelectionTransform {}
+
+impl EventEmitter<DismissEvent> for SelectionTransform {}
+
+impl Focusable for SelectionTransform {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.query_editor.focus_handle(cx)
+    }
+}
+
+impl SelectionTransform {
+    fn register(editor: &mut Editor, _window: Option<&mut Window>, cx: &mut Context<Editor>) {
+        let editor_handle = cx.weak_entity();
+        editor
+            .register_action(move |_: &InsertSequence, window, cx| {
+                Self::toggle(&editor_handle, TransformKind::Sequence, window, cx);
+            })
+            .detach();
+        let editor_handle = cx.weak_entity();
+        editor
+            .register_action(move |_: &EvaluateExpression, window, cx| {
+                Self::toggle(&editor_handle, TransformKind::Expression, window, cx);
+            })
+            .detach();
+        let editor_handle = cx.weak_entity();
+        editor
+            .register_action(move |_: &PipeThroughCommand, window, cx| {
+                Self::toggle(&editor_handle, TransformKind::Command, window, cx);
+            })
+            .detach();
+    }
+
+    fn toggle(editor: &WeakEntity<Editor>, kind: TransformKind, window: &mut Window, cx: &mut App) {
+        let Some(editor) = editor.upgrade() else {
+            return;
+        };
+        let Some(workspace) = editor.read(cx).workspace() else {
+            return;
+        };
+        workspace.update(cx, |workspace, cx| {
+            workspace.toggle_modal(window, cx, move |window, cx| {
+                SelectionTransform::new(kind, editor, window, cx)
+            });
+        })
+    }
+
+    fn new(
+        kind: TransformKind,
+        active_editor: Entity<Editor>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let contents = active_editor.update(cx, |editor, cx| editor.selection_contents(cx));
+        let query_editor = cx.new(|cx| {
+            let mut editor = Editor::single_line(window, cx);
+            let placeholder = match kind {
+                TransformKind::Sequence => "Start[:step] [format], like 1, 001:5 or a ({})",
+                TransformKind::Expression => "Expression of x, i and n, like x * 2 + i",
+                TransformKind::Command => "Shell command, like sort or jq .",
+            };
+            editor.set_placeholder_text(placeholder, window, cx);
+            editor
+        });
+        let subscriptions = vec![
+            cx.subscribe_in(&query_editor, window, |_, _, event, _, cx| match event {
+                EditorEvent::Blurred => cx.emit(DismissEvent),
+                EditorEvent::BufferEdited => cx.notify(),
+                _ => {}
+            }),
+        ];
+        Self {
+            kind,
+            query_editor,
+            active_editor,
+            contents,
+            _subscriptions: subscriptions,
+        }
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, _: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, window: &mut Window, cx: &mut Context<Self>) {
+        let query = self.query_editor.read(cx).text(cx);
+        if self.kind == TransformKind::Command {
+            if query.trim().is_empty() {
+                return;
+            }
+            self.run_command(query, window, cx);
+        } else {
+            let texts = self
+                .contents
+                .iter()
+                .map(|(_, text)| text.as_str())
+                .collect::<Vec<_>>();
+            let Ok(new_texts) = transform_texts(self.kind, &query, &texts) else {
+                return;
+            };
+            let new_contents = self
+                .contents
+                .iter()
+                .map(|(range, _)| range.clone())
+                .zip(new_texts)
+                .collect();
+            self.active_editor.update(cx, |editor, cx| {
+                editor.replace_selection_contents(new_contents, window, cx);
+            });
+        }
+        self.active_editor.focus_handle(cx).focus(window, cx);
+        cx.emit(DismissEvent);
+    }
+
+    fn run_command(&self, command: String, window: &mut Window, cx: &mut Context<Self>) {
+        let editor = self.active_editor.clone();
+        let Some(project) = editor.read(cx).project().cloned() else {
+            return;
+        };
+        let contents = self.contents.clone();
+        window
+            .spawn(cx, async move |cx| {
+                let new_contents = command_outputs(&project, &command, contents, cx).await?;
+                editor.update_in(cx, |editor, window, cx| {
+                    editor.replace_selection_contents(new_contents, window, cx);
+                })
+            })
+            .detach_and_prompt_err("Failed to run the command", window, cx, |_, _, _| None);
+    }
+
+    fn help_text(&self, cx: &App) -> Result<String> {
+        let query = self.query_editor.read(cx).text(cx);
+        let selections = match self.contents.len() {
+            1 => "the selection".to_string(),
+            count => format!("each of {count} selections"),
+        };
+        match self.kind {
+            TransformKind::Command if query.trim().is_empty() => {
+                Ok(format!("Enter a command to run on {selections}"))
+            }
+            TransformKind::Command if self.active_editor.read(cx).project().is_none() => {
+                Err(anyhow!("Commands can only run in projects"))
+            }
+            TransformKind::Command => Ok(format!("Run `{}` on {selections}", query.trim())),
+            TransformKind::Expression if query.trim().is_empty() => {
+                Ok(format!("Enter an expression to evaluate for {selections}"))
+            }
+            kind => {
+                let texts = self
+                    .contents
+                    .iter()
+                    .map(|(_, text)| text.as_str())
+                    .collect::<Vec<_>>();
+                let new_texts = transform_texts(kind, &query, &texts)?;
+                let mut preview = new_texts.iter().take(3).cloned().collect::<Vec<_>>();
+                if new_texts.len() > preview.len() {
+                    preview.push("…".to_string());
+                }
+                let preview = preview.join(", ");
+                Ok(match kind {
+                    TransformKind::Sequence => format!("Insert {preview}"),
+                    _ => format!("Replace with {preview}"),
+                })
+            }
+        }
+    }
+}
+
+/// Returns the new text of each selection for a sequence or expression.
+fn transform_texts(kind: TransformKind, query: &str, texts: &[&str]) -> Result<Vec<String>> {
+    match kind {
+        TransformKind::Sequence => {
+            let spec = if query.trim().is_empty() { "1" } else { query };
+            Sequence::parse(spec)?.items(texts.len())
+        }
+        TransformKind::Expression => {
+            let expression = Expression::parse(query)?;
+            let n = texts.len() as f64;
+            texts
+                .iter()
+                .enumerate()
+                .map(|(i, text)| {
+                    let variables = Variables {
+                        x: text.trim().parse().ok(),
+                        i: i as f64,
+                        n,
+                    };
+                    expression
+                        .evaluate(&variables)
+                        .and_then(format_number)
+                        .map_err(|error| anyhow!("{error} in selection {}", i + 1))
+                })
+                .collect()
+        }
+        TransformKind::Command => Err(anyhow!("Commands run asynchronously")),
+    }
+}
+
+/// Runs the command once for each selection, with the selection's contents as
+/// its input, and returns the outputs.
+async fn command_outputs(
+    project: &Entity<Project>,
+    command: &str,
+    contents: Vec<(Range<Anchor>, String)>,
+    cx: &mut AsyncWindowContext,
+) -> Result<Vec<(Range<Anchor>, String)>> {
+    let mut outputs = Vec::with_capacity(contents.len());
+    for (range, text) in contents {
+        let mut process = project
+            .update(cx, |project, cx| {
+                project.exec_in_shell(command.to_string(), cx)
+            })?
+            .await?;
+        process
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        let mut running = process.spawn()?;
+        if let Some(mut stdin) = running.stdin.take() {
+            let input = text.clone();
+            cx.background_spawn(async move {
+                stdin.write_all(input.as_bytes()).await.log_err();
+            })
+            .detach();
+        }
+
+        let output = cx.background_spawn(running.output()).await?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            bail!(
+                "`{command}` exited with {}: {}",
+                output.status,
+                stderr.trim()
+            );
+        }
+        let mut output = String::from_utf8_lossy(&output.stdout).into_owned();
+        // Commands end their output with a newline, which wasn't part of the
+        // selection unless it ended with one too.
+        if !text.ends_with('\n') && output.ends_with('\n') {
+            output.pop();
+            if output.ends_with('\r') {
+                output.pop();
+            }
+        }
+        outputs.push((range, output));
+    }
+    Ok(outputs)
+}
+
+impl Render for SelectionTransform {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let help_text = match self.help_text(cx) {
+            Ok(help_text) => Label::new(help_text).color(Color::Muted),
+            Err(error) => Label::new(error.to_string()).color(Color::Error),
+        };
+
+        v_flex()
+            .w(rems(34.))
+            .elevation_2(cx)
+            .key_context("SelectionTransform")
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::confirm))
+            .child(
+                div()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .px_2()
+                    .py_1()
+                    .child(self.query_editor.clone()),
+            )
+            .child(
+                h_flex()
+                    .px_2()
+                    .py_1()
+                    .gap_1()
+                    .child(help_text.single_line()),
+            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transform_texts() {
+        let texts = ["3", " 4 ", "five"];
+        assert_eq!(
+            transform_texts(TransformKind::Sequence, "", &texts).unwrap(),
+            ["1", "2", "3"]
+        );
+        assert_eq!(
+            transform_texts(TransformKind::Sequence, "a:2 ({})", &texts).unwrap(),
+            ["(a)", "(c)", "(e)"]
+        );
+        assert_eq!(
+            transform_texts(TransformKind::Expression, "i * 10 + n", &texts).unwrap(),
+            ["3", "13", "23"]
+        );
+        assert_eq!(
+            transform_texts(TransformKind::Expression, "x / 2", &texts[..2]).unwrap(),
+            ["1.5", "2"]
+        );
+        assert_eq!(
+            transform_texts(TransformKind::Expression, "x / 2", &texts)
+                .unwrap_err()
+                .to_string(),
+            "`x` isn't a number in selection 3"
+        );
+    }
+}
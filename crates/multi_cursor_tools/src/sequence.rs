@@ -0,0 +1,199 @@
+use anyhow::{Context as _, Result, anyhow};
+
+/// A sequence of numbers or letters to insert at each selection, parsed from a
+/// spec of the form `<start>[:<step>] [<format>]`.
+///
+/// The start decides the kind of sequence: a decimal number (zero-padded if it
+/// has leading zeros), a hexadecimal number prefixed with `0x`, or lowercase
+/// or uppercase letters, which count like spreadsheet columns. The format is
+/// text in which each `{}` is replaced with the value.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sequence {
+    kind: SequenceKind,
+    start: i64,
+    step: i64,
+    format: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SequenceKind {
+    Decimal { width: usize },
+    Hexadecimal { width: usize, uppercase: bool },
+    Letters { uppercase: bool },
+}
+
+impl Sequence {
+    pub fn parse(spec: &str) -> Result<Self> {
+        let spec = spec.trim_start();
+        let (range, format) = match spec.split_once(char::is_whitespace) {
+            Some((range, format)) => (range, Some(format.trim())),
+            None => (spec, None),
+        };
+        let (start, step) = match range.split_once(':') {
+            Some((start, step)) => (
+                start,
+                step.parse::<i64>()
+                    .with_context(|| format!("`{step}` isn't a step"))?,
+            ),
+            None => (range, 1),
+        };
+        let format = match format {
+            Some(format) if !format.contains("{}") => {
+                return Err(anyhow!("The format must contain `{{}}`"));
+            }
+            format => format.map(ToOwned::to_owned),
+        };
+
+        let (kind, start) = if let Some(digits) = start
+            .strip_prefix("0x")
+            .or_else(|| start.strip_prefix("0X"))
+        {
+            let start = i64::from_str_radix(digits, 16)
+                .with_context(|| format!("`{digits}` isn't a hexadecimal number"))?;
+            let kind = SequenceKind::Hexadecimal {
+                width: digits.len(),
+                uppercase: digits.chars().any(|c| c.is_ascii_uppercase()),
+            };
+            (kind, start)
+        } else if !start.is_empty() && start.chars().all(|c| c.is_ascii_lowercase()) {
+            (
+                SequenceKind::Letters { uppercase: false },
+                letters_to_number(start)?,
+            )
+        } else if !start.is_empty() && start.chars().all(|c| c.is_ascii_uppercase()) {
+            (
+                SequenceKind::Letters { uppercase: true },
+                letters_to_number(start)?,
+            )
+        } else {
+            let digits = start.trim_start_matches(['-', '+']);
+            let width = if digits.len() > 1 && digits.starts_with('0') {
+                digits.len()
+            } else {
+                0
+            };
+            let start = start
+                .parse::<i64>()
+                .with_context(|| format!("`{start}` isn't a number or letter"))?;
+            (SequenceKind::Decimal { width }, start)
+        };
+
+        Ok(Self {
+            kind,
+            start,
+            step,
+            format,
+        })
+    }
+
+    /// Returns the first `count` items of the sequence.
+    pub fn items(&self, count: usize) -> Result<Vec<String>> {
+        (0..count).map(|index| self.item(index)).collect()
+    }
+
+    fn item(&self, index: usize) -> Result<String> {
+        let value = i64::try_from(index)
+            .ok()
+            .and_then(|index| index.checked_mul(self.step))
+            .and_then(|offset| offset.checked_add(self.start))
+            .context("The sequence is too long")?;
+        let value = match self.kind {
+            SequenceKind::Decimal { width } => {
+                let sign = if value < 0 { "-" } else { "" };
+                format!("{sign}{:0width$}", value.unsigned_abs())
+            }
+            SequenceKind::Hexadecimal { width, uppercase } => {
+                if value < 0 {
+                    return Err(anyhow!("The sequence goes below 0x0"));
+                }
+                if uppercase {
+                    format!("0x{value:0width$X}")
+                } else {
+                    format!("0x{value:0width$x}")
+                }
+            }
+            SequenceKind::Letters { uppercase } => {
+                let letters = number_to_letters(value).with_context(|| {
+                    format!(
+                        "The sequence goes below {}",
+                        if uppercase { "A" } else { "a" }
+                    )
+                })?;
+                if uppercase {
+                    letters.to_ascii_uppercase()
+                } else {
+                    letters
+                }
+            }
+        };
+        Ok(match &self.format {
+            Some(format) => format.replace("{}", &value),
+            None => value,
+        })
+    }
+}
+
+/// Converts letters to the number they count to, where `a` is 1, `z` is 26 and
+/// `aa` is 27.
+fn letters_to_number(letters: &str) -> Result<i64> {
+    letters
+        .to_ascii_lowercase()
+        .bytes()
+        .try_fold(0i64, |number, letter| {
+            number
+                .checked_mul(26)?
+                .checked_add((letter - b'a') as i64 + 1)
+        })
+        .with_context(|| format!("`{letters}` is too long"))
+}
+
+fn number_to_letters(mut number: i64) -> Option<String> {
+    if number < 1 {
+        return None;
+    }
+    let mut letters = Vec::new();
+    while number > 0 {
+        number -= 1;
+        letters.push(b'a' + (number % 26) as u8);
+        number /= 26;
+    }
+    letters.reverse();
+    String::from_utf8(letters).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn items(spec: &str, count: usize) -> Vec<String> {
+        Sequence::parse(spec).unwrap().items(count).unwrap()
+    }
+
+    #[test]
+    fn test_number_sequences() {
+        assert_eq!(items("1", 3), ["1", "2", "3"]);
+        assert_eq!(items("10:-5", 3), ["10", "5", "0"]);
+        assert_eq!(items("-1:-1", 2), ["-1", "-2"]);
+        assert_eq!(items("008:2", 3), ["008", "010", "012"]);
+        assert_eq!(items("0x0e", 3), ["0x0e", "0x0f", "0x10"]);
+        assert_eq!(items("0xFE:1", 2), ["0xFE", "0xFF"]);
+        assert_eq!(items("1:2 item_{}", 2), ["item_1", "item_3"]);
+        assert_eq!(items("1 [{}] = {}", 2), ["[1] = 1", "[2] = 2"]);
+
+        assert!(Sequence::parse("1:x").is_err());
+        assert!(Sequence::parse("1.5").is_err());
+        assert!(Sequence::parse("1 item").is_err());
+        assert!(Sequence::parse("0x1:-1").unwrap().items(3).is_err());
+    }
+
+    #[test]
+    fn test_letter_sequences() {
+        assert_eq!(items("a", 3), ["a", "b", "c"]);
+        assert_eq!(items("Y", 4), ["Y", "Z", "AA", "AB"]);
+        assert_eq!(items("az:-1", 2), ["az", "ay"]);
+        assert_eq!(items("a:2 ({})", 2), ["(a)", "(c)"]);
+
+        assert!(Sequence::parse("b:-1").unwrap().items(3).is_err());
+        assert!(Sequence::parse("aB").is_err());
+    }
+}
@@ -0,0 +1,352 @@
+//! Bookmarks are positions in the files of a project that the user wants to
+//! get back to. They are anchored to the contents of their buffers, so that
+//! they follow the lines they were set on as the buffers are edited.
+
+use std::{ops::Range, sync::Arc};
+
+use anyhow::Result;
+use collections::{BTreeMap, HashSet};
+use gpui::{App, Context, Entity, EventEmitter, SharedString, Subscription, Task};
+use language::{Buffer, BufferEvent, BufferSnapshot, DiskState};
+use text::Point;
+use util::rel_path::RelPath;
+use worktree::WorktreeId;
+
+use crate::{ProjectItem as _, ProjectPath, buffer_store::BufferStore};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Bookmark {
+    /// The start of the line the bookmark is on.
+    pub position: text::Anchor,
+    pub label: Option<SharedString>,
+}
+
+/// A bookmark along with the file it is in.
+#[derive(Clone, Debug)]
+pub struct BookmarkEntry {
+    pub path: ProjectPath,
+    pub buffer: Entity<Buffer>,
+    pub row: u32,
+    pub bookmark: Bookmark,
+}
+
+/// A bookmark of a worktree as it is persisted, by row rather than anchor.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SerializedBookmark {
+    pub path: Arc<RelPath>,
+    pub row: u32,
+    pub label: Option<String>,
+}
+
+struct BookmarksInFile {
+    buffer: Entity<Buffer>,
+    /// Sorted by position.
+    bookmarks: Vec<Bookmark>,
+    _subscription: Subscription,
+}
+
+pub enum BookmarkStoreEvent {
+    /// Bookmarks were added, removed or relabeled, or a file with bookmarks
+    /// was saved, which may have moved them to other rows.
+    BookmarksChanged,
+}
+
+impl EventEmitter<BookmarkStoreEvent> for BookmarkStore {}
+
+pub struct BookmarkStore {
+    buffer_store: Entity<BufferStore>,
+    bookmarks: BTreeMap<ProjectPath, BookmarksInFile>,
+    /// The worktrees whose persisted bookmarks are still being loaded.
+    loading_worktrees: HashSet<WorktreeId>,
+}
+
+impl BookmarkStore {
+    pub fn new(buffer_store: Entity<BufferStore>) -> Self {
+        Self {
+            buffer_store,
+            bookmarks: BTreeMap::new(),
+            loading_worktrees: HashSet::default(),
+        }
+    }
+
+    fn bookmarks_in_file(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) -> Option<&mut BookmarksInFile> {
+        let path = buffer.read(cx).project_path(cx)?;
+        Some(
+            self.bookmarks
+                .entry(path)
+                .or_insert_with(|| BookmarksInFile {
+                    buffer: buffer.clone(),
+                    bookmarks: Vec::new(),
+                    _subscription: cx.subscribe(buffer, Self::on_buffer_event),
+                }),
+        )
+    }
+
+    fn on_buffer_event(
+        &mut self,
+        buffer: Entity<Buffer>,
+        event: &BufferEvent,
+        cx: &mut Context<Self>,
+    ) {
+        match event {
+            BufferEvent::Saved => cx.emit(BookmarkStoreEvent::BookmarksChanged),
+            BufferEvent::FileHandleChanged => {
+                let Some(old_path) = self
+                    .bookmarks
+                    .iter()
+                    .find(|(_, in_file)| in_file.buffer == buffer)
+                    .map(|(path, _)| path.clone())
+                else {
+                    return;
+                };
+                let new_path = buffer
+                    .read(cx)
+                    .file()
+                    .filter(|file| file.disk_state() != DiskState::Deleted)
+                    .and_then(|_| buffer.read(cx).project_path(cx));
+                if new_path.as_ref() == Some(&old_path) {
+                    return;
+                }
+                if let Some(in_file) = self.bookmarks.remove(&old_path)
+                    && let Some(new_path) = new_path
+                {
+                    self.bookmarks.insert(new_path, in_file);
+                }
+                cx.emit(BookmarkStoreEvent::BookmarksChanged);
+                cx.notify();
+            }
+            _ => {}
+        }
+    }
+
+    /// Removes the bookmark on the line of `position`, or adds one there if
+    /// there is none.
+    pub fn toggle_bookmark(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        position: text::Anchor,
+        cx: &mut Context<Self>,
+    ) {
+        let snapshot = buffer.read(cx).snapshot();
+        let row = snapshot.summary_for_anchor::<Point>(&position).row;
+        let Some(in_file) = self.bookmarks_in_file(buffer, cx) else {
+            return;
+        };
+        if let Some(ix) = bookmark_ix_at_row(&in_file.bookmarks, row, &snapshot) {
+            in_file.bookmarks.remove(ix);
+        } else {
+            insert_bookmark(&mut in_file.bookmarks, row, None, &snapshot);
+        }
+        self.remove_empty_files();
+        cx.emit(BookmarkStoreEvent::BookmarksChanged);
+        cx.notify();
+    }
+
+    /// Sets the label of the bookmark on the line of `position`, adding a
+    /// bookmark there if there is none.
+    pub fn set_bookmark_label(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        position: text::Anchor,
+        label: Option<SharedString>,
+        cx: &mut Context<Self>,
+    ) {
+        let snapshot = buffer.read(cx).snapshot();
+        let row = snapshot.summary_for_anchor::<Point>(&position).row;
+        let label = label.filter(|label| !label.trim().is_empty());
+        let Some(in_file) = self.bookmarks_in_file(buffer, cx) else {
+            return;
+        };
+        if let Some(ix) = bookmark_ix_at_row(&in_file.bookmarks, row, &snapshot) {
+            in_file.bookmarks[ix].label = label;
+        } else {
+            insert_bookmark(&mut in_file.bookmarks, row, label, &snapshot);
+        }
+        cx.emit(BookmarkStoreEvent::BookmarksChanged);
+        cx.notify();
+    }
+
+    pub fn remove_bookmark(
+        &mut self,
+        path: &ProjectPath,
+        position: text::Anchor,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(in_file) = self.bookmarks.get_mut(path) else {
+            return;
+        };
+        in_file
+            .bookmarks
+            .retain(|bookmark| bookmark.position != position);
+        self.remove_empty_files();
+        cx.emit(BookmarkStoreEvent::BookmarksChanged);
+        cx.notify();
+    }
+
+    pub fn clear_bookmarks(&mut self, cx: &mut Context<Self>) {
+        self.bookmarks.clear();
+        cx.emit(BookmarkStoreEvent::BookmarksChanged);
+        cx.notify();
+    }
+
+    fn remove_empty_files(&mut self) {
+        self.bookmarks
+            .retain(|_, in_file| !in_file.bookmarks.is_empty());
+    }
+
+    /// Returns the bookmarks of `buffer` within `range`.
+    pub fn bookmarks<'a>(
+        &'a self,
+        buffer: &Entity<Buffer>,
+        range: Range<text::Anchor>,
+        snapshot: &'a BufferSnapshot,
+        cx: &App,
+    ) -> impl Iterator<Item = &'a Bookmark> + 'a {
+        buffer
+            .read(cx)
+            .project_path(cx)
+            .and_then(|path| self.bookmarks.get(&path))
+            .filter(|in_file| in_file.buffer == *buffer)
+            .into_iter()
+            .flat_map(|in_file| in_file.bookmarks.iter())
+            .filter(move |bookmark| {
+                bookmark.position.cmp(&range.start, snapshot).is_ge()
+                    && bookmark.position.cmp(&range.end, snapshot).is_le()
+            })
+    }
+
+    pub fn bookmark_at_row(
+        &self,
+        buffer: &Entity<Buffer>,
+        row: u32,
+        cx: &App,
+    ) -> Option<&Bookmark> {
+        let in_file = self.bookmarks.get(&buffer.read(cx).project_path(cx)?)?;
+        let snapshot = buffer.read(cx).snapshot();
+        let ix = bookmark_ix_at_row(&in_file.bookmarks, row, &snapshot)?;
+        in_file.bookmarks.get(ix)
+    }
+
+    /// Returns all bookmarks of the project, ordered by path and row.
+    pub fn all_bookmarks(&self, cx: &App) -> Vec<BookmarkEntry> {
+        self.bookmarks
+            .iter()
+            .flat_map(|(path, in_file)| {
+                let snapshot = in_file.buffer.read(cx).snapshot();
+                in_file.bookmarks.iter().map(move |bookmark| BookmarkEntry {
+                    path: path.clone(),
+                    buffer: in_file.buffer.clone(),
+                    row: snapshot.summary_for_anchor::<Point>(&bookmark.position).row,
+                    bookmark: bookmark.clone(),
+                })
+            })
+            .collect()
+    }
+
+    pub fn serialized_bookmarks(
+        &self,
+        worktree_id: WorktreeId,
+        cx: &App,
+    ) -> Vec<SerializedBookmark> {
+        self.all_bookmarks(cx)
+            .into_iter()
+            .filter(|entry| entry.path.worktree_id == worktree_id)
+            .map(|entry| SerializedBookmark {
+                path: entry.path.path,
+                row: entry.row,
+                label: entry.bookmark.label.map(|label| label.to_string()),
+            })
+            .collect()
+    }
+
+    /// Adds persisted bookmarks of a worktree, opening the buffers they are in.
+    pub fn load_serialized_bookmarks(
+        &mut self,
+        worktree_id: WorktreeId,
+        bookmarks: Vec<SerializedBookmark>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let mut bookmarks_by_path = BTreeMap::<Arc<RelPath>, Vec<SerializedBookmark>>::new();
+        for bookmark in bookmarks {
+            bookmarks_by_path
+                .entry(bookmark.path.clone())
+                .or_default()
+                .push(bookmark);
+        }
+
+        self.loading_worktrees.insert(worktree_id);
+        let buffer_store = self.buffer_store.clone();
+        cx.spawn(async move |this, cx| {
+            for (path, bookmarks) in bookmarks_by_path {
+                let buffer = buffer_store
+                    .update(cx, |buffer_store, cx| {
+                        buffer_store.open_buffer(ProjectPath { worktree_id, path }, cx)
+                    })?
+                    .await;
+                let Ok(buffer) = buffer else {
+                    log::debug!("skipping the bookmarks of a file that could not be opened");
+                    continue;
+                };
+                this.update(cx, |this, cx| {
+                    let snapshot = buffer.read(cx).snapshot();
+                    let max_row = snapshot.max_point().row;
+                    let Some(in_file) = this.bookmarks_in_file(&buffer, cx) else {
+                        return;
+                    };
+                    for bookmark in bookmarks {
+                        if bookmark.row > max_row
+                            || bookmark_ix_at_row(&in_file.bookmarks, bookmark.row, &snapshot)
+                                .is_some()
+                        {
+                            continue;
+                        }
+                        insert_bookmark(
+                            &mut in_file.bookmarks,
+                            bookmark.row,
+                            bookmark.label.map(SharedString::from),
+                            &snapshot,
+                        );
+                    }
+                    this.remove_empty_files();
+                    cx.notify();
+                })?;
+            }
+            this.update(cx, |this, _| {
+                this.loading_worktrees.remove(&worktree_id);
+            })
+        })
+    }
+
+    /// Whether the persisted bookmarks of the worktree are still being
+    /// loaded, so that they should not be overwritten yet.
+    pub fn is_loading(&self, worktree_id: WorktreeId) -> bool {
+        self.loading_worktrees.contains(&worktree_id)
+    }
+}
+
+fn bookmark_ix_at_row(
+    bookmarks: &[Bookmark],
+    row: u32,
+    snapshot: &BufferSnapshot,
+) -> Option<usize> {
+    bookmarks
+        .iter()
+        .position(|bookmark| snapshot.summary_for_anchor::<Point>(&bookmark.position).row == row)
+}
+
+fn insert_bookmark(
+    bookmarks: &mut Vec<Bookmark>,
+    row: u32,
+    label: Option<SharedString>,
+    snapshot: &BufferSnapshot,
+) {
+    let position = snapshot.anchor_after(Point::new(row, 0));
+    let ix = bookmarks
+        .binary_search_by(|bookmark| bookmark.position.cmp(&position, snapshot))
+        .unwrap_or_else(|ix| ix);
+    bookmarks.insert(ix, Bookmark { position, label });
+}
@@ -6,6 +6,7 @@ use crate::{
 use anyhow::{Context as _, Result, anyhow};
 use client::Client;
 use collections::{HashMap, HashSet, hash_map};
+use fs::MTime;
 use futures::{Future, FutureExt as _, channel::oneshot, future::Shared};
 use gpui::{
     App, AppContext as _, AsyncApp, Context, Entity, EventEmitter, Subscription, Task, WeakEntity,
@@ -18,7 +19,7 @@ use language::{
     },
 };
 use rpc::{
-    AnyProtoClient, ErrorCode, ErrorExt as _, TypedEnvelope,
+    AnyProtoClient, ErrorCode, ErrorCodeExt as _, ErrorExt as _, TypedEnvelope,
     proto::{self},
 };
 
@@ -59,6 +60,31 @@ struct RemoteBufferStore {
     remote_buffer_listeners:
         HashMap<BufferId, Vec<oneshot::Sender<anyhow::Result<Entity<Buffer>>>>>,
     worktree_store: Entity<WorktreeStore>,
+    offline: bool,
+    queued_saves: HashMap<BufferId, QueuedSave>,
+    sync_states: HashMap<BufferId, RemoteBufferSyncState>,
+}
+
+/// A save requested while the connection to the remote host was offline.
+struct QueuedSave {
+    buffer: Entity<Buffer>,
+    /// The file's disk state and the buffer's saved version when the save was
+    /// first queued, used to detect changes made on the remote host meanwhile.
+    disk_state: Option<DiskState>,
+    saved_version: clock::Global,
+}
+
+/// The sync state of a remote buffer that was edited while the connection to
+/// the remote host was offline.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RemoteBufferSyncState {
+    /// The buffer has edits that were made while offline.
+    Unsynced,
+    /// The buffer was saved while offline and will be written back on reconnect.
+    SaveQueued,
+    /// The file changed on the remote host while offline, so the queued save
+    /// was not written back.
+    Conflict,
 }
 
 struct LocalBufferStore {
@@ -126,6 +152,7 @@ impl RemoteBufferStore {
         &self,
         buffer_handle: Entity<Buffer>,
         new_path: Option<proto::ProjectPath>,
+        expected_mtime: Option<MTime>,
         cx: &Context<BufferStore>,
     ) -> Task<Result<()>> {
         let buffer = buffer_handle.read(cx);
@@ -140,6 +167,7 @@ impl RemoteBufferStore {
                     buffer_id,
                     new_path,
                     version: serialize_version(&version),
+                    expected_mtime: expected_mtime.map(|mtime| mtime.into()),
                 })
                 .await?;
             let version = deserialize_version(&response.version);
@@ -773,6 +801,9 @@ impl BufferStore {
                 project_id: remote_id,
                 upstream_client,
                 worktree_store: worktree_store.clone(),
+                offline: false,
+                queued_saves: Default::default(),
+                sync_states: Default::default(),
             }),
             downstream_client: None,
             opened_buffers: Default::default(),
@@ -888,7 +919,30 @@ impl BufferStore {
     ) -> Task<Result<()>> {
         match &mut self.state {
             BufferStoreState::Local(this) => this.save_buffer(buffer, cx),
-            BufferStoreState::Remote(this) => this.save_remote_buffer(buffer, None, cx),
+            BufferStoreState::Remote(this) if this.offline => {
+                let buffer_id = buffer.read(cx).remote_id();
+                // Keep the state of the first queued save, so that changes made on
+                // the remote host since then are still detected on reconnect.
+                if let hash_map::Entry::Vacant(entry) = this.queued_saves.entry(buffer_id) {
+                    let (disk_state, saved_version) = {
+                        let buffer = buffer.read(cx);
+                        (
+                            buffer.file().map(|file| file.disk_state()),
+                            buffer.saved_version().clone(),
+                        )
+                    };
+                    entry.insert(QueuedSave {
+                        buffer,
+                        disk_state,
+                        saved_version,
+                    });
+                }
+                this.sync_states
+                    .insert(buffer_id, RemoteBufferSyncState::SaveQueued);
+                cx.notify();
+                Task::ready(Ok(()))
+            }
+            BufferStoreState::Remote(this) => this.save_remote_buffer(buffer, None, None, cx),
         }
     }
 
@@ -901,8 +955,11 @@ impl BufferStore {
         let old_file = buffer.read(cx).file().cloned();
         let task = match &self.state {
             BufferStoreState::Local(this) => this.save_buffer_as(buffer.clone(), path, cx),
+            BufferStoreState::Remote(this) if this.offline => {
+                Task::ready(Err(anyhow!("cannot save as a new file while offline")))
+            }
             BufferStoreState::Remote(this) => {
-                this.save_remote_buffer(buffer.clone(), Some(path.to_proto()), cx)
+                this.save_remote_buffer(buffer.clone(), Some(path.to_proto()), None, cx)
             }
         };
         cx.spawn(async move |this, cx| {
@@ -936,7 +993,10 @@ impl BufferStore {
         buffer_entity.update(cx, move |_, cx| {
             cx.on_release(move |buffer, cx| {
                 handle
-                    .update(cx, |_, cx| {
+                    .update(cx, |this, cx| {
+                        if let Some(remote) = this.as_remote_mut() {
+                            remote.sync_states.remove(&buffer.remote_id());
+                        }
                         cx.emit(BufferStoreEvent::BufferDropped(buffer.remote_id()))
                     })
                     .ok();
@@ -1061,10 +1121,109 @@ impl BufferStore {
         if let Some(remote) = self.as_remote_mut() {
             // Wake up all futures currently waiting on a buffer to get opened,
             // to give them a chance to fail now that we've disconnected.
-            remote.remote_buffer_listeners.clear()
+            remote.remote_buffer_listeners.clear();
+
+            // Saves queued while offline can no longer be written back.
+            remote.offline = false;
+            for buffer_id in remote.queued_saves.drain().map(|(buffer_id, _)| buffer_id) {
+                remote
+                    .sync_states
+                    .insert(buffer_id, RemoteBufferSyncState::Unsynced);
+            }
         }
     }
 
+    /// Whether the connection to the remote host is offline, with edits kept locally.
+    pub fn is_remote_offline(&self) -> bool {
+        self.as_remote().is_some_and(|remote| remote.offline)
+    }
+
+    pub fn remote_sync_state(&self, buffer_id: BufferId) -> Option<RemoteBufferSyncState> {
+        self.as_remote()?.sync_states.get(&buffer_id).copied()
+    }
+
+    /// Marks the connection to the remote host as offline or back online.
+    ///
+    /// While offline, saves are queued instead of being sent to the remote host.
+    /// When coming back online, each queued save is written back, unless the file
+    /// changed on the remote host in the meantime, in which case the buffer is
+    /// marked as conflicted. Since updates from the host may not have arrived yet,
+    /// the host rejects a replayed save if the file's modification time no longer
+    /// matches the one the save was queued against.
+    pub fn set_remote_offline(&mut self, offline: bool, cx: &mut Context<Self>) {
+        let Some(remote) = self.as_remote_mut() else {
+            return;
+        };
+        if remote.offline == offline {
+            return;
+        }
+        remote.offline = offline;
+        cx.notify();
+        if offline {
+            return;
+        }
+
+        // Edits made while offline were replayed by the connection on reconnect.
+        remote
+            .sync_states
+            .retain(|_, state| *state != RemoteBufferSyncState::Unsynced);
+
+        for (buffer_id, queued_save) in remote.queued_saves.drain().collect::<Vec<_>>() {
+            let buffer = queued_save.buffer.read(cx);
+            let changed_remotely = buffer.has_conflict()
+                || buffer.file().map(|file| file.disk_state()) != queued_save.disk_state
+                || *buffer.saved_version() != queued_save.saved_version;
+            if changed_remotely {
+                Self::mark_save_conflict(remote, &queued_save.buffer, cx);
+                continue;
+            }
+
+            let expected_mtime = queued_save.disk_state.and_then(|state| state.mtime());
+            let save =
+                remote.save_remote_buffer(queued_save.buffer.clone(), None, expected_mtime, cx);
+            cx.spawn(async move |this, cx| {
+                let result = save.await;
+                this.update(cx, |this, cx| {
+                    if let Some(remote) = this.as_remote_mut() {
+                        match &result {
+                            Ok(()) => {
+                                remote.sync_states.remove(&buffer_id);
+                            }
+                            Err(error) if error.error_code() == ErrorCode::SaveConflict => {
+                                Self::mark_save_conflict(remote, &queued_save.buffer, cx);
+                            }
+                            Err(_) => {
+                                remote
+                                    .sync_states
+                                    .insert(buffer_id, RemoteBufferSyncState::Unsynced);
+                            }
+                        }
+                        cx.notify();
+                    }
+                })?;
+                match result {
+                    Err(error) if error.error_code() == ErrorCode::SaveConflict => Ok(()),
+                    result => result
+                        .with_context(|| format!("replaying queued save for buffer {buffer_id}")),
+                }
+            })
+            .detach_and_log_err(cx);
+        }
+    }
+
+    fn mark_save_conflict(
+        remote: &mut RemoteBufferStore,
+        buffer: &Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) {
+        let buffer_id = buffer.read(cx).remote_id();
+        log::warn!("not replaying queued save for buffer {buffer_id}: file changed remotely");
+        buffer.update(cx, |buffer, _| buffer.set_conflict());
+        remote
+            .sync_states
+            .insert(buffer_id, RemoteBufferSyncState::Conflict);
+    }
+
     pub fn shared(&mut self, remote_id: u64, downstream_client: AnyProtoClient, _cx: &mut App) {
         self.downstream_client = Some((downstream_client, remote_id));
     }
@@ -1119,6 +1278,14 @@ impl BufferStore {
                 self.buffer_changed_file(buffer, cx);
             }
             BufferEvent::Reloaded => {
+                if let Some(remote) = self.as_remote_mut()
+                    && remote
+                        .sync_states
+                        .remove(&buffer.read(cx).remote_id())
+                        .is_some()
+                {
+                    cx.notify();
+                }
                 let Some((downstream_client, project_id)) = self.downstream_client.as_ref() else {
                     return;
                 };
@@ -1133,6 +1300,27 @@ impl BufferStore {
                     })
                     .log_err();
             }
+            BufferEvent::Edited => {
+                if let Some(remote) = self.as_remote_mut()
+                    && remote.offline
+                {
+                    let buffer_id = buffer.read(cx).remote_id();
+                    if let hash_map::Entry::Vacant(entry) = remote.sync_states.entry(buffer_id) {
+                        entry.insert(RemoteBufferSyncState::Unsynced);
+                        cx.notify();
+                    }
+                }
+            }
+            BufferEvent::Saved => {
+                if let Some(remote) = self.as_remote_mut()
+                    && remote
+                        .sync_states
+                        .remove(&buffer.read(cx).remote_id())
+                        .is_some()
+                {
+                    cx.notify();
+                }
+            }
             BufferEvent::LanguageChanged(_) => {}
             _ => {}
         }
@@ -1359,6 +1547,22 @@ impl BufferStore {
             .await?;
         let buffer_id = buffer.read_with(&cx, |buffer, _| buffer.remote_id())?;
 
+        if let Some(expected_mtime) = envelope.payload.expected_mtime {
+            let (fs, abs_path) = buffer
+                .read_with(&cx, |buffer, cx| {
+                    let file = File::from_dyn(buffer.file())?;
+                    let worktree = file.worktree.read(cx).as_local()?;
+                    Some((worktree.fs().clone(), worktree.absolutize(&file.path)))
+                })?
+                .context("buffer is not backed by a local file")?;
+            let mtime = fs.metadata(&abs_path).await?.map(|metadata| metadata.mtime);
+            if mtime != Some(expected_mtime.into()) {
+                return Err(ErrorCode::SaveConflict
+                    .message(format!("{abs_path:?} changed on disk"))
+                    .anyhow());
+            }
+        }
+
         if let Some(new_path) = envelope.payload.new_path
             && let Some(new_path) = ProjectPath::from_proto(new_path)
         {
@@ -111,6 +111,9 @@ struct BufferGitState {
     unstaged_diff: Option<WeakEntity<BufferDiff>>,
     uncommitted_diff: Option<WeakEntity<BufferDiff>>,
     conflict_set: Option<WeakEntity<ConflictSet>>,
+    /// Whether the buffer holds the result of a three-way merge, whose
+    /// conflict markers are parsed regardless of the repository status.
+    is_merge_result: bool,
     recalculate_diff_task: Option<Task<Result<()>>>,
     reparse_conflict_markers_task: Option<Task<Result<()>>>,
     language: Option<Arc<Language>>,
@@ -919,6 +922,29 @@ impl GitStore {
         conflict_set
     }
 
+    /// Opens the conflict set of a buffer holding the result of a three-way
+    /// merge, whose conflict markers are parsed even if the repository doesn't
+    /// report the file as unmerged.
+    pub fn open_merge_conflict_set(
+        &mut self,
+        buffer: Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) -> Entity<ConflictSet> {
+        let conflict_set = self.open_conflict_set(buffer.clone(), cx);
+        if let Some(git_state) = self.diffs.get(&buffer.read(cx).remote_id()) {
+            git_state.update(cx, |state, cx| {
+                state.is_merge_result = true;
+                let conflict_status_changed = conflict_set.update(cx, |conflict_set, cx| {
+                    conflict_set.set_has_conflict(true, cx)
+                });
+                if conflict_status_changed {
+                    let _ = state.reparse_conflict_markers(buffer.read(cx).text_snapshot(), cx);
+                }
+            });
+        }
+        conflict_set
+    }
+
     pub fn project_path_git_status(
         &self,
         project_path: &ProjectPath,
@@ -1316,9 +1342,11 @@ impl GitStore {
             {
                 diff.update(cx, |diff, cx| {
                     if let Some(conflict_set) = &diff.conflict_set {
+                        let is_merge_result = diff.is_merge_result;
                         let conflict_status_changed =
                             conflict_set.update(cx, |conflict_set, cx| {
-                                let has_conflict = repo_snapshot.has_conflict(&repo_path);
+                                let has_conflict =
+                                    is_merge_result || repo_snapshot.has_conflict(&repo_path);
                                 conflict_set.set_has_conflict(has_conflict, cx)
                             })?;
                         if conflict_status_changed {
@@ -2904,6 +2932,7 @@ impl BufferGitState {
             language_changed: Default::default(),
             conflict_updated_futures: Default::default(),
             conflict_set: Default::default(),
+            is_merge_result: false,
             reparse_conflict_markers_task: Default::default(),
         }
     }
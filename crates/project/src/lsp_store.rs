@@ -16,14 +16,15 @@ pub mod lsp_ext_command;
 pub mod rust_analyzer_ext;
 pub mod vue_language_server_ext;
 
+pub mod extension;
 mod inlay_hint_cache;
 
 use self::inlay_hint_cache::BufferInlayHints;
 use crate::{
     CodeAction, ColorPresentation, Completion, CompletionDisplayOptions, CompletionResponse,
-    CompletionSource, CoreCompletion, DocumentColor, Hover, InlayHint, InlayId, LocationLink,
-    LspAction, LspPullDiagnostics, ManifestProvidersStore, Project, ProjectItem, ProjectPath,
-    ProjectTransaction, PulledDiagnostics, ResolveState, Symbol,
+    CompletionSource, CoreCompletion, DocumentColor, DocumentLink, Hover, InlayHint, InlayId,
+    LocationLink, LspAction, LspPullDiagnostics, ManifestProvidersStore, Project, ProjectItem,
+    ProjectPath, ProjectTransaction, PulledDiagnostics, ResolveState, Symbol,
     buffer_store::{BufferStore, BufferStoreEvent},
     environment::ProjectEnvironment,
     lsp_command::{self, *},
@@ -1644,6 +1645,38 @@ impl LocalLspStore {
                         },
                     )?;
                 }
+                Formatter::Extension(formatter_name) => {
+                    let logger = zlog::scoped!(logger => "extension");
+                    zlog::trace!(logger => "formatting");
+                    let _timer = zlog::time!(logger => "Formatting buffer via extension");
+
+                    let edits =
+                        extension::format_buffer(formatter_name, &buffer.handle, &settings, cx)
+                            .await
+                            .with_context(|| {
+                                format!(
+                                    "Failed to format buffer via extension formatter: {}",
+                                    formatter_name
+                                )
+                            })?;
+                    let Some(edits) = edits else {
+                        zlog::warn!(logger => "Formatter {} does not support the buffer's language. Skipping", formatter_name);
+                        continue;
+                    };
+                    if edits.is_empty() {
+                        zlog::trace!(logger => "No changes");
+                        continue;
+                    }
+
+                    extend_formatting_transaction(
+                        buffer,
+                        formatting_transaction_id,
+                        cx,
+                        |buffer, cx| {
+                            buffer.edit(edits, None, cx);
+                        },
+                    )?;
+                }
                 Formatter::LanguageServer(specifier) => {
                     let logger = zlog::scoped!(logger => "language-server");
                     zlog::trace!(logger => "formatting");
@@ -2311,7 +2344,7 @@ impl LocalLspStore {
                         .into_response()?;
                 }
             }
-            LspAction::Command(_) => {}
+            LspAction::Command(_) | LspAction::Extension(_) => {}
         }
 
         action.resolved = true;
@@ -3915,6 +3948,7 @@ impl LspStore {
         client.add_entity_request_handler(Self::handle_apply_code_action);
         client.add_entity_request_handler(Self::handle_get_project_symbols);
         client.add_entity_request_handler(Self::handle_resolve_inlay_hint);
+        client.add_entity_request_handler(Self::handle_resolve_document_link);
         client.add_entity_request_handler(Self::handle_get_color_presentation);
         client.add_entity_request_handler(Self::handle_open_buffer_for_symbol);
         client.add_entity_request_handler(Self::handle_refresh_inlay_hints);
@@ -3930,6 +3964,8 @@ impl LspStore {
         client.add_entity_request_handler(Self::handle_lsp_command::<PrepareRename>);
         client.add_entity_request_handler(Self::handle_lsp_command::<PerformRename>);
         client.add_entity_request_handler(Self::handle_lsp_command::<LinkedEditingRange>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<GetSelectionRanges>);
+        client.add_entity_request_handler(Self::handle_lsp_command::<GetDocumentLinks>);
 
         client.add_entity_request_handler(Self::handle_lsp_ext_cancel_flycheck);
         client.add_entity_request_handler(Self::handle_lsp_ext_run_flycheck);
@@ -5036,6 +5072,16 @@ impl LspStore {
         push_to_history: bool,
         cx: &mut Context<Self>,
     ) -> Task<Result<ProjectTransaction>> {
+        if let LspAction::Extension(extension_action) = &action.lsp_action {
+            return extension::apply_code_action(
+                buffer_handle,
+                extension_action.clone(),
+                action.range,
+                push_to_history,
+                cx,
+            );
+        }
+
         if let Some((upstream_client, project_id)) = self.upstream_client() {
             let request = proto::ApplyCodeAction {
                 project_id,
@@ -5057,8 +5103,11 @@ impl LspStore {
                     .await
             })
         } else if self.mode.is_local() {
+            let Some(server_id) = action.server_id else {
+                return Task::ready(Ok(ProjectTransaction::default()));
+            };
             let Some((_, lang_server)) = buffer_handle.update(cx, |buffer, cx| {
-                self.language_server_for_local_buffer(buffer, action.server_id, cx)
+                self.language_server_for_local_buffer(buffer, server_id, cx)
                     .map(|(adapter, server)| (adapter.clone(), server.clone()))
             }) else {
                 return Task::ready(Ok(ProjectTransaction::default()));
@@ -5303,6 +5352,66 @@ impl LspStore {
         }
     }
 
+    pub fn resolve_document_link(
+        &self,
+        link: DocumentLink,
+        buffer: Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<DocumentLink>> {
+        if link.target().is_some() {
+            return Task::ready(Ok(link));
+        }
+        let resolve =
+            self.resolve_lsp_document_link(link.lsp_link.clone(), buffer, link.server_id, cx);
+        cx.background_spawn(async move {
+            Ok(DocumentLink {
+                lsp_link: resolve.await?,
+                ..link
+            })
+        })
+    }
+
+    fn resolve_lsp_document_link(
+        &self,
+        lsp_link: lsp::DocumentLink,
+        buffer: Entity<Buffer>,
+        server_id: LanguageServerId,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<lsp::DocumentLink>> {
+        if let Some((upstream_client, project_id)) = self.upstream_client() {
+            let request = proto::ResolveDocumentLink {
+                project_id,
+                buffer_id: buffer.read(cx).remote_id().into(),
+                language_server_id: server_id.to_proto(),
+                lsp_link: serde_json::to_vec(&lsp_link).unwrap(),
+            };
+            cx.background_spawn(async move {
+                let response = upstream_client
+                    .request(request)
+                    .await
+                    .context("document link proto request")?;
+                Ok(serde_json::from_slice(&response.lsp_link)?)
+            })
+        } else {
+            let Some(server) = buffer.update(cx, |buffer, cx| {
+                self.language_server_for_local_buffer(buffer, server_id, cx)
+                    .map(|(_, server)| server.clone())
+            }) else {
+                return Task::ready(Ok(lsp_link));
+            };
+            if !GetDocumentLinks::can_resolve_links(&server.capabilities()) {
+                return Task::ready(Ok(lsp_link));
+            }
+            cx.background_spawn(async move {
+                server
+                    .request::<lsp::request::DocumentLinkResolve>(lsp_link)
+                    .await
+                    .into_response()
+                    .context("document link resolve LSP request")
+            })
+        }
+    }
+
     pub fn resolve_color_presentation(
         &mut self,
         mut color: DocumentColor,
@@ -5912,6 +6021,8 @@ impl LspStore {
         kinds: Option<Vec<CodeActionKind>>,
         cx: &mut Context<Self>,
     ) -> Task<Result<Option<Vec<CodeAction>>>> {
+        let extension_actions =
+            extension::code_actions(buffer, range.clone(), kinds.as_deref(), cx);
         if let Some((upstream_client, project_id)) = self.upstream_client() {
             let request = GetCodeActions {
                 range: range.clone(),
@@ -5949,14 +6060,16 @@ impl LspStore {
                 }))
                 .await;
 
-                Ok(Some(
-                    actions
-                        .into_iter()
-                        .collect::<Result<Vec<Vec<_>>>>()?
-                        .into_iter()
-                        .flatten()
-                        .collect(),
-                ))
+                let mut actions = actions
+                    .into_iter()
+                    .collect::<Result<Vec<Vec<_>>>>()?
+                    .into_iter()
+                    .flatten()
+                    .collect::<Vec<_>>();
+                if let Some(extension_actions) = extension_actions {
+                    actions.extend(extension_actions.await);
+                }
+                Ok(Some(actions))
             })
         } else {
             let all_actions_task = self.request_multiple_lsp_locally(
@@ -5966,13 +6079,15 @@ impl LspStore {
                 cx,
             );
             cx.background_spawn(async move {
-                Ok(Some(
-                    all_actions_task
-                        .await
-                        .into_iter()
-                        .flat_map(|(_, actions)| actions)
-                        .collect(),
-                ))
+                let mut actions = all_actions_task
+                    .await
+                    .into_iter()
+                    .flat_map(|(_, actions)| actions)
+                    .collect::<Vec<_>>();
+                if let Some(extension_actions) = extension_actions {
+                    actions.extend(extension_actions.await);
+                }
+                Ok(Some(actions))
             })
         }
     }
@@ -10304,6 +10419,27 @@ impl LspStore {
         })
     }
 
+    async fn handle_resolve_document_link(
+        lsp_store: Entity<Self>,
+        envelope: TypedEnvelope<proto::ResolveDocumentLink>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::ResolveDocumentLinkResponse> {
+        let lsp_link: lsp::DocumentLink = serde_json::from_slice(&envelope.payload.lsp_link)?;
+        let buffer = lsp_store.update(&mut cx, |lsp_store, cx| {
+            let buffer_id = BufferId::new(envelope.payload.buffer_id)?;
+            lsp_store.buffer_store.read(cx).get_existing(buffer_id)
+        })??;
+        let server_id = LanguageServerId::from_proto(envelope.payload.language_server_id);
+        let resolved_link = lsp_store
+            .update(&mut cx, |lsp_store, cx| {
+                lsp_store.resolve_lsp_document_link(lsp_link, buffer, server_id, cx)
+            })?
+            .await?;
+        Ok(proto::ResolveDocumentLinkResponse {
+            lsp_link: serde_json::to_vec(&resolved_link)?,
+        })
+    }
+
     async fn handle_refresh_code_lens(
         this: Entity<Self>,
         _: TypedEnvelope<proto::RefreshCodeLens>,
@@ -11992,15 +12128,20 @@ impl LspStore {
                 proto::code_action::Kind::CodeLens as i32,
                 serde_json::to_vec(code_lens).unwrap(),
             ),
+            LspAction::Extension(extension_action) => (
+                proto::code_action::Kind::Extension as i32,
+                serde_json::to_vec(extension_action).unwrap(),
+            ),
         };
 
         proto::CodeAction {
-            server_id: action.server_id.0 as u64,
+            server_id: action.server_id.map_or(0, |server_id| server_id.0 as u64),
             start: Some(serialize_anchor(&action.range.start)),
             end: Some(serialize_anchor(&action.range.end)),
             lsp_action,
             kind,
             resolved: action.resolved,
+            non_lsp: action.server_id.is_none(),
         }
     }
 
@@ -12023,10 +12164,13 @@ impl LspStore {
             Some(proto::code_action::Kind::CodeLens) => {
                 LspAction::CodeLens(serde_json::from_slice(&action.lsp_action)?)
             }
+            Some(proto::code_action::Kind::Extension) => {
+                LspAction::Extension(serde_json::from_slice(&action.lsp_action)?)
+            }
             None => anyhow::bail!("Unknown action kind {}", action.kind),
         };
         Ok(CodeAction {
-            server_id: LanguageServerId(action.server_id as usize),
+            server_id: (!action.non_lsp).then(|| LanguageServerId(action.server_id as usize)),
             range: start..end,
             resolved: action.resolved,
             lsp_action,
@@ -12595,6 +12739,29 @@ impl LspStore {
                     });
                     notify_server_capabilities_updated(&server, cx);
                 }
+                "textDocument/documentLink" => {
+                    let options = parse_register_capabilities(reg)?;
+                    let provider = match options {
+                        OneOf::Left(false) => None,
+                        OneOf::Left(true) => Some(lsp::DocumentLinkOptions::default()),
+                        OneOf::Right(caps) => Some(caps),
+                    };
+                    server.update_capabilities(|capabilities| {
+                        capabilities.document_link_provider = provider;
+                    });
+                    notify_server_capabilities_updated(&server, cx);
+                }
+                "textDocument/selectionRange" => {
+                    let options = parse_register_capabilities(reg)?;
+                    let provider = match options {
+                        OneOf::Left(value) => lsp::SelectionRangeProviderCapability::Simple(value),
+                        OneOf::Right(caps) => caps,
+                    };
+                    server.update_capabilities(|capabilities| {
+                        capabilities.selection_range_provider = Some(provider);
+                    });
+                    notify_server_capabilities_updated(&server, cx);
+                }
                 _ => log::warn!("unhandled capability registration: {reg:?}"),
             }
         }
@@ -12786,6 +12953,18 @@ impl LspStore {
                     });
                     notify_server_capabilities_updated(&server, cx);
                 }
+                "textDocument/documentLink" => {
+                    server.update_capabilities(|capabilities| {
+                        capabilities.document_link_provider = None;
+                    });
+                    notify_server_capabilities_updated(&server, cx);
+                }
+                "textDocument/selectionRange" => {
+                    server.update_capabilities(|capabilities| {
+                        capabilities.selection_range_provider = None;
+                    });
+                    notify_server_capabilities_updated(&server, cx);
+                }
                 _ => log::warn!("unhandled capability unregistration: {unreg:?}"),
             }
         }
@@ -0,0 +1,537 @@
+use std::{ops::Range, sync::Arc};
+
+use anyhow::{Context as _, Result};
+use collections::BTreeMap;
+use extension::{
+    Extension, ExtensionCodeActionProviderProxy, ExtensionFormatterProxy, ExtensionHostProxy,
+    FormatSettings, TextEdit,
+};
+use futures::future::join_all;
+use gpui::{App, AppContext as _, AsyncApp, Entity, Global, Task};
+use language::{Anchor, Buffer, BufferSnapshot, LanguageName, language_settings::LanguageSettings};
+use lsp::CodeActionKind;
+use parking_lot::RwLock;
+use text::OffsetRangeExt as _;
+use util::ResultExt as _;
+
+use crate::{CodeAction, ExtensionCodeAction, LspAction, ProjectTransaction};
+
+pub fn init(proxy: Arc<ExtensionHostProxy>, cx: &mut App) {
+    let registry = ExtensionFormattingRegistry::global(cx).clone();
+    proxy.register_formatter_proxy(registry.clone());
+    proxy.register_code_action_provider_proxy(registry);
+}
+
+struct ExtensionProvider {
+    extension: Arc<dyn Extension>,
+    languages: Vec<LanguageName>,
+}
+
+#[derive(Default)]
+struct ExtensionProviders {
+    formatters: BTreeMap<Arc<str>, ExtensionProvider>,
+    code_action_providers: BTreeMap<Arc<str>, ExtensionProvider>,
+}
+
+#[derive(Clone, Default)]
+/// Stores the formatters and code action providers registered by extensions, keyed by their names.
+pub struct ExtensionFormattingRegistry(Arc<RwLock<ExtensionProviders>>);
+impl Global for ExtensionFormattingRegistry {}
+
+impl ExtensionFormattingRegistry {
+    pub fn global(cx: &mut App) -> &mut Self {
+        cx.default_global::<Self>()
+    }
+
+    fn formatter(&self, formatter_name: &str) -> Option<(Arc<dyn Extension>, Vec<LanguageName>)> {
+        self.0
+            .read()
+            .formatters
+            .get(formatter_name)
+            .map(|formatter| (formatter.extension.clone(), formatter.languages.clone()))
+    }
+
+    fn code_action_provider(&self, provider_name: &str) -> Option<Arc<dyn Extension>> {
+        self.0
+            .read()
+            .code_action_providers
+            .get(provider_name)
+            .map(|provider| provider.extension.clone())
+    }
+
+    fn code_action_providers_for_language(
+        &self,
+        language_name: &LanguageName,
+    ) -> Vec<(Arc<str>, Arc<dyn Extension>)> {
+        self.0
+            .read()
+            .code_action_providers
+            .iter()
+            .filter(|(_, provider)| provider.languages.contains(language_name))
+            .map(|(name, provider)| (name.clone(), provider.extension.clone()))
+            .collect()
+    }
+}
+
+impl ExtensionFormatterProxy for ExtensionFormattingRegistry {
+    fn register_formatter(
+        &self,
+        extension: Arc<dyn Extension>,
+        formatter_name: Arc<str>,
+        languages: Vec<LanguageName>,
+    ) {
+        self.0.write().formatters.insert(
+            formatter_name,
+            ExtensionProvider {
+                extension,
+                languages,
+            },
+        );
+    }
+
+    fn unregister_formatter(&self, formatter_name: Arc<str>) {
+        self.0.write().formatters.remove(&formatter_name);
+    }
+}
+
+impl ExtensionCodeActionProviderProxy for ExtensionFormattingRegistry {
+    fn register_code_action_provider(
+        &self,
+        extension: Arc<dyn Extension>,
+        provider_name: Arc<str>,
+        languages: Vec<LanguageName>,
+    ) {
+        self.0.write().code_action_providers.insert(
+            provider_name,
+            ExtensionProvider {
+                extension,
+                languages,
+            },
+        );
+    }
+
+    fn unregister_code_action_provider(&self, provider_name: Arc<str>) {
+        self.0.write().code_action_providers.remove(&provider_name);
+    }
+}
+
+/// Formats the buffer with the extension formatter of the given name.
+///
+/// Returns `None` when the formatter does not support the buffer's language.
+pub(super) async fn format_buffer(
+    formatter_name: &str,
+    buffer: &Entity<Buffer>,
+    settings: &LanguageSettings,
+    cx: &mut AsyncApp,
+) -> Result<Option<Vec<(Range<Anchor>, String)>>> {
+    let (extension, languages) = cx
+        .try_read_global(|registry: &ExtensionFormattingRegistry, _| {
+            registry.formatter(formatter_name)
+        })
+        .flatten()
+        .with_context(|| format!("no extension provides the formatter {formatter_name:?}"))?;
+    let snapshot = buffer.read_with(cx, |buffer, _| buffer.snapshot())?;
+    let Some(language_name) = snapshot.language().map(|language| language.name()) else {
+        return Ok(None);
+    };
+    if !languages.contains(&language_name) {
+        return Ok(None);
+    }
+
+    let settings = FormatSettings {
+        tab_size: settings.tab_size.get(),
+        hard_tabs: settings.hard_tabs,
+        preferred_line_length: settings.preferred_line_length,
+    };
+    let edits = extension
+        .format_buffer(
+            formatter_name.into(),
+            snapshot.text(),
+            language_name,
+            settings,
+        )
+        .await?;
+    anchor_edits(&snapshot, edits).map(Some)
+}
+
+/// Asks the extension code action providers registered for the buffer's language for actions
+/// in the given range.
+pub(super) fn code_actions(
+    buffer: &Entity<Buffer>,
+    range: Range<Anchor>,
+    kinds: Option<&[CodeActionKind]>,
+    cx: &App,
+) -> Option<Task<Vec<CodeAction>>> {
+    let snapshot = buffer.read(cx).snapshot();
+    let language_name = snapshot.language()?.name();
+    let providers = cx
+        .try_global::<ExtensionFormattingRegistry>()?
+        .code_action_providers_for_language(&language_name);
+    if providers.is_empty() {
+        return None;
+    }
+
+    let kinds = kinds.map(|kinds| kinds.to_vec());
+    let text = snapshot.text();
+    let offset_range = range.to_offset(&snapshot);
+    Some(cx.background_spawn(async move {
+        let actions = join_all(providers.into_iter().map(|(provider_name, extension)| {
+            let text = text.clone();
+            let language_name = language_name.clone();
+            let offset_range = offset_range.clone();
+            async move {
+                let actions = extension
+                    .code_actions(provider_name.clone(), text, language_name, offset_range)
+                    .await
+                    .with_context(|| {
+                        format!("getting code actions from code action provider {provider_name}")
+                    });
+                actions.map(|actions| (provider_name, actions))
+            }
+        }))
+        .await;
+
+        actions
+            .into_iter()
+            .filter_map(|actions| actions.log_err())
+            .flat_map(|(provider_name, actions)| {
+                actions
+                    .into_iter()
+                    .map(move |action| (provider_name.clone(), action))
+            })
+            .map(|(provider_name, action)| CodeAction {
+                server_id: None,
+                range: range.clone(),
+                lsp_action: LspAction::Extension(ExtensionCodeAction {
+                    provider_name: provider_name.to_string(),
+                    title: action.title,
+                    kind: action.kind.map(CodeActionKind::from),
+                }),
+                resolved: true,
+            })
+            .filter(|action| {
+                kinds.as_ref().is_none_or(|kinds| {
+                    action
+                        .lsp_action
+                        .action_kind()
+                        .is_some_and(|kind| kinds.contains(&kind))
+                })
+            })
+            .collect()
+    }))
+}
+
+/// Applies a code action provided by an extension.
+///
+/// The action is requested again against the current buffer contents, so that its edits
+/// match the text they are applied to.
+pub(super) fn apply_code_action(
+    buffer: Entity<Buffer>,
+    action: ExtensionCodeAction,
+    range: Range<Anchor>,
+    push_to_history: bool,
+    cx: &mut App,
+) -> Task<Result<ProjectTransaction>> {
+    let provider_name: Arc<str> = action.provider_name.into();
+    let Some(extension) = cx
+        .try_global::<ExtensionFormattingRegistry>()
+        .and_then(|registry| registry.code_action_provider(&provider_name))
+    else {
+        return Task::ready(Err(anyhow::anyhow!(
+            "no extension provides the code action provider {provider_name:?}"
+        )));
+    };
+    let snapshot = buffer.read(cx).snapshot();
+    let Some(language_name) = snapshot.language().map(|language| language.name()) else {
+        return Task::ready(Ok(ProjectTransaction::default()));
+    };
+    let title = action.title;
+    let range = range.to_offset(&snapshot);
+
+    cx.spawn(async move |cx| {
+        let actions = extension
+            .code_actions(provider_name.clone(), snapshot.text(), language_name, range)
+            .await?;
+        let action = actions
+            .into_iter()
+            .find(|action| action.title == title)
+            .with_context(|| {
+                format!("code action provider {provider_name} no longer offers {title:?}")
+            })?;
+        let edits = anchor_edits(&snapshot, action.edits)?;
+
+        let mut project_transaction = ProjectTransaction::default();
+        let transaction = buffer.update(cx, |buffer, cx| {
+            buffer.finalize_last_transaction();
+            buffer.start_transaction();
+            buffer.edit(edits, None, cx);
+            buffer.end_transaction(cx).and_then(|transaction_id| {
+                if push_to_history {
+                    buffer.finalize_last_transaction();
+                    buffer.get_transaction(transaction_id).cloned()
+                } else {
+                    buffer.forget_transaction(transaction_id)
+                }
+            })
+        })?;
+        if let Some(transaction) = transaction {
+            project_transaction.0.insert(buffer, transaction);
+        }
+        Ok(project_transaction)
+    })
+}
+
+fn anchor_edits(
+    snapshot: &BufferSnapshot,
+    edits: Vec<TextEdit>,
+) -> Result<Vec<(Range<Anchor>, String)>> {
+    edits
+        .into_iter()
+        .map(|edit| {
+            let Range { start, end } = edit.range;
+            anyhow::ensure!(
+                start <= end
+                    && end <= snapshot.len()
+                    && snapshot.as_rope().is_char_boundary(start)
+                    && snapshot.as_rope().is_char_boundary(end),
+                "invalid edit range {start}..{end}"
+            );
+            Ok((
+                snapshot.anchor_before(start)..snapshot.anchor_after(end),
+                edit.new_text,
+            ))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        Project,
+        lsp_store::{FormatTrigger, LspFormatTarget, LspStore},
+    };
+    use collections::HashSet;
+    use extension::FakeExtension;
+    use fs::FakeFs;
+    use gpui::TestAppContext;
+    use language::{Language, LanguageConfig, LanguageMatcher};
+    use serde_json::json;
+    use settings::{Formatter, FormatterList, SettingsStore};
+    use util::path;
+
+    #[gpui::test]
+    async fn test_extension_formatter(cx: &mut TestAppContext) {
+        let extension = FakeExtension::new("shout").with_formatter(
+            "shout",
+            vec!["Notes".into()],
+            |text, settings| {
+                assert_eq!(settings.tab_size, 4);
+                Ok(vec![TextEdit {
+                    range: 0..text.len(),
+                    new_text: text.to_uppercase(),
+                }])
+            },
+        );
+        let (project, buffer) = init_test(extension, "hello world\n", cx).await;
+        cx.update(|cx| {
+            SettingsStore::update_global(cx, |store, cx| {
+                store.update_user_settings(cx, |settings| {
+                    settings.project.all_languages.defaults.formatter = Some(
+                        FormatterList::Single(Formatter::Extension("shout".to_string())),
+                    );
+                });
+            });
+        });
+
+        let transaction = project
+            .update(cx, |project, cx| {
+                project.format(
+                    HashSet::from_iter([buffer.clone()]),
+                    LspFormatTarget::Buffers,
+                    true,
+                    FormatTrigger::Manual,
+                    cx,
+                )
+            })
+            .await
+            .unwrap();
+        assert!(transaction.0.contains_key(&buffer));
+        buffer.read_with(cx, |buffer, _| assert_eq!(buffer.text(), "HELLO WORLD\n"));
+
+        // Formatters are skipped for languages they don't support.
+        cx.update(|cx| {
+            ExtensionFormattingRegistry::global(cx).register_formatter(
+                Arc::new(FakeExtension::new("rusty").with_formatter(
+                    "rusty",
+                    vec!["Rust".into()],
+                    |_, _| panic!("formatted a buffer of an unsupported language"),
+                )),
+                "rusty".into(),
+                vec!["Rust".into()],
+            );
+            SettingsStore::update_global(cx, |store, cx| {
+                store.update_user_settings(cx, |settings| {
+                    settings.project.all_languages.defaults.formatter = Some(
+                        FormatterList::Single(Formatter::Extension("rusty".to_string())),
+                    );
+                });
+            });
+        });
+        project
+            .update(cx, |project, cx| {
+                project.format(
+                    HashSet::from_iter([buffer.clone()]),
+                    LspFormatTarget::Buffers,
+                    true,
+                    FormatTrigger::Manual,
+                    cx,
+                )
+            })
+            .await
+            .unwrap();
+        buffer.read_with(cx, |buffer, _| assert_eq!(buffer.text(), "HELLO WORLD\n"));
+    }
+
+    #[gpui::test]
+    async fn test_extension_code_actions(cx: &mut TestAppContext) {
+        let extension = FakeExtension::new("shout").with_code_action_provider(
+            "shout",
+            vec!["Notes".into()],
+            |text, range| {
+                Ok(vec![extension::CodeAction {
+                    title: "Shout".to_string(),
+                    kind: Some("refactor.rewrite".to_string()),
+                    edits: vec![TextEdit {
+                        new_text: text[range.clone()].to_uppercase(),
+                        range,
+                    }],
+                }])
+            },
+        );
+        let (project, buffer) = init_test(extension, "hello world\n", cx).await;
+
+        let actions = project
+            .update(cx, |project, cx| {
+                project.code_actions(&buffer, 0..5, None, cx)
+            })
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(actions.len(), 1);
+        let action = actions[0].clone();
+        assert_eq!(action.server_id, None);
+        assert_eq!(
+            action.lsp_action,
+            LspAction::Extension(ExtensionCodeAction {
+                provider_name: "shout".to_string(),
+                title: "Shout".to_string(),
+                kind: Some(CodeActionKind::REFACTOR_REWRITE),
+            })
+        );
+        assert_eq!(
+            LspStore::deserialize_code_action(LspStore::serialize_code_action(&action)).unwrap(),
+            action
+        );
+
+        // Actions are filtered by the requested kinds.
+        let quick_fixes = project
+            .update(cx, |project, cx| {
+                project.code_actions(&buffer, 0..5, Some(vec![CodeActionKind::QUICKFIX]), cx)
+            })
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(quick_fixes, Vec::new());
+
+        let transaction = project
+            .update(cx, |project, cx| {
+                project.apply_code_action(buffer.clone(), action, true, cx)
+            })
+            .await
+            .unwrap();
+        assert!(transaction.0.contains_key(&buffer));
+        buffer.read_with(cx, |buffer, _| assert_eq!(buffer.text(), "HELLO world\n"));
+    }
+
+    async fn init_test(
+        extension: FakeExtension,
+        text: &str,
+        cx: &mut TestAppContext,
+    ) -> (Entity<Project>, Entity<Buffer>) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+
+            let proxy = Arc::new(ExtensionHostProxy::new());
+            init(proxy.clone(), cx);
+            let manifest = extension.manifest();
+            let extension: Arc<dyn Extension> = Arc::new(extension);
+            for (formatter_name, formatter) in &manifest.formatters {
+                proxy.register_formatter(
+                    extension.clone(),
+                    formatter_name.clone(),
+                    formatter.languages.clone(),
+                );
+            }
+            for (provider_name, provider) in &manifest.code_action_providers {
+                proxy.register_code_action_provider(
+                    extension.clone(),
+                    provider_name.clone(),
+                    provider.languages.clone(),
+                );
+            }
+        });
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(path!("/project"), json!({ "file.notes": text }))
+            .await;
+        let project = Project::test(fs, [path!("/project").as_ref()], cx).await;
+        project.read_with(cx, |project, _| {
+            project.languages().add(Arc::new(Language::new(
+                LanguageConfig {
+                    name: "Notes".into(),
+                    matcher: LanguageMatcher {
+                        path_suffixes: vec!["notes".to_string()],
+                        ..LanguageMatcher::default()
+                    },
+                    ..LanguageConfig::default()
+                },
+                None,
+            )))
+        });
+        let buffer = project
+            .update(cx, |project, cx| {
+                project.open_local_buffer(path!("/project/file.notes"), cx)
+            })
+            .await
+            .unwrap();
+        cx.run_until_parked();
+        (project, buffer)
+    }
+
+    #[test]
+    fn test_code_action_from_server_zero() {
+        // Peers that predate non-LSP actions send the id of the first language server as
+        // the default, which is omitted on the wire.
+        let action = rpc::proto::CodeAction {
+            server_id: 0,
+            start: Some(language::proto::serialize_anchor(&Anchor::MIN)),
+            end: Some(language::proto::serialize_anchor(&Anchor::MAX)),
+            lsp_action: serde_json::to_vec(&lsp::CodeAction {
+                title: "Fix".into(),
+                ..Default::default()
+            })
+            .unwrap(),
+            kind: rpc::proto::code_action::Kind::Action as i32,
+            resolved: true,
+            non_lsp: false,
+        };
+        let action = LspStore::deserialize_code_action(action).unwrap();
+        assert_eq!(action.server_id, Some(lsp::LanguageServerId(0)));
+        assert_eq!(
+            LspStore::deserialize_code_action(LspStore::serialize_code_action(&action)).unwrap(),
+            action
+        );
+    }
+}
@@ -0,0 +1,362 @@
+//! Tracks the ports forwarded from the host of a remote project to the local
+//! host, and the ports that are listening on the remote host.
+
+use std::{collections::BTreeSet, time::Duration};
+
+use anyhow::Result;
+use gpui::{Context, Entity, EventEmitter, SharedString, Task};
+use remote::{ConnectionState, RemoteClient, RemoteConnectionOptions, port_forward};
+use rpc::proto::{self, REMOTE_SERVER_PROJECT_ID};
+use util::ResultExt as _;
+
+const LISTENING_PORTS_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+pub struct PortForwardStore {
+    remote_client: Entity<RemoteClient>,
+    forwards: Vec<PortForward>,
+    listening_ports: BTreeSet<u16>,
+    /// The ports that were already listening when the project was opened, which
+    /// are not announced as newly detected.
+    initial_listening_ports: Option<BTreeSet<u16>>,
+    _poll_listening_ports: Task<()>,
+}
+
+pub struct PortForward {
+    pub local_port: u16,
+    pub remote_host: String,
+    pub remote_port: u16,
+    /// Whether the forward is configured in the connection settings, and so
+    /// is managed by the connection rather than by this store.
+    pub is_static: bool,
+    /// Why forwarding stopped, if it did.
+    pub error: Option<SharedString>,
+    _task: Option<Task<()>>,
+}
+
+pub enum PortForwardStoreEvent {
+    /// A port started listening on the remote host and is not forwarded yet.
+    PortDetected(u16),
+}
+
+impl EventEmitter<PortForwardStoreEvent> for PortForwardStore {}
+
+impl PortForwardStore {
+    pub fn new(remote_client: Entity<RemoteClient>, cx: &mut Context<Self>) -> Self {
+        let mut forwards = Vec::new();
+        if let RemoteConnectionOptions::Ssh(options) = remote_client.read(cx).connection_options() {
+            for forward in options.port_forwards.into_iter().flatten() {
+                forwards.push(PortForward {
+                    local_port: forward.local_port,
+                    remote_host: forward
+                        .remote_host
+                        .unwrap_or_else(|| "localhost".to_string()),
+                    remote_port: forward.remote_port,
+                    is_static: true,
+                    error: None,
+                    _task: None,
+                });
+            }
+        }
+
+        Self {
+            remote_client,
+            forwards,
+            listening_ports: BTreeSet::new(),
+            initial_listening_ports: None,
+            _poll_listening_ports: cx.spawn(async move |this, cx| {
+                loop {
+                    let Ok(request) = this.update(cx, |this, cx| {
+                        let remote_client = this.remote_client.read(cx);
+                        (remote_client.connection_state() == ConnectionState::Connected).then(
+                            || {
+                                remote_client
+                                    .proto_client()
+                                    .request(proto::GetListeningPorts {
+                                        project_id: REMOTE_SERVER_PROJECT_ID,
+                                        terminal_id: remote_client.unique_identifier().to_string(),
+                                    })
+                            },
+                        )
+                    }) else {
+                        break;
+                    };
+                    if let Some(request) = request
+                        && let Some(response) = request.await.log_err()
+                    {
+                        let ports = response
+                            .ports
+                            .into_iter()
+                            .filter_map(|port| u16::try_from(port).ok())
+                            .collect();
+                        if this
+                            .update(cx, |this, cx| this.set_listening_ports(ports, cx))
+                            .is_err()
+                        {
+                            break;
+                        }
+                    }
+                    cx.background_executor()
+                        .timer(LISTENING_PORTS_POLL_INTERVAL)
+                        .await;
+                }
+            }),
+        }
+    }
+
+    pub fn forwards(&self) -> &[PortForward] {
+        &self.forwards
+    }
+
+    /// Returns the ports listening on the remote host that are not forwarded.
+    pub fn unforwarded_listening_ports(&self) -> impl Iterator<Item = u16> + '_ {
+        self.listening_ports
+            .iter()
+            .copied()
+            .filter(|port| !self.is_forwarded(*port))
+    }
+
+    pub fn is_forwarded(&self, remote_port: u16) -> bool {
+        self.forwards
+            .iter()
+            .any(|forward| forward.remote_port == remote_port && forward.error.is_none())
+    }
+
+    fn set_listening_ports(&mut self, ports: BTreeSet<u16>, cx: &mut Context<Self>) {
+        if self.initial_listening_ports.is_none() {
+            self.initial_listening_ports = Some(ports.clone());
+        }
+        let detected_ports = ports
+            .difference(&self.listening_ports)
+            .copied()
+            .filter(|port| {
+                !self
+                    .initial_listening_ports
+                    .as_ref()
+                    .is_some_and(|initial_ports| initial_ports.contains(port))
+                    && !self.is_forwarded(*port)
+            })
+            .collect::<Vec<_>>();
+        for port in detected_ports {
+            cx.emit(PortForwardStoreEvent::PortDetected(port));
+        }
+        if self.listening_ports != ports {
+            self.listening_ports = ports;
+            cx.notify();
+        }
+    }
+
+    /// Forwards `remote_host:remote_port` on the remote host to `local_port`,
+    /// or to the same port locally if it is free and `local_port` is `None`.
+    ///
+    /// Returns the local port that is forwarded.
+    pub fn forward_port(
+        &mut self,
+        remote_host: String,
+        remote_port: u16,
+        local_port: Option<u16>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<u16>> {
+        // Replace forwards that stopped, so that they can be restarted.
+        self.forwards.retain(|forward| {
+            forward.is_static || forward.error.is_none() || forward.remote_port != remote_port
+        });
+        if let Some(forward) = self.forwards.iter().find(|forward| {
+            forward.remote_host == remote_host
+                && forward.remote_port == remote_port
+                && local_port.is_none_or(|local_port| forward.local_port == local_port)
+        }) {
+            return Task::ready(Ok(forward.local_port));
+        }
+
+        cx.spawn(async move |this, cx| {
+            let local_port = match local_port {
+                Some(local_port) => local_port,
+                None => port_forward::unused_local_port(remote_port).await?,
+            };
+            this.update(cx, |this, cx| {
+                let forward = this.remote_client.read(cx).forward_port(
+                    local_port,
+                    remote_host.clone(),
+                    remote_port,
+                    cx,
+                );
+                let task = cx.spawn(async move |this, cx| {
+                    let error = match forward.await {
+                        Ok(()) => "Forwarding stopped".into(),
+                        Err(error) => format!("{error:#}").into(),
+                    };
+                    this.update(cx, |this, cx| {
+                        if let Some(forward) = this
+                            .forwards
+                            .iter_mut()
+                            .find(|forward| forward.local_port == local_port)
+                        {
+                            log::warn!(
+                                "stopped forwarding remote port {remote_port} to local port {local_port}: {error}"
+                            );
+                            forward.error = Some(error);
+                            forward._task = None;
+                            cx.notify();
+                        }
+                    })
+                    .ok();
+                });
+                this.forwards.push(PortForward {
+                    local_port,
+                    remote_host,
+                    remote_port,
+                    is_static: false,
+                    error: None,
+                    _task: Some(task),
+                });
+                cx.notify();
+                local_port
+            })
+        })
+    }
+
+    /// Stops forwarding `local_port`. Static forwards can't be stopped.
+    pub fn stop_forward(&mut self, local_port: u16, cx: &mut Context<Self>) {
+        let len = self.forwards.len();
+        self.forwards
+            .retain(|forward| forward.is_static || forward.local_port != local_port);
+        if self.forwards.len() != len {
+            cx.notify();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{AppContext as _, TestAppContext};
+    use rpc::TypedEnvelope;
+    use std::{cell::RefCell, rc::Rc};
+
+    #[gpui::test]
+    async fn test_port_forward_store(cx: &mut TestAppContext, server_cx: &mut TestAppContext) {
+        cx.update(|cx| release_channel::init(semver::Version::new(0, 0, 0), cx));
+        server_cx.update(|cx| release_channel::init(semver::Version::new(0, 0, 0), cx));
+        let (opts, server_client) = RemoteClient::fake_server(cx, server_cx);
+        let server_ports = server_cx.new(|_| vec![3000_u32]);
+        server_client.add_request_handler(
+            server_ports.downgrade(),
+            |_, _: TypedEnvelope<proto::Ping>, _| async { Ok(proto::Ack {}) },
+        );
+        server_client.add_request_handler(
+            server_ports.downgrade(),
+            |ports, _: TypedEnvelope<proto::GetListeningPorts>, cx| async move {
+                Ok(proto::GetListeningPortsResponse {
+                    ports: ports.read_with(&cx, |ports, _| ports.clone())?,
+                })
+            },
+        );
+        let remote_client = RemoteClient::fake_client(opts, cx).await;
+
+        let store = cx.new(|cx| PortForwardStore::new(remote_client.clone(), cx));
+        let detected_ports = Rc::new(RefCell::new(Vec::new()));
+        cx.update(|cx| {
+            let detected_ports = detected_ports.clone();
+            cx.subscribe(&store, move |_, event, _| match event {
+                PortForwardStoreEvent::PortDetected(port) => {
+                    detected_ports.borrow_mut().push(*port)
+                }
+            })
+            .detach();
+        });
+        let unforwarded_ports = |cx: &mut TestAppContext| {
+            store.read_with(cx, |store, _| {
+                store.unforwarded_listening_ports().collect::<Vec<_>>()
+            })
+        };
+        cx.run_until_parked();
+
+        // Ports that were listening when the project was opened are listed, but not announced.
+        assert_eq!(unforwarded_ports(cx), [3000]);
+        assert_eq!(*detected_ports.borrow(), []);
+
+        server_ports.update(server_cx, |ports, _| ports.push(5173));
+        cx.executor().advance_clock(LISTENING_PORTS_POLL_INTERVAL);
+        cx.run_until_parked();
+        assert_eq!(unforwarded_ports(cx), [3000, 5173]);
+        assert_eq!(*detected_ports.borrow(), [5173]);
+
+        let local_port = store
+            .update(cx, |store, cx| {
+                store.forward_port("localhost".into(), 5173, Some(15173), cx)
+            })
+            .await
+            .unwrap();
+        assert_eq!(local_port, 15173);
+        cx.run_until_parked();
+        assert_eq!(unforwarded_ports(cx), [3000]);
+        store.read_with(cx, |store, _| {
+            assert!(store.is_forwarded(5173));
+            assert_eq!(store.forwards().len(), 1);
+            assert_eq!(store.forwards()[0].error, None);
+        });
+
+        // Forwarding a port that is already forwarded reuses the existing forward.
+        let local_port = store
+            .update(cx, |store, cx| {
+                store.forward_port("localhost".into(), 5173, None, cx)
+            })
+            .await
+            .unwrap();
+        assert_eq!(local_port, 15173);
+        store.read_with(cx, |store, _| assert_eq!(store.forwards().len(), 1));
+
+        // A forward that stops is kept with its error, and its port is listed again.
+        remote_client
+            .update(cx, |client, cx| client.simulate_unreachable(true, cx))
+            .await;
+        store
+            .update(cx, |store, cx| {
+                store.forward_port("localhost".into(), 3000, Some(13000), cx)
+            })
+            .await
+            .unwrap();
+        cx.run_until_parked();
+        assert_eq!(unforwarded_ports(cx), [3000]);
+        store.read_with(cx, |store, _| {
+            assert!(!store.is_forwarded(3000));
+            assert_eq!(
+                store.forwards()[1].error,
+                Some(SharedString::from("host is unreachable"))
+            );
+        });
+
+        // Forwarding the port again replaces the forward that stopped.
+        remote_client
+            .update(cx, |client, cx| client.simulate_unreachable(false, cx))
+            .await;
+        store
+            .update(cx, |store, cx| {
+                store.forward_port("localhost".into(), 3000, Some(13000), cx)
+            })
+            .await
+            .unwrap();
+        cx.run_until_parked();
+        assert_eq!(unforwarded_ports(cx), []);
+        store.read_with(cx, |store, _| {
+            assert!(store.is_forwarded(3000));
+            assert_eq!(store.forwards().len(), 2);
+        });
+
+        store.update(cx, |store, cx| store.stop_forward(15173, cx));
+        assert_eq!(unforwarded_ports(cx), [5173]);
+
+        // Ports that stop listening are no longer listed, and are announced again
+        // when they start listening again.
+        server_ports.update(server_cx, |ports, _| ports.retain(|port| *port != 5173));
+        cx.executor().advance_clock(LISTENING_PORTS_POLL_INTERVAL);
+        cx.run_until_parked();
+        assert_eq!(unforwarded_ports(cx), []);
+
+        server_ports.update(server_cx, |ports, _| ports.push(5173));
+        cx.executor().advance_clock(LISTENING_PORTS_POLL_INTERVAL);
+        cx.run_until_parked();
+        assert_eq!(unforwarded_ports(cx), [5173]);
+        assert_eq!(*detected_ports.borrow(), [5173, 5173]);
+    }
+}
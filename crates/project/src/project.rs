@@ -1,4 +1,5 @@
 pub mod agent_server_store;
+pub mod bookmark_store;
 pub mod buffer_store;
 mod color_extractor;
 pub mod connection_manager;
@@ -10,6 +11,7 @@ pub mod image_store;
 pub mod lsp_command;
 pub mod lsp_store;
 mod manifest_tree;
+pub mod port_forward_store;
 pub mod prettier_store;
 mod project_search;
 pub mod project_settings;
@@ -51,9 +53,11 @@ pub use manifest_tree::ManifestTree;
 pub use project_search::Search;
 
 use anyhow::{Context as _, Result, anyhow};
+use bookmark_store::BookmarkStore;
 use buffer_store::{BufferStore, BufferStoreEvent};
 use client::{Client, Collaborator, PendingEntitySubscription, TypedEnvelope, UserStore, proto};
 use clock::ReplicaId;
+use port_forward_store::PortForwardStore;
 
 use dap::client::DebugAdapterClient;
 
@@ -102,11 +106,12 @@ pub use prettier_store::PrettierStore;
 use project_settings::{ProjectSettings, SettingsObserver, SettingsObserverEvent};
 use remote::{RemoteClient, RemoteConnectionOptions};
 use rpc::{
-    AnyProtoClient, ErrorCode,
+    AnyProtoClient, ErrorCode, ErrorExt as _,
     proto::{LanguageServerPromptResponse, REMOTE_SERVER_PROJECT_ID},
 };
 use search::{SearchInputKind, SearchQuery, SearchResult};
 use search_history::SearchHistory;
+use serde::{Deserialize, Serialize};
 use settings::{InvalidSettingsError, RegisterSetting, Settings, SettingsLocation, SettingsStore};
 use smol::channel::Receiver;
 use snippet::Snippet;
@@ -125,6 +130,7 @@ use std::{
 };
 
 use task_store::TaskStore;
+pub use terminals::TerminalSharing;
 use terminals::Terminals;
 use text::{Anchor, BufferId, OffsetRangeExt, Point, Rope};
 use toolchain_store::EmptyToolchainStore;
@@ -149,7 +155,7 @@ pub use task_inventory::{
     TaskSourceKind,
 };
 
-pub use buffer_store::ProjectTransaction;
+pub use buffer_store::{ProjectTransaction, RemoteBufferSyncState};
 pub use lsp_store::{
     DiagnosticSummary, InvalidationStrategy, LanguageServerLogType, LanguageServerProgress,
     LanguageServerPromptRequest, LanguageServerStatus, LanguageServerToQuery, LspStore,
@@ -159,6 +165,10 @@ pub use toolchain_store::{ToolchainStore, Toolchains};
 const MAX_PROJECT_SEARCH_HISTORY_SIZE: usize = 500;
 
 pub trait ProjectItem: 'static {
+    /// Returns a task that opens the item at `path`, or `None` if the item can't open it.
+    ///
+    /// When whether the item can open the path is only known once the path has been read,
+    /// the task can fail with [`UnsupportedProjectItem`] to let other items open it instead.
     fn try_open(
         project: &Entity<Project>,
         path: &ProjectPath,
@@ -171,6 +181,19 @@ pub trait ProjectItem: 'static {
     fn is_dirty(&self) -> bool;
 }
 
+/// The error of a [`ProjectItem::try_open`] task for a path that the item turned out not to
+/// support.
+#[derive(Debug)]
+pub struct UnsupportedProjectItem;
+
+impl std::fmt::Display for UnsupportedProjectItem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "the item can't open this path")
+    }
+}
+
+impl std::error::Error for UnsupportedProjectItem {}
+
 #[derive(Clone)]
 pub enum OpenedBufferEvent {
     Disconnected,
@@ -189,8 +212,10 @@ pub struct Project {
     languages: Arc<LanguageRegistry>,
     dap_store: Entity<DapStore>,
     agent_server_store: Entity<AgentServerStore>,
+    port_forward_store: Option<Entity<PortForwardStore>>,
 
     breakpoint_store: Entity<BreakpointStore>,
+    bookmark_store: Entity<BookmarkStore>,
     collab_client: Arc<client::Client>,
     join_project_response_message_id: u32,
     task_store: Entity<TaskStore>,
@@ -339,6 +364,8 @@ pub enum Event {
     },
     CollaboratorJoined(proto::PeerId),
     CollaboratorLeft(proto::PeerId),
+    /// The host shared a terminal with this guest.
+    SharedTerminalOpened(Entity<terminal::Terminal>),
     HostReshared,
     Reshared,
     Rejoined,
@@ -654,11 +681,12 @@ pub(crate) struct CoreCompletion {
     source: CompletionSource,
 }
 
-/// A code action provided by a language server.
+/// A code action provided by a language server or an extension.
 #[derive(Clone, Debug, PartialEq)]
 pub struct CodeAction {
     /// The id of the language server that produced this code action.
-    pub server_id: LanguageServerId,
+    /// `None` for actions provided by extensions.
+    pub server_id: Option<LanguageServerId>,
     /// The range of the buffer where this code action is applicable.
     pub range: Range<Anchor>,
     /// The raw code action provided by the language server.
@@ -678,6 +706,17 @@ pub enum LspAction {
     Command(lsp::Command),
     /// A code lens data to run as an action.
     CodeLens(lsp::CodeLens),
+    /// An action offered by an extension's code action provider.
+    Extension(ExtensionCodeAction),
+}
+
+/// A code action offered by an extension's code action provider.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ExtensionCodeAction {
+    /// The name of the code action provider that offered this action.
+    pub provider_name: String,
+    pub title: String,
+    pub kind: Option<lsp::CodeActionKind>,
 }
 
 impl LspAction {
@@ -690,6 +729,7 @@ impl LspAction {
                 .as_ref()
                 .map(|command| command.title.as_str())
                 .unwrap_or("Unknown command"),
+            Self::Extension(action) => &action.title,
         }
     }
 
@@ -698,6 +738,7 @@ impl LspAction {
             Self::Action(action) => action.kind.clone(),
             Self::Command(_) => Some(lsp::CodeActionKind::new("command")),
             Self::CodeLens(_) => Some(lsp::CodeActionKind::new("code lens")),
+            Self::Extension(action) => action.kind.clone(),
         }
     }
 
@@ -706,6 +747,7 @@ impl LspAction {
             Self::Action(action) => action.edit.as_ref(),
             Self::Command(_) => None,
             Self::CodeLens(_) => None,
+            Self::Extension(_) => None,
         }
     }
 
@@ -714,6 +756,7 @@ impl LspAction {
             Self::Action(action) => action.command.as_ref(),
             Self::Command(command) => Some(command),
             Self::CodeLens(lens) => lens.command.as_ref(),
+            Self::Extension(_) => None,
         }
     }
 }
@@ -776,6 +819,23 @@ pub struct DocumentHighlight {
     pub kind: DocumentHighlightKind,
 }
 
+/// A link in a document, such as a path or a URL, reported by a language server.
+#[derive(Clone, Debug)]
+pub struct DocumentLink {
+    pub range: Range<language::Anchor>,
+    pub server_id: LanguageServerId,
+    /// The link as the server reported it, which is needed to resolve its target.
+    pub lsp_link: lsp::DocumentLink,
+}
+
+impl DocumentLink {
+    /// The target of the link, which is only known once the link is resolved,
+    /// for servers that resolve links lazily.
+    pub fn target(&self) -> Option<&lsp::Uri> {
+        self.lsp_link.target.as_ref()
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct Symbol {
     pub language_server_name: LanguageServerName,
@@ -1053,6 +1113,9 @@ impl Project {
         client.add_entity_message_handler(Self::handle_create_buffer_for_peer);
         client.add_entity_message_handler(Self::handle_toggle_lsp_logs);
         client.add_entity_message_handler(Self::handle_create_image_for_peer);
+        client.add_entity_message_handler(Self::handle_update_shared_terminal);
+        client.add_entity_message_handler(Self::handle_unshare_terminal);
+        client.add_entity_request_handler(Self::handle_shared_terminal_input);
 
         WorktreeStore::init(&client);
         BufferStore::init(&client);
@@ -1064,6 +1127,8 @@ impl Project {
         DapStore::init(&client, cx);
         BreakpointStore::init(&client);
         context_server_store::init(cx);
+        task_inventory::init(cx);
+        lsp_store::extension::init(extension::ExtensionHostProxy::default_global(cx), cx);
     }
 
     pub fn local(
@@ -1119,6 +1184,7 @@ impl Project {
 
             let breakpoint_store =
                 cx.new(|_| BreakpointStore::local(worktree_store.clone(), buffer_store.clone()));
+            let bookmark_store = cx.new(|_| BookmarkStore::new(buffer_store.clone()));
 
             let dap_store = cx.new(|cx| {
                 DapStore::new_local(
@@ -1234,14 +1300,14 @@ impl Project {
                 fs,
                 remote_client: None,
                 breakpoint_store,
+                bookmark_store,
                 dap_store,
                 agent_server_store,
+                port_forward_store: None,
 
                 buffers_needing_diff: Default::default(),
                 git_diff_debouncer: DebouncedDelay::new(),
-                terminals: Terminals {
-                    local_handles: Vec::new(),
-                },
+                terminals: Terminals::default(),
                 node: Some(node),
                 search_history: Self::new_search_history(),
                 environment,
@@ -1303,7 +1369,7 @@ impl Project {
                             cx,
                         );
                     }
-                    RemoteConnectionOptions::Docker(..) => {}
+                    RemoteConnectionOptions::Docker(..) | RemoteConnectionOptions::Command(..) => {}
                 }
             }
 
@@ -1379,6 +1445,7 @@ impl Project {
 
             let breakpoint_store =
                 cx.new(|_| BreakpointStore::remote(REMOTE_SERVER_PROJECT_ID, remote_proto.clone()));
+            let bookmark_store = cx.new(|_| BookmarkStore::new(buffer_store.clone()));
 
             let dap_store = cx.new(|cx| {
                 DapStore::new_remote(
@@ -1405,8 +1472,15 @@ impl Project {
 
             let agent_server_store =
                 cx.new(|_| AgentServerStore::remote(REMOTE_SERVER_PROJECT_ID, remote.clone()));
+            let port_forward_store = cx.new(|cx| PortForwardStore::new(remote.clone(), cx));
 
             cx.subscribe(&remote, Self::on_remote_client_event).detach();
+            let offline_remote_editing = ProjectSettings::get_global(cx)
+                .session
+                .offline_remote_editing;
+            remote.update(cx, |remote, _| {
+                remote.set_offline_editing(offline_remote_editing)
+            });
 
             let this = Self {
                 buffer_ordered_messages_tx: tx,
@@ -1417,11 +1491,13 @@ impl Project {
                 lsp_store,
                 context_server_store,
                 breakpoint_store,
+                bookmark_store,
                 dap_store,
                 join_project_response_message_id: 0,
                 client_state: ProjectClientState::Local,
                 git_store,
                 agent_server_store,
+                port_forward_store: Some(port_forward_store),
                 client_subscriptions: Vec::new(),
                 _subscriptions: vec![
                     cx.on_release(Self::release),
@@ -1441,6 +1517,16 @@ impl Project {
                             }
                         })
                     }),
+                    cx.observe_global::<SettingsStore>(|this, cx| {
+                        let offline_remote_editing = ProjectSettings::get_global(cx)
+                            .session
+                            .offline_remote_editing;
+                        if let Some(remote) = this.remote_client.clone() {
+                            remote.update(cx, |remote, _| {
+                                remote.set_offline_editing(offline_remote_editing)
+                            });
+                        }
+                    }),
                 ],
                 active_entry: None,
                 snippets,
@@ -1453,9 +1539,7 @@ impl Project {
                 remote_client: Some(remote.clone()),
                 buffers_needing_diff: Default::default(),
                 git_diff_debouncer: DebouncedDelay::new(),
-                terminals: Terminals {
-                    local_handles: Vec::new(),
-                },
+                terminals: Terminals::default(),
                 node: Some(node),
                 search_history: Self::new_search_history(),
                 environment,
@@ -1584,6 +1668,7 @@ impl Project {
             cx.new(|cx| ProjectEnvironment::new(None, worktree_store.downgrade(), None, true, cx))?;
         let breakpoint_store =
             cx.new(|_| BreakpointStore::remote(remote_id, client.clone().into()))?;
+        let bookmark_store = cx.new(|_| BookmarkStore::new(buffer_store.clone()))?;
         let dap_store = cx.new(|cx| {
             DapStore::new_collab(
                 remote_id,
@@ -1707,14 +1792,14 @@ impl Project {
                     replica_id,
                 },
                 breakpoint_store,
+                bookmark_store,
                 dap_store: dap_store.clone(),
                 git_store: git_store.clone(),
                 agent_server_store,
+                port_forward_store: None,
                 buffers_needing_diff: Default::default(),
                 git_diff_debouncer: DebouncedDelay::new(),
-                terminals: Terminals {
-                    local_handles: Vec::new(),
-                },
+                terminals: Terminals::default(),
                 node: None,
                 search_history: Self::new_search_history(),
                 search_included_history: Self::new_search_history(),
@@ -1937,6 +2022,11 @@ impl Project {
         self.breakpoint_store.clone()
     }
 
+    #[inline]
+    pub fn bookmark_store(&self) -> Entity<BookmarkStore> {
+        self.bookmark_store.clone()
+    }
+
     pub fn active_debug_session(&self, cx: &App) -> Option<(Entity<Session>, ActiveStackFrame)> {
         let active_position = self.breakpoint_store.read(cx).active_position()?;
         let session = self
@@ -2060,6 +2150,16 @@ impl Project {
             .map(|remote| remote.read(cx).connection_state())
     }
 
+    /// Returns the sync state of a remote buffer edited while the connection to
+    /// the remote host was offline.
+    pub fn remote_buffer_sync_state(
+        &self,
+        buffer_id: BufferId,
+        cx: &App,
+    ) -> Option<RemoteBufferSyncState> {
+        self.buffer_store.read(cx).remote_sync_state(buffer_id)
+    }
+
     #[inline]
     pub fn remote_connection_options(&self, cx: &App) -> Option<RemoteConnectionOptions> {
         self.remote_client
@@ -2528,6 +2628,7 @@ impl Project {
             self.client_state = ProjectClientState::Local;
             self.collaborators.clear();
             self.client_subscriptions.clear();
+            self.terminals.shared.clear();
             self.worktree_store.update(cx, |store, cx| {
                 store.unshared(cx);
             });
@@ -2597,6 +2698,7 @@ impl Project {
         {
             *sharing_has_stopped = true;
             self.collaborators.clear();
+            self.close_remote_shared_terminals(cx);
             self.worktree_store.update(cx, |store, cx| {
                 store.disconnected_from_host(cx);
             });
@@ -3276,6 +3378,16 @@ impl Project {
                 });
                 cx.emit(Event::DisconnectedFromSshRemote);
             }
+            remote::RemoteClientEvent::WentOffline => {
+                self.buffer_store.update(cx, |buffer_store, cx| {
+                    buffer_store.set_remote_offline(true, cx)
+                });
+            }
+            remote::RemoteClientEvent::Reconnected => {
+                self.buffer_store.update(cx, |buffer_store, cx| {
+                    buffer_store.set_remote_offline(false, cx)
+                });
+            }
         }
     }
 
@@ -3838,6 +3950,51 @@ impl Project {
         )
     }
 
+    pub fn document_links(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<DocumentLink>>> {
+        self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::FirstCapable,
+            GetDocumentLinks,
+            cx,
+        )
+    }
+
+    /// Requests the target of a document link, for servers that resolve links lazily.
+    pub fn resolve_document_link(
+        &self,
+        link: DocumentLink,
+        buffer: Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<DocumentLink>> {
+        self.lsp_store.update(cx, |lsp_store, cx| {
+            lsp_store.resolve_document_link(link, buffer, cx)
+        })
+    }
+
+    /// Returns, for each position, the ranges that a language server considers
+    /// to contain it, from the innermost to the outermost.
+    pub fn selection_ranges<T: ToPointUtf16>(
+        &mut self,
+        buffer: &Entity<Buffer>,
+        positions: impl IntoIterator<Item = T>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<Vec<Vec<Range<Anchor>>>>> {
+        let positions = positions
+            .into_iter()
+            .map(|position| position.to_point_utf16(buffer.read(cx)))
+            .collect();
+        self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::FirstCapable,
+            GetSelectionRanges { positions },
+            cx,
+        )
+    }
+
     pub fn document_symbols(
         &mut self,
         buffer: &Entity<Buffer>,
@@ -4647,6 +4804,7 @@ impl Project {
                 buffer_store.forget_shared_buffers_for(&collaborator.peer_id);
             });
             this.breakpoint_store.read(cx).broadcast();
+            this.broadcast_shared_terminals(cx);
             cx.emit(Event::CollaboratorJoined(collaborator.peer_id));
             this.collaborators
                 .insert(collaborator.peer_id, collaborator);
@@ -5353,6 +5511,11 @@ impl Project {
         &self.agent_server_store
     }
 
+    /// Returns the store of forwarded ports, for projects on a remote server.
+    pub fn port_forward_store(&self) -> Option<&Entity<PortForwardStore>> {
+        self.port_forward_store.as_ref()
+    }
+
     #[cfg(test)]
     fn git_scans_complete(&self, cx: &Context<Self>) -> Task<()> {
         cx.spawn(async move |this, cx| {
@@ -5658,7 +5821,17 @@ impl ProjectItem for Buffer {
         path: &ProjectPath,
         cx: &mut App,
     ) -> Option<Task<Result<Entity<Self>>>> {
-        Some(project.update(cx, |project, cx| project.open_buffer(path.clone(), cx)))
+        let open_buffer = project.update(cx, |project, cx| project.open_buffer(path.clone(), cx));
+        Some(cx.background_spawn(async move {
+            // Binary files are left to the items that can open them.
+            open_buffer.await.map_err(|error| {
+                if error.error_code() == ErrorCode::BinaryFile {
+                    UnsupportedProjectItem.into()
+                } else {
+                    error
+                }
+            })
+        }))
     }
 
     fn entry_id(&self, _cx: &App) -> Option<ProjectEntryId> {
@@ -90,6 +90,12 @@ pub struct SessionSettings {
     ///
     /// Default: false
     pub trust_all_worktrees: bool,
+    /// Whether to keep editing remote buffers when the connection to the remote
+    /// host is lost. Saves made while offline are queued and written back once
+    /// the connection is re-established.
+    ///
+    /// Default: true
+    pub offline_remote_editing: bool,
 }
 
 #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
@@ -578,6 +584,7 @@ impl Settings for ProjectSettings {
             session: SessionSettings {
                 restore_unsaved_buffers: content.session.unwrap().restore_unsaved_buffers.unwrap(),
                 trust_all_worktrees: content.session.unwrap().trust_all_worktrees.unwrap(),
+                offline_remote_editing: content.session.unwrap().offline_remote_editing.unwrap(),
             },
         }
     }
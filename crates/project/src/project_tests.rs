@@ -2,6 +2,7 @@
 
 use crate::{
     Event,
+    bookmark_store::SerializedBookmark,
     git_store::{GitStoreEvent, RepositoryEvent, StatusEntry, pending_op},
     task_inventory::TaskContexts,
     task_store::TaskSettingsLocation,
@@ -5054,6 +5055,98 @@ async fn test_buffer_line_endings(cx: &mut gpui::TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_buffer_encoding(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(path!("/dir"), json!({})).await;
+    // "Café" in Windows-1252.
+    fs.insert_file(path!("/dir/file"), vec![0x43, 0x61, 0x66, 0xe9])
+        .await;
+
+    let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+    let buffer = project
+        .update(cx, |p, cx| p.open_local_buffer(path!("/dir/file"), cx))
+        .await
+        .unwrap();
+    buffer.update(cx, |buffer, _| {
+        assert_eq!(buffer.text(), "Café");
+        assert_eq!(buffer.encoding(), encoding_rs::WINDOWS_1252);
+        assert!(!buffer.has_bom());
+    });
+
+    // Reopen the file with another encoding.
+    buffer.update(cx, |buffer, cx| {
+        buffer.update_encoding(encoding_rs::MACINTOSH, false, cx);
+    });
+    project
+        .update(cx, |project, cx| {
+            project.reload_buffers(HashSet::from_iter([buffer.clone()]), false, cx)
+        })
+        .await
+        .unwrap();
+    buffer.update(cx, |buffer, _| {
+        assert_eq!(buffer.text(), "CafÈ");
+        assert!(!buffer.is_dirty());
+    });
+
+    // Save the file with another encoding and a byte order mark.
+    buffer.update(cx, |buffer, cx| {
+        buffer.update_encoding(encoding_rs::UTF_16LE, true, cx);
+    });
+    project
+        .update(cx, |project, cx| project.save_buffer(buffer.clone(), cx))
+        .await
+        .unwrap();
+    assert_eq!(
+        fs.load_bytes(path!("/dir/file").as_ref()).await.unwrap(),
+        [0xff, 0xfe, 0x43, 0x00, 0x61, 0x00, 0x66, 0x00, 0xc8, 0x00]
+    );
+
+    // Text that the encoding can't represent isn't saved.
+    buffer.update(cx, |buffer, cx| {
+        buffer.set_text("こんにちは", cx);
+        buffer.update_encoding(encoding_rs::WINDOWS_1252, false, cx);
+    });
+    assert!(
+        project
+            .update(cx, |project, cx| project.save_buffer(buffer.clone(), cx))
+            .await
+            .is_err()
+    );
+}
+
+#[gpui::test]
+async fn test_opening_binary_file(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(path!("/dir"), json!({})).await;
+    fs.insert_file(path!("/dir/a.bin"), b"\x7fELF\x02\x01\x01\x00".to_vec())
+        .await;
+    let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+
+    // Binary files can't be opened as buffers, which lets other items open them.
+    let error = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer(path!("/dir/a.bin"), cx)
+        })
+        .await
+        .unwrap_err();
+    assert_eq!(error.error_code(), ErrorCode::BinaryFile);
+
+    let project_path = project.read_with(cx, |project, cx| {
+        project.find_project_path(path!("/dir/a.bin"), cx).unwrap()
+    });
+    let error = cx
+        .update(|cx| <Buffer as ProjectItem>::try_open(&project, &project_path, cx))
+        .unwrap()
+        .await
+        .unwrap_err();
+    assert!(error.is::<UnsupportedProjectItem>());
+}
+
 #[gpui::test]
 async fn test_grouped_diagnostics(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -10922,3 +11015,118 @@ async fn test_git_worktree_remove(cx: &mut gpui::TestAppContext) {
     });
     assert!(active_repo_path.is_none());
 }
+
+#[gpui::test]
+async fn test_bookmarks(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        path!("/dir"),
+        json!({
+            "a.txt": "one\ntwo\nthree\nfour\n",
+            "b.txt": "alpha\nbeta\n",
+        }),
+    )
+    .await;
+    let project = Project::test(fs, [path!("/dir").as_ref()], cx).await;
+    let worktree_id = project.read_with(cx, |project, cx| {
+        project.worktrees(cx).next().unwrap().read(cx).id()
+    });
+    let bookmark_store = project.read_with(cx, |project, _| project.bookmark_store());
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer(path!("/dir/a.txt"), cx)
+        })
+        .await
+        .unwrap();
+
+    let anchor_at = |row: u32, column: u32, cx: &mut gpui::TestAppContext| {
+        buffer.read_with(cx, |buffer, _| {
+            buffer.anchor_before(Point::new(row, column))
+        })
+    };
+    let rows_and_labels = |cx: &mut gpui::TestAppContext| {
+        bookmark_store.read_with(cx, |store, cx| {
+            store
+                .all_bookmarks(cx)
+                .into_iter()
+                .map(|entry| {
+                    (
+                        entry.path.path.as_unix_str().to_string(),
+                        entry.row,
+                        entry.bookmark.label.map(|label| label.to_string()),
+                    )
+                })
+                .collect::<Vec<_>>()
+        })
+    };
+
+    let position = anchor_at(2, 3, cx);
+    bookmark_store.update(cx, |store, cx| store.toggle_bookmark(&buffer, position, cx));
+    let position = anchor_at(1, 0, cx);
+    bookmark_store.update(cx, |store, cx| {
+        store.set_bookmark_label(&buffer, position, Some("second".into()), cx)
+    });
+    assert_eq!(
+        rows_and_labels(cx),
+        [
+            ("a.txt".to_string(), 1, Some("second".to_string())),
+            ("a.txt".to_string(), 2, None),
+        ]
+    );
+
+    // Bookmarks follow the lines they were set on.
+    buffer.update(cx, |buffer, cx| {
+        buffer.edit([(Point::new(0, 0)..Point::new(0, 0), "zero\n")], None, cx)
+    });
+    assert_eq!(
+        rows_and_labels(cx),
+        [
+            ("a.txt".to_string(), 2, Some("second".to_string())),
+            ("a.txt".to_string(), 3, None),
+        ]
+    );
+
+    let position = anchor_at(3, 1, cx);
+    bookmark_store.update(cx, |store, cx| store.toggle_bookmark(&buffer, position, cx));
+    let serialized =
+        bookmark_store.read_with(cx, |store, cx| store.serialized_bookmarks(worktree_id, cx));
+    assert_eq!(
+        serialized,
+        [SerializedBookmark {
+            path: rel_path("a.txt").into(),
+            row: 2,
+            label: Some("second".to_string()),
+        }]
+    );
+
+    bookmark_store
+        .update(cx, |store, cx| {
+            store.load_serialized_bookmarks(
+                worktree_id,
+                vec![
+                    SerializedBookmark {
+                        path: rel_path("b.txt").into(),
+                        row: 1,
+                        label: None,
+                    },
+                    SerializedBookmark {
+                        path: rel_path("b.txt").into(),
+                        row: 100,
+                        label: None,
+                    },
+                ],
+                cx,
+            )
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        rows_and_labels(cx),
+        [
+            ("a.txt".to_string(), 2, Some("second".to_string())),
+            ("b.txt".to_string(), 1, None),
+        ]
+    );
+}
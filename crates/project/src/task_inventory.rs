@@ -1,5 +1,7 @@
 //! Project-wide storage of the tasks available, capable of updating itself from the sources set.
 
+mod extension;
+
 use std::{
     borrow::Cow,
     cmp::{self, Reverse},
@@ -28,7 +30,14 @@ use text::{BufferId, Point, ToPoint};
 use util::{NumericPrefixWithSuffix, ResultExt as _, post_inc, rel_path::RelPath};
 use worktree::WorktreeId;
 
-use crate::{task_store::TaskSettingsLocation, worktree_store::WorktreeStore};
+use crate::{
+    environment::ProjectEnvironment, task_store::TaskSettingsLocation,
+    worktree_store::WorktreeStore,
+};
+
+pub fn init(cx: &mut App) {
+    extension::init(cx);
+}
 
 #[derive(Clone, Debug, Default)]
 pub struct DebugScenarioContext {
@@ -43,6 +52,7 @@ pub struct Inventory {
     last_scheduled_scenarios: VecDeque<(DebugScenario, DebugScenarioContext)>,
     templates_from_settings: InventoryFor<TaskTemplate>,
     scenarios_from_settings: InventoryFor<DebugScenario>,
+    extension_context: Option<(WeakEntity<WorktreeStore>, WeakEntity<ProjectEnvironment>)>,
 }
 
 impl std::fmt::Debug for Inventory {
@@ -241,11 +251,32 @@ impl TaskSourceKind {
 
 impl Inventory {
     pub fn new(cx: &mut App) -> Entity<Self> {
+        Self::new_internal(None, cx)
+    }
+
+    /// Creates an inventory that also lists the tasks extensions provide for the worktrees in the store.
+    /// The extensions see each worktree's environment, as loaded by `environment`.
+    pub fn for_worktree_store(
+        worktree_store: &Entity<WorktreeStore>,
+        environment: &Entity<ProjectEnvironment>,
+        cx: &mut App,
+    ) -> Entity<Self> {
+        Self::new_internal(
+            Some((worktree_store.downgrade(), environment.downgrade())),
+            cx,
+        )
+    }
+
+    fn new_internal(
+        extension_context: Option<(WeakEntity<WorktreeStore>, WeakEntity<ProjectEnvironment>)>,
+        cx: &mut App,
+    ) -> Entity<Self> {
         cx.new(|_| Self {
             last_scheduled_tasks: VecDeque::default(),
             last_scheduled_scenarios: VecDeque::default(),
             templates_from_settings: InventoryFor::default(),
             scenarios_from_settings: InventoryFor::default(),
+            extension_context,
         })
     }
 
@@ -392,17 +423,19 @@ impl Inventory {
         let task_source_kind = language.as_ref().map(|language| TaskSourceKind::Language {
             name: language.name().into(),
         });
-        let language_tasks = language
-            .filter(|language| {
-                language_settings(Some(language.name()), file.as_ref(), cx)
-                    .tasks
-                    .enabled
-            })
-            .and_then(|language| {
-                language
-                    .context_provider()
-                    .map(|provider| provider.associated_tasks(file, cx))
-            });
+        let language = language.filter(|language| {
+            language_settings(Some(language.name()), file.as_ref(), cx)
+                .tasks
+                .enabled
+        });
+        let extension_tasks = language
+            .as_ref()
+            .and_then(|language| self.extension_tasks(language, file.as_ref(), worktree, cx));
+        let language_tasks = language.and_then(|language| {
+            language
+                .context_provider()
+                .map(|provider| provider.associated_tasks(file, cx))
+        });
         cx.background_spawn(async move {
             if let Some(t) = language_tasks {
                 worktree_tasks.extend(t.await.into_iter().flat_map(|tasks| {
@@ -412,6 +445,13 @@ impl Inventory {
                         .filter_map(|task| Some((task_source_kind.clone()?, task)))
                 }));
             }
+            if let Some(t) = extension_tasks {
+                worktree_tasks.extend(
+                    t.await
+                        .into_iter()
+                        .filter_map(|task| Some((task_source_kind.clone()?, task))),
+                );
+            }
             worktree_tasks.extend(global_tasks);
             worktree_tasks
         })
@@ -476,37 +516,40 @@ impl Inventory {
 
         let not_used_score = post_inc(&mut lru_score);
         let global_tasks = self.global_templates_from_settings().collect::<Vec<_>>();
-        let associated_tasks = language
-            .filter(|language| {
-                language_settings(Some(language.name()), file.as_ref(), cx)
-                    .tasks
-                    .enabled
-            })
-            .and_then(|language| {
-                language
-                    .context_provider()
-                    .map(|provider| provider.associated_tasks(file, cx))
-            });
+        let language = language.filter(|language| {
+            language_settings(Some(language.name()), file.as_ref(), cx)
+                .tasks
+                .enabled
+        });
+        let extension_tasks = language
+            .as_ref()
+            .and_then(|language| self.extension_tasks(language, file.as_ref(), worktree, cx));
+        let associated_tasks = language.and_then(|language| {
+            language
+                .context_provider()
+                .map(|provider| provider.associated_tasks(file, cx))
+        });
         let worktree_tasks = worktree
             .into_iter()
             .flat_map(|worktree| self.worktree_templates_from_settings(worktree))
             .collect::<Vec<_>>();
         let task_contexts = task_contexts.clone();
         cx.background_spawn(async move {
-            let language_tasks = if let Some(task) = associated_tasks {
-                task.await.map(|templates| {
-                    templates
-                        .0
-                        .into_iter()
-                        .flat_map(|task| Some((task_source_kind.clone()?, task)))
-                })
+            let mut language_tasks = if let Some(task) = associated_tasks {
+                task.await.map(|templates| templates.0).unwrap_or_default()
             } else {
-                None
+                Vec::new()
             };
+            if let Some(task) = extension_tasks {
+                language_tasks.extend(task.await);
+            }
+            let language_tasks = language_tasks
+                .into_iter()
+                .flat_map(|task| Some((task_source_kind.clone()?, task)));
 
             let worktree_tasks = worktree_tasks
                 .into_iter()
-                .chain(language_tasks.into_iter().flatten())
+                .chain(language_tasks)
                 .chain(global_tasks);
 
             let new_resolved_tasks = worktree_tasks
@@ -635,6 +678,24 @@ impl Inventory {
         self.templates_from_settings.worktree_scenarios(worktree)
     }
 
+    fn extension_tasks(
+        &self,
+        language: &Language,
+        file: Option<&Arc<dyn File>>,
+        worktree: Option<WorktreeId>,
+        cx: &App,
+    ) -> Option<Task<Vec<TaskTemplate>>> {
+        let (worktree_store, environment) = self.extension_context.as_ref()?;
+        extension::tasks_for_language(
+            &worktree_store.upgrade()?,
+            &environment.upgrade()?,
+            language.name(),
+            file,
+            worktree,
+            cx,
+        )
+    }
+
     /// Updates in-memory task metadata from the JSON string given.
     /// Will fail if the JSON is not a valid array of objects, but will continue if any object will not parse into a [`TaskTemplate`].
     ///
@@ -0,0 +1,235 @@
+use std::sync::Arc;
+
+use anyhow::{Context as _, Result};
+use async_trait::async_trait;
+use collections::{BTreeMap, HashMap};
+use extension::{Extension, ExtensionHostProxy, ExtensionTaskProviderProxy, WorktreeDelegate};
+use fs::Fs;
+use futures::future::{Shared, join_all};
+use gpui::{App, Entity, Global, Task};
+use language::{File, LanguageName};
+use parking_lot::RwLock;
+use task::TaskTemplate;
+use util::{ResultExt as _, rel_path::RelPath};
+use worktree::{Snapshot, Worktree, WorktreeId};
+
+use crate::{environment::ProjectEnvironment, worktree_store::WorktreeStore};
+
+pub fn init(cx: &mut App) {
+    let proxy = ExtensionHostProxy::default_global(cx);
+    proxy.register_task_provider_proxy(ExtensionTaskProviderRegistryProxy {
+        registry: ExtensionTaskProviderRegistry::global(cx).clone(),
+    });
+}
+
+struct ExtensionTaskProvider {
+    extension: Arc<dyn Extension>,
+    languages: Vec<LanguageName>,
+}
+
+#[derive(Clone, Default)]
+/// Stores the task providers registered by extensions, keyed by their names.
+pub struct ExtensionTaskProviderRegistry(Arc<RwLock<BTreeMap<Arc<str>, ExtensionTaskProvider>>>);
+impl Global for ExtensionTaskProviderRegistry {}
+
+impl ExtensionTaskProviderRegistry {
+    pub fn global(cx: &mut App) -> &mut Self {
+        cx.default_global::<Self>()
+    }
+
+    fn providers_for_language(
+        &self,
+        language_name: &LanguageName,
+    ) -> Vec<(Arc<str>, Arc<dyn Extension>)> {
+        self.0
+            .read()
+            .iter()
+            .filter(|(_, provider)| provider.languages.contains(language_name))
+            .map(|(name, provider)| (name.clone(), provider.extension.clone()))
+            .collect()
+    }
+}
+
+struct ExtensionTaskProviderRegistryProxy {
+    registry: ExtensionTaskProviderRegistry,
+}
+
+impl ExtensionTaskProviderProxy for ExtensionTaskProviderRegistryProxy {
+    fn register_task_provider(
+        &self,
+        extension: Arc<dyn Extension>,
+        provider_name: Arc<str>,
+        languages: Vec<LanguageName>,
+    ) {
+        self.registry.0.write().insert(
+            provider_name,
+            ExtensionTaskProvider {
+                extension,
+                languages,
+            },
+        );
+    }
+
+    fn unregister_task_provider(&self, provider_name: Arc<str>) {
+        self.registry.0.write().remove(&provider_name);
+    }
+}
+
+/// Lets extension task providers inspect a local worktree.
+///
+/// Extensions see the worktree's project environment, the same one its language servers get.
+struct ExtensionWorktree {
+    worktree: Snapshot,
+    fs: Arc<dyn Fs>,
+    load_shell_env_task: Shared<Task<Option<HashMap<String, String>>>>,
+}
+
+impl ExtensionWorktree {
+    fn new(
+        worktree: &Entity<Worktree>,
+        fs: Arc<dyn Fs>,
+        environment: &Entity<ProjectEnvironment>,
+        cx: &mut App,
+    ) -> Self {
+        let load_shell_env_task = environment.update(cx, |environment, cx| {
+            environment.worktree_environment(worktree.clone(), cx)
+        });
+        Self {
+            worktree: worktree.read(cx).snapshot(),
+            fs,
+            load_shell_env_task,
+        }
+    }
+}
+
+#[async_trait]
+impl WorktreeDelegate for ExtensionWorktree {
+    fn id(&self) -> u64 {
+        self.worktree.id().to_proto()
+    }
+
+    fn root_path(&self) -> String {
+        self.worktree.abs_path().to_string_lossy().into_owned()
+    }
+
+    async fn read_text_file(&self, path: &RelPath) -> Result<String> {
+        let entry = self
+            .worktree
+            .entry_for_path(path)
+            .with_context(|| format!("no worktree entry for path {path:?}"))?;
+        let abs_path = self.worktree.absolutize(&entry.path);
+        self.fs.load(&abs_path).await
+    }
+
+    async fn which(&self, binary_name: String) -> Option<String> {
+        let shell_path = self.load_shell_env_task.clone().await?.get("PATH").cloned();
+        which::which_in(
+            binary_name,
+            shell_path.as_ref(),
+            self.worktree.abs_path().as_ref(),
+        )
+        .ok()
+        .map(|path| path.to_string_lossy().into_owned())
+    }
+
+    async fn shell_env(&self) -> Vec<(String, String)> {
+        let task = self.load_shell_env_task.clone();
+        task.await.unwrap_or_default().into_iter().collect()
+    }
+}
+
+/// Asks the extension task providers registered for the language for their tasks.
+///
+/// Extensions run on the client, so only local worktrees are offered to them.
+pub(super) fn tasks_for_language(
+    worktree_store: &Entity<WorktreeStore>,
+    environment: &Entity<ProjectEnvironment>,
+    language_name: LanguageName,
+    file: Option<&Arc<dyn File>>,
+    worktree_id: Option<WorktreeId>,
+    cx: &App,
+) -> Option<Task<Vec<TaskTemplate>>> {
+    let providers = cx
+        .try_global::<ExtensionTaskProviderRegistry>()?
+        .providers_for_language(&language_name);
+    if providers.is_empty() {
+        return None;
+    }
+
+    let (worktree_id, file_path) = match file {
+        Some(file) => (file.worktree_id(cx), Some(file.path().clone())),
+        None => (worktree_id?, None),
+    };
+    let worktree_store = worktree_store.read(cx);
+    let worktree = worktree_store.worktree_for_id(worktree_id, cx)?;
+    let fs = worktree_store.fs()?;
+    let environment = environment.clone();
+
+    // Loading the worktree's environment updates the project environment,
+    // which can't be done from here.
+    Some(cx.spawn(async move |cx| {
+        let Ok(worktree) = cx.update(|cx| ExtensionWorktree::new(&worktree, fs, &environment, cx))
+        else {
+            return Vec::new();
+        };
+        let worktree: Arc<dyn WorktreeDelegate> = Arc::new(worktree);
+        let tasks = join_all(providers.into_iter().map(|(provider_name, extension)| {
+            let language_name = language_name.clone();
+            let file_path = file_path.clone();
+            let worktree = worktree.clone();
+            async move {
+                let tasks = extension
+                    .tasks_for_language(provider_name.clone(), language_name, file_path, worktree)
+                    .await;
+                tasks.with_context(|| format!("getting tasks from task provider {provider_name}"))
+            }
+        }))
+        .await;
+
+        tasks
+            .into_iter()
+            .filter_map(|tasks| tasks.log_err())
+            .flat_map(|tasks| tasks.0)
+            .collect()
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Project;
+    use fs::FakeFs;
+    use gpui::TestAppContext;
+    use serde_json::json;
+    use settings::SettingsStore;
+    use util::path;
+
+    #[gpui::test]
+    async fn test_extension_worktree_uses_project_environment(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+        });
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(path!("/project"), json!({ "Makefile": "all:\n" }))
+            .await;
+        let project = Project::test(fs.clone(), [path!("/project").as_ref()], cx).await;
+        let worktree = project.read_with(cx, |project, cx| project.worktrees(cx).next().unwrap());
+        // Tests use an empty project environment, which tells it apart from Zed's own.
+        let environment = project.read_with(cx, |project, _| project.environment().clone());
+
+        let extension_worktree =
+            cx.update(|cx| ExtensionWorktree::new(&worktree, fs.clone(), &environment, cx));
+        assert_eq!(extension_worktree.shell_env().await, Vec::new());
+        // Binaries are looked up on the project's `PATH`, not Zed's.
+        assert_eq!(extension_worktree.which("sh".to_string()).await, None);
+        assert_eq!(
+            extension_worktree
+                .read_text_file(RelPath::unix("Makefile").unwrap())
+                .await
+                .unwrap(),
+            "all:\n"
+        );
+    }
+}
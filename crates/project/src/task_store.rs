@@ -168,9 +168,9 @@ impl TaskStore {
         Self::Functional(StoreState {
             mode: StoreMode::Local {
                 downstream_client: None,
-                environment,
+                environment: environment.clone(),
             },
-            task_inventory: Inventory::new(cx),
+            task_inventory: Inventory::for_worktree_store(&worktree_store, &environment, cx),
             buffer_store,
             toolchain_store,
             worktree_store,
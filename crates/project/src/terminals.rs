@@ -1,27 +1,67 @@
-use anyhow::Result;
+use anyhow::{Context as _, Result};
+use client::{TypedEnvelope, proto};
 use collections::HashMap;
-use gpui::{App, AppContext as _, Context, Entity, Task, WeakEntity};
+use gpui::{App, AppContext as _, AsyncApp, Context, Entity, Subscription, Task, WeakEntity};
 
-use futures::{FutureExt, future::Shared};
+use futures::{FutureExt, StreamExt as _, channel::mpsc, future::Shared};
 use itertools::Itertools as _;
-use language::LanguageName;
+use language::{Capability, LanguageName};
 use remote::RemoteClient;
 use settings::{Settings, SettingsLocation};
 use smol::channel::bounded;
 use std::{
     path::{Path, PathBuf},
     sync::Arc,
+    time::Duration,
 };
 use task::{Shell, ShellBuilder, ShellKind, SpawnInTerminal};
 use terminal::{
-    TaskState, TaskStatus, Terminal, TerminalBuilder, terminal_settings::TerminalSettings,
+    ScreenSnapshot, TaskState, TaskStatus, Terminal, TerminalBuilder,
+    terminal_settings::{AlternateScroll, CursorShape, TerminalSettings},
 };
-use util::{command::new_std_command, get_default_system_shell, maybe, rel_path::RelPath};
+use util::{
+    ResultExt as _, command::new_std_command, get_default_system_shell, maybe, rel_path::RelPath,
+};
+
+use crate::{Event, Project, ProjectClientState, ProjectPath};
 
-use crate::{Project, ProjectPath};
+/// How often the screen of a shared terminal is sent to collaborators, at most.
+const SHARED_TERMINAL_UPDATE_INTERVAL: Duration = Duration::from_millis(100);
 
+/// Set in terminals on the remote host, and inherited by the processes started
+/// from them, so that the remote server can tell which ports they listen on.
+pub const REMOTE_TERMINAL_ID_ENV_VAR: &str = "ZED_REMOTE_TERMINAL_ID";
+
+#[derive(Default)]
 pub struct Terminals {
     pub(crate) local_handles: Vec<WeakEntity<terminal::Terminal>>,
+    /// The terminals shared with collaborators by the host, keyed by their id.
+    pub(crate) shared: HashMap<u64, SharedTerminal>,
+    /// The terminals the host shared with this guest, keyed by their id.
+    pub(crate) remote_shared: HashMap<u64, RemoteSharedTerminal>,
+}
+
+/// How collaborators can use a terminal shared with them.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TerminalSharing {
+    /// Collaborators can watch the terminal.
+    ReadOnly,
+    /// Collaborators can also type into the terminal.
+    ReadWrite,
+}
+
+pub(crate) struct SharedTerminal {
+    terminal: WeakEntity<Terminal>,
+    sharing: TerminalSharing,
+    last_snapshot: ScreenSnapshot,
+    last_title: String,
+    _subscriptions: Vec<Subscription>,
+    _send_updates: Task<()>,
+}
+
+pub(crate) struct RemoteSharedTerminal {
+    terminal: Entity<Terminal>,
+    forward_input: Option<Task<()>>,
 }
 
 impl Project {
@@ -413,6 +453,12 @@ impl Project {
         cx: &mut Context<'_, Project>,
         cwd: Option<PathBuf>,
     ) -> Task<Result<Entity<Terminal>>> {
+        // Terminals shared by the host of a collaborative project only mirror its screen.
+        if self.is_via_collab() {
+            return Task::ready(Err(anyhow::anyhow!(
+                "cannot open terminals in a project shared by another user"
+            )));
+        }
         // We cannot clone the task's terminal, as it will effectively re-spawn the task, which might not be desirable.
         // For now, create a new shell instead.
         if terminal.read(cx).task().is_some() {
@@ -536,6 +582,260 @@ impl Project {
         &self.terminals.local_handles
     }
 
+    /// Shares a terminal of this project with its collaborators, or changes how
+    /// it is shared.
+    pub fn share_terminal(
+        &mut self,
+        terminal: &Entity<Terminal>,
+        sharing: TerminalSharing,
+        cx: &mut Context<Self>,
+    ) -> Result<()> {
+        anyhow::ensure!(
+            matches!(self.client_state, ProjectClientState::Shared { .. }),
+            "only the host of a shared project can share terminals"
+        );
+        let terminal_id = terminal.entity_id().as_u64();
+        if let Some(shared) = self.terminals.shared.get_mut(&terminal_id) {
+            shared.sharing = sharing;
+            self.send_shared_terminal_update(terminal_id, true, cx);
+            return Ok(());
+        }
+
+        let (changes_tx, mut changes_rx) = mpsc::unbounded();
+        changes_tx.unbounded_send(()).ok();
+        let subscriptions = vec![
+            cx.subscribe(terminal, move |_, _, event, _| {
+                if matches!(
+                    event,
+                    terminal::Event::Wakeup | terminal::Event::TitleChanged
+                ) {
+                    changes_tx.unbounded_send(()).ok();
+                }
+            }),
+            cx.observe_release(terminal, move |this, _, cx| {
+                this.unshare_terminal_by_id(terminal_id, cx);
+            }),
+        ];
+        let send_updates = cx.spawn(async move |this, cx| {
+            while changes_rx.next().await.is_some() {
+                cx.background_executor()
+                    .timer(SHARED_TERMINAL_UPDATE_INTERVAL)
+                    .await;
+                while let Ok(Some(())) = changes_rx.try_next() {}
+                if this
+                    .update(cx, |this, cx| {
+                        this.send_shared_terminal_update(terminal_id, false, cx)
+                    })
+                    .is_err()
+                {
+                    break;
+                }
+            }
+        });
+        self.terminals.shared.insert(
+            terminal_id,
+            SharedTerminal {
+                terminal: terminal.downgrade(),
+                sharing,
+                last_snapshot: ScreenSnapshot::default(),
+                last_title: String::new(),
+                _subscriptions: subscriptions,
+                _send_updates: send_updates,
+            },
+        );
+        Ok(())
+    }
+
+    pub fn unshare_terminal(&mut self, terminal: &Entity<Terminal>, cx: &mut Context<Self>) {
+        self.unshare_terminal_by_id(terminal.entity_id().as_u64(), cx);
+    }
+
+    /// Returns how a terminal of this project is shared with collaborators, if it is.
+    pub fn terminal_sharing(&self, terminal: &Entity<Terminal>) -> Option<TerminalSharing> {
+        self.terminals
+            .shared
+            .get(&terminal.entity_id().as_u64())
+            .map(|shared| shared.sharing)
+    }
+
+    fn unshare_terminal_by_id(&mut self, terminal_id: u64, cx: &mut Context<Self>) {
+        if self.terminals.shared.remove(&terminal_id).is_some()
+            && let Some(project_id) = self.remote_id()
+        {
+            self.collab_client
+                .send(proto::UnshareTerminal {
+                    project_id,
+                    terminal_id,
+                })
+                .log_err();
+            cx.notify();
+        }
+    }
+
+    /// Closes the terminals the host shared with this guest, such as when the
+    /// guest leaves the project.
+    pub(crate) fn close_remote_shared_terminals(&mut self, cx: &mut App) {
+        for (_, shared) in self.terminals.remote_shared.drain() {
+            shared
+                .terminal
+                .update(cx, |_, cx| cx.emit(terminal::Event::CloseTerminal));
+        }
+    }
+
+    /// Sends the screen of all shared terminals, such as to a collaborator who just joined.
+    pub(crate) fn broadcast_shared_terminals(&mut self, cx: &mut Context<Self>) {
+        let terminal_ids = self.terminals.shared.keys().copied().collect::<Vec<_>>();
+        for terminal_id in terminal_ids {
+            self.send_shared_terminal_update(terminal_id, true, cx);
+        }
+    }
+
+    fn send_shared_terminal_update(&mut self, terminal_id: u64, force: bool, cx: &App) {
+        let Some(project_id) = self.remote_id() else {
+            return;
+        };
+        let Some(shared) = self.terminals.shared.get_mut(&terminal_id) else {
+            return;
+        };
+        let Some(terminal) = shared.terminal.upgrade() else {
+            return;
+        };
+        let terminal = terminal.read(cx);
+        let snapshot = terminal.screen_snapshot();
+        let title = terminal.title(false);
+        if !force && snapshot == shared.last_snapshot && title == shared.last_title {
+            return;
+        }
+
+        let scrolled_lines = terminal::scrolled_lines(&shared.last_snapshot.lines, &snapshot.lines);
+        self.collab_client
+            .send(proto::UpdateSharedTerminal {
+                project_id,
+                terminal_id,
+                title: title.clone(),
+                read_write: shared.sharing == TerminalSharing::ReadWrite,
+                lines: snapshot.lines.clone(),
+                scrolled_lines: scrolled_lines as u32,
+                cursor_line: snapshot.cursor_line as u32,
+                cursor_column: snapshot.cursor_column as u32,
+            })
+            .log_err();
+        shared.last_snapshot = snapshot;
+        shared.last_title = title;
+    }
+
+    pub(crate) async fn handle_update_shared_terminal(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::UpdateSharedTerminal>,
+        mut cx: AsyncApp,
+    ) -> Result<()> {
+        this.update(&mut cx, |this, cx| {
+            let payload = envelope.payload;
+            let terminal_id = payload.terminal_id;
+            if !this.terminals.remote_shared.contains_key(&terminal_id) {
+                let builder = TerminalBuilder::new_display_only(
+                    CursorShape::default(),
+                    AlternateScroll::On,
+                    None,
+                    0,
+                )?;
+                let terminal = cx.new(|cx| builder.subscribe(cx));
+                this.terminals.remote_shared.insert(
+                    terminal_id,
+                    RemoteSharedTerminal {
+                        terminal: terminal.clone(),
+                        forward_input: None,
+                    },
+                );
+                cx.emit(Event::SharedTerminalOpened(terminal));
+            }
+
+            let read_write = payload.read_write && this.capability() == Capability::ReadWrite;
+            let project_id = this.remote_id().context("project is not shared")?;
+            let client = this.collab_client.clone();
+            let shared = this
+                .terminals
+                .remote_shared
+                .get_mut(&terminal_id)
+                .context("shared terminal not found")?;
+            if read_write != shared.forward_input.is_some() {
+                shared.forward_input = read_write.then(|| {
+                    let (input_tx, mut input_rx) = mpsc::unbounded();
+                    shared.terminal.update(cx, |terminal, _| {
+                        terminal.set_input_forwarder(Some(input_tx))
+                    });
+                    cx.background_spawn(async move {
+                        while let Some(input) = input_rx.next().await {
+                            client
+                                .request(proto::SharedTerminalInput {
+                                    project_id,
+                                    terminal_id,
+                                    input,
+                                })
+                                .await
+                                .log_err();
+                        }
+                    })
+                });
+                if !read_write {
+                    shared
+                        .terminal
+                        .update(cx, |terminal, _| terminal.set_input_forwarder(None));
+                }
+            }
+
+            let snapshot = ScreenSnapshot {
+                lines: payload.lines,
+                cursor_line: payload.cursor_line as usize,
+                cursor_column: payload.cursor_column as usize,
+            };
+            shared.terminal.update(cx, |terminal, cx| {
+                terminal.set_title_override(Some(payload.title), cx);
+                terminal.write_screen_snapshot(&snapshot, payload.scrolled_lines as usize, cx);
+            });
+            anyhow::Ok(())
+        })?
+    }
+
+    pub(crate) async fn handle_unshare_terminal(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::UnshareTerminal>,
+        mut cx: AsyncApp,
+    ) -> Result<()> {
+        this.update(&mut cx, |this, cx| {
+            if let Some(shared) = this
+                .terminals
+                .remote_shared
+                .remove(&envelope.payload.terminal_id)
+            {
+                shared
+                    .terminal
+                    .update(cx, |_, cx| cx.emit(terminal::Event::CloseTerminal));
+            }
+        })
+    }
+
+    pub(crate) async fn handle_shared_terminal_input(
+        this: Entity<Self>,
+        envelope: TypedEnvelope<proto::SharedTerminalInput>,
+        mut cx: AsyncApp,
+    ) -> Result<proto::Ack> {
+        this.update(&mut cx, |this, cx| {
+            let shared = this
+                .terminals
+                .shared
+                .get(&envelope.payload.terminal_id)
+                .context("terminal is not shared")?;
+            anyhow::ensure!(
+                shared.sharing == TerminalSharing::ReadWrite,
+                "terminal is shared read-only"
+            );
+            let terminal = shared.terminal.upgrade().context("terminal was closed")?;
+            terminal.update(cx, |terminal, _| terminal.input(envelope.payload.input));
+            anyhow::Ok(proto::Ack {})
+        })?
+    }
+
     fn resolve_directory_environment(
         &self,
         shell: &str,
@@ -570,6 +870,10 @@ fn create_remote_shell(
 ) -> Result<(Shell, HashMap<String, String>)> {
     // Set default terminfo that does not break the highlighting via ssh.
     env.insert("TERM".to_string(), "xterm-256color".to_string());
+    env.insert(
+        REMOTE_TERMINAL_ID_ENV_VAR.to_string(),
+        remote_client.read(cx).unique_identifier().to_string(),
+    );
 
     let (program, args) = match spawn_command {
         Some((program, args)) => (Some(program.clone()), args),
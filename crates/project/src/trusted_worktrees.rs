@@ -164,6 +164,9 @@ impl From<RemoteConnectionOptions> for RemoteHostLocation {
                 Some(SharedString::new(docker_connection_options.name)),
                 SharedString::new(docker_connection_options.container_id),
             ),
+            RemoteConnectionOptions::Command(command_connection_options) => {
+                (None, SharedString::new(command_connection_options.name))
+            }
         };
         RemoteHostLocation {
             user_name,
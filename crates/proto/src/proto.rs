@@ -95,9 +95,13 @@ messages!(
     (GetDefinitionResponse, Background),
     (GetDocumentHighlights, Background),
     (GetDocumentHighlightsResponse, Background),
+    (GetDocumentLinks, Background),
+    (GetDocumentLinksResponse, Background),
     (GetDocumentSymbols, Background),
     (GetDocumentSymbolsResponse, Background),
     (GetHover, Background),
+    (GetSelectionRanges, Background),
+    (GetSelectionRangesResponse, Background),
     (GetHoverResponse, Background),
     (GetNotifications, Foreground),
     (GetNotificationsResponse, Foreground),
@@ -108,6 +112,8 @@ messages!(
     (GetPermalinkToLine, Foreground),
     (GetProcesses, Background),
     (GetProcessesResponse, Background),
+    (GetListeningPorts, Background),
+    (GetListeningPortsResponse, Background),
     (GetPermalinkToLineResponse, Foreground),
     (GetProjectSymbols, Background),
     (GetProjectSymbolsResponse, Background),
@@ -220,6 +226,8 @@ messages!(
     (ResolveCompletionDocumentationResponse, Background),
     (ResolveInlayHint, Background),
     (ResolveInlayHintResponse, Background),
+    (ResolveDocumentLink, Background),
+    (ResolveDocumentLinkResponse, Background),
     (GetDocumentColor, Background),
     (GetDocumentColorResponse, Background),
     (GetColorPresentation, Background),
@@ -240,6 +248,7 @@ messages!(
     (SetRoomParticipantRole, Foreground),
     (ShareProject, Foreground),
     (ShareProjectResponse, Foreground),
+    (SharedTerminalInput, Foreground),
     (ShowContacts, Foreground),
     (ShutdownRemoteServer, Foreground),
     (Stage, Background),
@@ -259,6 +268,7 @@ messages!(
     (Toast, Background),
     (Unfollow, Foreground),
     (UnshareProject, Foreground),
+    (UnshareTerminal, Foreground),
     (Unstage, Background),
     (Stash, Background),
     (StashPop, Background),
@@ -282,6 +292,7 @@ messages!(
     (UpdateParticipantLocation, Foreground),
     (UpdateProject, Foreground),
     (UpdateProjectCollaborator, Foreground),
+    (UpdateSharedTerminal, Foreground),
     (UpdateUserChannels, Foreground),
     (UpdateWorktree, Foreground),
     (UpdateWorktreeSettings, Foreground),
@@ -380,12 +391,14 @@ request_messages!(
     (GetDeclaration, GetDeclarationResponse),
     (GetImplementation, GetImplementationResponse),
     (GetDocumentHighlights, GetDocumentHighlightsResponse),
+    (GetDocumentLinks, GetDocumentLinksResponse),
     (GetDocumentSymbols, GetDocumentSymbolsResponse),
     (GetHover, GetHoverResponse),
     (GetNotifications, GetNotificationsResponse),
     (GetProjectSymbols, GetProjectSymbolsResponse),
     (GetReferences, GetReferencesResponse),
     (GetSignatureHelp, GetSignatureHelpResponse),
+    (GetSelectionRanges, GetSelectionRangesResponse),
     (OpenUnstagedDiff, OpenUnstagedDiffResponse),
     (OpenUncommittedDiff, OpenUncommittedDiffResponse),
     (GetSupermavenApiKey, GetSupermavenApiKeyResponse),
@@ -435,6 +448,7 @@ request_messages!(
         ResolveCompletionDocumentationResponse
     ),
     (ResolveInlayHint, ResolveInlayHintResponse),
+    (ResolveDocumentLink, ResolveDocumentLinkResponse),
     (GetDocumentColor, GetDocumentColorResponse),
     (GetColorPresentation, GetColorPresentationResponse),
     (RespondToChannelInvite, Ack),
@@ -528,12 +542,14 @@ request_messages!(
     (ToggleLspLogs, Ack),
     (GetDirectoryEnvironment, DirectoryEnvironment),
     (GetProcesses, GetProcessesResponse),
+    (GetListeningPorts, GetListeningPortsResponse),
     (GetAgentServerCommand, AgentServerCommand),
     (RemoteStarted, Ack),
     (GitGetWorktrees, GitWorktreesResponse),
     (GitCreateWorktree, Ack),
     (TrustWorktrees, Ack),
     (RestrictWorktrees, Ack),
+    (SharedTerminalInput, Ack),
 );
 
 lsp_messages!(
@@ -582,11 +598,13 @@ entity_messages!(
     GetDeclaration,
     GetImplementation,
     GetDocumentHighlights,
+    GetDocumentLinks,
     GetDocumentSymbols,
     GetHover,
     GetProjectSymbols,
     GetReferences,
     GetSignatureHelp,
+    GetSelectionRanges,
     OpenUnstagedDiff,
     OpenUncommittedDiff,
     GetTypeDefinition,
@@ -615,6 +633,7 @@ entity_messages!(
     RenameProjectEntry,
     ResolveCompletionDocumentation,
     ResolveInlayHint,
+    ResolveDocumentLink,
     SaveBuffer,
     Stage,
     StartLanguageServer,
@@ -665,6 +684,7 @@ entity_messages!(
     ResolveToolchain,
     GetPathMetadata,
     GetProcesses,
+    GetListeningPorts,
     CancelLanguageServerWork,
     RegisterBufferWithLanguageServers,
     GitShow,
@@ -709,6 +729,9 @@ entity_messages!(
     GitCreateWorktree,
     TrustWorktrees,
     RestrictWorktrees,
+    UpdateSharedTerminal,
+    UnshareTerminal,
+    SharedTerminalInput,
 );
 
 entity_messages!(
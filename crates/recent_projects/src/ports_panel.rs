@@ -0,0 +1,517 @@
+use anyhow::Result;
+use db::kvp::KEY_VALUE_STORE;
+use editor::Editor;
+use gpui::{
+    Action, App, AsyncWindowContext, Context, Entity, EventEmitter, FocusHandle, Focusable, Pixels,
+    SharedString, Subscription, Task, WeakEntity, Window, actions,
+};
+use project::{
+    Fs, Project,
+    port_forward_store::{PortForwardStore, PortForwardStoreEvent},
+};
+use serde::{Deserialize, Serialize};
+use settings::{RegisterSetting, Settings, SettingsStore};
+use std::sync::Arc;
+use ui::{IconButton, ListItem, ListItemSpacing, Tab, Tooltip, prelude::*};
+use util::{ResultExt, TryFutureExt};
+use workspace::{
+    Workspace,
+    dock::{DockPosition, Panel, PanelEvent},
+    notifications::{
+        DetachAndPromptErr, NotificationId, simple_message_notification::MessageNotification,
+    },
+};
+
+const PORTS_PANEL_KEY: &str = "PortsPanel";
+
+actions!(
+    ports_panel,
+    [
+        /// Toggles focus on the ports panel.
+        ToggleFocus
+    ]
+);
+
+#[derive(Debug, RegisterSetting)]
+pub struct PortsPanelSettings {
+    pub button: bool,
+    pub dock: DockPosition,
+    pub default_width: Pixels,
+    pub notify_detected_ports: bool,
+}
+
+impl Settings for PortsPanelSettings {
+    fn from_settings(content: &settings::SettingsContent) -> Self {
+        let panel = content.ports_panel.as_ref().unwrap();
+        Self {
+            button: panel.button.unwrap(),
+            dock: panel.dock.unwrap().into(),
+            default_width: panel.default_width.map(px).unwrap(),
+            notify_detected_ports: panel.notify_detected_ports.unwrap(),
+        }
+    }
+}
+
+pub fn init(cx: &mut App) {
+    cx.observe_new(|workspace: &mut Workspace, _, _| {
+        workspace.register_action(|workspace, _: &ToggleFocus, window, cx| {
+            workspace.toggle_panel_focus::<PortsPanel>(window, cx);
+        });
+    })
+    .detach();
+}
+
+#[derive(Serialize, Deserialize)]
+struct SerializedPortsPanel {
+    width: Option<Pixels>,
+}
+
+/// Lists the ports forwarded from the remote host of the project, along with
+/// the ports listening there that can be forwarded.
+pub struct PortsPanel {
+    fs: Arc<dyn Fs>,
+    workspace: WeakEntity<Workspace>,
+    project: Entity<Project>,
+    port_editor: Entity<Editor>,
+    focus_handle: FocusHandle,
+    width: Option<Pixels>,
+    pending_serialization: Task<Option<()>>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl PortsPanel {
+    pub fn new(
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) -> Entity<Self> {
+        let fs = workspace.app_state().fs.clone();
+        let project = workspace.project().clone();
+        let workspace_handle = workspace.weak_handle();
+
+        cx.new(|cx| {
+            let port_editor = cx.new(|cx| {
+                let mut editor = Editor::single_line(window, cx);
+                editor.set_placeholder_text("Port or host:port", window, cx);
+                editor
+            });
+
+            let mut subscriptions = vec![cx.observe_global::<SettingsStore>(|_, cx| cx.notify())];
+            if let Some(store) = project.read(cx).port_forward_store().cloned() {
+                subscriptions.push(cx.observe(&store, |_, _, cx| cx.notify()));
+                subscriptions.push(cx.subscribe_in(&store, window, Self::on_port_forward_event));
+            }
+
+            Self {
+                fs,
+                workspace: workspace_handle,
+                project,
+                port_editor,
+                focus_handle: cx.focus_handle(),
+                width: None,
+                pending_serialization: Task::ready(None),
+                _subscriptions: subscriptions,
+            }
+        })
+    }
+
+    pub fn load(
+        workspace: WeakEntity<Workspace>,
+        cx: AsyncWindowContext,
+    ) -> Task<Result<Entity<Self>>> {
+        cx.spawn(async move |cx| {
+            let serialized_panel = if let Some(panel) = cx
+                .background_spawn(async move { KEY_VALUE_STORE.read_kvp(PORTS_PANEL_KEY) })
+                .await
+                .log_err()
+                .flatten()
+            {
+                Some(serde_json::from_str::<SerializedPortsPanel>(&panel)?)
+            } else {
+                None
+            };
+
+            workspace.update_in(cx, |workspace, window, cx| {
+                let panel = Self::new(workspace, window, cx);
+                if let Some(serialized_panel) = serialized_panel {
+                    panel.update(cx, |panel, cx| {
+                        panel.width = serialized_panel.width.map(|w| w.round());
+                        cx.notify();
+                    });
+                }
+                panel
+            })
+        })
+    }
+
+    fn serialize(&mut self, cx: &mut Context<Self>) {
+        let width = self.width;
+        self.pending_serialization = cx.background_spawn(
+            async move {
+                KEY_VALUE_STORE
+                    .write_kvp(
+                        PORTS_PANEL_KEY.into(),
+                        serde_json::to_string(&SerializedPortsPanel { width })?,
+                    )
+                    .await?;
+                anyhow::Ok(())
+            }
+            .log_err(),
+        );
+    }
+
+    fn port_forward_store(&self, cx: &App) -> Option<Entity<PortForwardStore>> {
+        self.project.read(cx).port_forward_store().cloned()
+    }
+
+    fn on_port_forward_event(
+        &mut self,
+        store: &Entity<PortForwardStore>,
+        event: &PortForwardStoreEvent,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let PortForwardStoreEvent::PortDetected(port) = *event;
+        if !PortsPanelSettings::get_global(cx).notify_detected_ports {
+            return;
+        }
+
+        struct DetectedPortNotification;
+
+        let store = store.downgrade();
+        self.workspace
+            .update(cx, |workspace, cx| {
+                workspace.show_notification(
+                    NotificationId::composite::<DetectedPortNotification>(SharedString::from(
+                        port.to_string(),
+                    )),
+                    cx,
+                    |cx| {
+                        cx.new(|cx| {
+                            MessageNotification::new(
+                                format!(
+                                    "A process on the remote host is listening on port {port}."
+                                ),
+                                cx,
+                            )
+                            .primary_message("Forward Port")
+                            .primary_icon(IconName::ArrowUpRight)
+                            .primary_on_click(move |window, cx| {
+                                if let Some(store) = store.upgrade() {
+                                    store
+                                        .update(cx, |store, cx| {
+                                            store.forward_port("localhost".into(), port, None, cx)
+                                        })
+                                        .detach_and_prompt_err(
+                                            "Failed to forward port",
+                                            window,
+                                            cx,
+                                            |_, _, _| None,
+                                        );
+                                }
+                            })
+                        })
+                    },
+                );
+            })
+            .ok();
+    }
+
+    fn forward_port(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(store) = self.port_forward_store(cx) else {
+            return;
+        };
+        let text = self.port_editor.read(cx).text(cx);
+        let Some((remote_host, remote_port)) = parse_port_target(&text) else {
+            return;
+        };
+        self.port_editor
+            .update(cx, |editor, cx| editor.set_text("", window, cx));
+        store
+            .update(cx, |store, cx| {
+                store.forward_port(remote_host, remote_port, None, cx)
+            })
+            .detach_and_prompt_err("Failed to forward port", window, cx, |_, _, _| None);
+    }
+
+    fn render_forwards(&self, store: &PortForwardStore, cx: &Context<Self>) -> impl IntoElement {
+        v_flex().children(store.forwards().iter().map(|forward| {
+            let local_port = forward.local_port;
+            let remote = if forward.remote_host == "localhost" {
+                forward.remote_port.to_string()
+            } else {
+                format!("{}:{}", forward.remote_host, forward.remote_port)
+            };
+            ListItem::new(SharedString::from(format!("forward-{local_port}")))
+                .spacing(ListItemSpacing::Sparse)
+                .child(
+                    h_flex()
+                        .gap_2()
+                        .child(Label::new(format!("localhost:{local_port} → {remote}")))
+                        .when(forward.is_static, |this| {
+                            this.child(
+                                Label::new("static")
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted),
+                            )
+                        })
+                        .when(forward.error.is_some(), |this| {
+                            this.child(
+                                Label::new("stopped")
+                                    .size(LabelSize::Small)
+                                    .color(Color::Error),
+                            )
+                        }),
+                )
+                .when_some(forward.error.clone(), |this, error| {
+                    this.tooltip(Tooltip::text(error))
+                })
+                .when(!forward.is_static, |this| {
+                    this.end_slot(
+                        IconButton::new(
+                            SharedString::from(format!("stop-forward-{local_port}")),
+                            IconName::Close,
+                        )
+                        .icon_size(IconSize::Small)
+                        .tooltip(Tooltip::text("Stop Forwarding"))
+                        .on_click(cx.listener(move |this, _, _, cx| {
+                            if let Some(store) = this.port_forward_store(cx) {
+                                store.update(cx, |store, cx| store.stop_forward(local_port, cx));
+                            }
+                        })),
+                    )
+                })
+        }))
+    }
+
+    fn render_listening_ports(
+        &self,
+        store: &PortForwardStore,
+        cx: &Context<Self>,
+    ) -> impl IntoElement {
+        v_flex().children(store.unforwarded_listening_ports().map(|port| {
+            ListItem::new(SharedString::from(format!("listening-{port}")))
+                .spacing(ListItemSpacing::Sparse)
+                .child(Label::new(port.to_string()))
+                .end_slot(
+                    IconButton::new(
+                        SharedString::from(format!("forward-{port}")),
+                        IconName::ArrowUpRight,
+                    )
+                    .icon_size(IconSize::Small)
+                    .tooltip(Tooltip::text("Forward Port"))
+                    .on_click(cx.listener(move |this, _, window, cx| {
+                        if let Some(store) = this.port_forward_store(cx) {
+                            store
+                                .update(cx, |store, cx| {
+                                    store.forward_port("localhost".into(), port, None, cx)
+                                })
+                                .detach_and_prompt_err(
+                                    "Failed to forward port",
+                                    window,
+                                    cx,
+                                    |_, _, _| None,
+                                );
+                        }
+                    })),
+                )
+        }))
+    }
+}
+
+/// Parses a port, optionally prefixed by the host it is forwarded to as seen
+/// from the remote host, such as `3000` or `db.internal:5432`.
+fn parse_port_target(text: &str) -> Option<(String, u16)> {
+    let text = text.trim();
+    let (host, port) = text.rsplit_once(':').unwrap_or(("", text));
+    let host = if host.is_empty() { "localhost" } else { host };
+    let port = port.parse().ok().filter(|port| *port != 0)?;
+    Some((host.to_string(), port))
+}
+
+impl Render for PortsPanel {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let store = self.port_forward_store(cx);
+        v_flex()
+            .key_context("PortsPanel")
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .child(
+                h_flex()
+                    .justify_between()
+                    .px_2()
+                    .py_1()
+                    // Match the height of the tab bar so they line up.
+                    .h(Tab::container_height(cx))
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border)
+                    .child(Label::new("Ports"))
+                    .child(Icon::new(IconName::Server)),
+            )
+            .map(|this| {
+                let Some(store) = store else {
+                    return this.child(
+                        v_flex().p_4().child(
+                            Label::new("Ports can only be forwarded in remote projects.")
+                                .color(Color::Muted)
+                                .size(LabelSize::Small),
+                        ),
+                    );
+                };
+                let store = store.read(cx);
+                let has_listening_ports = store.unforwarded_listening_ports().next().is_some();
+                this.child(
+                    h_flex()
+                        .gap_1()
+                        .px_2()
+                        .py_1()
+                        .border_b_1()
+                        .border_color(cx.theme().colors().border)
+                        .on_action(cx.listener(|this, _: &menu::Confirm, window, cx| {
+                            this.forward_port(window, cx)
+                        }))
+                        .child(div().flex_1().child(self.port_editor.clone()))
+                        .child(
+                            IconButton::new("forward-port", IconName::Plus)
+                                .icon_size(IconSize::Small)
+                                .tooltip(Tooltip::text("Forward Port"))
+                                .on_click(
+                                    cx.listener(|this, _, window, cx| {
+                                        this.forward_port(window, cx)
+                                    }),
+                                ),
+                        ),
+                )
+                .child(
+                    v_flex()
+                        .id("ports")
+                        .flex_1()
+                        .overflow_y_scroll()
+                        .p_1()
+                        .child(
+                            div().mx_2().my_1().child(
+                                Label::new("Forwarded")
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted),
+                            ),
+                        )
+                        .map(|this| {
+                            if store.forwards().is_empty() {
+                                this.child(
+                                    div().mx_2().child(
+                                        Label::new("No ports are forwarded.")
+                                            .size(LabelSize::Small)
+                                            .color(Color::Muted),
+                                    ),
+                                )
+                            } else {
+                                this.child(self.render_forwards(store, cx))
+                            }
+                        })
+                        .when(has_listening_ports, |this| {
+                            this.child(
+                                div().mx_2().mt_3().mb_1().child(
+                                    Label::new("Listening on Remote Host")
+                                        .size(LabelSize::Small)
+                                        .color(Color::Muted),
+                                ),
+                            )
+                            .child(self.render_listening_ports(store, cx))
+                        }),
+                )
+            })
+    }
+}
+
+impl Focusable for PortsPanel {
+    fn focus_handle(&self, _: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<PanelEvent> for PortsPanel {}
+
+impl Panel for PortsPanel {
+    fn persistent_name() -> &'static str {
+        "PortsPanel"
+    }
+
+    fn panel_key() -> &'static str {
+        PORTS_PANEL_KEY
+    }
+
+    fn position(&self, _: &Window, cx: &App) -> DockPosition {
+        PortsPanelSettings::get_global(cx).dock
+    }
+
+    fn position_is_valid(&self, position: DockPosition) -> bool {
+        matches!(position, DockPosition::Left | DockPosition::Right)
+    }
+
+    fn set_position(&mut self, position: DockPosition, _: &mut Window, cx: &mut Context<Self>) {
+        settings::update_settings_file(self.fs.clone(), cx, move |settings, _| {
+            settings.ports_panel.get_or_insert_default().dock = Some(position.into())
+        });
+    }
+
+    fn size(&self, _: &Window, cx: &App) -> Pixels {
+        self.width
+            .unwrap_or_else(|| PortsPanelSettings::get_global(cx).default_width)
+    }
+
+    fn set_size(&mut self, size: Option<Pixels>, _: &mut Window, cx: &mut Context<Self>) {
+        self.width = size;
+        self.serialize(cx);
+        cx.notify();
+    }
+
+    fn icon(&self, _: &Window, cx: &App) -> Option<IconName> {
+        PortsPanelSettings::get_global(cx)
+            .button
+            .then_some(IconName::Server)
+    }
+
+    fn icon_tooltip(&self, _window: &Window, _cx: &App) -> Option<&'static str> {
+        Some("Ports Panel")
+    }
+
+    fn icon_label(&self, _window: &Window, cx: &App) -> Option<String> {
+        let count = self
+            .port_forward_store(cx)?
+            .read(cx)
+            .forwards()
+            .iter()
+            .filter(|forward| forward.error.is_none())
+            .count();
+        (count > 0).then(|| count.to_string())
+    }
+
+    fn toggle_action(&self) -> Box<dyn Action> {
+        Box::new(ToggleFocus)
+    }
+
+    fn activation_priority(&self) -> u32 {
+        10
+    }
+
+    fn enabled(&self, cx: &App) -> bool {
+        self.project.read(cx).is_via_remote_server()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_port_target() {
+        assert_eq!(parse_port_target("3000"), Some(("localhost".into(), 3000)));
+        assert_eq!(
+            parse_port_target(" db.internal:5432 "),
+            Some(("db.internal".into(), 5432))
+        );
+        assert_eq!(parse_port_target(":8080"), Some(("localhost".into(), 8080)));
+        assert_eq!(parse_port_target("0"), None);
+        assert_eq!(parse_port_target("localhost:http"), None);
+    }
+}
@@ -1,8 +1,10 @@
 mod dev_container;
 mod dev_container_suggest;
 pub mod disconnected_overlay;
+pub mod ports_panel;
 mod remote_connections;
 mod remote_servers;
+mod remote_sync_indicator;
 mod ssh_config;
 
 use std::path::PathBuf;
@@ -26,6 +28,7 @@ use picker::{
 };
 pub use remote_connections::SshSettings;
 pub use remote_servers::RemoteServerProjects;
+pub use remote_sync_indicator::RemoteSyncIndicator;
 use settings::Settings;
 use std::{path::Path, sync::Arc};
 use ui::{KeyBinding, ListItem, ListItemSpacing, Tooltip, prelude::*, tooltip_container};
@@ -38,6 +41,8 @@ use workspace::{
 use zed_actions::{OpenDevContainer, OpenRecent, OpenRemote};
 
 pub fn init(cx: &mut App) {
+    ports_panel::init(cx);
+
     #[cfg(target_os = "windows")]
     cx.on_action(|open_wsl: &zed_actions::wsl_actions::OpenFolderInWsl, cx| {
         let create_new_window = open_wsl.create_new_window;
@@ -703,6 +708,7 @@ impl PickerDelegate for RecentProjectsDelegate {
                                         RemoteConnectionOptions::Ssh { .. } => IconName::Server,
                                         RemoteConnectionOptions::Wsl { .. } => IconName::Linux,
                                         RemoteConnectionOptions::Docker(_) => IconName::Box,
+                                        RemoteConnectionOptions::Command(_) => IconName::Terminal,
                                     })
                                     .color(Color::Muted)
                                     .into_any_element()
@@ -19,12 +19,15 @@ use markdown::{Markdown, MarkdownElement, MarkdownStyle};
 use project::trusted_worktrees;
 use release_channel::ReleaseChannel;
 use remote::{
-    ConnectionIdentifier, DockerConnectionOptions, RemoteClient, RemoteConnection,
-    RemoteConnectionOptions, RemotePlatform, SshConnectionOptions,
+    CommandConnectionOptions, ConnectionIdentifier, DockerConnectionOptions, RemoteClient,
+    RemoteConnection, RemoteConnectionOptions, RemotePlatform, SshConnectionOptions,
 };
 use semver::Version;
 pub use settings::SshConnection;
-use settings::{DevContainerConnection, ExtendingVec, RegisterSetting, Settings, WslConnection};
+use settings::{
+    CommandConnection, DevContainerConnection, ExtendingVec, RegisterSetting, Settings,
+    WslConnection,
+};
 use theme::ThemeSettings;
 use ui::{
     ActiveTheme, Color, CommonAnimationExt, Context, InteractiveElement, IntoElement, KeyBinding,
@@ -37,6 +40,7 @@ use workspace::{AppState, ModalView, Workspace};
 pub struct SshSettings {
     pub ssh_connections: ExtendingVec<SshConnection>,
     pub wsl_connections: ExtendingVec<WslConnection>,
+    pub command_connections: ExtendingVec<CommandConnection>,
     /// Whether to read ~/.ssh/config for ssh connection sources.
     pub read_ssh_config: bool,
 }
@@ -50,6 +54,10 @@ impl SshSettings {
         self.wsl_connections.clone().0.into_iter()
     }
 
+    pub fn command_connections(&self) -> impl Iterator<Item = CommandConnection> + use<> {
+        self.command_connections.clone().0.into_iter()
+    }
+
     pub fn fill_connection_options_from_settings(&self, options: &mut SshConnectionOptions) {
         for conn in self.ssh_connections() {
             if conn.host == options.host.to_string()
@@ -87,6 +95,7 @@ pub enum Connection {
     Ssh(SshConnection),
     Wsl(WslConnection),
     DevContainer(DevContainerConnection),
+    Command(CommandConnection),
 }
 
 impl From<Connection> for RemoteConnectionOptions {
@@ -101,6 +110,16 @@ impl From<Connection> for RemoteConnectionOptions {
                     upload_binary_over_docker_exec: false,
                 })
             }
+            Connection::Command(conn) => {
+                RemoteConnectionOptions::Command(CommandConnectionOptions {
+                    name: conn.name.to_string(),
+                    program: conn.program,
+                    args: conn.args,
+                    tty_args: conn.tty_args,
+                    env: conn.env,
+                    upload_binary_over_exec: conn.upload_binary_over_exec.unwrap_or_default(),
+                })
+            }
         }
     }
 }
@@ -117,12 +136,23 @@ impl From<WslConnection> for Connection {
     }
 }
 
+impl From<CommandConnection> for Connection {
+    fn from(val: CommandConnection) -> Self {
+        Connection::Command(val)
+    }
+}
+
 impl Settings for SshSettings {
     fn from_settings(content: &settings::SettingsContent) -> Self {
         let remote = &content.remote;
         Self {
             ssh_connections: remote.ssh_connections.clone().unwrap_or_default().into(),
             wsl_connections: remote.wsl_connections.clone().unwrap_or_default().into(),
+            command_connections: remote
+                .command_connections
+                .clone()
+                .unwrap_or_default()
+                .into(),
             read_ssh_config: remote.read_ssh_config.unwrap(),
         }
     }
@@ -309,6 +339,7 @@ impl RemoteConnectionModal {
                 (options.distro_name.clone(), None, true, false)
             }
             RemoteConnectionOptions::Docker(options) => (options.name.clone(), None, false, true),
+            RemoteConnectionOptions::Command(options) => (options.name.clone(), None, false, false),
         };
         Self {
             prompt: cx.new(|cx| {
@@ -720,6 +751,7 @@ pub async fn open_remote_project(
                                     RemoteConnectionOptions::Docker(_) => {
                                         "Failed to connect to Dev Container"
                                     }
+                                    RemoteConnectionOptions::Command(_) => "Failed to connect",
                                 },
                                 Some(&format!("{e:#}")),
                                 &["Retry", "Cancel"],
@@ -779,6 +811,7 @@ pub async fn open_remote_project(
                                 RemoteConnectionOptions::Docker(_) => {
                                     "Failed to connect to Dev Container"
                                 }
+                                RemoteConnectionOptions::Command(_) => "Failed to connect",
                             },
                             Some(&format!("{e:#}")),
                             &["Retry", "Cancel"],
@@ -0,0 +1,100 @@
+use editor::Editor;
+use gpui::{Entity, Subscription};
+use language::BufferId;
+use project::{Project, RemoteBufferSyncState};
+use ui::{Tooltip, prelude::*};
+use workspace::{StatusItemView, item::ItemHandle};
+
+/// Shows the sync state of the active remote buffer while the connection to
+/// the remote host is offline, or when a save made offline is pending.
+pub struct RemoteSyncIndicator {
+    project: Entity<Project>,
+    active_buffer_id: Option<BufferId>,
+    _observe_active_editor: Option<Subscription>,
+    _observe_buffer_store: Subscription,
+}
+
+impl RemoteSyncIndicator {
+    pub fn new(project: Entity<Project>, cx: &mut Context<Self>) -> Self {
+        let buffer_store = project.read(cx).buffer_store().clone();
+        Self {
+            project,
+            active_buffer_id: None,
+            _observe_active_editor: None,
+            _observe_buffer_store: cx.observe(&buffer_store, |_, _, cx| cx.notify()),
+        }
+    }
+
+    fn update(&mut self, editor: Entity<Editor>, _: &mut Window, cx: &mut Context<Self>) {
+        self.active_buffer_id = editor
+            .read(cx)
+            .buffer()
+            .read(cx)
+            .as_singleton()
+            .map(|buffer| buffer.read(cx).remote_id());
+        cx.notify();
+    }
+}
+
+impl Render for RemoteSyncIndicator {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let project = self.project.read(cx);
+        let is_offline = project.buffer_store().read(cx).is_remote_offline();
+        let sync_state = self
+            .active_buffer_id
+            .and_then(|buffer_id| project.remote_buffer_sync_state(buffer_id, cx));
+
+        let (label, color, tooltip) = match sync_state {
+            Some(RemoteBufferSyncState::Conflict) => (
+                "Save Conflict",
+                Color::Error,
+                "The file changed on the remote host while offline. Reload it or save again to overwrite.",
+            ),
+            Some(RemoteBufferSyncState::SaveQueued) => (
+                "Save Queued",
+                Color::Warning,
+                "Saved while offline. The file is written back once reconnected.",
+            ),
+            Some(RemoteBufferSyncState::Unsynced) => (
+                "Unsynced Edits",
+                Color::Warning,
+                "Edited while offline. Edits are sent to the remote host once reconnected.",
+            ),
+            None if is_offline => (
+                "Offline",
+                Color::Warning,
+                "Lost connection to the remote host. Edits are kept locally while reconnecting.",
+            ),
+            None => return div(),
+        };
+
+        div().child(
+            Button::new("remote-sync-state", label)
+                .label_size(LabelSize::Small)
+                .color(color)
+                .icon(IconName::Disconnected)
+                .icon_size(IconSize::Small)
+                .icon_color(color)
+                .icon_position(IconPosition::Start)
+                .tooltip(Tooltip::text(tooltip)),
+        )
+    }
+}
+
+impl StatusItemView for RemoteSyncIndicator {
+    fn set_active_pane_item(
+        &mut self,
+        active_pane_item: Option<&dyn ItemHandle>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(editor) = active_pane_item.and_then(|item| item.downcast::<Editor>()) {
+            self._observe_active_editor = Some(cx.observe_in(&editor, window, Self::update));
+            self.update(editor, window, cx);
+        } else {
+            self.active_buffer_id = None;
+            self._observe_active_editor = None;
+        }
+        cx.notify();
+    }
+}
@@ -0,0 +1,113 @@
+use std::{net::Ipv4Addr, process::Stdio, sync::Arc};
+
+use anyhow::{Context as _, Result};
+use futures::{
+    AsyncWriteExt as _, FutureExt as _, StreamExt as _, select_biased, stream::FuturesUnordered,
+};
+use gpui::{App, AppContext as _, Task};
+use smol::net::{TcpListener, TcpStream};
+use util::{ResultExt as _, command::new_smol_command};
+
+use crate::{RemoteConnection, remote_client::CommandTemplate};
+
+/// Forwards `local_port` on the local host to `remote_host:remote_port`, as
+/// seen from the remote host, until the returned task is dropped.
+///
+/// Transports that can forward ports natively (such as SSH) run a forwarding
+/// process. Other transports accept connections locally and proxy each of them
+/// through a command on the remote host.
+pub(crate) fn forward_port(
+    connection: Arc<dyn RemoteConnection>,
+    local_port: u16,
+    remote_host: String,
+    remote_port: u16,
+    cx: &App,
+) -> Task<Result<()>> {
+    #[cfg(any(test, feature = "test-support"))]
+    if let Some(forward) = connection.simulate_port_forward(cx) {
+        return forward;
+    }
+
+    if let Ok(command) =
+        connection.build_forward_ports_command(vec![(local_port, remote_host.clone(), remote_port)])
+    {
+        return cx.background_spawn(async move {
+            let output = new_smol_command(&command.program)
+                .args(&command.args)
+                .envs(&command.env)
+                .stdin(Stdio::null())
+                .kill_on_drop(true)
+                .output()
+                .await
+                .context("spawning port forwarding process")?;
+            anyhow::ensure!(
+                output.status.success(),
+                "port forwarding process exited with {}: {}",
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+            Ok(())
+        });
+    }
+
+    let command = match connection.build_port_proxy_command(remote_host, remote_port) {
+        Ok(command) => command,
+        Err(error) => return Task::ready(Err(error)),
+    };
+    cx.background_spawn(async move {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, local_port))
+            .await
+            .with_context(|| format!("listening on local port {local_port}"))?;
+        let mut connections = FuturesUnordered::new();
+        loop {
+            select_biased! {
+                accepted = listener.accept().fuse() => {
+                    let (stream, _) = accepted?;
+                    connections.push(proxy_connection(stream, command.clone()));
+                }
+                result = connections.select_next_some() => {
+                    result.log_err();
+                }
+            }
+        }
+    })
+}
+
+async fn proxy_connection(stream: TcpStream, command: CommandTemplate) -> Result<()> {
+    let mut child = new_smol_command(&command.program)
+        .args(&command.args)
+        .envs(&command.env)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .kill_on_drop(true)
+        .spawn()
+        .context("spawning port proxy process")?;
+    let mut stdin = child.stdin.take().context("missing stdin")?;
+    let mut stdout = child.stdout.take().context("missing stdout")?;
+    let (mut reader, mut writer) = (stream.clone(), stream);
+
+    let upload = async {
+        futures::io::copy(&mut reader, &mut stdin).await?;
+        stdin.close().await?;
+        anyhow::Ok(())
+    };
+    let download = async {
+        futures::io::copy(&mut stdout, &mut writer).await?;
+        writer.close().await?;
+        anyhow::Ok(())
+    };
+    futures::future::try_join(upload, download).await?;
+    Ok(())
+}
+
+/// Returns `preferred_port` if it is free on the local host, or another free port otherwise.
+pub async fn unused_local_port(preferred_port: u16) -> Result<u16> {
+    if let Ok(listener) = TcpListener::bind((Ipv4Addr::LOCALHOST, preferred_port)).await {
+        return Ok(listener.local_addr()?.port());
+    }
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))
+        .await
+        .context("finding a free local port")?;
+    Ok(listener.local_addr()?.port())
+}
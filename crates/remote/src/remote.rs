@@ -1,4 +1,5 @@
 pub mod json_log;
+pub mod port_forward;
 pub mod protocol;
 pub mod proxy;
 pub mod remote_client;
@@ -11,6 +12,7 @@ pub use remote_client::{
     RemoteClientEvent, RemoteConnection, RemoteConnectionOptions, RemoteOs, RemotePlatform,
     connect,
 };
+pub use transport::command::CommandConnectionOptions;
 pub use transport::docker::DockerConnectionOptions;
 pub use transport::ssh::{SshConnectionOptions, SshPortForwardOption};
 pub use transport::wsl::WslConnectionOptions;
@@ -3,6 +3,7 @@ use crate::{
     protocol::MessageId,
     proxy::ProxyLaunchError,
     transport::{
+        command::{CommandConnectionOptions, CommandExecConnection},
         docker::{DockerConnectionOptions, DockerExecConnection},
         ssh::SshRemoteConnection,
         wsl::{WslConnectionOptions, WslRemoteConnection},
@@ -141,6 +142,7 @@ const INITIAL_CONNECTION_TIMEOUT: Duration =
     Duration::from_secs(if cfg!(debug_assertions) { 5 } else { 60 });
 
 const MAX_RECONNECT_ATTEMPTS: usize = 3;
+const OFFLINE_RECONNECT_INTERVAL: Duration = Duration::from_secs(10);
 
 enum State {
     Connecting,
@@ -169,6 +171,12 @@ enum State {
         attempts: usize,
     },
     ReconnectExhausted,
+    /// All reconnect attempts failed, but offline editing is enabled, so we
+    /// keep retrying periodically instead of giving up on the connection.
+    Offline {
+        ssh_connection: Arc<dyn RemoteConnection>,
+        delegate: Arc<dyn RemoteClientDelegate>,
+    },
     ServerNotRunning,
 }
 
@@ -180,6 +188,7 @@ impl fmt::Display for State {
             Self::Reconnecting => write!(f, "reconnecting"),
             Self::ReconnectFailed { .. } => write!(f, "reconnect failed"),
             Self::ReconnectExhausted => write!(f, "reconnect exhausted"),
+            Self::Offline { .. } => write!(f, "offline"),
             Self::HeartbeatMissed { .. } => write!(f, "heartbeat missed"),
             Self::ServerNotRunning { .. } => write!(f, "server not running"),
         }
@@ -195,6 +204,7 @@ impl State {
             } => Some(ssh_connection.clone()),
             Self::HeartbeatMissed { ssh_connection, .. } => Some(ssh_connection.clone()),
             Self::ReconnectFailed { ssh_connection, .. } => Some(ssh_connection.clone()),
+            Self::Offline { ssh_connection, .. } => Some(ssh_connection.clone()),
             _ => None,
         }
     }
@@ -203,7 +213,8 @@ impl State {
         match self {
             Self::Connected { .. }
             | Self::HeartbeatMissed { .. }
-            | Self::ReconnectFailed { .. } => true,
+            | Self::ReconnectFailed { .. }
+            | Self::Offline { .. } => true,
             State::Connecting
             | State::Reconnecting
             | State::ReconnectExhausted
@@ -219,6 +230,25 @@ impl State {
         matches!(self, Self::ReconnectExhausted { .. })
     }
 
+    fn is_offline(&self) -> bool {
+        matches!(self, Self::Offline { .. })
+    }
+
+    /// Whether the connection is currently unusable, but may still come back.
+    fn is_interrupted(&self) -> bool {
+        matches!(
+            self,
+            Self::HeartbeatMissed { .. }
+                | Self::Reconnecting
+                | Self::ReconnectFailed { .. }
+                | Self::Offline { .. }
+        )
+    }
+
+    fn is_connected(&self) -> bool {
+        matches!(self, Self::Connected { .. })
+    }
+
     fn is_server_not_running(&self) -> bool {
         matches!(self, Self::ServerNotRunning)
     }
@@ -284,6 +314,8 @@ pub enum ConnectionState {
     Connected,
     HeartbeatMissed,
     Reconnecting,
+    /// The connection is lost, but edits are kept locally while reconnecting in the background.
+    Offline,
     Disconnected,
 }
 
@@ -294,6 +326,7 @@ impl From<&State> for ConnectionState {
             State::Connected { .. } => Self::Connected,
             State::Reconnecting | State::ReconnectFailed { .. } => Self::Reconnecting,
             State::HeartbeatMissed { .. } => Self::HeartbeatMissed,
+            State::Offline { .. } => Self::Offline,
             State::ReconnectExhausted => Self::Disconnected,
             State::ServerNotRunning => Self::Disconnected,
         }
@@ -306,11 +339,18 @@ pub struct RemoteClient {
     connection_options: RemoteConnectionOptions,
     path_style: PathStyle,
     state: Option<State>,
+    offline_editing: bool,
+    was_offline: bool,
 }
 
 #[derive(Debug)]
 pub enum RemoteClientEvent {
     Disconnected,
+    /// With offline editing enabled, the connection was interrupted and the
+    /// client is trying to re-establish it.
+    WentOffline,
+    /// The connection was re-established after having gone offline.
+    Reconnected,
 }
 
 impl EventEmitter<RemoteClientEvent> for RemoteClient {}
@@ -395,6 +435,8 @@ impl RemoteClient {
                     connection_options: remote_connection.connection_options(),
                     path_style,
                     state: Some(State::Connecting),
+                    offline_editing: false,
+                    was_offline: false,
                 })?;
 
                 let io_task = remote_connection.start_proxy(
@@ -562,6 +604,11 @@ impl RemoteClient {
                 delegate,
                 ..
             } => (attempts, ssh_connection, delegate),
+            // While offline, every retry is a single attempt.
+            State::Offline {
+                ssh_connection,
+                delegate,
+            } => (MAX_RECONNECT_ATTEMPTS - 1, ssh_connection, delegate),
             State::Connecting
             | State::Reconnecting
             | State::ReconnectExhausted
@@ -570,6 +617,34 @@ impl RemoteClient {
 
         let attempts = attempts + 1;
         if attempts > MAX_RECONNECT_ATTEMPTS {
+            if self.offline_editing {
+                log::warn!(
+                    "Failed to reconnect after {} attempts, retrying in {:?}",
+                    MAX_RECONNECT_ATTEMPTS,
+                    OFFLINE_RECONNECT_INTERVAL
+                );
+                self.set_state(
+                    State::Offline {
+                        ssh_connection: remote_connection,
+                        delegate,
+                    },
+                    cx,
+                );
+                cx.spawn(async move |this, cx| {
+                    cx.background_executor()
+                        .timer(OFFLINE_RECONNECT_INTERVAL)
+                        .await;
+                    this.update(cx, |this, cx| {
+                        if this.state_is(State::is_offline) {
+                            this.reconnect(cx)
+                        } else {
+                            Ok(())
+                        }
+                    })?
+                })
+                .detach_and_log_err(cx);
+                return Ok(());
+            }
             log::error!(
                 "Failed to reconnect to after {} attempts, giving up",
                 MAX_RECONNECT_ATTEMPTS
@@ -675,7 +750,7 @@ impl RemoteClient {
                                     error
                                 );
                             }
-                            State::ReconnectExhausted => {
+                            State::ReconnectExhausted | State::Offline { .. } => {
                                 log::error!("Reconnect attempt failed and all attempts exhausted");
                             }
                         }
@@ -685,6 +760,11 @@ impl RemoteClient {
                     }
                 });
 
+                if this.was_offline && this.state_is(State::is_connected) {
+                    this.was_offline = false;
+                    cx.emit(RemoteClientEvent::Reconnected);
+                }
+
                 if this.state_is(State::is_reconnect_failed) {
                     this.reconnect(cx)
                 } else if this.state_is(State::is_reconnect_exhausted) {
@@ -854,10 +934,19 @@ impl RemoteClient {
 
         let is_reconnect_exhausted = state.is_reconnect_exhausted();
         let is_server_not_running = state.is_server_not_running();
+        let is_interrupted = state.is_interrupted();
+        let is_connected = state.is_connected();
         self.state.replace(state);
 
         if is_reconnect_exhausted || is_server_not_running {
+            self.was_offline = false;
             cx.emit(RemoteClientEvent::Disconnected);
+        } else if self.offline_editing && is_interrupted && !self.was_offline {
+            self.was_offline = true;
+            cx.emit(RemoteClientEvent::WentOffline);
+        } else if is_connected && self.was_offline {
+            self.was_offline = false;
+            cx.emit(RemoteClientEvent::Reconnected);
         }
         cx.notify();
     }
@@ -899,6 +988,21 @@ impl RemoteClient {
         connection.build_forward_ports_command(forwards)
     }
 
+    /// Forwards `local_port` on the local host to `remote_host:remote_port` on
+    /// the remote host, until the returned task is dropped.
+    pub fn forward_port(
+        &self,
+        local_port: u16,
+        remote_host: String,
+        remote_port: u16,
+        cx: &App,
+    ) -> Task<Result<()>> {
+        let Some(connection) = self.remote_connection() else {
+            return Task::ready(Err(anyhow!("no remote connection")));
+        };
+        crate::port_forward::forward_port(connection, local_port, remote_host, remote_port, cx)
+    }
+
     pub fn upload_directory(
         &self,
         src_path: PathBuf,
@@ -919,6 +1023,10 @@ impl RemoteClient {
         self.connection_options.clone()
     }
 
+    pub fn unique_identifier(&self) -> &str {
+        &self.unique_identifier
+    }
+
     pub fn connection(&self) -> Option<Arc<dyn RemoteConnection>> {
         if let State::Connected {
             remote_connection, ..
@@ -941,27 +1049,53 @@ impl RemoteClient {
         self.connection_state() == ConnectionState::Disconnected
     }
 
+    pub fn is_offline(&self) -> bool {
+        self.connection_state() == ConnectionState::Offline
+    }
+
+    /// When enabled, [`RemoteClientEvent::WentOffline`] is emitted as soon as the
+    /// connection is interrupted, and exhausting the reconnect attempts leaves the
+    /// client [`ConnectionState::Offline`] and keeps retrying, instead of disconnecting.
+    pub fn set_offline_editing(&mut self, enabled: bool) {
+        self.offline_editing = enabled;
+    }
+
     pub fn path_style(&self) -> PathStyle {
         self.path_style
     }
 
     #[cfg(any(test, feature = "test-support"))]
     pub fn simulate_disconnect(&self, client_cx: &mut App) -> Task<()> {
-        let opts = self.connection_options();
+        let connection = self.fake_connection(client_cx);
         client_cx.spawn(async move |cx| {
-            let connection = cx
-                .update_global(|c: &mut ConnectionPool, _| {
-                    if let Some(ConnectionPoolEntry::Connecting(c)) = c.connections.get(&opts) {
-                        c.clone()
-                    } else {
-                        panic!("missing test connection")
-                    }
-                })
-                .unwrap()
-                .await
-                .unwrap();
+            connection.await.simulate_disconnect(cx);
+        })
+    }
 
-            connection.simulate_disconnect(cx);
+    /// Makes every attempt to reconnect to the fake server, or to forward a port
+    /// through it, fail until this is called again with `false`.
+    #[cfg(any(test, feature = "test-support"))]
+    pub fn simulate_unreachable(&self, unreachable: bool, client_cx: &mut App) -> Task<()> {
+        let connection = self.fake_connection(client_cx);
+        client_cx.spawn(async move |_| {
+            connection.await.simulate_unreachable(unreachable);
+        })
+    }
+
+    #[cfg(any(test, feature = "test-support"))]
+    fn fake_connection(&self, client_cx: &mut App) -> Task<Arc<dyn RemoteConnection>> {
+        let opts = self.connection_options();
+        client_cx.spawn(async move |cx| {
+            cx.update_global(|c: &mut ConnectionPool, _| {
+                if let Some(ConnectionPoolEntry::Connecting(c)) = c.connections.get(&opts) {
+                    c.clone()
+                } else {
+                    panic!("missing test connection")
+                }
+            })
+            .unwrap()
+            .await
+            .unwrap()
         })
     }
 
@@ -987,6 +1121,7 @@ impl RemoteClient {
             connection_options: opts.clone(),
             server_cx: fake::SendableCx::new(server_cx),
             server_channel: server_client.clone(),
+            unreachable: Default::default(),
         });
 
         client_cx.update(|cx| {
@@ -1099,6 +1234,11 @@ impl ConnectionPool {
                                 .await
                                 .map(|connection| Arc::new(connection) as Arc<dyn RemoteConnection>)
                         }
+                        RemoteConnectionOptions::Command(opts) => {
+                            CommandExecConnection::new(opts, delegate, cx)
+                                .await
+                                .map(|connection| Arc::new(connection) as Arc<dyn RemoteConnection>)
+                        }
                     };
 
                     cx.update_global(|pool: &mut Self, _| {
@@ -1135,6 +1275,7 @@ pub enum RemoteConnectionOptions {
     Ssh(SshConnectionOptions),
     Wsl(WslConnectionOptions),
     Docker(DockerConnectionOptions),
+    Command(CommandConnectionOptions),
 }
 
 impl RemoteConnectionOptions {
@@ -1143,6 +1284,7 @@ impl RemoteConnectionOptions {
             RemoteConnectionOptions::Ssh(opts) => opts.host.to_string(),
             RemoteConnectionOptions::Wsl(opts) => opts.distro_name.clone(),
             RemoteConnectionOptions::Docker(opts) => opts.name.clone(),
+            RemoteConnectionOptions::Command(opts) => opts.name.clone(),
         }
     }
 }
@@ -1203,6 +1345,13 @@ pub trait RemoteConnection: Send + Sync {
         &self,
         forwards: Vec<(u16, String, u16)>,
     ) -> Result<CommandTemplate>;
+    /// Builds a command whose stdin and stdout are connected to a TCP port on
+    /// the remote host, used to forward ports when the transport can't do so natively.
+    fn build_port_proxy_command(&self, _host: String, _port: u16) -> Result<CommandTemplate> {
+        Err(anyhow!(
+            "port forwarding is not supported for this connection"
+        ))
+    }
     fn connection_options(&self) -> RemoteConnectionOptions;
     fn path_style(&self) -> PathStyle;
     fn shell(&self) -> String;
@@ -1211,6 +1360,12 @@ pub trait RemoteConnection: Send + Sync {
 
     #[cfg(any(test, feature = "test-support"))]
     fn simulate_disconnect(&self, _: &AsyncApp) {}
+    #[cfg(any(test, feature = "test-support"))]
+    fn simulate_unreachable(&self, _: bool) {}
+    #[cfg(any(test, feature = "test-support"))]
+    fn simulate_port_forward(&self, _: &App) -> Option<Task<Result<()>>> {
+        None
+    }
 }
 
 type ResponseChannels = Mutex<HashMap<MessageId, oneshot::Sender<(Envelope, oneshot::Sender<()>)>>>;
@@ -1570,7 +1725,7 @@ impl ProtoClient for ChannelClient {
 mod fake {
     use super::{ChannelClient, RemoteClientDelegate, RemoteConnection, RemotePlatform};
     use crate::remote_client::{CommandTemplate, RemoteConnectionOptions};
-    use anyhow::Result;
+    use anyhow::{Result, anyhow};
     use askpass::EncryptedPassword;
     use async_trait::async_trait;
     use collections::HashMap;
@@ -1586,13 +1741,20 @@ mod fake {
     use release_channel::ReleaseChannel;
     use rpc::proto::Envelope;
     use semver::Version;
-    use std::{path::PathBuf, sync::Arc};
+    use std::{
+        path::PathBuf,
+        sync::{
+            Arc,
+            atomic::{AtomicBool, Ordering::SeqCst},
+        },
+    };
     use util::paths::{PathStyle, RemotePathBuf};
 
     pub(super) struct FakeRemoteConnection {
         pub(super) connection_options: RemoteConnectionOptions,
         pub(super) server_channel: Arc<ChannelClient>,
         pub(super) server_cx: SendableCx,
+        pub(super) unreachable: AtomicBool,
     }
 
     pub(super) struct SendableCx(AsyncApp);
@@ -1676,6 +1838,18 @@ mod fake {
                 .reconnect(incoming_rx, outgoing_tx, &self.server_cx.get(cx));
         }
 
+        fn simulate_unreachable(&self, unreachable: bool) {
+            self.unreachable.store(unreachable, SeqCst);
+        }
+
+        fn simulate_port_forward(&self, cx: &App) -> Option<Task<Result<()>>> {
+            Some(if self.unreachable.load(SeqCst) {
+                Task::ready(Err(anyhow!("host is unreachable")))
+            } else {
+                cx.background_spawn(futures::future::pending())
+            })
+        }
+
         fn start_proxy(
             &self,
             _unique_identifier: String,
@@ -1686,6 +1860,10 @@ mod fake {
             _delegate: Arc<dyn RemoteClientDelegate>,
             cx: &mut AsyncApp,
         ) -> Task<Result<i32>> {
+            if self.unreachable.load(SeqCst) {
+                return Task::ready(Err(anyhow!("host is unreachable")));
+            }
+
             let (mut server_incoming_tx, server_incoming_rx) = mpsc::unbounded::<Envelope>();
             let (server_outgoing_tx, mut server_outgoing_rx) = mpsc::unbounded::<Envelope>();
 
@@ -0,0 +1,567 @@
+use anyhow::Context as _;
+use anyhow::Result;
+use anyhow::anyhow;
+use async_trait::async_trait;
+use collections::{BTreeMap, HashMap};
+use futures::AsyncWriteExt as _;
+use std::{
+    path::{Path, PathBuf},
+    process::Stdio,
+    sync::Arc,
+};
+use util::{
+    paths::{PathStyle, RemotePathBuf},
+    rel_path::RelPath,
+};
+
+use futures::channel::mpsc::{Sender, UnboundedReceiver, UnboundedSender};
+use gpui::{App, AppContext, AsyncApp, Task};
+use rpc::proto::Envelope;
+
+use super::exec::{self, ExecConnection, ProxyProcess, quote};
+use crate::{
+    RemoteClientDelegate, RemoteConnection, RemoteConnectionOptions, RemoteOs,
+    remote_client::CommandTemplate,
+};
+
+/// A connection that runs every remote command through a user-provided exec-style
+/// wrapper, such as `kubectl exec -i <pod> --` or `podman exec -i <container>`.
+///
+/// The wrapper is invoked with `sh -c <script>` appended to its arguments, so it
+/// must forward stdin and pass the remaining arguments through to the remote side.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct CommandConnectionOptions {
+    pub name: String,
+    pub program: String,
+    pub args: Vec<String>,
+    /// The arguments to use instead of `args` when the command runs in a terminal,
+    /// e.g. to pass `-t` to allocate a TTY.
+    pub tty_args: Option<Vec<String>>,
+    /// Environment variables to set for the local wrapper process.
+    pub env: BTreeMap<String, String>,
+    pub upload_binary_over_exec: bool,
+}
+
+impl CommandConnectionOptions {
+    /// Returns the command that runs `script` on the remote side.
+    fn wrap(&self, script: String, tty: bool) -> CommandTemplate {
+        let mut args = match &self.tty_args {
+            Some(tty_args) if tty => tty_args.clone(),
+            _ => self.args.clone(),
+        };
+        args.extend(["sh".to_string(), "-c".to_string(), script]);
+        CommandTemplate {
+            program: self.program.clone(),
+            args,
+            env: self
+                .env
+                .iter()
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect(),
+        }
+    }
+}
+
+pub(crate) struct CommandExecConnection {
+    proxy_process: ProxyProcess,
+    remote_dir_for_server: String,
+    remote_binary_relpath: Option<Arc<RelPath>>,
+    connection_options: CommandConnectionOptions,
+    path_style: Option<PathStyle>,
+    shell: Option<String>,
+}
+
+impl CommandExecConnection {
+    pub async fn new(
+        connection_options: CommandConnectionOptions,
+        delegate: Arc<dyn RemoteClientDelegate>,
+        cx: &mut AsyncApp,
+    ) -> Result<Self> {
+        anyhow::ensure!(
+            !connection_options.program.is_empty(),
+            "no program configured for connection {:?}",
+            connection_options.name
+        );
+        let mut this = Self {
+            proxy_process: ProxyProcess::default(),
+            remote_dir_for_server: "/".to_string(),
+            remote_binary_relpath: None,
+            connection_options,
+            path_style: None,
+            shell: None,
+        };
+
+        delegate.set_status(Some("Detecting remote platform"), cx);
+        let remote_platform = super::parse_platform(&this.run_script("uname -sm", None).await?)?;
+        this.path_style = match remote_platform.os {
+            RemoteOs::Windows => Some(PathStyle::Windows),
+            _ => Some(PathStyle::Posix),
+        };
+
+        this.shell = Some(this.discover_shell().await);
+
+        let home_dir = this.run_script("echo $HOME", None).await?;
+        this.remote_dir_for_server = super::parse_shell(&home_dir, "/");
+
+        this.remote_binary_relpath = Some(
+            exec::ensure_server_binary(
+                &this,
+                remote_platform,
+                &this.remote_dir_for_server,
+                &delegate,
+                cx,
+            )
+            .await?,
+        );
+
+        Ok(this)
+    }
+
+    async fn discover_shell(&self) -> String {
+        let default_shell = "sh";
+        match self.run_script("echo $SHELL", None).await {
+            Ok(shell) => super::parse_shell(&shell, default_shell),
+            Err(e) => {
+                log::error!("Failed to get shell: {e}");
+                default_shell.to_owned()
+            }
+        }
+    }
+
+    async fn run_script_with_stdin(
+        &self,
+        script: &str,
+        working_directory: Option<&str>,
+        stdin: &[u8],
+    ) -> Result<String> {
+        let script = match working_directory {
+            Some(dir) => format!("cd {} && {script}", quote(dir)?),
+            None => script.to_string(),
+        };
+        let template = self.connection_options.wrap(script, false);
+        let mut command = util::command::new_smol_command(&template.program);
+        command
+            .args(&template.args)
+            .envs(&template.env)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+
+        let mut child = command
+            .spawn()
+            .with_context(|| format!("failed to spawn {}", template.program))?;
+        let mut child_stdin = child.stdin.take().context("failed to open stdin")?;
+        if !stdin.is_empty() {
+            child_stdin.write_all(stdin).await?;
+        }
+        child_stdin.close().await?;
+        drop(child_stdin);
+
+        let output = child.output().await?;
+        anyhow::ensure!(
+            output.status.success(),
+            "failed to run command {command:?}: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    }
+}
+
+#[async_trait(?Send)]
+impl ExecConnection for CommandExecConnection {
+    fn upload_binary_from_local(&self) -> bool {
+        self.connection_options.upload_binary_over_exec
+    }
+
+    async fn run_script(&self, script: &str, working_directory: Option<&str>) -> Result<String> {
+        self.run_script_with_stdin(script, working_directory, &[])
+            .await
+    }
+
+    /// Uploads a file by streaming its contents through the wrapper's stdin.
+    async fn upload_file(
+        &self,
+        src_path: &Path,
+        dest_path: &RelPath,
+        remote_dir_for_server: &str,
+    ) -> Result<()> {
+        log::debug!("uploading file {:?} to {:?}", src_path, dest_path);
+
+        let script = format!("cat > {}", quote(&dest_path.display(self.path_style()))?);
+        let contents = smol::fs::read(src_path)
+            .await
+            .with_context(|| format!("reading {}", src_path.display()))?;
+        self.run_script_with_stdin(&script, Some(remote_dir_for_server), &contents)
+            .await
+            .with_context(|| {
+                format!(
+                    "failed to upload {} over {}",
+                    src_path.display(),
+                    self.connection_options.program
+                )
+            })?;
+        Ok(())
+    }
+}
+
+/// Builds a `sh` script that runs `program` in `working_dir` with `env` set.
+fn exec_script(
+    program: &str,
+    args: &[String],
+    env: &HashMap<String, String>,
+    working_dir: Option<&str>,
+) -> Result<String> {
+    let mut script = String::new();
+    if let Some(working_dir) = working_dir {
+        match working_dir.strip_prefix("~/") {
+            Some(relative) if relative.is_empty() => script.push_str("cd \"$HOME\" && "),
+            Some(relative) => {
+                script.push_str(&format!("cd \"$HOME\"/{} && ", quote(relative)?));
+            }
+            None => script.push_str(&format!("cd {} && ", quote(working_dir)?)),
+        }
+    }
+    script.push_str("exec ");
+    if !env.is_empty() {
+        script.push_str("env ");
+        for (key, value) in env {
+            script.push_str(&quote(&format!("{key}={value}"))?);
+            script.push(' ');
+        }
+    }
+    script.push_str(&quote(program)?);
+    for arg in args {
+        script.push(' ');
+        script.push_str(&quote(arg)?);
+    }
+    Ok(script)
+}
+
+#[async_trait(?Send)]
+impl RemoteConnection for CommandExecConnection {
+    fn has_wsl_interop(&self) -> bool {
+        false
+    }
+
+    fn start_proxy(
+        &self,
+        unique_identifier: String,
+        reconnect: bool,
+        incoming_tx: UnboundedSender<Envelope>,
+        outgoing_rx: UnboundedReceiver<Envelope>,
+        connection_activity_tx: Sender<()>,
+        delegate: Arc<dyn RemoteClientDelegate>,
+        cx: &mut AsyncApp,
+    ) -> Task<Result<i32>> {
+        if !self.has_been_killed() {
+            if let Err(e) = self.proxy_process.kill() {
+                return Task::ready(Err(e));
+            };
+        }
+
+        delegate.set_status(Some("Starting proxy"), cx);
+
+        let Some(remote_binary_relpath) = self.remote_binary_relpath.clone() else {
+            return Task::ready(Err(anyhow!("Remote binary path not set")));
+        };
+
+        let mut env = HashMap::default();
+        for env_var in ["RUST_LOG", "RUST_BACKTRACE", "ZED_GENERATE_MINIDUMPS"] {
+            if let Ok(value) = std::env::var(env_var) {
+                env.insert(env_var.to_string(), value);
+            }
+        }
+        let mut proxy_args = vec![
+            "proxy".to_string(),
+            "--identifier".to_string(),
+            unique_identifier,
+        ];
+        if reconnect {
+            proxy_args.push("--reconnect".to_string());
+        }
+        let script = match exec_script(
+            &remote_binary_relpath.display(self.path_style()),
+            &proxy_args,
+            &env,
+            Some(&self.remote_dir_for_server),
+        ) {
+            Ok(script) => script,
+            Err(e) => return Task::ready(Err(e)),
+        };
+        self.proxy_process.start(
+            self.connection_options.wrap(script, false),
+            incoming_tx,
+            outgoing_rx,
+            connection_activity_tx,
+            cx,
+        )
+    }
+
+    fn upload_directory(
+        &self,
+        src_path: PathBuf,
+        dest_path: RemotePathBuf,
+        cx: &App,
+    ) -> Task<Result<()>> {
+        let dest_path = match quote(&dest_path.to_string()) {
+            Ok(dest_path) => dest_path.into_owned(),
+            Err(e) => return Task::ready(Err(e)),
+        };
+        let template = self.connection_options.wrap(
+            format!("mkdir -p {dest_path} && tar -xf - -C {dest_path}"),
+            false,
+        );
+
+        cx.background_spawn(async move {
+            let mut tar = util::command::new_smol_command("tar");
+            tar.arg("-C")
+                .arg(&src_path)
+                .args(["-cf", "-", "."])
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped());
+            let mut tar = tar.spawn().context("failed to spawn tar")?;
+
+            let mut command = util::command::new_smol_command(&template.program);
+            command
+                .args(&template.args)
+                .envs(&template.env)
+                .stdin(Stdio::piped())
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped());
+            let mut child = command
+                .spawn()
+                .with_context(|| format!("failed to spawn {}", template.program))?;
+
+            let mut tar_stdout = tar.stdout.take().context("failed to open tar stdout")?;
+            let mut child_stdin = child.stdin.take().context("failed to open stdin")?;
+            futures::io::copy(&mut tar_stdout, &mut child_stdin).await?;
+            child_stdin.close().await?;
+            drop(child_stdin);
+
+            let tar_output = tar.output().await?;
+            anyhow::ensure!(
+                tar_output.status.success(),
+                "failed to archive {}: {}",
+                src_path.display(),
+                String::from_utf8_lossy(&tar_output.stderr)
+            );
+            let output = child.output().await?;
+            anyhow::ensure!(
+                output.status.success(),
+                "Failed to upload directory: {}",
+                String::from_utf8_lossy(&output.stderr)
+            );
+            Ok(())
+        })
+    }
+
+    async fn kill(&self) -> Result<()> {
+        self.proxy_process.kill()
+    }
+
+    fn has_been_killed(&self) -> bool {
+        self.proxy_process.has_been_killed()
+    }
+
+    fn build_command(
+        &self,
+        program: Option<String>,
+        args: &[String],
+        env: &HashMap<String, String>,
+        working_dir: Option<String>,
+        _port_forward: Option<(u16, String, u16)>,
+    ) -> Result<CommandTemplate> {
+        let working_dir =
+            working_dir.map(|dir| RemotePathBuf::new(dir, self.path_style()).to_string());
+        let script = match program {
+            Some(program) => exec_script(&program, args, env, working_dir.as_deref())?,
+            None => exec_script(
+                &self.shell(),
+                &["-l".to_string()],
+                env,
+                working_dir.as_deref(),
+            )?,
+        };
+        // The environment is passed through `env` in the script, so the wrapper
+        // only receives the environment configured for the connection.
+        Ok(self.connection_options.wrap(script, true))
+    }
+
+    fn build_forward_ports_command(
+        &self,
+        _forwards: Vec<(u16, String, u16)>,
+    ) -> Result<CommandTemplate> {
+        Err(anyhow::anyhow!(
+            "Port forwarding is not supported for command connections"
+        ))
+    }
+
+    fn build_port_proxy_command(&self, host: String, port: u16) -> Result<CommandTemplate> {
+        let remote_binary_relpath = self
+            .remote_binary_relpath
+            .as_ref()
+            .context("Remote binary path not set")?;
+        let script = exec_script(
+            &remote_binary_relpath.display(self.path_style()),
+            &[
+                "forward-port".to_string(),
+                "--host".to_string(),
+                host,
+                "--port".to_string(),
+                port.to_string(),
+            ],
+            &HashMap::default(),
+            Some(&self.remote_dir_for_server),
+        )?;
+        Ok(self.connection_options.wrap(script, false))
+    }
+
+    fn connection_options(&self) -> RemoteConnectionOptions {
+        RemoteConnectionOptions::Command(self.connection_options.clone())
+    }
+
+    fn path_style(&self) -> PathStyle {
+        self.path_style.unwrap_or(PathStyle::Posix)
+    }
+
+    fn shell(&self) -> String {
+        match &self.shell {
+            Some(shell) => shell.clone(),
+            None => self.default_system_shell(),
+        }
+    }
+
+    fn default_system_shell(&self) -> String {
+        String::from("/bin/sh")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exec_script() {
+        assert_eq!(
+            exec_script("ls", &["-la".into()], &HashMap::default(), None).unwrap(),
+            "exec ls -la"
+        );
+        assert_eq!(
+            exec_script(
+                "/bin/bash",
+                &["-l".into()],
+                &HashMap::from_iter([("FOO".to_string(), "a b".to_string())]),
+                Some("~/my project"),
+            )
+            .unwrap(),
+            "cd \"$HOME\"/'my project' && exec env 'FOO=a b' /bin/bash -l"
+        );
+        assert_eq!(
+            exec_script("pwd", &[], &HashMap::default(), Some("/srv/app")).unwrap(),
+            "cd /srv/app && exec pwd"
+        );
+    }
+
+    #[test]
+    fn test_wrap() {
+        let options = CommandConnectionOptions {
+            name: "pod".into(),
+            program: "kubectl".into(),
+            args: vec!["exec".into(), "-i".into(), "pod".into(), "--".into()],
+            tty_args: Some(vec!["exec".into(), "-it".into(), "pod".into(), "--".into()]),
+            env: BTreeMap::from_iter([("KUBECONFIG".to_string(), "/tmp/config".to_string())]),
+            upload_binary_over_exec: false,
+        };
+
+        let template = options.wrap("uname -sm".into(), false);
+        assert_eq!(template.program, "kubectl");
+        assert_eq!(
+            template.args,
+            ["exec", "-i", "pod", "--", "sh", "-c", "uname -sm"]
+        );
+        assert_eq!(
+            template.env.get("KUBECONFIG").map(String::as_str),
+            Some("/tmp/config")
+        );
+
+        let template = options.wrap("exec bash -l".into(), true);
+        assert_eq!(
+            template.args,
+            ["exec", "-it", "pod", "--", "sh", "-c", "exec bash -l"]
+        );
+    }
+
+    #[test]
+    fn test_build_command() {
+        let connection = CommandExecConnection {
+            proxy_process: ProxyProcess::default(),
+            remote_dir_for_server: "/home/user".into(),
+            remote_binary_relpath: Some(RelPath::unix(".zed_server/server").unwrap().into()),
+            connection_options: CommandConnectionOptions {
+                name: "pod".into(),
+                program: "kubectl".into(),
+                args: vec!["exec".into(), "-i".into(), "pod".into(), "--".into()],
+                tty_args: Some(vec!["exec".into(), "-it".into(), "pod".into(), "--".into()]),
+                ..Default::default()
+            },
+            path_style: Some(PathStyle::Posix),
+            shell: Some("/bin/zsh".into()),
+        };
+
+        // The script is passed to `sh -c` as a single argument, with its own
+        // arguments quoted inside of it.
+        let template = connection
+            .build_command(
+                Some("echo".into()),
+                &["hello world".into(), "$HOME".into()],
+                &HashMap::from_iter([("NAME".to_string(), "my pod".to_string())]),
+                Some("~/my project".into()),
+                None,
+            )
+            .unwrap();
+        assert_eq!(template.program, "kubectl");
+        assert_eq!(
+            template.args,
+            [
+                "exec",
+                "-it",
+                "pod",
+                "--",
+                "sh",
+                "-c",
+                "cd \"$HOME\"/'my project' && exec env 'NAME=my pod' echo 'hello world' '$HOME'"
+            ]
+        );
+        assert!(template.env.is_empty());
+
+        // Without a program, the user's shell is started as a login shell.
+        let template = connection
+            .build_command(None, &[], &HashMap::default(), None, None)
+            .unwrap();
+        assert_eq!(
+            template.args,
+            ["exec", "-it", "pod", "--", "sh", "-c", "exec /bin/zsh -l"]
+        );
+
+        let template = connection
+            .build_port_proxy_command("localhost".into(), 8080)
+            .unwrap();
+        assert_eq!(
+            template.args,
+            [
+                "exec",
+                "-i",
+                "pod",
+                "--",
+                "sh",
+                "-c",
+                "cd /home/user && exec .zed_server/server forward-port --host localhost --port 8080"
+            ]
+        );
+        assert!(
+            connection
+                .build_forward_ports_command(vec![(8080, "localhost".into(), 80)])
+                .is_err()
+        );
+    }
+}
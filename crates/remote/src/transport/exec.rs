@@ -0,0 +1,725 @@
+//! Plumbing shared by the transports that reach the remote side by wrapping every command
+//! in a local exec-style command, such as `docker exec`.
+
+use anyhow::Context as _;
+use anyhow::Result;
+use async_trait::async_trait;
+use futures::channel::mpsc::{Sender, UnboundedReceiver, UnboundedSender};
+use gpui::{AsyncApp, Task};
+use parking_lot::Mutex;
+use release_channel::{AppCommitSha, AppVersion, ReleaseChannel};
+use rpc::proto::Envelope;
+use std::borrow::Cow;
+use std::time::Instant;
+use std::{path::Path, process::Stdio, sync::Arc};
+use util::ResultExt;
+use util::rel_path::RelPath;
+use util::shell::ShellKind;
+
+use crate::{
+    RemoteClientDelegate, RemoteConnection, RemotePlatform, remote_client::CommandTemplate,
+};
+
+/// A connection that runs commands on the remote side through a local exec-style command.
+#[async_trait(?Send)]
+pub(super) trait ExecConnection: RemoteConnection {
+    /// Whether the server binary should always be uploaded from this machine, rather than
+    /// downloaded on the remote side.
+    fn upload_binary_from_local(&self) -> bool;
+
+    /// Runs a `sh` script on the remote side and returns its stdout.
+    async fn run_script(&self, script: &str, working_directory: Option<&str>) -> Result<String>;
+
+    /// Copies a local file to `dest_path`, relative to `remote_dir_for_server`.
+    ///
+    /// The parent directory of `dest_path` already exists.
+    async fn upload_file(
+        &self,
+        src_path: &Path,
+        dest_path: &RelPath,
+        remote_dir_for_server: &str,
+    ) -> Result<()>;
+}
+
+pub(super) fn quote(arg: &str) -> Result<Cow<'_, str>> {
+    ShellKind::Posix.try_quote(arg).context("shell quoting")
+}
+
+/// Makes sure a remote server binary matching this version of Zed exists on the remote side,
+/// downloading or uploading it if necessary, and returns its path relative to
+/// `remote_dir_for_server`.
+pub(super) async fn ensure_server_binary(
+    connection: &impl ExecConnection,
+    remote_platform: RemotePlatform,
+    remote_dir_for_server: &str,
+    delegate: &Arc<dyn RemoteClientDelegate>,
+    cx: &mut AsyncApp,
+) -> Result<Arc<RelPath>> {
+    let (release_channel, version, commit) = cx.update(|cx| {
+        (
+            ReleaseChannel::global(cx),
+            AppVersion::global(cx),
+            AppCommitSha::try_global(cx),
+        )
+    })?;
+    let version_str = match release_channel {
+        ReleaseChannel::Nightly => {
+            let commit = commit.map(|s| s.full()).unwrap_or_default();
+            format!("{}-{}", version, commit)
+        }
+        ReleaseChannel::Dev => "build".to_string(),
+        _ => version.to_string(),
+    };
+    let binary_name = format!(
+        "zed-remote-server-{}-{}",
+        release_channel.dev_name(),
+        version_str
+    );
+    let dst_path = paths::remote_server_dir_relative().join(RelPath::unix(&binary_name).unwrap());
+
+    // Tests use fake connections, which can't run a server built from source.
+    #[cfg(all(debug_assertions, not(test)))]
+    if let Some(remote_server_path) =
+        super::build_remote_server_from_source(&remote_platform, delegate.as_ref(), cx).await?
+    {
+        let tmp_path = paths::remote_server_dir_relative().join(
+            RelPath::unix(&format!(
+                "download-{}-{}",
+                std::process::id(),
+                remote_server_path.file_name().unwrap().to_string_lossy()
+            ))
+            .unwrap(),
+        );
+        upload_local_server_binary(
+            connection,
+            &remote_server_path,
+            &tmp_path,
+            remote_dir_for_server,
+            delegate,
+            cx,
+        )
+        .await?;
+        extract_server_binary(
+            connection,
+            &dst_path,
+            &tmp_path,
+            remote_dir_for_server,
+            delegate,
+            cx,
+        )
+        .await?;
+        return Ok(dst_path);
+    }
+
+    let quoted_dst_path = quote(&dst_path.display(connection.path_style()))?.into_owned();
+    if connection
+        .run_script(
+            &format!("{quoted_dst_path} version"),
+            Some(remote_dir_for_server),
+        )
+        .await
+        .is_ok()
+    {
+        return Ok(dst_path);
+    }
+
+    let wanted_version = cx.update(|cx| match release_channel {
+        ReleaseChannel::Nightly => Ok(None),
+        ReleaseChannel::Dev => {
+            anyhow::bail!(
+                "ZED_BUILD_REMOTE_SERVER is not set and no remote server exists at ({:?})",
+                dst_path
+            )
+        }
+        _ => Ok(Some(AppVersion::global(cx))),
+    })??;
+
+    let tmp_path_gz = paths::remote_server_dir_relative().join(
+        RelPath::unix(&format!(
+            "{}-download-{}.gz",
+            binary_name,
+            std::process::id()
+        ))
+        .unwrap(),
+    );
+    if !connection.upload_binary_from_local()
+        && let Some(url) = delegate
+            .get_download_url(remote_platform, release_channel, wanted_version.clone(), cx)
+            .await?
+    {
+        match download_binary_on_server(
+            connection,
+            &url,
+            &tmp_path_gz,
+            remote_dir_for_server,
+            delegate,
+            cx,
+        )
+        .await
+        {
+            Ok(_) => {
+                extract_server_binary(
+                    connection,
+                    &dst_path,
+                    &tmp_path_gz,
+                    remote_dir_for_server,
+                    delegate,
+                    cx,
+                )
+                .await
+                .context("extracting server binary")?;
+                return Ok(dst_path);
+            }
+            Err(e) => {
+                log::error!(
+                    "Failed to download binary on server, attempting to download locally and then upload it the server: {e:#}",
+                )
+            }
+        }
+    }
+
+    let src_path = delegate
+        .download_server_binary_locally(remote_platform, release_channel, wanted_version, cx)
+        .await
+        .context("downloading server binary locally")?;
+    upload_local_server_binary(
+        connection,
+        &src_path,
+        &tmp_path_gz,
+        remote_dir_for_server,
+        delegate,
+        cx,
+    )
+    .await
+    .context("uploading server binary")?;
+    extract_server_binary(
+        connection,
+        &dst_path,
+        &tmp_path_gz,
+        remote_dir_for_server,
+        delegate,
+        cx,
+    )
+    .await
+    .context("extracting server binary")?;
+    Ok(dst_path)
+}
+
+async fn extract_server_binary(
+    connection: &impl ExecConnection,
+    dst_path: &RelPath,
+    tmp_path: &RelPath,
+    remote_dir_for_server: &str,
+    delegate: &Arc<dyn RemoteClientDelegate>,
+    cx: &mut AsyncApp,
+) -> Result<()> {
+    delegate.set_status(Some("Extracting remote development server"), cx);
+    let server_mode = format!("{:o}", 0o755);
+    let orig_tmp_path = tmp_path.display(connection.path_style());
+    let dst_path = quote(&dst_path.display(connection.path_style()))?.into_owned();
+    let script = if let Some(tmp_path) = orig_tmp_path.strip_suffix(".gz") {
+        let orig_tmp_path = quote(&orig_tmp_path)?;
+        let tmp_path = quote(tmp_path)?;
+        format!(
+            "gunzip -f {orig_tmp_path} && chmod {server_mode} {tmp_path} && mv {tmp_path} {dst_path}",
+        )
+    } else {
+        let orig_tmp_path = quote(&orig_tmp_path)?;
+        format!("chmod {server_mode} {orig_tmp_path} && mv {orig_tmp_path} {dst_path}")
+    };
+    connection
+        .run_script(&script, Some(remote_dir_for_server))
+        .await
+        .log_err();
+    Ok(())
+}
+
+async fn upload_local_server_binary(
+    connection: &impl ExecConnection,
+    src_path: &Path,
+    tmp_path_gz: &RelPath,
+    remote_dir_for_server: &str,
+    delegate: &Arc<dyn RemoteClientDelegate>,
+    cx: &mut AsyncApp,
+) -> Result<()> {
+    create_parent_dir(connection, tmp_path_gz, remote_dir_for_server).await?;
+
+    let size = smol::fs::metadata(&src_path).await?.len();
+
+    let t0 = Instant::now();
+    delegate.set_status(Some("Uploading remote development server"), cx);
+    log::info!(
+        "uploading remote development server to {:?} ({}kb)",
+        tmp_path_gz,
+        size / 1024
+    );
+    connection
+        .upload_file(src_path, tmp_path_gz, remote_dir_for_server)
+        .await
+        .context("failed to upload server binary")?;
+    log::info!("uploaded remote development server in {:?}", t0.elapsed());
+    Ok(())
+}
+
+async fn download_binary_on_server(
+    connection: &impl ExecConnection,
+    url: &str,
+    tmp_path_gz: &RelPath,
+    remote_dir_for_server: &str,
+    delegate: &Arc<dyn RemoteClientDelegate>,
+    cx: &mut AsyncApp,
+) -> Result<()> {
+    create_parent_dir(connection, tmp_path_gz, remote_dir_for_server).await?;
+
+    delegate.set_status(Some("Downloading remote development server on host"), cx);
+
+    let url = quote(url)?;
+    let tmp_path_gz = quote(&tmp_path_gz.display(connection.path_style()))?.into_owned();
+    let script = format!(
+        "if command -v curl >/dev/null 2>&1; then curl -f -L {url} -o {tmp_path_gz}; \
+         elif command -v wget >/dev/null 2>&1; then wget {url} -O {tmp_path_gz}; \
+         else echo 'Neither curl nor wget is available' >&2; exit 1; fi"
+    );
+    connection
+        .run_script(&script, Some(remote_dir_for_server))
+        .await?;
+    Ok(())
+}
+
+async fn create_parent_dir(
+    connection: &impl ExecConnection,
+    path: &RelPath,
+    remote_dir_for_server: &str,
+) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        connection
+            .run_script(
+                &format!(
+                    "mkdir -p {}",
+                    quote(&parent.display(connection.path_style()))?
+                ),
+                Some(remote_dir_for_server),
+            )
+            .await?;
+    }
+    Ok(())
+}
+
+/// The local process that proxies messages to the remote server.
+#[derive(Default)]
+pub(super) struct ProxyProcess(Mutex<Option<u32>>);
+
+impl ProxyProcess {
+    /// Spawns the proxy with `template` and relays messages over its stdio.
+    pub fn start(
+        &self,
+        template: CommandTemplate,
+        incoming_tx: UnboundedSender<Envelope>,
+        outgoing_rx: UnboundedReceiver<Envelope>,
+        connection_activity_tx: Sender<()>,
+        cx: &AsyncApp,
+    ) -> Task<Result<i32>> {
+        let mut command = util::command::new_smol_command(&template.program);
+        command
+            .kill_on_drop(true)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .args(&template.args)
+            .envs(&template.env);
+
+        let Ok(child) = command.spawn() else {
+            return Task::ready(Err(anyhow::anyhow!(
+                "Failed to start remote server process"
+            )));
+        };
+
+        *self.0.lock() = Some(child.id());
+
+        super::handle_rpc_messages_over_child_process_stdio(
+            child,
+            incoming_tx,
+            outgoing_rx,
+            connection_activity_tx,
+            cx,
+        )
+    }
+
+    pub fn kill(&self) -> Result<()> {
+        if let Some(pid) = self.0.lock().take() {
+            if let Ok(_) = util::command::new_smol_command("kill")
+                .arg(pid.to_string())
+                .spawn()
+            {
+                Ok(())
+            } else {
+                Err(anyhow::anyhow!("Failed to kill process"))
+            }
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn has_been_killed(&self) -> bool {
+        self.0.lock().is_none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{RemoteArch, RemoteConnectionOptions, RemoteOs};
+    use askpass::EncryptedPassword;
+    use collections::HashMap;
+    use futures::channel::oneshot;
+    use gpui::{App, TestAppContext};
+    use semver::Version;
+    use std::path::PathBuf;
+    use util::paths::{PathStyle, RemotePathBuf};
+
+    const REMOTE_DIR: &str = "/home/user";
+
+    /// A connection that records the scripts it runs and the files it uploads.
+    #[derive(Default)]
+    struct FakeExecConnection {
+        upload_binary_from_local: bool,
+        /// Scripts that fail, by their prefix.
+        failing_scripts: Vec<String>,
+        scripts: Mutex<Vec<String>>,
+        uploads: Mutex<Vec<(PathBuf, String)>>,
+    }
+
+    #[async_trait(?Send)]
+    impl ExecConnection for FakeExecConnection {
+        fn upload_binary_from_local(&self) -> bool {
+            self.upload_binary_from_local
+        }
+
+        async fn run_script(
+            &self,
+            script: &str,
+            working_directory: Option<&str>,
+        ) -> Result<String> {
+            assert_eq!(working_directory, Some(REMOTE_DIR));
+            self.scripts.lock().push(script.to_string());
+            if self
+                .failing_scripts
+                .iter()
+                .any(|prefix| script.starts_with(prefix))
+            {
+                anyhow::bail!("script failed");
+            }
+            Ok(String::new())
+        }
+
+        async fn upload_file(
+            &self,
+            src_path: &Path,
+            dest_path: &RelPath,
+            remote_dir_for_server: &str,
+        ) -> Result<()> {
+            assert_eq!(remote_dir_for_server, REMOTE_DIR);
+            self.uploads.lock().push((
+                src_path.to_path_buf(),
+                dest_path.display(PathStyle::Posix).into_owned(),
+            ));
+            Ok(())
+        }
+    }
+
+    #[async_trait(?Send)]
+    impl RemoteConnection for FakeExecConnection {
+        fn start_proxy(
+            &self,
+            _: String,
+            _: bool,
+            _: UnboundedSender<Envelope>,
+            _: UnboundedReceiver<Envelope>,
+            _: Sender<()>,
+            _: Arc<dyn RemoteClientDelegate>,
+            _: &mut AsyncApp,
+        ) -> Task<Result<i32>> {
+            unreachable!()
+        }
+
+        fn upload_directory(&self, _: PathBuf, _: RemotePathBuf, _: &App) -> Task<Result<()>> {
+            unreachable!()
+        }
+
+        async fn kill(&self) -> Result<()> {
+            Ok(())
+        }
+
+        fn has_been_killed(&self) -> bool {
+            false
+        }
+
+        fn build_command(
+            &self,
+            _: Option<String>,
+            _: &[String],
+            _: &HashMap<String, String>,
+            _: Option<String>,
+            _: Option<(u16, String, u16)>,
+        ) -> Result<CommandTemplate> {
+            unreachable!()
+        }
+
+        fn build_forward_ports_command(
+            &self,
+            _: Vec<(u16, String, u16)>,
+        ) -> Result<CommandTemplate> {
+            unreachable!()
+        }
+
+        fn connection_options(&self) -> RemoteConnectionOptions {
+            unreachable!()
+        }
+
+        fn path_style(&self) -> PathStyle {
+            PathStyle::Posix
+        }
+
+        fn shell(&self) -> String {
+            "sh".to_owned()
+        }
+
+        fn default_system_shell(&self) -> String {
+            "sh".to_owned()
+        }
+
+        fn has_wsl_interop(&self) -> bool {
+            false
+        }
+    }
+
+    struct Delegate {
+        download_url: Option<String>,
+        local_binary: PathBuf,
+    }
+
+    impl RemoteClientDelegate for Delegate {
+        fn ask_password(&self, _: String, _: oneshot::Sender<EncryptedPassword>, _: &mut AsyncApp) {
+            unreachable!()
+        }
+
+        fn get_download_url(
+            &self,
+            _: RemotePlatform,
+            _: ReleaseChannel,
+            _: Option<Version>,
+            _: &mut AsyncApp,
+        ) -> Task<Result<Option<String>>> {
+            Task::ready(Ok(self.download_url.clone()))
+        }
+
+        fn download_server_binary_locally(
+            &self,
+            _: RemotePlatform,
+            _: ReleaseChannel,
+            _: Option<Version>,
+            _: &mut AsyncApp,
+        ) -> Task<Result<PathBuf>> {
+            Task::ready(Ok(self.local_binary.clone()))
+        }
+
+        fn set_status(&self, _: Option<&str>, _: &mut AsyncApp) {}
+    }
+
+    fn init_test(release_channel: ReleaseChannel, cx: &mut TestAppContext) {
+        cx.update(|cx| release_channel::init_test(Version::new(1, 2, 3), release_channel, cx));
+    }
+
+    async fn ensure_binary(
+        connection: &FakeExecConnection,
+        delegate: Delegate,
+        cx: &mut TestAppContext,
+    ) -> Result<Arc<RelPath>> {
+        let platform = RemotePlatform {
+            os: RemoteOs::Linux,
+            arch: RemoteArch::X86_64,
+        };
+        let delegate: Arc<dyn RemoteClientDelegate> = Arc::new(delegate);
+        ensure_server_binary(
+            connection,
+            platform,
+            REMOTE_DIR,
+            &delegate,
+            &mut cx.to_async(),
+        )
+        .await
+    }
+
+    #[gpui::test]
+    async fn test_ensure_server_binary_exists(cx: &mut TestAppContext) {
+        init_test(ReleaseChannel::Stable, cx);
+        let connection = FakeExecConnection::default();
+        let delegate = Delegate {
+            download_url: Some("https://example.com/server.gz".into()),
+            local_binary: PathBuf::new(),
+        };
+
+        let path = ensure_binary(&connection, delegate, cx).await.unwrap();
+        assert_eq!(
+            path.display(PathStyle::Posix),
+            ".zed_server/zed-remote-server-stable-1.2.3"
+        );
+        assert_eq!(
+            *connection.scripts.lock(),
+            [".zed_server/zed-remote-server-stable-1.2.3 version"]
+        );
+        assert!(connection.uploads.lock().is_empty());
+    }
+
+    #[gpui::test]
+    async fn test_ensure_server_binary_downloads_on_server(cx: &mut TestAppContext) {
+        init_test(ReleaseChannel::Stable, cx);
+        let connection = FakeExecConnection {
+            failing_scripts: vec![".zed_server/zed-remote-server-stable-1.2.3 version".into()],
+            ..Default::default()
+        };
+        let delegate = Delegate {
+            download_url: Some("https://example.com/server?version=1.2.3&os=linux".into()),
+            local_binary: PathBuf::new(),
+        };
+
+        ensure_binary(&connection, delegate, cx).await.unwrap();
+        let tmp_path = format!(
+            ".zed_server/zed-remote-server-stable-1.2.3-download-{}",
+            std::process::id()
+        );
+        assert_eq!(
+            *connection.scripts.lock(),
+            [
+                ".zed_server/zed-remote-server-stable-1.2.3 version".to_string(),
+                "mkdir -p .zed_server".to_string(),
+                format!(
+                    "if command -v curl >/dev/null 2>&1; then curl -f -L 'https://example.com/server?version=1.2.3&os=linux' -o {tmp_path}.gz; \
+                     elif command -v wget >/dev/null 2>&1; then wget 'https://example.com/server?version=1.2.3&os=linux' -O {tmp_path}.gz; \
+                     else echo 'Neither curl nor wget is available' >&2; exit 1; fi"
+                ),
+                format!(
+                    "gunzip -f {tmp_path}.gz && chmod 755 {tmp_path} && mv {tmp_path} .zed_server/zed-remote-server-stable-1.2.3"
+                ),
+            ]
+        );
+        assert!(connection.uploads.lock().is_empty());
+    }
+
+    #[gpui::test]
+    async fn test_ensure_server_binary_uploads_when_download_fails(cx: &mut TestAppContext) {
+        init_test(ReleaseChannel::Stable, cx);
+        let local_binary = tempfile::NamedTempFile::new().unwrap();
+        let connection = FakeExecConnection {
+            failing_scripts: vec![
+                ".zed_server/zed-remote-server-stable-1.2.3 version".into(),
+                "if command -v curl".into(),
+            ],
+            ..Default::default()
+        };
+        let delegate = Delegate {
+            download_url: Some("https://example.com/server.gz".into()),
+            local_binary: local_binary.path().to_path_buf(),
+        };
+
+        ensure_binary(&connection, delegate, cx).await.unwrap();
+        let tmp_path = format!(
+            ".zed_server/zed-remote-server-stable-1.2.3-download-{}",
+            std::process::id()
+        );
+        let scripts = connection.scripts.lock();
+        assert_eq!(scripts.len(), 5);
+        assert_eq!(scripts[1], "mkdir -p .zed_server");
+        assert!(scripts[2].starts_with("if command -v curl"));
+        assert_eq!(scripts[3], "mkdir -p .zed_server");
+        assert!(scripts[4].starts_with(&format!("gunzip -f {tmp_path}.gz && ")));
+        assert_eq!(
+            *connection.uploads.lock(),
+            [(local_binary.path().to_path_buf(), format!("{tmp_path}.gz"))]
+        );
+    }
+
+    #[gpui::test]
+    async fn test_ensure_server_binary_uploads_from_local(cx: &mut TestAppContext) {
+        init_test(ReleaseChannel::Stable, cx);
+        let local_binary = tempfile::NamedTempFile::new().unwrap();
+        let connection = FakeExecConnection {
+            upload_binary_from_local: true,
+            failing_scripts: vec![".zed_server/zed-remote-server-stable-1.2.3 version".into()],
+            ..Default::default()
+        };
+        let delegate = Delegate {
+            download_url: Some("https://example.com/server.gz".into()),
+            local_binary: local_binary.path().to_path_buf(),
+        };
+
+        ensure_binary(&connection, delegate, cx).await.unwrap();
+        let scripts = connection.scripts.lock();
+        assert!(
+            !scripts
+                .iter()
+                .any(|script| script.starts_with("if command -v curl"))
+        );
+        assert_eq!(connection.uploads.lock().len(), 1);
+    }
+
+    #[gpui::test]
+    async fn test_ensure_server_binary_requires_dev_build(cx: &mut TestAppContext) {
+        init_test(ReleaseChannel::Dev, cx);
+        let connection = FakeExecConnection {
+            failing_scripts: vec![".zed_server/zed-remote-server-dev-build version".into()],
+            ..Default::default()
+        };
+        let delegate = Delegate {
+            download_url: None,
+            local_binary: PathBuf::new(),
+        };
+
+        let error = ensure_binary(&connection, delegate, cx).await.unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .starts_with("ZED_BUILD_REMOTE_SERVER is not set")
+        );
+        assert!(connection.uploads.lock().is_empty());
+    }
+
+    #[gpui::test]
+    async fn test_extract_server_binary_quotes_paths(cx: &mut TestAppContext) {
+        let connection = FakeExecConnection::default();
+        let delegate: Arc<dyn RemoteClientDelegate> = Arc::new(Delegate {
+            download_url: None,
+            local_binary: PathBuf::new(),
+        });
+
+        extract_server_binary(
+            &connection,
+            RelPath::unix("my server/zed-remote-server").unwrap(),
+            RelPath::unix("my server/download").unwrap(),
+            REMOTE_DIR,
+            &delegate,
+            &mut cx.to_async(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            *connection.scripts.lock(),
+            [
+                "chmod 755 'my server/download' && mv 'my server/download' 'my server/zed-remote-server'"
+            ]
+        );
+    }
+
+    #[gpui::test]
+    async fn test_create_parent_dir(_cx: &mut TestAppContext) {
+        let connection = FakeExecConnection::default();
+        create_parent_dir(
+            &connection,
+            RelPath::unix("a dir/nested/file.gz").unwrap(),
+            REMOTE_DIR,
+        )
+        .await
+        .unwrap();
+        assert_eq!(*connection.scripts.lock(), ["mkdir -p 'a dir/nested'"]);
+    }
+}
@@ -92,6 +92,7 @@ impl HeadlessProject {
         cx: &mut Context<Self>,
     ) -> Self {
         debug_adapter_extension::init(proxy.clone(), cx);
+        project::lsp_store::extension::init(proxy.clone(), cx);
         languages::init(languages.clone(), fs.clone(), node_runtime.clone(), cx);
 
         let worktree_store = cx.new(|cx| {
@@ -266,6 +267,7 @@ impl HeadlessProject {
         session.add_request_handler(cx.weak_entity(), Self::handle_shutdown_remote_server);
         session.add_request_handler(cx.weak_entity(), Self::handle_ping);
         session.add_request_handler(cx.weak_entity(), Self::handle_get_processes);
+        session.add_request_handler(cx.weak_entity(), Self::handle_get_listening_ports);
 
         session.add_entity_request_handler(Self::handle_add_worktree);
         session.add_request_handler(cx.weak_entity(), Self::handle_remove_worktree);
@@ -914,6 +916,23 @@ impl HeadlessProject {
         Ok(proto::GetProcessesResponse { processes })
     }
 
+    async fn handle_get_listening_ports(
+        _this: Entity<Self>,
+        envelope: TypedEnvelope<proto::GetListeningPorts>,
+        cx: AsyncApp,
+    ) -> Result<proto::GetListeningPortsResponse> {
+        let terminal_id = envelope.payload.terminal_id;
+        let ports = cx
+            .background_spawn(async move {
+                let pids = crate::listening_ports::terminal_processes(&terminal_id);
+                crate::listening_ports::listening_ports(&pids)
+            })
+            .await?;
+        Ok(proto::GetListeningPortsResponse {
+            ports: ports.into_iter().map(u32::from).collect(),
+        })
+    }
+
     async fn handle_get_directory_environment(
         this: Entity<Self>,
         envelope: TypedEnvelope<proto::GetDirectoryEnvironment>,
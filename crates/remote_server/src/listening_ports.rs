@@ -0,0 +1,166 @@
+//! Detection of the TCP ports listening on the remote host, so that the client
+//! can offer to forward them.
+
+use anyhow::Result;
+use collections::HashSet;
+use project::terminals::REMOTE_TERMINAL_ID_ENV_VAR;
+use sysinfo::{ProcessRefreshKind, RefreshKind, System, UpdateKind};
+
+/// Returns the processes started from the client's terminals, which are marked
+/// with `terminal_id`, along with their descendants, which inherit the marker.
+pub fn terminal_processes(terminal_id: &str) -> HashSet<u32> {
+    let marker = format!("{REMOTE_TERMINAL_ID_ENV_VAR}={terminal_id}");
+    let refresh_kind = RefreshKind::nothing().with_processes(
+        ProcessRefreshKind::nothing()
+            .without_tasks()
+            .with_environ(UpdateKind::Always),
+    );
+    System::new_with_specifics(refresh_kind)
+        .processes()
+        .values()
+        .filter(|process| process.environ().iter().any(|var| *var == *marker))
+        .map(|process| process.pid().as_u32())
+        .collect()
+}
+
+/// Returns the TCP ports that the given processes are listening on, sorted and
+/// deduplicated.
+pub fn listening_ports(pids: &HashSet<u32>) -> Result<Vec<u16>> {
+    if pids.is_empty() {
+        return Ok(Vec::new());
+    }
+    let mut ports = listening_ports_inner(pids)?;
+    ports.sort_unstable();
+    ports.dedup();
+    Ok(ports)
+}
+
+#[cfg(target_os = "linux")]
+fn listening_ports_inner(pids: &HashSet<u32>) -> Result<Vec<u16>> {
+    use anyhow::Context as _;
+    use collections::HashMap;
+
+    let mut ports_by_inode = HashMap::default();
+    for path in ["/proc/net/tcp", "/proc/net/tcp6"] {
+        match std::fs::read_to_string(path) {
+            Ok(contents) => ports_by_inode.extend(parse_proc_net_tcp(&contents)),
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => {}
+            Err(error) => return Err(error).with_context(|| format!("reading {path}")),
+        }
+    }
+
+    let mut ports = Vec::new();
+    for pid in pids {
+        // The process may have exited since it was listed.
+        let Ok(fds) = std::fs::read_dir(format!("/proc/{pid}/fd")) else {
+            continue;
+        };
+        for fd in fds.flatten() {
+            if let Ok(target) = std::fs::read_link(fd.path())
+                && let Some(inode) = parse_socket_inode(&target.to_string_lossy())
+                && let Some(port) = ports_by_inode.get(&inode)
+            {
+                ports.push(*port);
+            }
+        }
+    }
+    Ok(ports)
+}
+
+#[cfg(target_os = "macos")]
+fn listening_ports_inner(pids: &HashSet<u32>) -> Result<Vec<u16>> {
+    use anyhow::Context as _;
+
+    // lsof exits with a non-zero status when nothing is listening, so only
+    // the output is checked.
+    let output = std::process::Command::new("lsof")
+        .args(["-nP", "-a", "-iTCP", "-sTCP:LISTEN", "-Fn", "-p"])
+        .arg(
+            pids.iter()
+                .map(|pid| pid.to_string())
+                .collect::<Vec<_>>()
+                .join(","),
+        )
+        .output()
+        .context("running lsof")?;
+    Ok(parse_lsof_output(&String::from_utf8_lossy(&output.stdout)).collect())
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn listening_ports_inner(_: &HashSet<u32>) -> Result<Vec<u16>> {
+    Ok(Vec::new())
+}
+
+/// Parses the contents of `/proc/net/tcp` or `/proc/net/tcp6`, where each row
+/// looks like `0: 0100007F:0BB8 00000000:0000 0A ... 1000 0 12345 ...`, with
+/// hex-encoded ports, into the inodes and ports of the listening sockets.
+#[cfg(any(target_os = "linux", test))]
+fn parse_proc_net_tcp(contents: &str) -> impl Iterator<Item = (u64, u16)> + '_ {
+    const TCP_LISTEN: &str = "0A";
+
+    contents.lines().skip(1).filter_map(|line| {
+        let mut fields = line.split_whitespace();
+        let local_address = fields.nth(1)?;
+        let state = fields.nth(1)?;
+        if state != TCP_LISTEN {
+            return None;
+        }
+        let inode = fields.nth(5)?.parse().ok()?;
+        let (_, port) = local_address.rsplit_once(':')?;
+        Some((inode, u16::from_str_radix(port, 16).ok()?))
+    })
+}
+
+/// Parses the target of a `/proc/<pid>/fd` link to a socket, like `socket:[12345]`.
+#[cfg(any(target_os = "linux", test))]
+fn parse_socket_inode(target: &str) -> Option<u64> {
+    target
+        .strip_prefix("socket:[")?
+        .strip_suffix(']')?
+        .parse()
+        .ok()
+}
+
+/// Parses the output of `lsof -Fn`, where each listening socket is reported on
+/// a line like `n*:3000`, `n127.0.0.1:8080` or `n[::1]:5173`.
+#[cfg(any(target_os = "macos", test))]
+fn parse_lsof_output(output: &str) -> impl Iterator<Item = u16> + '_ {
+    output.lines().filter_map(|line| {
+        let name = line.strip_prefix('n')?;
+        let (_, port) = name.rsplit_once(':')?;
+        port.parse().ok()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_proc_net_tcp() {
+        let contents = "  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode\n   \
+            0: 0100007F:0BB8 00000000:0000 0A 00000000:00000000 00:00000000 00000000  1000        0 1 1 0 100 0 0 10 0\n   \
+            1: 00000000:1F90 00000000:0000 0A 00000000:00000000 00:00000000 00000000  1000        0 2 1 0 100 0 0 10 0\n   \
+            2: 0100007F:0BB8 0100007F:D5A2 01 00000000:00000000 00:00000000 00000000  1000        0 3 1 0 20 4 30 10 -1\n";
+        assert_eq!(
+            parse_proc_net_tcp(contents).collect::<Vec<_>>(),
+            vec![(1, 3000), (2, 8080)]
+        );
+    }
+
+    #[test]
+    fn test_parse_socket_inode() {
+        assert_eq!(parse_socket_inode("socket:[12345]"), Some(12345));
+        assert_eq!(parse_socket_inode("pipe:[12345]"), None);
+        assert_eq!(parse_socket_inode("/dev/null"), None);
+    }
+
+    #[test]
+    fn test_parse_lsof_output() {
+        let output = "p123\nf5\nn*:3000\nf6\nn127.0.0.1:8080\np456\nf7\nn[::1]:5173\n";
+        assert_eq!(
+            parse_lsof_output(output).collect::<Vec<_>>(),
+            vec![3000, 8080, 5173]
+        );
+    }
+}
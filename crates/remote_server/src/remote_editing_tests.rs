@@ -20,7 +20,7 @@ use language::{
 use lsp::{CompletionContext, CompletionResponse, CompletionTriggerKind, LanguageServerName};
 use node_runtime::NodeRuntime;
 use project::{
-    ProgressToken, Project,
+    ProgressToken, Project, RemoteBufferSyncState,
     agent_server_store::AgentServerCommand,
     search::{SearchQuery, SearchResult},
 };
@@ -31,6 +31,7 @@ use smol::stream::StreamExt;
 use std::{
     path::{Path, PathBuf},
     sync::Arc,
+    time::Duration,
 };
 use unindent::Unindent as _;
 use util::{path, rel_path::rel_path};
@@ -1186,6 +1187,128 @@ async fn test_reconnect(cx: &mut TestAppContext, server_cx: &mut TestAppContext)
     );
 }
 
+#[gpui::test]
+async fn test_offline_saves_replayed_on_reconnect(
+    cx: &mut TestAppContext,
+    server_cx: &mut TestAppContext,
+) {
+    let fs = FakeFs::new(server_cx.executor());
+    fs.insert_tree(
+        path!("/code"),
+        json!({
+            "project1": {
+                ".git": {},
+                "README.md": "# project 1",
+                "src": {
+                    "lib.rs": "fn one() -> usize { 1 }"
+                }
+            },
+        }),
+    )
+    .await;
+
+    let (project, _headless) = init_test(&fs, cx, server_cx).await;
+    let (worktree, _) = project
+        .update(cx, |project, cx| {
+            project.find_or_create_worktree(path!("/code/project1"), true, cx)
+        })
+        .await
+        .unwrap();
+    let worktree_id = worktree.read_with(cx, |worktree, _| worktree.id());
+
+    let lib = project
+        .update(cx, |project, cx| {
+            project.open_buffer((worktree_id, rel_path("src/lib.rs")), cx)
+        })
+        .await
+        .unwrap();
+    let readme = project
+        .update(cx, |project, cx| {
+            project.open_buffer((worktree_id, rel_path("README.md")), cx)
+        })
+        .await
+        .unwrap();
+
+    // Drop the connection, and keep the host unreachable so that reconnecting fails.
+    let client = project.read_with(cx, |project, _| project.remote_client().unwrap());
+    client
+        .update(cx, |client, cx| client.simulate_unreachable(true, cx))
+        .await;
+    client
+        .update(cx, |client, cx| client.simulate_disconnect(cx))
+        .await;
+    cx.run_until_parked();
+    let buffer_store = project.read_with(cx, |project, _| project.buffer_store().clone());
+    assert!(buffer_store.read_with(cx, |buffer_store, _| buffer_store.is_remote_offline()));
+
+    lib.update(cx, |buffer, cx| {
+        let ix = buffer.text().find('1').unwrap();
+        buffer.edit([(ix..ix + 1, "100")], None, cx);
+    });
+    readme.update(cx, |buffer, cx| buffer.edit([(0..0, "local ")], None, cx));
+    let sync_state = |buffer: &Entity<Buffer>, cx: &mut TestAppContext| {
+        let buffer_id = buffer.read_with(cx, |buffer, _| buffer.remote_id());
+        project.read_with(cx, |project, cx| {
+            project.remote_buffer_sync_state(buffer_id, cx)
+        })
+    };
+    assert_eq!(sync_state(&lib, cx), Some(RemoteBufferSyncState::Unsynced));
+
+    for buffer in [&lib, &readme] {
+        project
+            .update(cx, |project, cx| project.save_buffer(buffer.clone(), cx))
+            .await
+            .unwrap();
+    }
+    cx.run_until_parked();
+    assert_eq!(
+        sync_state(&lib, cx),
+        Some(RemoteBufferSyncState::SaveQueued)
+    );
+    assert_eq!(
+        fs.load(path!("/code/project1/src/lib.rs").as_ref())
+            .await
+            .unwrap(),
+        "fn one() -> usize { 1 }"
+    );
+
+    // The README changes on the remote host while its save is queued.
+    fs.save(
+        &PathBuf::from(path!("/code/project1/README.md")),
+        &("# remote".to_string().into()),
+        LineEnding::Unix,
+    )
+    .await
+    .unwrap();
+    cx.run_until_parked();
+
+    client
+        .update(cx, |client, cx| client.simulate_unreachable(false, cx))
+        .await;
+    cx.executor().advance_clock(Duration::from_secs(60));
+    cx.run_until_parked();
+    assert!(!buffer_store.read_with(cx, |buffer_store, _| buffer_store.is_remote_offline()));
+
+    assert_eq!(sync_state(&lib, cx), None);
+    assert_eq!(
+        fs.load(path!("/code/project1/src/lib.rs").as_ref())
+            .await
+            .unwrap(),
+        "fn one() -> usize { 100 }"
+    );
+    assert_eq!(
+        sync_state(&readme, cx),
+        Some(RemoteBufferSyncState::Conflict)
+    );
+    assert!(readme.read_with(cx, |buffer, _| buffer.has_conflict()));
+    assert_eq!(
+        fs.load(path!("/code/project1/README.md").as_ref())
+            .await
+            .unwrap(),
+        "# remote"
+    );
+}
+
 #[gpui::test]
 async fn test_remote_root_rename(cx: &mut TestAppContext, server_cx: &mut TestAppContext) {
     let fs = FakeFs::new(server_cx.executor());
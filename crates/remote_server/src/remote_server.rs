@@ -1,4 +1,5 @@
 mod headless_project;
+mod listening_ports;
 
 #[cfg(not(windows))]
 pub mod unix;
@@ -31,6 +32,13 @@ pub enum Commands {
         #[arg(long)]
         identifier: String,
     },
+    /// Connects stdin and stdout to a TCP port on the remote host.
+    ForwardPort {
+        #[arg(long, default_value = "localhost")]
+        host: String,
+        #[arg(long)]
+        port: u16,
+    },
     Version,
 }
 
@@ -38,7 +46,7 @@ pub enum Commands {
 pub fn run(command: Commands) -> anyhow::Result<()> {
     use anyhow::Context;
     use release_channel::{RELEASE_CHANNEL, ReleaseChannel};
-    use unix::{ExecuteProxyError, execute_proxy, execute_run};
+    use unix::{ExecuteProxyError, execute_forward_port, execute_proxy, execute_run};
 
     match command {
         Commands::Run {
@@ -64,6 +72,7 @@ pub fn run(command: Commands) -> anyhow::Result<()> {
                 }
             })
             .context("running proxy on the remote server"),
+        Commands::ForwardPort { host, port } => execute_forward_port(host, port),
         Commands::Version => {
             let release_channel = *RELEASE_CHANNEL;
             match release_channel {
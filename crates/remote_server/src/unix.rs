@@ -678,6 +678,33 @@ pub(crate) fn execute_proxy(
     Ok(())
 }
 
+/// Connects stdin and stdout to a TCP port on this host, so that the client can
+/// forward a local port through any transport that can run a command.
+pub(crate) fn execute_forward_port(host: String, port: u16) -> Result<()> {
+    use std::io::Read as _;
+    use std::net::{Shutdown, TcpStream};
+
+    let stream = TcpStream::connect((host.as_str(), port))
+        .with_context(|| format!("connecting to {host}:{port}"))?;
+    let mut writer = stream.try_clone()?;
+    std::thread::spawn(move || {
+        std::io::copy(&mut std::io::stdin().lock(), &mut writer).log_err();
+        writer.shutdown(Shutdown::Write).log_err();
+    });
+
+    let mut reader = stream;
+    let mut stdout = std::io::stdout().lock();
+    let mut buffer = [0; 8192];
+    loop {
+        let len = reader.read(&mut buffer)?;
+        if len == 0 {
+            return Ok(());
+        }
+        stdout.write_all(&buffer[..len])?;
+        stdout.flush()?;
+    }
+}
+
 async fn kill_running_server(pid: u32, paths: &ServerPaths) -> Result<(), ExecuteProxyError> {
     log::info!("killing existing server with PID {}", pid);
     new_smol_command("kill")
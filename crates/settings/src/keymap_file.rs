@@ -7,7 +7,7 @@ use gpui::{
     NoAction, SharedString, register_action,
 };
 use schemars::{JsonSchema, json_schema};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use serde_json::{Value, json};
 use std::borrow::Cow;
 use std::{any::TypeId, fmt::Write, rc::Rc, sync::Arc, sync::LazyLock};
@@ -104,7 +104,7 @@ impl KeymapSection {
 /// Unlike the other json types involved in keymaps (including actions), this doc-comment will not
 /// be included in the generated JSON schema, as it manually defines its `JsonSchema` impl. The
 /// actual schema used for it is automatically generated in `KeymapFile::generate_json_schema`.
-#[derive(Debug, Deserialize, Default, Clone)]
+#[derive(Debug, Serialize, Deserialize, Default, Clone, PartialEq)]
 #[serde(transparent)]
 pub struct KeymapAction(Value);
 
@@ -135,6 +135,33 @@ impl JsonSchema for KeymapAction {
     }
 }
 
+impl KeymapAction {
+    /// Returns how `action` is written in a keymap, or `None` if its input is unknown. Actions
+    /// built without input are written as their name, and other actions with the input of one of
+    /// their bindings.
+    pub fn for_action(action: &dyn Action, cx: &App) -> Option<Self> {
+        let name = action.name();
+        if cx
+            .build_action(name, None)
+            .is_ok_and(|built| built.partial_eq(action))
+        {
+            return Some(Self(Value::String(name.to_string())));
+        }
+        let keymap = cx.key_bindings();
+        let keymap = keymap.borrow();
+        keymap.bindings_for_action(action).find_map(|binding| {
+            let input = serde_json::from_str::<Value>(&binding.action_input()?).ok()?;
+            Some(Self(json!([name, input])))
+        })
+    }
+
+    /// Builds the action the way keymaps do, returning a description of the error if it can't be
+    /// built.
+    pub fn build(&self, cx: &App) -> std::result::Result<Box<dyn Action>, String> {
+        KeymapFile::build_keymap_action(self, cx).map(|(action, _)| action)
+    }
+}
+
 #[derive(Debug)]
 #[must_use]
 pub enum KeymapFileLoadResult {
@@ -81,6 +81,9 @@ pub struct SettingsContent {
     /// Default: VSCode
     pub base_keymap: Option<BaseKeymapContent>,
 
+    /// Configuration for the Bookmarks Panel
+    pub bookmarks_panel: Option<BookmarksPanelSettingsContent>,
+
     /// Configuration for the collab panel visual settings.
     pub collaboration_panel: Option<PanelSettingsContent>,
 
@@ -98,6 +101,9 @@ pub struct SettingsContent {
     /// The settings for the image viewer.
     pub image_viewer: Option<ImageViewerSettingsContent>,
 
+    /// The settings for viewing files that are too large to edit.
+    pub large_file_viewer: Option<LargeFileViewerSettingsContent>,
+
     pub repl: Option<ReplSettingsContent>,
 
     /// Whether or not to enable Helix mode.
@@ -107,6 +113,12 @@ pub struct SettingsContent {
 
     pub journal: Option<JournalSettingsContent>,
 
+    /// Keyboard macros by name, each being the actions it runs, written as in
+    /// keymaps. Macros are played with the `keyboard_macros::Play` action.
+    ///
+    /// Example: {"keyboard_macros": {"quote_line": ["editor::MoveToBeginningOfLine", ["editor::HandleInput", "> "]]}}
+    pub keyboard_macros: Option<HashMap<String, Vec<crate::KeymapAction>>>,
+
     /// A map of log scopes to the desired log level.
     /// Useful for filtering out noisy logs or enabling more verbose logging.
     ///
@@ -130,6 +142,9 @@ pub struct SettingsContent {
     /// Configuration for the Notification Panel
     pub notification_panel: Option<NotificationPanelSettingsContent>,
 
+    /// Configuration for the Ports Panel
+    pub ports_panel: Option<PortsPanelSettingsContent>,
+
     pub proxy: Option<String>,
 
     /// The URL of the Zed server to connect to.
@@ -137,6 +152,10 @@ pub struct SettingsContent {
 
     /// Configuration for session-related features
     pub session: Option<SessionSettingsContent>,
+
+    /// Configuration for spell checking in editors.
+    pub spell_check: Option<SpellCheckSettingsContent>,
+
     /// Control what info is collected by Zed.
     pub telemetry: Option<TelemetrySettingsContent>,
 
@@ -188,6 +207,32 @@ pub struct UserSettingsContent {
 
     #[serde(default)]
     pub profiles: IndexMap<String, SettingsContent>,
+
+    /// Rules that activate settings profiles automatically, based on the
+    /// project. The profile of the first matching rule applies to the
+    /// project's files, between user and project settings, unless a profile
+    /// was selected manually.
+    #[serde(default)]
+    pub profile_rules: Vec<SettingsProfileRuleContent>,
+}
+
+/// A rule that activates a settings profile when all of its conditions hold.
+#[with_fallible_options]
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize, JsonSchema, MergeFrom)]
+pub struct SettingsProfileRuleContent {
+    /// The name of the profile to activate.
+    pub profile: String,
+    /// A glob that the absolute path of a worktree in the project must match,
+    /// such as "~/work/monorepo*".
+    pub worktree_path: Option<String>,
+    /// The remote host the project must be opened on, as shown in the title bar.
+    pub remote_host: Option<String>,
+    /// A path, relative to the root of a worktree, that must exist in the
+    /// project, such as "Cargo.toml".
+    pub file_exists: Option<String>,
+    /// The range of local time during which the rule holds, such as
+    /// "09:00-17:00". Ranges that end before they start span midnight.
+    pub time_of_day: Option<String>,
 }
 
 pub struct ExtensionsSettingsContent {
@@ -572,6 +617,46 @@ pub struct NotificationPanelSettingsContent {
     pub default_width: Option<f32>,
 }
 
+#[with_fallible_options]
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema, MergeFrom, Debug, PartialEq)]
+pub struct BookmarksPanelSettingsContent {
+    /// Whether to show the panel button in the status bar.
+    ///
+    /// Default: true
+    pub button: Option<bool>,
+    /// Where to dock the panel.
+    ///
+    /// Default: left
+    pub dock: Option<DockPosition>,
+    /// Default width of the panel in pixels.
+    ///
+    /// Default: 300
+    #[serde(serialize_with = "crate::serialize_optional_f32_with_two_decimal_places")]
+    pub default_width: Option<f32>,
+}
+
+#[with_fallible_options]
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema, MergeFrom, Debug, PartialEq)]
+pub struct PortsPanelSettingsContent {
+    /// Whether to show the panel button in the status bar.
+    ///
+    /// Default: true
+    pub button: Option<bool>,
+    /// Where to dock the panel.
+    ///
+    /// Default: right
+    pub dock: Option<DockPosition>,
+    /// Default width of the panel in pixels.
+    ///
+    /// Default: 300
+    #[serde(serialize_with = "crate::serialize_optional_f32_with_two_decimal_places")]
+    pub default_width: Option<f32>,
+    /// Whether to offer forwarding ports that start listening on the remote host.
+    ///
+    /// Default: true
+    pub notify_detected_ports: Option<bool>,
+}
+
 #[with_fallible_options]
 #[derive(Clone, Default, Serialize, Deserialize, JsonSchema, MergeFrom, Debug, PartialEq)]
 pub struct PanelSettingsContent {
@@ -873,6 +958,40 @@ pub struct ImageViewerSettingsContent {
     pub unit: Option<ImageFileSizeUnit>,
 }
 
+/// The settings for viewing files that are too large to edit.
+#[with_fallible_options]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, MergeFrom, Default, PartialEq)]
+pub struct LargeFileViewerSettingsContent {
+    /// The size, in megabytes, from which files in local projects are opened
+    /// read-only in the large file viewer, rather than loaded into an editor.
+    ///
+    /// Default: 256
+    pub threshold_mb: Option<u64>,
+}
+
+/// The settings for checking the spelling of comments, strings and prose.
+#[with_fallible_options]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, MergeFrom, Default, PartialEq)]
+pub struct SpellCheckSettingsContent {
+    /// Whether to check the spelling of comments and strings in code, and of
+    /// whole Markdown, plain text and git commit message files.
+    ///
+    /// Default: false
+    pub enabled: Option<bool>,
+    /// The Hunspell dictionaries to check words against, by name, such as
+    /// `en_US`. Each is made of a `.aff` and a `.dic` file, which are looked up
+    /// in the `dictionaries` directory of the Zed config directory, and in the
+    /// directories where the system keeps Hunspell dictionaries.
+    ///
+    /// Default: ["en_US"]
+    pub dictionaries: Option<Vec<String>>,
+    /// Words that are never reported as misspelled, in addition to the ones in
+    /// the `.zed/dictionary.txt` file of each project.
+    ///
+    /// Default: []
+    pub words: Option<Vec<String>>,
+}
+
 #[with_fallible_options]
 #[derive(
     Clone,
@@ -902,6 +1021,9 @@ pub struct RemoteSettingsContent {
     pub ssh_connections: Option<Vec<SshConnection>>,
     pub wsl_connections: Option<Vec<WslConnection>>,
     pub dev_container_connections: Option<Vec<DevContainerConnection>>,
+    /// Connections that run the remote server through an exec-style command,
+    /// such as `kubectl exec` or `podman exec`.
+    pub command_connections: Option<Vec<CommandConnection>>,
     pub read_ssh_config: Option<bool>,
 }
 
@@ -938,6 +1060,30 @@ pub struct SshConnection {
     pub connection_timeout: Option<u16>,
 }
 
+#[with_fallible_options]
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, JsonSchema, MergeFrom)]
+pub struct CommandConnection {
+    /// Name to use for this connection in UI.
+    pub name: SharedString,
+    /// The program that runs commands on the remote side, e.g. `kubectl`.
+    pub program: String,
+    /// The arguments passed to `program` before the remote command, e.g.
+    /// `["exec", "-i", "my-pod", "--"]`. The command must forward stdin.
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// The arguments to use instead of `args` for terminals, e.g. to pass `-t`.
+    pub tty_args: Option<Vec<String>>,
+    /// Environment variables to set for `program`.
+    #[serde(default)]
+    pub env: collections::BTreeMap<String, String>,
+    #[serde(default)]
+    pub projects: BTreeSet<RemoteProject>,
+    // By default Zed will download the binary to the remote side directly.
+    // If this is set to true, Zed will download the binary to your local machine,
+    // and then upload it through the command.
+    pub upload_binary_over_exec: Option<bool>,
+}
+
 #[derive(Clone, Default, Serialize, Deserialize, PartialEq, JsonSchema, MergeFrom, Debug)]
 pub struct WslConnection {
     pub distro_name: SharedString,
@@ -119,6 +119,18 @@ pub struct AgentSettingsContent {
     ///
     /// Default: 4
     pub message_editor_min_lines: Option<usize>,
+    /// Whether to automatically compact a thread when it gets close to the
+    /// model's context window, summarizing older messages with the thread
+    /// summary model.
+    ///
+    /// Default: true
+    pub auto_compact: Option<bool>,
+    /// The fraction of the model's context window that must be used before
+    /// a thread is automatically compacted.
+    ///
+    /// Default: 0.85
+    #[serde(serialize_with = "crate::serialize_optional_f32_with_two_decimal_places")]
+    pub auto_compact_threshold: Option<f32>,
 }
 
 impl AgentSettingsContent {
@@ -315,6 +327,49 @@ impl From<&str> for LanguageModelProviderSetting {
     }
 }
 
+#[with_fallible_options]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, JsonSchema, MergeFrom)]
+pub struct AgentHooksContent {
+    /// Hooks to run before a tool is called.
+    ///
+    /// A hook that exits with a non-zero status blocks the tool call,
+    /// and its output is sent to the model instead of the tool's result.
+    ///
+    /// Default: []
+    pub pre_tool_use: Option<Vec<AgentHookContent>>,
+    /// Hooks to run after a tool is called.
+    ///
+    /// The hook's output is added to the tool's result, which is marked
+    /// as failed if the hook exits with a non-zero status.
+    ///
+    /// Default: []
+    pub post_tool_use: Option<Vec<AgentHookContent>>,
+    /// Hooks to run when the agent is about to end its turn.
+    ///
+    /// A hook that exits with a non-zero status sends its output to the
+    /// model, and the turn continues.
+    ///
+    /// Default: []
+    pub stop: Option<Vec<AgentHookContent>>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct AgentHookContent {
+    /// The shell command to run.
+    pub command: String,
+    /// The names of the tools this hook applies to. Applies to every tool if empty.
+    ///
+    /// Ignored for `stop` hooks.
+    #[serde(default)]
+    pub tools: Vec<String>,
+}
+
+impl AgentHookContent {
+    pub fn matches_tool(&self, tool_name: &str) -> bool {
+        self.tools.is_empty() || self.tools.iter().any(|tool| tool == tool_name)
+    }
+}
+
 #[with_fallible_options]
 #[derive(Default, PartialEq, Deserialize, Serialize, Clone, JsonSchema, MergeFrom, Debug)]
 pub struct AllAgentServersSettings {
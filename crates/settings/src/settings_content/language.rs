@@ -7,7 +7,7 @@ use serde::{Deserialize, Serialize, de::Error as _};
 use settings_macros::{MergeFrom, with_fallible_options};
 use std::sync::Arc;
 
-use crate::{ExtendingVec, merge_from};
+use crate::{DelayMs, ExtendingVec, merge_from};
 
 #[with_fallible_options]
 #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
@@ -76,6 +76,7 @@ pub enum EditPredictionProvider {
     Supermaven,
     Zed,
     Codestral,
+    Local,
     Experimental(&'static str),
 }
 
@@ -96,6 +97,7 @@ impl<'de> Deserialize<'de> for EditPredictionProvider {
             Supermaven,
             Zed,
             Codestral,
+            Local,
             Experimental(String),
         }
 
@@ -105,6 +107,7 @@ impl<'de> Deserialize<'de> for EditPredictionProvider {
             Content::Supermaven => EditPredictionProvider::Supermaven,
             Content::Zed => EditPredictionProvider::Zed,
             Content::Codestral => EditPredictionProvider::Codestral,
+            Content::Local => EditPredictionProvider::Local,
             Content::Experimental(name)
                 if name == EXPERIMENTAL_SWEEP_EDIT_PREDICTION_PROVIDER_NAME =>
             {
@@ -144,6 +147,7 @@ impl EditPredictionProvider {
             | EditPredictionProvider::Copilot
             | EditPredictionProvider::Supermaven
             | EditPredictionProvider::Codestral
+            | EditPredictionProvider::Local
             | EditPredictionProvider::Experimental(_) => false,
         }
     }
@@ -164,6 +168,8 @@ pub struct EditPredictionSettingsContent {
     pub copilot: Option<CopilotSettingsContent>,
     /// Settings specific to Codestral.
     pub codestral: Option<CodestralSettingsContent>,
+    /// Settings specific to the local provider.
+    pub local: Option<LocalEditPredictionSettingsContent>,
     /// Whether edit predictions are enabled in the assistant prompt editor.
     /// This has no effect if globally disabled.
     pub enabled_in_text_threads: Option<bool>,
@@ -203,6 +209,67 @@ pub struct CodestralSettingsContent {
     pub api_url: Option<String>,
 }
 
+/// Settings for the local provider, which requests fill-in-the-middle completions
+/// from a self-hosted model server.
+#[with_fallible_options]
+#[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema, MergeFrom, PartialEq)]
+pub struct LocalEditPredictionSettingsContent {
+    /// The API the model server exposes.
+    ///
+    /// Default: ollama
+    pub api: Option<LocalEditPredictionApi>,
+    /// The URL of the model server.
+    ///
+    /// Default: "http://localhost:11434"
+    pub api_url: Option<String>,
+    /// The model to request completions from. It must support fill-in-the-middle prompts.
+    ///
+    /// Default: "qwen2.5-coder:1.5b"
+    pub model: Option<String>,
+    /// The template used to build the prompt. `{prefix}` and `{suffix}` are replaced
+    /// with the text before and after the cursor, and `{file_path}` with the path of the file.
+    ///
+    /// Default: "<|fim_prefix|>{prefix}<|fim_suffix|>{suffix}<|fim_middle|>"
+    pub prompt_template: Option<String>,
+    /// Maximum number of tokens from around the cursor to include in the prompt.
+    ///
+    /// Default: 1024
+    pub max_context_tokens: Option<u32>,
+    /// Maximum number of tokens to generate.
+    ///
+    /// Default: 128
+    pub max_output_tokens: Option<u32>,
+    /// How long to wait after the last edit before requesting a prediction, in milliseconds.
+    /// Each edit made while waiting restarts the wait.
+    ///
+    /// Default: 150
+    pub debounce_ms: Option<DelayMs>,
+}
+
+/// The API exposed by the model server used for local edit predictions.
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    Default,
+    Eq,
+    PartialEq,
+    Serialize,
+    Deserialize,
+    JsonSchema,
+    MergeFrom,
+    strum::VariantArray,
+    strum::VariantNames,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum LocalEditPredictionApi {
+    /// Ollama's `/api/generate` endpoint.
+    #[default]
+    Ollama,
+    /// The `/v1/completions` endpoint of an OpenAI-compatible server, such as llama.cpp or vLLM.
+    OpenAiCompatible,
+}
+
 /// The mode in which edit predictions should be displayed.
 #[derive(
     Copy,
@@ -398,6 +465,11 @@ pub struct LanguageSettingsContent {
     ///
     /// Default: true
     pub linked_edits: Option<bool>,
+    /// Whether expanding and shrinking the selection should use the ranges provided by
+    /// the language server, if it supports them, before falling back to syntax nodes.
+    ///
+    /// Default: false
+    pub prefer_lsp_selection_ranges: Option<bool>,
     /// Whether indentation should be adjusted based on the context whilst typing.
     ///
     /// Default: true
@@ -763,6 +835,8 @@ pub enum Formatter {
     },
     /// Files should be formatted using a code action executed by language servers.
     CodeAction(String),
+    /// Format code using a formatter provided by an extension.
+    Extension(String),
     /// Format code using a language server.
     #[serde(untagged)]
     LanguageServer(LanguageServerFormatterSpecifier),
@@ -985,6 +1059,16 @@ mod test {
             ]))
         );
 
+        let raw = "{\"formatter\": [{\"extension\": \"mix-format\"}, \"language_server\"]}";
+        let settings: LanguageSettingsContent = serde_json::from_str(raw).unwrap();
+        assert_eq!(
+            settings.formatter,
+            Some(FormatterList::Vec(vec![
+                Formatter::Extension("mix-format".to_string()),
+                Formatter::LanguageServer(LanguageServerFormatterSpecifier::Current),
+            ]))
+        );
+
         assert_eq!(
             serde_json::to_string(&LanguageServerFormatterSpecifier::Current).unwrap(),
             "\"language_server\"",
@@ -7,8 +7,8 @@ use settings_macros::{MergeFrom, with_fallible_options};
 use util::serde::default_true;
 
 use crate::{
-    AllLanguageSettingsContent, DelayMs, ExtendingVec, ProjectTerminalSettingsContent,
-    SlashCommandSettings,
+    AgentHooksContent, AllLanguageSettingsContent, DelayMs, ExtendingVec,
+    ProjectTerminalSettingsContent, SlashCommandSettings,
 };
 
 #[with_fallible_options]
@@ -41,6 +41,9 @@ pub struct ProjectSettingsContent {
     #[serde(default)]
     pub context_servers: HashMap<Arc<str>, ContextServerSettingsContent>,
 
+    /// Shell commands that run at points in the agent's lifecycle.
+    pub agent_hooks: Option<AgentHooksContent>,
+
     /// Configuration for how direnv configuration should be loaded
     pub load_direnv: Option<DirenvSettings>,
 
@@ -193,6 +196,12 @@ pub struct SessionSettingsContent {
     ///
     /// Default: false
     pub trust_all_worktrees: Option<bool>,
+    /// Whether to keep editing remote buffers when the connection to the remote
+    /// host is lost. Saves made while offline are queued and written back once
+    /// the connection is re-established.
+    ///
+    /// Default: true
+    pub offline_remote_editing: Option<bool>,
 }
 
 #[derive(Deserialize, Serialize, Clone, PartialEq, Eq, JsonSchema, MergeFrom, Debug)]
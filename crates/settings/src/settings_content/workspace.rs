@@ -431,6 +431,10 @@ pub struct StatusBarSettingsContent {
     ///
     /// Default: false
     pub line_endings_button: Option<bool>,
+    /// Whether to show the active character encoding button in the status bar.
+    ///
+    /// Default: true
+    pub encoding_button: Option<bool>,
 }
 
 #[derive(
@@ -35,7 +35,8 @@ use crate::{
     LanguageToSettingsMap, ThemeName, VsCodeSettings, WorktreeId, fallible_options,
     merge_from::MergeFrom,
     settings_content::{
-        ExtensionsSettingsContent, ProjectSettingsContent, SettingsContent, UserSettingsContent,
+        ExtensionsSettingsContent, ProjectSettingsContent, SettingsContent,
+        SettingsProfileRuleContent, UserSettingsContent,
     },
 };
 
@@ -142,6 +143,9 @@ pub struct SettingsStore {
     setting_values: HashMap<TypeId, Box<dyn AnySettingValue>>,
     default_settings: Rc<SettingsContent>,
     user_settings: Option<UserSettingsContent>,
+    /// The profiles activated by the user's profile rules, by worktree, which
+    /// apply to the worktrees' files unless a profile was selected manually.
+    auto_activated_profiles: BTreeMap<WorktreeId, String>,
     global_settings: Option<Box<SettingsContent>>,
 
     extension_settings: Option<Box<SettingsContent>>,
@@ -269,6 +273,7 @@ impl SettingsStore {
             global_settings: None,
             server_settings: None,
             user_settings: None,
+            auto_activated_profiles: BTreeMap::default(),
             extension_settings: None,
 
             merged_settings: default_settings,
@@ -406,6 +411,43 @@ impl SettingsStore {
             .flat_map(|settings| settings.profiles.keys().map(|k| k.as_str()))
     }
 
+    /// Get the rules that activate settings profiles automatically.
+    pub fn settings_profile_rules(&self) -> &[SettingsProfileRuleContent] {
+        self.user_settings
+            .as_ref()
+            .map_or(&[], |settings| settings.profile_rules.as_slice())
+    }
+
+    /// Get the name of the settings profile activated by the profile rules
+    /// for the given worktree.
+    pub fn auto_activated_settings_profile(&self, root_id: WorktreeId) -> Option<&str> {
+        self.auto_activated_profiles
+            .get(&root_id)
+            .map(|profile| profile.as_str())
+    }
+
+    /// Sets the settings profile activated by the profile rules for the given
+    /// worktree, which is layered between the user settings and the
+    /// worktree's project settings.
+    pub fn set_auto_activated_settings_profile(
+        &mut self,
+        root_id: WorktreeId,
+        profile: Option<String>,
+        cx: &mut App,
+    ) {
+        if self.auto_activated_profiles.get(&root_id) == profile.as_ref() {
+            return;
+        }
+        match profile {
+            Some(profile) => self.auto_activated_profiles.insert(root_id, profile),
+            None => self.auto_activated_profiles.remove(&root_id),
+        };
+        for setting_value in self.setting_values.values_mut() {
+            setting_value.clear_local_values(root_id);
+        }
+        self.recompute_values(Some((root_id, RelPath::empty())), cx);
+    }
+
     #[cfg(any(test, feature = "test-support"))]
     pub fn test(cx: &mut App) -> Self {
         Self::new(cx, &crate::test_settings())
@@ -972,6 +1014,7 @@ impl SettingsStore {
     pub fn clear_local_settings(&mut self, root_id: WorktreeId, cx: &mut App) -> Result<()> {
         self.local_settings
             .retain(|(worktree_id, _), _| worktree_id != &root_id);
+        self.auto_activated_profiles.remove(&root_id);
         self.raw_editorconfig_settings
             .retain(|(worktree_id, _), _| worktree_id != &root_id);
         for setting_value in self.setting_values.values_mut() {
@@ -1096,7 +1139,30 @@ impl SettingsStore {
             }
         }
 
-        for ((root_id, directory_path), local_settings) in &self.local_settings {
+        // Worktrees with an automatically activated profile get a layer at
+        // their root, even if they have no settings file there.
+        let empty_settings = SettingsContent::default();
+        let profile_roots = self
+            .auto_activated_profiles
+            .keys()
+            .map(|root_id| (*root_id, Arc::<RelPath>::from(RelPath::empty())))
+            .filter(|root| !self.local_settings.contains_key(root))
+            .collect::<Vec<_>>();
+        let mut local_settings = self
+            .local_settings
+            .iter()
+            .chain(profile_roots.iter().map(|root| (root, &empty_settings)))
+            .collect::<Vec<_>>();
+        local_settings.sort_by(|(a, _), (b, _)| a.cmp(b));
+        let profile = |root_id: &WorktreeId| {
+            if cx.has_global::<ActiveSettingsProfileName>() {
+                return None;
+            }
+            let profile = self.auto_activated_profiles.get(root_id)?;
+            self.user_settings.as_ref()?.profiles.get(profile)
+        };
+
+        for ((root_id, directory_path), local_settings) in local_settings {
             // Build a stack of all of the local values for that setting.
             while let Some(prev_entry) = paths_stack.last() {
                 if let Some((prev_root_id, prev_path)) = prev_entry
@@ -1113,7 +1179,9 @@ impl SettingsStore {
             let mut merged_local_settings = if let Some(deepest) = project_settings_stack.last() {
                 (*deepest).clone()
             } else {
-                self.merged_settings.as_ref().clone()
+                let mut merged = self.merged_settings.as_ref().clone();
+                merged.merge_from_option(profile(root_id));
+                merged
             };
             merged_local_settings.merge_from(local_settings);
 
@@ -1544,6 +1612,74 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    fn test_auto_activated_settings_profile(cx: &mut App) {
+        let mut store = SettingsStore::new(cx, &test_settings());
+        store.register_setting::<AutoUpdateSetting>();
+        store
+            .set_user_settings(
+                r#"{
+                    "auto_update": true,
+                    "profiles": {
+                        "quiet": { "auto_update": false },
+                        "loud": { "auto_update": true }
+                    }
+                }"#,
+                cx,
+            )
+            .unwrap();
+        let profiled = WorktreeId::from_usize(1);
+        let unprofiled = WorktreeId::from_usize(2);
+        let overridden = WorktreeId::from_usize(3);
+        store
+            .set_local_settings(
+                overridden,
+                rel_path("sub").into(),
+                LocalSettingsKind::Settings,
+                Some(r#"{ "auto_update": true }"#),
+                cx,
+            )
+            .unwrap();
+
+        store.set_auto_activated_settings_profile(profiled, Some("quiet".into()), cx);
+        store.set_auto_activated_settings_profile(overridden, Some("quiet".into()), cx);
+        assert_eq!(
+            store.auto_activated_settings_profile(profiled),
+            Some("quiet")
+        );
+        assert_eq!(store.auto_activated_settings_profile(unprofiled), None);
+        let auto_update = |store: &SettingsStore, worktree_id, path| {
+            store
+                .get::<AutoUpdateSetting>(Some(SettingsLocation {
+                    worktree_id,
+                    path: rel_path(path),
+                }))
+                .auto_update
+        };
+        // The profile only applies to the files of its worktrees, below their
+        // project settings.
+        assert!(store.get::<AutoUpdateSetting>(None).auto_update);
+        assert!(!auto_update(&store, profiled, "a.txt"));
+        assert!(auto_update(&store, unprofiled, "a.txt"));
+        assert!(!auto_update(&store, overridden, "a.txt"));
+        assert!(auto_update(&store, overridden, "sub/a.txt"));
+
+        // A manually selected profile takes precedence.
+        cx.set_global(ActiveSettingsProfileName("loud".into()));
+        store.recompute_values(None, cx);
+        assert!(auto_update(&store, profiled, "a.txt"));
+        cx.remove_global::<ActiveSettingsProfileName>();
+        store.recompute_values(None, cx);
+        assert!(!auto_update(&store, profiled, "a.txt"));
+
+        store.set_auto_activated_settings_profile(profiled, None, cx);
+        assert_eq!(store.auto_activated_settings_profile(profiled), None);
+        assert!(auto_update(&store, profiled, "a.txt"));
+        store.clear_local_settings(overridden, cx).unwrap();
+        assert_eq!(store.auto_activated_settings_profile(overridden), None);
+        assert!(auto_update(&store, overridden, "a.txt"));
+    }
+
     #[track_caller]
     fn check_settings_update(
         store: &mut SettingsStore,
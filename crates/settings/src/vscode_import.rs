@@ -176,6 +176,7 @@ impl VsCodeSettings {
             audio: None,
             auto_update: None,
             base_keymap: Some(BaseKeymapContent::VSCode),
+            bookmarks_panel: None,
             calls: None,
             collaboration_panel: None,
             debugger: None,
@@ -190,6 +191,7 @@ impl VsCodeSettings {
             helix_mode: None,
             image_viewer: None,
             journal: None,
+            keyboard_macros: None,
             language_models: None,
             line_indicator_format: None,
             log: None,
@@ -197,6 +199,7 @@ impl VsCodeSettings {
             node: self.node_binary_settings(),
             notification_panel: None,
             outline_panel: self.outline_panel_settings_content(),
+            ports_panel: None,
             preview_tabs: self.preview_tabs_settings_content(),
             project: self.project_settings_content(),
             project_panel: self.project_panel_settings_content(),
@@ -205,6 +208,7 @@ impl VsCodeSettings {
             repl: None,
             server_url: None,
             session: None,
+            spell_check: None,
             status_bar: self.status_bar_settings_content(),
             tab_bar: self.tab_bar_settings_content(),
             tabs: self.item_settings_content(),
@@ -321,6 +325,7 @@ impl VsCodeSettings {
             min_line_number_digits: None,
             runnables: None,
             breakpoints: None,
+            bookmarks: None,
             folds: self.read_enum("editor.showFoldingControls", |s| match s {
                 "always" | "mouseover" => Some(true),
                 "never" => Some(false),
@@ -402,6 +407,7 @@ impl VsCodeSettings {
             terminal: None,
             dap: Default::default(),
             context_servers: self.context_servers(),
+            agent_hooks: None,
             load_direnv: None,
             slash_commands: None,
             git_hosting_providers: None,
@@ -447,6 +453,7 @@ impl VsCodeSettings {
             jsx_tag_auto_close: None,
             language_servers: None,
             linked_edits: self.read_bool("editor.linkedEditing"),
+            prefer_lsp_selection_ranges: None,
             preferred_line_length: self.read_u32("editor.wordWrapColumn"),
             prettier: None,
             remove_trailing_whitespace_on_save: self.read_bool("editor.trimAutoWhitespace"),
@@ -651,6 +658,7 @@ impl VsCodeSettings {
             active_language_button: None,
             cursor_position_button: None,
             line_endings_button: None,
+            encoding_button: None,
         })
     }
 
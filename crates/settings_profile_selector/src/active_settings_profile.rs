@@ -0,0 +1,68 @@
+use gpui::{Context, Entity, IntoElement, ParentElement, Render, Subscription, Window, div};
+use project::Project;
+use settings::{ActiveSettingsProfileName, SettingsStore};
+use ui::{Button, ButtonCommon, Clickable, FluentBuilder, LabelSize, Tooltip};
+use workspace::{StatusItemView, item::ItemHandle};
+use zed_actions::settings_profile_selector::Toggle;
+
+/// Shows the settings profile that applies to the project in the status bar,
+/// if any.
+pub struct ActiveSettingsProfile {
+    project: Entity<Project>,
+    _observe_settings: Subscription,
+}
+
+impl ActiveSettingsProfile {
+    pub fn new(project: Entity<Project>, cx: &mut Context<Self>) -> Self {
+        Self {
+            project,
+            _observe_settings: cx.observe_global::<SettingsStore>(|_, cx| cx.notify()),
+        }
+    }
+}
+
+impl Render for ActiveSettingsProfile {
+    fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let is_auto_activated = !cx.has_global::<ActiveSettingsProfileName>();
+        let profile = if let Some(profile) = cx.try_global::<ActiveSettingsProfileName>() {
+            Some(profile.0.clone())
+        } else {
+            let store = SettingsStore::global(cx);
+            self.project
+                .read(cx)
+                .visible_worktrees(cx)
+                .find_map(|worktree| store.auto_activated_settings_profile(worktree.read(cx).id()))
+                .map(|profile| profile.to_string())
+        };
+
+        div().when_some(profile, |el, profile| {
+            el.child(
+                Button::new("active-settings-profile", profile)
+                    .label_size(LabelSize::Small)
+                    .on_click(|_, window, cx| window.dispatch_action(Box::new(Toggle), cx))
+                    .tooltip(move |_window, cx| {
+                        if is_auto_activated {
+                            Tooltip::with_meta(
+                                "Select Settings Profile",
+                                Some(&Toggle),
+                                "Activated by a profile rule",
+                                cx,
+                            )
+                        } else {
+                            Tooltip::for_action("Select Settings Profile", &Toggle, cx)
+                        }
+                    }),
+            )
+        })
+    }
+}
+
+impl StatusItemView for ActiveSettingsProfile {
+    fn set_active_pane_item(
+        &mut self,
+        _: Option<&dyn ItemHandle>,
+        _: &mut Window,
+        _: &mut Context<Self>,
+    ) {
+    }
+}
@@ -0,0 +1,433 @@
+//! Activates settings profiles automatically, according to the user's
+//! `profile_rules`, for the worktrees of each project.
+//!
+//! The matched profile is layered below each worktree's project settings, so
+//! windows with different projects keep their own profiles.
+
+use std::time::Duration;
+
+use chrono::{Local, NaiveTime, TimeDelta};
+use globset::GlobMatcher;
+use gpui::{App, AppContext as _, Context, Entity, Global, Subscription, Task};
+use project::Project;
+use settings::{SettingsProfileRuleContent, SettingsStore};
+use util::{paths::home_dir, rel_path::RelPath};
+use workspace::Workspace;
+
+pub(crate) fn init(cx: &mut App) {
+    cx.observe_new(|workspace: &mut Workspace, _, cx| {
+        let project = workspace.project().clone();
+        let project_profile_rules = cx.new(|cx| ProjectProfileRules::new(project.clone(), cx));
+        // The subscription keeps the rules of the project alive as long as
+        // the workspace.
+        cx.subscribe(&project, move |_, _, event, cx| {
+            if matches!(
+                event,
+                project::Event::WorktreeAdded(_)
+                    | project::Event::WorktreeRemoved(_)
+                    | project::Event::WorktreeUpdatedEntries(..)
+            ) {
+                project_profile_rules.update(cx, |rules, cx| rules.apply(cx));
+            }
+        })
+        .detach();
+    })
+    .detach();
+}
+
+/// Applies the profile rules to the worktrees of a project.
+struct ProjectProfileRules {
+    project: Entity<Project>,
+    /// Applies the rules again when a `time_of_day` range next starts or ends.
+    next_time_of_day_change: Task<()>,
+    _observe_settings: Subscription,
+}
+
+impl ProjectProfileRules {
+    fn new(project: Entity<Project>, cx: &mut Context<Self>) -> Self {
+        let mut this = Self {
+            project,
+            next_time_of_day_change: Task::ready(()),
+            _observe_settings: cx.observe_global::<SettingsStore>(|this, cx| this.apply(cx)),
+        };
+        this.apply(cx);
+        this
+    }
+
+    fn apply(&mut self, cx: &mut Context<Self>) {
+        let now = Local::now().time();
+        let profile = cx.update_default_global(|matchers: &mut WorktreePathMatchers, cx| {
+            let rules = SettingsStore::global(cx).settings_profile_rules();
+            matchers.update(rules);
+            let project = self.project.read(cx);
+            rules
+                .iter()
+                .zip(&matchers.matchers)
+                .find(|(rule, matcher)| rule_matches(rule, matcher.as_ref(), project, now, cx))
+                .map(|(rule, _)| rule.profile.clone())
+        });
+
+        let worktree_ids = self
+            .project
+            .read(cx)
+            .visible_worktrees(cx)
+            .map(|worktree| worktree.read(cx).id())
+            .collect::<Vec<_>>();
+        for worktree_id in worktree_ids {
+            if SettingsStore::global(cx).auto_activated_settings_profile(worktree_id)
+                != profile.as_deref()
+            {
+                SettingsStore::update_global(cx, |store, cx| {
+                    store.set_auto_activated_settings_profile(worktree_id, profile.clone(), cx)
+                });
+            }
+        }
+
+        let rules = SettingsStore::global(cx).settings_profile_rules();
+        self.next_time_of_day_change = match next_time_of_day_change(rules, now) {
+            Some(delay) => cx.spawn(async move |this, cx| {
+                cx.background_executor().timer(delay).await;
+                this.update(cx, |this, cx| this.apply(cx)).ok();
+            }),
+            None => Task::ready(()),
+        };
+    }
+}
+
+/// The compiled `worktree_path` globs of the profile rules, which are only
+/// recompiled when the rules change.
+#[derive(Default)]
+struct WorktreePathMatchers {
+    globs: Vec<Option<String>>,
+    matchers: Vec<Option<GlobMatcher>>,
+}
+
+impl Global for WorktreePathMatchers {}
+
+impl WorktreePathMatchers {
+    fn update(&mut self, rules: &[SettingsProfileRuleContent]) {
+        if self
+            .globs
+            .iter()
+            .eq(rules.iter().map(|rule| &rule.worktree_path))
+        {
+            return;
+        }
+        self.globs = rules
+            .iter()
+            .map(|rule| rule.worktree_path.clone())
+            .collect();
+        self.matchers = self
+            .globs
+            .iter()
+            .map(|glob| glob.as_deref().and_then(compile_worktree_path_glob))
+            .collect();
+    }
+}
+
+fn compile_worktree_path_glob(glob: &str) -> Option<GlobMatcher> {
+    let glob = match glob.strip_prefix("~/") {
+        Some(glob) => home_dir().join(glob).to_string_lossy().into_owned(),
+        None => glob.to_string(),
+    };
+    Some(globset::Glob::new(&glob).ok()?.compile_matcher())
+}
+
+/// Whether all conditions of `rule` hold, where `worktree_path` is the rule's
+/// compiled `worktree_path` glob, if it is valid.
+fn rule_matches(
+    rule: &SettingsProfileRuleContent,
+    worktree_path: Option<&GlobMatcher>,
+    project: &Project,
+    now: NaiveTime,
+    cx: &App,
+) -> bool {
+    if rule.worktree_path.is_some() {
+        let Some(matcher) = worktree_path else {
+            return false;
+        };
+        if !project
+            .visible_worktrees(cx)
+            .any(|worktree| matcher.is_match(worktree.read(cx).abs_path()))
+        {
+            return false;
+        }
+    }
+
+    if let Some(remote_host) = &rule.remote_host
+        && project
+            .remote_connection_options(cx)
+            .is_none_or(|options| &options.display_name() != remote_host)
+    {
+        return false;
+    }
+
+    if let Some(path) = &rule.file_exists {
+        let Ok(path) = RelPath::unix(path.as_str()) else {
+            return false;
+        };
+        if !project
+            .visible_worktrees(cx)
+            .any(|worktree| worktree.read(cx).entry_for_path(path).is_some())
+        {
+            return false;
+        }
+    }
+
+    if let Some(range) = &rule.time_of_day
+        && !parse_time_range(range).is_some_and(|(start, end)| time_in_range(now, start, end))
+    {
+        return false;
+    }
+
+    true
+}
+
+/// Parses a range of local time, such as "09:00-17:00".
+fn parse_time_range(range: &str) -> Option<(NaiveTime, NaiveTime)> {
+    let (start, end) = range.split_once('-')?;
+    let start = NaiveTime::parse_from_str(start.trim(), "%H:%M").ok()?;
+    let end = NaiveTime::parse_from_str(end.trim(), "%H:%M").ok()?;
+    Some((start, end))
+}
+
+fn time_in_range(time: NaiveTime, start: NaiveTime, end: NaiveTime) -> bool {
+    if start <= end {
+        start <= time && time < end
+    } else {
+        start <= time || time < end
+    }
+}
+
+/// Returns how long it is from `now` until the `time_of_day` range of one of
+/// the rules next starts or ends.
+fn next_time_of_day_change(
+    rules: &[SettingsProfileRuleContent],
+    now: NaiveTime,
+) -> Option<Duration> {
+    rules
+        .iter()
+        .filter_map(|rule| parse_time_range(rule.time_of_day.as_deref()?))
+        .flat_map(|(start, end)| [start, end])
+        .map(|time| {
+            let delay = time.signed_duration_since(now);
+            if delay <= TimeDelta::zero() {
+                delay + TimeDelta::days(1)
+            } else {
+                delay
+            }
+        })
+        .min()?
+        .to_std()
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+    use language::language_settings::AllLanguageSettings;
+    use project::FakeFs;
+    use serde_json::json;
+    use settings::{Settings as _, SettingsLocation};
+    use workspace::AppState;
+
+    fn time(time: &str) -> NaiveTime {
+        NaiveTime::parse_from_str(time, "%H:%M").unwrap()
+    }
+
+    #[test]
+    fn test_time_of_day() {
+        let (start, end) = parse_time_range("09:00 - 17:30").unwrap();
+        assert!(time_in_range(time("09:00"), start, end));
+        assert!(time_in_range(time("17:29"), start, end));
+        assert!(!time_in_range(time("17:30"), start, end));
+        assert!(!time_in_range(time("08:59"), start, end));
+
+        let (start, end) = parse_time_range("22:00-06:00").unwrap();
+        assert!(time_in_range(time("23:00"), start, end));
+        assert!(time_in_range(time("05:59"), start, end));
+        assert!(!time_in_range(time("12:00"), start, end));
+
+        assert_eq!(parse_time_range("9am-5pm"), None);
+        assert_eq!(parse_time_range("09:00"), None);
+    }
+
+    #[test]
+    fn test_next_time_of_day_change() {
+        let rule = |time_of_day: Option<&str>| SettingsProfileRuleContent {
+            profile: "profile".into(),
+            time_of_day: time_of_day.map(Into::into),
+            ..Default::default()
+        };
+        let minutes = |minutes| Some(Duration::from_secs(minutes * 60));
+
+        let rules = [
+            rule(Some("09:00-17:00")),
+            rule(None),
+            rule(Some("nonsense")),
+        ];
+        assert_eq!(next_time_of_day_change(&rules, time("08:30")), minutes(30));
+        assert_eq!(
+            next_time_of_day_change(&rules, time("09:00")),
+            minutes(8 * 60)
+        );
+        assert_eq!(
+            next_time_of_day_change(&rules, time("18:00")),
+            minutes(15 * 60)
+        );
+
+        let rules = [rule(Some("09:00-17:00")), rule(Some("22:00-06:00"))];
+        assert_eq!(
+            next_time_of_day_change(&rules, time("23:00")),
+            minutes(7 * 60)
+        );
+        assert_eq!(next_time_of_day_change(&[rule(None)], time("12:00")), None);
+    }
+
+    #[test]
+    fn test_worktree_path_matchers() {
+        let rule = |worktree_path: Option<&str>| SettingsProfileRuleContent {
+            profile: "profile".into(),
+            worktree_path: worktree_path.map(Into::into),
+            ..Default::default()
+        };
+        let mut matchers = WorktreePathMatchers::default();
+
+        matchers.update(&[rule(Some("/work/*")), rule(None), rule(Some("/work/[a"))]);
+        assert_eq!(matchers.matchers.len(), 3);
+        assert!(matchers.matchers[0].as_ref().unwrap().is_match("/work/zed"));
+        assert!(matchers.matchers[1].is_none());
+        assert!(matchers.matchers[2].is_none());
+
+        matchers.update(&[rule(Some("/home/*"))]);
+        assert_eq!(matchers.matchers.len(), 1);
+        assert!(!matchers.matchers[0].as_ref().unwrap().is_match("/work/zed"));
+        assert!(matchers.matchers[0].as_ref().unwrap().is_match("/home/zed"));
+    }
+
+    #[gpui::test]
+    async fn test_rule_matches(cx: &mut TestAppContext) {
+        cx.update(AppState::test);
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            "/work/monorepo",
+            json!({ "Cargo.toml": "", "src": { "main.rs": "" } }),
+        )
+        .await;
+        let project = Project::test(fs, ["/work/monorepo".as_ref()], cx).await;
+        cx.run_until_parked();
+
+        let rule = |json: serde_json::Value| -> SettingsProfileRuleContent {
+            serde_json::from_value(json).unwrap()
+        };
+        let matches = |rule: &SettingsProfileRuleContent, now: &str, cx: &mut TestAppContext| {
+            let matcher = rule
+                .worktree_path
+                .as_deref()
+                .and_then(compile_worktree_path_glob);
+            cx.update(|cx| rule_matches(rule, matcher.as_ref(), project.read(cx), time(now), cx))
+        };
+
+        let always = rule(json!({ "profile": "always" }));
+        assert!(matches(&always, "12:00", cx));
+
+        let rust = rule(json!({ "profile": "rust", "file_exists": "Cargo.toml" }));
+        assert!(matches(&rust, "12:00", cx));
+        let node = rule(json!({ "profile": "node", "file_exists": "package.json" }));
+        assert!(!matches(&node, "12:00", cx));
+
+        let monorepo = rule(json!({
+            "profile": "monorepo-heavy",
+            "worktree_path": "/work/mono*",
+            "time_of_day": "09:00-17:00",
+        }));
+        assert!(matches(&monorepo, "10:00", cx));
+        assert!(!matches(&monorepo, "18:00", cx));
+        let other_path = rule(json!({ "profile": "other", "worktree_path": "/home/**" }));
+        assert!(!matches(&other_path, "12:00", cx));
+
+        let remote = rule(json!({ "profile": "remote", "remote_host": "devbox" }));
+        assert!(!matches(&remote, "12:00", cx));
+    }
+
+    #[gpui::test]
+    async fn test_profiles_per_project(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            AppState::test(cx);
+            super::init(cx);
+            SettingsStore::update_global(cx, |store, cx| {
+                store
+                    .set_user_settings(
+                        r#"{
+                            "tab_size": 4,
+                            "profiles": {
+                                "rust": { "tab_size": 8 },
+                                "node": { "tab_size": 2 }
+                            },
+                            "profile_rules": [
+                                { "profile": "rust", "file_exists": "Cargo.toml" },
+                                { "profile": "node", "file_exists": "package.json" }
+                            ]
+                        }"#,
+                        cx,
+                    )
+                    .unwrap();
+            });
+        });
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree("/rust", json!({ "Cargo.toml": "", "main.rs": "" }))
+            .await;
+        fs.insert_tree("/node", json!({ "package.json": "", "main.js": "" }))
+            .await;
+        let rust_project = Project::test(fs.clone(), ["/rust".as_ref()], cx).await;
+        let node_project = Project::test(fs.clone(), ["/node".as_ref()], cx).await;
+        for project in [&rust_project, &node_project] {
+            cx.add_window(|window, cx| Workspace::test_new(project.clone(), window, cx));
+        }
+        cx.run_until_parked();
+
+        let profile_and_tab_size = |project: &Entity<Project>, cx: &mut TestAppContext| {
+            cx.update(|cx| {
+                let worktree_id = project.read(cx).worktrees(cx).next().unwrap().read(cx).id();
+                let location = SettingsLocation {
+                    worktree_id,
+                    path: RelPath::unix("main").unwrap(),
+                };
+                (
+                    SettingsStore::global(cx)
+                        .auto_activated_settings_profile(worktree_id)
+                        .map(|profile| profile.to_string()),
+                    AllLanguageSettings::get(Some(location), cx)
+                        .defaults
+                        .tab_size
+                        .get(),
+                )
+            })
+        };
+        assert_eq!(
+            profile_and_tab_size(&rust_project, cx),
+            (Some("rust".into()), 8)
+        );
+        assert_eq!(
+            profile_and_tab_size(&node_project, cx),
+            (Some("node".into()), 2)
+        );
+        cx.update(|cx| {
+            assert_eq!(
+                AllLanguageSettings::get_global(cx).defaults.tab_size.get(),
+                4
+            );
+        });
+
+        fs.remove_file("/rust/Cargo.toml".as_ref(), Default::default())
+            .await
+            .unwrap();
+        cx.run_until_parked();
+        assert_eq!(profile_and_tab_size(&rust_project, cx), (None, 4));
+        assert_eq!(
+            profile_and_tab_size(&node_project, cx),
+            (Some("node".into()), 2)
+        );
+    }
+}
@@ -1664,6 +1664,29 @@ pub(crate) fn settings_data(cx: &App) -> Vec<SettingsPage> {
                         metadata: None,
                         files: USER,
                     }),
+                    SettingsPageItem::SettingItem(SettingItem {
+                        title: "Show Bookmarks",
+                        description: "Show bookmarks in the gutter.",
+                        field: Box::new(SettingField {
+                            json_path: Some("gutter.bookmarks"),
+                            pick: |settings_content| {
+                                settings_content
+                                    .editor
+                                    .gutter
+                                    .as_ref()
+                                    .and_then(|gutter| gutter.bookmarks.as_ref())
+                            },
+                            write: |settings_content, value| {
+                                settings_content
+                                    .editor
+                                    .gutter
+                                    .get_or_insert_default()
+                                    .bookmarks = value;
+                            },
+                        }),
+                        metadata: None,
+                        files: USER,
+                    }),
                     SettingsPageItem::SettingItem(SettingItem {
                         title: "Show Folds",
                         description: "Show code folding controls in the gutter.",
@@ -2843,6 +2866,28 @@ pub(crate) fn settings_data(cx: &App) -> Vec<SettingsPage> {
                     metadata: None,
                     files: USER,
                 }),
+                SettingsPageItem::SettingItem(SettingItem {
+                    title: "Encoding Button",
+                    description: "Show the active character encoding button in the status bar.",
+                    field: Box::new(SettingField {
+                        json_path: Some("status_bar.encoding_button"),
+                        pick: |settings_content| {
+                            settings_content
+                                .status_bar
+                                .as_ref()?
+                                .encoding_button
+                                .as_ref()
+                        },
+                        write: |settings_content, value| {
+                            settings_content
+                                .status_bar
+                                .get_or_insert_default()
+                                .encoding_button = value;
+                        },
+                    }),
+                    metadata: None,
+                    files: USER,
+                }),
                 SettingsPageItem::SettingItem(SettingItem {
                     title: "Terminal Button",
                     description: "Show the terminal button in the status bar.",
@@ -6099,6 +6144,22 @@ pub(crate) fn settings_data(cx: &App) -> Vec<SettingsPage> {
                         metadata: None,
                         files: USER,
                     }),
+                    SettingsPageItem::SettingItem(SettingItem {
+                        title: "Auto Compact",
+                        description: "Whether to summarize older messages when a thread gets close to the model's context window.",
+                        field: Box::new(SettingField {
+                            json_path: Some("agent.auto_compact"),
+                            pick: |settings_content| {
+                                settings_content.agent.as_ref()?.auto_compact.as_ref()
+                            },
+                            write: |settings_content, value| {
+                                settings_content.agent.get_or_insert_default().auto_compact =
+                                    value;
+                            },
+                        }),
+                        metadata: None,
+                        files: USER,
+                    }),
                 ];
                 items.extend(edit_prediction_language_settings_section());
                 items.extend(
@@ -7267,6 +7328,38 @@ fn language_settings_data() -> Vec<SettingsPageItem> {
                 metadata: None,
                 files: USER,
             }),
+            SettingsPageItem::SettingItem(SettingItem {
+                title: "Large File Threshold",
+                description: "The size, in megabytes, from which files are opened read-only in the large file viewer.",
+                field: Box::new(SettingField {
+                    json_path: Some("large_file_viewer.threshold_mb"),
+                    pick: |settings_content| {
+                        settings_content.large_file_viewer.as_ref().and_then(|large_file_viewer| large_file_viewer.threshold_mb.as_ref())
+                    },
+                    write: |settings_content, value| {
+                        settings_content.large_file_viewer.get_or_insert_default().threshold_mb = value;
+
+                    },
+                }),
+                metadata: None,
+                files: USER,
+            }),
+            SettingsPageItem::SettingItem(SettingItem {
+                title: "Spell Check",
+                description: "Whether to check the spelling of comments and strings in code, and of whole Markdown, plain text and git commit message files.",
+                field: Box::new(SettingField {
+                    json_path: Some("spell_check.enabled"),
+                    pick: |settings_content| {
+                        settings_content.spell_check.as_ref().and_then(|spell_check| spell_check.enabled.as_ref())
+                    },
+                    write: |settings_content, value| {
+                        settings_content.spell_check.get_or_insert_default().enabled = value;
+
+                    },
+                }),
+                metadata: None,
+                files: USER,
+            }),
             SettingsPageItem::SettingItem(SettingItem {
                 title: "Auto Replace Emoji Shortcode",
                 description: "Whether to automatically replace emoji shortcodes with emoji characters.",
@@ -7369,6 +7462,25 @@ fn non_editor_language_settings_data() -> Vec<SettingsPageItem> {
             metadata: None,
             files: USER | PROJECT,
         }),
+        SettingsPageItem::SettingItem(SettingItem {
+            title: "Prefer LSP Selection Ranges",
+            description: "Whether expanding and shrinking the selection should use the ranges provided by the language server, before falling back to syntax nodes.",
+            field: Box::new(SettingField {
+                json_path: Some("languages.$(language).prefer_lsp_selection_ranges"),
+                pick: |settings_content| {
+                    language_settings_field(settings_content, |language| {
+                        language.prefer_lsp_selection_ranges.as_ref()
+                    })
+                },
+                write: |settings_content, value| {
+                    language_settings_field_mut(settings_content, value, |language, value| {
+                        language.prefer_lsp_selection_ranges = value;
+                    })
+                },
+            }),
+            metadata: None,
+            files: USER | PROJECT,
+        }),
         SettingsPageItem::SettingItem(SettingItem {
             title: "Go To Definition Fallback",
             description: "Whether to follow-up empty Go to definition responses from the language server.",
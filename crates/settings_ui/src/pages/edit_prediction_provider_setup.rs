@@ -82,6 +82,7 @@ impl Render for EditPredictionSetupPage {
                 )
                 .into_any_element(),
             ),
+            Some(render_local_provider(&settings_window, window, cx).into_any_element()),
         ];
 
         div()
@@ -236,6 +237,49 @@ fn render_api_key_provider(
     })
 }
 
+fn render_local_provider(
+    settings_window: &Entity<SettingsWindow>,
+    window: &mut Window,
+    cx: &mut Context<EditPredictionSetupPage>,
+) -> impl IntoElement {
+    v_flex()
+        .id("local-model")
+        .min_w_0()
+        .pt_8()
+        .gap_1p5()
+        .child(
+            SettingsSectionHeader::new("Local Model")
+                .icon(IconName::AiOllama)
+                .no_padding(true),
+        )
+        .child(
+            Label::new(
+                "Request fill-in-the-middle completions from Ollama or an OpenAI-compatible server, \
+                so your code never leaves your machine or network.",
+            )
+            .size(LabelSize::Small)
+            .color(Color::Muted),
+        )
+        .child(
+            div()
+                .mt_4()
+                .px_neg_8()
+                .border_t_1()
+                .border_color(cx.theme().colors().border_variant)
+                .child(settings_window.update(cx, |settings_window, cx| {
+                    let local_settings = local_settings();
+                    settings_window
+                        .render_sub_page_items_section(
+                            local_settings.iter().enumerate(),
+                            None,
+                            window,
+                            cx,
+                        )
+                        .into_any_element()
+                })),
+        )
+}
+
 fn codestral_settings() -> Box<[SettingsPageItem]> {
     Box::new([
         SettingsPageItem::SettingItem(SettingItem {
@@ -337,6 +381,227 @@ fn codestral_settings() -> Box<[SettingsPageItem]> {
     ])
 }
 
+fn local_settings() -> Box<[SettingsPageItem]> {
+    Box::new([
+        SettingsPageItem::SettingItem(SettingItem {
+            title: "API",
+            description: "The API the model server exposes.",
+            field: Box::new(SettingField {
+                pick: |settings| {
+                    settings
+                        .project
+                        .all_languages
+                        .edit_predictions
+                        .as_ref()?
+                        .local
+                        .as_ref()?
+                        .api
+                        .as_ref()
+                },
+                write: |settings, value| {
+                    settings
+                        .project
+                        .all_languages
+                        .edit_predictions
+                        .get_or_insert_default()
+                        .local
+                        .get_or_insert_default()
+                        .api = value;
+                },
+                json_path: Some("edit_predictions.local.api"),
+            }),
+            metadata: None,
+            files: USER,
+        }),
+        SettingsPageItem::SettingItem(SettingItem {
+            title: "API URL",
+            description: "The URL of the model server.",
+            field: Box::new(SettingField {
+                pick: |settings| {
+                    settings
+                        .project
+                        .all_languages
+                        .edit_predictions
+                        .as_ref()?
+                        .local
+                        .as_ref()?
+                        .api_url
+                        .as_ref()
+                },
+                write: |settings, value| {
+                    settings
+                        .project
+                        .all_languages
+                        .edit_predictions
+                        .get_or_insert_default()
+                        .local
+                        .get_or_insert_default()
+                        .api_url = value;
+                },
+                json_path: Some("edit_predictions.local.api_url"),
+            }),
+            metadata: Some(Box::new(SettingsFieldMetadata {
+                placeholder: Some("http://localhost:11434"),
+                ..Default::default()
+            })),
+            files: USER,
+        }),
+        SettingsPageItem::SettingItem(SettingItem {
+            title: "Model",
+            description: "The model to request completions from. It must support fill-in-the-middle prompts.",
+            field: Box::new(SettingField {
+                pick: |settings| {
+                    settings
+                        .project
+                        .all_languages
+                        .edit_predictions
+                        .as_ref()?
+                        .local
+                        .as_ref()?
+                        .model
+                        .as_ref()
+                },
+                write: |settings, value| {
+                    settings
+                        .project
+                        .all_languages
+                        .edit_predictions
+                        .get_or_insert_default()
+                        .local
+                        .get_or_insert_default()
+                        .model = value;
+                },
+                json_path: Some("edit_predictions.local.model"),
+            }),
+            metadata: Some(Box::new(SettingsFieldMetadata {
+                placeholder: Some("qwen2.5-coder:1.5b"),
+                ..Default::default()
+            })),
+            files: USER,
+        }),
+        SettingsPageItem::SettingItem(SettingItem {
+            title: "Prompt Template",
+            description: "The prompt sent to the model. `{prefix}`, `{suffix}`, and `{file_path}` are replaced with the text around the cursor and the file's path.",
+            field: Box::new(SettingField {
+                pick: |settings| {
+                    settings
+                        .project
+                        .all_languages
+                        .edit_predictions
+                        .as_ref()?
+                        .local
+                        .as_ref()?
+                        .prompt_template
+                        .as_ref()
+                },
+                write: |settings, value| {
+                    settings
+                        .project
+                        .all_languages
+                        .edit_predictions
+                        .get_or_insert_default()
+                        .local
+                        .get_or_insert_default()
+                        .prompt_template = value;
+                },
+                json_path: Some("edit_predictions.local.prompt_template"),
+            }),
+            metadata: None,
+            files: USER,
+        }),
+        SettingsPageItem::SettingItem(SettingItem {
+            title: "Max Context Tokens",
+            description: "The maximum number of tokens from around the cursor to include in the prompt.",
+            field: Box::new(SettingField {
+                pick: |settings| {
+                    settings
+                        .project
+                        .all_languages
+                        .edit_predictions
+                        .as_ref()?
+                        .local
+                        .as_ref()?
+                        .max_context_tokens
+                        .as_ref()
+                },
+                write: |settings, value| {
+                    settings
+                        .project
+                        .all_languages
+                        .edit_predictions
+                        .get_or_insert_default()
+                        .local
+                        .get_or_insert_default()
+                        .max_context_tokens = value;
+                },
+                json_path: Some("edit_predictions.local.max_context_tokens"),
+            }),
+            metadata: None,
+            files: USER,
+        }),
+        SettingsPageItem::SettingItem(SettingItem {
+            title: "Max Output Tokens",
+            description: "The maximum number of tokens to generate.",
+            field: Box::new(SettingField {
+                pick: |settings| {
+                    settings
+                        .project
+                        .all_languages
+                        .edit_predictions
+                        .as_ref()?
+                        .local
+                        .as_ref()?
+                        .max_output_tokens
+                        .as_ref()
+                },
+                write: |settings, value| {
+                    settings
+                        .project
+                        .all_languages
+                        .edit_predictions
+                        .get_or_insert_default()
+                        .local
+                        .get_or_insert_default()
+                        .max_output_tokens = value;
+                },
+                json_path: Some("edit_predictions.local.max_output_tokens"),
+            }),
+            metadata: None,
+            files: USER,
+        }),
+        SettingsPageItem::SettingItem(SettingItem {
+            title: "Debounce",
+            description: "How long to wait after the last edit before requesting a prediction, in milliseconds.",
+            field: Box::new(SettingField {
+                pick: |settings| {
+                    settings
+                        .project
+                        .all_languages
+                        .edit_predictions
+                        .as_ref()?
+                        .local
+                        .as_ref()?
+                        .debounce_ms
+                        .as_ref()
+                },
+                write: |settings, value| {
+                    settings
+                        .project
+                        .all_languages
+                        .edit_predictions
+                        .get_or_insert_default()
+                        .local
+                        .get_or_insert_default()
+                        .debounce_ms = value;
+                },
+                json_path: Some("edit_predictions.local.debounce_ms"),
+            }),
+            metadata: None,
+            files: USER,
+        }),
+    ])
+}
+
 pub(crate) fn render_github_copilot_provider(
     window: &mut Window,
     cx: &mut App,
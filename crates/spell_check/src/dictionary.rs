@@ -0,0 +1,377 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use anyhow::{Context as _, Result, anyhow};
+use collections::HashSet;
+use encoding_rs::Encoding;
+use fs::Fs;
+use gpui::{App, AppContext as _, Context, Entity, Global, Subscription, Task};
+use settings::{Settings as _, SettingsStore};
+
+use crate::{SpellCheckSettings, words::is_apostrophe};
+
+#[derive(Default)]
+struct GlobalSpellChecker(Option<Entity<SpellChecker>>);
+
+impl Global for GlobalSpellChecker {}
+
+/// Loads the dictionaries that words are checked against, following the
+/// spell checking settings.
+pub struct SpellChecker {
+    fs: Arc<dyn Fs>,
+    /// The dictionaries that were loaded, or are being loaded, by name.
+    dictionary_names: Vec<String>,
+    dictionaries: Option<Arc<Dictionaries>>,
+    words: Arc<HashSet<String>>,
+    load_dictionaries: Task<()>,
+    _settings_subscription: Subscription,
+}
+
+impl SpellChecker {
+    pub(crate) fn init_global(fs: Arc<dyn Fs>, cx: &mut App) {
+        let spell_checker = cx.new(|cx| {
+            let mut spell_checker = Self {
+                fs,
+                dictionary_names: Vec::new(),
+                dictionaries: None,
+                words: Arc::default(),
+                load_dictionaries: Task::ready(()),
+                _settings_subscription: cx
+                    .observe_global::<SettingsStore>(|this, cx| this.settings_changed(cx)),
+            };
+            spell_checker.settings_changed(cx);
+            spell_checker
+        });
+        cx.set_global(GlobalSpellChecker(Some(spell_checker)));
+    }
+
+    pub fn global(cx: &App) -> Option<Entity<Self>> {
+        cx.try_global::<GlobalSpellChecker>()?.0.clone()
+    }
+
+    pub(crate) fn fs(&self) -> Arc<dyn Fs> {
+        self.fs.clone()
+    }
+
+    /// Returns what words are checked against, or `None` when spell checking
+    /// is disabled or its dictionaries have not been loaded yet.
+    pub(crate) fn vocabulary(&self) -> Option<Vocabulary> {
+        Some(Vocabulary {
+            dictionaries: self.dictionaries.clone()?,
+            word_lists: vec![self.words.clone()],
+        })
+    }
+
+    fn settings_changed(&mut self, cx: &mut Context<Self>) {
+        let settings = SpellCheckSettings::get_global(cx);
+        let words = settings.words.iter().cloned().collect::<HashSet<_>>();
+        if *self.words != words {
+            self.words = Arc::new(words);
+            cx.notify();
+        }
+
+        if !settings.enabled {
+            if self.dictionaries.take().is_some() {
+                cx.notify();
+            }
+            self.dictionary_names.clear();
+            self.load_dictionaries = Task::ready(());
+            return;
+        }
+        if self.dictionary_names == settings.dictionaries {
+            return;
+        }
+
+        self.dictionary_names = settings.dictionaries.clone();
+        let names = self.dictionary_names.clone();
+        let fs = self.fs.clone();
+        self.load_dictionaries = cx.spawn(async move |this, cx| {
+            let dictionaries = cx
+                .background_spawn(async move {
+                    let mut dictionaries = Vec::new();
+                    for name in &names {
+                        match load_dictionary(fs.as_ref(), name).await {
+                            Ok(dictionary) => dictionaries.push(dictionary),
+                            Err(error) => log::warn!("failed to load dictionary: {error:#}"),
+                        }
+                    }
+                    Dictionaries(dictionaries)
+                })
+                .await;
+            this.update(cx, |this, cx| {
+                this.dictionaries = Some(Arc::new(dictionaries));
+                cx.notify();
+            })
+            .ok();
+        });
+    }
+}
+
+/// Hunspell dictionaries, a word being spelled correctly if it is in any of
+/// them.
+pub(crate) struct Dictionaries(Vec<spellbook::Dictionary>);
+
+/// What words are checked against: the dictionaries, along with the words that
+/// the user and the project have added.
+#[derive(Clone)]
+pub(crate) struct Vocabulary {
+    dictionaries: Arc<Dictionaries>,
+    word_lists: Vec<Arc<HashSet<String>>>,
+}
+
+impl Vocabulary {
+    pub(crate) fn with_words(mut self, words: Arc<HashSet<String>>) -> Self {
+        self.word_lists.push(words);
+        self
+    }
+
+    pub(crate) fn contains(&self, word: &str) -> bool {
+        let word = normalize_apostrophes(word);
+        if self.word_lists.iter().any(|words| {
+            words.contains(word.as_str()) || words.contains(word.to_lowercase().as_str())
+        }) {
+            return true;
+        }
+        self.dictionaries
+            .0
+            .iter()
+            .any(|dictionary| dictionary.check(&word))
+    }
+
+    /// Returns the words from the dictionaries that `word` may be a
+    /// misspelling of, the likeliest first.
+    pub(crate) fn suggestions(&self, word: &str, limit: usize) -> Vec<String> {
+        let word = normalize_apostrophes(word);
+        let mut suggestions = Vec::new();
+        let mut dictionary_suggestions = Vec::new();
+        for dictionary in &self.dictionaries.0 {
+            dictionary.suggest(&word, &mut dictionary_suggestions);
+            for suggestion in dictionary_suggestions.drain(..) {
+                if !suggestions.contains(&suggestion) {
+                    suggestions.push(suggestion);
+                }
+            }
+        }
+        suggestions.truncate(limit);
+        suggestions
+    }
+}
+
+fn normalize_apostrophes(word: &str) -> String {
+    word.chars()
+        .map(|c| if is_apostrophe(c) { '\'' } else { c })
+        .collect()
+}
+
+async fn load_dictionary(fs: &dyn Fs, name: &str) -> Result<spellbook::Dictionary> {
+    let dirs = dictionary_dirs();
+    for dir in &dirs {
+        let aff_path = dir.join(format!("{name}.aff"));
+        let dic_path = dir.join(format!("{name}.dic"));
+        if !fs.is_file(&aff_path).await || !fs.is_file(&dic_path).await {
+            continue;
+        }
+        let aff = load_dictionary_file(fs, &aff_path).await?;
+        let dic = load_dictionary_file(fs, &dic_path).await?;
+        let encoding = dictionary_encoding(&aff, &dic);
+        let (aff, _, _) = encoding.decode(&aff);
+        let (dic, _, _) = encoding.decode(&dic);
+        // The files are passed on decoded, so the encoding that the affix file
+        // names no longer applies.
+        let aff = aff
+            .lines()
+            .filter(|line| line.split_whitespace().next() != Some("SET"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        return spellbook::Dictionary::new(&aff, &dic)
+            .map_err(|error| anyhow!("failed to parse {name} dictionary in {dir:?}: {error}"));
+    }
+    Err(anyhow!(
+        "no {name} dictionary was found in any of {}",
+        dirs.iter()
+            .map(|dir| dir.display().to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    ))
+}
+
+async fn load_dictionary_file(fs: &dyn Fs, path: &Path) -> Result<Vec<u8>> {
+    fs.load_bytes(path)
+        .await
+        .with_context(|| format!("failed to read {path:?}"))
+}
+
+/// Returns the encoding of the files of a dictionary, which is named by the
+/// `SET` line of its affix file.
+///
+/// Without a `SET` line, Hunspell reads dictionaries as ISO-8859-1, but the
+/// files are read as UTF-8 when they are valid UTF-8, as many newer
+/// dictionaries omit it.
+fn dictionary_encoding(aff: &[u8], dic: &[u8]) -> &'static Encoding {
+    let aff = aff.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(aff);
+    let label = aff.split(|byte| *byte == b'\n').find_map(|line| {
+        let mut fields = line
+            .split(u8::is_ascii_whitespace)
+            .filter(|field| !field.is_empty());
+        if fields.next()? == b"SET" {
+            fields.next()
+        } else {
+            None
+        }
+    });
+    match label {
+        Some(label) => {
+            // Hunspell names Windows code pages like `microsoft-cp1251`.
+            let label = label.strip_prefix(b"microsoft-").unwrap_or(label);
+            Encoding::for_label(label).unwrap_or_else(|| {
+                log::warn!(
+                    "unsupported dictionary encoding {:?}, reading it as UTF-8",
+                    String::from_utf8_lossy(label)
+                );
+                encoding_rs::UTF_8
+            })
+        }
+        None if std::str::from_utf8(aff).is_ok() && std::str::from_utf8(dic).is_ok() => {
+            encoding_rs::UTF_8
+        }
+        None => encoding_rs::WINDOWS_1252,
+    }
+}
+
+/// Returns the directories that dictionaries are looked up in, in order.
+fn dictionary_dirs() -> Vec<PathBuf> {
+    let mut dirs = vec![paths::dictionaries_dir().clone()];
+    if cfg!(target_os = "macos") {
+        dirs.push(paths::home_dir().join("Library/Spelling"));
+        dirs.push(PathBuf::from("/Library/Spelling"));
+    } else if cfg!(any(target_os = "linux", target_os = "freebsd")) {
+        dirs.extend(
+            [
+                "/usr/share/hunspell",
+                "/usr/local/share/hunspell",
+                "/usr/share/myspell",
+                "/usr/share/myspell/dicts",
+            ]
+            .map(PathBuf::from),
+        );
+    }
+    dirs
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use fs::FakeFs;
+    use gpui::TestAppContext;
+    use indoc::indoc;
+
+    pub(crate) const AFF: &str = indoc! {"
+        SET UTF-8
+        TRY esianrtolcdugmphbyfvkwz'
+
+        SFX S Y 1
+        SFX S 0 s .
+    "};
+
+    pub(crate) const DIC: &str = indoc! {"
+        4
+        hello
+        world/S
+        café
+        don't
+    "};
+
+    fn vocabulary(aff: &str, dic: &str, words: &[&str]) -> Vocabulary {
+        Vocabulary {
+            dictionaries: Arc::new(Dictionaries(vec![
+                spellbook::Dictionary::new(aff, dic).unwrap(),
+            ])),
+            word_lists: vec![Arc::new(
+                words.iter().map(|word| word.to_string()).collect(),
+            )],
+        }
+    }
+
+    #[test]
+    fn test_vocabulary() {
+        let vocabulary = vocabulary(AFF, DIC, &["zed"]);
+        for word in [
+            "hello", "Hello", "world", "worlds", "café", "don't", "don’t",
+        ] {
+            assert!(
+                vocabulary.contains(word),
+                "{word} should be spelled correctly"
+            );
+        }
+        for word in ["helo", "wrold", "cafe", "dont"] {
+            assert!(!vocabulary.contains(word), "{word} should be misspelled");
+        }
+
+        // Added words match regardless of the case of their first letter.
+        assert!(vocabulary.contains("zed"));
+        assert!(vocabulary.contains("Zed"));
+        assert!(!vocabulary.contains("hunspell"));
+        let vocabulary = vocabulary.with_words(Arc::new(HashSet::from_iter(["hunspell".into()])));
+        assert!(vocabulary.contains("hunspell"));
+
+        assert!(
+            vocabulary
+                .suggestions("helo", 5)
+                .contains(&"hello".to_string())
+        );
+        assert!(
+            vocabulary
+                .suggestions("wrold", 5)
+                .contains(&"world".to_string())
+        );
+        assert!(vocabulary.suggestions("helo", 0).is_empty());
+    }
+
+    #[test]
+    fn test_dictionary_encoding() {
+        assert_eq!(
+            dictionary_encoding(b"SET UTF-8\nTRY abc\n", b""),
+            encoding_rs::UTF_8
+        );
+        assert_eq!(
+            dictionary_encoding(b"\xEF\xBB\xBFSET ISO8859-1\r\n", b""),
+            encoding_rs::WINDOWS_1252
+        );
+        assert_eq!(
+            dictionary_encoding(b"# Russian\nSET microsoft-cp1251\n", b""),
+            encoding_rs::WINDOWS_1251
+        );
+        assert_eq!(
+            dictionary_encoding(b"SET KOI8-R\n", b""),
+            encoding_rs::KOI8_R
+        );
+        assert_eq!(
+            dictionary_encoding(b"TRY abc\n", "café".as_bytes()),
+            encoding_rs::UTF_8
+        );
+        assert_eq!(
+            dictionary_encoding(b"TRY abc\n", b"caf\xE9"),
+            encoding_rs::WINDOWS_1252
+        );
+    }
+
+    #[gpui::test]
+    async fn test_loading_legacy_encoded_dictionary(cx: &mut TestAppContext) {
+        let fs = FakeFs::new(cx.executor());
+        let dir = paths::dictionaries_dir();
+        fs.insert_file(
+            dir.join("fr.aff"),
+            AFF.replace("SET UTF-8", "SET ISO8859-1").into_bytes(),
+        )
+        .await;
+        let (dic, _, _) = encoding_rs::WINDOWS_1252.encode(DIC);
+        fs.insert_file(dir.join("fr.dic"), dic.into_owned()).await;
+
+        let dictionary = load_dictionary(fs.as_ref(), "fr").await.unwrap();
+        assert!(dictionary.check("café"));
+        assert!(!dictionary.check("cafÃ©"));
+        assert!(load_dictionary(fs.as_ref(), "de").await.is_err());
+    }
+}
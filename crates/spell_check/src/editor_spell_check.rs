@@ -0,0 +1,696 @@
+use std::{ops::Range, rc::Rc, sync::Arc, time::Duration};
+
+use anyhow::{Context as _, Result};
+use collections::{HashMap, HashSet};
+use editor::{Addon, CodeActionProvider, Editor, EditorEvent, EditorMode, ExcerptId};
+use gpui::{
+    App, AppContext as _, Context, Entity, HighlightStyle, Subscription, Task, UnderlineStyle,
+    WeakEntity, Window, px,
+};
+use language::{Buffer, BufferSnapshot, Point};
+use project::{CodeAction, LspAction, Project, ProjectPath, ProjectTransaction, WorktreeId};
+use serde::{Deserialize, Serialize};
+use text::OffsetRangeExt as _;
+use theme::ActiveTheme as _;
+
+use crate::{SpellChecker, dictionary::Vocabulary, misspelled_words};
+
+const REFRESH_DEBOUNCE: Duration = Duration::from_millis(300);
+/// Buffers larger than this are not checked, as they are rarely written by
+/// hand.
+const MAX_BUFFER_LEN: usize = 1024 * 1024;
+const MAX_SUGGESTIONS: usize = 5;
+const CODE_ACTION_PROVIDER_ID: &str = "spell_check";
+
+enum SpellCheckHighlight {}
+
+pub(crate) fn init(cx: &mut App) {
+    cx.observe_new(|editor: &mut Editor, window, cx| {
+        let Some(window) = window else {
+            return;
+        };
+        if matches!(
+            editor.mode(),
+            EditorMode::SingleLine | EditorMode::Minimap { .. }
+        ) {
+            return;
+        }
+        let Some(spell_checker) = SpellChecker::global(cx) else {
+            return;
+        };
+
+        let editor_handle = cx.entity();
+        let project = editor.project().cloned();
+        let checks_all_buffers = editor.mode().is_full();
+        let spell_check = cx.new(|cx| {
+            EditorSpellCheck::new(
+                editor_handle,
+                project,
+                spell_checker,
+                checks_all_buffers,
+                cx,
+            )
+        });
+        editor.add_code_action_provider(
+            Rc::new(SpellCheckCodeActionProvider {
+                spell_check: spell_check.clone(),
+            }),
+            window,
+            cx,
+        );
+        editor.register_addon(SpellCheckAddon {
+            _spell_check: spell_check,
+        });
+    })
+    .detach();
+}
+
+/// Keeps the spell checking of an editor alive for as long as the editor.
+struct SpellCheckAddon {
+    _spell_check: Entity<EditorSpellCheck>,
+}
+
+impl Addon for SpellCheckAddon {
+    fn to_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+/// Underlines the misspelled words of the excerpts of an editor.
+struct EditorSpellCheck {
+    editor: WeakEntity<Editor>,
+    project: Option<Entity<Project>>,
+    spell_checker: Entity<SpellChecker>,
+    /// Whether all buffers are checked, rather than only git commit messages,
+    /// which are edited in smaller editors.
+    checks_all_buffers: bool,
+    project_dictionaries: HashMap<WorktreeId, ProjectDictionary>,
+    refresh_task: Task<()>,
+    _subscriptions: Vec<Subscription>,
+}
+
+/// The words of the `.zed/dictionary.txt` file of a worktree, one per line.
+enum ProjectDictionary {
+    Missing,
+    Loading(Task<()>),
+    Loaded {
+        buffer: Entity<Buffer>,
+        words: Arc<HashSet<String>>,
+        _subscription: Subscription,
+    },
+}
+
+impl EditorSpellCheck {
+    fn new(
+        editor: Entity<Editor>,
+        project: Option<Entity<Project>>,
+        spell_checker: Entity<SpellChecker>,
+        checks_all_buffers: bool,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let mut subscriptions = vec![
+            cx.subscribe(&editor, |this, _, event: &EditorEvent, cx| match event {
+                EditorEvent::BufferEdited
+                | EditorEvent::Reparsed(_)
+                | EditorEvent::ExcerptsAdded { .. }
+                | EditorEvent::ExcerptsEdited { .. }
+                | EditorEvent::ExcerptsExpanded { .. } => this.refresh(cx),
+                _ => {}
+            }),
+            cx.observe(&spell_checker, |this, _, cx| this.refresh(cx)),
+        ];
+        if let Some(project) = &project {
+            subscriptions.push(cx.subscribe(project, Self::on_project_event));
+        }
+
+        let mut this = Self {
+            editor: editor.downgrade(),
+            project,
+            spell_checker,
+            checks_all_buffers,
+            project_dictionaries: HashMap::default(),
+            refresh_task: Task::ready(()),
+            _subscriptions: subscriptions,
+        };
+        this.refresh(cx);
+        this
+    }
+
+    fn on_project_event(
+        &mut self,
+        _: Entity<Project>,
+        event: &project::Event,
+        cx: &mut Context<Self>,
+    ) {
+        match event {
+            project::Event::WorktreeUpdatedEntries(worktree_id, entries) => {
+                let dictionary_path = paths::local_dictionary_file_relative_path();
+                let Some((_, _, change)) = entries
+                    .iter()
+                    .find(|(path, _, _)| path.as_ref() == dictionary_path)
+                else {
+                    return;
+                };
+                // Loaded dictionaries follow the edits of their buffer, so
+                // only the creation and removal of the file matter.
+                let is_loaded = matches!(
+                    self.project_dictionaries.get(worktree_id),
+                    Some(ProjectDictionary::Loaded { .. })
+                );
+                if !is_loaded || *change == project::PathChange::Removed {
+                    self.project_dictionaries.remove(worktree_id);
+                    self.refresh(cx);
+                }
+            }
+            project::Event::WorktreeRemoved(worktree_id) => {
+                self.project_dictionaries.remove(worktree_id);
+            }
+            _ => {}
+        }
+    }
+
+    fn checks_buffer(&self, snapshot: &BufferSnapshot) -> bool {
+        if snapshot.len() > MAX_BUFFER_LEN {
+            return false;
+        }
+        self.checks_all_buffers
+            || snapshot
+                .language()
+                .is_some_and(|language| language.name().as_ref() == "Git Commit")
+    }
+
+    /// Returns the worktree whose dictionary applies to `buffer`: the one
+    /// containing it, or the first folder of the project for buffers outside
+    /// of any folder.
+    fn dictionary_worktree(&self, buffer: &Buffer, cx: &App) -> Option<WorktreeId> {
+        let project = self.project.as_ref()?.read(cx);
+        buffer
+            .file()
+            .and_then(|file| project.worktree_for_id(file.worktree_id(cx), cx))
+            .filter(|worktree| !worktree.read(cx).is_single_file())
+            .or_else(|| {
+                project
+                    .visible_worktrees(cx)
+                    .find(|worktree| !worktree.read(cx).is_single_file())
+            })
+            .map(|worktree| worktree.read(cx).id())
+    }
+
+    fn vocabulary(&self, buffer: &Buffer, cx: &App) -> Option<Vocabulary> {
+        let vocabulary = self.spell_checker.read(cx).vocabulary()?;
+        let project_words = self
+            .dictionary_worktree(buffer, cx)
+            .and_then(|worktree_id| self.project_dictionaries.get(&worktree_id))
+            .and_then(|dictionary| match dictionary {
+                ProjectDictionary::Loaded { words, .. } => Some(words.clone()),
+                ProjectDictionary::Missing | ProjectDictionary::Loading(_) => None,
+            });
+        Some(match project_words {
+            Some(words) => vocabulary.with_words(words),
+            None => vocabulary,
+        })
+    }
+
+    fn load_project_dictionary(&mut self, worktree_id: WorktreeId, cx: &mut Context<Self>) {
+        if self.project_dictionaries.contains_key(&worktree_id) {
+            return;
+        }
+        let Some(project) = self.project.clone() else {
+            return;
+        };
+        let dictionary_path = paths::local_dictionary_file_relative_path();
+        let exists = project
+            .read(cx)
+            .worktree_for_id(worktree_id, cx)
+            .and_then(|worktree| worktree.read(cx).entry_for_path(dictionary_path).cloned())
+            .is_some_and(|entry| entry.is_file());
+        if !exists {
+            self.project_dictionaries
+                .insert(worktree_id, ProjectDictionary::Missing);
+            return;
+        }
+
+        let open_buffer = project.update(cx, |project, cx| {
+            project.open_buffer(
+                ProjectPath {
+                    worktree_id,
+                    path: dictionary_path.into_arc(),
+                },
+                cx,
+            )
+        });
+        let load = cx.spawn(async move |this, cx| {
+            let buffer = open_buffer.await;
+            this.update(cx, |this, cx| {
+                let dictionary = match buffer {
+                    Ok(buffer) => ProjectDictionary::Loaded {
+                        words: dictionary_words(&buffer.read(cx).snapshot()),
+                        _subscription: cx.observe(&buffer, move |this, buffer, cx| {
+                            if let Some(ProjectDictionary::Loaded { words, .. }) =
+                                this.project_dictionaries.get_mut(&worktree_id)
+                            {
+                                *words = dictionary_words(&buffer.read(cx).snapshot());
+                                this.refresh(cx);
+                            }
+                        }),
+                        buffer,
+                    },
+                    Err(error) => {
+                        log::error!("failed to open the project dictionary: {error:#}");
+                        ProjectDictionary::Missing
+                    }
+                };
+                this.project_dictionaries.insert(worktree_id, dictionary);
+                this.refresh(cx);
+            })
+            .ok();
+        });
+        self.project_dictionaries
+            .insert(worktree_id, ProjectDictionary::Loading(load));
+    }
+
+    fn refresh(&mut self, cx: &mut Context<Self>) {
+        self.refresh_task = cx.spawn(async move |this, cx| {
+            cx.background_executor().timer(REFRESH_DEBOUNCE).await;
+            let Some((editor, checks)) = this
+                .update(cx, |this, cx| {
+                    let editor = this.editor.upgrade()?;
+                    Some((editor, this.buffers_to_check(cx)))
+                })
+                .ok()
+                .flatten()
+            else {
+                return;
+            };
+
+            let Some(checks) = checks else {
+                editor
+                    .update(cx, |editor, cx| {
+                        editor.clear_highlights::<SpellCheckHighlight>(cx)
+                    })
+                    .ok();
+                return;
+            };
+            let misspellings = cx
+                .background_spawn(async move {
+                    checks
+                        .into_iter()
+                        .map(|check| {
+                            let ranges = misspelled_words(
+                                &check.snapshot,
+                                check.range.clone(),
+                                &check.vocabulary,
+                            )
+                            .into_iter()
+                            .map(|range| {
+                                check.snapshot.anchor_after(range.start)
+                                    ..check.snapshot.anchor_before(range.end)
+                            })
+                            .collect::<Vec<_>>();
+                            (check.excerpt_id, ranges)
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .await;
+
+            editor
+                .update(cx, |editor, cx| {
+                    let snapshot = editor.buffer().read(cx).snapshot(cx);
+                    let ranges = misspellings
+                        .into_iter()
+                        .flat_map(|(excerpt_id, ranges)| {
+                            let snapshot = &snapshot;
+                            ranges.into_iter().filter_map(move |range| {
+                                snapshot.anchor_range_in_excerpt(excerpt_id, range)
+                            })
+                        })
+                        .collect::<Vec<_>>();
+                    let style = HighlightStyle {
+                        underline: Some(UnderlineStyle {
+                            color: Some(cx.theme().status().info),
+                            thickness: px(1.),
+                            wavy: true,
+                        }),
+                        ..HighlightStyle::default()
+                    };
+                    editor.highlight_text::<SpellCheckHighlight>(ranges, style, cx);
+                })
+                .ok();
+        });
+    }
+
+    /// Returns the excerpts to check, or `None` when spell checking is off.
+    fn buffers_to_check(&mut self, cx: &mut Context<Self>) -> Option<Vec<ExcerptCheck>> {
+        let editor = self.editor.upgrade()?;
+        let multi_buffer = editor.read(cx).buffer().clone();
+        let snapshot = multi_buffer.read(cx).snapshot(cx);
+        let mut checks = Vec::new();
+        for (excerpt_id, buffer_snapshot, range) in snapshot.excerpts() {
+            if !self.checks_buffer(buffer_snapshot) {
+                continue;
+            }
+            let Some(buffer) = multi_buffer.read(cx).buffer(buffer_snapshot.remote_id()) else {
+                continue;
+            };
+            if let Some(worktree_id) = self.dictionary_worktree(buffer.read(cx), cx) {
+                self.load_project_dictionary(worktree_id, cx);
+            }
+            let vocabulary = self.vocabulary(buffer.read(cx), cx)?;
+            checks.push(ExcerptCheck {
+                excerpt_id,
+                range: range.context.to_offset(buffer_snapshot),
+                snapshot: buffer_snapshot.clone(),
+                vocabulary,
+            });
+        }
+        Some(checks)
+    }
+
+    fn add_to_dictionary(
+        &mut self,
+        word: String,
+        buffer: &Entity<Buffer>,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let worktree_id = self.dictionary_worktree(buffer.read(cx), cx);
+        let (Some(project), Some(worktree_id)) = (self.project.clone(), worktree_id) else {
+            // Without a project folder to keep a dictionary in, words are
+            // added to the user's settings.
+            let fs = self.spell_checker.read(cx).fs();
+            settings::update_settings_file(fs, cx, move |settings, _| {
+                let words = settings
+                    .spell_check
+                    .get_or_insert_default()
+                    .words
+                    .get_or_insert_default();
+                if !words.contains(&word) {
+                    words.push(word);
+                }
+            });
+            return Task::ready(Ok(()));
+        };
+
+        let open_buffer = match self.project_dictionaries.get(&worktree_id) {
+            Some(ProjectDictionary::Loaded { buffer, .. }) => Task::ready(Ok(buffer.clone())),
+            _ => project.update(cx, |project, cx| {
+                project.open_buffer(
+                    ProjectPath {
+                        worktree_id,
+                        path: paths::local_dictionary_file_relative_path().into_arc(),
+                    },
+                    cx,
+                )
+            }),
+        };
+        cx.spawn(async move |_, cx| {
+            let dictionary = open_buffer
+                .await
+                .context("failed to open the project dictionary")?;
+            project
+                .update(cx, |project, cx| {
+                    dictionary.update(cx, |dictionary, cx| {
+                        let len = dictionary.len();
+                        let mut text = String::new();
+                        if len > 0 && dictionary.reversed_chars_at(len).next() != Some('\n') {
+                            text.push('\n');
+                        }
+                        text.push_str(&word);
+                        text.push('\n');
+                        dictionary.edit([(len..len, text)], None, cx);
+                    });
+                    project.save_buffer(dictionary, cx)
+                })?
+                .await
+                .context("failed to save the project dictionary")
+        })
+    }
+}
+
+struct ExcerptCheck {
+    excerpt_id: ExcerptId,
+    range: Range<usize>,
+    snapshot: BufferSnapshot,
+    vocabulary: Vocabulary,
+}
+
+fn dictionary_words(snapshot: &BufferSnapshot) -> Arc<HashSet<String>> {
+    Arc::new(
+        snapshot
+            .text()
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(ToOwned::to_owned)
+            .collect(),
+    )
+}
+
+#[derive(Serialize, Deserialize)]
+enum SpellCheckAction {
+    Replace { replacement: String },
+    AddToDictionary { word: String },
+}
+
+struct SpellCheckCodeActionProvider {
+    spell_check: Entity<EditorSpellCheck>,
+}
+
+impl CodeActionProvider for SpellCheckCodeActionProvider {
+    fn id(&self) -> Arc<str> {
+        CODE_ACTION_PROVIDER_ID.into()
+    }
+
+    fn code_actions(
+        &self,
+        buffer: &Entity<Buffer>,
+        range: Range<text::Anchor>,
+        _: &mut Window,
+        cx: &mut App,
+    ) -> Task<Result<Vec<CodeAction>>> {
+        let spell_check = self.spell_check.read(cx);
+        let snapshot = buffer.read(cx).snapshot();
+        if !spell_check.checks_buffer(&snapshot) {
+            return Task::ready(Ok(Vec::new()));
+        }
+        let Some(vocabulary) = spell_check.vocabulary(buffer.read(cx), cx) else {
+            return Task::ready(Ok(Vec::new()));
+        };
+
+        let range = range.to_offset(&snapshot);
+        let start_row = snapshot.offset_to_point(range.start).row;
+        let end_row = snapshot.offset_to_point(range.end).row;
+        let lines = snapshot.point_to_offset(Point::new(start_row, 0))
+            ..snapshot.point_to_offset(Point::new(end_row, snapshot.line_len(end_row)));
+        cx.background_spawn(async move {
+            let mut actions = Vec::new();
+            for word_range in misspelled_words(&snapshot, lines, &vocabulary) {
+                if word_range.end < range.start || word_range.start > range.end {
+                    continue;
+                }
+                let word = snapshot
+                    .text_for_range(word_range.clone())
+                    .collect::<String>();
+                let anchor_range =
+                    snapshot.anchor_after(word_range.start)..snapshot.anchor_before(word_range.end);
+                for replacement in vocabulary.suggestions(&word, MAX_SUGGESTIONS) {
+                    actions.push(code_action(
+                        format!("Change to \"{replacement}\""),
+                        anchor_range.clone(),
+                        SpellCheckAction::Replace { replacement },
+                    ));
+                }
+                actions.push(code_action(
+                    format!("Add \"{word}\" to dictionary"),
+                    anchor_range,
+                    SpellCheckAction::AddToDictionary { word },
+                ));
+            }
+            Ok(actions)
+        })
+    }
+
+    fn apply_code_action(
+        &self,
+        buffer: Entity<Buffer>,
+        action: CodeAction,
+        _: ExcerptId,
+        push_to_history: bool,
+        _: &mut Window,
+        cx: &mut App,
+    ) -> Task<Result<ProjectTransaction>> {
+        let LspAction::Action(lsp_action) = &action.lsp_action else {
+            return Task::ready(Ok(ProjectTransaction::default()));
+        };
+        let spell_check_action = match lsp_action
+            .data
+            .clone()
+            .context("missing spell check action")
+            .and_then(|data| Ok(serde_json::from_value::<SpellCheckAction>(data)?))
+        {
+            Ok(spell_check_action) => spell_check_action,
+            Err(error) => return Task::ready(Err(error)),
+        };
+
+        match spell_check_action {
+            SpellCheckAction::Replace { replacement } => {
+                let transaction = buffer.update(cx, |buffer, cx| {
+                    buffer.finalize_last_transaction();
+                    buffer.start_transaction();
+                    buffer.edit([(action.range, replacement)], None, cx);
+                    if buffer.end_transaction(cx).is_some() {
+                        let transaction = buffer.finalize_last_transaction().unwrap().clone();
+                        if !push_to_history {
+                            buffer.forget_transaction(transaction.id);
+                        }
+                        Some(transaction)
+                    } else {
+                        None
+                    }
+                });
+                let mut project_transaction = ProjectTransaction::default();
+                if let Some(transaction) = transaction {
+                    project_transaction.0.insert(buffer, transaction);
+                }
+                Task::ready(Ok(project_transaction))
+            }
+            SpellCheckAction::AddToDictionary { word } => {
+                let add_word = self.spell_check.update(cx, |spell_check, cx| {
+                    spell_check.add_to_dictionary(word, &buffer, cx)
+                });
+                cx.background_spawn(async move {
+                    add_word.await?;
+                    Ok(ProjectTransaction::default())
+                })
+            }
+        }
+    }
+}
+
+fn code_action(
+    title: String,
+    range: Range<text::Anchor>,
+    spell_check_action: SpellCheckAction,
+) -> CodeAction {
+    CodeAction {
+        server_id: None,
+        range,
+        lsp_action: LspAction::Action(Box::new(lsp::CodeAction {
+            title,
+            data: serde_json::to_value(spell_check_action).ok(),
+            ..Default::default()
+        })),
+        resolved: true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dictionary::tests::{AFF, DIC};
+    use fs::FakeFs;
+    use gpui::{AppContext as _, TestAppContext, UpdateGlobal as _, VisualTestContext};
+    use serde_json::json;
+    use settings::SettingsStore;
+    use util::path;
+
+    #[gpui::test]
+    async fn test_code_actions(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let store = SettingsStore::test(cx);
+            cx.set_global(store);
+            theme::init(theme::LoadThemes::JustBase, cx);
+            SettingsStore::update_global(cx, |store, cx| {
+                store.update_user_settings(cx, |settings| {
+                    settings.spell_check.get_or_insert_default().enabled = Some(true);
+                });
+            });
+        });
+        let fs = FakeFs::new(cx.executor());
+        let dictionaries_dir = paths::dictionaries_dir();
+        fs.insert_file(dictionaries_dir.join("en_US.aff"), AFF.into())
+            .await;
+        fs.insert_file(dictionaries_dir.join("en_US.dic"), DIC.into())
+            .await;
+        fs.insert_tree(
+            path!("/dir"),
+            json!({
+                ".zed": {},
+                "notes.txt": "Helo world, zedd\n",
+            }),
+        )
+        .await;
+        let project = Project::test(fs.clone(), [path!("/dir").as_ref()], cx).await;
+        cx.update(|cx| SpellChecker::init_global(fs.clone(), cx));
+        let buffer = project
+            .update(cx, |project, cx| {
+                project.open_local_buffer(path!("/dir/notes.txt"), cx)
+            })
+            .await
+            .unwrap();
+
+        let (editor, cx) = cx.add_window_view(|window, cx| {
+            Editor::for_buffer(buffer.clone(), Some(project.clone()), window, cx)
+        });
+        let spell_check = cx.new(|cx| {
+            let spell_checker = SpellChecker::global(cx).unwrap();
+            EditorSpellCheck::new(editor, Some(project.clone()), spell_checker, true, cx)
+        });
+        let provider = SpellCheckCodeActionProvider { spell_check };
+        cx.run_until_parked();
+
+        let code_actions = |range: Range<usize>, cx: &mut VisualTestContext| {
+            cx.update(|window, cx| {
+                let snapshot = buffer.read(cx).snapshot();
+                let range = snapshot.anchor_before(range.start)..snapshot.anchor_after(range.end);
+                provider.code_actions(&buffer, range, window, cx)
+            })
+        };
+        let apply = |action: CodeAction, cx: &mut VisualTestContext| {
+            cx.update(|window, cx| {
+                provider.apply_code_action(
+                    buffer.clone(),
+                    action,
+                    ExcerptId::min(),
+                    true,
+                    window,
+                    cx,
+                )
+            })
+        };
+
+        // Misspelled words can be replaced with the words they are likely
+        // misspellings of.
+        let actions = code_actions(0..0, cx).await.unwrap();
+        let titles = actions
+            .iter()
+            .map(|action| action.lsp_action.title())
+            .collect::<Vec<_>>();
+        assert_eq!(titles.first(), Some(&"Change to \"Hello\""));
+        assert_eq!(titles.last(), Some(&"Add \"Helo\" to dictionary"));
+        let transaction = apply(actions[0].clone(), cx).await.unwrap();
+        assert!(transaction.0.contains_key(&buffer));
+        buffer.read_with(cx, |buffer, _| {
+            assert_eq!(buffer.text(), "Hello world, zedd\n");
+        });
+
+        // Words added to the dictionary are kept in the project's dictionary,
+        // and are no longer misspelled.
+        let actions = code_actions(13..17, cx).await.unwrap();
+        let add_to_dictionary = actions
+            .into_iter()
+            .find(|action| action.lsp_action.title() == "Add \"zedd\" to dictionary")
+            .unwrap();
+        let transaction = apply(add_to_dictionary, cx).await.unwrap();
+        assert!(transaction.0.is_empty());
+        cx.run_until_parked();
+        assert_eq!(
+            fs.load(path!("/dir/.zed/dictionary.txt").as_ref())
+                .await
+                .unwrap(),
+            "zedd\n"
+        );
+        cx.executor().advance_clock(REFRESH_DEBOUNCE);
+        cx.run_until_parked();
+        assert!(code_actions(0..17, cx).await.unwrap().is_empty());
+    }
+}
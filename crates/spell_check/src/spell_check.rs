@@ -0,0 +1,246 @@
+//! Checks the spelling of comments and strings in code, and of prose files as
+//! a whole, against Hunspell dictionaries.
+
+mod dictionary;
+mod editor_spell_check;
+mod words;
+
+use std::{ops::Range, sync::Arc};
+
+use fs::Fs;
+use gpui::App;
+use language::{BufferSnapshot, Language};
+use settings::{RegisterSetting, Settings};
+
+pub use dictionary::SpellChecker;
+use dictionary::Vocabulary;
+
+#[derive(Debug, RegisterSetting)]
+pub struct SpellCheckSettings {
+    pub enabled: bool,
+    pub dictionaries: Vec<String>,
+    pub words: Vec<String>,
+}
+
+impl Settings for SpellCheckSettings {
+    fn from_settings(content: &settings::SettingsContent) -> Self {
+        let spell_check = content.spell_check.as_ref().unwrap();
+        Self {
+            enabled: spell_check.enabled.unwrap(),
+            dictionaries: spell_check.dictionaries.clone().unwrap(),
+            words: spell_check.words.clone().unwrap(),
+        }
+    }
+}
+
+pub fn init(fs: Arc<dyn Fs>, cx: &mut App) {
+    SpellChecker::init_global(fs, cx);
+    editor_spell_check::init(cx);
+}
+
+/// The languages of documents that are prose as a whole, rather than code with
+/// prose in its comments and strings.
+const PROSE_LANGUAGES: &[&str] = &["Markdown", "Markdown-Inline", "Plain Text", "Git Commit"];
+
+/// The highlight captures of the parts of prose documents that are not prose,
+/// such as code spans, links and commit hashes.
+const NON_PROSE_CAPTURES: &[&str] = &[
+    "text.literal",
+    "link_uri",
+    "punctuation.embedded",
+    "string",
+    "constant",
+    "markup.link.url",
+];
+
+fn is_prose(language: &Language) -> bool {
+    PROSE_LANGUAGES.contains(&language.name().as_ref())
+}
+
+fn capture_matches(name: &str, prefixes: &[&str]) -> bool {
+    prefixes.iter().any(|prefix| {
+        name.strip_prefix(prefix)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with('.'))
+    })
+}
+
+/// Returns the ranges within `range` whose spelling is checked: the whole
+/// text of prose documents except for the code within them, and only the
+/// comments and strings of code.
+fn checked_ranges(snapshot: &BufferSnapshot, range: Range<usize>) -> Vec<Range<usize>> {
+    let is_prose_document = snapshot
+        .language()
+        .is_none_or(|language| is_prose(language));
+    let mut included = Vec::new();
+    let mut excluded = Vec::new();
+    if is_prose_document {
+        included.push(range.clone());
+        for layer in snapshot.syntax_layers_for_range(range.clone(), false) {
+            if !is_prose(layer.language) {
+                excluded.push(layer.node().byte_range());
+            }
+        }
+    }
+
+    let mut captures = snapshot
+        .syntax
+        .captures(range.clone(), &snapshot.text, |grammar| {
+            grammar
+                .highlights_config
+                .as_ref()
+                .map(|config| &config.query)
+        });
+    let capture_names = captures
+        .grammars()
+        .iter()
+        .copied()
+        .map(|grammar| {
+            grammar
+                .highlights_config
+                .as_ref()
+                .map(|config| config.query.capture_names())
+        })
+        .collect::<Vec<_>>();
+    while let Some(capture) = captures.peek() {
+        let name = capture_names[capture.grammar_index]
+            .and_then(|names| names.get(capture.index as usize).copied());
+        if let Some(name) = name {
+            if is_prose_document {
+                if capture_matches(name, NON_PROSE_CAPTURES) {
+                    excluded.push(capture.node.byte_range());
+                }
+            } else if capture_matches(name, &["comment", "string"])
+                && !capture_matches(name, &["string.escape", "string.regex", "string.special"])
+            {
+                included.push(capture.node.byte_range());
+            }
+        }
+        captures.advance();
+    }
+
+    subtract_ranges(merge_ranges(included), merge_ranges(excluded))
+        .into_iter()
+        .filter_map(|checked| {
+            let start = checked.start.max(range.start);
+            let end = checked.end.min(range.end);
+            (start < end).then_some(start..end)
+        })
+        .collect()
+}
+
+fn merge_ranges(mut ranges: Vec<Range<usize>>) -> Vec<Range<usize>> {
+    ranges.sort_unstable_by_key(|range| range.start);
+    let mut merged: Vec<Range<usize>> = Vec::with_capacity(ranges.len());
+    for range in ranges {
+        if let Some(last) = merged.last_mut()
+            && range.start <= last.end
+        {
+            last.end = last.end.max(range.end);
+        } else {
+            merged.push(range);
+        }
+    }
+    merged
+}
+
+/// Removes `excluded` from `included`, both being sorted and disjoint.
+fn subtract_ranges(included: Vec<Range<usize>>, excluded: Vec<Range<usize>>) -> Vec<Range<usize>> {
+    let mut result = Vec::new();
+    let mut excluded = excluded.into_iter().peekable();
+    for mut range in included {
+        while let Some(next_excluded) = excluded.peek() {
+            if next_excluded.end <= range.start {
+                excluded.next();
+                continue;
+            }
+            if next_excluded.start >= range.end {
+                break;
+            }
+            if next_excluded.start > range.start {
+                result.push(range.start..next_excluded.start);
+            }
+            range.start = next_excluded.end;
+            if range.start >= range.end {
+                break;
+            }
+            excluded.next();
+        }
+        if range.start < range.end {
+            result.push(range);
+        }
+    }
+    result
+}
+
+/// Returns the ranges of the misspelled words of `snapshot` within `range`.
+fn misspelled_words(
+    snapshot: &BufferSnapshot,
+    range: Range<usize>,
+    vocabulary: &Vocabulary,
+) -> Vec<Range<usize>> {
+    let mut misspelled = Vec::new();
+    for checked in checked_ranges(snapshot, range) {
+        let text = snapshot.text_for_range(checked.clone()).collect::<String>();
+        for word in words::words(&text) {
+            if !vocabulary.contains(&text[word.clone()]) {
+                misspelled.push(checked.start + word.start..checked.start + word.end);
+            }
+        }
+    }
+    misspelled
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{AppContext as _, TestAppContext};
+    use indoc::indoc;
+    use language::{Buffer, rust_lang};
+
+    #[test]
+    fn test_subtract_ranges() {
+        assert_eq!(
+            subtract_ranges(vec![0..10, 20..30], vec![2..4, 8..22, 25..26, 40..50]),
+            [0..2, 4..8, 22..25, 26..30]
+        );
+        assert_eq!(
+            subtract_ranges(vec![5..10], vec![0..20]),
+            Vec::<Range<usize>>::new()
+        );
+        assert_eq!(merge_ranges(vec![10..12, 0..5, 3..8, 8..9]), [0..9, 10..12]);
+    }
+
+    #[gpui::test]
+    async fn test_checked_ranges(cx: &mut TestAppContext) {
+        let text = indoc! {r#"
+            // A commnet about the functoin.
+            fn mispeled_name() -> &'static str {
+                "a strnig\n"
+            }
+        "#};
+        let buffer = cx.new(|cx| Buffer::local(text, cx).with_language(rust_lang(), cx));
+        cx.run_until_parked();
+        let snapshot = buffer.read_with(cx, |buffer, _| buffer.snapshot());
+        assert_eq!(
+            checked_words(&snapshot, text),
+            ["commnet", "about", "the", "functoin", "strnig"]
+        );
+
+        let text = "Teh whole docment.";
+        let buffer = cx.new(|cx| Buffer::local(text, cx));
+        let snapshot = buffer.read_with(cx, |buffer, _| buffer.snapshot());
+        assert_eq!(checked_ranges(&snapshot, 0..text.len()), [0..text.len()]);
+        assert_eq!(checked_words(&snapshot, text), ["Teh", "whole", "docment"]);
+    }
+
+    fn checked_words<'a>(snapshot: &BufferSnapshot, text: &'a str) -> Vec<&'a str> {
+        checked_ranges(snapshot, 0..text.len())
+            .into_iter()
+            .flat_map(|range| {
+                words::words(&text[range.clone()])
+                    .into_iter()
+                    .map(move |word| &text[range.start + word.start..range.start + word.end])
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,165 @@
+use std::ops::Range;
+
+/// Tokens longer than this are more likely to be hashes or encoded data than
+/// words, and are not checked.
+const MAX_TOKEN_LEN: usize = 64;
+
+/// Returns the ranges of the words to check in `text`.
+///
+/// Identifiers are split into the words they are made of, so that each part
+/// of `snake_case` and `camelCase` names is checked on its own. Tokens that
+/// are unlikely to be words, such as numbers, acronyms, URLs and email
+/// addresses, are skipped.
+pub fn words(text: &str) -> Vec<Range<usize>> {
+    let mut words = Vec::new();
+    for chunk in chunks(text, char::is_whitespace) {
+        let chunk_text = &text[chunk.clone()];
+        if chunk_text.contains("://") || chunk_text.contains('@') {
+            continue;
+        }
+
+        for token in tokens(chunk_text) {
+            let token_text = &chunk_text[token.clone()];
+            if token_text.len() > MAX_TOKEN_LEN || token_text.chars().any(char::is_numeric) {
+                continue;
+            }
+            let token_start = chunk.start + token.start;
+            for part in identifier_parts(token_text) {
+                let part_text = &token_text[part.clone()];
+                if is_word(part_text) {
+                    words.push(token_start + part.start..token_start + part.end);
+                }
+            }
+        }
+    }
+    words
+}
+
+/// Returns the ranges of the runs of characters in `text` that are not
+/// separated by `is_separator`.
+fn chunks(text: &str, is_separator: impl Fn(char) -> bool) -> Vec<Range<usize>> {
+    let mut chunks = Vec::new();
+    let mut start = None;
+    for (ix, c) in text.char_indices() {
+        match (is_separator(c), start) {
+            (true, Some(chunk_start)) => {
+                chunks.push(chunk_start..ix);
+                start = None;
+            }
+            (false, None) => start = Some(ix),
+            _ => {}
+        }
+    }
+    if let Some(chunk_start) = start {
+        chunks.push(chunk_start..text.len());
+    }
+    chunks
+}
+
+/// Returns the ranges of the identifiers and words in `text`, which may
+/// contain apostrophes between letters, as in "don't".
+fn tokens(text: &str) -> Vec<Range<usize>> {
+    let mut tokens = Vec::new();
+    let mut start = None;
+    let mut chars = text.char_indices().peekable();
+    let mut prev = None;
+    while let Some((ix, c)) = chars.next() {
+        let next = chars.peek().map(|(_, c)| *c);
+        let is_token_char = c.is_alphanumeric()
+            || c == '_'
+            || (is_apostrophe(c)
+                && prev.is_some_and(char::is_alphabetic)
+                && next.is_some_and(char::is_alphabetic));
+        match (is_token_char, start) {
+            (false, Some(token_start)) => {
+                tokens.push(token_start..ix);
+                start = None;
+            }
+            (true, None) => start = Some(ix),
+            _ => {}
+        }
+        prev = Some(c);
+    }
+    if let Some(token_start) = start {
+        tokens.push(token_start..text.len());
+    }
+    tokens
+}
+
+/// Splits an identifier at underscores and at changes of case, keeping runs
+/// of capitals together, so that `parseHTTPResponse` is made of `parse`,
+/// `HTTP` and `Response`.
+fn identifier_parts(identifier: &str) -> Vec<Range<usize>> {
+    let mut parts = Vec::new();
+    for segment in chunks(identifier, |c| c == '_') {
+        let segment_text = &identifier[segment.clone()];
+        let chars = segment_text.char_indices().collect::<Vec<_>>();
+        let mut part_start = 0;
+        for (i, &(ix, c)) in chars.iter().enumerate().skip(1) {
+            let prev = chars[i - 1].1;
+            let next = chars.get(i + 1).map(|(_, c)| *c);
+            let starts_part = c.is_uppercase()
+                && (prev.is_lowercase()
+                    || (prev.is_uppercase() && next.is_some_and(char::is_lowercase)));
+            if starts_part {
+                parts.push(segment.start + part_start..segment.start + ix);
+                part_start = ix;
+            }
+        }
+        parts.push(segment.start + part_start..segment.end);
+    }
+    parts
+}
+
+fn is_word(text: &str) -> bool {
+    let mut letters = text.chars().filter(|c| !is_apostrophe(*c));
+    let Some(first) = letters.next() else {
+        return false;
+    };
+    // Single letters are too short to be misspelled, and words written in
+    // capitals are usually acronyms or constants.
+    let mut is_all_uppercase = first.is_uppercase();
+    let mut len = 1;
+    for c in letters {
+        is_all_uppercase &= c.is_uppercase();
+        len += 1;
+    }
+    len > 1 && !is_all_uppercase
+}
+
+pub fn is_apostrophe(c: char) -> bool {
+    c == '\'' || c == '’'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words_in(text: &str) -> Vec<&str> {
+        words(text).into_iter().map(|range| &text[range]).collect()
+    }
+
+    #[test]
+    fn test_words() {
+        assert_eq!(
+            words_in("// Returns the wrold, or nothing."),
+            ["Returns", "the", "wrold", "or", "nothing"]
+        );
+        assert_eq!(
+            words_in("parseHTTPResponse snake_case_name __private XMLHttpRequest"),
+            [
+                "parse", "Response", "snake", "case", "name", "private", "Http", "Request"
+            ]
+        );
+        assert_eq!(
+            words_in("Don't 'quote' can’t it's' o'"),
+            ["Don't", "quote", "can’t", "it's"]
+        );
+        assert_eq!(
+            words_in("See https://exmaple.com/docs or mail me@exmaple.com."),
+            ["See", "or", "mail"]
+        );
+        assert_eq!(words_in("utf8 v2 0xff 42 MAX_LEN a I façade"), ["façade"]);
+        assert_eq!(words_in(&"a".repeat(MAX_TOKEN_LEN + 1)), Vec::<&str>::new());
+    }
+}
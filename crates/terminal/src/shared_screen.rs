@@ -0,0 +1,247 @@
+//! Snapshots of the visible screen of a terminal, used to mirror a terminal in
+//! a display-only terminal, such as for collaborators watching a shared terminal.
+
+use std::fmt::Write as _;
+
+use alacritty_terminal::{
+    Term,
+    grid::{Dimensions, Row},
+    index::{Column, Line},
+    term::cell::{Cell, Flags},
+    vte::ansi::{Color, NamedColor},
+};
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ScreenSnapshot {
+    /// The lines of the screen, from top to bottom, with their colors and
+    /// styles encoded as SGR escape sequences.
+    pub lines: Vec<String>,
+    pub cursor_line: usize,
+    pub cursor_column: usize,
+}
+
+pub(crate) fn snapshot<T>(term: &Term<T>) -> ScreenSnapshot {
+    let grid = term.grid();
+    let lines = (0..grid.screen_lines())
+        .map(|line| render_row(&grid[Line(line as i32)], grid.columns()))
+        .collect();
+    let cursor = grid.cursor.point;
+    ScreenSnapshot {
+        lines,
+        cursor_line: cursor.line.0.max(0) as usize,
+        cursor_column: cursor.column.0,
+    }
+}
+
+/// Returns how many lines the screen scrolled between two snapshots of it, or
+/// 0 if the screens don't overlap, such as after the screen was cleared.
+///
+/// The last line of the previous screen is ignored, as it may have been
+/// written to before scrolling.
+pub fn scrolled_lines(previous: &[String], current: &[String]) -> usize {
+    if previous.len() != current.len() {
+        return 0;
+    }
+    let len = previous.len().saturating_sub(1);
+    (0..len)
+        .find(|&scrolled| {
+            let overlap = &previous[scrolled..len];
+            overlap == &current[..len - scrolled]
+                && (scrolled == 0 || overlap.iter().any(|line| !line.is_empty()))
+        })
+        .unwrap_or(0)
+}
+
+/// Returns the output that repaints a terminal with `screen_lines` lines with
+/// the given snapshot, after scrolling `scrolled_lines` of its lines into the
+/// scrollback.
+pub(crate) fn repaint(
+    snapshot: &ScreenSnapshot,
+    scrolled_lines: usize,
+    screen_lines: usize,
+) -> String {
+    // Show the bottom of the snapshot when it has more lines than the terminal.
+    let skipped_lines = snapshot.lines.len().saturating_sub(screen_lines);
+
+    // Disable line wrapping, so that lines longer than the terminal is wide
+    // don't move the following lines.
+    let mut output = String::from("\x1b[?7l");
+    if scrolled_lines > 0 {
+        write!(output, "\x1b[{screen_lines};1H").ok();
+        output.push_str(&"\n".repeat(scrolled_lines.min(screen_lines)));
+    }
+    for (ix, line) in snapshot.lines[skipped_lines..].iter().enumerate() {
+        write!(output, "\x1b[{};1H\x1b[0m{line}\x1b[0m\x1b[K", ix + 1).ok();
+    }
+    output.push_str("\x1b[J");
+    write!(
+        output,
+        "\x1b[{};{}H",
+        snapshot.cursor_line.saturating_sub(skipped_lines) + 1,
+        snapshot.cursor_column + 1
+    )
+    .ok();
+    output
+}
+
+fn render_row(row: &Row<Cell>, columns: usize) -> String {
+    let cells = (0..columns).map(|column| &row[Column(column)]);
+    let len = cells
+        .clone()
+        .rposition(|cell| !is_blank(cell))
+        .map_or(0, |ix| ix + 1);
+
+    let mut output = String::new();
+    let mut style = None;
+    for cell in cells.take(len) {
+        if cell
+            .flags
+            .intersects(Flags::WIDE_CHAR_SPACER | Flags::LEADING_WIDE_CHAR_SPACER)
+        {
+            continue;
+        }
+        let cell_style = (cell.fg, cell.bg, cell.flags & STYLE_FLAGS);
+        if style != Some(cell_style) {
+            write_sgr(&mut output, cell);
+            style = Some(cell_style);
+        }
+        output.push(cell.c);
+        if let Some(zerowidth) = cell.zerowidth() {
+            output.extend(zerowidth);
+        }
+    }
+    if style.is_some_and(|style| style != DEFAULT_STYLE) {
+        output.push_str("\x1b[0m");
+    }
+    output
+}
+
+const STYLE_FLAGS: Flags = Flags::BOLD
+    .union(Flags::DIM)
+    .union(Flags::ITALIC)
+    .union(Flags::ALL_UNDERLINES)
+    .union(Flags::INVERSE)
+    .union(Flags::HIDDEN)
+    .union(Flags::STRIKEOUT);
+
+const DEFAULT_STYLE: (Color, Color, Flags) = (
+    Color::Named(NamedColor::Foreground),
+    Color::Named(NamedColor::Background),
+    Flags::empty(),
+);
+
+fn is_blank(cell: &Cell) -> bool {
+    cell.c == ' '
+        && cell.bg == Color::Named(NamedColor::Background)
+        && !cell.flags.intersects(STYLE_FLAGS)
+}
+
+fn write_sgr(output: &mut String, cell: &Cell) {
+    output.push_str("\x1b[0");
+    for (flag, code) in [
+        (Flags::BOLD, 1),
+        (Flags::DIM, 2),
+        (Flags::ITALIC, 3),
+        (Flags::ALL_UNDERLINES, 4),
+        (Flags::INVERSE, 7),
+        (Flags::HIDDEN, 8),
+        (Flags::STRIKEOUT, 9),
+    ] {
+        if cell.flags.intersects(flag) {
+            write!(output, ";{code}").ok();
+        }
+    }
+    write_color(output, cell.fg, 30, 90, 38);
+    write_color(output, cell.bg, 40, 100, 48);
+    output.push('m');
+}
+
+fn write_color(output: &mut String, color: Color, base: u8, bright_base: u8, extended: u8) {
+    match color {
+        Color::Named(color) if (color as usize) < 8 => {
+            write!(output, ";{}", base as usize + color as usize).ok();
+        }
+        Color::Named(color) if (color as usize) < 16 => {
+            write!(output, ";{}", bright_base as usize + color as usize - 8).ok();
+        }
+        Color::Named(_) => {}
+        Color::Indexed(index) => {
+            write!(output, ";{extended};5;{index}").ok();
+        }
+        Color::Spec(rgb) => {
+            write!(output, ";{extended};2;{};{};{}", rgb.r, rgb.g, rgb.b).ok();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        TerminalBuilder,
+        terminal_settings::{AlternateScroll, CursorShape},
+    };
+    use gpui::{AppContext as _, TestAppContext};
+
+    fn lines(lines: &[&str]) -> Vec<String> {
+        lines.iter().map(|line| line.to_string()).collect()
+    }
+
+    #[test]
+    fn test_scrolled_lines() {
+        let previous = lines(&["a", "b", "c", "d"]);
+        assert_eq!(scrolled_lines(&previous, &previous), 0);
+        assert_eq!(scrolled_lines(&previous, &lines(&["a", "b", "c", "e"])), 0);
+        assert_eq!(scrolled_lines(&previous, &lines(&["b", "c", "d", "e"])), 1);
+        assert_eq!(scrolled_lines(&previous, &lines(&["c", "d", "e", "f"])), 2);
+        assert_eq!(scrolled_lines(&previous, &lines(&["x", "y", "z", "w"])), 0);
+
+        // Lines written below the cursor of a screen that isn't full don't
+        // count as scrolling, even though the empty lines match.
+        let previous = lines(&["a", "b", "", "", ""]);
+        assert_eq!(
+            scrolled_lines(&previous, &lines(&["a", "b", "c", "", ""])),
+            0
+        );
+    }
+
+    #[gpui::test]
+    fn test_mirror_screen(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = settings::SettingsStore::test(cx);
+            cx.set_global(settings_store);
+        });
+
+        let new_terminal = |cx: &mut TestAppContext| {
+            cx.new(|cx| {
+                TerminalBuilder::new_display_only(
+                    CursorShape::default(),
+                    AlternateScroll::On,
+                    None,
+                    0,
+                )
+                .unwrap()
+                .subscribe(cx)
+            })
+        };
+        let source = new_terminal(cx);
+        let mirror = new_terminal(cx);
+
+        source.update(cx, |terminal, cx| {
+            terminal.write_output(b"$ cargo test\n\x1b[1;32mok\x1b[0m done\n$ ", cx)
+        });
+        let snapshot = source.read_with(cx, |terminal, _| terminal.screen_snapshot());
+        assert_eq!(snapshot.lines[0], "\x1b[0m$ cargo test");
+        assert_eq!(snapshot.lines[1], "\x1b[0;1;32mok\x1b[0m done");
+        assert_eq!((snapshot.cursor_line, snapshot.cursor_column), (2, 2));
+
+        mirror.update(cx, |terminal, cx| {
+            terminal.write_screen_snapshot(&snapshot, 0, cx)
+        });
+        cx.run_until_parked();
+        assert_eq!(
+            mirror.read_with(cx, |terminal, _| terminal.screen_snapshot()),
+            snapshot
+        );
+    }
+}
@@ -1,8 +1,10 @@
 pub mod mappings;
 
 pub use alacritty_terminal;
+pub use shared_screen::{ScreenSnapshot, scrolled_lines};
 
 mod pty_info;
+mod shared_screen;
 mod terminal_hyperlinks;
 pub mod terminal_settings;
 
@@ -396,6 +398,7 @@ impl TerminalBuilder {
             },
             child_exited: None,
             event_loop_task: Task::ready(Ok(())),
+            input_forwarder: None,
         };
 
         Ok(TerminalBuilder {
@@ -627,6 +630,7 @@ impl TerminalBuilder {
                 },
                 child_exited: None,
                 event_loop_task: Task::ready(Ok(())),
+                input_forwarder: None,
             };
 
             if !activation_script.is_empty() && no_task {
@@ -849,6 +853,9 @@ pub struct Terminal {
     activation_script: Vec<String>,
     child_exited: Option<ExitStatus>,
     event_loop_task: Task<Result<(), anyhow::Error>>,
+    /// Where the input of a display-only terminal is sent, such as to the
+    /// host of a terminal shared with collaborators.
+    input_forwarder: Option<UnboundedSender<Vec<u8>>>,
 }
 
 struct CopyTemplate {
@@ -1293,6 +1300,38 @@ impl Terminal {
         cx.emit(Event::Wakeup);
     }
 
+    /// Sets where the input of a display-only terminal is sent. Without an input
+    /// forwarder, the input of a display-only terminal is discarded.
+    pub fn set_input_forwarder(&mut self, input_forwarder: Option<UnboundedSender<Vec<u8>>>) {
+        self.input_forwarder = input_forwarder;
+    }
+
+    pub fn set_title_override(&mut self, title_override: Option<String>, cx: &mut Context<Self>) {
+        if self.title_override != title_override {
+            self.title_override = title_override;
+            cx.emit(Event::TitleChanged);
+        }
+    }
+
+    /// Returns the visible screen of the terminal, to mirror it in a display-only terminal.
+    pub fn screen_snapshot(&self) -> ScreenSnapshot {
+        shared_screen::snapshot(&self.term.lock_unfair())
+    }
+
+    /// Repaints a display-only terminal with the screen of another terminal,
+    /// after scrolling the top `scrolled_lines` lines of the previous screen
+    /// into the scrollback.
+    pub fn write_screen_snapshot(
+        &mut self,
+        snapshot: &ScreenSnapshot,
+        scrolled_lines: usize,
+        cx: &mut Context<Self>,
+    ) {
+        let screen_lines = self.term.lock_unfair().screen_lines();
+        let output = shared_screen::repaint(snapshot, scrolled_lines, screen_lines);
+        self.write_output(output.as_bytes(), cx);
+    }
+
     pub fn total_lines(&self) -> usize {
         self.term.lock_unfair().total_lines()
     }
@@ -1410,9 +1449,13 @@ impl Terminal {
     }
 
     /// Write the Input payload to the PTY, if applicable.
-    /// (This is a no-op for display-only terminals.)
+    /// (Display-only terminals forward it to their input forwarder, if any.)
     fn write_to_pty(&self, input: impl Into<Cow<'static, [u8]>>) {
-        if let TerminalType::Pty { pty_tx, .. } = &self.terminal_type {
+        if let Some(input_forwarder) = &self.input_forwarder {
+            input_forwarder
+                .unbounded_send(input.into().into_owned())
+                .ok();
+        } else if let TerminalType::Pty { pty_tx, .. } = &self.terminal_type {
             let input = input.into();
             if log::log_enabled!(log::Level::Debug) {
                 if let Ok(str) = str::from_utf8(&input) {
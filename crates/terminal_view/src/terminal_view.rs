@@ -13,7 +13,7 @@ use gpui::{
     ScrollWheelEvent, Styled, Subscription, Task, WeakEntity, actions, anchored, deferred, div,
 };
 use persistence::TERMINAL_DB;
-use project::{Project, search::SearchQuery};
+use project::{Project, TerminalSharing, search::SearchQuery};
 use schemars::JsonSchema;
 use task::TaskId;
 use terminal::{
@@ -85,7 +85,13 @@ actions!(
     terminal,
     [
         /// Reruns the last executed task in the terminal.
-        RerunTask
+        RerunTask,
+        /// Shares the terminal with the collaborators in the project, who can watch its output.
+        ShareReadOnly,
+        /// Shares the terminal with the collaborators in the project, who can also type into it.
+        ShareReadWrite,
+        /// Stops sharing the terminal with the collaborators in the project.
+        StopSharing
     ]
 );
 
@@ -95,8 +101,22 @@ pub fn init(cx: &mut App) {
 
     register_serializable_item::<TerminalView>(cx);
 
-    cx.observe_new(|workspace: &mut Workspace, _window, _cx| {
+    cx.observe_new(|workspace: &mut Workspace, window, cx| {
         workspace.register_action(TerminalView::deploy);
+
+        let Some(window) = window else {
+            return;
+        };
+        cx.subscribe_in(
+            workspace.project(),
+            window,
+            |workspace, _, event, window, cx| {
+                if let project::Event::SharedTerminalOpened(terminal) = event {
+                    TerminalView::open_shared_terminal(workspace, terminal.clone(), window, cx);
+                }
+            },
+        )
+        .detach();
     })
     .detach();
     SlashCommandRegistry::global(cx).register_command(TerminalSlashCommand, true);
@@ -387,6 +407,7 @@ impl TerminalView {
             .upgrade()
             .and_then(|workspace| workspace.read(cx).panel::<TerminalPanel>(cx))
             .is_some_and(|terminal_panel| terminal_panel.read(cx).assistant_enabled());
+        let sharing = self.sharing(cx);
         let context_menu = ContextMenu::build(window, cx, |menu, _, _| {
             menu.context(self.focus_handle.clone())
                 .action("New Terminal", Box::new(NewTerminal))
@@ -399,6 +420,23 @@ impl TerminalView {
                     menu.separator()
                         .action("Inline Assist", Box::new(InlineAssist::default()))
                 })
+                .when_some(sharing, |menu, sharing| {
+                    let menu = menu.separator();
+                    match sharing {
+                        None => menu
+                            .action("Share with Collaborators", Box::new(ShareReadOnly))
+                            .action(
+                                "Share with Collaborators (Read-Write)",
+                                Box::new(ShareReadWrite),
+                            ),
+                        Some(TerminalSharing::ReadOnly) => menu
+                            .action("Let Collaborators Type", Box::new(ShareReadWrite))
+                            .action("Stop Sharing", Box::new(StopSharing)),
+                        Some(TerminalSharing::ReadWrite) => menu
+                            .action("Make Read-Only for Collaborators", Box::new(ShareReadOnly))
+                            .action("Stop Sharing", Box::new(StopSharing)),
+                    }
+                })
                 .separator()
                 .action(
                     "Close Terminal Tab",
@@ -490,6 +528,65 @@ impl TerminalView {
         cx.notify();
     }
 
+    /// Opens a terminal that the host of the project shared, without focusing it.
+    fn open_shared_terminal(
+        workspace: &mut Workspace,
+        terminal: Entity<Terminal>,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        let terminal_view = cx.new(|cx| {
+            TerminalView::new(
+                terminal,
+                workspace.weak_handle(),
+                workspace.database_id(),
+                workspace.project().downgrade(),
+                window,
+                cx,
+            )
+        });
+        workspace.active_pane().update(cx, |pane, cx| {
+            pane.add_item(Box::new(terminal_view), false, false, None, window, cx)
+        });
+    }
+
+    fn share_read_only(&mut self, _: &ShareReadOnly, _: &mut Window, cx: &mut Context<Self>) {
+        self.share(TerminalSharing::ReadOnly, cx);
+    }
+
+    fn share_read_write(&mut self, _: &ShareReadWrite, _: &mut Window, cx: &mut Context<Self>) {
+        self.share(TerminalSharing::ReadWrite, cx);
+    }
+
+    fn share(&mut self, sharing: TerminalSharing, cx: &mut Context<Self>) {
+        if let Some(project) = self.project.upgrade() {
+            project
+                .update(cx, |project, cx| {
+                    project.share_terminal(&self.terminal, sharing, cx)
+                })
+                .log_err();
+            cx.notify();
+        }
+    }
+
+    fn stop_sharing(&mut self, _: &StopSharing, _: &mut Window, cx: &mut Context<Self>) {
+        if let Some(project) = self.project.upgrade() {
+            project.update(cx, |project, cx| {
+                project.unshare_terminal(&self.terminal, cx)
+            });
+            cx.notify();
+        }
+    }
+
+    /// Returns how the terminal is shared with collaborators, if this is the
+    /// host of a shared project.
+    fn sharing(&self, cx: &App) -> Option<Option<TerminalSharing>> {
+        let project = self.project.upgrade()?;
+        let project = project.read(cx);
+        (project.is_shared() && !project.is_via_collab())
+            .then(|| project.terminal_sharing(&self.terminal))
+    }
+
     fn rerun_task(&mut self, _: &RerunTask, window: &mut Window, cx: &mut Context<Self>) {
         let task = self
             .terminal
@@ -1097,6 +1194,9 @@ impl Render for TerminalView {
             .on_action(cx.listener(TerminalView::show_character_palette))
             .on_action(cx.listener(TerminalView::select_all))
             .on_action(cx.listener(TerminalView::rerun_task))
+            .on_action(cx.listener(TerminalView::share_read_only))
+            .on_action(cx.listener(TerminalView::share_read_write))
+            .on_action(cx.listener(TerminalView::stop_sharing))
             .on_key_down(cx.listener(Self::key_down))
             .on_mouse_down(
                 MouseButton::Right,
@@ -1181,6 +1281,7 @@ impl Item for TerminalView {
     fn tab_content(&self, params: TabContentParams, _window: &Window, cx: &App) -> AnyElement {
         let terminal = self.terminal().read(cx);
         let title = terminal.title(true);
+        let is_shared = self.sharing(cx).flatten().is_some();
 
         let (icon, icon_color, rerun_button) = match terminal.task() {
             Some(terminal_task) => match &terminal_task.status {
@@ -1230,6 +1331,13 @@ impl Item for TerminalView {
                     }),
             )
             .child(Label::new(title).color(params.text_color()))
+            .when(is_shared, |this| {
+                this.child(
+                    Icon::new(IconName::UserGroup)
+                        .size(IconSize::XSmall)
+                        .color(Color::Muted),
+                )
+            })
             .into_any()
     }
 
@@ -364,6 +364,7 @@ impl TitleBar {
             RemoteConnectionOptions::Docker(_dev_container_connection) => {
                 (None, "Dev Container", IconName::Box)
             }
+            RemoteConnectionOptions::Command(_) => (None, "Remote Project", IconName::Terminal),
         };
 
         let nickname = nickname.unwrap_or_else(|| host.clone());
@@ -379,6 +380,10 @@ impl TitleBar {
                 Color::Warning,
                 format!("Lost connection to {host}. Reconnecting..."),
             ),
+            remote::ConnectionState::Offline => (
+                Color::Warning,
+                format!("Offline from {host}. Edits are kept locally until reconnected"),
+            ),
             remote::ConnectionState::Disconnected => {
                 (Color::Error, format!("Disconnected from {host}"))
             }
@@ -389,6 +394,7 @@ impl TitleBar {
             remote::ConnectionState::Connected => Color::Default,
             remote::ConnectionState::HeartbeatMissed => Color::Warning,
             remote::ConnectionState::Reconnecting => Color::Warning,
+            remote::ConnectionState::Offline => Color::Warning,
             remote::ConnectionState::Disconnected => Color::Error,
         };
 
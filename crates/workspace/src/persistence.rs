@@ -26,7 +26,8 @@ use project::{
 use language::{LanguageName, Toolchain, ToolchainScope};
 use project::WorktreeId;
 use remote::{
-    DockerConnectionOptions, RemoteConnectionOptions, SshConnectionOptions, WslConnectionOptions,
+    CommandConnectionOptions, DockerConnectionOptions, RemoteConnectionOptions,
+    SshConnectionOptions, WslConnectionOptions,
 };
 use serde::{Deserialize, Serialize};
 use sqlez::{
@@ -190,6 +191,18 @@ pub async fn write_default_window_bounds(
     Ok(())
 }
 
+/// The wrapper command of a [`CommandConnectionOptions`], as stored in the
+/// `command` column of `remote_connections`.
+#[derive(Serialize, Deserialize)]
+struct RemoteCommandJson {
+    program: String,
+    args: Vec<String>,
+    #[serde(default)]
+    tty_args: Option<Vec<String>>,
+    #[serde(default)]
+    env: BTreeMap<String, String>,
+}
+
 #[derive(Serialize, Deserialize)]
 pub enum WindowBoundsJson {
     Windowed {
@@ -845,6 +858,9 @@ impl Domain for WorkspaceDb {
                 host_name TEXT
             ) STRICT;
         ),
+        sql!(
+            ALTER TABLE remote_connections ADD COLUMN command TEXT;
+        ),
     ];
 
     // Allow recovering from bad migration that was initially shipped to nightly
@@ -1270,6 +1286,7 @@ impl WorkspaceDb {
         let mut distro = None;
         let mut name = None;
         let mut container_id = None;
+        let mut command = None;
         match options {
             RemoteConnectionOptions::Ssh(options) => {
                 kind = RemoteConnectionKind::Ssh;
@@ -1287,6 +1304,16 @@ impl WorkspaceDb {
                 container_id = Some(options.container_id);
                 name = Some(options.name);
             }
+            RemoteConnectionOptions::Command(options) => {
+                kind = RemoteConnectionKind::Command;
+                name = Some(options.name);
+                command = Some(serde_json::to_string(&RemoteCommandJson {
+                    program: options.program,
+                    args: options.args,
+                    tty_args: options.tty_args,
+                    env: options.env,
+                })?);
+            }
         }
         Self::get_or_create_remote_connection_query(
             this,
@@ -1297,6 +1324,7 @@ impl WorkspaceDb {
             distro,
             name,
             container_id,
+            command,
         )
     }
 
@@ -1309,6 +1337,7 @@ impl WorkspaceDb {
         distro: Option<String>,
         name: Option<String>,
         container_id: Option<String>,
+        command: Option<String>,
     ) -> Result<RemoteConnectionId> {
         if let Some(id) = this.select_row_bound(sql!(
             SELECT id
@@ -1320,7 +1349,8 @@ impl WorkspaceDb {
                 user IS ? AND
                 distro IS ? AND
                 name IS ? AND
-                container_id IS ?
+                container_id IS ? AND
+                command IS ?
             LIMIT 1
         ))?((
             kind.serialize(),
@@ -1330,6 +1360,7 @@ impl WorkspaceDb {
             distro.clone(),
             name.clone(),
             container_id.clone(),
+            command.clone(),
         ))? {
             Ok(RemoteConnectionId(id))
         } else {
@@ -1341,8 +1372,9 @@ impl WorkspaceDb {
                     user,
                     distro,
                     name,
-                    container_id
-                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+                    container_id,
+                    command
+                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
                 RETURNING id
             ))?((
                 kind.serialize(),
@@ -1352,6 +1384,7 @@ impl WorkspaceDb {
                 distro,
                 name,
                 container_id,
+                command,
             ))?
             .context("failed to insert remote project")?;
             Ok(RemoteConnectionId(id))
@@ -1435,25 +1468,28 @@ impl WorkspaceDb {
     fn remote_connections(&self) -> Result<HashMap<RemoteConnectionId, RemoteConnectionOptions>> {
         Ok(self.select(sql!(
             SELECT
-                id, kind, host, port, user, distro, container_id, name
+                id, kind, host, port, user, distro, container_id, name, command
             FROM
                 remote_connections
         ))?()?
         .into_iter()
-        .filter_map(|(id, kind, host, port, user, distro, container_id, name)| {
-            Some((
-                RemoteConnectionId(id),
-                Self::remote_connection_from_row(
-                    kind,
-                    host,
-                    port,
-                    user,
-                    distro,
-                    container_id,
-                    name,
-                )?,
-            ))
-        })
+        .filter_map(
+            |(id, kind, host, port, user, distro, container_id, name, command)| {
+                Some((
+                    RemoteConnectionId(id),
+                    Self::remote_connection_from_row(
+                        kind,
+                        host,
+                        port,
+                        user,
+                        distro,
+                        container_id,
+                        name,
+                        command,
+                    )?,
+                ))
+            },
+        )
         .collect())
     }
 
@@ -1461,14 +1497,24 @@ impl WorkspaceDb {
         &self,
         id: RemoteConnectionId,
     ) -> Result<RemoteConnectionOptions> {
-        let (kind, host, port, user, distro, container_id, name) = self.select_row_bound(sql!(
-            SELECT kind, host, port, user, distro, container_id, name
-            FROM remote_connections
-            WHERE id = ?
-        ))?(id.0)?
+        let (kind, host, port, user, distro, container_id, name, command) = self
+            .select_row_bound(sql!(
+                SELECT kind, host, port, user, distro, container_id, name, command
+                FROM remote_connections
+                WHERE id = ?
+            ))?(id.0)?
         .context("no such remote connection")?;
-        Self::remote_connection_from_row(kind, host, port, user, distro, container_id, name)
-            .context("invalid remote_connection row")
+        Self::remote_connection_from_row(
+            kind,
+            host,
+            port,
+            user,
+            distro,
+            container_id,
+            name,
+            command,
+        )
+        .context("invalid remote_connection row")
     }
 
     fn remote_connection_from_row(
@@ -1479,6 +1525,7 @@ impl WorkspaceDb {
         distro: Option<String>,
         container_id: Option<String>,
         name: Option<String>,
+        command: Option<String>,
     ) -> Option<RemoteConnectionOptions> {
         match RemoteConnectionKind::deserialize(&kind)? {
             RemoteConnectionKind::Wsl => Some(RemoteConnectionOptions::Wsl(WslConnectionOptions {
@@ -1498,6 +1545,17 @@ impl WorkspaceDb {
                     upload_binary_over_docker_exec: false,
                 }))
             }
+            RemoteConnectionKind::Command => {
+                let command: RemoteCommandJson = serde_json::from_str(&command?).log_err()?;
+                Some(RemoteConnectionOptions::Command(CommandConnectionOptions {
+                    name: name?,
+                    program: command.program,
+                    args: command.args,
+                    tty_args: command.tty_args,
+                    env: command.env,
+                    upload_binary_over_exec: false,
+                }))
+            }
         }
     }
 
@@ -3070,6 +3128,49 @@ mod tests {
         assert_ne!(connection_id, different_connection);
     }
 
+    #[gpui::test]
+    async fn test_get_or_create_command_connection() {
+        let db = WorkspaceDb::open_test_db("test_get_or_create_command_connection").await;
+
+        let options = RemoteConnectionOptions::Command(CommandConnectionOptions {
+            name: "my-pod".to_string(),
+            program: "kubectl".to_string(),
+            args: vec!["exec".into(), "-i".into(), "my-pod".into(), "--".into()],
+            tty_args: Some(vec![
+                "exec".into(),
+                "-it".into(),
+                "my-pod".into(),
+                "--".into(),
+            ]),
+            env: BTreeMap::from_iter([("KUBECONFIG".to_string(), "/tmp/config".to_string())]),
+            upload_binary_over_exec: false,
+        });
+
+        let connection_id = db
+            .get_or_create_remote_connection(options.clone())
+            .await
+            .unwrap();
+        let same_connection = db
+            .get_or_create_remote_connection(options.clone())
+            .await
+            .unwrap();
+        assert_eq!(connection_id, same_connection);
+        assert_eq!(db.remote_connection(connection_id).unwrap(), options);
+
+        let different_connection = db
+            .get_or_create_remote_connection(RemoteConnectionOptions::Command(
+                CommandConnectionOptions {
+                    name: "my-pod".to_string(),
+                    program: "kubectl".to_string(),
+                    args: vec!["exec".into(), "-i".into(), "other-pod".into(), "--".into()],
+                    ..Default::default()
+                },
+            ))
+            .await
+            .unwrap();
+        assert_ne!(connection_id, different_connection);
+    }
+
     #[gpui::test]
     async fn test_get_or_create_ssh_project_with_null_user() {
         let db = WorkspaceDb::open_test_db("test_get_or_create_ssh_project_with_null_user").await;
@@ -26,7 +26,8 @@ impl Workspace {
                 ConnectionState::Connecting
                 | ConnectionState::Disconnected
                 | ConnectionState::HeartbeatMissed
-                | ConnectionState::Reconnecting,
+                | ConnectionState::Reconnecting
+                | ConnectionState::Offline,
             ) => {
                 log::warn!("Cannot schedule tasks when disconnected from a remote host");
                 return;
@@ -649,10 +649,13 @@ type BuildProjectItemForPathFn =
 struct ProjectItemRegistry {
     build_project_item_fns_by_type: HashMap<TypeId, BuildProjectItemFn>,
     build_project_item_for_path_fns: Vec<BuildProjectItemForPathFn>,
+    /// Tried after all of `build_project_item_for_path_fns`, for the paths
+    /// that the other items didn't open.
+    build_fallback_project_item_for_path_fns: Vec<BuildProjectItemForPathFn>,
 }
 
 impl ProjectItemRegistry {
-    fn register<T: ProjectItem>(&mut self) {
+    fn register<T: ProjectItem>(&mut self, fallback: bool) {
         self.build_project_item_fns_by_type.insert(
             TypeId::of::<T::Item>(),
             |item, project, pane, window, cx| {
@@ -661,8 +664,8 @@ impl ProjectItemRegistry {
                     as Box<dyn ItemHandle>
             },
         );
-        self.build_project_item_for_path_fns
-            .push(|project, project_path, window, cx| {
+        let build_project_item_for_path: BuildProjectItemForPathFn =
+            |project, project_path, window, cx| {
                 let project_path = project_path.clone();
                 let is_file = project
                     .read(cx)
@@ -699,6 +702,7 @@ impl ProjectItemRegistry {
                             ) as Box<_>;
                             Ok((project_entry_id, build_workspace_item))
                         }
+                        Err(e) if e.is::<project::UnsupportedProjectItem>() => Err(e),
                         Err(e) => {
                             log::warn!("Failed to open a project item: {e:#}");
                             if e.error_code() == ErrorCode::Internal {
@@ -726,7 +730,14 @@ impl ProjectItemRegistry {
                         }
                     }
                 }))
-            });
+            };
+        if fallback {
+            self.build_fallback_project_item_for_path_fns
+                .push(build_project_item_for_path);
+        } else {
+            self.build_project_item_for_path_fns
+                .push(build_project_item_for_path);
+        }
     }
 
     fn open_path(
@@ -736,15 +747,44 @@ impl ProjectItemRegistry {
         window: &mut Window,
         cx: &mut App,
     ) -> Task<Result<(Option<ProjectEntryId>, WorkspaceItemBuilder)>> {
-        let Some(open_project_item) = self
+        let mut open_project_item_fns = self
             .build_project_item_for_path_fns
             .iter()
             .rev()
+            .chain(self.build_fallback_project_item_for_path_fns.iter().rev());
+        let Some(open_project_item) = open_project_item_fns
+            .by_ref()
             .find_map(|open_project_item| open_project_item(project, path, window, cx))
         else {
             return Task::ready(Err(anyhow!("cannot open file {:?}", path.path)));
         };
-        open_project_item
+
+        // Items that can only tell whether they support the path once it has been read
+        // leave it to the items after them, such as to the fallback items.
+        let mut open_project_item_fns = open_project_item_fns
+            .copied()
+            .collect::<Vec<_>>()
+            .into_iter();
+        let project = project.clone();
+        let path = path.clone();
+        window.spawn(cx, async move |cx| {
+            let mut open_project_item = open_project_item;
+            loop {
+                match open_project_item.await {
+                    Err(e) if e.is::<project::UnsupportedProjectItem>() => {}
+                    result => return result,
+                }
+                open_project_item = cx
+                    .update(|window, cx| {
+                        open_project_item_fns
+                            .by_ref()
+                            .find_map(|open_project_item| {
+                                open_project_item(&project, &path, window, cx)
+                            })
+                    })?
+                    .with_context(|| format!("cannot open file {:?}", path.path))?;
+            }
+        })
     }
 
     fn build_item<T: project::ProjectItem>(
@@ -771,7 +811,16 @@ impl Global for ProjectItemRegistry {}
 /// items will get a chance to open the file, starting from the project item that
 /// was added last.
 pub fn register_project_item<I: ProjectItem>(cx: &mut App) {
-    cx.default_global::<ProjectItemRegistry>().register::<I>();
+    cx.default_global::<ProjectItemRegistry>()
+        .register::<I>(false);
+}
+
+/// Registers a [ProjectItem] for the app that only gets a chance to open a file
+/// after all the items registered with [register_project_item] didn't open it,
+/// such as when the file isn't text.
+pub fn register_fallback_project_item<I: ProjectItem>(cx: &mut App) {
+    cx.default_global::<ProjectItemRegistry>()
+        .register::<I>(true);
 }
 
 #[derive(Default)]
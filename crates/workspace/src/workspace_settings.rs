@@ -128,6 +128,7 @@ pub struct StatusBarSettings {
     pub active_language_button: bool,
     pub cursor_position_button: bool,
     pub line_endings_button: bool,
+    pub encoding_button: bool,
 }
 
 impl Settings for StatusBarSettings {
@@ -138,6 +139,7 @@ impl Settings for StatusBarSettings {
             active_language_button: status_bar.active_language_button.unwrap(),
             cursor_position_button: status_bar.cursor_position_button.unwrap(),
             line_endings_button: status_bar.line_endings_button.unwrap(),
+            encoding_button: status_bar.encoding_button.unwrap(),
         }
     }
 }
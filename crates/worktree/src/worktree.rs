@@ -5,7 +5,6 @@ mod worktree_tests;
 
 use ::ignore::gitignore::{Gitignore, GitignoreBuilder};
 use anyhow::{Context as _, Result, anyhow};
-use chardetng::EncodingDetector;
 use clock::ReplicaId;
 use collections::{HashMap, HashSet, VecDeque};
 use encoding_rs::Encoding;
@@ -39,7 +38,7 @@ use postage::{
     watch,
 };
 use rpc::{
-    AnyProtoClient,
+    AnyProtoClient, ErrorCode, ErrorCodeExt as _,
     proto::{self, split_worktree_update},
 };
 pub use settings::WorktreeId;
@@ -1361,7 +1360,13 @@ impl LocalWorktree {
             }
 
             let content = fs.load_bytes(&abs_path).await?;
-            let (text, encoding, has_bom) = decode_byte(content);
+            if language::encoding::is_binary(&content) {
+                return Err(ErrorCode::BinaryFile
+                    .message(format!("{abs_path:?} is a binary file"))
+                    .anyhow());
+            }
+            let (encoding, _) = language::encoding::detect_encoding(&content);
+            let (text, has_bom) = language::encoding::decode(content, encoding);
 
             let worktree = this.upgrade().context("worktree was dropped")?;
             let file = match entry.await? {
@@ -1489,20 +1494,6 @@ impl LocalWorktree {
             let fs = fs.clone();
             let abs_path = abs_path.clone();
             async move {
-                let bom_bytes = if has_bom {
-                    if encoding == encoding_rs::UTF_16LE {
-                        vec![0xFF, 0xFE]
-                    } else if encoding == encoding_rs::UTF_16BE {
-                        vec![0xFE, 0xFF]
-                    } else if encoding == encoding_rs::UTF_8 {
-                        vec![0xEF, 0xBB, 0xBF]
-                    } else {
-                        vec![]
-                    }
-                } else {
-                    vec![]
-                };
-
                 // For UTF-8, use the optimized `fs.save` which writes Rope chunks directly to disk
                 // without allocating a contiguous string.
                 if encoding == encoding_rs::UTF_8 && !has_bom {
@@ -1520,15 +1511,7 @@ impl LocalWorktree {
                     LineEnding::Windows => text_string.replace('\n', "\r\n"),
                 };
 
-                let (cow, _, _) = encoding.encode(&normalized_text);
-                let bytes = if !bom_bytes.is_empty() {
-                    let mut bytes = bom_bytes;
-                    bytes.extend_from_slice(&cow);
-                    bytes.into()
-                } else {
-                    cow
-                };
-
+                let bytes = language::encoding::encode(&normalized_text, encoding, has_bom)?;
                 fs.write(&abs_path, &bytes).await
             }
         });
@@ -5841,40 +5824,3 @@ impl fs::Watcher for NullWatcher {
         Ok(())
     }
 }
-
-fn decode_byte(bytes: Vec<u8>) -> (String, &'static Encoding, bool) {
-    // check BOM
-    if let Some((encoding, _bom_len)) = Encoding::for_bom(&bytes) {
-        let (cow, _) = encoding.decode_with_bom_removal(&bytes);
-        return (cow.into_owned(), encoding, true);
-    }
-
-    fn detect_encoding(bytes: Vec<u8>) -> (String, &'static Encoding) {
-        let mut detector = EncodingDetector::new();
-        detector.feed(&bytes, true);
-
-        let encoding = detector.guess(None, true); // Use None for TLD hint to ensure neutral detection logic.
-
-        let (cow, _, _) = encoding.decode(&bytes);
-        (cow.into_owned(), encoding)
-    }
-
-    match String::from_utf8(bytes) {
-        Ok(text) => {
-            // ISO-2022-JP (and other ISO-2022 variants) consists entirely of 7-bit ASCII bytes,
-            // so it is valid UTF-8. However, it contains escape sequences starting with '\x1b'.
-            // If we find an escape character, we double-check the encoding to prevent
-            // displaying raw escape sequences instead of the correct characters.
-            if text.contains('\x1b') {
-                let (s, enc) = detect_encoding(text.into_bytes());
-                (s, enc, false)
-            } else {
-                (text, encoding_rs::UTF_8, false)
-            }
-        }
-        Err(e) => {
-            let (s, enc) = detect_encoding(e.into_bytes());
-            (s, enc, false)
-        }
-    }
-}
@@ -613,6 +613,12 @@ pub fn main() {
         load_embedded_fonts(cx);
 
         editor::init(cx);
+        large_file_viewer::init(cx);
+        hex_editor::init(cx);
+        bookmarks::init(cx);
+        spell_check::init(app_state.fs.clone(), cx);
+        keyboard_macros::init(cx);
+        multi_cursor_tools::init(cx);
         image_viewer::init(cx);
         repl::notebook::init(cx);
         diagnostics::init(cx);
@@ -637,6 +643,7 @@ pub fn main() {
         journal::init(app_state.clone(), cx);
         language_selector::init(cx);
         line_ending_selector::init(cx);
+        encoding_selector::init(cx);
         toolchain_selector::init(cx);
         theme_selector::init(cx);
         settings_profile_selector::init(cx);
@@ -730,15 +737,29 @@ pub fn main() {
             .map(|chunk| [chunk[0].clone(), chunk[1].clone()])
             .collect();
 
+        let merge_paths: Vec<[String; 4]> = args
+            .merge
+            .chunks(4)
+            .map(|chunk| {
+                [
+                    chunk[0].clone(),
+                    chunk[1].clone(),
+                    chunk[2].clone(),
+                    chunk[3].clone(),
+                ]
+            })
+            .collect();
+
         #[cfg(target_os = "windows")]
         let wsl = args.wsl;
         #[cfg(not(target_os = "windows"))]
         let wsl = None;
 
-        if !urls.is_empty() || !diff_paths.is_empty() {
+        if !urls.is_empty() || !diff_paths.is_empty() || !merge_paths.is_empty() {
             open_listener.open(RawOpenRequest {
                 urls,
                 diff_paths,
+                merge_paths,
                 wsl,
             })
         }
@@ -900,6 +921,7 @@ fn handle_open_request(request: OpenRequest, app_state: Arc<AppState>, cx: &mut
                     let (workspace, _results) = open_paths_with_positions(
                         &paths_with_position,
                         &[],
+                        &[],
                         app_state,
                         workspace::OpenOptions::default(),
                         cx,
@@ -953,7 +975,10 @@ fn handle_open_request(request: OpenRequest, app_state: Arc<AppState>, cx: &mut
     }
 
     let mut task = None;
-    if !request.open_paths.is_empty() || !request.diff_paths.is_empty() {
+    if !request.open_paths.is_empty()
+        || !request.diff_paths.is_empty()
+        || !request.merge_paths.is_empty()
+    {
         let app_state = app_state.clone();
         task = Some(cx.spawn(async move |cx| {
             let paths_with_position =
@@ -961,6 +986,7 @@ fn handle_open_request(request: OpenRequest, app_state: Arc<AppState>, cx: &mut
             let (_window, results) = open_paths_with_positions(
                 &paths_with_position,
                 &request.diff_paths,
+                &request.merge_paths,
                 app_state,
                 workspace::OpenOptions::default(),
                 cx,
@@ -1320,6 +1346,11 @@ struct Args {
     #[arg(long, action = clap::ArgAction::Append, num_args = 2, value_names = ["OLD_PATH", "NEW_PATH"])]
     diff: Vec<String>,
 
+    /// Three-way merges a base file with the changes of two other files, and
+    /// writes the result to an output file. Can be specified multiple times.
+    #[arg(long, action = clap::ArgAction::Append, num_args = 4, value_names = ["BASE", "LOCAL", "REMOTE", "OUTPUT"])]
+    merge: Vec<String>,
+
     /// Sets a custom directory for all user data (e.g., database, extensions, logs).
     ///
     /// This overrides the default platform-specific data directory location.
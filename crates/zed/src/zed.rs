@@ -449,15 +449,26 @@ pub fn initialize_workspace(
             cx.new(|_| go_to_line::cursor_position::CursorPosition::new(workspace));
         let line_ending_indicator =
             cx.new(|_| line_ending_selector::LineEndingIndicator::default());
+        let encoding_indicator = cx.new(|_| encoding_selector::EncodingIndicator::default());
+        let remote_sync_indicator =
+            cx.new(|cx| recent_projects::RemoteSyncIndicator::new(workspace.project().clone(), cx));
+        let active_settings_profile = cx.new(|cx| {
+            settings_profile_selector::ActiveSettingsProfile::new(workspace.project().clone(), cx)
+        });
+        let keyboard_macro_recording = cx.new(keyboard_macros::RecordingIndicator::new);
         workspace.status_bar().update(cx, |status_bar, cx| {
             status_bar.add_left_item(search_button, window, cx);
             status_bar.add_left_item(lsp_button, window, cx);
             status_bar.add_left_item(diagnostic_summary, window, cx);
             status_bar.add_left_item(activity_indicator, window, cx);
+            status_bar.add_right_item(remote_sync_indicator, window, cx);
+            status_bar.add_right_item(keyboard_macro_recording, window, cx);
+            status_bar.add_right_item(active_settings_profile, window, cx);
             status_bar.add_right_item(edit_prediction_ui, window, cx);
             status_bar.add_right_item(active_buffer_language, window, cx);
             status_bar.add_right_item(active_toolchain_language, window, cx);
             status_bar.add_right_item(line_ending_indicator, window, cx);
+            status_bar.add_right_item(encoding_indicator, window, cx);
             status_bar.add_right_item(vim_mode_indicator, window, cx);
             status_bar.add_right_item(cursor_position, window, cx);
             status_bar.add_right_item(image_info, window, cx);
@@ -658,6 +669,12 @@ fn initialize_panels(
             workspace_handle.clone(),
             cx.clone(),
         );
+        let ports_panel = recent_projects::ports_panel::PortsPanel::load(
+            workspace_handle.clone(),
+            cx.clone(),
+        );
+        let bookmarks_panel =
+            bookmarks::BookmarksPanel::load(workspace_handle.clone(), cx.clone());
         let debug_panel = DebugPanel::load(workspace_handle.clone(), cx);
 
         async fn add_panel_when_ready(
@@ -682,6 +699,8 @@ fn initialize_panels(
             add_panel_when_ready(git_panel, workspace_handle.clone(), cx.clone()),
             add_panel_when_ready(channels_panel, workspace_handle.clone(), cx.clone()),
             add_panel_when_ready(notification_panel, workspace_handle.clone(), cx.clone()),
+            add_panel_when_ready(ports_panel, workspace_handle.clone(), cx.clone()),
+            add_panel_when_ready(bookmarks_panel, workspace_handle.clone(), cx.clone()),
             add_panel_when_ready(debug_panel, workspace_handle.clone(), cx.clone()),
             initialize_agent_panel(workspace_handle.clone(), prompt_builder, cx.clone()).map(|r| r.log_err()),
             initialize_agents_panel(workspace_handle, cx.clone()).map(|r| r.log_err())
@@ -4785,6 +4804,8 @@ mod tests {
                 "assistant",
                 "assistant2",
                 "auto_update",
+                "bookmarks",
+                "bookmarks_panel",
                 "branch_picker",
                 "bedrock",
                 "branches",
@@ -4804,15 +4825,18 @@ mod tests {
                 "diagnostics",
                 "edit_prediction",
                 "editor",
+                "encoding_selector",
                 "feedback",
                 "file_finder",
                 "git",
                 "git_onboarding",
                 "git_panel",
                 "go_to_line",
+                "hex_editor",
                 "icon_theme_selector",
                 "inline_assistant",
                 "journal",
+                "keyboard_macros",
                 "keymap_editor",
                 "keystroke_input",
                 "language_selector",
@@ -4821,6 +4845,7 @@ mod tests {
                 "lsp_tool",
                 "markdown",
                 "menu",
+                "multi_cursor_tools",
                 "notebook",
                 "notification_panel",
                 "onboarding",
@@ -4829,6 +4854,7 @@ mod tests {
                 "pane",
                 "panel",
                 "picker",
+                "ports_panel",
                 "project_panel",
                 "project_search",
                 "project_symbols",
@@ -4994,6 +5020,12 @@ mod tests {
                 copilot::copilot_chat::CopilotChatConfiguration::default(),
                 cx,
             );
+            large_file_viewer::init(cx);
+            hex_editor::init(cx);
+            bookmarks::init(cx);
+            spell_check::init(app_state.fs.clone(), cx);
+            keyboard_macros::init(cx);
+            multi_cursor_tools::init(cx);
             image_viewer::init(cx);
             language_model::init(app_state.client.clone(), cx);
             language_models::init(app_state.user_store.clone(), app_state.client.clone(), cx);
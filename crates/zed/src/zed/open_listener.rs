@@ -14,6 +14,7 @@ use futures::future;
 use futures::future::join_all;
 use futures::{FutureExt, SinkExt, StreamExt};
 use git_ui::file_diff_view::FileDiffView;
+use git_ui::merge_view::MergeView;
 use gpui::{App, AsyncApp, Global, WindowHandle};
 use language::Point;
 use onboarding::FIRST_OPEN;
@@ -36,6 +37,7 @@ pub struct OpenRequest {
     pub kind: Option<OpenRequestKind>,
     pub open_paths: Vec<String>,
     pub diff_paths: Vec<[String; 2]>,
+    pub merge_paths: Vec<[String; 4]>,
     pub open_channel_notes: Vec<(u64, Option<String>)>,
     pub join_channel: Option<u64>,
     pub remote_connection: Option<RemoteConnectionOptions>,
@@ -68,6 +70,7 @@ impl OpenRequest {
         let mut this = Self::default();
 
         this.diff_paths = request.diff_paths;
+        this.merge_paths = request.merge_paths;
         if let Some(wsl) = request.wsl {
             let (user, distro_name) = if let Some((user, distro)) = wsl.split_once('@') {
                 if user.is_empty() {
@@ -203,6 +206,7 @@ pub struct OpenListener(UnboundedSender<RawOpenRequest>);
 pub struct RawOpenRequest {
     pub urls: Vec<String>,
     pub diff_paths: Vec<[String; 2]>,
+    pub merge_paths: Vec<[String; 4]>,
     pub wsl: Option<String>,
 }
 
@@ -279,6 +283,7 @@ fn connect_to_cli(
 pub async fn open_paths_with_positions(
     path_positions: &[PathWithPosition],
     diff_paths: &[[String; 2]],
+    merge_paths: &[[String; 4]],
     app_state: Arc<AppState>,
     open_options: workspace::OpenOptions,
     cx: &mut AsyncApp,
@@ -303,19 +308,57 @@ pub async fn open_paths_with_positions(
         })
         .collect::<Vec<_>>();
 
+    let fs = app_state.fs.clone();
     let (workspace, mut items) = cx
         .update(|cx| workspace::open_paths(&paths, app_state, open_options, cx))?
         .await?;
 
     for diff_pair in diff_paths {
-        let old_path = Path::new(&diff_pair[0]).canonicalize()?;
-        let new_path = Path::new(&diff_pair[1]).canonicalize()?;
-        if let Ok(diff_view) = workspace.update(cx, |workspace, window, cx| {
+        let old_path = fs.canonicalize(Path::new(&diff_pair[0])).await?;
+        let new_path = fs.canonicalize(Path::new(&diff_pair[1])).await?;
+        let diff_view = match workspace.update(cx, |workspace, window, cx| {
             FileDiffView::open(old_path, new_path, workspace, window, cx)
-        }) && let Some(diff_view) = diff_view.await.log_err()
-        {
-            items.push(Some(Ok(Box::new(diff_view))))
-        }
+        }) {
+            Ok(diff_view) => diff_view.await,
+            Err(error) => Err(error),
+        };
+        items.push(Some(
+            diff_view
+                .map(|diff_view| Box::new(diff_view) as Box<dyn ItemHandle>)
+                .map_err(|error| {
+                    anyhow!(
+                        "error opening diff of {:?} and {:?}: {error}",
+                        diff_pair[0],
+                        diff_pair[1]
+                    )
+                }),
+        ));
+    }
+
+    for merge_paths in merge_paths {
+        let base_path = fs.canonicalize(Path::new(&merge_paths[0])).await?;
+        let local_path = fs.canonicalize(Path::new(&merge_paths[1])).await?;
+        let remote_path = fs.canonicalize(Path::new(&merge_paths[2])).await?;
+        let output_path = fs.canonicalize(Path::new(&merge_paths[3])).await?;
+        let merge_view = match workspace.update(cx, |workspace, window, cx| {
+            MergeView::open(
+                base_path,
+                local_path,
+                remote_path,
+                output_path,
+                workspace,
+                window,
+                cx,
+            )
+        }) {
+            Ok(merge_view) => merge_view.await,
+            Err(error) => Err(error),
+        };
+        items.push(Some(
+            merge_view
+                .map(|merge_view| Box::new(merge_view) as Box<dyn ItemHandle>)
+                .map_err(|error| anyhow!("error opening merge into {:?}: {error}", merge_paths[3])),
+        ));
     }
 
     for (item, path) in items.iter_mut().zip(&paths) {
@@ -354,6 +397,7 @@ pub async fn handle_cli_connection(
                 urls,
                 paths,
                 diff_paths,
+                merge_paths,
                 wait,
                 wsl,
                 open_new_workspace,
@@ -367,6 +411,7 @@ pub async fn handle_cli_connection(
                             RawOpenRequest {
                                 urls,
                                 diff_paths,
+                                merge_paths,
                                 wsl,
                             },
                             cx,
@@ -392,6 +437,7 @@ pub async fn handle_cli_connection(
                 let open_workspace_result = open_workspaces(
                     paths,
                     diff_paths,
+                    merge_paths,
                     open_new_workspace,
                     reuse,
                     &responses,
@@ -412,6 +458,7 @@ pub async fn handle_cli_connection(
 async fn open_workspaces(
     paths: Vec<String>,
     diff_paths: Vec<[String; 2]>,
+    merge_paths: Vec<[String; 4]>,
     open_new_workspace: Option<bool>,
     reuse: bool,
     responses: &IpcSender<CliResponse>,
@@ -420,7 +467,7 @@ async fn open_workspaces(
     env: Option<collections::HashMap<String, String>>,
     cx: &mut AsyncApp,
 ) -> Result<()> {
-    let grouped_locations = if paths.is_empty() && diff_paths.is_empty() {
+    let grouped_locations = if paths.is_empty() && diff_paths.is_empty() && merge_paths.is_empty() {
         // If no paths are provided, restore from previous workspaces unless a new workspace is requested with -n
         if open_new_workspace == Some(true) {
             Vec::new()
@@ -472,6 +519,7 @@ async fn open_workspaces(
                     let workspace_failed_to_open = open_local_workspace(
                         workspace_paths,
                         diff_paths.clone(),
+                        merge_paths.clone(),
                         open_new_workspace,
                         reuse,
                         wait,
@@ -519,6 +567,7 @@ async fn open_workspaces(
 async fn open_local_workspace(
     workspace_paths: Vec<String>,
     diff_paths: Vec<[String; 2]>,
+    merge_paths: Vec<[String; 4]>,
     open_new_workspace: Option<bool>,
     reuse: bool,
     wait: bool,
@@ -545,6 +594,7 @@ async fn open_local_workspace(
     let (workspace, items) = match open_paths_with_positions(
         &paths_with_position,
         &diff_paths,
+        &merge_paths,
         app_state.clone(),
         workspace::OpenOptions {
             open_new_workspace,
@@ -575,7 +625,8 @@ async fn open_local_workspace(
     // If --wait flag is used with no paths, or a directory, then wait until
     // the entire workspace is closed.
     if wait {
-        let mut wait_for_window_close = paths_with_position.is_empty() && diff_paths.is_empty();
+        let mut wait_for_window_close =
+            paths_with_position.is_empty() && diff_paths.is_empty() && merge_paths.is_empty();
         for path_with_position in &paths_with_position {
             if app_state.fs.is_dir(&path_with_position.path).await {
                 wait_for_window_close = true;
@@ -683,7 +734,7 @@ mod tests {
     use serde_json::json;
     use std::{sync::Arc, task::Poll};
     use util::path;
-    use workspace::{AppState, Workspace};
+    use workspace::{AppState, CloseActiveItem, SaveIntent, Workspace};
 
     #[gpui::test]
     fn test_parse_ssh_url(cx: &mut TestAppContext) {
@@ -884,6 +935,7 @@ mod tests {
                 let errored = open_local_workspace(
                     workspace_paths,
                     vec![],
+                    vec![],
                     None,
                     false,
                     true,
@@ -911,6 +963,114 @@ mod tests {
         assert!(!errored);
     }
 
+    #[gpui::test]
+    async fn test_wait_with_merge_waits_for_merge_view_close(cx: &mut TestAppContext) {
+        let app_state = init_test(cx);
+
+        app_state
+            .fs
+            .as_fake()
+            .insert_tree(
+                path!("/root"),
+                json!({
+                    "base.txt": "one\ntwo\nthree\n",
+                    "local.txt": "ONE\ntwo\nthree\n",
+                    "remote.txt": "one\ntwo\nTHREE\n",
+                    "output.txt": "",
+                }),
+            )
+            .await;
+
+        let (response_tx, _) = ipc::channel::<CliResponse>().unwrap();
+        let merge_paths = vec![[
+            path!("/root/base.txt").to_owned(),
+            path!("/root/local.txt").to_owned(),
+            path!("/root/remote.txt").to_owned(),
+            path!("/root/output.txt").to_owned(),
+        ]];
+
+        let (done_tx, mut done_rx) = futures::channel::oneshot::channel();
+        cx.spawn({
+            let app_state = app_state.clone();
+            move |mut cx| async move {
+                let errored = open_local_workspace(
+                    vec![],
+                    vec![],
+                    merge_paths,
+                    None,
+                    false,
+                    true,
+                    &response_tx,
+                    None,
+                    &app_state,
+                    &mut cx,
+                )
+                .await;
+                let _ = done_tx.send(errored);
+            }
+        })
+        .detach();
+
+        cx.background_executor.run_until_parked();
+        assert_eq!(cx.windows().len(), 1);
+        assert!(matches!(poll!(&mut done_rx), Poll::Pending));
+
+        let workspace = cx.windows()[0].downcast::<Workspace>().unwrap();
+        workspace
+            .update(cx, |workspace, _, cx| {
+                assert!(workspace.active_item_as::<MergeView>(cx).is_some());
+                let mut opened_paths = workspace
+                    .project()
+                    .read(cx)
+                    .opened_buffers(cx)
+                    .into_iter()
+                    .filter_map(|buffer| Some(buffer.read(cx).file()?.as_local()?.abs_path(cx)))
+                    .collect::<Vec<_>>();
+                opened_paths.sort();
+                assert_eq!(
+                    opened_paths,
+                    [
+                        PathBuf::from(path!("/root/base.txt")),
+                        PathBuf::from(path!("/root/local.txt")),
+                        PathBuf::from(path!("/root/output.txt")),
+                        PathBuf::from(path!("/root/remote.txt")),
+                    ]
+                );
+            })
+            .unwrap();
+
+        workspace
+            .update(cx, |workspace, window, cx| {
+                workspace.save_active_item(SaveIntent::Save, window, cx)
+            })
+            .unwrap()
+            .await
+            .unwrap();
+        assert_eq!(
+            app_state
+                .fs
+                .load(path!("/root/output.txt").as_ref())
+                .await
+                .unwrap(),
+            "ONE\ntwo\nTHREE\n"
+        );
+        assert!(matches!(poll!(&mut done_rx), Poll::Pending));
+
+        workspace
+            .update(cx, |workspace, window, cx| {
+                workspace.active_pane().update(cx, |pane, cx| {
+                    pane.close_active_item(&CloseActiveItem::default(), window, cx)
+                })
+            })
+            .unwrap()
+            .await
+            .unwrap();
+        cx.background_executor.run_until_parked();
+
+        let errored = done_rx.await.unwrap();
+        assert!(!errored);
+    }
+
     #[gpui::test]
     async fn test_open_workspace_with_nonexistent_files(cx: &mut TestAppContext) {
         let app_state = init_test(cx);
@@ -975,6 +1135,7 @@ mod tests {
                 open_local_workspace(
                     workspace_paths,
                     vec![],
+                    vec![],
                     open_new_workspace,
                     false,
                     false,
@@ -1048,6 +1209,7 @@ mod tests {
                     open_local_workspace(
                         workspace_paths,
                         vec![],
+                        vec![],
                         None,
                         false,
                         false,
@@ -1072,6 +1234,7 @@ mod tests {
                     open_local_workspace(
                         workspace_paths_reuse,
                         vec![],
+                        vec![],
                         None, // open_new_workspace will be overridden by reuse logic
                         true, // reuse = true
                         false,